@@ -1,2 +1,2 @@
 // CLI utilities and helpers
-// Currently empty, reserved for future use
+pub mod output;
@@ -0,0 +1,83 @@
+//! Console output for humans and scripts
+//!
+//! Commands print decorated lines (the `🍵 Chaba - …` banner, `✓`/`⚠️`
+//! markers, boxes around agent output) through [`say!`](crate::say). With
+//! `--quiet` the decoration is dropped: banners and frame lines disappear and
+//! markers are stripped, so the remaining text can be read by scripts. Plain
+//! `println!` output, such as JSON, is never touched.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Drop decorative output for the rest of the process (`--quiet`)
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Whether `--quiet` is on
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Print a decorated line to stdout, undecorated with `--quiet`
+#[macro_export]
+macro_rules! say {
+    () => {
+        if !$crate::cli::output::is_quiet() {
+            println!();
+        }
+    };
+    ($($arg:tt)*) => {
+        $crate::cli::output::print(&format!($($arg)*))
+    };
+}
+
+#[doc(hidden)]
+pub fn print(text: &str) {
+    if !is_quiet() {
+        println!("{}", text);
+    } else if let Some(plain) = plain(text) {
+        println!("{}", plain);
+    }
+}
+
+/// `text` without decoration, or `None` if nothing but decoration is left
+///
+/// Works line by line: banner lines and blank lines are dropped, and symbols
+/// that are neither ASCII nor letters (emoji, check marks, box drawing) are
+/// stripped from both ends of the rest, keeping the indentation.
+pub fn plain(text: &str) -> Option<String> {
+    let is_decoration = |c: char| !c.is_ascii() && !c.is_alphanumeric();
+    let lines: Vec<String> = text
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('🍵'))
+        .filter_map(|line| {
+            let content = line.trim_start();
+            let indent = &line[..line.len() - content.len()];
+            let content = content
+                .trim_start_matches(is_decoration)
+                .trim_end_matches(is_decoration)
+                .trim();
+            (!content.is_empty()).then(|| format!("{}{}", indent, content))
+        })
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain() {
+        assert_eq!(plain("🍵 Chaba - Cleaning up review environment...\n"), None);
+        assert_eq!(plain("✓ Removed worktree for PR #42").as_deref(), Some("Removed worktree for PR #42"));
+        assert_eq!(plain("\n⚠️  Kept PR #7: 2 uncommitted change(s)").as_deref(), Some("Kept PR #7: 2 uncommitted change(s)"));
+        assert_eq!(plain("  ✗ claude failed").as_deref(), Some("  claude failed"));
+        assert_eq!(plain("│ Token is logged │").as_deref(), Some("Token is logged"));
+        assert_eq!(plain("┌──────────┐"), None);
+        assert_eq!(plain("レビュー環境 ✨").as_deref(), Some("レビュー環境"));
+        assert_eq!(plain("[{\"pr\": 1}]").as_deref(), Some("[{\"pr\": 1}]"));
+    }
+}
@@ -0,0 +1,27 @@
+use std::path::Path;
+
+use crate::config::Config;
+use crate::core::worktree::WorktreeManager;
+use crate::error::Result;
+use crate::say;
+
+pub async fn execute(path: &Path, pr: Option<u32>) -> Result<()> {
+    let config = Config::load()?;
+    let manager = WorktreeManager::new(config)?;
+
+    let review = manager.adopt(path, pr).await?;
+
+    say!("✓ Adopted {} as PR #{}", review.worktree_path.display(), review.pr_number);
+    println!("  Branch: {}", review.branch);
+    if let Some(project_type) = &review.project_type {
+        println!("  Project: {}", project_type);
+    }
+    if let Some(port) = review.port {
+        println!("  Port: {}", port);
+    }
+    if pr.is_none() {
+        println!("\nPass --pr to link the worktree to its pull request");
+    }
+
+    Ok(())
+}
@@ -1,9 +1,19 @@
-use crate::core::review_analysis::{Severity, Category};
+use std::path::Path;
+
+use crate::config::Config;
+use crate::core::baseline::{Baseline, BASELINE_FILE};
+use crate::core::history::{self, AgentFinding};
+use crate::core::junit;
+use crate::core::review_analysis::{AggregatedReport, Category, FindingFilter, ReviewAnalysis, Severity, Usage};
 use crate::core::state::State;
 use crate::error::{ChabaError, Result};
+use crate::say;
 
-pub async fn execute(pr: u32) -> Result<()> {
+pub async fn execute(pr: u32, mut filter: FindingFilter, diff: bool) -> Result<()> {
     let state = State::load()?;
+    if filter.min_confidence.is_none() {
+        filter.min_confidence = Config::load()?.agents.min_confidence;
+    }
 
     let review = state
         .get_review(pr)
@@ -11,15 +21,15 @@ pub async fn execute(pr: u32) -> Result<()> {
 
     if review.agent_analyses.is_empty() {
         println!("No AI agent analysis found for PR #{}", pr);
-        println!("\nTip: Run 'chaba review --pr {} --with-agent' to generate analysis", pr);
+        println!("\nTip: Run 'chaba analyze --pr {}' to generate analysis", pr);
         return Ok(());
     }
 
-    println!("╔═══════════════════════════════════════════════════════════════╗");
-    println!("║  AI Agent Review Results - PR #{}                          ", pr);
-    println!("╚═══════════════════════════════════════════════════════════════╝\n");
+    say!("╔═══════════════════════════════════════════════════════════════╗");
+    say!("║  AI Agent Review Results - PR #{}                          ", pr);
+    say!("╚═══════════════════════════════════════════════════════════════╝\n");
 
-    println!("📊 Review Environment:");
+    say!("📊 Review Environment:");
     println!("  Branch: {}", review.branch);
     println!("  Path: {}", review.worktree_path.display());
     if let Some(project_type) = &review.project_type {
@@ -27,24 +37,158 @@ pub async fn execute(pr: u32) -> Result<()> {
     }
     println!();
 
-    for analysis in &review.agent_analyses {
-        print_agent_analysis(analysis);
+    if review.analysis_interrupted {
+        say!("⏹  Analysis interrupted: the last agent run was cancelled, so these results are partial");
+        println!("   Re-run with 'chaba analyze --pr {}'\n", pr);
+    }
+
+    let baseline = Baseline::for_review(review)?;
+    let (unbaselined, baselined) = baseline.suppress(&review.agent_analyses);
+
+    if diff {
+        let Some(previous) = history::previous(review) else {
+            println!("No earlier analysis of PR #{} to compare with", pr);
+            println!("\nTip: Agents re-run when the PR gets new commits ('chaba watch' or 'chaba analyze --pr {}')", pr);
+            return Ok(());
+        };
+        let (previous_unbaselined, _) = baseline.suppress(&previous.analyses);
+
+        say!(
+            "🔀 Changes since the previous run ({} → {})\n",
+            previous.short_commit(),
+            history::short(review.analysis_commit.as_deref())
+        );
+        print_diff(&history::diff(&filter.apply(&previous_unbaselined), &filter.apply(&unbaselined)));
+        return Ok(());
+    }
+
+    if baselined > 0 {
+        say!("🔕 {} baselined finding(s) hidden (see {})\n", baselined, BASELINE_FILE);
+    }
+
+    let analyses = filter.apply(&unbaselined);
+    if !filter.is_empty() {
+        let shown: usize = analyses.iter().map(|a| a.findings.len()).sum();
+        let total: usize = unbaselined.iter().map(|a| a.findings.len()).sum();
+        say!("🔍 Showing {} of {} findings matching the filters\n", shown, total);
+    }
+
+    for analysis in &analyses {
+        print_agent_analysis(analysis, !filter.is_empty() || baselined > 0);
     }
 
     // Summary statistics
-    print_summary(&review.agent_analyses);
+    print_summary(&analyses);
+
+    if !review.agent_usage.is_empty() {
+        say!("\n💰 Agent usage for PR #{}:", pr);
+        println!("  Latest run: {}", Usage::sum(&review.agent_analyses));
+        println!("  All runs:   {}", review.agent_usage);
+    }
+
+    Ok(())
+}
+
+/// Write the latest findings as a JUnit XML report (`--junit`)
+///
+/// Baselined findings are left out like in the regular output. `-` prints
+/// the report to stdout.
+pub async fn junit(pr: u32, mut filter: FindingFilter, path: &Path) -> Result<()> {
+    let state = State::load()?;
+    if filter.min_confidence.is_none() {
+        filter.min_confidence = Config::load()?.agents.min_confidence;
+    }
+
+    let review = state
+        .get_review(pr)
+        .ok_or_else(|| ChabaError::PrNotFound(pr))?;
+
+    let (unbaselined, _) = Baseline::for_review(review)?.suppress(&review.agent_analyses);
+    let analyses = filter.apply(&unbaselined);
+    let report = junit::render(pr, &analyses);
+
+    if path == Path::new("-") {
+        print!("{}", report);
+        return Ok(());
+    }
+
+    tokio::fs::write(path, report).await?;
+    let findings: usize = analyses.iter().map(|a| a.findings.len()).sum();
+    let failures: usize = analyses.iter().map(junit::failure_count).sum();
+    say!(
+        "📝 Wrote JUnit report for PR #{} to {} ({} finding(s), {} failure(s))",
+        pr,
+        path.display(),
+        findings,
+        failures
+    );
+    Ok(())
+}
+
+/// Print the transcripts of the latest agent runs (`--raw`)
+///
+/// With `agents`, only their transcripts are printed.
+pub async fn raw(pr: u32, agents: &[String]) -> Result<()> {
+    let state = State::load()?;
+    let review = state
+        .get_review(pr)
+        .ok_or_else(|| ChabaError::PrNotFound(pr))?;
+
+    let analyses: Vec<&ReviewAnalysis> = review
+        .agent_analyses
+        .iter()
+        .filter(|analysis| agents.is_empty() || agents.iter().any(|a| a.eq_ignore_ascii_case(&analysis.agent)))
+        .collect();
+    if analyses.is_empty() {
+        println!("No AI agent analysis found for PR #{}", pr);
+        println!("\nTip: Run 'chaba analyze --pr {}' to generate analysis", pr);
+        return Ok(());
+    }
+
+    for analysis in analyses {
+        let label = match &analysis.profile {
+            Some(profile) => format!("{} ({})", analysis.agent, profile),
+            None => analysis.agent.clone(),
+        };
+        match &analysis.transcript {
+            Some(path) => match tokio::fs::read_to_string(path).await {
+                Ok(transcript) => {
+                    say!("━━━ {} · {} ━━━", label, path.display());
+                    print!("{}", transcript);
+                    println!();
+                }
+                Err(e) => say!("━━━ {} ━━━\n⚠️  Cannot read transcript {}: {}\n", label, path.display(), e),
+            },
+            None => say!("━━━ {} ━━━\nNo transcript saved for this run\n", label),
+        }
+    }
 
     Ok(())
 }
 
-fn print_agent_analysis(analysis: &crate::core::review_analysis::ReviewAnalysis) {
-    println!("┌────────────────────────────────────────────────────────────┐");
-    println!("│ 🤖 Agent: {:<50} │", analysis.agent);
-    println!("│ 🕐 Time: {:<51} │", &analysis.timestamp[..19]);
+/// Print one agent's findings, grouped by severity
+///
+/// `filtered` says findings may have been hidden, so an empty list is not
+/// reported as a lack of structured output.
+pub fn print_agent_analysis(analysis: &ReviewAnalysis, filtered: bool) {
+    say!("┌────────────────────────────────────────────────────────────┐");
+    say!("│ 🤖 Agent: {:<50} │", analysis.agent);
+    if let Some(profile) = &analysis.profile {
+        say!("│ 🎯 Profile: {:<48} │", profile);
+    }
+    say!("│ 🕐 Time: {:<51} │", &analysis.timestamp[..19]);
     if let Some(score) = analysis.score {
-        println!("│ ⭐ Score: {:.1}/5.0{:<44} │", score, "");
+        say!("│ ⭐ Score: {:.1}/5.0{:<44} │", score, "");
+    }
+    if let Some(usage) = &analysis.usage {
+        say!("│ 💰 Usage: {:<50} │", usage.to_string());
+    }
+    say!("└────────────────────────────────────────────────────────────┘");
+
+    if analysis.findings.is_empty() && filtered {
+        println!("  No matching findings\n");
+        return;
     }
-    println!("└────────────────────────────────────────────────────────────┘");
 
     if analysis.findings.is_empty() {
         println!("  No structured findings");
@@ -57,67 +201,82 @@ fn print_agent_analysis(analysis: &crate::core::review_analysis::ReviewAnalysis)
                 println!("    ... ({} more lines)", raw.lines().count() - 5);
             }
         }
+        print_follow_ups(analysis);
         println!();
         return;
     }
 
-    // Group findings by severity
-    let critical = analysis.findings.iter()
-        .filter(|f| f.severity == Severity::Critical)
-        .collect::<Vec<_>>();
-    let high = analysis.findings.iter()
-        .filter(|f| f.severity == Severity::High)
-        .collect::<Vec<_>>();
-    let medium = analysis.findings.iter()
-        .filter(|f| f.severity == Severity::Medium)
-        .collect::<Vec<_>>();
-    let low = analysis.findings.iter()
-        .filter(|f| f.severity == Severity::Low)
-        .collect::<Vec<_>>();
-    let info = analysis.findings.iter()
-        .filter(|f| f.severity == Severity::Info)
-        .collect::<Vec<_>>();
-
-    if !critical.is_empty() {
-        println!("\n  🔴 CRITICAL ({}):", critical.len());
-        for finding in critical {
+    for (severity, findings) in analysis.findings_by_severity() {
+        say!("\n  {} ({}):", severity_heading(severity), findings.len());
+        for finding in findings {
             print_finding(finding);
         }
     }
 
-    if !high.is_empty() {
-        println!("\n  🟠 HIGH ({}):", high.len());
-        for finding in high {
-            print_finding(finding);
-        }
+    print_follow_ups(analysis);
+    println!();
+}
+
+fn print_follow_ups(analysis: &ReviewAnalysis) {
+    if analysis.follow_ups.is_empty() {
+        return;
     }
 
-    if !medium.is_empty() {
-        println!("\n  🟡 MEDIUM ({}):", medium.len());
-        for finding in medium {
-            print_finding(finding);
+    println!("\n  💬 Follow-up questions ({}):", analysis.follow_ups.len());
+    for follow_up in &analysis.follow_ups {
+        println!("    Q: {}", follow_up.question);
+        for (i, line) in follow_up.answer.lines().enumerate() {
+            println!("    {} {}", if i == 0 { "A:" } else { "  " }, line);
         }
     }
+}
 
-    if !low.is_empty() {
-        println!("\n  🔵 LOW ({}):", low.len());
-        for finding in low {
-            print_finding(finding);
+fn print_diff(diff: &history::FindingDiff) {
+    let sections = [
+        ("🆕 New", &diff.new),
+        ("✅ Resolved", &diff.resolved),
+        ("➖ Persisting", &diff.persisting),
+    ];
+
+    for (heading, findings) in sections {
+        println!("{} ({}):", heading, findings.len());
+        for AgentFinding { agent, finding } in findings {
+            print!("  • [{:?}] {}", finding.severity, finding.title);
+            if let Some(file) = &finding.file {
+                match finding.line {
+                    Some(line) => print!(" ({}:{})", file, line),
+                    None => print!(" ({})", file),
+                }
+            }
+            println!(" — {}", agent);
         }
+        println!();
     }
 
-    if !info.is_empty() {
-        println!("\n  ⚪ INFO ({}):", info.len());
-        for finding in info {
-            print_finding(finding);
-        }
+    if !diff.skipped_agents.is_empty() {
+        println!(
+            "Not compared (ran in only one of the two runs): {}",
+            diff.skipped_agents.join(", ")
+        );
     }
+}
 
-    println!();
+fn severity_heading(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "🔴 CRITICAL",
+        Severity::High => "🟠 HIGH",
+        Severity::Medium => "🟡 MEDIUM",
+        Severity::Low => "🔵 LOW",
+        Severity::Info => "⚪ INFO",
+    }
 }
 
 fn print_finding(finding: &crate::core::review_analysis::Finding) {
-    print!("    • {}", finding.title);
+    if finding.id.is_empty() {
+        print!("    • {}", finding.title);
+    } else {
+        print!("    • [{}] {}", finding.id, finding.title);
+    }
 
     if let Some(file) = &finding.file {
         if let Some(line) = finding.line {
@@ -127,6 +286,14 @@ fn print_finding(finding: &crate::core::review_analysis::Finding) {
         }
     }
 
+    if let Some(confidence) = finding.confidence {
+        print!(" ({:.0}% confident)", confidence * 100.0);
+    }
+
+    if !finding.triage.is_open() {
+        print!(" [{}]", finding.triage);
+    }
+
     println!();
 
     if !finding.description.is_empty() {
@@ -138,21 +305,25 @@ fn print_finding(finding: &crate::core::review_analysis::Finding) {
     }
 }
 
-fn print_summary(analyses: &[crate::core::review_analysis::ReviewAnalysis]) {
-    println!("╔═══════════════════════════════════════════════════════════════╗");
-    println!("║  Summary                                                        ║");
-    println!("╚═══════════════════════════════════════════════════════════════╝\n");
+fn print_summary(analyses: &[ReviewAnalysis]) {
+    say!("╔═══════════════════════════════════════════════════════════════╗");
+    say!("║  Summary                                                        ║");
+    say!("╚═══════════════════════════════════════════════════════════════╝\n");
 
-    let total_findings: usize = analyses.iter().map(|a| a.findings.len()).sum();
-    let total_critical: usize = analyses.iter()
-        .map(|a| a.count_by_severity(&Severity::Critical))
-        .sum();
-    let total_high: usize = analyses.iter()
-        .map(|a| a.count_by_severity(&Severity::High))
-        .sum();
+    let report = AggregatedReport::from_analyses(analyses);
+    let reported: usize = analyses.iter().map(|a| a.findings.len()).sum();
+    let total_critical = report.count_by_severity(&Severity::Critical);
+    let total_high = report.count_by_severity(&Severity::High);
 
-    println!("  Total Agents: {}", analyses.len());
-    println!("  Total Findings: {}", total_findings);
+    println!("  Total Agents: {}", report.agents.len());
+    if reported > report.total() {
+        println!("  Total Findings: {} ({} duplicates merged)", report.total(), reported - report.total());
+    } else {
+        println!("  Total Findings: {}", report.total());
+    }
+    if let Some(score) = report.score {
+        println!("  Combined Score: {:.1}/5.0", score);
+    }
 
     if total_critical > 0 || total_high > 0 {
         println!("\n  ⚠️  Attention Required:");
@@ -167,26 +338,15 @@ fn print_summary(analyses: &[crate::core::review_analysis::ReviewAnalysis]) {
     }
 
     // Category breakdown
-    let categories = [
-        Category::Security,
-        Category::Performance,
-        Category::BestPractice,
-        Category::CodeQuality,
-        Category::Architecture,
-        Category::Testing,
-        Category::Documentation,
-    ];
+    let categories: Vec<(Category, usize)> = report
+        .category_counts()
+        .into_iter()
+        .filter(|(category, _)| *category != Category::Other)
+        .collect();
 
-    let mut has_categories = false;
-    for category in &categories {
-        let count: usize = analyses.iter()
-            .map(|a| a.count_by_category(category))
-            .sum();
-        if count > 0 {
-            if !has_categories {
-                println!("\n  Categories:");
-                has_categories = true;
-            }
+    if !categories.is_empty() {
+        println!("\n  Categories:");
+        for (category, count) in categories {
             println!("    • {:?}: {}", category, count);
         }
     }
@@ -0,0 +1,52 @@
+use crate::commands::review;
+use crate::config::Config;
+use crate::core::agent::AgentManager;
+use crate::core::state::State;
+use crate::core::worktree::WorktreeManager;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+/// Run agents on an existing review environment without recreating it
+///
+/// With `agents`, only those agents run and the analyses of the others are
+/// kept; otherwise the default (or thorough) agents replace the analyses.
+pub async fn execute(pr: u32, agents: Vec<String>, thorough: bool) -> Result<()> {
+    let config = Config::load()?;
+    let state = State::load()?;
+    let mut review = state
+        .get_review(pr)
+        .cloned()
+        .ok_or(ChabaError::WorktreeNotFound(pr))?;
+
+    if !review.worktree_path.exists() {
+        return Err(ChabaError::Other(anyhow::anyhow!(
+            "Worktree does not exist: {}",
+            review.worktree_path.display()
+        )));
+    }
+
+    if !config.agents.enabled {
+        println!("AI agents are disabled (agents.enabled: false in chaba.yaml)");
+        return Ok(());
+    }
+
+    if let Some(unknown) = agents.iter().find(|agent| !AgentManager::is_supported(agent)) {
+        return Err(ChabaError::ConfigError(format!(
+            "Unknown agent: {} (available: claude, codex, gemini, cursor-agent, copilot)",
+            unknown
+        )));
+    }
+
+    let merge = !agents.is_empty();
+    let agents = if merge {
+        agents
+    } else if thorough {
+        config.agents.thorough_agents.clone()
+    } else {
+        config.agents.default_agents.clone()
+    };
+
+    say!("🍵 Chaba - Analyzing PR #{} with {}", pr, agents.join(", "));
+    let local = WorktreeManager::is_local(&review);
+    review::analyze_with(&config, &mut review, &agents, local, merge).await
+}
@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+
+use crate::core::git::GitOps;
+use crate::core::state::State;
+use crate::core::suggestions;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+/// Scratch file for single-hunk patches, in `.chaba/`, excluded from git
+const HUNK_PATCH_FILE: &str = ".chaba/apply-hunk.patch";
+
+pub async fn execute(pr: u32, from: PathBuf) -> Result<()> {
+    let state = State::load()?;
+    let review = state
+        .get_review(pr)
+        .ok_or(ChabaError::WorktreeNotFound(pr))?;
+
+    if !review.worktree_path.exists() {
+        return Err(ChabaError::Other(anyhow::anyhow!(
+            "Worktree does not exist: {}",
+            review.worktree_path.display()
+        )));
+    }
+
+    let suggestions = suggestions::load(&from)?;
+    if suggestions.is_empty() {
+        println!("No suggestions with a diff found in {}", from.display());
+        return Ok(());
+    }
+
+    say!("🍵 Chaba - Applying suggestions...\n");
+    println!("PR #:         {}", pr);
+    println!("Worktree:     {}", review.worktree_path.display());
+    println!("Source:       {}\n", from.display());
+
+    let git_ops = GitOps::for_review(review)?;
+    let hunk_file = review.worktree_path.join(HUNK_PATCH_FILE);
+    if let Some(parent) = hunk_file.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+
+    let mut applied = 0;
+    let mut conflicts = 0;
+
+    for suggestion in &suggestions {
+        println!("{}", suggestion.label());
+
+        let hunks = suggestion.hunks();
+        if hunks.is_empty() {
+            println!("  ⚠️  No hunks found in diff");
+            continue;
+        }
+
+        for hunk in hunks {
+            tokio::fs::write(&hunk_file, &hunk.patch).await?;
+
+            match git_ops.apply_patch(&review.worktree_path, &hunk_file).await {
+                Ok(()) => {
+                    println!("  ✓ {} {}", hunk.file, hunk.range);
+                    applied += 1;
+                }
+                Err(e) => {
+                    println!("  ✗ {} {} (conflict)", hunk.file, hunk.range);
+                    tracing::debug!("{}", e);
+                    conflicts += 1;
+                }
+            }
+        }
+    }
+
+    let _ = tokio::fs::remove_file(&hunk_file).await;
+
+    println!("\n{} hunk(s) applied and staged, {} conflict(s)", applied, conflicts);
+    if applied > 0 {
+        println!("\nReview the staged changes:");
+        println!("  cd {}", review.worktree_path.display());
+        println!("  git diff --cached");
+    }
+
+    if conflicts > 0 {
+        return Err(ChabaError::Other(anyhow::anyhow!(
+            "{} hunk(s) could not be applied; the worktree has diverged from the suggested diff",
+            conflicts
+        )));
+    }
+
+    Ok(())
+}
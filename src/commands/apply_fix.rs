@@ -0,0 +1,96 @@
+use crate::core::git::GitOps;
+use crate::core::review_analysis::{self, TriageStatus};
+use crate::core::state::State;
+use crate::core::suggestions;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+/// Scratch file for the fix patch, in `.chaba/`, excluded from git
+const FIX_PATCH_FILE: &str = ".chaba/apply-fix.patch";
+
+pub async fn execute(pr: u32, finding_id: String, dry_run: bool) -> Result<()> {
+    let state = State::load()?;
+    let review = state
+        .get_review(pr)
+        .cloned()
+        .ok_or(ChabaError::WorktreeNotFound(pr))?;
+
+    let finding = review
+        .agent_analyses
+        .iter()
+        .find_map(|analysis| analysis.finding(&finding_id))
+        .ok_or_else(|| {
+            ChabaError::Other(anyhow::anyhow!(
+                "No finding {} in PR #{}; see 'chaba agent-result --pr {}'",
+                finding_id,
+                pr,
+                pr
+            ))
+        })?;
+
+    if finding.triage == TriageStatus::Fixed {
+        println!("Finding {} is already marked as fixed", finding_id);
+        return Ok(());
+    }
+
+    if !review.worktree_path.exists() {
+        return Err(ChabaError::Other(anyhow::anyhow!(
+            "Worktree does not exist: {}",
+            review.worktree_path.display()
+        )));
+    }
+
+    let Some(fix) = suggestions::finding_fix(finding, &review.worktree_path)? else {
+        println!("Finding {} has no diff or code snippet to apply", finding_id);
+        if let Some(suggestion) = &finding.suggestion {
+            println!("\n  💡 Suggestion: {}", suggestion);
+        }
+        return Ok(());
+    };
+
+    say!("🍵 Chaba - {} fix for [{}] {}\n", if dry_run { "Previewing" } else { "Applying" }, finding.id, finding.title);
+    for line in fix.lines() {
+        println!("  {}", line);
+    }
+    println!();
+
+    let git_ops = GitOps::for_review(&review)?;
+    let patch_file = review.worktree_path.join(FIX_PATCH_FILE);
+    if let Some(parent) = patch_file.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(&patch_file, &fix).await?;
+
+    let result = if dry_run {
+        git_ops.check_patch(&review.worktree_path, &patch_file).await
+    } else {
+        git_ops.apply_patch(&review.worktree_path, &patch_file).await
+    };
+    let _ = tokio::fs::remove_file(&patch_file).await;
+
+    if let Err(e) = result {
+        tracing::debug!("{}", e);
+        return Err(ChabaError::Other(anyhow::anyhow!(
+            "The fix for {} does not apply; the worktree has diverged from the suggestion",
+            finding_id
+        )));
+    }
+
+    if dry_run {
+        say!("✓ The fix applies cleanly (dry run, nothing changed)");
+        println!("\nApply it with: chaba apply-fix --pr {} --finding {}", pr, finding_id);
+        return Ok(());
+    }
+
+    let mut state = State::load()?;
+    state.update_review(&review.key(), |review| {
+        review_analysis::set_triage(&mut review.agent_analyses, &finding_id, TriageStatus::Fixed);
+    })?;
+
+    say!("✓ Applied and staged the fix; marked {} as fixed", finding_id);
+    println!("\nReview the staged changes:");
+    println!("  cd {}", review.worktree_path.display());
+    println!("  git diff --cached");
+
+    Ok(())
+}
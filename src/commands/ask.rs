@@ -0,0 +1,84 @@
+use crate::config::Config;
+use crate::core::activity;
+use crate::core::agent::AgentManager;
+use crate::core::review_analysis;
+use crate::core::state::State;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+/// Ask an agent a follow-up question about a review's findings
+pub async fn execute(pr: u32, question: String, agent: Option<String>) -> Result<()> {
+    let config = Config::load()?;
+    let state = State::load()?;
+    let review = state
+        .get_review(pr)
+        .cloned()
+        .ok_or(ChabaError::WorktreeNotFound(pr))?;
+
+    if review.agent_analyses.is_empty() {
+        println!("No AI agent analysis found for PR #{}", pr);
+        println!("\nTip: Run 'chaba analyze --pr {}' to generate analysis", pr);
+        return Ok(());
+    }
+
+    // The question and findings would reach the agent with real names
+    if config.agents.anonymize.enabled {
+        return Err(ChabaError::ConfigError(
+            "chaba ask sends the worktree and findings to the agent as they are; it is not available with agents.anonymize enabled".to_string(),
+        ));
+    }
+
+    if !review.worktree_path.exists() {
+        return Err(ChabaError::Other(anyhow::anyhow!(
+            "Worktree does not exist: {}",
+            review.worktree_path.display()
+        )));
+    }
+
+    // Prefer the default agent that did the review
+    let agent = agent.unwrap_or_else(|| {
+        config
+            .agents
+            .default_agents
+            .iter()
+            .find(|agent| review.agent_analyses.iter().any(|a| &a.agent == *agent))
+            .unwrap_or(&review.agent_analyses[0].agent)
+            .clone()
+    });
+
+    if !review.agent_analyses.iter().any(|analysis| analysis.agent == agent) {
+        let reviewed: Vec<&str> = review.agent_analyses.iter().map(|a| a.agent.as_str()).collect();
+        return Err(ChabaError::Other(anyhow::anyhow!(
+            "{} has not reviewed PR #{}; ask one of: {}",
+            agent,
+            pr,
+            reviewed.join(", ")
+        )));
+    }
+
+    let context = review_analysis::follow_up_context(&review.agent_analyses, &agent);
+    let manager = AgentManager::new(config.agents.clone()).with_context(context);
+
+    say!("🤖 Asking {} about PR #{}...\n", agent, pr);
+    let _activity = activity::start(&review.key(), std::slice::from_ref(&agent), config.agents.timeout)
+        .map_err(|e| tracing::debug!("Failed to record running agents: {}", e))
+        .ok();
+    let answer = manager.ask(&agent, pr, &review.worktree_path, &question).await?;
+    let answer = answer.trim_end().to_string();
+
+    say!("┌────────────────────────────────────────────────────────────┐");
+    say!("│ 🤖 Agent: {:<50} │", agent);
+    say!("└────────────────────────────────────────────────────────────┘");
+    println!("{}\n", answer);
+
+    let mut state = State::load()?;
+    state.update_review(&review.key(), |review| {
+        if let Some(analysis) = review.agent_analyses.iter_mut().find(|analysis| analysis.agent == agent) {
+            analysis.add_follow_up(question.clone(), answer.clone());
+        }
+    })?;
+
+    println!("Saved to the review; see 'chaba agent-result --pr {}'", pr);
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use std::io::BufRead;
+
+use crate::core::secrets::{self, KNOWN_TOKENS};
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+pub async fn set(name: String, stdin: bool) -> Result<()> {
+    let token = if stdin {
+        let mut line = String::new();
+        std::io::stdin().lock().read_line(&mut line)?;
+        line.trim().to_string()
+    } else {
+        use dialoguer::Password;
+
+        Password::new()
+            .with_prompt(format!("Token for '{}'", name))
+            .interact()
+            .map_err(|e| ChabaError::Other(e.into()))?
+    };
+
+    if token.is_empty() {
+        return Err(ChabaError::ConfigError("Token must not be empty".to_string()));
+    }
+
+    secrets::keychain_set(&name, &token)?;
+
+    say!("✓ Stored '{}' in the OS keychain", name);
+    if let Some(var) = secrets::token_env_var(&name) {
+        println!("  It will be provided as {} when needed", var);
+    } else {
+        println!("  Reference it from chaba.yaml with: {{ keychain: {} }}", name);
+    }
+
+    Ok(())
+}
+
+pub async fn delete(name: String) -> Result<()> {
+    secrets::keychain_delete(&name)?;
+    say!("✓ Removed '{}' from the OS keychain", name);
+    Ok(())
+}
+
+pub async fn status() -> Result<()> {
+    println!("Keychain tokens (service: {}):\n", secrets::KEYCHAIN_SERVICE);
+
+    for (name, var) in KNOWN_TOKENS {
+        let stored = secrets::keychain_get(name).is_ok();
+        let marker = if stored { "✓" } else { "·" };
+        let state = if stored { "stored" } else { "not set" };
+        println!("  {} {:<8} {:<18} {}", marker, name, var, state);
+    }
+
+    println!("\nStore a token with: chaba auth set <name>");
+
+    Ok(())
+}
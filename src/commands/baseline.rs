@@ -0,0 +1,47 @@
+use crate::core::baseline::Baseline;
+use crate::core::review_analysis::TriageStatus;
+use crate::core::state::State;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+pub async fn update(pr: u32) -> Result<()> {
+    let state = State::load()?;
+
+    let review = state
+        .get_review(pr)
+        .ok_or_else(|| ChabaError::PrNotFound(pr))?;
+
+    if review.agent_analyses.is_empty() {
+        println!("No AI agent analysis found for PR #{}", pr);
+        println!("\nTip: Run 'chaba analyze --pr {}' to generate analysis", pr);
+        return Ok(());
+    }
+
+    let path = Baseline::path_for_review(review)?;
+    let mut baseline = Baseline::load(&path)?;
+
+    // Fixed findings are gone from the code; there's nothing left to accept
+    let findings = review
+        .agent_analyses
+        .iter()
+        .flat_map(|analysis| &analysis.findings)
+        .filter(|finding| finding.triage != TriageStatus::Fixed);
+    let (mut added, mut existing) = (0, 0);
+    for finding in findings {
+        if baseline.add(finding) {
+            added += 1;
+        } else {
+            existing += 1;
+        }
+    }
+
+    baseline.save(&path)?;
+
+    say!("✓ Added {} finding(s) from PR #{} to {}", added, pr, path.display());
+    if existing > 0 {
+        println!("  {} were already baselined", existing);
+    }
+    println!("  Baselined findings are hidden from agent-result and ignored by verdict");
+
+    Ok(())
+}
@@ -1,25 +1,81 @@
-use dialoguer::Confirm;
+use dialoguer::{Confirm, Select};
 
 use crate::config::Config;
-use crate::core::state::State;
+use crate::core::git::{GitOps, UnsavedWork};
+use crate::core::state::{ReviewState, ReviewTarget, State};
 use crate::core::worktree::WorktreeManager;
-use crate::error::Result;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+/// Unsaved lines listed per kind before the rest is summarized
+const MAX_LISTED: usize = 10;
+
+/// What to do with uncommitted changes and local commits in a worktree being removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsavedWorkAction {
+    /// Ask interactively; refuse with `--force`
+    Ask,
+    /// Remove the worktree anyway (`--discard-changes`)
+    Discard,
+    /// Save the worktree to a backup branch first, even without unsaved work (`--archive`)
+    Archive,
+}
+
+impl UnsavedWorkAction {
+    /// From the `--discard-changes` and `--archive` flags
+    pub fn from_flags(discard_changes: bool, archive: bool) -> Self {
+        match (discard_changes, archive) {
+            (true, _) => UnsavedWorkAction::Discard,
+            (_, true) => UnsavedWorkAction::Archive,
+            _ => UnsavedWorkAction::Ask,
+        }
+    }
+}
+
+pub async fn execute(target: Option<ReviewTarget>, force: bool, unsaved: UnsavedWorkAction) -> Result<()> {
+    let state = State::load()?;
+    let pr = state.resolve_target(target)?;
 
-pub async fn execute(pr: u32, force: bool) -> Result<()> {
     let config = Config::load()?;
     let manager = WorktreeManager::new(config)?;
 
-    println!("🍵 Chaba - Cleaning up review environment...\n");
+    say!("🍵 Chaba - Cleaning up review environment...\n");
 
     // Get review info for confirmation
-    let state = State::load()?;
     if let Some(review) = state.get_review(pr) {
         println!("Review environment for PR #{}:", pr);
         println!("  Branch: {}", review.branch);
         println!("  Path: {}", review.worktree_path.display());
 
-        // Interactive confirmation (unless --force/--yes is specified)
-        if !force {
+        let mut action = unsaved;
+        let work = match unsaved {
+            UnsavedWorkAction::Ask => unsaved_work(review).await?,
+            _ => UnsavedWork::default(),
+        };
+        if !work.is_empty() {
+            print_unsaved_work(&work);
+            if force {
+                return Err(ChabaError::ConfigError(format!(
+                    "PR #{}'s worktree has unsaved work; use --archive to save it to a branch first, or --discard-changes to remove it anyway",
+                    pr
+                )));
+            }
+            let choice = Select::new()
+                .with_prompt("Remove the worktree anyway?")
+                .items(&["Save to a backup branch, then remove", "Discard and remove", "Cancel"])
+                .default(0)
+                .interact()
+                .unwrap_or(2);
+            action = match choice {
+                0 => UnsavedWorkAction::Archive,
+                1 => UnsavedWorkAction::Discard,
+                _ => {
+                    println!("Cleanup cancelled.");
+                    return Ok(());
+                }
+            };
+        } else if !force {
+            // Interactive confirmation (unless --force/--yes is specified)
             let confirmed = Confirm::new()
                 .with_prompt("Are you sure you want to remove this worktree?")
                 .default(false)
@@ -31,12 +87,135 @@ pub async fn execute(pr: u32, force: bool) -> Result<()> {
                 return Ok(());
             }
         }
+
+        if action == UnsavedWorkAction::Archive {
+            archive(&manager, review).await?;
+        }
     }
 
     manager.remove(pr).await?;
 
-    println!("✓ Removed worktree for PR #{}", pr);
-    println!("✨ Cleanup complete!");
+    say!("✓ Removed worktree for PR #{}", pr);
+    say!("✨ Cleanup complete!");
 
     Ok(())
 }
+
+/// Remove every review environment whose TTL has passed
+///
+/// Reviews with unsaved work are kept unless `unsaved` says to archive or
+/// discard it.
+pub async fn execute_expired(force: bool, unsaved: UnsavedWorkAction) -> Result<()> {
+    let config = Config::load()?;
+    let manager = WorktreeManager::new(config)?;
+
+    let expired = manager.expired()?;
+
+    if expired.is_empty() {
+        println!("No expired review environments.");
+        return Ok(());
+    }
+
+    say!("🍵 Chaba - Cleaning up expired review environments...\n");
+    for review in &expired {
+        println!("  PR #{} - {} ({})", review.pr_number, review.branch, review.worktree_path.display());
+    }
+
+    if !force {
+        let confirmed = Confirm::new()
+            .with_prompt(format!("Remove {} expired worktree(s)?", expired.len()))
+            .default(false)
+            .interact()
+            .unwrap_or(false);
+
+        if !confirmed {
+            println!("Cleanup cancelled.");
+            return Ok(());
+        }
+    }
+
+    for review in &expired {
+        match unsaved {
+            UnsavedWorkAction::Archive => {
+                if let Err(e) = archive(&manager, review).await {
+                    eprintln!("⚠️  Kept PR #{}: failed to save a backup branch: {}", review.pr_number, e);
+                    continue;
+                }
+            }
+            UnsavedWorkAction::Ask => {
+                let work = match unsaved_work(review).await {
+                    Ok(work) => work,
+                    Err(e) => {
+                        eprintln!("⚠️  Kept PR #{}: {}", review.pr_number, e);
+                        continue;
+                    }
+                };
+                if !work.is_empty() {
+                    say!(
+                        "⚠️  Kept PR #{}: {} uncommitted change(s) and {} local commit(s) (use --archive or --discard-changes)",
+                        review.pr_number,
+                        work.changes.len(),
+                        work.commits.len()
+                    );
+                    continue;
+                }
+            }
+            UnsavedWorkAction::Discard => {}
+        }
+
+        match manager.remove_review(review).await {
+            Ok(()) => say!("✓ Removed worktree for PR #{}", review.pr_number),
+            Err(e) => eprintln!("⚠️  Failed to remove PR #{}: {}", review.pr_number, e),
+        }
+    }
+
+    say!("✨ Cleanup complete!");
+
+    Ok(())
+}
+
+/// Save a review's worktree to a backup branch, printing how to restore it
+async fn archive(manager: &WorktreeManager, review: &ReviewState) -> Result<()> {
+    let branch = manager.backup(review).await?;
+    say!("✓ Saved PR #{} to branch {}", review.pr_number, branch);
+    println!("  Restore it with: git worktree add {} {}", review.worktree_path.display(), branch);
+    Ok(())
+}
+
+/// Unsaved work in a review's worktree
+///
+/// Fails when the worktree can't be checked, so nothing is removed on a guess;
+/// `--archive` and `--discard-changes` skip the check.
+pub async fn unsaved_work(review: &ReviewState) -> Result<UnsavedWork> {
+    if !review.worktree_path.exists() {
+        return Ok(UnsavedWork::default());
+    }
+    let result = match GitOps::for_review(review) {
+        Ok(git) => git.unsaved_work(&review.worktree_path).await,
+        Err(e) => Err(e),
+    };
+    result.map_err(|e| {
+        ChabaError::ConfigError(format!(
+            "Cannot check PR #{}'s worktree for unsaved work ({}); use --archive to save it to a branch first, or --discard-changes to remove it anyway",
+            review.pr_number, e
+        ))
+    })
+}
+
+/// List what removing a worktree would lose
+pub fn print_unsaved_work(work: &UnsavedWork) {
+    say!("\n⚠️  Removing the worktree would lose:");
+    for (label, lines) in [("Uncommitted changes", &work.changes), ("Commits not on any remote", &work.commits)] {
+        if lines.is_empty() {
+            continue;
+        }
+        println!("  {} ({}):", label, lines.len());
+        for line in lines.iter().take(MAX_LISTED) {
+            println!("    {}", line);
+        }
+        if lines.len() > MAX_LISTED {
+            println!("    … and {} more", lines.len() - MAX_LISTED);
+        }
+    }
+    println!();
+}
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 use crate::config::Config;
 use crate::error::Result;
+use crate::say;
 
 pub async fn execute(local: bool) -> Result<()> {
     let config_path = if local {
@@ -24,7 +25,7 @@ pub async fn execute(local: bool) -> Result<()> {
     let example_config = Config::example();
     tokio::fs::write(&config_path, example_config).await?;
 
-    println!("✓ Created configuration file at: {}", config_path.display());
+    say!("✓ Created configuration file at: {}", config_path.display());
     println!("\nEdit this file to customize Chaba's behavior.");
 
     // For local config, suggest adding to .gitignore
@@ -44,10 +45,16 @@ pub async fn execute(local: bool) -> Result<()> {
         };
 
         if should_suggest {
-            println!("\n💡 Tip: Add 'chaba.yaml' to .gitignore to avoid committing local settings:");
+            say!("\n💡 Tip: Add 'chaba.yaml' to .gitignore to avoid committing local settings:");
             println!("   echo 'chaba.yaml' >> .gitignore");
         }
     }
 
     Ok(())
 }
+
+/// Print the JSON Schema for chaba.yaml to stdout
+pub async fn schema() -> Result<()> {
+    println!("{}", Config::json_schema());
+    Ok(())
+}
@@ -0,0 +1,18 @@
+use crate::config::Config;
+use crate::core::worktree::WorktreeManager;
+use crate::error::Result;
+use crate::say;
+
+pub async fn execute(pr: u32) -> Result<()> {
+    let config = Config::load()?;
+    let manager = WorktreeManager::new(config)?;
+
+    let review = manager.detach(pr).await?;
+
+    say!("✓ Stopped tracking PR #{}", pr);
+    println!("  The worktree was kept at: {}", review.worktree_path.display());
+    println!("\nTo remove it later:");
+    println!("  git worktree remove {}", review.worktree_path.display());
+
+    Ok(())
+}
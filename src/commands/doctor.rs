@@ -0,0 +1,94 @@
+use std::ffi::OsStr;
+use std::path::Path;
+
+use crate::config::Config;
+use crate::core::agent::AgentManager;
+use crate::core::command;
+use crate::core::preflight::{self, Check, CheckStatus};
+use crate::core::secrets;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+/// Check the tools chaba runs: git, gh, and the configured agents
+pub async fn execute() -> Result<()> {
+    let config = Config::load()?;
+    let mut checks = vec![tool_check("git", "Install git from https://git-scm.com"), github_check().await];
+
+    let mut agents = config.agents.default_agents.clone();
+    for agent in &config.agents.thorough_agents {
+        if !agents.contains(agent) {
+            agents.push(agent.clone());
+        }
+    }
+    if config.agents.enabled {
+        checks.extend(AgentManager::new(config.agents.clone()).check_agents(&agents));
+    }
+
+    say!("🍵 Chaba - Doctor\n");
+    for check in &checks {
+        let (marker, detail) = match &check.status {
+            CheckStatus::Ok(detail) => ("✓", detail),
+            CheckStatus::Warning(detail) => ("⚠", detail),
+            CheckStatus::Failed(detail) => ("✗", detail),
+        };
+        println!("  {} {:<8} {}", marker, check.name, detail);
+        if let Some(hint) = &check.hint {
+            println!("    {}", hint);
+        }
+    }
+    if !config.agents.enabled {
+        println!("\nAI agents are disabled (agents.enabled: false in chaba.yaml)");
+    }
+
+    let failed = checks.iter().filter(|check| !check.is_available()).count();
+    if failed > 0 {
+        return Err(ChabaError::ConfigError(format!("{} check(s) failed", failed)));
+    }
+    say!("\n✓ All checks passed");
+    Ok(())
+}
+
+/// Check that `program` is on `PATH`
+fn tool_check(program: &str, install: &str) -> Check {
+    match preflight::find_in_path(program, std::env::var_os("PATH").as_deref()) {
+        Some(path) => Check {
+            name: program.to_string(),
+            status: CheckStatus::Ok(path.display().to_string()),
+            hint: None,
+        },
+        None => Check {
+            name: program.to_string(),
+            status: CheckStatus::Failed(format!("'{}' not found on PATH", program)),
+            hint: Some(install.to_string()),
+        },
+    }
+}
+
+/// Check that `gh` is installed and authenticated
+async fn github_check() -> Check {
+    let mut check = tool_check("gh", "Install the GitHub CLI from https://cli.github.com");
+    let CheckStatus::Ok(path) = &check.status else {
+        return check;
+    };
+
+    let token = ["GH_TOKEN", "GITHUB_TOKEN"]
+        .into_iter()
+        .find(|var| std::env::var(var).is_ok_and(|value| !value.is_empty()));
+    if let Some(var) = token {
+        check.status = CheckStatus::Ok(format!("{} ({})", path, var));
+    } else if secrets::keychain_get("github").is_ok() {
+        check.status = CheckStatus::Ok(format!("{} (keychain token)", path));
+    } else {
+        let logged_in = command::default_runner()
+            .run("gh", &[OsStr::new("auth"), OsStr::new("status")], Path::new("."))
+            .await
+            .is_ok_and(|output| output.status.success());
+        if logged_in {
+            check.status = CheckStatus::Ok(format!("{} (logged in)", path));
+        } else {
+            check.status = CheckStatus::Failed("gh is not logged in".to_string());
+            check.hint = Some("Run 'gh auth login', or store a token with 'chaba auth set github'".to_string());
+        }
+    }
+    check
+}
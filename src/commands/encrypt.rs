@@ -0,0 +1,79 @@
+use crate::core::encryption::{self, KeyStorage, StateKey};
+use crate::core::state;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+pub async fn enable(passphrase: bool) -> Result<()> {
+    if let Some(storage) = encryption::storage()? {
+        println!("Encryption at rest is already enabled (key in {})", storage);
+        return Ok(());
+    }
+
+    let key = StateKey::generate();
+    let storage = if passphrase { KeyStorage::Passphrase } else { KeyStorage::Keychain };
+    encryption::enable(&key, storage)?;
+
+    let mut encrypted = 0;
+    for file in encryption::protected_files()? {
+        if encryption::rewrite(&file, None, Some(&key))? {
+            encrypted += 1;
+        }
+    }
+
+    say!("✓ Enabled encryption at rest (key in {})", storage);
+    println!("  Encrypted {} file(s): state and snapshot env copies", encrypted);
+    if passphrase {
+        println!("  Set {} to unlock the key without a prompt", encryption::PASSPHRASE_ENV);
+    }
+    println!("  Logs under {} are not encrypted", state::state_dir()?.join("logs").display());
+
+    Ok(())
+}
+
+pub async fn disable() -> Result<()> {
+    let Some(storage) = encryption::storage()? else {
+        println!("Encryption at rest is not enabled");
+        return Ok(());
+    };
+    if storage == KeyStorage::Env {
+        return Err(ChabaError::ConfigError(format!(
+            "The key comes from {}; unset it to stop encrypting new writes",
+            encryption::KEY_ENV
+        )));
+    }
+
+    let key = encryption::key()?;
+    let mut decrypted = 0;
+    for file in encryption::protected_files()? {
+        if encryption::rewrite(&file, key, None)? {
+            decrypted += 1;
+        }
+    }
+    encryption::disable(storage)?;
+
+    say!("✓ Disabled encryption at rest");
+    println!("  Decrypted {} file(s) and removed the key from the {}", decrypted, storage);
+
+    Ok(())
+}
+
+pub async fn status() -> Result<()> {
+    let storage = encryption::storage()?;
+    match storage {
+        Some(storage) => println!("Encryption at rest: enabled (key in {})", storage),
+        None => println!("Encryption at rest: disabled"),
+    }
+
+    let files = encryption::protected_files()?;
+    let encrypted = files
+        .iter()
+        .filter(|file| std::fs::read(file).is_ok_and(|data| encryption::is_encrypted(&data)))
+        .count();
+    println!("  {}/{} state and snapshot env file(s) encrypted", encrypted, files.len());
+
+    if storage.is_none() {
+        println!("\nEnable it with: chaba encrypt enable [--passphrase]");
+    }
+
+    Ok(())
+}
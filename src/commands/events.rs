@@ -0,0 +1,29 @@
+use crate::core::events;
+use crate::core::logs::ReviewLogs;
+use crate::core::state::State;
+use crate::error::Result;
+
+/// Print what happened to a review environment, oldest first
+pub async fn execute(pr: u32) -> Result<()> {
+    // The log outlives the review, so this works after cleanup too
+    let logs = ReviewLogs::for_review(&State::load()?.review_key(pr))?;
+    let events = events::load(&logs)?;
+
+    if events.is_empty() {
+        println!("No events recorded for PR #{}", pr);
+        println!("  Expected location: {}", logs.events_log().display());
+        return Ok(());
+    }
+
+    println!("Events for PR #{}:\n", pr);
+    for event in &events {
+        println!(
+            "  {}  {:<15} {}",
+            event.at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+            event.event.to_string(),
+            event.detail
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use crate::core::git::GitOps;
+use crate::core::state::{ReviewState, State};
+use crate::error::{ChabaError, Result};
+
+pub async fn execute(pattern: String, pr: Option<u32>, ignore_case: bool) -> Result<()> {
+    let state = State::load()?;
+
+    let reviews: Vec<&ReviewState> = match pr {
+        Some(pr) => vec![state.get_review(pr).ok_or(ChabaError::WorktreeNotFound(pr))?],
+        None => state.reviews.iter().collect(),
+    };
+
+    if reviews.is_empty() {
+        println!("No active review environments.");
+        return Ok(());
+    }
+
+    let mut total = 0;
+    let mut environments = 0;
+    for review in reviews {
+        if !review.worktree_path.exists() {
+            eprintln!("⚠️  PR #{}: worktree is missing, skipping", review.pr_number);
+            continue;
+        }
+
+        let matches = GitOps::for_review(review)?
+            .grep(&review.worktree_path, &pattern, ignore_case)
+            .await?;
+        if matches.is_empty() {
+            continue;
+        }
+
+        println!("PR #{} ({}) - {}", review.pr_number, review.branch, review.worktree_path.display());
+        for m in &matches {
+            println!("  {}:{}: {}", m.file, m.line, m.text.trim_end());
+        }
+        println!();
+
+        total += matches.len();
+        environments += 1;
+    }
+
+    if total == 0 {
+        println!("No matches for '{}'", pattern);
+    } else {
+        println!("{} match(es) in {} review environment(s)", total, environments);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use crate::commands::agent_result::print_agent_analysis;
+use crate::core::archive::{self, ArchivedReview};
+use crate::core::review_analysis::Usage;
+use crate::error::Result;
+use crate::say;
+
+/// Show archived reviews, or the archived reviews of one PR with their findings
+pub async fn execute(pr: Option<u32>) -> Result<()> {
+    // Only the archive is read, so this works outside any repository
+    let mut archived = archive::load()?;
+    archived.reverse();
+    if let Some(pr) = pr {
+        archived.retain(|a| a.review.pr_number == pr);
+    }
+
+    match (pr, archived.is_empty()) {
+        (Some(pr), true) => println!("No archived review of PR #{}", pr),
+        (None, true) => println!("No archived reviews. Reviews are archived when cleaned up."),
+        (Some(_), false) => {
+            for entry in &archived {
+                print_review(entry);
+            }
+        }
+        (None, false) => print_list(&archived),
+    }
+
+    Ok(())
+}
+
+/// One line per archived review, newest first
+fn print_list(archived: &[ArchivedReview]) {
+    println!("Archived reviews:\n");
+    println!("{:<8} {:<30} {:<17} {:<17} {:<9} Title", "PR #", "Branch", "Created", "Cleaned up", "Findings");
+    println!("{}", "-".repeat(100));
+
+    for ArchivedReview { archived_at, review } in archived {
+        let findings: usize = review.agent_analyses.iter().map(|a| a.findings.len()).sum();
+        println!(
+            "{:<8} {:<30} {:<17} {:<17} {:<9} {}",
+            review.pr_number,
+            review.branch,
+            review.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+            archived_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+            findings,
+            review.pr_info.as_ref().map(|info| info.headline()).unwrap_or_default()
+        );
+    }
+
+    println!("\nRun 'chaba history --pr <PR>' to see an archived review's findings");
+}
+
+/// An archived review with the findings of its last agent run
+fn print_review(ArchivedReview { archived_at, review }: &ArchivedReview) {
+    say!("📦 PR #{} - {}", review.pr_number, review.branch);
+    if let Some(info) = &review.pr_info {
+        println!("  Title: {}", info.headline());
+    }
+    if let Some(repo) = &review.repo {
+        println!("  Repository: {}", repo.display());
+    }
+    println!("  Created: {}", review.created_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"));
+    println!("  Cleaned up: {}", archived_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"));
+    if !review.analysis_history.is_empty() {
+        println!("  Earlier agent runs: {}", review.analysis_history.len());
+    }
+    if !review.agent_usage.is_empty() {
+        println!("  Agent usage: {}", review.agent_usage);
+    }
+    println!();
+
+    if review.agent_analyses.is_empty() {
+        println!("  No agent analysis was run\n");
+        return;
+    }
+    for analysis in &review.agent_analyses {
+        print_agent_analysis(analysis, false);
+    }
+    let usage = Usage::sum(&review.agent_analyses);
+    if !usage.is_empty() {
+        println!("  Latest run: {}\n", usage);
+    }
+}
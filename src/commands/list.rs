@@ -1,79 +1,538 @@
-use crate::config::Config;
-use crate::core::git::GitOps;
-use crate::core::worktree::WorktreeManager;
-use crate::error::Result;
+use futures::{future, stream, StreamExt};
+use std::str::FromStr;
+use std::time::Duration;
 
-pub async fn execute() -> Result<()> {
-    let config = Config::load()?;
-    let manager = WorktreeManager::new(config)?;
-    let git_ops = GitOps::open()?;
+use crate::core::ci;
+use crate::core::git::{GitOps, GitStats};
+use crate::core::launcher;
+use crate::core::review_analysis::Usage;
+use crate::core::stack;
+use crate::core::state::{ReviewState, State};
+use crate::core::stats_cache::{Fingerprint, StatsCache};
+use crate::core::ttl;
+use crate::error::{ChabaError, Result};
 
-    let reviews = manager.list()?;
+/// Output format for `chaba list`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Human-readable table
+    #[default]
+    Table,
+    /// Review state as JSON
+    Json,
+    /// Tab-separated rows with a header, for scripts
+    Tsv,
+    /// Alfred Script Filter JSON
+    Alfred,
+    /// List items for Raycast extensions
+    Raycast,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            "tsv" => Ok(OutputFormat::Tsv),
+            "alfred" => Ok(OutputFormat::Alfred),
+            "raycast" => Ok(OutputFormat::Raycast),
+            other => Err(format!(
+                "unknown output '{}' (expected table, json, tsv, alfred, or raycast)",
+                other
+            )),
+        }
+    }
+}
+
+/// Order of `chaba list`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListSort {
+    /// Newest first
+    Created,
+    /// By PR number
+    Pr,
+    /// Most changed lines first
+    Changes,
+}
+
+impl FromStr for ListSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "created" => Ok(ListSort::Created),
+            "pr" => Ok(ListSort::Pr),
+            "changes" => Ok(ListSort::Changes),
+            other => Err(format!("unknown sort '{}' (expected created, pr, or changes)", other)),
+        }
+    }
+}
+
+/// State of a review's worktree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorktreeStatus {
+    Ok,
+    /// A merge or rebase is left unfinished
+    Conflict,
+    /// The worktree directory no longer exists
+    Missing,
+}
+
+impl FromStr for WorktreeStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "ok" => Ok(WorktreeStatus::Ok),
+            "conflict" => Ok(WorktreeStatus::Conflict),
+            "missing" => Ok(WorktreeStatus::Missing),
+            other => Err(format!("unknown status '{}' (expected ok, conflict, or missing)", other)),
+        }
+    }
+}
+
+impl WorktreeStatus {
+    fn name(self) -> &'static str {
+        match self {
+            WorktreeStatus::Ok => "ok",
+            WorktreeStatus::Conflict => "conflict",
+            WorktreeStatus::Missing => "missing",
+        }
+    }
+}
+
+/// A `chaba list --filter KEY=VALUE` condition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListFilter {
+    /// `status=ok|conflict|missing`
+    Status(WorktreeStatus),
+    /// `project=<type>`, e.g. `project=rust`
+    Project(String),
+}
+
+impl FromStr for ListFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some(("status", value)) => Ok(ListFilter::Status(value.parse()?)),
+            Some(("project", value)) => Ok(ListFilter::Project(value.to_string())),
+            _ => Err(format!("unknown filter '{}' (expected status=<ok|conflict|missing> or project=<type>)", s)),
+        }
+    }
+}
+
+impl ListFilter {
+    /// Whether checking the filter needs git stats
+    fn needs_stats(&self) -> bool {
+        matches!(self, ListFilter::Status(status) if *status != WorktreeStatus::Missing)
+    }
+
+    fn matches(&self, entry: &Entry) -> bool {
+        match self {
+            ListFilter::Status(status) => entry.status() == *status,
+            ListFilter::Project(project) => entry
+                .review
+                .project_type
+                .as_deref()
+                .is_some_and(|p| p.eq_ignore_ascii_case(project)),
+        }
+    }
+}
+
+/// Options of `chaba list`
+#[derive(Debug, Clone, Default)]
+pub struct ListOptions {
+    pub output: OutputFormat,
+    /// Add each review's agent usage and a total
+    pub costs: bool,
+    /// Flat order instead of stacked PRs under their parents
+    pub sort: Option<ListSort>,
+    /// Only list reviews matching all of these
+    pub filters: Vec<ListFilter>,
+}
+
+/// Worktrees read at the same time
+const MAX_CONCURRENT: usize = 8;
+
+/// How long reading one worktree's git stats or CI checks may take
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A listed review with its git stats, when they were needed
+struct Entry<'a> {
+    review: &'a ReviewState,
+    /// Depth in the PR stack, 0 when sorted
+    depth: usize,
+    /// `None` for missing worktrees, and when reading them timed out
+    stats: Option<GitStats>,
+    /// CI status symbol, for the table
+    ci: &'static str,
+    /// Set when `stats` were read rather than cached, to cache them under
+    fingerprint: Option<Fingerprint>,
+}
+
+impl Entry<'_> {
+    fn status(&self) -> WorktreeStatus {
+        if !self.review.worktree_path.exists() {
+            WorktreeStatus::Missing
+        } else if self.stats.as_ref().is_some_and(GitStats::has_conflict) {
+            WorktreeStatus::Conflict
+        } else {
+            WorktreeStatus::Ok
+        }
+    }
+
+    /// Changed lines compared with the base
+    fn changed_lines(&self) -> usize {
+        self.stats.as_ref().map_or(0, |stats| stats.lines_added + stats.lines_deleted)
+    }
+
+    /// Commits ahead of and behind the PR's target branch, falling back to upstream tracking
+    fn ahead_behind(&self) -> (usize, usize) {
+        match &self.stats {
+            Some(GitStats { base: Some(base), .. }) => (base.commits_ahead, base.commits_behind),
+            Some(stats) => (stats.commits_ahead, stats.commits_behind),
+            None => (0, 0),
+        }
+    }
+}
+
+/// Read what `chaba list` shows about a review: git stats and CI status, when wanted
+///
+/// Git stats still valid in `cache` are reused. Each read is given
+/// [`FETCH_TIMEOUT`], so one worktree on a hanging network filesystem doesn't
+/// hold up the list.
+async fn fetch<'a>(
+    review: &'a ReviewState,
+    depth: usize,
+    with_stats: bool,
+    with_ci: bool,
+    cache: &StatsCache,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Entry<'a> {
+    let stats = async {
+        if !with_stats || !review.worktree_path.exists() {
+            return (None, None);
+        }
+        let base_branch = review.base_branch.as_deref();
+        let fingerprint = Fingerprint::of(&review.worktree_path);
+        if let Some(stats) = fingerprint
+            .as_ref()
+            .and_then(|fingerprint| cache.get(&review.worktree_path, base_branch, fingerprint, now))
+        {
+            return (Some(stats), None);
+        }
+
+        let stats = async {
+            let git_ops = GitOps::for_review(review).ok()?;
+            git_ops.get_stats(&review.worktree_path, base_branch).await.ok()
+        };
+        match tokio::time::timeout(FETCH_TIMEOUT, stats).await {
+            Ok(Some(stats)) => (Some(stats), fingerprint),
+            // Failures show as an unchanged worktree and aren't cached
+            Ok(None) => (Some(GitStats::default()), None),
+            Err(_) => {
+                tracing::warn!("Timed out reading git stats of PR #{}", review.pr_number);
+                (None, None)
+            }
+        }
+    };
+    let ci = async {
+        if !with_ci {
+            return "-";
+        }
+        tokio::time::timeout(FETCH_TIMEOUT, ci_status(review)).await.unwrap_or("?")
+    };
+    let ((stats, fingerprint), ci) = tokio::join!(stats, ci);
+    Entry { review, depth, stats, ci, fingerprint }
+}
+
+/// Cache the git stats that were read rather than reused
+fn save_stats<'a>(mut cache: StatsCache, entries: impl IntoIterator<Item = &'a Entry<'a>>, now: chrono::DateTime<chrono::Utc>) {
+    let mut changed = false;
+    for entry in entries {
+        if let (Some(fingerprint), Some(stats)) = (&entry.fingerprint, &entry.stats) {
+            let base_branch = entry.review.base_branch.as_deref();
+            cache.insert(&entry.review.worktree_path, base_branch, fingerprint.clone(), stats.clone(), now);
+            changed = true;
+        }
+    }
+    if changed {
+        if let Err(e) = cache.save(now) {
+            tracing::warn!("Failed to save git stats cache: {}", e);
+        }
+    }
+}
+
+/// CI status symbol of a review's PR
+async fn ci_status(review: &ReviewState) -> &'static str {
+    // Branch, patch, and local reviews have no checks to ask GitHub about
+    if !review.is_pull_request() {
+        return "-";
+    }
+    match GitOps::for_review(review) {
+        Ok(git_ops) => match git_ops.get_pr_checks(review.pr_number).await {
+            Ok(checks) => ci::summarize(&checks).symbol(),
+            Err(ChabaError::GhCliNotFound) => "-",
+            Err(_) => "?",
+        },
+        Err(_) => "?",
+    }
+}
+
+/// Show `chaba list`
+pub async fn execute(options: ListOptions) -> Result<()> {
+    // Only state is needed, so this works outside any repository
+    let reviews = State::load()?.reviews;
+
+    // Machine-readable formats only use recorded state, so launchers stay
+    // fast, unless sorting or filtering needs the worktrees' git stats
+    let table = options.output == OutputFormat::Table;
+    let needs_stats = matches!(options.output, OutputFormat::Table | OutputFormat::Tsv)
+        || options.sort == Some(ListSort::Changes)
+        || options.filters.iter().any(ListFilter::needs_stats);
+
+    let cache = if needs_stats { StatsCache::load() } else { StatsCache::default() };
+    let now = chrono::Utc::now();
+    // PR numbers repeat across repositories, so titles name theirs when there are several
+    let several_repos = reviews.iter().any(|review| review.repo != reviews[0].repo);
 
-    if reviews.is_empty() {
-        println!("No active review environments.");
+    // Worktrees are read concurrently; rows keep the stack order
+    let fetches = stream::iter(stack::stack_order(&reviews))
+        .map(|(depth, review)| fetch(review, depth, needs_stats, table, &cache, now))
+        .buffered(MAX_CONCURRENT);
+
+    // The plain table is printed row by row as results arrive
+    if table && options.sort.is_none() && options.filters.is_empty() && !reviews.is_empty() {
+        let missing: Vec<&ReviewState> = reviews.iter().filter(|review| !review.worktree_path.exists()).collect();
+        print_stale_warning(&missing);
+        print_table_header(options.costs);
+        let mut entries = Vec::new();
+        {
+            let mut fetches = std::pin::pin!(fetches);
+            while let Some(entry) = fetches.next().await {
+                print_table_row(&entry, options.costs, several_repos, now);
+                entries.push(entry);
+            }
+        }
+        save_stats(cache, &entries, now);
+        if options.costs {
+            print_total_usage(reviews.iter());
+        }
         return Ok(());
     }
 
-    // Check for missing worktrees and collect stale entries
-    let mut stale_prs = Vec::new();
-    for review in &reviews {
-        if !review.worktree_path.exists() {
-            stale_prs.push(review.pr_number);
+    let mut entries: Vec<Entry> = fetches
+        .filter(|entry| future::ready(options.filters.iter().all(|filter| filter.matches(entry))))
+        .collect()
+        .await;
+    save_stats(cache, &entries, now);
+
+    if let Some(sort) = options.sort {
+        for entry in &mut entries {
+            entry.depth = 0;
+        }
+        match sort {
+            ListSort::Created => entries.sort_by_key(|entry| std::cmp::Reverse(entry.review.created_at)),
+            ListSort::Pr => entries.sort_by_key(|entry| (entry.review.pr_number, entry.review.repo.clone())),
+            ListSort::Changes => entries.sort_by_key(|entry| std::cmp::Reverse(entry.changed_lines())),
         }
     }
 
-    // Warn about stale entries
-    if !stale_prs.is_empty() {
-        eprintln!("⚠️  Warning: Found {} stale worktree(s) that no longer exist:", stale_prs.len());
-        for pr in &stale_prs {
-            eprintln!("    PR #{} - worktree was manually removed", pr);
+    let selected = || entries.iter().map(|entry| entry.review.clone()).collect::<Vec<_>>();
+    let json = match options.output {
+        OutputFormat::Table => None,
+        OutputFormat::Tsv => {
+            print_tsv(&entries, options.costs);
+            return Ok(());
         }
-        eprintln!("\n💡 Tip: Run 'chaba cleanup --force --pr <PR>' to clean up the state.\n");
+        OutputFormat::Json => Some(serde_json::to_string_pretty(&selected())),
+        OutputFormat::Alfred => Some(serde_json::to_string(&launcher::alfred_items(&selected()))),
+        OutputFormat::Raycast => Some(serde_json::to_string(&launcher::raycast_items(&selected()))),
+    };
+    if let Some(json) = json {
+        println!("{}", json.map_err(|e| ChabaError::Other(e.into()))?);
+        return Ok(());
     }
 
-    println!("Active review environments:\n");
-    println!("{:<8} {:<30} {:<15} {:<15} {:<10} {}",
-        "PR #", "Branch", "Created", "Changes", "Commits", "Status");
-    println!("{}", "-".repeat(100));
+    if entries.is_empty() {
+        if reviews.is_empty() {
+            println!("No active review environments.");
+        } else {
+            println!("No review environments match the filters.");
+        }
+        return Ok(());
+    }
 
-    for review in reviews {
-        let time_ago = format_time_ago(review.created_at);
+    let missing: Vec<&ReviewState> = entries
+        .iter()
+        .filter(|entry| entry.status() == WorktreeStatus::Missing)
+        .map(|entry| entry.review)
+        .collect();
+    print_stale_warning(&missing);
+    print_table_header(options.costs);
+    for entry in &entries {
+        print_table_row(entry, options.costs, several_repos, now);
+    }
+    if options.costs {
+        print_total_usage(entries.iter().map(|entry| entry.review));
+    }
+
+    Ok(())
+}
+
+/// Warn about reviews whose worktree was removed by hand
+fn print_stale_warning(missing: &[&ReviewState]) {
+    if missing.is_empty() {
+        return;
+    }
+    eprintln!("⚠️  Warning: Found {} stale worktree(s) that no longer exist:", missing.len());
+    for review in missing {
+        eprintln!("    PR #{} - worktree was manually removed", review.pr_number);
+    }
+    eprintln!("\n💡 Tip: Run 'chaba cleanup --force --pr <PR>' to clean up the state.\n");
+}
 
-        let (status, changes, commits) = if review.worktree_path.exists() {
-            // Get git stats for existing worktrees
-            let stats = git_ops.get_stats(&review.worktree_path).await
-                .unwrap_or_default();
+fn print_table_header(costs: bool) {
+    println!("Active review environments:\n");
+    let cost_header = if costs { format!("{:<22} ", "Agent cost") } else { String::new() };
+    println!("{:<8} {:<30} {:<15} {:<15} {:<10} {:<8} {:<6} {:<10} {:<12} {:<10} {:<6} {}Title",
+        "PR #", "Branch", "Created", "Changes", "Commits", "Tests", "CI", "Expires", "Status", "Project", "Port", cost_header);
+    println!("{}", "-".repeat(165 + cost_header.len()));
+}
 
+fn print_table_row(entry: &Entry, costs: bool, several_repos: bool, now: chrono::DateTime<chrono::Utc>) {
+    let review = entry.review;
+    let time_ago = format_time_ago(review.created_at);
+
+    let (status, changes, commits) = match (entry.status(), &entry.stats) {
+        (WorktreeStatus::Missing, _) => ("⚠️  MISSING".to_string(), "-".to_string(), "-".to_string()),
+        // Reading the worktree timed out
+        (_, None) => ("?".to_string(), "?".to_string(), "?".to_string()),
+        (status, Some(stats)) => {
             let changes_str = if stats.files_changed > 0 || stats.lines_added > 0 || stats.lines_deleted > 0 {
                 format!("+{} -{}", stats.lines_added, stats.lines_deleted)
             } else {
                 "-".to_string()
             };
 
-            let commits_str = if stats.commits_ahead > 0 || stats.commits_behind > 0 {
-                format!("↑{} ↓{}", stats.commits_ahead, stats.commits_behind)
+            let (ahead, behind) = entry.ahead_behind();
+            let commits_str = if ahead > 0 || behind > 0 {
+                format!("↑{} ↓{}", ahead, behind)
             } else {
                 "-".to_string()
             };
 
-            ("✓".to_string(), changes_str, commits_str)
-        } else {
-            ("⚠️  MISSING".to_string(), "-".to_string(), "-".to_string())
-        };
+            let status = if status == WorktreeStatus::Conflict { "⚠️  CONFLICT" } else { "✓" };
+
+            (status.to_string(), changes_str, commits_str)
+        }
+    };
+
+    let tests = match &review.tests {
+        Some(t) if t.passed => "✓",
+        Some(_) => "✗",
+        None => "-",
+    };
+
+    let expires = review
+        .expires_at
+        .map(|expires_at| ttl::format_remaining(expires_at, now))
+        .unwrap_or_else(|| "-".to_string());
 
-        println!(
-            "{:<8} {:<30} {:<15} {:<15} {:<10} {}",
-            review.pr_number,
-            review.branch,
-            time_ago,
-            changes,
-            commits,
-            status
-        );
+    let mut title = review
+        .pr_info
+        .as_ref()
+        .map(|info| info.headline())
+        .unwrap_or_default();
+    if let Some(repo) = review.repo.as_ref().and_then(|repo| repo.file_name()).filter(|_| several_repos) {
+        title = format!("[{}] {}", repo.to_string_lossy(), title);
     }
 
-    Ok(())
+    let cost = if !costs {
+        String::new()
+    } else if review.agent_usage.is_empty() {
+        format!("{:<22} ", "-")
+    } else {
+        format!("{:<22} ", review.agent_usage.to_string())
+    };
+
+    println!(
+        "{:<8} {:<30} {:<15} {:<15} {:<10} {:<8} {:<6} {:<10} {:<12} {:<10} {:<6} {}{}",
+        review.pr_number,
+        format!("{}{}", stack::tree_prefix(entry.depth), review.branch),
+        time_ago,
+        changes,
+        commits,
+        tests,
+        entry.ci,
+        expires,
+        status,
+        review.project_type.as_deref().unwrap_or("-"),
+        review.port.map_or_else(|| "-".to_string(), |port| port.to_string()),
+        cost,
+        title
+    );
+}
+
+fn print_total_usage<'a>(reviews: impl Iterator<Item = &'a ReviewState>) {
+    let mut total = Usage::default();
+    for review in reviews {
+        total.add(&review.agent_usage);
+    }
+    if total.is_empty() {
+        println!("\nNo agent usage recorded (agents report it when they run)");
+    } else {
+        println!("\nTotal agent usage: {}", total);
+    }
+}
+
+/// Print entries as tab-separated values with a header row
+///
+/// Empty fields stay empty, and tabs and newlines in titles become spaces.
+/// CI status is left out since it takes a GitHub call per review.
+fn print_tsv(entries: &[Entry], costs: bool) {
+    let mut header = vec![
+        "pr", "branch", "created", "status", "added", "deleted", "ahead", "behind", "tests", "expires", "project",
+        "port", "path", "title",
+    ];
+    if costs {
+        header.push("agent_cost");
+    }
+    println!("{}", header.join("\t"));
+
+    for entry in entries {
+        let review = entry.review;
+        let (ahead, behind) = entry.ahead_behind();
+        let stats = entry.stats.clone().unwrap_or_default();
+        let mut fields = vec![
+            review.pr_number.to_string(),
+            review.branch.clone(),
+            review.created_at.to_rfc3339(),
+            entry.status().name().to_string(),
+            stats.lines_added.to_string(),
+            stats.lines_deleted.to_string(),
+            ahead.to_string(),
+            behind.to_string(),
+            match &review.tests {
+                Some(t) if t.passed => "passed".to_string(),
+                Some(_) => "failed".to_string(),
+                None => String::new(),
+            },
+            review.expires_at.map(|e| e.to_rfc3339()).unwrap_or_default(),
+            review.project_type.clone().unwrap_or_default(),
+            review.port.map(|port| port.to_string()).unwrap_or_default(),
+            review.worktree_path.display().to_string(),
+            review.pr_info.as_ref().map(|info| info.headline()).unwrap_or_default(),
+        ];
+        if costs {
+            fields.push(if review.agent_usage.is_empty() { String::new() } else { review.agent_usage.to_string() });
+        }
+        let fields: Vec<String> = fields.iter().map(|field| field.replace(['\t', '\n', '\r'], " ")).collect();
+        println!("{}", fields.join("\t"));
+    }
 }
 
 fn format_time_ago(created_at: chrono::DateTime<chrono::Utc>) -> String {
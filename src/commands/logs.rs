@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::core::logs::ReviewLogs;
+use crate::core::state::State;
+use crate::error::Result;
+use crate::say;
+
+pub async fn execute(pr: u32, follow: bool) -> Result<()> {
+    let logs = ReviewLogs::for_review(&State::load()?.review_key(pr))?;
+    let files = logs.files();
+
+    if files.is_empty() && !follow {
+        println!("No logs found for PR #{}", pr);
+        println!("  Expected location: {}", logs.dir().display());
+        return Ok(());
+    }
+
+    // Track how far each file has been printed so --follow only shows new output
+    let mut offsets: HashMap<PathBuf, u64> = HashMap::new();
+
+    for file in &files {
+        print_header(&logs, file);
+        let offset = print_from(file, 0)?;
+        offsets.insert(file.clone(), offset);
+        println!();
+    }
+
+    if !follow {
+        return Ok(());
+    }
+
+    println!("Following logs in {} (Ctrl+C to stop)...\n", logs.dir().display());
+
+    let mut last_printed: Option<PathBuf> = files.last().cloned();
+
+    loop {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        for file in logs.files() {
+            let offset = offsets.get(&file).copied().unwrap_or(0);
+            let len = std::fs::metadata(&file).map(|m| m.len()).unwrap_or(0);
+
+            if len <= offset {
+                continue;
+            }
+
+            if last_printed.as_ref() != Some(&file) {
+                print_header(&logs, &file);
+                last_printed = Some(file.clone());
+            }
+
+            let new_offset = print_from(&file, offset)?;
+            offsets.insert(file, new_offset);
+        }
+    }
+}
+
+fn print_header(logs: &ReviewLogs, file: &Path) {
+    let name = file.strip_prefix(logs.dir()).unwrap_or(file);
+    say!("━━━ {} ━━━", name.display());
+}
+
+/// Print file content starting at `offset`, returning the new offset
+fn print_from(path: &Path, offset: u64) -> Result<u64> {
+    let mut file = std::fs::File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+
+    let mut buf = Vec::new();
+    let read = file.read_to_end(&mut buf)?;
+    print!("{}", String::from_utf8_lossy(&buf));
+
+    Ok(offset + read as u64)
+}
@@ -1,14 +1,17 @@
+use crate::core::events::{self, EventKind};
 use crate::core::git::GitOps;
 use crate::core::state::State;
 use crate::error::{ChabaError, Result};
+use crate::say;
 
-pub async fn execute(pr: u32, from_branch: String) -> Result<()> {
+pub async fn execute(pr: Option<u32>, from_branch: String) -> Result<()> {
     let state = State::load()?;
+    let pr = state.resolve_pr(pr)?;
     let review = state
         .get_review(pr)
         .ok_or(ChabaError::WorktreeNotFound(pr))?;
 
-    println!("🍵 Chaba - Merging branch into worktree...\n");
+    say!("🍵 Chaba - Merging branch into worktree...\n");
     println!("PR #:         {}", pr);
     println!("Worktree:     {}", review.worktree_path.display());
     println!("Current:      {}", review.branch);
@@ -22,13 +25,14 @@ pub async fn execute(pr: u32, from_branch: String) -> Result<()> {
         )));
     }
 
-    let git_ops = GitOps::open()?;
+    let git_ops = GitOps::for_review(review)?;
 
     // Perform the merge
     println!("Merging...");
     git_ops.merge(&review.worktree_path, &from_branch).await?;
+    events::record(&review.key(), EventKind::Merged, from_branch.as_str());
 
-    println!("\n✓ Merge completed successfully!");
+    say!("\n✓ Merge completed successfully!");
     println!("\nNext steps:");
     println!("  cd {}", review.worktree_path.display());
     println!("  git push  # Push the merged changes");
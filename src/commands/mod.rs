@@ -1,9 +1,38 @@
+pub mod adopt;
 pub mod agent_result;
+pub mod analyze;
+pub mod apply;
+pub mod apply_fix;
+pub mod ask;
+pub mod auth;
+pub mod baseline;
 pub mod cleanup;
 pub mod config;
+pub mod detach;
+pub mod doctor;
+pub mod encrypt;
+pub mod events;
+pub mod grep;
+pub mod history;
 pub mod list;
+pub mod logs;
 pub mod merge;
+pub mod open;
+pub mod port;
+pub mod queue;
 pub mod rebase;
+pub mod recreate;
+pub mod report;
+pub mod resolve;
 pub mod review;
+pub mod rollback;
+pub mod snapshot;
+pub mod state;
 pub mod status;
+pub mod statusline;
+pub mod sync;
+pub mod trace;
+pub mod triage;
 pub mod tui;
+pub mod verdict;
+pub mod watch;
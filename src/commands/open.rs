@@ -0,0 +1,67 @@
+use std::path::Path;
+use tokio::process::Command;
+
+use crate::core::platform;
+use crate::core::state::{ReviewTarget, State};
+use crate::core::vscode;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+/// Open a review's worktree, in VS Code with a generated workspace if `vscode`
+pub async fn execute(target: Option<ReviewTarget>, vscode: bool, launch: bool) -> Result<()> {
+    let state = State::load()?;
+    let pr = state.resolve_target(target)?;
+    let review = state.get_review(pr).ok_or(ChabaError::WorktreeNotFound(pr))?;
+    if !review.worktree_path.exists() {
+        return Err(ChabaError::ConfigError(format!(
+            "PR #{}'s worktree is missing: {} (run 'chaba recreate --pr {}')",
+            pr,
+            review.worktree_path.display(),
+            pr
+        )));
+    }
+
+    if !vscode {
+        say!("📂 Opening {}", review.worktree_path.display());
+        return run(opener(), &review.worktree_path).await;
+    }
+
+    let workspace = vscode::write_workspace(review)?;
+    say!("✓ Wrote {}", workspace.display());
+    if launch {
+        match vscode::write_launch(review)? {
+            Some(path) => say!("✓ Wrote {}", path.display()),
+            None => say!("⚠️  Kept the existing {}", vscode::LAUNCH_FILE),
+        }
+        if review.port.is_none() {
+            say!("⚠️  No port is assigned to PR #{}; debug configurations use the app's default", pr);
+        }
+    }
+
+    say!("📂 Opening PR #{} in VS Code", pr);
+    run("code", &workspace).await
+}
+
+/// Program that opens a directory in the platform's file manager
+fn opener() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    }
+}
+
+async fn run(program: &str, path: &Path) -> Result<()> {
+    let status = Command::new(platform::resolve_program(program))
+        .arg(path)
+        .status()
+        .await
+        .map_err(|e| ChabaError::Other(anyhow::anyhow!("Cannot run {}: {}", program, e)))?;
+    // explorer exits with 1 even when it opened the folder
+    if !status.success() && program != "explorer" {
+        return Err(ChabaError::Other(anyhow::anyhow!("{} exited with {}", program, status)));
+    }
+    Ok(())
+}
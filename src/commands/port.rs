@@ -0,0 +1,141 @@
+use crate::config::Config;
+use crate::core::env::{self, TemplateVars};
+use crate::core::git::GitOps;
+use crate::core::metadata::ReviewMetadata;
+use crate::core::port::{self, PortManager, PortOwner};
+use crate::core::state::State;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+/// Check a review's port before starting its dev server, moving it if taken
+pub async fn execute(pr: u32) -> Result<()> {
+    let config = Config::load()?;
+    let mut state = State::load()?;
+    let mut review = state
+        .get_review(pr)
+        .cloned()
+        .ok_or(ChabaError::WorktreeNotFound(pr))?;
+
+    let Some(old_port) = review.port else {
+        println!("PR #{} has no assigned port", pr);
+        println!("  Enable sandbox.port to assign ports to review environments");
+        return Ok(());
+    };
+
+    let pids = match port::port_owner(old_port, &review) {
+        PortOwner::Free => {
+            say!("✓ Port {} is free for PR #{}", old_port, pr);
+            return Ok(());
+        }
+        PortOwner::Review => {
+            say!("✓ Port {} is in use by PR #{}'s own server", old_port, pr);
+            return Ok(());
+        }
+        PortOwner::Other { pids } => pids,
+    };
+
+    let sandbox = &config.sandbox;
+    let new_port = PortManager::new(sandbox.port.range_start, sandbox.port.range_end).assign_port(&state)?;
+    review.port = Some(new_port);
+
+    // Re-render {port} in env overrides and the metadata tools read
+    let main_worktree = GitOps::for_review(&review)?.repo_root();
+    let vars = TemplateVars {
+        pr,
+        port: Some(new_port),
+    };
+    let overrides = env::apply_env_overrides(
+        &main_worktree,
+        &review.worktree_path,
+        &sandbox.additional_env_files,
+        &sandbox.env,
+        &vars,
+    )
+    .await?;
+    ReviewMetadata::from_review(&review, &main_worktree)
+        .write(&review.worktree_path)
+        .await?;
+    state.update_review(&review.key(), |r| r.port = Some(new_port))?;
+
+    let holder = if pids.is_empty() {
+        "another process".to_string()
+    } else {
+        format!(
+            "another process (pid {})",
+            pids.iter().map(|pid| pid.to_string()).collect::<Vec<_>>().join(", ")
+        )
+    };
+    say!("⚠️  Port {} is taken by {}", old_port, holder);
+    say!("✓ Reassigned PR #{} to port {} (http://localhost:{})", pr, new_port, new_port);
+    if overrides > 0 {
+        println!("  Updated {} env override(s) with the new port", overrides);
+    }
+
+    Ok(())
+}
+
+/// List review ports and who holds them, releasing those of deleted worktrees with `prune`
+pub async fn list(prune: bool) -> Result<()> {
+    let config = Config::load()?;
+    let mut state = State::load()?;
+    let range = &config.sandbox.port;
+
+    say!("🍵 Chaba - Review ports ({}-{})\n", range.range_start, range.range_end);
+
+    let mut reviews: Vec<_> = state.reviews.iter().filter(|review| review.port.is_some()).collect();
+    reviews.sort_by_key(|review| review.port);
+    if reviews.is_empty() {
+        println!("No review has an assigned port");
+    }
+    for review in reviews {
+        let port = review.port.unwrap_or_default();
+        let status = if port::is_stale(review) {
+            if prune {
+                "worktree missing, released".to_string()
+            } else {
+                "worktree missing (run 'chaba ports --prune' to release)".to_string()
+            }
+        } else {
+            match port::port_owner(port, review) {
+                PortOwner::Free => "free".to_string(),
+                PortOwner::Review => "serving".to_string(),
+                PortOwner::Other { pids } if pids.is_empty() => {
+                    format!("conflict: taken by another process (run 'chaba port --pr {}')", review.pr_number)
+                }
+                PortOwner::Other { pids } => format!(
+                    "conflict: taken by pid {} (run 'chaba port --pr {}')",
+                    pids.iter().map(|pid| pid.to_string()).collect::<Vec<_>>().join(", "),
+                    review.pr_number
+                ),
+            }
+        };
+        println!("  :{:<6} PR #{:<6} {}", port, review.pr_number, status);
+    }
+
+    if prune {
+        let released = port::release_stale(&mut state)?;
+        if !released.is_empty() {
+            println!();
+        }
+        for (pr, port) in &released {
+            say!("✓ Released port {} from PR #{}", port, pr);
+        }
+    }
+
+    let held: Vec<u16> = state
+        .reviews
+        .iter()
+        .filter(|review| !port::is_stale(review))
+        .filter_map(|review| review.port)
+        .collect();
+    let free = (range.range_start..=range.range_end)
+        .filter(|port| !held.contains(port) && !port::is_port_in_use(*port))
+        .count();
+    println!(
+        "\n{} of {} port(s) free for new reviews",
+        free,
+        usize::from(range.range_end.saturating_sub(range.range_start)) + 1
+    );
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use crate::config::Config;
+use crate::core::quota::QuotaLedger;
+use crate::error::Result;
+use crate::say;
+
+/// Show today's agent quota usage and runs deferred until the quota allows
+pub async fn execute() -> Result<()> {
+    let config = Config::load()?;
+    let quota = &config.agents.quota;
+    let ledger = QuotaLedger::load()?;
+    let now = chrono::Utc::now();
+
+    say!("🍵 Chaba - Agent run queue\n");
+
+    if quota.is_limited() {
+        let used = ledger.used(None, now);
+        match quota.per_day {
+            Some(per_day) => println!("Today: {} of {} agent runs used", used, per_day),
+            None => println!("Today: {} agent runs used", used),
+        }
+        for (author, used) in ledger.used_by_author(now) {
+            match quota.per_user {
+                Some(per_user) => println!("  @{:<20} {} of {}", author, used, per_user),
+                None => println!("  @{:<20} {}", author, used),
+            }
+        }
+    } else {
+        println!("No agent quota configured (agents.quota); batch and watch runs are not capped");
+    }
+
+    if ledger.deferred.is_empty() {
+        println!("\nNo deferred agent runs.");
+        return Ok(());
+    }
+
+    println!("\nDeferred agent runs ({}), oldest first:", ledger.deferred.len());
+    for run in &ledger.deferred {
+        let author = run.author.as_deref().map(|a| format!("@{}", a)).unwrap_or_default();
+        let mode = if run.thorough { " (thorough)" } else { "" };
+        println!(
+            "  #{:<6} {:<20} {:<16} {}{}",
+            run.pr_number,
+            author,
+            format_time_ago(run.deferred_at),
+            run.reason,
+            mode
+        );
+    }
+    println!("\n'chaba watch' starts deferred runs once the quota allows.");
+
+    Ok(())
+}
+
+fn format_time_ago(deferred_at: chrono::DateTime<chrono::Utc>) -> String {
+    let now = chrono::Utc::now();
+    let duration = now.signed_duration_since(deferred_at);
+
+    if duration.num_days() > 0 {
+        format!("{} days ago", duration.num_days())
+    } else if duration.num_hours() > 0 {
+        format!("{} hours ago", duration.num_hours())
+    } else if duration.num_minutes() > 0 {
+        format!("{} minutes ago", duration.num_minutes())
+    } else {
+        "just now".to_string()
+    }
+}
@@ -1,14 +1,16 @@
 use crate::core::git::GitOps;
 use crate::core::state::State;
 use crate::error::{ChabaError, Result};
+use crate::say;
 
-pub async fn execute(pr: u32, onto_branch: String) -> Result<()> {
+pub async fn execute(pr: Option<u32>, onto_branch: String) -> Result<()> {
     let state = State::load()?;
+    let pr = state.resolve_pr(pr)?;
     let review = state
         .get_review(pr)
         .ok_or(ChabaError::WorktreeNotFound(pr))?;
 
-    println!("🍵 Chaba - Rebasing worktree onto branch...\n");
+    say!("🍵 Chaba - Rebasing worktree onto branch...\n");
     println!("PR #:        {}", pr);
     println!("Worktree:    {}", review.worktree_path.display());
     println!("Current:     {}", review.branch);
@@ -22,13 +24,13 @@ pub async fn execute(pr: u32, onto_branch: String) -> Result<()> {
         )));
     }
 
-    let git_ops = GitOps::open()?;
+    let git_ops = GitOps::for_review(review)?;
 
     // Perform the rebase
     println!("Rebasing...");
     git_ops.rebase(&review.worktree_path, &onto_branch).await?;
 
-    println!("\n✓ Rebase completed successfully!");
+    say!("\n✓ Rebase completed successfully!");
     println!("\nNext steps:");
     println!("  cd {}", review.worktree_path.display());
     println!("  git push --force-with-lease  # Force push the rebased changes");
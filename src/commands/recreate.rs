@@ -0,0 +1,49 @@
+use crate::commands::cleanup::{self, UnsavedWorkAction};
+use crate::config::Config;
+use crate::core::state::State;
+use crate::core::worktree::WorktreeManager;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+pub async fn execute(pr: u32, unsaved: UnsavedWorkAction) -> Result<()> {
+    let config = Config::load()?;
+    let manager = WorktreeManager::new(config)?;
+
+    let state = State::load()?;
+    let review = state.get_review(pr).ok_or(ChabaError::WorktreeNotFound(pr))?;
+
+    match unsaved {
+        UnsavedWorkAction::Ask => {
+            let work = cleanup::unsaved_work(review).await?;
+            if !work.is_empty() {
+                cleanup::print_unsaved_work(&work);
+                return Err(ChabaError::ConfigError(format!(
+                    "PR #{}'s worktree has unsaved work; use --archive to save it to a branch first, or --discard-changes to rebuild anyway",
+                    pr
+                )));
+            }
+        }
+        UnsavedWorkAction::Archive => {
+            let branch = manager.backup(review).await?;
+            say!("✓ Saved PR #{} to branch {}", pr, branch);
+        }
+        UnsavedWorkAction::Discard => {}
+    }
+    let old_port = review.port;
+
+    say!("🍵 Chaba - Rebuilding review environment for PR #{}...\n", pr);
+    let review = manager.recreate(pr).await?;
+
+    say!("✓ Recreated {}", review.worktree_path.display());
+    println!("  Branch: {}", review.branch);
+    match (review.port, old_port) {
+        (Some(port), Some(old)) if port != old => println!("  Port: {} (port {} was taken)", port, old),
+        (Some(port), _) => println!("  Port: {}", port),
+        (None, _) => {}
+    }
+    if !review.agent_analyses.is_empty() {
+        println!("  Kept {} agent analysis(es)", review.agent_analyses.len());
+    }
+
+    Ok(())
+}
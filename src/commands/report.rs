@@ -0,0 +1,66 @@
+use chrono::{Duration, Utc};
+
+use crate::core::archive;
+use crate::core::report::{self, ReviewRecord};
+use crate::core::review_analysis::{Category, Severity};
+use crate::core::state::State;
+use crate::error::Result;
+use crate::say;
+
+/// Show `chaba report --summary` for reviews created in the last `since`
+pub async fn execute(since: Duration) -> Result<()> {
+    // Only state and the archive are read, so this works outside any repository
+    let mut records: Vec<ReviewRecord> = archive::load()?
+        .iter()
+        .map(|archived| ReviewRecord::from_review(&archived.review, Some(archived.archived_at)))
+        .collect();
+    records.extend(State::load()?.reviews.iter().map(|review| ReviewRecord::from_review(review, None)));
+
+    let start = Utc::now() - since;
+    let summary = report::summarize(&records, start);
+
+    say!("📈 Review summary since {}\n", start.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"));
+
+    if summary.reviews == 0 {
+        println!("No reviews were created in this period.");
+        return Ok(());
+    }
+
+    println!("Reviews created: {} ({} still active)", summary.reviews, summary.active);
+    match summary.average_time_to_cleanup {
+        Some(average) => println!("Average time to cleanup: {}", report::format_duration(average)),
+        None => println!("Average time to cleanup: - (none cleaned up yet)"),
+    }
+
+    let runs: usize = summary.agent_runs.values().sum();
+    say!("\n🤖 Agent runs: {}", runs);
+    for (agent, count) in &summary.agent_runs {
+        println!("  {:<14} {}", agent, count);
+    }
+
+    say!("\n🔍 Findings: {}", summary.findings());
+    for severity in Severity::ALL {
+        if let Some(count) = summary.severities.get(&severity) {
+            println!("  {:<14} {}", format!("{:?}", severity), count);
+        }
+    }
+    if !summary.categories.is_empty() {
+        println!("  By category:");
+        for category in Category::ALL {
+            if let Some(count) = summary.categories.get(&category) {
+                println!("    {:<14} {}", format!("{:?}", category), count);
+            }
+        }
+    }
+
+    say!("\n📁 By repository:");
+    println!("  {:<24} {:>8} {:>11} {:>9}", "Repository", "Reviews", "Agent runs", "Findings");
+    for (repo, repo_summary) in &summary.repos {
+        println!(
+            "  {:<24} {:>8} {:>11} {:>9}",
+            repo, repo_summary.reviews, repo_summary.agent_runs, repo_summary.findings
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use crate::config::Config;
+use crate::core::agent::AgentManager;
+use crate::core::git::GitOps;
+use crate::core::state::State;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+pub async fn execute(pr: u32, abort: bool, continue_: bool, with_agent: bool) -> Result<()> {
+    let state = State::load()?;
+    let review = state
+        .get_review(pr)
+        .ok_or(ChabaError::WorktreeNotFound(pr))?;
+
+    if !review.worktree_path.exists() {
+        return Err(ChabaError::Other(anyhow::anyhow!(
+            "Worktree does not exist: {}",
+            review.worktree_path.display()
+        )));
+    }
+
+    let operation = match GitOps::operation_in_progress(&review.worktree_path) {
+        Some(operation) => operation,
+        None => {
+            say!("✓ PR #{}: no merge or rebase in progress", pr);
+            return Ok(());
+        }
+    };
+    let conflicted = GitOps::conflicted_files(&review.worktree_path);
+
+    say!("🍵 Chaba - Resolving conflicts...\n");
+    println!("PR #:         {}", pr);
+    println!("Worktree:     {}", review.worktree_path.display());
+    println!("Operation:    {} (in progress)", operation);
+
+    if conflicted.is_empty() {
+        println!("Conflicts:    none remaining");
+    } else {
+        println!("Conflicts:    {} file(s)", conflicted.len());
+        for file in &conflicted {
+            println!("  ✗ {}", file);
+        }
+    }
+    println!();
+
+    let git_ops = GitOps::for_review(review)?;
+
+    if abort {
+        git_ops.abort_operation(&review.worktree_path, operation).await?;
+        say!("✓ Aborted {}; worktree restored", operation);
+        return Ok(());
+    }
+
+    if with_agent && !conflicted.is_empty() {
+        let config = Config::load()?;
+        let manager = AgentManager::new(config.agents);
+
+        say!("🤖 Asking agent for resolution proposals...\n");
+        let (agent, proposal) = manager
+            .propose_conflict_resolution(&review.worktree_path, operation.command(), &conflicted)
+            .await?;
+
+        say!("┌────────────────────────────────────────────────────────────┐");
+        say!("│ 🤖 Agent: {:<50} │", agent);
+        say!("└────────────────────────────────────────────────────────────┘");
+        println!("{}\n", proposal.trim_end());
+    }
+
+    if continue_ {
+        git_ops.continue_operation(&review.worktree_path, operation).await?;
+        say!("✓ {} completed", operation);
+        return Ok(());
+    }
+
+    println!("Next steps:");
+    println!("  cd {}", review.worktree_path.display());
+    println!("  # edit the conflicted files, then: git add <files>");
+    println!("  chaba resolve --pr {} --continue   # or --abort", pr);
+
+    Ok(())
+}
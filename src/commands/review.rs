@@ -1,50 +1,173 @@
-use crate::config::Config;
-use crate::core::agent::AgentManager;
+use crate::config::{AgentProfile, AgentScope, Config};
+use crate::core::activity;
+use crate::core::agent::{AgentManager, AgentRun};
+use crate::core::anonymize;
+use crate::core::cancel;
+use crate::core::ci::{self, CiStatus};
+use crate::core::git::GitOps;
+use crate::core::history;
 use crate::core::hooks::HookManager;
+use crate::core::logs::ReviewLogs;
+use crate::core::notification;
+use crate::core::queue::{self, PrFilter};
+use crate::core::quota;
+use crate::core::review_analysis::{Category, Usage};
 use crate::core::session::SessionManager;
-use crate::core::state::State;
-use crate::core::worktree::WorktreeManager;
-use crate::error::Result;
+use crate::core::state::{ReviewState, State};
+use crate::core::ttl;
+use crate::core::webhook;
+use crate::core::worktree::{ReviewSource, WorktreeManager};
+use crate::error::{ChabaError, Result};
+use crate::say;
+use futures::stream::{self, StreamExt};
 use std::path::PathBuf;
+use std::time::Duration;
+
+/// How long `--wait-ci` waits for pending checks
+const CI_WAIT_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+const CI_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Most open PRs `--all-open` looks at
+const OPEN_PR_LIMIT: u32 = 100;
+
+/// Options for `chaba review`
+#[derive(Debug, Default)]
+pub struct ReviewOptions {
+    pub pr: Option<u32>,
+    pub branch: Option<String>,
+    /// Patch file to review instead of a PR or branch
+    pub patch: Option<PathBuf>,
+    /// Branch to apply `patch` on (defaults to the repository's default branch)
+    pub base: Option<String>,
+    /// Review uncommitted changes in the current repository
+    pub local: bool,
+    /// Use sparse checkout, also including these paths
+    pub sparse: Option<Vec<String>>,
+    pub force: bool,
+    pub worktree: Option<String>,
+    pub with_agent: bool,
+    pub thorough: bool,
+    /// Run only these agent profiles (see `agents.profiles`)
+    pub profiles: Vec<String>,
+    /// Hold agent analysis until the PR's CI checks pass
+    pub wait_ci: bool,
+    /// Print the agent invocations instead of running the agents
+    pub dry_run: bool,
+    pub copy_session_from: Option<String>,
+    pub ttl: Option<chrono::Duration>,
+}
+
+/// Options for `chaba review --all-open`
+#[derive(Debug)]
+pub struct BatchOptions {
+    pub filter: PrFilter,
+    /// How many environments to set up at once
+    pub jobs: usize,
+    pub sparse: Option<Vec<String>>,
+    pub force: bool,
+    pub with_agent: bool,
+    pub thorough: bool,
+    pub wait_ci: bool,
+    pub ttl: Option<chrono::Duration>,
+}
+
+pub async fn execute(options: ReviewOptions) -> Result<()> {
+    let ReviewOptions {
+        pr,
+        branch,
+        patch,
+        base,
+        local,
+        sparse,
+        force,
+        worktree,
+        with_agent,
+        thorough,
+        profiles,
+        wait_ci,
+        dry_run,
+        copy_session_from,
+        ttl,
+    } = options;
+
+    let source = match (pr, branch, patch, local) {
+        (Some(pr), None, None, false) => ReviewSource::Pr(pr),
+        (None, Some(branch), None, false) => ReviewSource::Branch(branch),
+        (None, None, Some(path), false) => ReviewSource::Patch { path, base },
+        (None, None, None, true) => ReviewSource::Local,
+        _ => return Err(ChabaError::InvalidInput),
+    };
+    if wait_ci && !matches!(source, ReviewSource::Pr(_)) {
+        return Err(ChabaError::ConfigError("--wait-ci needs a PR number".to_string()));
+    }
 
-pub async fn execute(
-    pr: Option<u32>,
-    branch: Option<String>,
-    force: bool,
-    worktree: Option<String>,
-    with_agent: bool,
-    thorough: bool,
-    copy_session_from: Option<String>,
-) -> Result<()> {
     let config = Config::load()?;
+    // Resolve profiles before creating the environment so a typo fails fast
+    let profiles = resolve_profiles(&config, &profiles)?;
     let manager = WorktreeManager::new(config.clone())?;
 
-    println!("🍵 Chaba - Creating review environment...\n");
+    say!("🍵 Chaba - Creating review environment...\n");
 
-    let mut review = manager.create(pr, branch.clone(), force, worktree).await?;
+    let mut review = match manager.create(source, force, worktree, ttl, sparse).await {
+        Ok(review) => review,
+        Err(e) => {
+            notification::notify(&config.notifications, "Review setup failed", &e.to_string()).await;
+            return Err(e);
+        }
+    };
 
-    println!("✓ Fetched branch: {}", review.branch);
-    println!("✓ Created worktree at: {}", review.worktree_path.display());
+    if local {
+        say!("✓ Copied local changes on top of: {}", review.branch);
+    } else {
+        say!("✓ Fetched branch: {}", review.branch);
+    }
+    if let Some(patch) = &review.patch {
+        say!("✓ Applied patch: {}", patch.display());
+    }
+    if let Some(parent) = review.parent_pr {
+        say!("✓ Stacked on PR #{}", parent);
+    }
+    say!("✓ Created worktree at: {}", review.worktree_path.display());
+    if let Some(sparse_paths) = &review.sparse_paths {
+        if sparse_paths.is_empty() {
+            say!("✓ Sparse checkout: repository root files only");
+        } else {
+            say!("✓ Sparse checkout: {}", sparse_paths.join(", "));
+        }
+    }
 
     if let Some(project_type) = &review.project_type {
-        println!("✓ Detected project type: {}", project_type);
+        say!("✓ Detected project type: {}", project_type);
     }
 
     if review.deps_installed {
-        println!("✓ Dependencies installed");
+        say!("✓ Dependencies installed");
     }
 
     if review.env_copied {
-        println!("✓ Environment files copied");
+        say!("✓ Environment files copied");
     }
 
     if let Some(port) = review.port {
-        println!("✓ Assigned port: {}", port);
+        say!("✓ Assigned port: {}", port);
+    }
+
+    if let Some(seed) = &review.seed {
+        let mark = if seed.succeeded() { "✓" } else { "✗" };
+        println!("{} Seeded data: {}", mark, seed.summary());
+    }
+
+    if let Some(tests) = &review.tests {
+        println!("{} Tests ({})", if tests.passed { "✓" } else { "✗" }, tests.command);
+    }
+
+    if let Some(expires_at) = review.expires_at {
+        say!("✓ Expires in {}", ttl::format_remaining(expires_at, chrono::Utc::now()));
     }
 
     // Copy session data if requested
     if let Some(source_path_str) = copy_session_from {
-        println!("\n📋 Copying Claude Code session data...");
+        say!("\n📋 Copying Claude Code session data...");
 
         let session_manager = SessionManager::new()?;
         let source_path = PathBuf::from(source_path_str);
@@ -52,10 +175,10 @@ pub async fn execute(
 
         match session_manager.copy_session_data(&source_path, target_path).await {
             Ok(true) => {
-                println!("✓ Session data copied successfully");
+                say!("✓ Session data copied successfully");
             }
             Ok(false) => {
-                println!("⚠️  No session data found at source path");
+                say!("⚠️  No session data found at source path");
             }
             Err(e) => {
                 eprintln!("⚠️  Warning: Failed to copy session data: {}", e);
@@ -65,11 +188,14 @@ pub async fn execute(
     }
 
     // Run post-create hook if configured
-    let hook_manager = HookManager::new(config.hooks.clone());
+    let mut hook_manager = HookManager::new(config.hooks.clone());
+    if let Ok(logs) = ReviewLogs::for_review(&review.key()) {
+        hook_manager = hook_manager.with_logs(logs);
+    }
     hook_manager.run_post_create(&review.worktree_path, &review.branch, review.pr_number);
 
     // Run AI agents if requested
-    let run_agents = if with_agent || thorough {
+    let mut run_agents = if with_agent || thorough || !profiles.is_empty() || dry_run {
         true
     } else if config.agents.enabled {
         // Interactive mode: ask if user wants to run agents
@@ -84,28 +210,31 @@ pub async fn execute(
         false
     };
 
-    if run_agents {
-        println!("\n🤖 Running AI agent analysis...");
-
-        let agent_manager = AgentManager::new(config.agents);
-        let pr_number = review.pr_number;
-        let analyses = agent_manager
-            .run_review(pr_number, &review.worktree_path, thorough)
-            .await?;
-
-        if !analyses.is_empty() {
-            println!("✓ Completed analysis with {} agent(s)", analyses.len());
-
-            // Save analyses to state
-            review.agent_analyses = analyses;
-            let mut state = State::load()?;
-            state.add_review(review.clone())?;
+    if run_agents && wait_ci && !dry_run {
+        run_agents = ci_allows_agents(review.pr_number).await?;
+    }
 
-            println!("\nRun 'chaba agent-result {}' to view detailed results", pr_number);
-        }
+    if run_agents && dry_run {
+        let selection = if !profiles.is_empty() {
+            Selection::Profiles(&profiles)
+        } else if thorough {
+            Selection::Agents(&config.agents.thorough_agents)
+        } else {
+            Selection::Agents(&config.agents.default_agents)
+        };
+        show_invocations(&config, &review, selection, local).await?;
+    } else if run_agents && !profiles.is_empty() {
+        analyze_profiles(&config, &mut review, &profiles, local).await?;
+    } else if run_agents {
+        analyze(&config, &mut review, thorough, local).await?;
+    }
+    // An agent run already notified
+    if !run_agents {
+        let body = format!("PR #{} ({}) at {}", review.pr_number, review.branch, review.worktree_path.display());
+        notification::notify(&config.notifications, "Review environment ready", &body).await;
     }
 
-    println!("\n✨ Ready to review!");
+    say!("\n✨ Ready to review!");
     println!("\nTo start reviewing:");
     println!("  cd {}", review.worktree_path.display());
 
@@ -117,3 +246,536 @@ pub async fn execute(
 
     Ok(())
 }
+
+/// Create review environments for every open PR matching the filter
+///
+/// PRs that already have an environment are skipped unless `force` is set.
+/// Failures are reported per PR and don't stop the others.
+pub async fn execute_all_open(options: BatchOptions) -> Result<()> {
+    let config = Config::load()?;
+    let manager = WorktreeManager::new(config.clone())?;
+    let git_ops = GitOps::open()?;
+
+    let open = git_ops.list_open_prs(OPEN_PR_LIMIT).await?;
+    let existing: Vec<u32> = if options.force {
+        Vec::new()
+    } else {
+        manager.list()?.iter().map(|review| review.pr_number).collect()
+    };
+    let prs = queue::select(open, &options.filter, &existing);
+
+    if prs.is_empty() {
+        println!("No open PRs to review.");
+        return Ok(());
+    }
+
+    say!(
+        "🍵 Chaba - Creating {} review environment(s), {} at a time...\n",
+        prs.len(),
+        options.jobs
+    );
+    for pr in &prs {
+        println!("  #{:<6} {}", pr.number, pr.info.headline());
+    }
+    println!();
+
+    let results: Vec<(u32, Result<ReviewState>)> = stream::iter(prs)
+        .map(|pr| {
+            let (config, manager, options) = (&config, &manager, &options);
+            async move {
+                // After Ctrl-C, don't start environments for the PRs still queued
+                if cancel::is_cancelled() {
+                    return (pr.number, Err(ChabaError::Interrupted));
+                }
+                let result = create_for_batch(config, manager, pr.number, options).await;
+                match &result {
+                    Ok(review) => say!("✓ PR #{}: {}", pr.number, review.worktree_path.display()),
+                    Err(e) => eprintln!("✗ PR #{}: {}", pr.number, e),
+                }
+                (pr.number, result)
+            }
+        })
+        .buffer_unordered(options.jobs.max(1))
+        .collect()
+        .await;
+
+    let failed: Vec<u32> = results
+        .iter()
+        .filter(|(_, result)| result.is_err())
+        .map(|(pr, _)| *pr)
+        .collect();
+
+    say!(
+        "\n✨ Created {} of {} review environment(s)",
+        results.len() - failed.len(),
+        results.len()
+    );
+    let mut body = format!("Created {} of {} review environment(s)", results.len() - failed.len(), results.len());
+    if !failed.is_empty() {
+        let failed: Vec<String> = failed.iter().map(|pr| format!("#{}", pr)).collect();
+        say!("⚠️  Failed: {}", failed.join(", "));
+        body.push_str(&format!("; failed: {}", failed.join(", ")));
+    }
+    notification::notify(&config.notifications, "Review setup finished", &body).await;
+    println!("\nRun 'chaba list' to see them.");
+
+    if cancel::is_cancelled() {
+        return Err(ChabaError::Interrupted);
+    }
+    Ok(())
+}
+
+/// Create one environment of a batch, then run hooks and (optionally) agents
+async fn create_for_batch(
+    config: &Config,
+    manager: &WorktreeManager,
+    pr: u32,
+    options: &BatchOptions,
+) -> Result<ReviewState> {
+    let mut review = manager
+        .create(ReviewSource::Pr(pr), options.force, None, options.ttl, options.sparse.clone())
+        .await?;
+
+    let mut hook_manager = HookManager::new(config.hooks.clone());
+    if let Ok(logs) = ReviewLogs::for_review(&review.key()) {
+        hook_manager = hook_manager.with_logs(logs);
+    }
+    hook_manager.run_post_create(&review.worktree_path, &review.branch, pr);
+
+    if options.with_agent || options.thorough {
+        let run_agents = !options.wait_ci || ci_allows_agents(pr).await?;
+        if run_agents {
+            analyze_within_quota(config, &mut review, options.thorough).await?;
+        }
+    }
+
+    Ok(review)
+}
+
+/// Wait for the PR's CI checks, returning whether agent analysis should run
+async fn ci_allows_agents(pr_number: u32) -> Result<bool> {
+    say!("\n⏳ Waiting for CI checks...");
+    let git_ops = GitOps::open()?;
+    match ci::wait_for_checks(&git_ops, pr_number, CI_WAIT_TIMEOUT, CI_POLL_INTERVAL).await? {
+        CiStatus::Passing => say!("✓ CI checks passed"),
+        CiStatus::NoChecks => say!("✓ No CI checks configured"),
+        CiStatus::Failing => {
+            say!("✗ CI checks failed; skipping AI agent analysis");
+            println!("  Run 'chaba status --pr {}' to see the failing checks", pr_number);
+            return Ok(false);
+        }
+        CiStatus::Pending => {
+            say!(
+                "⚠️  CI checks still pending after {} minutes; skipping AI agent analysis",
+                CI_WAIT_TIMEOUT.as_secs() / 60
+            );
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Run AI agents unattended, counting the run against `agents.quota`
+///
+/// Returns `false` when the run was deferred because it would exceed the quota.
+pub async fn analyze_within_quota(config: &Config, review: &mut ReviewState, thorough: bool) -> Result<bool> {
+    let agents = if thorough {
+        config.agents.thorough_agents.len()
+    } else {
+        config.agents.default_agents.len()
+    };
+    let author = review.pr_info.as_ref().map(|info| info.author.as_str());
+
+    if let Some(reason) = quota::reserve(&config.agents.quota, review.pr_number, author, agents, thorough)? {
+        say!(
+            "⏸  PR #{}: agent run deferred, {}; see 'chaba queue'",
+            review.pr_number, reason
+        );
+        return Ok(false);
+    }
+
+    analyze(config, review, thorough, false).await?;
+    Ok(true)
+}
+
+/// Run AI agents on a review and save their analyses
+pub async fn analyze(config: &Config, review: &mut ReviewState, thorough: bool, local: bool) -> Result<()> {
+    let agents = if thorough {
+        &config.agents.thorough_agents
+    } else {
+        &config.agents.default_agents
+    };
+    analyze_with(config, review, agents, local, false).await
+}
+
+/// Look up agent profiles by name
+fn resolve_profiles(config: &Config, names: &[String]) -> Result<Vec<(String, AgentProfile)>> {
+    names
+        .iter()
+        .map(|name| {
+            let profile = config.agents.profile(name).ok_or_else(|| {
+                ChabaError::ConfigError(format!(
+                    "Unknown agent profile '{}' (available: {})",
+                    name,
+                    config.agents.profile_names().join(", ")
+                ))
+            })?;
+            if !AgentManager::is_supported(&profile.agent) {
+                return Err(ChabaError::ConfigError(format!(
+                    "Agent profile '{}' uses unknown agent '{}'",
+                    name, profile.agent
+                )));
+            }
+            if let Some(category) = &profile.category {
+                category.parse::<Category>().map_err(|e| {
+                    ChabaError::ConfigError(format!("Agent profile '{}': {}", name, e))
+                })?;
+            }
+            Ok((name.clone(), profile))
+        })
+        .collect()
+}
+
+/// Run agent profiles on a review and save their analyses
+pub async fn analyze_profiles(
+    config: &Config,
+    review: &mut ReviewState,
+    profiles: &[(String, AgentProfile)],
+    local: bool,
+) -> Result<()> {
+    run_analysis(config, review, Selection::Profiles(profiles), local, false).await
+}
+
+/// Run `agents` on a review and save their analyses
+///
+/// With `merge`, analyses by agents that didn't run are kept; otherwise the
+/// new run replaces them.
+pub async fn analyze_with(
+    config: &Config,
+    review: &mut ReviewState,
+    agents: &[String],
+    local: bool,
+    merge: bool,
+) -> Result<()> {
+    run_analysis(config, review, Selection::Agents(agents), local, merge).await
+}
+
+/// What an analysis run executes
+#[derive(Clone, Copy)]
+enum Selection<'a> {
+    Agents(&'a [String]),
+    Profiles(&'a [(String, AgentProfile)]),
+}
+
+impl Selection<'_> {
+    fn runs(self) -> Vec<AgentRun> {
+        match self {
+            Selection::Agents(agents) => agents.iter().map(AgentRun::agent).collect(),
+            Selection::Profiles(profiles) => profiles
+                .iter()
+                .map(|(name, profile)| AgentRun::profile(name, profile.clone()))
+                .collect(),
+        }
+    }
+}
+
+async fn run_analysis(
+    config: &Config,
+    review: &mut ReviewState,
+    selection: Selection<'_>,
+    local: bool,
+    merge: bool,
+) -> Result<()> {
+    let result = run_agents_and_save(config, review, selection, local, merge).await;
+    match &result {
+        Ok(()) => {
+            let findings: usize = review.agent_analyses.iter().map(|analysis| analysis.findings.len()).sum();
+            let body = format!(
+                "PR #{}: {} finding(s) from {} agent(s)",
+                review.pr_number,
+                findings,
+                review.agent_analyses.len()
+            );
+            notification::notify(&config.notifications, "Agent analysis finished", &body).await;
+            webhook::analysis_finished(config, review).await;
+        }
+        // The user stopped it and knows
+        Err(ChabaError::Interrupted) => {}
+        Err(e) => {
+            let body = format!("PR #{}: {}", review.pr_number, e);
+            notification::notify(&config.notifications, "Agent analysis failed", &body).await;
+        }
+    }
+    result
+}
+
+async fn run_agents_and_save(
+    config: &Config,
+    review: &mut ReviewState,
+    selection: Selection<'_>,
+    local: bool,
+    merge: bool,
+) -> Result<()> {
+    say!("\n🤖 Running AI agent analysis...");
+
+    let (agent_manager, anonymized) = prepare_agents(config, review, local).await?;
+    let pr_number = review.pr_number;
+    let agent_dir = match &anonymized {
+        Some((workspace, _)) => workspace.path(),
+        None => review.worktree_path.as_path(),
+    };
+
+    let agents: Vec<String> = match selection {
+        Selection::Agents(agents) => agents.to_vec(),
+        Selection::Profiles(profiles) => profiles.iter().map(|(_, profile)| profile.agent.clone()).collect(),
+    };
+    let _activity = activity::start(&review.key(), &agents, config.agents.timeout)
+        .map_err(|e| tracing::debug!("Failed to record running agents: {}", e))
+        .ok();
+    let _cancel = cancel::guard();
+    let mut analyses = match selection {
+        Selection::Agents(agents) => agent_manager.run_agents(pr_number, agent_dir, agents).await?,
+        Selection::Profiles(profiles) => agent_manager.run_profiles(pr_number, agent_dir, profiles).await?,
+    };
+    let interrupted = cancel::is_cancelled();
+    if let Some((_, mapping)) = &anonymized {
+        for analysis in &mut analyses {
+            mapping.restore_analysis(analysis);
+        }
+    }
+    // A run started by hand replaces one waiting for quota
+    if let Err(e) = quota::forget(pr_number) {
+        tracing::debug!("Failed to update the agent quota ledger: {}", e);
+    }
+
+    review.analysis_interrupted = interrupted;
+    if interrupted {
+        say!("⏹  Analysis interrupted; saving the results of {} agent(s) that finished", analyses.len());
+    }
+
+    if !analyses.is_empty() {
+        let usage = Usage::sum(&analyses);
+        if usage.is_empty() {
+            say!("✓ Completed analysis with {} agent(s)", analyses.len());
+        } else {
+            say!("✓ Completed analysis with {} agent(s), {}", analyses.len(), usage);
+        }
+
+        // Keep the previous run for `agent-result --diff`
+        let commit = match GitOps::for_review(review) {
+            Ok(git_ops) => git_ops.head_sha(&review.worktree_path).await.ok(),
+            Err(_) => None,
+        };
+        // Applied to the review as last saved, so triage done while the agents
+        // ran is kept along with the team's triage of findings seen before
+        let record = |review: &mut ReviewState| {
+            let mut analyses = analyses.clone();
+            for analysis in &mut analyses {
+                analysis.carry_triage(&review.agent_analyses);
+            }
+            if merge {
+                let kept = review
+                    .agent_analyses
+                    .iter()
+                    .filter(|previous| {
+                        !analyses
+                            .iter()
+                            .any(|analysis| analysis.agent == previous.agent && analysis.profile == previous.profile)
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>();
+                analyses.splice(0..0, kept);
+            }
+            review.analysis_interrupted = interrupted;
+            review.agent_usage.add(&usage);
+            history::record(review, analyses, commit.clone());
+        };
+        record(review);
+        State::load()?.update_review(&review.key(), record)?;
+
+        println!("\nRun 'chaba agent-result {}' to view detailed results", pr_number);
+    } else if interrupted {
+        State::load()?.update_review(&review.key(), |review| review.analysis_interrupted = true)?;
+    }
+
+    if interrupted {
+        return Err(ChabaError::Interrupted);
+    }
+    Ok(())
+}
+
+/// Set up an agent manager with the review's prompt context
+///
+/// In anonymized mode, also returns the scratch directory agents run in and
+/// the mapping that restores their findings.
+async fn prepare_agents(
+    config: &Config,
+    review: &ReviewState,
+    local: bool,
+) -> Result<(AgentManager, Option<(tempfile::TempDir, anonymize::Mapping)>)> {
+    let mut context = Vec::new();
+    if local {
+        context.push(
+            "The changes under review are uncommitted local changes that have no PR yet. Inspect them with `git status` and `git diff`."
+                .to_string(),
+        );
+    }
+    if let Some(patch) = &review.patch {
+        context.push(format!(
+            "The changes under review come from the patch `{}`, applied and staged on top of `{}`. Inspect them with `git diff --cached`.",
+            patch.display(),
+            review.branch
+        ));
+    }
+    if let Some(base_context) = review.base_context() {
+        context.push(base_context);
+    }
+    if let Some(stack_context) = review.stack_context() {
+        context.push(stack_context);
+    }
+    if let Some(tests) = &review.tests {
+        context.push(tests.prompt_context());
+    }
+    // The discussion names people and code that anonymized mode hides
+    if review.is_pull_request() && !config.agents.anonymize.enabled {
+        match pr_discussion_context(review).await {
+            Ok(discussion) => context.extend(discussion),
+            Err(e) => say!("⚠️  Could not fetch the PR description and comments: {}", e),
+        }
+    }
+    // An anonymized diff holds only the changes already
+    if config.agents.scope == AgentScope::ChangedFiles && !config.agents.anonymize.enabled {
+        match changed_files_context(review, local).await {
+            Ok(scope) => context.extend(scope),
+            Err(e) => say!("⚠️  Could not list the changed files; agents review the whole change: {}", e),
+        }
+    }
+
+    let mut agent_manager = AgentManager::new(config.agents.clone());
+    if let Ok(logs) = ReviewLogs::for_review(&review.key()) {
+        agent_manager = agent_manager.with_logs(logs);
+    }
+
+    // In anonymized mode agents only see a scrubbed diff in a scratch directory
+    let anonymized = if config.agents.anonymize.enabled {
+        let (workspace, mapping) = prepare_anonymized(config, review, local).await?;
+        agent_manager = agent_manager.with_context(anonymize::PROMPT_CONTEXT);
+        Some((workspace, mapping))
+    } else {
+        if !context.is_empty() {
+            agent_manager = agent_manager.with_context(context.join("\n\n"));
+        }
+        None
+    };
+    Ok((agent_manager, anonymized))
+}
+
+/// Print how each agent would be run, without running it (`--dry-run`)
+async fn show_invocations(config: &Config, review: &ReviewState, selection: Selection<'_>, local: bool) -> Result<()> {
+    say!("\n🧪 Dry run: agents are not started");
+
+    let (agent_manager, anonymized) = prepare_agents(config, review, local).await?;
+    let agent_dir = match &anonymized {
+        Some((workspace, _)) => workspace.path(),
+        None => review.worktree_path.as_path(),
+    };
+
+    for invocation in agent_manager.invocations(review.pr_number, agent_dir, &selection.runs())? {
+        say!("\n── {} ──", invocation.label);
+        println!("  Command: {} {} <prompt>", invocation.program, invocation.flags.join(" "));
+        println!("  Working directory: {}", invocation.working_dir.display());
+        if anonymized.is_some() {
+            println!("    (a scratch directory removed after the run)");
+        }
+        if !invocation.env.is_empty() {
+            println!("  Environment: {}", invocation.env.join(", "));
+        }
+        println!("  Prompt:");
+        for line in invocation.prompt.lines() {
+            println!("    {}", line);
+        }
+    }
+    Ok(())
+}
+
+/// Revision range holding the review's changes
+///
+/// Local changes and patches are uncommitted (`None`); PRs are compared with
+/// their merge target.
+async fn review_range(git_ops: &GitOps, review: &ReviewState, local: bool) -> Option<String> {
+    if local || review.patch.is_some() {
+        return None;
+    }
+    let base = match &review.base_branch {
+        Some(base) => base.clone(),
+        None => git_ops.default_branch().await,
+    };
+    Some(format!("origin/{}...HEAD", base))
+}
+
+/// Most changed files listed in the prompt for `agents.scope: changed_files`
+const MAX_SCOPE_FILES: usize = 200;
+
+/// Prompt context limiting agents to the files the review changes
+///
+/// Returns `None` when nothing changed.
+async fn changed_files_context(review: &ReviewState, local: bool) -> Result<Option<String>> {
+    let git_ops = GitOps::for_review(review)?;
+    let range = review_range(&git_ops, review, local).await;
+    let files = git_ops.changed_files_in(&review.worktree_path, range.as_deref()).await?;
+    if files.is_empty() {
+        return Ok(None);
+    }
+
+    say!("🎯 Limiting the review to {} changed file(s)", files.len());
+    let mut context = String::from(
+        "Review only the following files changed by this PR. Read other files only when needed to understand them, and do not report findings in other files:\n",
+    );
+    for file in files.iter().take(MAX_SCOPE_FILES) {
+        context.push_str(&format!("- {}\n", file));
+    }
+    if files.len() > MAX_SCOPE_FILES {
+        context.push_str(&format!("- ... and {} more (see `git diff --name-only`)\n", files.len() - MAX_SCOPE_FILES));
+    }
+    Ok(Some(context))
+}
+
+/// Most characters of PR description and comments included in the prompt
+const MAX_DISCUSSION_CHARS: usize = 12_000;
+
+/// Prompt context with the PR's description and the comments left on it
+///
+/// Returns `None` when the PR has neither.
+async fn pr_discussion_context(review: &ReviewState) -> Result<Option<String>> {
+    let git_ops = GitOps::for_review(review)?;
+    let discussion = git_ops.get_pr_discussion(review.pr_number).await?;
+    if !discussion.comments.is_empty() {
+        say!("💬 Sharing the PR description and {} comment(s) with agents", discussion.comments.len());
+    }
+    Ok(discussion.prompt_context(MAX_DISCUSSION_CHARS))
+}
+
+/// Write the review's anonymized diff to a scratch directory for the agents
+///
+/// The mapping back to real names is saved in the worktree.
+async fn prepare_anonymized(
+    config: &Config,
+    review: &ReviewState,
+    local: bool,
+) -> Result<(tempfile::TempDir, anonymize::Mapping)> {
+    let git_ops = GitOps::for_review(review)?;
+    let range = review_range(&git_ops, review, local).await;
+    let diff = git_ops.diff(&review.worktree_path, range.as_deref()).await?;
+
+    let (diff, mapping) = anonymize::anonymize(&diff, &config.agents.anonymize);
+    let mapping_path = mapping.save(&review.worktree_path)?;
+    let workspace = tempfile::Builder::new().prefix("chaba-anonymized-").tempdir()?;
+    tokio::fs::write(workspace.path().join(anonymize::DIFF_FILE), diff).await?;
+
+    say!(
+        "🔒 Sending agents an anonymized diff ({} name(s) replaced, mapping in {})",
+        mapping.len(),
+        mapping_path.display()
+    );
+    Ok((workspace, mapping))
+}
@@ -0,0 +1,68 @@
+use dialoguer::Confirm;
+
+use crate::commands::snapshot::env_files;
+use crate::config::Config;
+use crate::core::git::GitOps;
+use crate::core::snapshot::SnapshotStore;
+use crate::core::state::State;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+pub async fn execute(pr: u32, to: String, force: bool) -> Result<()> {
+    let state = State::load()?;
+    let review = state
+        .get_review(pr)
+        .ok_or(ChabaError::WorktreeNotFound(pr))?;
+
+    if !review.worktree_path.exists() {
+        return Err(ChabaError::Other(anyhow::anyhow!(
+            "Worktree does not exist: {}",
+            review.worktree_path.display()
+        )));
+    }
+
+    let store = SnapshotStore::for_review(&review.key())?;
+    let snapshot = store.get(&to)?;
+
+    say!("🍵 Chaba - Rolling back review environment...\n");
+    println!("PR #:         {}", pr);
+    println!("Worktree:     {}", review.worktree_path.display());
+    println!("Snapshot:     {}", snapshot.id);
+    if let Some(message) = &snapshot.message {
+        println!("Message:      {}", message);
+    }
+    println!();
+
+    if !force {
+        let confirmed = Confirm::new()
+            .with_prompt("Discard all changes made since this snapshot?")
+            .default(false)
+            .interact()
+            .unwrap_or(false);
+
+        if !confirmed {
+            println!("Rollback cancelled.");
+            return Ok(());
+        }
+    }
+
+    let config = Config::load()?;
+    let git_ops = GitOps::for_review(review)?;
+
+    // Keep the current state too, so the rollback itself can be undone
+    let backup = store
+        .take(
+            &git_ops,
+            review,
+            &env_files(&config),
+            Some(format!("before rollback to {}", snapshot.id)),
+        )
+        .await?;
+
+    store.restore(&git_ops, review, &snapshot).await?;
+
+    say!("✓ Rolled back PR #{} to snapshot {}", pr, snapshot.id);
+    println!("  Previous state saved as snapshot {}", backup.id);
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use chrono::Local;
+
+use crate::config::Config;
+use crate::core::git::GitOps;
+use crate::core::snapshot::SnapshotStore;
+use crate::core::state::State;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+pub async fn execute(pr: u32, message: Option<String>, list: bool) -> Result<()> {
+    let state = State::load()?;
+    let review = state
+        .get_review(pr)
+        .ok_or(ChabaError::WorktreeNotFound(pr))?;
+
+    let store = SnapshotStore::for_review(&review.key())?;
+
+    if list {
+        let snapshots = store.list()?;
+        if snapshots.is_empty() {
+            println!("No snapshots for PR #{}.", pr);
+            return Ok(());
+        }
+
+        println!("Snapshots for PR #{}:\n", pr);
+        for snapshot in snapshots {
+            println!(
+                "  {}  {}  {}",
+                snapshot.id,
+                snapshot.created_at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"),
+                snapshot.message.as_deref().unwrap_or("")
+            );
+        }
+        return Ok(());
+    }
+
+    if !review.worktree_path.exists() {
+        return Err(ChabaError::Other(anyhow::anyhow!(
+            "Worktree does not exist: {}",
+            review.worktree_path.display()
+        )));
+    }
+
+    let config = Config::load()?;
+    let git_ops = GitOps::for_review(review)?;
+
+    let snapshot = store
+        .take(&git_ops, review, &env_files(&config), message)
+        .await?;
+
+    say!("✓ Created snapshot {} for PR #{}", snapshot.id, pr);
+    if !snapshot.env_files.is_empty() {
+        println!("  Env files: {}", snapshot.env_files.join(", "));
+    }
+    println!("\nTo undo later changes:");
+    println!("  chaba rollback --pr {} --to {}", pr, snapshot.id);
+
+    Ok(())
+}
+
+/// Env files saved with each snapshot, relative to the worktree
+pub(crate) fn env_files(config: &Config) -> Vec<String> {
+    std::iter::once(".env".to_string())
+        .chain(config.sandbox.additional_env_files.iter().cloned())
+        .collect()
+}
@@ -0,0 +1,93 @@
+use std::io::Read;
+use std::path::Path;
+
+use crate::config::Config;
+use crate::core::archive;
+use crate::core::migrate::{PathRewrite, StateExport};
+use crate::core::state::State;
+use crate::core::workspace;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+/// Print all tracked reviews, and with `with_archive` the archived ones, as JSON
+pub async fn export(with_archive: bool) -> Result<()> {
+    let reviews = State::load()?.reviews;
+    let archived = if with_archive { archive::load()? } else { Vec::new() };
+
+    let export = StateExport::new(reviews, archived);
+    let json = serde_json::to_string_pretty(&export).map_err(|e| ChabaError::Other(e.into()))?;
+    println!("{}", json);
+
+    eprintln!(
+        "Exported {} review(s){}; worktrees are not included",
+        export.reviews.len(),
+        if with_archive { format!(" and {} archived", export.archive.len()) } else { String::new() }
+    );
+    Ok(())
+}
+
+/// Import reviews exported on another machine, rewriting their paths
+///
+/// `file` is `-` for stdin. Reviews already tracked are kept unless `force`.
+pub async fn import(file: &Path, mut rewrites: Vec<PathRewrite>, force: bool) -> Result<()> {
+    let content = if file == Path::new("-") {
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content)?;
+        content
+    } else {
+        std::fs::read_to_string(file)?
+    };
+    let mut export = StateExport::parse(&content)?;
+
+    // Explicit rewrites are more specific, so they win over the home directory
+    if let (Some(from), Some(to)) = (export.home.clone(), dirs::home_dir()) {
+        if from != to {
+            rewrites.push(PathRewrite { from, to });
+        }
+    }
+    export.rewrite_paths(&rewrites);
+
+    let mut state = State::load()?;
+    let mut imported = 0;
+    let mut skipped = Vec::new();
+    for review in export.reviews {
+        if state.find_review(&review.key()).is_some() && !force {
+            skipped.push(review.pr_number);
+            continue;
+        }
+        state.reviews.retain(|r| !r.key().matches(&review.key()));
+        state.reviews.push(review);
+        imported += 1;
+    }
+    state.save()?;
+    workspace::update(&Config::load()?.worktree, &state.reviews);
+
+    // Archive files are named by PR and time, so importing twice overwrites them
+    let archive_dir = archive::archive_dir()?;
+    for archived in &export.archive {
+        archive::archive_in(&archive_dir, &archived.review, archived.archived_at)?;
+    }
+
+    say!("✓ Imported {} review(s)", imported);
+    if !export.archive.is_empty() {
+        say!("✓ Imported {} archived review(s)", export.archive.len());
+    }
+    if !skipped.is_empty() {
+        let prs: Vec<String> = skipped.iter().map(|pr| format!("#{}", pr)).collect();
+        say!("⚠️  Skipped {} already tracked: {} (use --force to replace)", skipped.len(), prs.join(", "));
+    }
+
+    let missing: Vec<_> = state
+        .reviews
+        .iter()
+        .filter(|review| !review.worktree_path.exists())
+        .collect();
+    if !missing.is_empty() {
+        say!("\n⚠️  {} worktree(s) not found on this machine:", missing.len());
+        for review in &missing {
+            println!("    PR #{} - {}", review.pr_number, review.worktree_path.display());
+        }
+        say!("\n💡 Copy the worktrees over, map their location with --rewrite OLD=NEW, or remove them with 'chaba cleanup --force --pr <PR>'");
+    }
+    Ok(())
+}
@@ -1,26 +1,57 @@
+use crate::config::Config;
+use crate::core::ci;
 use crate::core::git::GitOps;
-use crate::core::state::State;
+use crate::core::resources::{self, ResourceMonitor};
+use crate::core::state::{ReviewTarget, State};
+use crate::core::ttl;
 use crate::error::{ChabaError, Result};
+use crate::say;
 use chrono::Local;
 
-pub async fn execute(pr: u32) -> Result<()> {
+pub async fn execute(target: Option<ReviewTarget>) -> Result<()> {
     let state = State::load()?;
+    let pr = state.resolve_target(target)?;
     let review = state
         .get_review(pr)
         .ok_or(ChabaError::WorktreeNotFound(pr))?;
 
-    let git_ops = GitOps::open()?;
+    let git_ops = GitOps::for_review(review)?;
 
-    println!("🍵 Review Environment Status\n");
+    say!("🍵 Review Environment Status\n");
     println!("PR Number:     #{}", review.pr_number);
     println!("Branch:        {}", review.branch);
+    if let Some(info) = &review.pr_info {
+        println!("Title:         {}", info.title);
+        println!("Author:        @{}", info.author);
+        if info.draft {
+            println!("Draft:         yes");
+        }
+        if !info.labels.is_empty() {
+            println!("Labels:        {}", info.labels.join(", "));
+        }
+    }
+    if let Some(base) = &review.base_branch {
+        println!("Base Branch:   {}", base);
+    }
+    if let Some(parent) = review.parent_pr {
+        println!("Stacked On:    PR #{}", parent);
+    }
+    if let Some(patch) = &review.patch {
+        println!("Patch:         {}", patch.display());
+    }
+    if let Some(repo) = &review.repo {
+        println!("Repository:    {}", repo.display());
+    }
     println!("Path:          {}", review.worktree_path.display());
+    if let Some(sparse_paths) = &review.sparse_paths {
+        println!("Sparse:        {}", if sparse_paths.is_empty() { "root files only".to_string() } else { sparse_paths.join(", ") });
+    }
 
     // Check if worktree actually exists
     let worktree_exists = review.worktree_path.exists();
     if !worktree_exists {
         println!("Status:        ⚠️  MISSING (worktree was manually removed)");
-        println!("\n💡 Tip: Run 'chaba cleanup --force --pr {}' to clean up the state.", pr);
+        say!("\n💡 Tip: Run 'chaba cleanup --force --pr {}' to clean up the state.", pr);
     } else {
         println!("Status:        ✓ Active");
     }
@@ -29,6 +60,14 @@ pub async fn execute(pr: u32) -> Result<()> {
     let time_ago = format_time_ago(review.created_at);
     println!("Created:       {} ({})", created.format("%Y-%m-%d %H:%M:%S"), time_ago);
 
+    if let Some(expires_at) = review.expires_at {
+        println!(
+            "Expires:       {} ({})",
+            expires_at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"),
+            ttl::format_remaining(expires_at, chrono::Utc::now())
+        );
+    }
+
     if let Some(project_type) = &review.project_type {
         println!("\nProject Type:  {}", project_type);
     }
@@ -40,12 +79,66 @@ pub async fn execute(pr: u32) -> Result<()> {
     println!("\nSandbox Setup:");
     println!("  Dependencies: {}", if review.deps_installed { "✓ Installed" } else { "✗ Not installed" });
     println!("  Environment:  {}", if review.env_copied { "✓ Copied" } else { "✗ Not copied" });
+    if let Some(seed) = &review.seed {
+        let mark = if seed.succeeded() { "✓" } else { "✗" };
+        println!("  Seed data:    {} {}", mark, seed.summary());
+    }
+
+    if let Some(tests) = &review.tests {
+        println!("  Tests:        {} ({}, {}s)", tests.status_label(), tests.command, tests.duration_secs);
+        if let Some(summary) = &tests.failure_summary {
+            for line in summary.lines() {
+                println!("                {}", line);
+            }
+        }
+    }
+
+    if review.analysis_interrupted {
+        println!("  Agents:       ⏹ Analysis interrupted ({} agent(s) finished)", review.agent_analyses.len());
+    }
+
+    // Show processes (dev server, tests, agents) running in the worktree
+    if worktree_exists {
+        let usage = ResourceMonitor::sample().await.review_usage(review);
+        println!("\nResources:");
+        if usage.processes.is_empty() {
+            println!("  Processes:    None running");
+        } else {
+            println!("  Usage:        {}", usage.summary());
+            for process in usage.processes.iter().take(5) {
+                println!(
+                    "                {} (pid {}): {:.1}% CPU, {}",
+                    process.name,
+                    process.pid,
+                    process.cpu_percent,
+                    resources::format_bytes(process.memory_bytes)
+                );
+            }
+
+            let config = Config::load()?;
+            for alert in usage.alerts(&config.monitor) {
+                println!("  ⚠️  High usage: {}", alert);
+            }
+        }
+    }
 
     // Show Git statistics if worktree exists
     if worktree_exists {
-        if let Ok(stats) = git_ops.get_stats(&review.worktree_path).await {
+        if let Ok(stats) = git_ops.get_stats(&review.worktree_path, review.base_branch.as_deref()).await {
             println!("\nGit Status:");
 
+            if stats.has_conflict() {
+                let operation = stats
+                    .operation
+                    .map(|op| op.to_string())
+                    .unwrap_or_else(|| "merge".to_string());
+                println!("  State:        ⚠️  CONFLICT ({} in progress)", operation);
+                for file in &stats.conflicted_files {
+                    println!("                ✗ {}", file);
+                }
+                println!("  💡 Tip: Run 'chaba resolve --pr {}' to continue or abort.", pr);
+            }
+
             if let Some(ref upstream) = stats.upstream_branch {
                 println!("  Upstream:     {}", upstream);
             }
@@ -59,6 +152,18 @@ pub async fn execute(pr: u32) -> Result<()> {
                 println!("  Changes:      No uncommitted changes");
             }
 
+            if let Some(base) = &stats.base {
+                println!(
+                    "  Vs Target:    ↑{} ahead, ↓{} behind {} ({} file(s), +{} -{} lines)",
+                    base.commits_ahead,
+                    base.commits_behind,
+                    base.target,
+                    base.files_changed,
+                    base.lines_added,
+                    base.lines_deleted
+                );
+            }
+
             if stats.commits_ahead > 0 || stats.commits_behind > 0 {
                 let mut status_parts = Vec::new();
                 if stats.commits_ahead > 0 {
@@ -74,6 +179,25 @@ pub async fn execute(pr: u32) -> Result<()> {
         }
     }
 
+    // Show CI checks for PR reviews
+    if review.is_pull_request() {
+        match git_ops.get_pr_checks(review.pr_number).await {
+            Ok(checks) => {
+                println!("\nCI:");
+                let status = ci::summarize(&checks);
+                if status == ci::CiStatus::NoChecks {
+                    println!("  Status:       {}", status);
+                } else {
+                    println!("  Status:       {} ({} check(s))", status, checks.len());
+                }
+                for name in ci::failed_checks(&checks) {
+                    println!("                ✗ {}", name);
+                }
+            }
+            Err(e) => tracing::debug!("Failed to fetch CI checks: {}", e),
+        }
+    }
+
     Ok(())
 }
 
@@ -0,0 +1,23 @@
+use crate::core::activity;
+use crate::core::git::GitOps;
+use crate::core::state::State;
+use crate::core::statusline::{self, StatuslineFormat};
+use crate::error::Result;
+
+pub async fn execute(format: StatuslineFormat) -> Result<()> {
+    let state = State::load()?;
+
+    let conflicted: Vec<u32> = state
+        .reviews
+        .iter()
+        .filter(|review| GitOps::operation_in_progress(&review.worktree_path).is_some())
+        .map(|review| review.pr_number)
+        .collect();
+
+    println!(
+        "{}",
+        statusline::render(&state.reviews, &conflicted, &activity::running(), format, chrono::Utc::now())
+    );
+
+    Ok(())
+}
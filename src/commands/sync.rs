@@ -0,0 +1,63 @@
+use crate::core::git::{GitOps, SyncOutcome};
+use crate::core::state::{ReviewState, State};
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+pub async fn execute(pr: Option<u32>, all: bool, autostash: bool) -> Result<()> {
+    let state = State::load()?;
+
+    let reviews: Vec<&ReviewState> = match pr {
+        Some(pr) if !all => vec![state.get_review(pr).ok_or(ChabaError::WorktreeNotFound(pr))?],
+        _ => state.reviews.iter().collect(),
+    };
+
+    if reviews.is_empty() {
+        println!("No active review environments.");
+        return Ok(());
+    }
+
+    say!("🍵 Chaba - Syncing review environments...\n");
+
+    let mut failed = 0;
+    for review in reviews {
+        if !review.worktree_path.exists() {
+            eprintln!("⚠️  PR #{}: worktree is missing, skipping", review.pr_number);
+            continue;
+        }
+
+        let git_ops = match GitOps::for_review(review) {
+            Ok(git_ops) => git_ops,
+            Err(e) => {
+                eprintln!("✗ PR #{}: {}", review.pr_number, e);
+                failed += 1;
+                continue;
+            }
+        };
+
+        match git_ops
+            .sync_worktree(&review.worktree_path, &review.branch, autostash)
+            .await
+        {
+            Ok(SyncOutcome::UpToDate) => say!("✓ PR #{}: already up to date", review.pr_number),
+            Ok(SyncOutcome::FastForwarded) => {
+                say!("✓ PR #{}: fast-forwarded to origin/{}", review.pr_number, review.branch)
+            }
+            Ok(SyncOutcome::Rebased) => {
+                say!("✓ PR #{}: rebased onto origin/{}", review.pr_number, review.branch)
+            }
+            Err(e) => {
+                eprintln!("✗ PR #{}: {}", review.pr_number, e);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(ChabaError::Other(anyhow::anyhow!(
+            "{} review environment(s) failed to sync",
+            failed
+        )));
+    }
+
+    Ok(())
+}
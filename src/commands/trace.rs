@@ -0,0 +1,112 @@
+use crate::core::trace::{self, TracedCommand};
+use crate::error::Result;
+use crate::say;
+
+pub async fn list() -> Result<()> {
+    let run_ids = trace::list()?;
+
+    if run_ids.is_empty() {
+        println!("No command traces found");
+        println!("  Record one by running any command with --trace-commands");
+        return Ok(());
+    }
+
+    println!("Command traces in {}:\n", trace::traces_dir()?.display());
+    for run_id in run_ids {
+        let trace = trace::load(&run_id)?;
+        println!(
+            "  {:<24} {:>4} commands  {}",
+            run_id,
+            trace.commands.len(),
+            trace.args.join(" ")
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn show(run_id: Option<String>) -> Result<()> {
+    let run_id = match run_id {
+        Some(run_id) => run_id,
+        None => match trace::list()?.into_iter().next() {
+            Some(run_id) => run_id,
+            None => {
+                println!("No command traces found");
+                println!("  Record one by running any command with --trace-commands");
+                return Ok(());
+            }
+        },
+    };
+
+    let trace = trace::load(&run_id)?;
+
+    say!("🔎 Trace {}", run_id);
+    if !trace.args.is_empty() {
+        println!("  Command: {}", trace.args.join(" "));
+    }
+    if let Some(started_at) = trace.started_at {
+        println!("  Started: {}", started_at.format("%Y-%m-%d %H:%M:%S UTC"));
+    }
+    println!();
+
+    if trace.commands.is_empty() {
+        println!("No external commands were run");
+        return Ok(());
+    }
+
+    println!("  {:>8}  {:>8}  {:>6}  COMMAND", "START", "TOOK", "EXIT");
+    for command in &trace.commands {
+        let offset = trace
+            .started_at
+            .map(|started_at| (command.started_at - started_at).num_milliseconds())
+            .unwrap_or_default();
+        println!(
+            "  {:>8}  {:>8}  {:>6}  {}",
+            format_ms(offset.max(0) as u64),
+            command.duration_ms.map(format_ms).unwrap_or_else(|| "…".to_string()),
+            outcome(command),
+            command.command_line()
+        );
+        println!("  {:>8}  {:>8}  {:>6}  in {}", "", "", "", command.cwd.display());
+        if let Some(error) = &command.error {
+            println!("  {:>8}  {:>8}  {:>6}  {}", "", "", "", error);
+        }
+    }
+
+    let unfinished = trace.commands.iter().filter(|c| c.duration_ms.is_none()).count();
+    let failed = trace
+        .commands
+        .iter()
+        .filter(|c| c.duration_ms.is_some() && c.exit_code != Some(0))
+        .count();
+    let total: u64 = trace.commands.iter().filter_map(|c| c.duration_ms).sum();
+
+    println!(
+        "\n{} command(s), {} in total, {} failed",
+        trace.commands.len(),
+        format_ms(total),
+        failed
+    );
+    if unfinished > 0 {
+        say!("⚠️  {} command(s) never finished (the run hung or was interrupted there)", unfinished);
+    }
+
+    Ok(())
+}
+
+/// Exit column: the exit code, `err` if the command couldn't run, `-` if it never finished
+fn outcome(command: &TracedCommand) -> String {
+    match (command.duration_ms, command.exit_code) {
+        (None, _) => "-".to_string(),
+        (Some(_), Some(code)) => code.to_string(),
+        (Some(_), None) => "err".to_string(),
+    }
+}
+
+fn format_ms(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    }
+}
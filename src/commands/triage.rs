@@ -0,0 +1,113 @@
+use dialoguer::Select;
+
+use crate::core::review_analysis::{self, Finding, TriageStatus};
+use crate::core::state::State;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+/// Choices offered for each finding, in menu order
+const CHOICES: [(&str, Option<TriageStatus>); 5] = [
+    ("Keep open", Some(TriageStatus::Open)),
+    ("Acknowledge", Some(TriageStatus::Acknowledged)),
+    ("Dismiss", Some(TriageStatus::Dismissed)),
+    ("Mark fixed", Some(TriageStatus::Fixed)),
+    ("Stop triaging", None),
+];
+
+/// Step through untriaged findings and ask for each one's status
+pub async fn execute(pr: u32, all: bool) -> Result<()> {
+    let state = State::load()?;
+    let review = state
+        .get_review(pr)
+        .ok_or_else(|| ChabaError::PrNotFound(pr))?;
+
+    let pending: Vec<(String, Finding)> = review
+        .agent_analyses
+        .iter()
+        .flat_map(|analysis| analysis.sorted_findings().into_iter().map(|f| (analysis.agent.clone(), f.clone())))
+        .filter(|(_, finding)| {
+            finding.triage.is_open() || (all && finding.triage == TriageStatus::Acknowledged)
+        })
+        .collect();
+
+    if pending.is_empty() {
+        println!("No findings left to triage for PR #{}", pr);
+        return Ok(());
+    }
+
+    say!("🍵 Chaba - Triaging {} finding(s) for PR #{}\n", pending.len(), pr);
+
+    let mut changes = Vec::new();
+    for (index, (agent, finding)) in pending.iter().enumerate() {
+        println!("[{}/{}] {:?} · {:?} · {}", index + 1, pending.len(), finding.severity, finding.category, agent);
+        println!("  {}", finding.title);
+        if let Some(file) = &finding.file {
+            match finding.line {
+                Some(line) => println!("  {}:{}", file, line),
+                None => println!("  {}", file),
+            }
+        }
+        if !finding.description.is_empty() {
+            println!("  {}", finding.description);
+        }
+
+        let current = CHOICES
+            .iter()
+            .position(|(_, status)| *status == Some(finding.triage))
+            .unwrap_or(0);
+        let choice = Select::new()
+            .items(&CHOICES.map(|(label, _)| label))
+            .default(current)
+            .interact()
+            .map_err(|e| ChabaError::Other(anyhow::anyhow!("Failed to read choice: {}", e)))?;
+        println!();
+
+        match CHOICES[choice].1 {
+            Some(status) if status != finding.triage => changes.push((finding.id.clone(), status)),
+            Some(_) => {}
+            None => break,
+        }
+    }
+
+    save(pr, &changes)?;
+    say!("✓ Updated {} finding(s)", changes.len());
+
+    Ok(())
+}
+
+/// Set the status of a single finding
+pub async fn set(pr: u32, id: &str, status: TriageStatus) -> Result<()> {
+    save(pr, &[(id.to_string(), status)])?;
+    say!("✓ Marked finding {} as {}", id, status);
+    Ok(())
+}
+
+/// Apply status changes to the latest saved state
+fn save(pr: u32, changes: &[(String, TriageStatus)]) -> Result<()> {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let mut state = State::load()?;
+    let mut review = state
+        .get_review(pr)
+        .cloned()
+        .ok_or_else(|| ChabaError::PrNotFound(pr))?;
+
+    // Check every ID before saving any change
+    for (id, status) in changes {
+        if !review_analysis::set_triage(&mut review.agent_analyses, id, *status) {
+            return Err(ChabaError::Other(anyhow::anyhow!(
+                "No finding with ID {} in PR #{}",
+                id,
+                pr
+            )));
+        }
+    }
+
+    state.update_review(&review.key(), |review| {
+        for (id, status) in changes {
+            review_analysis::set_triage(&mut review.agent_analyses, id, *status);
+        }
+    })
+}
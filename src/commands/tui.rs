@@ -5,18 +5,481 @@ use crossterm::{
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
     Terminal,
 };
+use futures::stream::FuturesUnordered;
+use futures::StreamExt;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::future::Future;
 use std::io;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::process::Stdio;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use crate::config::Config;
-use crate::core::git::GitOps;
-use crate::core::worktree::WorktreeManager;
-use crate::error::Result;
+use crate::core::activity::{self, AgentRun, AgentState};
+use crate::core::events;
+use crate::core::git::{GitOps, GitStats};
+use crate::core::logs::ReviewLogs;
+use crate::core::port;
+use crate::core::resources::{self, ResourceMonitor, ResourceUsage};
+use crate::core::review_analysis::{AggregatedReport, ReviewAnalysis, Severity};
+use crate::core::stack;
+use crate::core::state::{ReviewKey, ReviewState, State};
+use crate::core::stats_cache::{Fingerprint, StatsCache};
+use crate::core::ttl;
+use crate::core::watcher::WorktreeWatcher;
+use crate::error::{ChabaError, Result};
+
+/// Most recent events shown in the timeline panel
+const TIMELINE_EVENTS: usize = 10;
+
+/// Lines of the agents panel
+const AGENT_PANE_LINES: usize = 8;
+
+/// Loads running in the background at the same time
+const MAX_CONCURRENT_LOADS: usize = 8;
+
+/// How long to wait for a finished load before checking for input
+const INPUT_POLL: Duration = Duration::from_millis(50);
+
+/// Reviews listed by name in the confirmation dialog
+const CONFIRM_LISTED: usize = 8;
+
+/// Spinner shown on rows whose stats are still loading
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Git stats and dev-server health of a review, refreshed when its worktree changes
+#[derive(Default)]
+struct ReviewHealth {
+    stats: Option<GitStats>,
+    server_up: bool,
+}
+
+impl ReviewHealth {
+    /// Load a review's health, reusing its git stats from `cache` while valid
+    async fn load(review: &ReviewState, cache: &RefCell<StatsCache>) -> Self {
+        // Stat calls, HEAD resolution and the port probe can all be slow on
+        // large or remote worktrees
+        let path = review.worktree_path.clone();
+        let port = review.port;
+        let probed = tokio::task::spawn_blocking(move || {
+            path.exists()
+                .then(|| (Fingerprint::of(&path), port.is_some_and(port::is_port_in_use)))
+        })
+        .await
+        .ok()
+        .flatten();
+        let Some((fingerprint, server_up)) = probed else {
+            return ReviewHealth::default();
+        };
+
+        let now = chrono::Utc::now();
+        let base_branch = review.base_branch.as_deref();
+        let cached = fingerprint
+            .as_ref()
+            .and_then(|fingerprint| cache.borrow().get(&review.worktree_path, base_branch, fingerprint, now));
+        let stats = match cached {
+            Some(stats) => Some(stats),
+            None => {
+                let stats = match GitOps::for_review(review) {
+                    Ok(git_ops) => git_ops.get_stats(&review.worktree_path, base_branch).await.ok(),
+                    Err(_) => None,
+                };
+                if let (Some(fingerprint), Some(stats)) = (fingerprint, &stats) {
+                    cache
+                        .borrow_mut()
+                        .insert(&review.worktree_path, base_branch, fingerprint, stats.clone(), now);
+                }
+                stats
+            }
+        };
+
+        ReviewHealth { stats, server_up }
+    }
+}
+
+/// Result of a background load
+enum Loaded {
+    /// Reviews reloaded from state
+    Reviews(Result<Vec<ReviewState>>),
+    Health(ReviewKey, ReviewHealth),
+    /// Resource usage measured, with the monitor to measure it next time,
+    /// or `None` if measuring failed
+    Usage(Option<(ResourceMonitor, BTreeMap<ReviewKey, ResourceUsage>)>),
+    /// An action on a review finished, with why it failed if it did
+    Action(Action, ReviewKey, Option<String>),
+}
+
+/// What can be done to reviews from the TUI, one or several at once
+///
+/// Each review gets its own `chaba` process, like the command run by hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Analyze,
+    Sync,
+    Cleanup,
+}
+
+impl Action {
+    /// Arguments of the chaba command doing the action on a review
+    ///
+    /// The command runs in the review's repository, where its PR number is unique.
+    fn args(self, review: &ReviewKey) -> Vec<String> {
+        let pr = review.pr_number.to_string();
+        let args = match self {
+            Action::Analyze => vec!["analyze", "--pr", &pr],
+            Action::Sync => vec!["sync", "--pr", &pr],
+            // Confirmed in the TUI. `--force` only skips the prompt: worktrees
+            // whose unsaved work isn't pushed or can't be checked are kept, and
+            // commits only a shared clone has are saved to the main repository
+            Action::Cleanup => vec!["cleanup", "--pr", &pr, "--force"],
+        };
+        let mut command = Vec::new();
+        if let Some(repo) = &review.repo {
+            command.push("--repo".to_string());
+            command.push(repo.display().to_string());
+        }
+        command.extend(args.into_iter().map(String::from));
+        command
+    }
+
+    fn verb(self) -> &'static str {
+        match self {
+            Action::Analyze => "Run agents on",
+            Action::Sync => "Sync",
+            Action::Cleanup => "Clean up",
+        }
+    }
+
+    /// Shown on the row of a review while the action runs
+    fn doing(self) -> &'static str {
+        match self {
+            Action::Analyze => "running agents",
+            Action::Sync => "syncing",
+            Action::Cleanup => "cleaning up",
+        }
+    }
+}
+
+/// Progress of the last confirmed action, shown above the key help
+struct Batch {
+    action: Action,
+    prs: Vec<ReviewKey>,
+    done: usize,
+    failed: Vec<ReviewKey>,
+}
+
+impl Batch {
+    fn summary(&self) -> String {
+        let mut summary = format!("{}: {}/{} done", self.action.verb(), self.done, self.prs.len());
+        if let Some(first) = self.failed.first() {
+            let failed: Vec<String> = self.failed.iter().map(|review| format!("#{}", review.pr_number)).collect();
+            summary.push_str(&format!(
+                ", failed: {} (see chaba logs --pr {})",
+                failed.join(", "),
+                first.pr_number
+            ));
+        }
+        summary
+    }
+}
+
+/// Loads reviews and their health in the background, keeping the UI responsive
+///
+/// Loads run concurrently on the UI task, at most [`MAX_CONCURRENT_LOADS`] at
+/// a time; the file system, git stats and processes are read on blocking
+/// threads.
+struct Loader {
+    running: FuturesUnordered<Pin<Box<dyn Future<Output = Loaded>>>>,
+    queued: VecDeque<ReviewState>,
+    /// Reviews whose health is queued or loading
+    loading: HashSet<ReviewKey>,
+    reloading_reviews: bool,
+    /// Whether resource usage is being measured
+    measuring: bool,
+    cache: Rc<RefCell<StatsCache>>,
+    /// Actions waiting for the running one; each saves the state, so they
+    /// run one at a time
+    actions: VecDeque<(Action, ReviewKey)>,
+    acting: Option<(Action, ReviewKey)>,
+}
+
+impl Loader {
+    fn new() -> Self {
+        Loader {
+            running: FuturesUnordered::new(),
+            queued: VecDeque::new(),
+            loading: HashSet::new(),
+            reloading_reviews: false,
+            measuring: false,
+            cache: Rc::new(RefCell::new(StatsCache::default())),
+            actions: VecDeque::new(),
+            acting: None,
+        }
+    }
+
+    /// Reload reviews from state, unless a reload is already running
+    fn reload_reviews(&mut self) {
+        if self.reloading_reviews {
+            return;
+        }
+        self.reloading_reviews = true;
+        self.running.push(Box::pin(async {
+            let reviews = tokio::task::spawn_blocking(|| State::load().map(|state| state.reviews))
+                .await
+                .unwrap_or_else(|e| Err(ChabaError::Other(e.into())));
+            Loaded::Reviews(reviews)
+        }));
+    }
+
+    /// Measure the resource usage of `reviews`, refreshing `monitor` or
+    /// creating one if there is none
+    fn measure_usage(&mut self, monitor: Option<ResourceMonitor>, reviews: Vec<ReviewState>) {
+        self.measuring = true;
+        self.running.push(Box::pin(async move {
+            let measured = tokio::task::spawn_blocking(move || {
+                let monitor = match monitor {
+                    Some(mut monitor) => {
+                        monitor.refresh();
+                        monitor
+                    }
+                    None => ResourceMonitor::new(),
+                };
+                let usage = monitor.usage(&reviews);
+                (monitor, usage)
+            })
+            .await;
+            Loaded::Usage(measured.ok())
+        }));
+    }
+
+    /// Load a review's health, unless it is already queued or loading
+    fn load_health(&mut self, review: &ReviewState) {
+        if self.loading.insert(review.key()) {
+            self.queued.push_back(review.clone());
+            self.start_queued();
+        }
+    }
+
+    fn start_queued(&mut self) {
+        // Only health loads count; reloads and agent runs don't hold up stats
+        while self.loading.len() - self.queued.len() < MAX_CONCURRENT_LOADS {
+            let Some(review) = self.queued.pop_front() else {
+                break;
+            };
+            let cache = Rc::clone(&self.cache);
+            self.running.push(Box::pin(async move {
+                let health = ReviewHealth::load(&review, &cache).await;
+                Loaded::Health(review.key(), health)
+            }));
+        }
+    }
+
+    /// Queue an action on a review, unless one is already queued or running for it
+    fn act(&mut self, action: Action, review: ReviewKey) {
+        if self.action_on(&review).is_none() {
+            self.actions.push_back((action, review));
+            self.start_action();
+        }
+    }
+
+    /// Action queued or running for a review, and whether it is running
+    fn action_on(&self, review: &ReviewKey) -> Option<(Action, bool)> {
+        match &self.acting {
+            Some((action, acting)) if acting == review => Some((*action, true)),
+            _ => self
+                .actions
+                .iter()
+                .find(|(_, queued)| queued == review)
+                .map(|(action, _)| (*action, false)),
+        }
+    }
+
+    /// Start the next queued action in a separate chaba process
+    ///
+    /// The process keeps running if the TUI quits; agent progress shows up
+    /// through the run marker and event log like that of any other run. The
+    /// output of a failed action goes to the review's actions log.
+    fn start_action(&mut self) {
+        if self.acting.is_some() {
+            return;
+        }
+        let Some((action, review)) = self.actions.pop_front() else {
+            return;
+        };
+        self.acting = Some((action, review.clone()));
+        self.running.push(Box::pin(async move {
+            let command = format!("chaba {}", action.args(&review).join(" "));
+            let output = match std::env::current_exe() {
+                Ok(exe) => {
+                    tokio::process::Command::new(exe)
+                        .args(action.args(&review))
+                        .stdin(Stdio::null())
+                        .output()
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            let failure = match output {
+                Ok(output) if output.status.success() => None,
+                Ok(output) => {
+                    let logged = match ReviewLogs::for_review(&review) {
+                        Ok(logs) => logs.append_output(&logs.actions_log(), &command, &output).await,
+                        Err(e) => Err(e),
+                    };
+                    if let Err(e) = logged {
+                        tracing::warn!("Failed to log the output of {}: {}", command, e);
+                    }
+                    Some(output.status.to_string())
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to run {}: {}", command, e);
+                    Some(format!("couldn't start: {}", e))
+                }
+            };
+            Loaded::Action(action, review, failure)
+        }));
+    }
+
+    /// Record that a load finished and start the next queued one
+    fn finished(&mut self, loaded: &Loaded) {
+        match loaded {
+            Loaded::Reviews(_) => self.reloading_reviews = false,
+            Loaded::Usage(_) => self.measuring = false,
+            Loaded::Health(review, _) => {
+                self.loading.remove(review);
+            }
+            Loaded::Action(..) => {
+                self.acting = None;
+                self.start_action();
+            }
+        }
+        self.start_queued();
+    }
+}
+
+/// Watch the worktrees of `reviews`, or `None` to rely on the periodic refresh
+fn watch(reviews: &[ReviewState]) -> Option<WorktreeWatcher> {
+    WorktreeWatcher::new(reviews)
+        .map_err(|e| tracing::warn!("Falling back to periodic refresh: {}", e))
+        .ok()
+}
+
+/// Elapsed time of an agent run, e.g. `1m 05s`
+fn format_elapsed(elapsed: chrono::Duration) -> String {
+    let secs = elapsed.num_seconds().max(0);
+    format!("{}m {:02}s", secs / 60, secs % 60)
+}
+
+fn severity_label(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "critical",
+        Severity::High => "high",
+        Severity::Medium => "medium",
+        Severity::Low => "low",
+        Severity::Info => "info",
+    }
+}
+
+/// Title and lines of the agents panel for a review
+///
+/// While agents run, each agent's progress; afterwards, a summary of the
+/// findings, so there is no need to switch to `chaba agent-result`.
+/// `analyzing` is `Some(running)` while agents started from the TUI are
+/// queued or running.
+fn agents_pane(
+    review: &ReviewState,
+    run: Option<&AgentRun>,
+    analyzing: Option<bool>,
+    events: &[events::Event],
+    show_resolved: bool,
+    spinner: &str,
+) -> (String, Vec<String>) {
+    let now = chrono::Utc::now();
+
+    if let Some(run) = run {
+        let title = format!(
+            "Agents - PR #{}, running {}",
+            review.pr_number,
+            format_elapsed(now - run.started_at)
+        );
+        let lines = activity::progress(run, events)
+            .into_iter()
+            .map(|agent| match agent.state {
+                AgentState::Waiting => format!("·  {:<24} waiting", agent.label),
+                AgentState::Running { since } => {
+                    format!("{}  {:<24} running {}", spinner, agent.label, format_elapsed(now - since))
+                }
+                AgentState::Finished { took, outcome } if outcome == "failed" => {
+                    format!("✗  {:<24} failed after {}", agent.label, format_elapsed(took))
+                }
+                AgentState::Finished { took, outcome } => {
+                    format!("✓  {:<24} {} in {}", agent.label, outcome, format_elapsed(took))
+                }
+            })
+            .collect();
+        return (title, lines);
+    }
+
+    // Before its marker is written and while it saves the findings
+    let title = format!("Agents - PR #{}", review.pr_number);
+    match analyzing {
+        Some(true) => return (title, vec![format!("{}  Waiting for chaba analyze...", spinner)]),
+        Some(false) => return (title, vec!["Queued behind other actions".to_string()]),
+        None => {}
+    }
+
+    let title = format!("Findings - PR #{}", review.pr_number);
+    if review.agent_analyses.is_empty() {
+        return (title, vec!["No agent analysis yet. Press a to run agents.".to_string()]);
+    }
+
+    let analyses: Vec<ReviewAnalysis> = review
+        .agent_analyses
+        .iter()
+        .cloned()
+        .map(|mut analysis| {
+            analysis.findings.retain(|finding| show_resolved || !finding.triage.is_resolved());
+            analysis
+        })
+        .collect();
+    let report = AggregatedReport::from_analyses(&analyses);
+    let label = if show_resolved { "finding(s)" } else { "unresolved finding(s)" };
+    let counts: Vec<String> = report
+        .severity_counts()
+        .into_iter()
+        .map(|(severity, count)| format!("{} {}", count, severity_label(severity)))
+        .collect();
+    let mut summary = format!("{} {} from {}", report.total(), label, report.agents.join(", "));
+    if !counts.is_empty() {
+        summary.push_str(&format!(": {}", counts.join(", ")));
+    }
+    if let Some(score) = report.score {
+        summary.push_str(&format!(" — score {:.1}/5", score));
+    }
+    let mut lines = vec![summary];
+
+    for aggregated in &report.findings {
+        let finding = &aggregated.finding;
+        let mut line = format!("[{}] {}", severity_label(finding.severity).to_uppercase(), finding.title);
+        match (&finding.file, finding.line) {
+            (Some(file), Some(line_number)) => line.push_str(&format!(" ({}:{})", file, line_number)),
+            (Some(file), None) => line.push_str(&format!(" ({})", file)),
+            _ => {}
+        }
+        line.push_str(&format!(" — {}", aggregated.agents.join(", ")));
+        lines.push(line);
+    }
+    (title, lines)
+}
 
 pub async fn execute() -> Result<()> {
     // Setup terminal
@@ -26,15 +489,92 @@ pub async fn execute() -> Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Load reviews
-    let config = Config::load()?;
-    let manager = WorktreeManager::new(config)?;
-    let _git_ops = GitOps::open()?;
-    let reviews = manager.list()?;
+    let monitor_config = Config::load()?.monitor;
+    let refresh_interval = Duration::from_secs(monitor_config.refresh_secs);
+
+    // Reviews and their health load in the background; rows fill in as they arrive
+    let mut loader = Loader::new();
+    loader.reload_reviews();
+    let mut reviews: Vec<ReviewState> = Vec::new();
+    let mut reviews_loaded = false;
+    let mut health: HashMap<ReviewKey, ReviewHealth> = HashMap::new();
+    let mut last_reload = Instant::now();
+    let started = Instant::now();
 
     let mut selected = 0;
+    // Count only findings that still need attention unless toggled
+    let mut show_resolved = false;
+    // Event log of the selected review, for the timeline and agents panels,
+    // reloaded with the resource usage
+    let mut show_timeline = false;
+    let mut timeline: Option<(ReviewKey, Vec<events::Event>)> = None;
+    // Agent progress or findings of the selected review, from its event log
+    let mut show_agents = false;
+    let mut runs: Vec<AgentRun> = activity::running();
+    // Reviews marked with space for the next action, and the action waiting
+    // for confirmation with the reviews it applies to
+    let mut marked: BTreeSet<ReviewKey> = BTreeSet::new();
+    let mut confirming: Option<(Action, Vec<ReviewKey>)> = None;
+    let mut batch: Option<Batch> = None;
+    // Why the last action on a review failed, until another one is started
+    let mut failures: HashMap<ReviewKey, (Action, String)> = HashMap::new();
+
+    // Resource usage is refreshed every couple of seconds rather than every frame
+    // The monitor is away while it is measuring
+    let mut monitor: Option<ResourceMonitor> = None;
+    let mut usage: BTreeMap<ReviewKey, ResourceUsage> = BTreeMap::new();
+    loader.measure_usage(None, Vec::new());
+    let mut last_refresh = Instant::now();
 
+    // Git stats only change with the files, so watched worktrees refresh as
+    // soon as they change; the watcher follows reviews as they come and go
+    let mut watcher: Option<WorktreeWatcher> = None;
+    let mut watched: Vec<(ReviewKey, PathBuf)> = Vec::new();
+
+    let mut outcome = Ok(());
     loop {
+        if !refresh_interval.is_zero() && last_reload.elapsed() >= refresh_interval {
+            loader.reload_reviews();
+            last_reload = Instant::now();
+        }
+
+        let mut refreshed = false;
+        if last_refresh.elapsed() >= Duration::from_secs(2) && !loader.measuring {
+            loader.measure_usage(monitor.take(), reviews.clone());
+            last_refresh = Instant::now();
+            refreshed = true;
+
+            // Findings of finished runs are saved with the reviews
+            let previous = std::mem::replace(&mut runs, activity::running());
+            if previous.iter().any(|run| !runs.iter().any(|r| r.key() == run.key())) {
+                loader.reload_reviews();
+            }
+        }
+
+        let selected_key = stack::stack_order(&reviews).get(selected).map(|(_, review)| review.key());
+        if show_timeline || show_agents {
+            let stale = timeline.as_ref().map(|(key, _)| key) != selected_key.as_ref() || refreshed;
+            if let (Some(key), true) = (&selected_key, stale) {
+                let events = ReviewLogs::for_review(key)
+                    .and_then(|logs| events::load(&logs))
+                    .unwrap_or_default();
+                timeline = Some((key.clone(), events));
+            }
+        }
+
+        if let Some(watcher) = &watcher {
+            let changed = watcher.changed();
+            for review in reviews.iter().filter(|review| changed.contains(&review.pr_number)) {
+                // Files changed, which the cache can't tell from HEAD and the index
+                loader.cache.borrow_mut().invalidate(&review.worktree_path);
+                loader.load_health(review);
+            }
+        }
+        let spinner = SPINNER[(started.elapsed().as_millis() / 100) as usize % SPINNER.len()];
+
+        // PR numbers repeat across repositories, so rows name theirs when there are several
+        let several_repos = reviews.iter().any(|review| review.repo != reviews[0].repo);
+
         terminal.draw(|f| {
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
@@ -52,18 +592,25 @@ pub async fn execute() -> Result<()> {
             f.render_widget(title, chunks[0]);
 
             // Review list
-            let items: Vec<ListItem> = reviews
-                .iter()
+            let items: Vec<ListItem> = stack::stack_order(&reviews)
+                .into_iter()
                 .enumerate()
-                .map(|(i, review)| {
+                .map(|(i, (depth, review))| {
                     let status = if review.worktree_path.exists() {
                         "✓"
                     } else {
                         "⚠️"
                     };
 
-                    let content = format!(
-                        "{} PR #{:<6} {} ({})",
+                    let mark = match (marked.is_empty(), marked.contains(&review.key())) {
+                        (true, _) => "",
+                        (false, true) => "[x] ",
+                        (false, false) => "[ ] ",
+                    };
+                    let mut content = format!(
+                        "{}{}{} PR #{:<6} {} ({})",
+                        mark,
+                        stack::tree_prefix(depth),
                         status,
                         review.pr_number,
                         review.branch,
@@ -73,11 +620,72 @@ pub async fn execute() -> Result<()> {
                             "Missing"
                         }
                     );
+                    if let Some(repo) = review.repo.as_ref().and_then(|repo| repo.file_name()).filter(|_| several_repos) {
+                        content.push_str(&format!(" [{}]", repo.to_string_lossy()));
+                    }
+
+                    if let Some(review_health) = health.get(&review.key()) {
+                        if let Some(stats) = &review_health.stats {
+                            if stats.has_conflict() {
+                                content.push_str(" ⚠ conflict");
+                            } else if stats.lines_added > 0 || stats.lines_deleted > 0 {
+                                content.push_str(&format!(" ±+{} -{}", stats.lines_added, stats.lines_deleted));
+                            }
+                        }
+                        if let Some(port) = review.port {
+                            let server = if review_health.server_up { "up" } else { "down" };
+                            content.push_str(&format!(" 🌐 :{} {}", port, server));
+                        }
+                    } else if loader.loading.contains(&review.key()) {
+                        content.push_str(&format!(" {} loading stats", spinner));
+                    }
+
+                    match loader.action_on(&review.key()) {
+                        Some((action, true)) => content.push_str(&format!(" {} {}", spinner, action.doing())),
+                        Some((action, false)) => content.push_str(&format!(" ⏸ queued: {}", action.doing())),
+                        None => {}
+                    }
+                    let failure = failures.get(&review.key());
+                    if let Some((action, reason)) = failure {
+                        content.push_str(&format!(" ✗ {} failed: {}", action.doing(), reason));
+                    }
+
+                    let findings = review
+                        .agent_analyses
+                        .iter()
+                        .flat_map(|analysis| &analysis.findings)
+                        .filter(|finding| show_resolved || !finding.triage.is_resolved())
+                        .count();
+                    if findings > 0 {
+                        let label = if show_resolved { "findings" } else { "unresolved" };
+                        content.push_str(&format!(" 🔍 {} {}", findings, label));
+                    }
+
+                    if let Some(info) = &review.pr_info {
+                        content.push_str(&format!(" — {}", info.headline()));
+                    }
+
+                    if let Some(expires_at) = review.expires_at {
+                        let remaining = ttl::format_remaining(expires_at, chrono::Utc::now());
+                        content.push_str(&format!(" ⏳ {}", remaining));
+                    }
+
+                    let mut alerting = false;
+                    if let Some(review_usage) = usage.get(&review.key()) {
+                        content.push_str(&format!(
+                            " 💻 {:.0}% {}",
+                            review_usage.cpu_percent(),
+                            resources::format_bytes(review_usage.memory_bytes())
+                        ));
+                        alerting = !review_usage.alerts(&monitor_config).is_empty();
+                    }
 
                     let style = if i == selected {
                         Style::default()
                             .fg(Color::Yellow)
                             .add_modifier(Modifier::BOLD)
+                    } else if alerting || failure.is_some() {
+                        Style::default().fg(Color::Red)
                     } else {
                         Style::default()
                     };
@@ -85,38 +693,258 @@ pub async fn execute() -> Result<()> {
                     ListItem::new(Line::from(vec![Span::styled(content, style)]))
                 })
                 .collect();
+            let items = if !reviews_loaded {
+                vec![ListItem::new(format!("{} Loading reviews...", spinner))]
+            } else if reviews.is_empty() {
+                vec![ListItem::new("No active review environments.")]
+            } else {
+                items
+            };
 
             let list = List::new(items).block(Block::default().borders(Borders::ALL).title("Reviews"));
-            f.render_widget(list, chunks[1]);
+            let selected_review = reviews.iter().find(|review| Some(review.key()) == selected_key);
+            let mut constraints = vec![Constraint::Min(0)];
+            if show_agents && selected_review.is_some() {
+                constraints.push(Constraint::Length(AGENT_PANE_LINES as u16 + 2));
+            }
+            if show_timeline && timeline.is_some() {
+                constraints.push(Constraint::Length(TIMELINE_EVENTS as u16 + 2));
+            }
+            let mut panes = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(constraints)
+                .split(chunks[1])
+                .to_vec()
+                .into_iter();
+            let list_area = panes.next().unwrap_or(chunks[1]);
+            f.render_widget(list, list_area);
+
+            if let (Some(review), true) = (selected_review, show_agents) {
+                let run = runs.iter().find(|run| run.key().matches(&review.key()));
+                let events = match &timeline {
+                    Some((key, events)) if *key == review.key() => events.as_slice(),
+                    _ => &[],
+                };
+                let analyzing = match loader.action_on(&review.key()) {
+                    Some((Action::Analyze, running)) => Some(running),
+                    _ => None,
+                };
+                let (title, lines) = agents_pane(review, run, analyzing, events, show_resolved, spinner);
+                let lines: Vec<ListItem> = lines.into_iter().map(ListItem::new).collect();
+                if let Some(area) = panes.next() {
+                    f.render_widget(List::new(lines).block(Block::default().borders(Borders::ALL).title(title)), area);
+                }
+            }
+
+            if let (Some((key, events)), true) = (&timeline, show_timeline) {
+                if let Some(area) = panes.next() {
+                    let lines: Vec<ListItem> = events
+                        .iter()
+                        .skip(events.len().saturating_sub(TIMELINE_EVENTS))
+                        .map(|event| {
+                            ListItem::new(format!(
+                                "{}  {:<15} {}",
+                                event.at.with_timezone(&chrono::Local).format("%m-%d %H:%M:%S"),
+                                event.event.to_string(),
+                                event.detail
+                            ))
+                        })
+                        .collect();
+                    let title = format!("Timeline - PR #{}", key.pr_number);
+                    f.render_widget(List::new(lines).block(Block::default().borders(Borders::ALL).title(title)), area);
+                }
+            }
 
-            // Help
-            let help = Paragraph::new("↑/↓: Navigate | Enter: Open | q: Quit")
+            // Help, under the progress of the last action
+            let help_block = match &batch {
+                Some(batch) => Block::default().borders(Borders::ALL).title(batch.summary()),
+                None => Block::default().borders(Borders::ALL),
+            };
+            let help = Paragraph::new("↑/↓: Navigate | Space: Mark | a: Run agents | s: Sync | c: Clean up | f: Unresolved/all findings | t: Timeline | p: Agents | r: Refresh | q: Quit")
                 .style(Style::default().fg(Color::Gray))
-                .block(Block::default().borders(Borders::ALL));
+                .block(help_block);
             f.render_widget(help, chunks[2]);
+
+            // Confirmation of an action, over everything else
+            if let Some((action, prs)) = &confirming {
+                let mut lines = vec![format!("{} {} review(s)?", action.verb(), prs.len()), String::new()];
+                for key in prs.iter().take(CONFIRM_LISTED) {
+                    let branch = reviews
+                        .iter()
+                        .find(|review| review.key() == *key)
+                        .map(|review| review.branch.as_str())
+                        .unwrap_or_default();
+                    lines.push(format!("  PR #{:<6} {}", key.pr_number, branch));
+                }
+                if prs.len() > CONFIRM_LISTED {
+                    lines.push(format!("  … and {} more", prs.len() - CONFIRM_LISTED));
+                }
+                if *action == Action::Cleanup {
+                    lines.push(String::new());
+                    lines.push("Worktrees with uncommitted changes or local commits are kept.".to_string());
+                }
+                lines.push(String::new());
+                lines.push("y/Enter: Confirm | n/Esc: Cancel".to_string());
+
+                let area = f.area();
+                let width = 70.min(area.width);
+                let height = (lines.len() as u16 + 2).min(area.height);
+                let dialog = Rect::new(
+                    area.x + (area.width - width) / 2,
+                    area.y + (area.height - height) / 2,
+                    width,
+                    height,
+                );
+                let text: Vec<Line> = lines.into_iter().map(Line::from).collect();
+                f.render_widget(Clear, dialog);
+                f.render_widget(
+                    Paragraph::new(text)
+                        .style(Style::default().fg(Color::Yellow))
+                        .block(Block::default().borders(Borders::ALL).title("Confirm")),
+                    dialog,
+                );
+            }
         })?;
 
+        // Wait briefly for a load to finish, then handle input
+        let finished = tokio::select! {
+            Some(loaded) = loader.running.next() => Some(loaded),
+            _ = tokio::time::sleep(INPUT_POLL) => None,
+        };
+        if let Some(loaded) = finished {
+            loader.finished(&loaded);
+            match loaded {
+                Loaded::Reviews(Ok(loaded_reviews)) => {
+                    reviews = loaded_reviews;
+                    reviews_loaded = true;
+                    selected = selected.min(reviews.len().saturating_sub(1));
+                    health.retain(|key, _| reviews.iter().any(|review| review.key() == *key));
+                    marked.retain(|key| reviews.iter().any(|review| review.key() == *key));
+                    failures.retain(|key, _| reviews.iter().any(|review| review.key() == *key));
+                    if !loader.measuring {
+                        loader.measure_usage(monitor.take(), reviews.clone());
+                    }
+                    let worktrees: Vec<(ReviewKey, PathBuf)> = reviews
+                        .iter()
+                        .map(|review| (review.key(), review.worktree_path.clone()))
+                        .collect();
+                    if monitor_config.watch && worktrees != watched {
+                        watcher = watch(&reviews);
+                        watched = worktrees;
+                    }
+                    for review in &reviews {
+                        loader.load_health(review);
+                    }
+                }
+                // Without reviews to show, there's nothing to fall back to
+                Loaded::Reviews(Err(e)) if !reviews_loaded => {
+                    outcome = Err(e);
+                    break;
+                }
+                Loaded::Reviews(Err(e)) => tracing::warn!("Failed to reload reviews: {}", e),
+                Loaded::Usage(Some((measured_by, measured))) => {
+                    monitor = Some(measured_by);
+                    usage = measured;
+                }
+                Loaded::Usage(None) => tracing::warn!("Failed to measure resource usage"),
+                Loaded::Health(key, review_health) => {
+                    health.insert(key, review_health);
+                }
+                Loaded::Action(action, key, failure) => {
+                    if let Some(batch) = batch.as_mut().filter(|batch| batch.action == action && batch.prs.contains(&key)) {
+                        batch.done += 1;
+                        if failure.is_some() {
+                            batch.failed.push(key.clone());
+                        }
+                    }
+                    if let Some(reason) = failure {
+                        failures.insert(key, (action, reason));
+                    }
+                    runs = activity::running();
+                    loader.reload_reviews();
+                }
+            }
+        }
+
         // Handle input
-        if event::poll(std::time::Duration::from_millis(100))? {
+        if event::poll(Duration::ZERO)? {
             if let Event::Key(key) = event::read()? {
+                // The confirmation dialog takes all input until answered
+                if let Some((action, prs)) = confirming.take() {
+                    match key.code {
+                        KeyCode::Char('y') | KeyCode::Enter => {
+                            for key in &prs {
+                                failures.remove(key);
+                                loader.act(action, key.clone());
+                            }
+                            if action == Action::Analyze {
+                                show_agents = true;
+                            }
+                            marked.clear();
+                            batch = Some(Batch { action, prs, done: 0, failed: Vec::new() });
+                        }
+                        KeyCode::Char('n') | KeyCode::Esc => {}
+                        _ => confirming = Some((action, prs)),
+                    }
+                    continue;
+                }
+
+                let action = match key.code {
+                    KeyCode::Char('a') => Some(Action::Analyze),
+                    KeyCode::Char('s') => Some(Action::Sync),
+                    KeyCode::Char('c') => Some(Action::Cleanup),
+                    _ => None,
+                };
+                if let Some(action) = action {
+                    // Marked reviews in list order, or else the selected one
+                    let prs: Vec<ReviewKey> = stack::stack_order(&reviews)
+                        .into_iter()
+                        .map(|(_, review)| review.key())
+                        .filter(|key| if marked.is_empty() { Some(key) == selected_key.as_ref() } else { marked.contains(key) })
+                        // Nothing is queued twice, and agents already at work keep going
+                        .filter(|key| loader.action_on(key).is_none())
+                        .filter(|key| action != Action::Analyze || !runs.iter().any(|run| run.key().matches(key)))
+                        .collect();
+                    if !prs.is_empty() {
+                        confirming = Some((action, prs));
+                    }
+                    continue;
+                }
+
                 match key.code {
                     KeyCode::Char('q') => break,
-                    KeyCode::Down => {
-                        if selected < reviews.len().saturating_sub(1) {
-                            selected += 1;
+                    KeyCode::Char('f') => show_resolved = !show_resolved,
+                    KeyCode::Char('r') => {
+                        // Read everything again, ignoring cached stats
+                        for review in &reviews {
+                            loader.cache.borrow_mut().invalidate(&review.worktree_path);
                         }
+                        health.clear();
+                        loader.reload_reviews();
+                        last_reload = Instant::now();
                     }
-                    KeyCode::Up => {
-                        if selected > 0 {
-                            selected -= 1;
-                        }
+                    KeyCode::Char('t') => {
+                        show_timeline = !show_timeline;
+                        timeline = None;
                     }
-                    KeyCode::Enter => {
-                        if selected < reviews.len() {
-                            // Show selected review info
-                            // In a real implementation, this would navigate to a detail view
+                    KeyCode::Char('p') => show_agents = !show_agents,
+                    KeyCode::Char(' ') => {
+                        if let Some(key) = &selected_key {
+                            if !marked.remove(key) {
+                                marked.insert(key.clone());
+                            }
                         }
                     }
+                    KeyCode::Down if selected < reviews.len().saturating_sub(1) => {
+                        selected += 1;
+                    }
+                    KeyCode::Up => {
+                        selected = selected.saturating_sub(1);
+                    }
+                    KeyCode::Enter if selected < reviews.len() => {
+                        // Show selected review info
+                        // In a real implementation, this would navigate to a detail view
+                    }
                     _ => {}
                 }
             }
@@ -131,5 +959,83 @@ pub async fn execute() -> Result<()> {
     )?;
     terminal.show_cursor()?;
 
-    Ok(())
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(repo: &str, pr_number: u32) -> ReviewKey {
+        ReviewKey {
+            repo: Some(PathBuf::from(repo)),
+            pr_number,
+        }
+    }
+
+    #[test]
+    fn test_action_args_run_in_the_review_repo() {
+        assert_eq!(
+            Action::Cleanup.args(&key("/src/app", 42)),
+            vec!["--repo", "/src/app", "cleanup", "--pr", "42", "--force"]
+        );
+        let legacy = ReviewKey {
+            repo: None,
+            pr_number: 42,
+        };
+        assert_eq!(Action::Sync.args(&legacy), vec!["sync", "--pr", "42"]);
+    }
+
+    #[test]
+    fn test_act_runs_one_action_at_a_time_in_order() {
+        let mut loader = Loader::new();
+        loader.act(Action::Sync, key("/src/app", 1));
+        loader.act(Action::Cleanup, key("/src/app", 2));
+        loader.act(Action::Analyze, key("/src/lib", 1));
+
+        assert_eq!(loader.action_on(&key("/src/app", 1)), Some((Action::Sync, true)));
+        assert_eq!(loader.action_on(&key("/src/app", 2)), Some((Action::Cleanup, false)));
+        // The same PR number in another repository is another review
+        assert_eq!(loader.action_on(&key("/src/lib", 1)), Some((Action::Analyze, false)));
+        assert_eq!(loader.action_on(&key("/src/lib", 2)), None);
+        assert_eq!(loader.running.len(), 1);
+
+        loader.finished(&Loaded::Action(Action::Sync, key("/src/app", 1), None));
+        assert_eq!(loader.action_on(&key("/src/app", 1)), None);
+        assert_eq!(loader.action_on(&key("/src/app", 2)), Some((Action::Cleanup, true)));
+        assert_eq!(loader.action_on(&key("/src/lib", 1)), Some((Action::Analyze, false)));
+
+        loader.finished(&Loaded::Action(Action::Cleanup, key("/src/app", 2), Some("exit status: 1".to_string())));
+        assert_eq!(loader.action_on(&key("/src/lib", 1)), Some((Action::Analyze, true)));
+        assert!(loader.actions.is_empty());
+    }
+
+    #[test]
+    fn test_act_ignores_reviews_already_queued_or_running() {
+        let mut loader = Loader::new();
+        loader.act(Action::Sync, key("/src/app", 1));
+        loader.act(Action::Sync, key("/src/app", 2));
+        loader.act(Action::Cleanup, key("/src/app", 1));
+        loader.act(Action::Cleanup, key("/src/app", 2));
+
+        assert_eq!(loader.action_on(&key("/src/app", 1)), Some((Action::Sync, true)));
+        assert_eq!(loader.action_on(&key("/src/app", 2)), Some((Action::Sync, false)));
+        assert_eq!(loader.actions.len(), 1);
+        assert_eq!(loader.running.len(), 1);
+    }
+
+    #[test]
+    fn test_batch_summary_points_at_the_logs_of_failures() {
+        let mut batch = Batch {
+            action: Action::Cleanup,
+            prs: vec![key("/src/app", 1), key("/src/app", 2), key("/src/lib", 3)],
+            done: 1,
+            failed: Vec::new(),
+        };
+        assert_eq!(batch.summary(), "Clean up: 1/3 done");
+
+        batch.done = 3;
+        batch.failed = vec![key("/src/app", 2), key("/src/lib", 3)];
+        assert_eq!(batch.summary(), "Clean up: 3/3 done, failed: #2, #3 (see chaba logs --pr 2)");
+    }
 }
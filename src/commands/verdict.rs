@@ -0,0 +1,92 @@
+use crate::config::Config;
+use crate::core::baseline::Baseline;
+use crate::core::git::GitOps;
+use crate::core::review_analysis::{AggregatedReport, FindingFilter, TriageStatus};
+use crate::core::state::State;
+use crate::core::verdict;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+pub async fn execute(pr: u32, submit: bool) -> Result<()> {
+    let config = Config::load()?;
+    let state = State::load()?;
+
+    let review = state
+        .get_review(pr)
+        .ok_or_else(|| ChabaError::PrNotFound(pr))?;
+
+    if review.agent_analyses.is_empty() {
+        println!("No AI agent analysis found for PR #{}", pr);
+        println!("\nTip: Run 'chaba analyze --pr {}' to generate analysis", pr);
+        return Ok(());
+    }
+
+    // Baselined, dismissed, fixed, and low-confidence findings don't count against the PR
+    let (unbaselined, baselined) = Baseline::for_review(review)?.suppress(&review.agent_analyses);
+    let unresolved = FindingFilter {
+        statuses: vec![TriageStatus::Open, TriageStatus::Acknowledged],
+        min_confidence: config.agents.min_confidence,
+        ..FindingFilter::default()
+    }
+    .apply(&unbaselined);
+    let report = AggregatedReport::from_analyses(&unresolved);
+    let decision = verdict::decide(&report, &config.agents.verdict);
+
+    say!("🍵 Chaba - Verdict for PR #{}\n", pr);
+    println!("  Verdict: {}", decision.verdict);
+    for reason in &decision.reasons {
+        println!("    • {}", reason);
+    }
+    let resolved = unbaselined
+        .iter()
+        .flat_map(|analysis| &analysis.findings)
+        .filter(|finding| finding.triage.is_resolved())
+        .count();
+    let speculative = match config.agents.min_confidence {
+        Some(min) => unbaselined
+            .iter()
+            .flat_map(|analysis| &analysis.findings)
+            .filter(|finding| !finding.triage.is_resolved())
+            .filter(|finding| finding.confidence.is_some_and(|confidence| confidence < min))
+            .count(),
+        None => 0,
+    };
+    let mut uncounted = Vec::new();
+    if resolved > 0 {
+        uncounted.push(format!("{} dismissed or fixed", resolved));
+    }
+    if speculative > 0 {
+        uncounted.push(format!("{} low-confidence", speculative));
+    }
+    if baselined > 0 {
+        uncounted.push(format!("{} baselined", baselined));
+    }
+    if uncounted.is_empty() {
+        println!("  Findings: {}", report.total());
+    } else {
+        println!("  Findings: {} ({} not counted)", report.total(), uncounted.join(", "));
+    }
+    if let Some(score) = report.score {
+        println!("  Combined Score: {:.1}/5.0", score);
+    }
+
+    if !submit {
+        println!("\nTip: Run 'chaba verdict --pr {} --submit' to post this review to GitHub", pr);
+        return Ok(());
+    }
+
+    if !review.is_pull_request() {
+        return Err(ChabaError::Other(anyhow::anyhow!(
+            "Review #{} is not a GitHub PR; nothing to submit",
+            pr
+        )));
+    }
+
+    let git_ops = GitOps::for_review(review)?;
+    git_ops
+        .submit_pr_review(pr, decision.verdict, &verdict::summary(&report, &decision))
+        .await?;
+    say!("\n✓ Submitted review to PR #{}", pr);
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use chrono::{Local, Utc};
+
+use crate::commands::{cleanup, review};
+use crate::config::Config;
+use crate::core::cancel;
+use crate::core::git::{GitOps, SyncOutcome};
+use crate::core::notification;
+use crate::core::pr_state::{self, PrUpdate};
+use crate::core::quota::QuotaLedger;
+use crate::core::state::{ReviewState, State};
+use crate::core::ttl;
+use crate::core::worktree::WorktreeManager;
+use crate::error::{ChabaError, Result};
+use crate::say;
+
+/// Options for `chaba watch`
+#[derive(Debug)]
+pub struct WatchOptions {
+    /// Time between polls
+    pub interval: chrono::Duration,
+    /// Poll once and exit
+    pub once: bool,
+    /// Run agents on new commits even for reviews that weren't analyzed before
+    pub with_agent: bool,
+    pub autostash: bool,
+}
+
+pub async fn execute(options: WatchOptions) -> Result<()> {
+    let config = Config::load()?;
+    let manager = WorktreeManager::new(config.clone())?;
+
+    if options.once {
+        say!("🍵 Chaba - Checking review environments for PR updates\n");
+    } else {
+        let now = chrono::Utc::now();
+        say!(
+            "🍵 Chaba - Watching review environments every {} (Ctrl+C to stop)\n",
+            ttl::format_remaining(now + options.interval, now)
+        );
+    }
+
+    loop {
+        if let Err(e) = remove_expired(&config, &manager).await {
+            eprintln!("✗ Expired reviews: {}", e);
+        }
+
+        let reviews: Vec<ReviewState> = State::load()?
+            .reviews
+            .into_iter()
+            .filter(|review| review.is_pull_request() && review.worktree_path.exists())
+            .collect();
+
+        println!("[{}] Checking {} PR(s)", Local::now().format("%H:%M:%S"), reviews.len());
+        for review in reviews {
+            let pr_number = review.pr_number;
+            if let Err(e) = poll(&config, &manager, review, &options).await {
+                eprintln!("✗ PR #{}: {}", pr_number, e);
+            }
+            if cancel::is_cancelled() {
+                return Err(ChabaError::Interrupted);
+            }
+        }
+        if let Err(e) = run_deferred(&config).await {
+            eprintln!("✗ Deferred agent runs: {}", e);
+        }
+        if cancel::is_cancelled() {
+            return Err(ChabaError::Interrupted);
+        }
+
+        if options.once {
+            return Ok(());
+        }
+        tokio::time::sleep(options.interval.to_std().unwrap_or_default()).await;
+    }
+}
+
+/// Bring one review up to date with its PR
+async fn poll(config: &Config, manager: &WorktreeManager, mut review: ReviewState, options: &WatchOptions) -> Result<()> {
+    let git_ops = GitOps::for_review(&review)?;
+    let status = git_ops.get_pr_status(review.pr_number).await?;
+    let local_head = git_ops.head_sha(&review.worktree_path).await.ok();
+
+    match pr_state::plan(&status, local_head.as_deref()) {
+        PrUpdate::UpToDate => {}
+        PrUpdate::Pull => {
+            let outcome = git_ops
+                .sync_worktree(&review.worktree_path, &review.branch, options.autostash)
+                .await?;
+            if outcome == SyncOutcome::UpToDate {
+                return Ok(());
+            }
+            say!("✓ PR #{}: pulled new commits from origin/{}", review.pr_number, review.branch);
+            let body = format!("PR #{}: pulled new commits from origin/{}", review.pr_number, review.branch);
+            notification::notify(&config.notifications, "Review updated", &body).await;
+
+            if options.with_agent || !review.agent_analyses.is_empty() {
+                review::analyze_within_quota(config, &mut review, false).await?;
+            }
+        }
+        PrUpdate::Remove(state) => {
+            // Don't throw away work done in the review environment
+            if !git_ops.unsaved_work(&review.worktree_path).await?.is_empty() {
+                say!(
+                    "⚠️  PR #{} was {}, but its worktree has uncommitted changes or local commits; keeping it",
+                    review.pr_number, state
+                );
+                return Ok(());
+            }
+
+            manager.remove_review(&review).await?;
+            say!("✓ PR #{} was {}; removed its review environment", review.pr_number, state);
+            let body = format!("PR #{} was {}; removed its review environment", review.pr_number, state);
+            notification::notify(&config.notifications, "Review removed", &body).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove reviews whose TTL has passed, like `chaba cleanup --expired --force`
+///
+/// Worktrees with unsaved work, or whose work can't be checked, are kept.
+async fn remove_expired(config: &Config, manager: &WorktreeManager) -> Result<()> {
+    for review in manager.expired()? {
+        if cancel::is_cancelled() {
+            break;
+        }
+        let work = match cleanup::unsaved_work(&review).await {
+            Ok(work) => work,
+            Err(e) => {
+                eprintln!("✗ PR #{}: {}", review.pr_number, e);
+                continue;
+            }
+        };
+        if !work.is_empty() {
+            say!(
+                "⚠️  PR #{} expired, but its worktree has uncommitted changes or local commits; keeping it",
+                review.pr_number
+            );
+            continue;
+        }
+
+        match manager.remove_review(&review).await {
+            Ok(()) => {
+                say!("✓ PR #{} expired; removed its review environment", review.pr_number);
+                let body = format!("PR #{} expired; removed its review environment", review.pr_number);
+                notification::notify(&config.notifications, "Review removed", &body).await;
+            }
+            Err(e) => eprintln!("✗ PR #{}: failed to remove: {}", review.pr_number, e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Start agent runs deferred by the quota, oldest first, while the quota allows
+///
+/// Runs for reviews that no longer exist are dropped from the queue.
+async fn run_deferred(config: &Config) -> Result<()> {
+    let mut ledger = QuotaLedger::load()?;
+    if ledger.deferred.is_empty() {
+        return Ok(());
+    }
+
+    let state = State::load()?;
+    let (deferred, gone): (Vec<_>, Vec<_>) = ledger
+        .deferred
+        .clone()
+        .into_iter()
+        .partition(|run| state.get_review(run.pr_number).is_some_and(|review| review.worktree_path.exists()));
+    if !gone.is_empty() {
+        for run in &gone {
+            ledger.forget(run.pr_number);
+        }
+        ledger.save()?;
+    }
+
+    for run in deferred {
+        if cancel::is_cancelled() {
+            break;
+        }
+        // Stay quiet about runs that still don't fit
+        let agents = if run.thorough {
+            config.agents.thorough_agents.len()
+        } else {
+            config.agents.default_agents.len()
+        };
+        let quota = &config.agents.quota;
+        if QuotaLedger::load()?
+            .exceeded(quota, run.author.as_deref(), agents as u32, Utc::now())
+            .is_some()
+        {
+            continue;
+        }
+
+        let Some(mut review) = State::load()?.get_review(run.pr_number).cloned() else {
+            continue;
+        };
+        say!("▶ PR #{}: starting deferred agent run", run.pr_number);
+        if let Err(e) = review::analyze_within_quota(config, &mut review, run.thorough).await {
+            eprintln!("✗ PR #{}: {}", run.pr_number, e);
+        }
+    }
+
+    Ok(())
+}
@@ -30,6 +30,7 @@
 //!     enabled: true
 //!     range_start: 3000
 //!     range_end: 4000
+//!     preferred: auto
 //!
 //! agents:
 //!   enabled: true
@@ -43,9 +44,12 @@
 //!   parallel: true
 //! ```
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+use crate::core::platform::Shell;
 use crate::error::Result;
 
 /// Main configuration structure for Chaba.
@@ -70,7 +74,7 @@ use crate::error::Result;
 /// // Access agents configuration
 /// println!("Agents enabled: {}", config.agents.enabled);
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Config {
     /// Worktree management settings
     #[serde(default)]
@@ -87,6 +91,18 @@ pub struct Config {
     /// Hooks configuration
     #[serde(default)]
     pub hooks: HooksConfig,
+
+    /// Resource usage alert thresholds
+    #[serde(default)]
+    pub monitor: MonitorConfig,
+
+    /// Where chaba keeps its state
+    #[serde(default)]
+    pub state: StateConfig,
+
+    /// Desktop notifications and Slack/Discord webhooks
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
 }
 
 /// Configuration for git worktree management.
@@ -99,7 +115,13 @@ pub struct Config {
 /// - `naming_template`: `"pr-{pr}"`
 /// - `auto_cleanup`: `true`
 /// - `keep_days`: `7`
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// - `editor_workspace`: `true`
+/// - `git_lfs`: `true`
+/// - `status_cache`: `true`
+/// - `checkout`: `worktree`
+/// - `sparse_paths`: `[]` (full checkout)
+/// - `default_base`: None (detected from `origin/HEAD`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WorktreeConfig {
     /// Base directory for creating worktrees
     ///
@@ -109,11 +131,13 @@ pub struct WorktreeConfig {
 
     /// Naming template for worktrees
     ///
-    /// Use `{pr}` as placeholder for PR number.
+    /// Placeholders: `{pr}` (PR number), `{branch}`, `{date}` (`YYYY-MM-DD`),
+    /// `{repo}` (repository directory name), and `{author}` (PR author).
+    /// Values are sanitized, so `feature/login` becomes `feature-login`.
     ///
     /// Default: `"pr-{pr}"`
     ///
-    /// Examples: `"pr-{pr}"`, `"review-{pr}"`, `"feature-{pr}"`
+    /// Examples: `"pr-{pr}"`, `"{repo}-{pr}"`, `"{branch}-{date}"`
     #[serde(default = "default_naming_template")]
     pub naming_template: String,
 
@@ -128,6 +152,81 @@ pub struct WorktreeConfig {
     /// Default: `7`
     #[serde(default = "default_keep_days")]
     pub keep_days: u32,
+
+    /// Keep a VSCode/Cursor multi-root workspace file listing all active
+    /// review worktrees at `<base_dir>/chaba.code-workspace`
+    ///
+    /// Default: `true`
+    #[serde(default = "default_editor_workspace")]
+    pub editor_workspace: bool,
+
+    /// Run `git lfs install --local` and `git lfs pull` in new worktrees whose
+    /// `.gitattributes` use Git LFS, so binary assets aren't left as pointer files
+    ///
+    /// Default: `true`
+    #[serde(default = "default_git_lfs")]
+    pub git_lfs: bool,
+
+    /// Enable git's untracked cache (and builtin fsmonitor on macOS and
+    /// Windows) in new worktrees, scoped to the worktree via
+    /// `extensions.worktreeConfig`, so `list` and the TUI compute stats
+    /// quickly on huge repositories
+    ///
+    /// Default: `true`
+    #[serde(default = "default_status_cache")]
+    pub status_cache: bool,
+
+    /// How review checkouts are created
+    ///
+    /// `worktree` adds a linked worktree of the main repository.
+    /// `shared_clone` makes a clone that borrows the main repository's
+    /// objects instead (`git clone --shared`), for repositories where linked
+    /// worktrees are impractical. It appears as quickly as a worktree even
+    /// for multi-GB repositories.
+    ///
+    /// The main repository's `git gc` doesn't know about the clones and may
+    /// delete objects they still use once nothing in the main repository
+    /// references them (after `gc.pruneExpire`, two weeks by default, e.g.
+    /// when a PR branch is deleted upstream; at once with
+    /// `git gc --prune=now`), leaving the clone corrupt. Don't prune while
+    /// shared clones exist, or set `gc.pruneExpire` to `never`. Branches and
+    /// tags with commits only a clone has are saved to the main repository
+    /// as `chaba/clone/<dir>/<name>` when the clone is removed.
+    ///
+    /// Default: `worktree`
+    #[serde(default)]
+    pub checkout: CheckoutMode,
+
+    /// Paths always checked out when using sparse checkout
+    ///
+    /// When set, new worktrees use cone-mode sparse checkout limited to the
+    /// directories touched by the changes plus these paths, which keeps
+    /// checkouts of large monorepos fast. `review --sparse` enables sparse
+    /// checkout for a single review.
+    ///
+    /// Default: `[]` (full checkout)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sparse_paths: Vec<String>,
+
+    /// Branch that branch and patch reviews are compared against
+    ///
+    /// PR reviews always use the PR's actual target branch from GitHub. A
+    /// repository can override this with `git config chaba.defaultBase <branch>`.
+    ///
+    /// Default: None (the default branch of `origin`, falling back to `main`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_base: Option<String>,
+}
+
+/// How `chaba review` creates a review's checkout
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckoutMode {
+    /// `git worktree add`
+    #[default]
+    Worktree,
+    /// `git clone --shared` of the main repository
+    SharedClone,
 }
 
 fn default_base_dir() -> PathBuf {
@@ -148,7 +247,19 @@ fn default_keep_days() -> u32 {
     7
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+fn default_editor_workspace() -> bool {
+    true
+}
+
+fn default_git_lfs() -> bool {
+    true
+}
+
+fn default_status_cache() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SandboxConfig {
     /// Auto install dependencies
     #[serde(default = "default_auto_install_deps")]
@@ -162,6 +273,14 @@ pub struct SandboxConfig {
     #[serde(default)]
     pub additional_env_files: Vec<String>,
 
+    /// How environment files are copied
+    #[serde(default)]
+    pub env: EnvCopyConfig,
+
+    /// Untracked files to copy from the main worktree (glob patterns, e.g. `certs/**`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub copy_files: Vec<String>,
+
     /// Node.js configuration
     #[serde(default)]
     pub node: NodeConfig,
@@ -169,16 +288,158 @@ pub struct SandboxConfig {
     /// Port configuration
     #[serde(default)]
     pub port: PortConfig,
+
+    /// Run the project's test suite after setup
+    #[serde(default)]
+    pub run_tests: bool,
+
+    /// Test command override (defaults to npm test, cargo test, pytest, or go test by project type)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_command: Option<String>,
+
+    /// Test suite timeout in seconds
+    #[serde(default = "default_test_timeout")]
+    pub test_timeout: u64,
+
+    /// Files copied into the worktree to seed it with data (e.g. a SQLite database or SQL dump)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub seed_fixtures: Vec<SeedFixture>,
+
+    /// Commands run in order after setup to load data (e.g. `npm run seed`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub seed_commands: Vec<String>,
+
+    /// Timeout for each seed command in seconds
+    #[serde(default = "default_seed_timeout")]
+    pub seed_timeout: u64,
+}
+
+/// A file copied into review worktrees before seed commands run
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SeedFixture {
+    /// Source file: relative to the main worktree, absolute, or starting with `~/`
+    pub from: String,
+
+    /// Destination relative to the review worktree
+    ///
+    /// Defaults to `from` for files in the repository and to the file name
+    /// otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NodeConfig {
     /// Package manager: auto, npm, yarn, pnpm, bun
     #[serde(default = "default_package_manager")]
     pub package_manager: String,
+
+    /// Dependency cache sharing between worktrees
+    #[serde(default)]
+    pub cache: DependencyCacheConfig,
+}
+
+/// Configuration for sharing dependency caches between worktrees.
+///
+/// Installing `node_modules` from scratch in every review worktree is slow
+/// and disk-hungry. These options let review worktrees reuse what the main
+/// worktree already has.
+///
+/// # Example
+///
+/// ```yaml
+/// sandbox:
+///   node:
+///     cache:
+///       share_pnpm_store: true
+///       cache_dir: ~/.chaba/cache
+///       clone_node_modules: true
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct DependencyCacheConfig {
+    /// Reuse the pnpm store of the main worktree
+    ///
+    /// Default: `false`
+    #[serde(default)]
+    pub share_pnpm_store: bool,
+
+    /// Shared cache directory for npm and yarn
+    ///
+    /// npm uses `<cache_dir>/npm` and yarn uses `<cache_dir>/yarn`. A leading
+    /// `~` is the home directory.
+    ///
+    /// Default: None (package manager default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Clone `node_modules` from the main worktree when lockfiles match
+    ///
+    /// Files are cloned copy-on-write where the filesystem supports it and
+    /// copied otherwise, so changes in the review never reach the main
+    /// worktree. Falls back to a regular install when the lockfiles differ
+    /// or the main worktree has no `node_modules`. `hardlink_node_modules`,
+    /// its earlier name, is still accepted.
+    ///
+    /// Default: `false`
+    #[serde(default, alias = "hardlink_node_modules")]
+    pub clone_node_modules: bool,
+}
+
+/// Configuration for copying environment files into review worktrees.
+///
+/// # Example
+///
+/// ```yaml
+/// sandbox:
+///   env:
+///     redact: true
+///     overrides_file: .env.review
+///     overrides:
+///       DATABASE_URL: postgres://localhost/app_pr_{pr}
+///       PORT: "{port}"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EnvCopyConfig {
+    /// Replace values of sensitive-looking variables instead of copying them verbatim
+    ///
+    /// Applies to env files matched by `copy_files` too (`.env.*`, `*.env`).
+    ///
+    /// Default: `false`
+    #[serde(default)]
+    pub redact: bool,
+
+    /// File in the main worktree whose values replace redacted variables
+    ///
+    /// Variables not listed here are replaced with a placeholder.
+    ///
+    /// Default: `.env.review`
+    #[serde(default = "default_env_overrides_file")]
+    pub overrides_file: String,
+
+    /// Variables set in the review's env files after copying
+    ///
+    /// `{pr}` and `{port}` in values are replaced with the review's PR number
+    /// and assigned port. Entries in `.chaba/env.overrides.yaml` in the
+    /// repository take precedence over these.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<String, String>,
+}
+
+fn default_env_overrides_file() -> String {
+    ".env.review".to_string()
+}
+
+impl Default for EnvCopyConfig {
+    fn default() -> Self {
+        EnvCopyConfig {
+            redact: false,
+            overrides_file: default_env_overrides_file(),
+            overrides: BTreeMap::new(),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PortConfig {
     /// Enable automatic port assignment
     #[serde(default = "default_port_enabled")]
@@ -191,6 +452,34 @@ pub struct PortConfig {
     /// Port range end
     #[serde(default = "default_port_range_end")]
     pub range_end: u16,
+
+    /// Port to try first, then the ones right after it, before scanning the range
+    ///
+    /// A port number, or `auto` for the usual port of the detected framework
+    /// (Next.js 3000, Vite 5173, Angular 4200, Django 8000, Flask 5000, Go
+    /// 8080, ...), so dev-server URLs stay predictable across reviews. May lie
+    /// outside the range.
+    ///
+    /// Default: None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preferred: Option<PreferredPort>,
+}
+
+/// Where port assignment starts looking
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum PreferredPort {
+    /// This port
+    Port(u16),
+    /// The detected framework's usual port
+    Convention(PortConvention),
+}
+
+/// `auto`, for [`PreferredPort::Convention`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PortConvention {
+    Auto,
 }
 
 fn default_auto_install_deps() -> bool {
@@ -201,6 +490,14 @@ fn default_copy_env_from_main() -> bool {
     true
 }
 
+fn default_test_timeout() -> u64 {
+    600
+}
+
+fn default_seed_timeout() -> u64 {
+    300
+}
+
 fn default_package_manager() -> String {
     "auto".to_string()
 }
@@ -223,8 +520,16 @@ impl Default for SandboxConfig {
             auto_install_deps: default_auto_install_deps(),
             copy_env_from_main: default_copy_env_from_main(),
             additional_env_files: vec![".env.local".to_string()],
+            env: EnvCopyConfig::default(),
+            copy_files: Vec::new(),
             node: NodeConfig::default(),
             port: PortConfig::default(),
+            run_tests: false,
+            test_command: None,
+            test_timeout: default_test_timeout(),
+            seed_fixtures: Vec::new(),
+            seed_commands: Vec::new(),
+            seed_timeout: default_seed_timeout(),
         }
     }
 }
@@ -233,6 +538,7 @@ impl Default for NodeConfig {
     fn default() -> Self {
         NodeConfig {
             package_manager: default_package_manager(),
+            cache: DependencyCacheConfig::default(),
         }
     }
 }
@@ -243,6 +549,7 @@ impl Default for PortConfig {
             enabled: default_port_enabled(),
             range_start: default_port_range_start(),
             range_end: default_port_range_end(),
+            preferred: None,
         }
     }
 }
@@ -277,6 +584,14 @@ impl PortConfig {
             ));
         }
 
+        if let Some(PreferredPort::Port(port)) = self.preferred {
+            if port < 1024 {
+                return Err(crate::error::ChabaError::ConfigError(
+                    format!("Invalid preferred port: {} should be >= 1024 (avoid well-known ports)", port)
+                ));
+            }
+        }
+
         Ok(())
     }
 }
@@ -290,6 +605,8 @@ impl PortConfig {
 /// - `claude`: Claude Code (fast, general-purpose)
 /// - `codex`: OpenAI Codex (implementation expert)
 /// - `gemini`: Google Gemini (strategic analyst)
+/// - `cursor-agent`: Cursor Agent CLI
+/// - `copilot`: GitHub Copilot CLI
 ///
 /// # Default Values
 ///
@@ -298,6 +615,9 @@ impl PortConfig {
 /// - `thorough_agents`: `["claude", "codex", "gemini"]`
 /// - `timeout`: `600` (10 minutes)
 /// - `parallel`: `true`
+/// - `preflight`: `true`
+/// - `structured_output`: `true`
+/// - `scope`: `repository`
 ///
 /// # Examples
 ///
@@ -312,8 +632,10 @@ impl PortConfig {
 ///     - gemini
 ///   timeout: 600
 ///   parallel: true
+///   preflight: true
+///   structured_output: true
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AgentsConfig {
     /// Enable AI agent integration
     ///
@@ -351,6 +673,227 @@ pub struct AgentsConfig {
     /// Default: `true`
     #[serde(default = "default_parallel")]
     pub parallel: bool,
+
+    /// Check that each agent CLI is installed and logged in before running it
+    ///
+    /// Agents that fail the check are skipped with instructions instead of
+    /// running into the timeout.
+    ///
+    /// Default: `true`
+    #[serde(default = "default_preflight")]
+    pub preflight: bool,
+
+    /// Run agent CLIs with their JSON output format
+    ///
+    /// `claude --output-format json`, `codex exec --json`, and `gemini
+    /// --output-format json` report the reply and token usage in a form chaba
+    /// reads directly. Turn this off for CLI versions without these flags;
+    /// plain text output is parsed heuristically.
+    ///
+    /// Default: `true`
+    #[serde(default = "default_structured_output")]
+    pub structured_output: bool,
+
+    /// What agents are asked to review
+    ///
+    /// `changed_files` gives agents the list of files the PR changes and asks
+    /// them to review only those, which cuts cost and noise on large
+    /// repositories.
+    ///
+    /// Default: `repository`
+    #[serde(default)]
+    pub scope: AgentScope,
+
+    /// Hide findings the agents are less confident about than this (0.0–1.0)
+    ///
+    /// Applies to `chaba agent-result` (unless `--min-confidence` is given)
+    /// and `chaba verdict`. Findings without a confidence are always kept.
+    ///
+    /// Default: None (show every finding)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_confidence: Option<f32>,
+
+    /// Severity given to every finding in a category, overriding the agent's
+    ///
+    /// Applied as each agent's output is parsed, after `category_mapping`.
+    ///
+    /// ```yaml
+    /// agents:
+    ///   severity_overrides:
+    ///     best-practice: info
+    ///     documentation: low
+    /// ```
+    ///
+    /// Default: empty (keep the agents' severities)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub severity_overrides: BTreeMap<String, String>,
+
+    /// Category to file findings under instead of the one the agent chose
+    ///
+    /// ```yaml
+    /// agents:
+    ///   category_mapping:
+    ///     architecture: code-quality
+    /// ```
+    ///
+    /// Default: empty (keep the agents' categories)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub category_mapping: BTreeMap<String, String>,
+
+    /// Per-agent environment variables
+    ///
+    /// Injected only into the named agent's process. Variables configured for
+    /// other agents are removed from its environment, so credentials stay isolated.
+    ///
+    /// ```yaml
+    /// agents:
+    ///   env:
+    ///     claude:
+    ///       ANTHROPIC_BASE_URL: https://proxy.example.com
+    ///       ANTHROPIC_API_KEY:
+    ///         secret: anthropic_api_key
+    ///     codex:
+    ///       OPENAI_API_KEY:
+    ///         keychain: openai
+    /// ```
+    ///
+    /// Default: empty
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, BTreeMap<String, EnvValue>>,
+
+    /// Secrets file used to resolve `secret:` environment values
+    ///
+    /// A YAML map of secret names to values. Should be readable only by the owner.
+    ///
+    /// Default: `~/.chaba/secrets.yaml`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secrets_file: Option<PathBuf>,
+
+    /// Thresholds `chaba verdict` uses to approve or request changes
+    #[serde(default)]
+    pub verdict: VerdictConfig,
+
+    /// Caps on agent runs in batch (`review --all-open`) and watch modes
+    #[serde(default)]
+    pub quota: QuotaConfig,
+
+    /// Send agents an anonymized diff instead of the worktree
+    #[serde(default)]
+    pub anonymize: AnonymizeConfig,
+
+    /// Agent profiles run with `chaba review --profile <name>`
+    ///
+    /// Profiles named `security`, `performance`, or `architecture` replace
+    /// the built-in ones.
+    ///
+    /// ```yaml
+    /// agents:
+    ///   profiles:
+    ///     accessibility:
+    ///       agent: claude
+    ///       prompt: PR #{pr} のUIの変更をアクセシビリティの観点からレビューしてください。
+    ///       category: best-practice
+    /// ```
+    ///
+    /// Default: empty (only the built-in profiles)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, AgentProfile>,
+}
+
+/// An agent bound to one review focus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AgentProfile {
+    /// Agent that runs the profile (claude, codex, gemini, cursor-agent, or copilot)
+    pub agent: String,
+
+    /// Review prompt used instead of the agent's default; `{pr}` is replaced
+    /// by the PR number
+    pub prompt: String,
+
+    /// Category given to findings the agent leaves uncategorized
+    /// (e.g. `security`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+}
+
+/// Profiles available without configuration
+fn builtin_profiles() -> BTreeMap<String, AgentProfile> {
+    let profile = |agent: &str, prompt: &str, category: &str| AgentProfile {
+        agent: agent.to_string(),
+        prompt: prompt.to_string(),
+        category: Some(category.to_string()),
+    };
+
+    BTreeMap::from([
+        (
+            "security".to_string(),
+            profile(
+                "claude",
+                "PR #{pr} をセキュリティの観点のみからレビューしてください。インジェクション、認証・認可の不備、秘密情報の漏洩、安全でない入力処理を重点的に確認し、それ以外の指摘は不要です。",
+                "security",
+            ),
+        ),
+        (
+            "performance".to_string(),
+            profile(
+                "codex",
+                "PR #{pr} をパフォーマンスの観点のみからレビューしてください。計算量、不要なアロケーションやコピー、N+1クエリ、ブロッキングI/Oを重点的に確認し、それ以外の指摘は不要です。",
+                "performance",
+            ),
+        ),
+        (
+            "architecture".to_string(),
+            profile(
+                "gemini",
+                "PR #{pr} を設計の観点のみからレビューしてください。責務の分割、モジュール間の依存関係、拡張性、既存の設計との一貫性を重点的に確認し、それ以外の指摘は不要です。",
+                "architecture",
+            ),
+        ),
+    ])
+}
+
+impl AgentsConfig {
+    /// Look up a profile, preferring configured profiles over built-in ones
+    pub fn profile(&self, name: &str) -> Option<AgentProfile> {
+        self.profiles
+            .get(name)
+            .cloned()
+            .or_else(|| builtin_profiles().remove(name))
+    }
+
+    /// Names of the configured and built-in profiles
+    pub fn profile_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = builtin_profiles()
+            .into_keys()
+            .chain(self.profiles.keys().cloned())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+/// What agents review, see [`AgentsConfig::scope`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentScope {
+    /// The changes, with the whole repository available for context
+    #[default]
+    Repository,
+    /// Only the files the changes touch
+    ChangedFiles,
+}
+
+/// Source of an agent environment variable value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum EnvValue {
+    /// Literal value
+    Literal(String),
+    /// Value looked up by name in the secrets file
+    Secret { secret: String },
+    /// Value looked up by name in the OS keychain
+    Keychain { keychain: String },
 }
 
 fn default_agents_enabled() -> bool {
@@ -377,6 +920,14 @@ fn default_parallel() -> bool {
     true
 }
 
+fn default_preflight() -> bool {
+    true
+}
+
+fn default_structured_output() -> bool {
+    true
+}
+
 impl Default for AgentsConfig {
     fn default() -> Self {
         AgentsConfig {
@@ -385,6 +936,190 @@ impl Default for AgentsConfig {
             thorough_agents: default_thorough_agents(),
             timeout: default_agent_timeout(),
             parallel: default_parallel(),
+            preflight: default_preflight(),
+            structured_output: default_structured_output(),
+            scope: AgentScope::default(),
+            env: BTreeMap::new(),
+            secrets_file: None,
+            verdict: VerdictConfig::default(),
+            quota: QuotaConfig::default(),
+            anonymize: AnonymizeConfig::default(),
+            profiles: BTreeMap::new(),
+            min_confidence: None,
+            severity_overrides: BTreeMap::new(),
+            category_mapping: BTreeMap::new(),
+        }
+    }
+}
+
+/// Thresholds for `chaba verdict`.
+///
+/// Changes are requested when the agents' deduplicated findings exceed any
+/// of the limits, or the combined score is below `min_score`. Otherwise the
+/// PR is approved. A limit that is not set allows any number of findings.
+///
+/// # Example
+///
+/// ```yaml
+/// agents:
+///   verdict:
+///     max_critical: 0   # any critical finding requests changes
+///     max_high: 2
+///     min_score: 3.0
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct VerdictConfig {
+    /// Most critical findings allowed
+    ///
+    /// Default: `0`
+    #[serde(default = "default_max_critical", skip_serializing_if = "Option::is_none")]
+    pub max_critical: Option<usize>,
+
+    /// Most high-severity findings allowed
+    ///
+    /// Default: None (no limit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_high: Option<usize>,
+
+    /// Most medium-severity findings allowed
+    ///
+    /// Default: None (no limit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_medium: Option<usize>,
+
+    /// Lowest combined agent score (out of 5.0) that can be approved
+    ///
+    /// Default: None (score is ignored)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_score: Option<f32>,
+}
+
+fn default_max_critical() -> Option<usize> {
+    Some(0)
+}
+
+impl Default for VerdictConfig {
+    fn default() -> Self {
+        VerdictConfig {
+            max_critical: default_max_critical(),
+            max_high: None,
+            max_medium: None,
+            min_score: None,
+        }
+    }
+}
+
+/// Daily caps on agent runs started by `chaba review --all-open` and `chaba watch`.
+///
+/// Each agent invoked counts as one run (a `--thorough` review of three
+/// agents uses three). Runs that would exceed a cap are deferred and shown
+/// in `chaba queue`; `chaba watch` starts them once the quota allows, oldest
+/// first. Reviews started by hand are never capped.
+///
+/// # Example
+///
+/// ```yaml
+/// agents:
+///   quota:
+///     per_day: 50    # agent runs per day, across all PRs
+///     per_user: 10   # agent runs per day for one PR author's PRs
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct QuotaConfig {
+    /// Most agent runs per day
+    ///
+    /// Default: None (no limit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_day: Option<u32>,
+
+    /// Most agent runs per day for the PRs of one author
+    ///
+    /// Default: None (no limit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_user: Option<u32>,
+}
+
+impl QuotaConfig {
+    /// Whether any cap is set
+    pub fn is_limited(&self) -> bool {
+        self.per_day.is_some() || self.per_user.is_some()
+    }
+}
+
+/// Anonymized review mode for teams that can't send source code to cloud agents.
+///
+/// When enabled, agents don't run in the worktree. They get a scratch
+/// directory holding only `changes.diff`, the review's diff with names,
+/// email addresses, and string literals replaced by placeholders (`id_1`,
+/// `user_1@example.invalid`, `"str_1"`, `file_1.rs`). Language keywords are
+/// kept so the code stays readable. The placeholder mapping never leaves the
+/// machine: it is saved to `.chaba/anonymize-map.json` in the worktree and
+/// used to restore the real names in the agents' findings.
+///
+/// Other prompt context (test results, merge target) is not sent in this mode.
+///
+/// # Example
+///
+/// ```yaml
+/// agents:
+///   anonymize:
+///     enabled: true
+///     strings: false       # keep string literals
+///     keep: [serde, tokio] # names that are fine to send
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AnonymizeConfig {
+    /// Send agents an anonymized diff
+    ///
+    /// Default: `false`
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Replace identifiers and file paths with placeholders
+    ///
+    /// Default: `true`
+    #[serde(default = "default_anonymize_identifiers")]
+    pub identifiers: bool,
+
+    /// Replace email addresses with placeholders
+    ///
+    /// Default: `true`
+    #[serde(default = "default_anonymize_emails")]
+    pub emails: bool,
+
+    /// Replace the contents of string literals with placeholders
+    ///
+    /// Default: `true`
+    #[serde(default = "default_anonymize_strings")]
+    pub strings: bool,
+
+    /// Identifiers sent as they are, in addition to language keywords
+    ///
+    /// Default: empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keep: Vec<String>,
+}
+
+fn default_anonymize_identifiers() -> bool {
+    true
+}
+
+fn default_anonymize_emails() -> bool {
+    true
+}
+
+fn default_anonymize_strings() -> bool {
+    true
+}
+
+impl Default for AnonymizeConfig {
+    fn default() -> Self {
+        AnonymizeConfig {
+            enabled: false,
+            identifiers: default_anonymize_identifiers(),
+            emails: default_anonymize_emails(),
+            strings: default_anonymize_strings(),
+            keep: Vec::new(),
         }
     }
 }
@@ -400,8 +1135,9 @@ impl Default for AgentsConfig {
 ///   post_create: |
 ///     npm install
 ///     echo "Setup complete for $CHABA_BRANCH"
+///   shell: powershell   # e.g. on Windows
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct HooksConfig {
     /// Command to run after worktree creation
     ///
@@ -413,19 +1149,191 @@ pub struct HooksConfig {
     /// Default: None
     #[serde(default)]
     pub post_create: Option<String>,
+
+    /// Shell that runs hooks: `sh`, `cmd`, or `powershell`
+    ///
+    /// Default: `sh` on Unix, `cmd` on Windows
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<Shell>,
+}
+
+/// Resource usage alert thresholds for review environments.
+///
+/// `chaba status` and the TUI show the CPU and memory used by processes
+/// running inside each worktree (dev servers, tests, agents) and warn when a
+/// review exceeds these limits. Useful on shared hosts.
+///
+/// # Example
+///
+/// ```yaml
+/// monitor:
+///   cpu_alert_percent: 200   # two full cores
+///   memory_alert_mb: 4096
+///   watch: true
+///   refresh_secs: 5
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MonitorConfig {
+    /// Warn when a review's processes use more CPU than this (100 = one core)
+    ///
+    /// Default: None (no alert)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_alert_percent: Option<f32>,
+
+    /// Warn when a review's processes use more memory than this, in MB
+    ///
+    /// Default: None (no alert)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_alert_mb: Option<u64>,
+
+    /// Watch worktrees for file changes in the TUI and refresh git stats and
+    /// dev-server health only for reviews that changed
+    ///
+    /// When disabled (e.g. when a huge repo exhausts inotify watches), the
+    /// TUI relies on its periodic refresh instead.
+    ///
+    /// Default: `true`
+    #[serde(default = "default_watch")]
+    pub watch: bool,
+
+    /// How often the TUI reloads reviews and refreshes their git stats and
+    /// dev-server health, in seconds; `0` refreshes only when `r` is pressed
+    ///
+    /// Default: `5`
+    #[serde(default = "default_refresh_secs")]
+    pub refresh_secs: u64,
+}
+
+fn default_watch() -> bool {
+    true
+}
+
+fn default_refresh_secs() -> u64 {
+    5
 }
 
-impl Default for Config {
+impl Default for MonitorConfig {
     fn default() -> Self {
-        Config {
-            worktree: WorktreeConfig::default(),
-            sandbox: SandboxConfig::default(),
-            agents: AgentsConfig::default(),
-            hooks: HooksConfig::default(),
+        MonitorConfig {
+            cpu_alert_percent: None,
+            memory_alert_mb: None,
+            watch: default_watch(),
+            refresh_secs: default_refresh_secs(),
         }
     }
 }
 
+/// Location of chaba's state directory.
+///
+/// The state file, per-review logs, snapshots, traces, and the archive of
+/// cleaned-up reviews live in one directory. Move it on shared hosts or in
+/// CI where the home directory is read-only or shared between jobs.
+///
+/// Resolved in this order:
+/// 1. `CHABA_STATE_DIR` environment variable
+/// 2. `state.path`
+/// 3. `$XDG_STATE_HOME/chaba`, when `XDG_STATE_HOME` is set
+/// 4. `~/.chaba`
+///
+/// # Example
+///
+/// ```yaml
+/// state:
+///   path: ~/.local/state/chaba
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct StateConfig {
+    /// State directory; `~/` expands to the home directory
+    ///
+    /// Default: None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+/// Desktop and webhook notifications.
+///
+/// Review setup, agent analysis, and `chaba watch` can take minutes. With
+/// `desktop: true`, chaba shows a notification when review creation or an
+/// agent run finishes or fails, and when `watch` pulls new commits or removes
+/// an environment. Uses `osascript` on macOS, `notify-send` on Linux, and a
+/// PowerShell toast on Windows.
+///
+/// `webhooks` post agent results to Slack or Discord channels: a summary of
+/// the findings by severity, the most severe ones, and a link to the PR.
+/// Webhook URLs are secrets, so they can come from the secrets file or the
+/// OS keychain like agent environment variables.
+///
+/// # Example
+///
+/// ```yaml
+/// notifications:
+///   desktop: true
+///   webhooks:
+///     - url: { secret: slack_webhook }
+///       events: [critical_finding, cleanup]
+///     - url: https://discord.com/api/webhooks/123/abc
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct NotificationsConfig {
+    /// Show desktop notifications
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub desktop: bool,
+
+    /// Slack or Discord webhooks to post to
+    ///
+    /// Default: []
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<WebhookConfig>,
+}
+
+/// A Slack or Discord incoming webhook
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WebhookConfig {
+    /// Webhook URL: a literal, `{ secret: <name> }`, or `{ keychain: <name> }`
+    pub url: EnvValue,
+
+    /// Events to post; all of them when empty
+    ///
+    /// Default: []
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<WebhookEvent>,
+
+    /// Message format; detected from the URL when unset (`discord.com` URLs are Discord)
+    ///
+    /// Default: None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<WebhookFormat>,
+}
+
+impl WebhookConfig {
+    /// Whether this webhook wants `event`
+    pub fn wants(&self, event: WebhookEvent) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+}
+
+/// Event that can be posted to a webhook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// An agent analysis finished
+    AnalysisComplete,
+    /// An agent analysis finished with at least one critical finding
+    CriticalFinding,
+    /// A review environment was removed
+    Cleanup,
+}
+
+/// Payload format of a webhook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    Slack,
+    Discord,
+}
+
 impl Default for WorktreeConfig {
     fn default() -> Self {
         WorktreeConfig {
@@ -433,6 +1341,12 @@ impl Default for WorktreeConfig {
             naming_template: default_naming_template(),
             auto_cleanup: default_auto_cleanup(),
             keep_days: default_keep_days(),
+            editor_workspace: default_editor_workspace(),
+            git_lfs: default_git_lfs(),
+            status_cache: default_status_cache(),
+            checkout: CheckoutMode::default(),
+            sparse_paths: Vec::new(),
+            default_base: None,
         }
     }
 }
@@ -473,18 +1387,56 @@ impl Config {
         let config = Config::default();
         serde_yaml::to_string(&config).unwrap_or_else(|_| String::from("# Failed to generate config"))
     }
+
+    /// Generate JSON Schema for `chaba.yaml`
+    ///
+    /// Editors can use this for completion and validation, and CI can lint config changes.
+    pub fn json_schema() -> String {
+        let schema = schemars::schema_for!(Config);
+        serde_json::to_string_pretty(&schema).unwrap_or_else(|_| String::from("{}"))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_agent_profiles() {
+        let mut config = AgentsConfig::default();
+        assert_eq!(config.profile("security").unwrap().agent, "claude");
+        assert!(config.profile("accessibility").is_none());
+
+        config.profiles.insert(
+            "security".to_string(),
+            AgentProfile {
+                agent: "codex".to_string(),
+                prompt: "PR #{pr}".to_string(),
+                category: None,
+            },
+        );
+        config.profiles.insert(
+            "accessibility".to_string(),
+            AgentProfile {
+                agent: "gemini".to_string(),
+                prompt: "PR #{pr}".to_string(),
+                category: None,
+            },
+        );
+        assert_eq!(config.profile("security").unwrap().agent, "codex");
+        assert_eq!(
+            config.profile_names(),
+            vec!["accessibility", "architecture", "performance", "security"]
+        );
+    }
+
     #[test]
     fn test_port_config_valid() {
         let config = PortConfig {
             enabled: true,
             range_start: 3000,
             range_end: 4000,
+            preferred: None,
         };
         assert!(config.validate().is_ok());
     }
@@ -495,6 +1447,7 @@ mod tests {
             enabled: true,
             range_start: 4000,
             range_end: 3000,
+            preferred: None,
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -507,6 +1460,7 @@ mod tests {
             enabled: true,
             range_start: 80,
             range_end: 4000,
+            preferred: None,
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -520,16 +1474,31 @@ mod tests {
             enabled: true,
             range_start: 60000,
             range_end: 65535,
+            preferred: None,
         };
         assert!(config.validate().is_ok());
     }
 
+    #[test]
+    fn test_port_config_preferred() {
+        let config: PortConfig = serde_yaml::from_str("preferred: auto").unwrap();
+        assert_eq!(config.preferred, Some(PreferredPort::Convention(PortConvention::Auto)));
+        let config: PortConfig = serde_yaml::from_str("preferred: 8000").unwrap();
+        assert_eq!(config.preferred, Some(PreferredPort::Port(8000)));
+        assert!(config.validate().is_ok());
+        assert!(serde_yaml::from_str::<PortConfig>("preferred: sometimes").is_err());
+
+        let config: PortConfig = serde_yaml::from_str("preferred: 80").unwrap();
+        assert!(config.validate().unwrap_err().to_string().contains("Invalid preferred port"));
+    }
+
     #[test]
     fn test_port_config_range_too_small() {
         let config = PortConfig {
             enabled: true,
             range_start: 3000,
             range_end: 3005, // Only 5 ports
+            preferred: None,
         };
         let result = config.validate();
         assert!(result.is_err());
@@ -542,6 +1511,7 @@ mod tests {
             enabled: true,
             range_start: 3000,
             range_end: 3010, // Exactly 10 ports
+            preferred: None,
         };
         assert!(config.validate().is_ok());
     }
@@ -557,12 +1527,13 @@ mod tests {
                 start in 1024u16..60000u16,
                 range_size in 10u16..1000u16
             ) {
-                let end = start.saturating_add(range_size).min(65535);
+                let end = start.saturating_add(range_size);
                 if end > start + 10 {
                     let config = PortConfig {
                         enabled: true,
                         range_start: start,
                         range_end: end,
+                        preferred: None,
                     };
                     prop_assert!(config.validate().is_ok());
                 }
@@ -576,6 +1547,7 @@ mod tests {
                     enabled: true,
                     range_start: start,
                     range_end: start + 100,
+                    preferred: None,
                 };
                 prop_assert!(config.validate().is_err());
             }
@@ -589,19 +1561,21 @@ mod tests {
                     enabled: true,
                     range_start: start + offset,
                     range_end: start,
+                    preferred: None,
                 };
                 prop_assert!(config.validate().is_err());
             }
 
             #[test]
             fn test_small_ranges_always_fail(
-                start in 1024u16..65530u16,
+                start in 1024u16..65526u16,
                 size in 1u16..10u16,
             ) {
                 let config = PortConfig {
                     enabled: true,
                     range_start: start,
                     range_end: start + size,
+                    preferred: None,
                 };
                 prop_assert!(config.validate().is_err());
             }
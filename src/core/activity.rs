@@ -0,0 +1,281 @@
+//! Running agent tracking
+//!
+//! While `chaba review` runs AI agents it keeps a marker file in
+//! `~/.chaba/running/<repo-hash>-pr-<number>.json`, so other processes (such
+//! as `chaba statusline`) can show which reviews have agents at work. The marker
+//! is removed when the run finishes; markers left behind by a killed process
+//! are ignored once the run's timeout has passed. Together with the agent
+//! events in the review's event log, the marker tells how far each agent of
+//! the run has got (see [`progress`]).
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::core::events::{Event, EventKind};
+use crate::core::state::{self, ReviewKey};
+use crate::error::{ChabaError, Result};
+
+/// An agent run in progress
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentRun {
+    pub pr_number: u32,
+    /// Repository of the review, `None` in markers from older versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<PathBuf>,
+    pub agents: Vec<String>,
+    pub started_at: DateTime<Utc>,
+    /// Latest time the run can still be going, given the agent timeout
+    pub deadline: DateTime<Utc>,
+}
+
+impl AgentRun {
+    pub fn key(&self) -> ReviewKey {
+        ReviewKey {
+            repo: self.repo.clone(),
+            pr_number: self.pr_number,
+        }
+    }
+}
+
+/// Marker for an agent run; removes itself when dropped
+#[derive(Debug)]
+pub struct AgentActivity {
+    path: PathBuf,
+}
+
+impl Drop for AgentActivity {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Directory holding markers, `~/.chaba/running/` by default
+pub fn running_dir() -> Result<PathBuf> {
+    Ok(state::state_dir()?.join("running"))
+}
+
+/// Record that `agents` are running for a review
+///
+/// `timeout_secs` is the per-agent timeout; agents are assumed to run one
+/// after another when computing how long the marker stays valid.
+pub fn start(key: &ReviewKey, agents: &[String], timeout_secs: u64) -> Result<AgentActivity> {
+    start_in(&running_dir()?, key, agents, timeout_secs)
+}
+
+/// Record an agent run under a specific directory
+pub fn start_in(dir: &Path, key: &ReviewKey, agents: &[String], timeout_secs: u64) -> Result<AgentActivity> {
+    let started_at = Utc::now();
+    let budget = timeout_secs.saturating_mul(agents.len().max(1) as u64);
+    let run = AgentRun {
+        pr_number: key.pr_number,
+        repo: key.repo.clone(),
+        agents: agents.to_vec(),
+        started_at,
+        deadline: started_at + Duration::seconds(budget.min(i64::MAX as u64) as i64),
+    };
+
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.json", key.file_stem()));
+    let json = serde_json::to_string(&run).map_err(|e| ChabaError::Other(e.into()))?;
+    std::fs::write(&path, json)?;
+
+    Ok(AgentActivity { path })
+}
+
+/// Agent runs currently in progress, by PR number
+pub fn running() -> Vec<AgentRun> {
+    running_dir()
+        .map(|dir| running_in(&dir, Utc::now()))
+        .unwrap_or_default()
+}
+
+/// Agent runs recorded under `dir` that haven't passed their deadline at `now`
+pub fn running_in(dir: &Path, now: DateTime<Utc>) -> Vec<AgentRun> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut runs: Vec<AgentRun> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+        .filter_map(|content| serde_json::from_str::<AgentRun>(&content).ok())
+        .filter(|run| run.deadline > now)
+        .collect();
+    runs.sort_by_key(|run| run.pr_number);
+
+    runs
+}
+
+/// How far an agent of a run has got
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AgentState {
+    /// Not started yet
+    Waiting,
+    Running { since: DateTime<Utc> },
+    /// Finished; `outcome` is e.g. `3 finding(s)` or `failed`
+    Finished { took: Duration, outcome: String },
+}
+
+/// Progress of one agent of a run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentProgress {
+    /// Agent name, with its profile if it runs with one (`claude (security)`)
+    pub label: String,
+    pub state: AgentState,
+}
+
+/// Progress of each agent of `run`, from the review's event log
+///
+/// Agents that started come first, in the order they started; agents of the
+/// run without events yet are waiting.
+pub fn progress(run: &AgentRun, events: &[Event]) -> Vec<AgentProgress> {
+    let mut progress: Vec<AgentProgress> = Vec::new();
+
+    for event in events.iter().filter(|event| event.at >= run.started_at) {
+        match event.event {
+            EventKind::AgentStarted => progress.push(AgentProgress {
+                label: event.detail.clone(),
+                state: AgentState::Running { since: event.at },
+            }),
+            EventKind::AgentFinished => {
+                let Some((label, outcome)) = event.detail.rsplit_once(": ") else {
+                    continue;
+                };
+                let running = progress.iter_mut().find_map(|agent| match agent.state {
+                    AgentState::Running { since } if agent.label == label => Some((agent, since)),
+                    _ => None,
+                });
+                if let Some((agent, since)) = running {
+                    agent.state = AgentState::Finished {
+                        took: event.at - since,
+                        outcome: outcome.to_string(),
+                    };
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut waiting = run.agents.clone();
+    for agent in &progress {
+        let name = agent.label.split(" (").next().unwrap_or_default();
+        if let Some(i) = waiting.iter().position(|waiting| waiting == name) {
+            waiting.remove(i);
+        }
+    }
+    progress.extend(waiting.into_iter().map(|label| AgentProgress {
+        label,
+        state: AgentState::Waiting,
+    }));
+
+    progress
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn key(pr_number: u32) -> ReviewKey {
+        ReviewKey {
+            repo: Some(PathBuf::from("/repos/app")),
+            pr_number,
+        }
+    }
+
+    #[test]
+    fn test_marker_lifetime() {
+        let temp_dir = TempDir::new().unwrap();
+        let agents = vec!["claude".to_string(), "codex".to_string()];
+
+        let activity = start_in(temp_dir.path(), &key(123), &agents, 600).unwrap();
+        let runs = running_in(temp_dir.path(), Utc::now());
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].pr_number, 123);
+        assert_eq!(runs[0].agents, agents);
+
+        drop(activity);
+        assert!(running_in(temp_dir.path(), Utc::now()).is_empty());
+    }
+
+    #[test]
+    fn test_same_pr_in_two_repositories() {
+        let temp_dir = TempDir::new().unwrap();
+        let agents = vec!["claude".to_string()];
+        let other = ReviewKey {
+            repo: Some(PathBuf::from("/repos/other")),
+            pr_number: 123,
+        };
+
+        let _first = start_in(temp_dir.path(), &key(123), &agents, 600).unwrap();
+        let second = start_in(temp_dir.path(), &other, &agents, 600).unwrap();
+        assert_eq!(running_in(temp_dir.path(), Utc::now()).len(), 2);
+
+        drop(second);
+        let runs = running_in(temp_dir.path(), Utc::now());
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].key(), key(123));
+    }
+
+    #[test]
+    fn test_stale_marker_ignored() {
+        let temp_dir = TempDir::new().unwrap();
+        let _activity = start_in(temp_dir.path(), &key(123), &["claude".to_string()], 600).unwrap();
+
+        let later = Utc::now() + Duration::seconds(601);
+        assert!(running_in(temp_dir.path(), later).is_empty());
+    }
+
+    #[test]
+    fn test_missing_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(running_in(&temp_dir.path().join("nope"), Utc::now()).is_empty());
+    }
+
+    #[test]
+    fn test_progress_from_events() {
+        let started_at = Utc::now();
+        let run = AgentRun {
+            pr_number: 123,
+            repo: None,
+            agents: vec!["claude".to_string(), "claude".to_string(), "codex".to_string()],
+            started_at,
+            deadline: started_at + Duration::minutes(30),
+        };
+        let event = |secs: i64, event: EventKind, detail: &str| Event {
+            at: started_at + Duration::seconds(secs),
+            event,
+            detail: detail.to_string(),
+        };
+        let events = vec![
+            // From an earlier run
+            event(-60, EventKind::AgentStarted, "codex"),
+            event(1, EventKind::AgentStarted, "claude (security)"),
+            event(2, EventKind::AgentStarted, "claude (style)"),
+            event(40, EventKind::AgentFinished, "claude (security): 3 finding(s)"),
+        ];
+
+        let progress = progress(&run, &events);
+        assert_eq!(
+            progress,
+            vec![
+                AgentProgress {
+                    label: "claude (security)".to_string(),
+                    state: AgentState::Finished {
+                        took: Duration::seconds(39),
+                        outcome: "3 finding(s)".to_string(),
+                    },
+                },
+                AgentProgress {
+                    label: "claude (style)".to_string(),
+                    state: AgentState::Running { since: started_at + Duration::seconds(2) },
+                },
+                AgentProgress {
+                    label: "codex".to_string(),
+                    state: AgentState::Waiting,
+                },
+            ]
+        );
+    }
+}
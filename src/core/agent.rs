@@ -1,18 +1,81 @@
 use std::ffi::OsStr;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::Output;
 use std::sync::Arc;
 use std::time::Duration;
 
 use indicatif::{ProgressBar, ProgressStyle};
 
-use crate::config::AgentsConfig;
-use crate::core::command::{CommandRunner, LiveCommandRunner};
-use crate::core::review_analysis::{ReviewAnalysis, Finding, Severity, Category};
+use crate::config::{AgentProfile, AgentsConfig};
+use crate::core::agent_output;
+use crate::core::cancel;
+use crate::core::command::{self, CommandEnv, CommandRunner, EnvCommandRunner, LineSink};
+use crate::core::events::{self, EventKind};
+use crate::core::logs::ReviewLogs;
+use crate::core::preflight::{self, Check, CheckStatus};
+use crate::core::secrets;
+use crate::core::review_analysis::{ReviewAnalysis, Finding, FindingPolicy, Severity, Category, Usage};
 use crate::error::{ChabaError, Result};
 
+/// One agent to run, optionally under a profile
+#[derive(Debug, Clone)]
+pub struct AgentRun {
+    agent: String,
+    /// Profile name and settings replacing the agent's default prompt
+    profile: Option<(String, AgentProfile)>,
+}
+
+impl AgentRun {
+    /// Run `agent` with its default review prompt
+    pub fn agent(agent: impl Into<String>) -> Self {
+        AgentRun { agent: agent.into(), profile: None }
+    }
+
+    /// Run the profile's agent with the profile's prompt
+    pub fn profile(name: impl Into<String>, profile: AgentProfile) -> Self {
+        AgentRun {
+            agent: profile.agent.clone(),
+            profile: Some((name.into(), profile)),
+        }
+    }
+
+    /// Review prompt for `pr_number`, before context is added
+    fn prompt(&self, pr_number: u32) -> Result<String> {
+        match &self.profile {
+            Some((_, profile)) => Ok(profile.prompt.replace("{pr}", &pr_number.to_string())),
+            None => AgentManager::default_prompt(&self.agent, pr_number),
+        }
+    }
+
+    /// Name shown in progress output, e.g. `claude (security)`
+    fn label(&self) -> String {
+        match &self.profile {
+            Some((name, _)) => format!("{} ({})", self.agent, name),
+            None => self.agent.clone(),
+        }
+    }
+}
+
+/// An agent command line, as shown by `--dry-run`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invocation {
+    /// Agent name, with the profile if any
+    pub label: String,
+    pub program: String,
+    /// Arguments before the prompt
+    pub flags: Vec<String>,
+    pub working_dir: PathBuf,
+    /// Names of the variables set from `agents.env`
+    pub env: Vec<String>,
+    /// Fully rendered prompt, passed as the last argument
+    pub prompt: String,
+}
+
 pub struct AgentManager {
     config: AgentsConfig,
     runner: Arc<dyn CommandRunner + Send + Sync>,
+    context: Option<String>,
+    logs: Option<ReviewLogs>,
 }
 
 impl AgentManager {
@@ -23,15 +86,35 @@ impl AgentManager {
         config: AgentsConfig,
         runner: Arc<dyn CommandRunner + Send + Sync>,
     ) -> Self {
-        AgentManager { config, runner }
+        AgentManager {
+            config,
+            runner,
+            context: None,
+            logs: None,
+        }
     }
 
-    /// Create a new AgentManager with default LiveCommandRunner
+    /// Create a new AgentManager with the default command runner
     pub fn new(config: AgentsConfig) -> Self {
-        Self::new_with_runner(config, Arc::new(LiveCommandRunner))
+        Self::new_with_runner(config, command::default_runner())
+    }
+
+    /// Add background information (such as test results) to every agent prompt
+    pub fn with_context(mut self, context: impl Into<String>) -> Self {
+        self.context = Some(context.into());
+        self
+    }
+
+    /// Write agent logs and transcripts to a review's log directory
+    pub fn with_logs(mut self, logs: ReviewLogs) -> Self {
+        self.logs = Some(logs);
+        self
     }
 
     /// Run agents for PR review
+    ///
+    /// After a Ctrl-C (see [`crate::core::cancel`]) this returns the analyses
+    /// of the agents that finished before it.
     pub async fn run_review(
         &self,
         pr_number: u32,
@@ -48,22 +131,231 @@ impl AgentManager {
             &self.config.default_agents
         };
 
-        if self.config.parallel {
-            self.run_parallel(agents, pr_number, worktree_path).await
+        self.run_agents(pr_number, worktree_path, agents).await
+    }
+
+    /// Run the given agents for PR review, regardless of the configured lists
+    pub async fn run_agents(
+        &self,
+        pr_number: u32,
+        worktree_path: &Path,
+        agents: &[String],
+    ) -> Result<Vec<ReviewAnalysis>> {
+        let runs = agents.iter().map(AgentRun::agent).collect();
+        self.run(pr_number, worktree_path, runs).await
+    }
+
+    /// Run each profile's agent with the profile's prompt
+    ///
+    /// The analyses record the profile name, and findings the agent left in
+    /// [`Category::Other`] get the profile's category.
+    pub async fn run_profiles(
+        &self,
+        pr_number: u32,
+        worktree_path: &Path,
+        profiles: &[(String, AgentProfile)],
+    ) -> Result<Vec<ReviewAnalysis>> {
+        let runs = profiles
+            .iter()
+            .map(|(name, profile)| AgentRun::profile(name, profile.clone()))
+            .collect();
+        self.run(pr_number, worktree_path, runs).await
+    }
+
+    async fn run(&self, pr_number: u32, worktree_path: &Path, runs: Vec<AgentRun>) -> Result<Vec<ReviewAnalysis>> {
+        if !self.config.enabled {
+            return Ok(Vec::new());
+        }
+        let policy = FindingPolicy::from_config(&self.config)?;
+
+        let runs = if self.config.preflight {
+            let mut agents: Vec<String> = runs.iter().map(|run| run.agent.clone()).collect();
+            agents.dedup();
+            let available = self.available_agents(&agents);
+            let runs: Vec<AgentRun> = runs.into_iter().filter(|run| available.contains(&run.agent)).collect();
+            if runs.is_empty() {
+                return Ok(Vec::new());
+            }
+            runs
+        } else {
+            runs
+        };
+
+        let logs = self.logs.clone();
+
+        let mut analyses = if self.config.parallel {
+            self.run_parallel(&runs, pr_number, worktree_path, logs).await?
         } else {
-            self.run_sequential(agents, pr_number, worktree_path).await
+            self.run_sequential(&runs, pr_number, worktree_path, logs).await?
+        };
+        for analysis in &mut analyses {
+            policy.apply(analysis);
+        }
+        Ok(analyses)
+    }
+
+    /// How each run would invoke its agent, without running anything
+    ///
+    /// Used by `--dry-run` to debug prompts. Environment values are not
+    /// resolved; only the names of the configured variables are listed.
+    pub fn invocations(&self, pr_number: u32, worktree_path: &Path, runs: &[AgentRun]) -> Result<Vec<Invocation>> {
+        runs.iter()
+            .map(|run| {
+                let prompt = Self::with_prompt_context(run.prompt(pr_number)?, self.context.as_deref());
+                let args = Self::agent_args(&run.agent, &prompt, self.config.structured_output)?;
+                // The prompt is always the last argument
+                let flags = args[..args.len() - 1]
+                    .iter()
+                    .map(|arg| arg.to_string_lossy().into_owned())
+                    .collect();
+                let env = self
+                    .config
+                    .env
+                    .get(&run.agent)
+                    .map(|vars| vars.keys().cloned().collect())
+                    .unwrap_or_default();
+
+                Ok(Invocation {
+                    label: run.label(),
+                    program: run.agent.clone(),
+                    flags,
+                    working_dir: worktree_path.to_path_buf(),
+                    env,
+                    prompt,
+                })
+            })
+            .collect()
+    }
+
+    /// Check that each agent is installed and logged in, see [`preflight`]
+    ///
+    /// Agents are checked with the environment they would be run with.
+    pub fn check_agents(&self, agents: &[String]) -> Vec<Check> {
+        let environment = preflight::Environment::current();
+        agents
+            .iter()
+            .map(|agent| {
+                let env = Self::agent_env(&self.config, agent).unwrap_or_default();
+                preflight::check_agent(agent, &env, &environment)
+            })
+            .collect()
+    }
+
+    /// Keep the agents that pass the preflight check
+    ///
+    /// Skipped agents are reported with what to do about them.
+    fn available_agents(&self, agents: &[String]) -> Vec<String> {
+        let mut available = Vec::new();
+        for check in self.check_agents(agents) {
+            match &check.status {
+                CheckStatus::Ok(_) => {}
+                CheckStatus::Warning(detail) => {
+                    tracing::debug!("Preflight warning for {}: {}", check.name, detail);
+                }
+                CheckStatus::Failed(detail) => {
+                    eprintln!("✗ Skipping {}: {}", check.name, detail);
+                    if let Some(hint) = &check.hint {
+                        eprintln!("  {}", hint);
+                    }
+                }
+            }
+            if check.is_available() {
+                available.push(check.name);
+            }
+        }
+
+        if available.len() < agents.len() {
+            eprintln!("  (set agents.preflight: false in chaba.yaml to skip this check)");
+        }
+        if available.is_empty() {
+            eprintln!("✗ No agents available; skipping analysis");
+        }
+        available
+    }
+
+    /// Ask the first default agent how to resolve merge or rebase conflicts
+    ///
+    /// The agent only proposes resolutions; it is asked not to edit files.
+    /// Returns the agent name and its raw output.
+    pub async fn propose_conflict_resolution(
+        &self,
+        worktree_path: &Path,
+        operation: &str,
+        conflicted_files: &[String],
+    ) -> Result<(String, String)> {
+        let agent = self.config.default_agents.first().ok_or_else(|| {
+            ChabaError::ConfigError("No default agents configured".to_string())
+        })?;
+
+        let prompt = format!(
+            "このワークツリーでは {} のコンフリクトが解消されていません。以下のファイルのコンフリクトマーカーを確認し、両方の変更の意図を踏まえた解消方法をファイルごとに提案してください。ファイルは編集せず、提案のみを出力してください。\n\n{}",
+            operation,
+            conflicted_files
+                .iter()
+                .map(|file| format!("- {}", file))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+        let output = self.run_prompt(agent, prompt, worktree_path).await?;
+
+        Ok((agent.clone(), output))
+    }
+
+    /// Ask `agent` a follow-up question about a review
+    ///
+    /// The stored findings should be given as context with [`Self::with_context`].
+    /// Returns the agent's answer.
+    pub async fn ask(&self, agent: &str, pr_number: u32, worktree_path: &Path, question: &str) -> Result<String> {
+        let prompt = format!(
+            "PR #{} のレビュー結果について追加の質問があります。必要に応じてワークツリーのコードを確認し、質問に回答してください。ファイルは編集しないでください。\n\n質問: {}",
+            pr_number, question
+        );
+
+        self.run_prompt(agent, prompt, worktree_path).await
+    }
+
+    /// Run `agent` once with `prompt` (plus context) and return its output
+    async fn run_prompt(&self, agent: &str, prompt: String, worktree_path: &Path) -> Result<String> {
+        let prompt = Self::with_prompt_context(prompt, self.context.as_deref());
+
+        let runner = Self::with_env(self.runner.clone(), Self::agent_env(&self.config, agent)?);
+        let args = Self::agent_args(agent, &prompt, self.config.structured_output)?;
+
+        let output = tokio::time::timeout(
+            Duration::from_secs(self.config.timeout),
+            runner.run(agent, &args, worktree_path),
+        )
+        .await
+        .map_err(|_| {
+            ChabaError::Other(anyhow::anyhow!(
+                "Agent {} timed out after {} seconds",
+                agent,
+                self.config.timeout
+            ))
+        })??;
+
+        if !output.status.success() {
+            return Err(ChabaError::AgentExecutionError {
+                agent: agent.to_string(),
+                stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            });
         }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(agent_output::unwrap(agent, &stdout).map_or_else(|| stdout.to_string(), |envelope| envelope.text))
     }
 
     /// Run agents in parallel
     async fn run_parallel(
         &self,
-        agents: &[String],
+        runs: &[AgentRun],
         pr_number: u32,
         worktree_path: &Path,
+        logs: Option<ReviewLogs>,
     ) -> Result<Vec<ReviewAnalysis>> {
         // Create progress bar
-        let pb = ProgressBar::new(agents.len() as u64);
+        let pb = ProgressBar::new(runs.len() as u64);
         pb.set_style(
             ProgressStyle::default_bar()
                 .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
@@ -74,30 +366,41 @@ impl AgentManager {
 
         let mut tasks = Vec::new();
 
-        for agent in agents {
-            let agent = agent.clone();
+        for run in runs {
+            let run = run.clone();
             let worktree_path = worktree_path.to_path_buf();
             let timeout = self.config.timeout;
+            let structured = self.config.structured_output;
             let runner = self.runner.clone();
+            let logs = logs.clone();
+            let context = self.context.clone();
+            let env = Self::agent_env(&self.config, &run.agent);
+            let pb = pb.clone();
 
             tasks.push(tokio::spawn(async move {
-                Self::run_single_agent(&agent, pr_number, &worktree_path, timeout, runner).await
+                let runner = Self::with_env(runner, env?);
+                Self::run_single_agent(&run, pr_number, &worktree_path, context.as_deref(), structured, timeout, runner, logs.as_ref(), &pb).await
             }));
         }
 
         let results = futures::future::join_all(tasks).await;
+        let cancelled = cancel::is_cancelled();
 
         let mut analyses = Vec::new();
         let mut errors = Vec::new();
 
         for (idx, result) in results.into_iter().enumerate() {
-            let agent_name = &agents[idx];
+            let agent_name = &runs[idx].label();
             match result {
                 Ok(Ok(analysis)) => {
                     pb.set_message(format!("✓ {} completed", agent_name));
                     tracing::info!("✓ {} completed analysis", agent_name);
                     analyses.push(analysis);
                 }
+                Ok(Err(e)) if cancelled => {
+                    tracing::info!("⏹ {} cancelled: {}", agent_name, e);
+                    errors.push((agent_name.clone(), e.to_string()));
+                }
                 Ok(Err(e)) => {
                     pb.set_message(format!("✗ {} failed", agent_name));
                     tracing::warn!("✗ {} failed: {}", agent_name, e);
@@ -112,12 +415,14 @@ impl AgentManager {
             pb.inc(1);
         }
 
-        if !errors.is_empty() && analyses.is_empty() {
+        if cancelled {
+            pb.finish_with_message(format!("⏹  Interrupted: {} agent(s) completed", analyses.len()));
+        } else if !errors.is_empty() && analyses.is_empty() {
             // All agents failed
             pb.finish_with_message("⚠️  All agents failed");
             tracing::error!("⚠️  All agents failed to complete analysis");
             tracing::error!("Review the errors above and check:");
-            tracing::error!("  - Agent CLI tools are installed (claude, codex, gemini)");
+            tracing::error!("  - Agent CLI tools are installed (claude, codex, gemini, cursor-agent, copilot)");
             tracing::error!("  - Network connectivity");
             tracing::error!("  - Agent timeout setting (current: {}s)", self.config.timeout);
         } else if !errors.is_empty() {
@@ -135,12 +440,13 @@ impl AgentManager {
     /// Run agents sequentially
     async fn run_sequential(
         &self,
-        agents: &[String],
+        runs: &[AgentRun],
         pr_number: u32,
         worktree_path: &Path,
+        logs: Option<ReviewLogs>,
     ) -> Result<Vec<ReviewAnalysis>> {
         // Create progress bar
-        let pb = ProgressBar::new(agents.len() as u64);
+        let pb = ProgressBar::new(runs.len() as u64);
         pb.set_style(
             ProgressStyle::default_bar()
                 .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
@@ -151,15 +457,30 @@ impl AgentManager {
         let mut analyses = Vec::new();
         let mut errors = Vec::new();
 
-        for agent in agents {
+        for run in runs {
+            if cancel::is_cancelled() {
+                break;
+            }
+            let agent = &run.label();
             pb.set_message(format!("Running {} analysis...", agent));
             tracing::info!("Running {} analysis...", agent);
-            match Self::run_single_agent(agent, pr_number, worktree_path, self.config.timeout, self.runner.clone()).await {
+            let result = match Self::agent_env(&self.config, &run.agent) {
+                Ok(env) => {
+                    let runner = Self::with_env(self.runner.clone(), env);
+                    Self::run_single_agent(run, pr_number, worktree_path, self.context.as_deref(), self.config.structured_output, self.config.timeout, runner, logs.as_ref(), &pb).await
+                }
+                Err(e) => Err(e),
+            };
+            match result {
                 Ok(analysis) => {
                     pb.set_message(format!("✓ {} completed", agent));
                     tracing::info!("✓ {} completed", agent);
                     analyses.push(analysis);
                 }
+                Err(e) if cancel::is_cancelled() => {
+                    tracing::info!("⏹ {} cancelled: {}", agent, e);
+                    errors.push((agent.clone(), e.to_string()));
+                }
                 Err(e) => {
                     pb.set_message(format!("✗ {} failed", agent));
                     tracing::warn!("✗ {} failed: {}", agent, e);
@@ -169,7 +490,9 @@ impl AgentManager {
             pb.inc(1);
         }
 
-        if !errors.is_empty() && analyses.is_empty() {
+        if cancel::is_cancelled() {
+            pb.finish_with_message(format!("⏹  Interrupted: {} agent(s) completed", analyses.len()));
+        } else if !errors.is_empty() && analyses.is_empty() {
             pb.finish_with_message("⚠️  All agents failed");
             tracing::error!("⚠️  All agents failed to complete analysis");
             tracing::error!("Check agent CLI tool installations and network connectivity");
@@ -183,184 +506,419 @@ impl AgentManager {
         Ok(analyses)
     }
 
+    /// Environment for an agent: configured variables plus its keychain token
+    fn agent_env(config: &AgentsConfig, agent: &str) -> Result<CommandEnv> {
+        let mut env = secrets::resolve_agent_env(config, agent)?;
+        secrets::inject_keychain_token(&mut env, agent);
+        Ok(env)
+    }
+
+    /// Wrap `runner` so `env` is applied only to this agent's process
+    fn with_env(
+        runner: Arc<dyn CommandRunner + Send + Sync>,
+        env: CommandEnv,
+    ) -> Arc<dyn CommandRunner + Send + Sync> {
+        if env.is_empty() {
+            runner
+        } else {
+            Arc::new(EnvCommandRunner::new(runner, env))
+        }
+    }
+
     /// Run a single agent with timeout
+    ///
+    /// The agent's output is shown above the progress bar and appended to its
+    /// log file (when `logs` is given) line by line while it runs.
+    #[allow(clippy::too_many_arguments)]
     async fn run_single_agent(
-        agent: &str,
+        run: &AgentRun,
         pr_number: u32,
         worktree_path: &Path,
+        context: Option<&str>,
+        structured: bool,
         timeout_secs: u64,
         runner: Arc<dyn CommandRunner + Send + Sync>,
+        logs: Option<&ReviewLogs>,
+        pb: &ProgressBar,
     ) -> Result<ReviewAnalysis> {
         let timeout = Duration::from_secs(timeout_secs);
+        let agent = run.agent.as_str();
+        let started = chrono::Local::now();
+
+        if let Some(logs) = logs {
+            events::record_in(logs, EventKind::AgentStarted, run.label());
+        }
+        let stream = logs.and_then(|logs| {
+            logs.stream(&logs.agent_log(agent), &format!("{} started", agent))
+                .map_err(|e| tracing::warn!("Failed to write agent log: {}", e))
+                .ok()
+        });
+        let on_line = |line: &str| {
+            if !line.trim().is_empty() {
+                pb.println(format!("  {} │ {}", agent, line));
+            }
+            if let Some(stream) = &stream {
+                stream.line(line);
+            }
+        };
 
         let result = tokio::time::timeout(
             timeout,
-            Self::execute_agent(agent, pr_number, worktree_path, runner),
+            Self::execute_agent(run, pr_number, worktree_path, context, structured, runner, &on_line),
         )
         .await;
 
-        match result {
-            Ok(Ok(analysis)) => Ok(analysis),
-            Ok(Err(e)) => Err(e),
+        let result = match result {
+            Ok(Ok((mut analysis, output))) => {
+                if let Some(logs) = logs {
+                    let stdout = String::from_utf8_lossy(&output.stdout);
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    analysis.transcript = Self::write_transcript(logs, run, started, &stdout, &stderr).await;
+                }
+                Ok(analysis)
+            }
+            Ok(Err(e)) => {
+                if let (Some(logs), ChabaError::AgentExecutionError { stdout, stderr, .. }) = (logs, &e) {
+                    Self::write_transcript(logs, run, started, stdout, stderr).await;
+                }
+                Err(e)
+            }
             Err(_) => Err(ChabaError::Other(anyhow::anyhow!(
                 "Agent {} timed out after {} seconds",
                 agent,
                 timeout_secs
             ))),
+        };
+
+        if let Some(logs) = logs {
+            Self::write_agent_log(logs, agent, &result).await;
+            let outcome = match &result {
+                Ok(analysis) => format!("{} finding(s)", analysis.findings.len()),
+                Err(_) => "failed".to_string(),
+            };
+            events::record_in(logs, EventKind::AgentFinished, format!("{}: {}", run.label(), outcome));
+        }
+
+        result
+    }
+
+    /// Save the full stdout/stderr of an agent run, returning the file written
+    async fn write_transcript(
+        logs: &ReviewLogs,
+        run: &AgentRun,
+        started: chrono::DateTime<chrono::Local>,
+        stdout: &str,
+        stderr: &str,
+    ) -> Option<PathBuf> {
+        let name = match &run.profile {
+            Some((profile, _)) => format!("{}-{}", run.agent, profile),
+            None => run.agent.clone(),
+        };
+        let path = logs.transcript_log(&name, started);
+
+        let mut body = stdout.to_string();
+        if !stderr.is_empty() {
+            if !body.is_empty() && !body.ends_with('\n') {
+                body.push('\n');
+            }
+            body.push_str("--> stderr\n");
+            body.push_str(stderr);
+        }
+
+        match logs.append(&path, &format!("{} transcript", run.label()), &body).await {
+            Ok(()) => Some(path),
+            Err(e) => {
+                tracing::warn!("Failed to write agent transcript: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Append the outcome of an agent run to its log file
+    ///
+    /// Stdout was already streamed into the log while the agent ran.
+    async fn write_agent_log(logs: &ReviewLogs, agent: &str, result: &Result<ReviewAnalysis>) {
+        let (title, body) = match result {
+            Ok(_) => (format!("{} completed", agent), String::new()),
+            Err(ChabaError::AgentExecutionError { stderr, .. }) => (format!("{} failed", agent), stderr.clone()),
+            Err(e) => (format!("{} failed", agent), e.to_string()),
+        };
+
+        if let Err(e) = logs.append(&logs.agent_log(agent), &title, &body).await {
+            tracing::warn!("Failed to write agent log: {}", e);
         }
     }
 
     /// Execute a specific agent
     async fn execute_agent(
-        agent: &str,
+        run: &AgentRun,
         pr_number: u32,
         worktree_path: &Path,
+        context: Option<&str>,
+        structured: bool,
         runner: Arc<dyn CommandRunner + Send + Sync>,
-    ) -> Result<ReviewAnalysis> {
+        on_line: &LineSink<'_>,
+    ) -> Result<(ReviewAnalysis, Output)> {
+        let agent = run.agent.as_str();
         let mut analysis = ReviewAnalysis::new(agent.to_string());
 
-        match agent {
-            "claude" => Self::run_claude(pr_number, worktree_path, &mut analysis, runner).await?,
-            "codex" => Self::run_codex(pr_number, worktree_path, &mut analysis, runner).await?,
-            "gemini" => Self::run_gemini(pr_number, worktree_path, &mut analysis, runner).await?,
+        if let Some((name, profile)) = &run.profile {
+            let prompt = Self::with_prompt_context(run.prompt(pr_number)?, context);
+            let output =
+                Self::run_prompted(agent, &prompt, worktree_path, structured, &mut analysis, runner, on_line).await?;
+
+            analysis.profile = Some(name.clone());
+            if let Some(category) = profile.category.as_deref().and_then(|category| category.parse().ok()) {
+                analysis.recategorize_other(category);
+            }
+            return Ok((analysis, output));
+        }
+
+        let output = match agent {
+            "claude" => Self::run_claude(pr_number, worktree_path, context, structured, &mut analysis, runner, on_line).await?,
+            "codex" => Self::run_codex(pr_number, worktree_path, context, structured, &mut analysis, runner, on_line).await?,
+            "gemini" => Self::run_gemini(pr_number, worktree_path, context, structured, &mut analysis, runner, on_line).await?,
+            "cursor-agent" => Self::run_cursor_agent(pr_number, worktree_path, context, structured, &mut analysis, runner, on_line).await?,
+            "copilot" => Self::run_copilot(pr_number, worktree_path, context, structured, &mut analysis, runner, on_line).await?,
             _ => {
                 return Err(ChabaError::ConfigError(format!(
                     "Unknown agent: {}",
                     agent
                 )))
             }
-        }
+        };
 
-        Ok(analysis)
+        Ok((analysis, output))
     }
 
     /// Run Claude Code agent
     async fn run_claude(
         pr_number: u32,
         worktree_path: &Path,
+        context: Option<&str>,
+        structured: bool,
         analysis: &mut ReviewAnalysis,
         runner: Arc<dyn CommandRunner + Send + Sync>,
-    ) -> Result<()> {
-        let prompt = format!(
-            "PR #{} のコードレビューを実施してください。品質、セキュリティ、パフォーマンスの観点から分析し、改善点を指摘してください。",
-            pr_number
-        );
-
-        let output = runner
-            .run(
-                "claude",
-                &[
-                    "--model".as_ref(),
-                    "sonnet".as_ref(),
-                    "--yes".as_ref(),
-                    OsStr::new(&prompt),
-                ],
-                worktree_path,
-            )
-            .await?;
-
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            Self::parse_output(&stdout, analysis);
-            Ok(())
-        } else {
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-            Err(ChabaError::AgentExecutionError {
-                agent: "claude".to_string(),
-                stdout,
-                stderr,
-            })
-        }
+        on_line: &LineSink<'_>,
+    ) -> Result<Output> {
+        let prompt = Self::with_prompt_context(Self::default_prompt("claude", pr_number)?, context);
+        Self::run_prompted("claude", &prompt, worktree_path, structured, analysis, runner, on_line).await
     }
 
     /// Run Codex agent
     async fn run_codex(
         pr_number: u32,
         worktree_path: &Path,
+        context: Option<&str>,
+        structured: bool,
         analysis: &mut ReviewAnalysis,
         runner: Arc<dyn CommandRunner + Send + Sync>,
-    ) -> Result<()> {
-        let prompt = format!(
-            "このPR #{}のコードをレビューしてください。バグ、セキュリティ問題、ベストプラクティス違反を指摘してください。",
-            pr_number
-        );
-
-        let output = runner
-            .run(
-                "codex",
-                &[
-                    "exec".as_ref(),
-                    "--full-auto".as_ref(),
-                    "--sandbox".as_ref(),
-                    "read-only".as_ref(),
-                    OsStr::new(&prompt),
-                ],
-                worktree_path,
-            )
-            .await?;
-
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            Self::parse_output(&stdout, analysis);
-            Ok(())
-        } else {
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-            Err(ChabaError::AgentExecutionError {
-                agent: "codex".to_string(),
-                stdout,
-                stderr,
-            })
-        }
+        on_line: &LineSink<'_>,
+    ) -> Result<Output> {
+        let prompt = Self::with_prompt_context(Self::default_prompt("codex", pr_number)?, context);
+        Self::run_prompted("codex", &prompt, worktree_path, structured, analysis, runner, on_line).await
     }
 
     /// Run Gemini agent
     async fn run_gemini(
         pr_number: u32,
         worktree_path: &Path,
+        context: Option<&str>,
+        structured: bool,
         analysis: &mut ReviewAnalysis,
         runner: Arc<dyn CommandRunner + Send + Sync>,
-    ) -> Result<()> {
-        let prompt = format!(
-            "このPR #{}を戦略的視点からレビューしてください。アーキテクチャ、設計パターン、拡張性について分析してください。",
-            pr_number
-        );
+        on_line: &LineSink<'_>,
+    ) -> Result<Output> {
+        let prompt = Self::with_prompt_context(Self::default_prompt("gemini", pr_number)?, context);
+        Self::run_prompted("gemini", &prompt, worktree_path, structured, analysis, runner, on_line).await
+    }
+
+    /// Run Cursor Agent
+    async fn run_cursor_agent(
+        pr_number: u32,
+        worktree_path: &Path,
+        context: Option<&str>,
+        structured: bool,
+        analysis: &mut ReviewAnalysis,
+        runner: Arc<dyn CommandRunner + Send + Sync>,
+        on_line: &LineSink<'_>,
+    ) -> Result<Output> {
+        let prompt = Self::with_prompt_context(Self::default_prompt("cursor-agent", pr_number)?, context);
+        Self::run_prompted("cursor-agent", &prompt, worktree_path, structured, analysis, runner, on_line).await
+    }
+
+    /// Run GitHub Copilot CLI
+    async fn run_copilot(
+        pr_number: u32,
+        worktree_path: &Path,
+        context: Option<&str>,
+        structured: bool,
+        analysis: &mut ReviewAnalysis,
+        runner: Arc<dyn CommandRunner + Send + Sync>,
+        on_line: &LineSink<'_>,
+    ) -> Result<Output> {
+        let prompt = Self::with_prompt_context(Self::default_prompt("copilot", pr_number)?, context);
+        Self::run_prompted("copilot", &prompt, worktree_path, structured, analysis, runner, on_line).await
+    }
 
+    /// Review prompt an agent gets when it runs without a profile
+    fn default_prompt(agent: &str, pr_number: u32) -> Result<String> {
+        let prompt = match agent {
+            "claude" => format!(
+                "PR #{} のコードレビューを実施してください。品質、セキュリティ、パフォーマンスの観点から分析し、改善点を指摘してください。",
+                pr_number
+            ),
+            "codex" => format!(
+                "このPR #{}のコードをレビューしてください。バグ、セキュリティ問題、ベストプラクティス違反を指摘してください。",
+                pr_number
+            ),
+            "gemini" => format!(
+                "このPR #{}を戦略的視点からレビューしてください。アーキテクチャ、設計パターン、拡張性について分析してください。",
+                pr_number
+            ),
+            "cursor-agent" => format!(
+                "PR #{} のコードレビューを実施してください。変更内容の正しさ、エッジケース、保守性の観点から問題点を指摘してください。ファイルは編集しないでください。",
+                pr_number
+            ),
+            "copilot" => format!(
+                "PR #{} のコードレビューを実施してください。バグ、セキュリティ問題、テストの不足を指摘してください。ファイルは編集しないでください。",
+                pr_number
+            ),
+            _ => return Err(ChabaError::ConfigError(format!("Unknown agent: {}", agent))),
+        };
+        Ok(prompt)
+    }
+
+    /// Run `agent` with a complete review prompt and read its findings
+    ///
+    /// Returns the agent's raw output for its transcript.
+    async fn run_prompted(
+        agent: &str,
+        prompt: &str,
+        worktree_path: &Path,
+        structured: bool,
+        analysis: &mut ReviewAnalysis,
+        runner: Arc<dyn CommandRunner + Send + Sync>,
+        on_line: &LineSink<'_>,
+    ) -> Result<Output> {
+        let args = Self::agent_args(agent, prompt, structured)?;
+        let show = |line: &str| Self::show_line(agent, line, on_line);
         let output = runner
-            .run(
-                "gemini",
-                &[
-                    "-m".as_ref(),
-                    "gemini-2.5-pro".as_ref(),
-                    "-s".as_ref(),
-                    "-y".as_ref(),
-                    "-p".as_ref(),
-                    OsStr::new(&prompt),
-                ],
-                worktree_path,
-            )
+            .run_streaming(agent, &args, worktree_path, &CommandEnv::default(), &show)
             .await?;
 
         if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            Self::parse_output(&stdout, analysis);
-            Ok(())
+            Self::read_output(agent, &output, analysis);
+            Ok(output)
         } else {
             let stdout = String::from_utf8_lossy(&output.stdout).to_string();
             let stderr = String::from_utf8_lossy(&output.stderr).to_string();
             Err(ChabaError::AgentExecutionError {
-                agent: "gemini".to_string(),
+                agent: agent.to_string(),
                 stdout,
                 stderr,
             })
         }
     }
 
+    /// Whether chaba knows how to run `agent`
+    pub fn is_supported(agent: &str) -> bool {
+        Self::agent_args(agent, "", false).is_ok()
+    }
+
+    /// Command-line arguments for running `agent` non-interactively with `prompt`
+    ///
+    /// With `structured`, the agent is asked for its native JSON output (see
+    /// [`agent_output::unwrap`]).
+    fn agent_args<'a>(agent: &str, prompt: &'a str, structured: bool) -> Result<Vec<&'a OsStr>> {
+        let flags: &[&'static str] = match (agent, structured) {
+            ("claude", false) => &["--model", "sonnet", "--yes"],
+            ("claude", true) => &["--model", "sonnet", "--yes", "--output-format", "json"],
+            ("codex", false) => &["exec", "--full-auto", "--sandbox", "read-only"],
+            ("codex", true) => &["exec", "--full-auto", "--sandbox", "read-only", "--json"],
+            ("gemini", false) => &["-m", "gemini-2.5-pro", "-s", "-y", "-p"],
+            ("gemini", true) => &["-m", "gemini-2.5-pro", "-s", "-y", "--output-format", "json", "-p"],
+            ("cursor-agent", false) => &["--print", "--output-format", "text"],
+            ("cursor-agent", true) => &["--print", "--output-format", "json"],
+            // Copilot has no JSON output; its usage summary is read from the text
+            ("copilot", _) => &["--allow-all-tools", "--deny-tool", "write", "-p"],
+            _ => {
+                return Err(ChabaError::ConfigError(format!(
+                    "Unknown agent: {}",
+                    agent
+                )))
+            }
+        };
+
+        let mut args: Vec<&OsStr> = flags.iter().map(OsStr::new).collect();
+        args.push(OsStr::new(prompt));
+        Ok(args)
+    }
+
+    /// Show a line of agent output, or the text inside it for JSON output
+    fn show_line(agent: &str, line: &str, on_line: &LineSink<'_>) {
+        match agent_output::unwrap(agent, line) {
+            Some(envelope) => envelope.text.lines().for_each(on_line),
+            None => on_line(line),
+        }
+    }
+
+    /// Read findings and usage from a successful agent run
+    ///
+    /// Native JSON output is unwrapped first; plain text (older CLIs, or
+    /// `agents.structured_output: false`) is parsed as is.
+    fn read_output(agent: &str, output: &Output, analysis: &mut ReviewAnalysis) {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        match agent_output::unwrap(agent, &stdout) {
+            Some(envelope) => {
+                Self::parse_output(&envelope.text, analysis);
+                analysis.usage = envelope.usage;
+            }
+            None => {
+                Self::parse_output(&stdout, analysis);
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                match agent {
+                    "codex" => analysis.usage = Self::codex_usage(&stdout, &stderr),
+                    // Copilot prints its usage summary on stderr when stdout is a pipe
+                    "copilot" => analysis.usage = agent_output::unwrap(agent, &stderr).and_then(|envelope| envelope.usage),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Read the `tokens used` total that `codex exec` prints when it finishes
+    ///
+    /// Codex prints the count after the label or on the next line, on stdout
+    /// or stderr depending on the version. It doesn't report a cost.
+    fn codex_usage(stdout: &str, stderr: &str) -> Option<Usage> {
+        const LABEL: &str = "tokens used";
+        let lines: Vec<&str> = stdout.lines().chain(stderr.lines()).map(str::trim).collect();
+        let (position, start) = lines
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(position, line)| Some((position, line.find(LABEL)?)))?;
+        let count = match lines[position][start + LABEL.len()..].trim_start_matches(':').trim() {
+            "" => lines.get(position + 1)?,
+            count => count,
+        };
+        let tokens = count.replace(',', "").parse().ok()?;
+        Some(Usage { tokens, cost_usd: None })
+    }
+
+    /// Append review context to an agent prompt
+    fn with_prompt_context(prompt: String, context: Option<&str>) -> String {
+        match context {
+            Some(context) => format!("{}\n\n{}", prompt, context),
+            None => prompt,
+        }
+    }
+
     /// Parse agent output and extract findings
     ///
     /// This function attempts to parse the output in the following order:
-    /// 1. JSON format (structured output from agents)
+    /// 1. JSON format (fenced blocks first, then JSON anywhere in the output)
     /// 2. Enhanced pattern matching (keywords and severity indicators)
     /// 3. Fallback to basic info finding
     fn parse_output(output: &str, analysis: &mut ReviewAnalysis) {
@@ -387,105 +945,22 @@ impl AgentManager {
         }
     }
 
-    /// Try to parse output as JSON
+    /// Try to read structured findings, see [`agent_output`]
     fn try_parse_json(output: &str, analysis: &mut ReviewAnalysis) -> bool {
-        use serde_json::Value;
-
-        // Try to find JSON object or array in the output
-        // Look for JSON between common delimiters
-        let json_str = if let Some(start) = output.find('{') {
-            &output[start..]
-        } else if let Some(start) = output.find('[') {
-            &output[start..]
-        } else {
-            return false;
-        };
-
-        // Try to parse as JSON
-        let parsed: Value = match serde_json::from_str(json_str) {
-            Ok(v) => v,
-            Err(_) => {
-                // Try to extract JSON more carefully
-                for line in output.lines() {
-                    if line.trim().starts_with('{') || line.trim().starts_with('[') {
-                        if let Ok(v) = serde_json::from_str(line.trim()) {
-                            v
-                        } else {
-                            continue;
-                        }
-                    } else {
-                        continue;
-                    }
-                }
-                return false;
-            }
-        };
-
-        // Extract findings from JSON
-        let findings = if let Some(findings_array) = parsed.get("findings").and_then(|v| v.as_array()) {
-            findings_array
-        } else if parsed.is_array() {
-            parsed.as_array().unwrap()
-        } else {
+        let Some(structured) = agent_output::extract(output) else {
             return false;
         };
 
-        for finding_value in findings {
-            if let Some(finding) = Self::parse_json_finding(finding_value) {
-                analysis.add_finding(finding);
-            }
+        for finding in structured.findings {
+            analysis.add_finding(finding);
         }
-
-        // Extract score if present
-        if let Some(score) = parsed.get("score").and_then(|v| v.as_f64()) {
-            analysis.set_score(score as f32);
+        if let Some(score) = structured.score {
+            analysis.set_score(score);
         }
 
         !analysis.findings.is_empty()
     }
 
-    /// Parse a single finding from JSON value
-    fn parse_json_finding(value: &serde_json::Value) -> Option<Finding> {
-        let severity_str = value.get("severity")?.as_str()?;
-        let severity = match severity_str.to_lowercase().as_str() {
-            "critical" | "重大" => Severity::Critical,
-            "high" | "高" => Severity::High,
-            "medium" | "中" => Severity::Medium,
-            "low" | "低" => Severity::Low,
-            _ => Severity::Info,
-        };
-
-        let category_str = value.get("category").and_then(|v| v.as_str()).unwrap_or("other");
-        let category = match category_str.to_lowercase().as_str() {
-            "security" | "セキュリティ" => Category::Security,
-            "performance" | "パフォーマンス" => Category::Performance,
-            "bug" | "バグ" | "codequality" | "code_quality" => Category::CodeQuality,
-            "bestpractice" | "best_practice" | "ベストプラクティス" => Category::BestPractice,
-            "architecture" | "アーキテクチャ" => Category::Architecture,
-            "testing" | "テスト" => Category::Testing,
-            "documentation" | "ドキュメント" => Category::Documentation,
-            _ => Category::Other,
-        };
-
-        let title = value.get("title")?.as_str()?.to_string();
-        let description = value.get("description").and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-        let mut finding = Finding::new(severity, category, title, description);
-
-        // Optional fields
-        if let Some(file) = value.get("file").and_then(|v| v.as_str()) {
-            finding = finding.with_file(file.to_string());
-        }
-        if let Some(line) = value.get("line").and_then(|v| v.as_u64()) {
-            finding = finding.with_line(line as u32);
-        }
-        if let Some(suggestion) = value.get("suggestion").and_then(|v| v.as_str()) {
-            finding = finding.with_suggestion(suggestion.to_string());
-        }
-
-        Some(finding)
-    }
-
     /// Enhanced pattern matching for text output
     fn parse_with_patterns(output: &str, analysis: &mut ReviewAnalysis) {
         let lines: Vec<&str> = output.lines().collect();
@@ -538,8 +1013,8 @@ impl AgentManager {
 mod tests {
     use super::*;
     use async_trait::async_trait;
-    use std::os::unix::process::ExitStatusExt;
-    use std::process::{ExitStatus, Output};
+    use crate::core::platform::exit_status;
+    use std::process::Output;
     use std::sync::Mutex;
 
     // Simple mock implementation for testing
@@ -583,7 +1058,7 @@ mod tests {
     // Helper to create a successful output
     fn success_output(stdout: &str) -> Output {
         Output {
-            status: ExitStatus::from_raw(0),
+            status: exit_status(0),
             stdout: stdout.as_bytes().to_vec(),
             stderr: vec![],
         }
@@ -592,7 +1067,7 @@ mod tests {
     // Helper to create a failed output
     fn error_output(stderr: &str) -> Output {
         Output {
-            status: ExitStatus::from_raw(1),
+            status: exit_status(1),
             stdout: vec![],
             stderr: stderr.as_bytes().to_vec(),
         }
@@ -656,7 +1131,7 @@ mod tests {
 
         let mut analysis = ReviewAnalysis::new("claude".to_string());
         let result =
-            AgentManager::run_claude(123, Path::new("/tmp"), &mut analysis, mock_runner.clone())
+            AgentManager::run_claude(123, Path::new("/tmp"), None, true, &mut analysis, mock_runner.clone(), &|_| {})
                 .await;
 
         assert!(result.is_ok());
@@ -667,6 +1142,116 @@ mod tests {
         assert_eq!(calls[0].0, "claude");
         assert!(calls[0].1.contains(&"--model".to_string()));
         assert!(calls[0].1.contains(&"sonnet".to_string()));
+        assert!(calls[0].1.contains(&"--output-format".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_run_claude_includes_context() {
+        let mock_runner = Arc::new(TestCommandRunner::new(success_output("Looks good")));
+
+        let mut analysis = ReviewAnalysis::new("claude".to_string());
+        AgentManager::run_claude(
+            123,
+            Path::new("/tmp"),
+            Some("The test suite (`cargo test`) fails on this branch."),
+            false,
+            &mut analysis,
+            mock_runner.clone(),
+            &|_| {},
+        )
+        .await
+        .unwrap();
+
+        let calls = mock_runner.get_calls();
+        assert!(!calls[0].1.contains(&"--output-format".to_string()));
+        let prompt = calls[0].1.last().unwrap();
+        assert!(prompt.contains("PR #123"));
+        assert!(prompt.ends_with("The test suite (`cargo test`) fails on this branch."));
+    }
+
+    #[tokio::test]
+    async fn test_run_claude_records_usage() {
+        let envelope = serde_json::json!({
+            "type": "result",
+            "result": "Warning: Code quality issue",
+            "total_cost_usd": 0.042,
+            "usage": { "input_tokens": 1200, "cache_read_input_tokens": 3000, "output_tokens": 800 },
+        });
+        let mock_runner = Arc::new(TestCommandRunner::new(success_output(&envelope.to_string())));
+
+        let mut analysis = ReviewAnalysis::new("claude".to_string());
+        AgentManager::run_claude(123, Path::new("/tmp"), None, true, &mut analysis, mock_runner, &|_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(analysis.usage, Some(Usage { tokens: 5000, cost_usd: Some(0.042) }));
+        assert_eq!(analysis.raw_output.as_deref(), Some("Warning: Code quality issue"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_agent_with_profile() {
+        let mock_runner = Arc::new(TestCommandRunner::new(success_output("Looks good")));
+        let run = AgentRun::profile(
+            "perf",
+            AgentProfile {
+                agent: "codex".to_string(),
+                prompt: "PR #{pr} のパフォーマンスだけを見てください。".to_string(),
+                category: Some("performance".to_string()),
+            },
+        );
+
+        let (analysis, _) =
+            AgentManager::execute_agent(&run, 42, Path::new("/tmp"), Some("Context"), false, mock_runner.clone(), &|_| {})
+                .await
+                .unwrap();
+
+        let calls = mock_runner.get_calls();
+        assert_eq!(calls[0].0, "codex");
+        assert_eq!(calls[0].1.last().unwrap(), "PR #42 のパフォーマンスだけを見てください。\n\nContext");
+        assert_eq!(analysis.profile.as_deref(), Some("perf"));
+        // The fallback finding is uncategorized, so it takes the profile's category
+        assert_eq!(analysis.findings[0].category, Category::Performance);
+    }
+
+    #[test]
+    fn test_invocations() {
+        let mut config = AgentsConfig::default();
+        config.env.insert(
+            "claude".to_string(),
+            [("ANTHROPIC_API_KEY".to_string(), crate::config::EnvValue::Literal("secret".to_string()))].into(),
+        );
+        let manager = AgentManager::new(config).with_context("Context");
+
+        let invocations = manager
+            .invocations(7, Path::new("/tmp/pr-7"), &[AgentRun::agent("claude"), AgentRun::agent("gemini")])
+            .unwrap();
+
+        let claude = &invocations[0];
+        assert_eq!(claude.program, "claude");
+        assert_eq!(claude.flags, ["--model", "sonnet", "--yes", "--output-format", "json"]);
+        assert_eq!(claude.working_dir, Path::new("/tmp/pr-7"));
+        assert_eq!(claude.env, ["ANTHROPIC_API_KEY"]);
+        assert!(claude.prompt.starts_with("PR #7 "));
+        assert!(claude.prompt.ends_with("\n\nContext"));
+        assert_eq!(invocations[1].flags.last().map(String::as_str), Some("-p"));
+        assert!(manager.invocations(7, Path::new("/tmp"), &[AgentRun::agent("aider")]).is_err());
+    }
+
+    #[test]
+    fn test_codex_usage() {
+        assert_eq!(
+            AgentManager::codex_usage("No issues found\ntokens used: 12,345\n", ""),
+            Some(Usage { tokens: 12345, cost_usd: None })
+        );
+        assert_eq!(
+            AgentManager::codex_usage("No issues found", "[2025-01-01T09:00:00] tokens used: 987\n"),
+            Some(Usage { tokens: 987, cost_usd: None })
+        );
+        assert_eq!(
+            AgentManager::codex_usage("", "tokens used\n4,321\n"),
+            Some(Usage { tokens: 4321, cost_usd: None })
+        );
+        assert_eq!(AgentManager::codex_usage("No issues found", ""), None);
     }
 
     #[tokio::test]
@@ -676,7 +1261,7 @@ mod tests {
 
         let mut analysis = ReviewAnalysis::new("claude".to_string());
         let result =
-            AgentManager::run_claude(123, Path::new("/tmp"), &mut analysis, mock_runner).await;
+            AgentManager::run_claude(123, Path::new("/tmp"), None, true, &mut analysis, mock_runner, &|_| {}).await;
 
         assert!(result.is_err());
         match result.unwrap_err() {
@@ -708,4 +1293,27 @@ mod tests {
         // Verify runner was injected (Arc count should be 2: manager + test)
         assert_eq!(Arc::strong_count(&manager.runner), 2);
     }
+
+    #[tokio::test]
+    async fn test_propose_conflict_resolution() {
+        let config = AgentsConfig {
+            default_agents: vec!["codex".to_string()],
+            ..AgentsConfig::default()
+        };
+        let mock_runner = Arc::new(TestCommandRunner::new(success_output("Keep both imports")));
+        let manager = AgentManager::new_with_runner(config, mock_runner.clone());
+
+        let (agent, output) = manager
+            .propose_conflict_resolution(Path::new("/tmp"), "rebase", &["src/lib.rs".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(agent, "codex");
+        assert_eq!(output, "Keep both imports");
+
+        let calls = mock_runner.get_calls();
+        assert_eq!(calls[0].0, "codex");
+        assert_eq!(calls[0].1[..4], ["exec", "--full-auto", "--sandbox", "read-only"]);
+        assert!(calls[0].1.last().unwrap().contains("- src/lib.rs"));
+    }
 }
@@ -0,0 +1,577 @@
+//! Structured findings in agent output
+//!
+//! Agent CLIs are run with their native JSON output when
+//! `agents.structured_output` is on; [`unwrap`] takes the agent's text and
+//! reported usage out of that envelope.
+//!
+//! Agents are asked for JSON findings, but they wrap it in prose, put it in fenced
+//! code blocks, or print several objects (one per finding, or a status line
+//! before the report). JSON is looked for in fenced blocks first and, when
+//! there are none, anywhere in the output by balancing brackets. Each
+//! candidate is read with a lenient schema that also accepts the other field
+//! names agents commonly use.
+
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::core::review_analysis::{Category, Finding, Severity, Usage};
+
+/// The text of an agent's reply and the usage it reported
+#[derive(Debug, Clone, PartialEq)]
+pub struct Envelope {
+    pub text: String,
+    pub usage: Option<Usage>,
+}
+
+/// Unwrap the native JSON output of `agent`
+///
+/// - `claude --output-format json`: one result object with the reply, tokens, and cost
+/// - `codex exec --json`: one event per line; replies are `agent_message` items
+///   and tokens come with `turn.completed`
+/// - `gemini --output-format json`: one object with the reply and per-model token stats
+/// - `cursor-agent --output-format json`: one result object with the reply, no usage
+/// - `copilot -p`: plain text followed by a usage summary, which is cut off
+///
+/// Returns `None` for plain text output, so it can be parsed as is. A single
+/// line of codex events unwraps too, which is how streamed output is shown.
+pub fn unwrap(agent: &str, output: &str) -> Option<Envelope> {
+    match agent {
+        "claude" => unwrap_claude(output),
+        "codex" => unwrap_codex(output),
+        "gemini" => unwrap_gemini(output),
+        "cursor-agent" => unwrap_cursor_agent(output),
+        "copilot" => unwrap_copilot(output),
+        _ => None,
+    }
+}
+
+fn unwrap_claude(output: &str) -> Option<Envelope> {
+    let value: Value = serde_json::from_str(output.trim()).ok()?;
+    if value.get("type")?.as_str()? != "result" {
+        return None;
+    }
+    let text = value.get("result")?.as_str()?.to_string();
+
+    let tokens = ["input_tokens", "cache_creation_input_tokens", "cache_read_input_tokens", "output_tokens"]
+        .iter()
+        .filter_map(|field| value.get("usage")?.get(field)?.as_u64())
+        .sum();
+    let cost_usd = value
+        .get("total_cost_usd")
+        .or_else(|| value.get("cost_usd"))
+        .and_then(Value::as_f64);
+    Some(Envelope {
+        text,
+        usage: Some(Usage { tokens, cost_usd }),
+    })
+}
+
+fn unwrap_codex(output: &str) -> Option<Envelope> {
+    let mut messages = Vec::new();
+    let mut usage: Option<Usage> = None;
+    let mut events = 0;
+
+    for line in output.lines().filter(|line| !line.trim().is_empty()) {
+        let event: Value = serde_json::from_str(line.trim()).ok()?;
+        let kind = event.get("type")?.as_str()?;
+        events += 1;
+
+        match kind {
+            "item.completed" => {
+                let item = event.get("item")?;
+                if item.get("type").and_then(Value::as_str) == Some("agent_message") {
+                    messages.push(item.get("text").and_then(Value::as_str).unwrap_or_default().to_string());
+                }
+            }
+            "turn.completed" => {
+                // Cached input tokens are part of input_tokens
+                let tokens = ["input_tokens", "output_tokens"]
+                    .iter()
+                    .filter_map(|field| event.get("usage")?.get(field)?.as_u64())
+                    .sum();
+                usage.get_or_insert_with(Usage::default).add(&Usage { tokens, cost_usd: None });
+            }
+            _ => {}
+        }
+    }
+
+    (events > 0).then(|| Envelope {
+        text: messages.join("\n"),
+        usage,
+    })
+}
+
+fn unwrap_gemini(output: &str) -> Option<Envelope> {
+    let value: Value = serde_json::from_str(output.trim()).ok()?;
+    let text = value.get("response")?.as_str()?.to_string();
+
+    let usage = value
+        .get("stats")
+        .and_then(|stats| stats.get("models"))
+        .and_then(Value::as_object)
+        .map(|models| Usage {
+            tokens: models
+                .values()
+                .filter_map(|model| model.get("tokens")?.get("total")?.as_u64())
+                .sum(),
+            cost_usd: None,
+        });
+    Some(Envelope { text, usage })
+}
+
+fn unwrap_cursor_agent(output: &str) -> Option<Envelope> {
+    let value: Value = serde_json::from_str(output.trim()).ok()?;
+    if value.get("type")?.as_str()? != "result" {
+        return None;
+    }
+    let text = value.get("result")?.as_str()?.to_string();
+    Some(Envelope { text, usage: None })
+}
+
+/// First line of the summary `copilot` prints after its reply
+const COPILOT_SUMMARY: &str = "Total usage est:";
+
+/// Split Copilot's reply from its usage summary
+///
+/// The summary lists tokens per model, e.g.
+/// `claude-sonnet-4.5  24.1k input, 1.2k output, 0 cache read, ...`.
+fn unwrap_copilot(output: &str) -> Option<Envelope> {
+    let lines: Vec<&str> = output.lines().collect();
+    let start = lines.iter().position(|line| line.trim_start().starts_with(COPILOT_SUMMARY))?;
+
+    let tokens: Option<u64> = lines[start..]
+        .iter()
+        .flat_map(|line| line.split(','))
+        .filter_map(|part| {
+            let mut words = part.split_whitespace().rev();
+            match (words.next(), words.next()) {
+                (Some("input" | "output"), Some(count)) => parse_token_count(count),
+                _ => None,
+            }
+        })
+        .reduce(|total, count| total + count);
+
+    Some(Envelope {
+        text: lines[..start].join("\n"),
+        usage: tokens.map(|tokens| Usage { tokens, cost_usd: None }),
+    })
+}
+
+/// Read a token count such as `812`, `24.1k`, or `1.2m`
+fn parse_token_count(count: &str) -> Option<u64> {
+    let count = count.to_ascii_lowercase();
+    let (number, scale) = match count.chars().last()? {
+        'k' => (&count[..count.len() - 1], 1_000.0),
+        'm' => (&count[..count.len() - 1], 1_000_000.0),
+        _ => (count.as_str(), 1.0),
+    };
+    let number: f64 = number.parse().ok()?;
+    Some((number * scale).round() as u64)
+}
+
+/// Findings and score read from an agent's output
+#[derive(Debug, Default)]
+pub struct StructuredOutput {
+    pub findings: Vec<Finding>,
+    pub score: Option<f32>,
+}
+
+/// Other names agents use for the fields of the schema
+///
+/// When an object has both a field and its alias, the field wins.
+const ALIASES: &[(&str, &[&str])] = &[
+    ("findings", &["issues", "problems", "comments", "results"]),
+    ("score", &["overall_score", "rating"]),
+    ("severity", &["level", "priority"]),
+    ("category", &["type", "kind"]),
+    ("title", &["summary", "issue", "message"]),
+    ("description", &["details", "explanation", "body"]),
+    ("file", &["path", "filename", "file_path"]),
+    ("line", &["line_number", "lineno", "start_line"]),
+    ("suggestion", &["fix", "recommendation"]),
+    ("diff", &["patch"]),
+    ("confidence", &["certainty", "likelihood"]),
+];
+
+/// Rename aliased keys in every object of `value` to their schema names
+fn normalize(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (field, aliases) in ALIASES {
+                for alias in *aliases {
+                    if let Some(aliased) = map.remove(*alias) {
+                        map.entry(*field).or_insert(aliased);
+                    }
+                }
+            }
+            map.values_mut().for_each(normalize);
+        }
+        Value::Array(items) => items.iter_mut().for_each(normalize),
+        _ => {}
+    }
+}
+
+/// A JSON value agents print for a review
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Payload {
+    Report(Report),
+    List(Vec<FindingPayload>),
+    Single(FindingPayload),
+}
+
+/// `{"findings": [...], "score": 4.2}`
+#[derive(Debug, Deserialize)]
+struct Report {
+    findings: Vec<FindingPayload>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    score: Option<f64>,
+}
+
+/// One finding; only `severity` and `title` are required
+#[derive(Debug, Deserialize)]
+struct FindingPayload {
+    #[serde(default, deserialize_with = "lenient_string")]
+    severity: Option<String>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    category: Option<String>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    title: Option<String>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    description: Option<String>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    file: Option<String>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    line: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    suggestion: Option<String>,
+    #[serde(default, deserialize_with = "lenient_string")]
+    diff: Option<String>,
+    #[serde(default, deserialize_with = "lenient_number")]
+    confidence: Option<f64>,
+}
+
+/// A string, or nothing for any other type
+fn lenient_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(value) => Some(value),
+        _ => None,
+    })
+}
+
+/// A number, also when written as a string (`"42"`)
+fn lenient_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Number(value) => value.as_f64(),
+        Value::String(value) => value.trim().parse().ok(),
+        _ => None,
+    })
+}
+
+impl FindingPayload {
+    fn into_finding(self) -> Option<Finding> {
+        let severity = parse_severity(&self.severity?);
+        let category = parse_category(self.category.as_deref().unwrap_or("other"));
+        let mut finding = Finding::new(severity, category, self.title?, self.description.unwrap_or_default());
+
+        if let Some(file) = self.file {
+            finding = finding.with_file(file);
+        }
+        if let Some(line) = self.line.filter(|line| *line >= 1.0) {
+            finding = finding.with_line(line as u32);
+        }
+        if let Some(suggestion) = self.suggestion {
+            finding = finding.with_suggestion(suggestion);
+        }
+        if let Some(diff) = self.diff {
+            finding = finding.with_diff(diff);
+        }
+        // Some agents answer in percent (`85`) instead of a fraction (`0.85`)
+        if let Some(confidence) = self.confidence.filter(|confidence| *confidence >= 0.0) {
+            let confidence = if confidence > 1.0 { confidence / 100.0 } else { confidence };
+            finding = finding.with_confidence(confidence as f32);
+        }
+        Some(finding)
+    }
+}
+
+fn parse_severity(severity: &str) -> Severity {
+    match severity.trim().to_lowercase().as_str() {
+        "critical" | "blocker" | "重大" => Severity::Critical,
+        "high" | "major" | "error" | "高" => Severity::High,
+        "medium" | "moderate" | "warning" | "中" => Severity::Medium,
+        "low" | "minor" | "低" => Severity::Low,
+        _ => Severity::Info,
+    }
+}
+
+fn parse_category(category: &str) -> Category {
+    match category.trim().to_lowercase().replace([' ', '-'], "_").as_str() {
+        "security" | "セキュリティ" => Category::Security,
+        "performance" | "パフォーマンス" => Category::Performance,
+        "bug" | "バグ" | "codequality" | "code_quality" => Category::CodeQuality,
+        "bestpractice" | "best_practice" | "ベストプラクティス" => Category::BestPractice,
+        "architecture" | "アーキテクチャ" => Category::Architecture,
+        "testing" | "tests" | "テスト" => Category::Testing,
+        "documentation" | "docs" | "ドキュメント" => Category::Documentation,
+        _ => Category::Other,
+    }
+}
+
+/// Read findings from agent output
+///
+/// Findings from every JSON candidate are combined; the first score wins.
+/// Returns `None` when no candidate looks like a findings payload.
+pub fn extract(output: &str) -> Option<StructuredOutput> {
+    let fenced = fenced_blocks(output);
+    let candidates = if fenced.is_empty() {
+        json_values(output)
+    } else {
+        fenced.iter().flat_map(|block| json_values(block)).collect()
+    };
+
+    let mut result = StructuredOutput::default();
+    let mut found = false;
+    for candidate in candidates {
+        let Ok(mut value) = serde_json::from_str::<Value>(candidate) else {
+            continue;
+        };
+        normalize(&mut value);
+        let Ok(payload) = serde_json::from_value::<Payload>(value) else {
+            continue;
+        };
+        let findings = match payload {
+            Payload::Report(report) => {
+                result.score = result.score.or(report.score.map(|score| score as f32));
+                report.findings
+            }
+            Payload::List(findings) => findings,
+            Payload::Single(finding) => vec![finding],
+        };
+        let findings: Vec<Finding> = findings.into_iter().filter_map(FindingPayload::into_finding).collect();
+        found |= !findings.is_empty() || result.score.is_some();
+        result.findings.extend(findings);
+    }
+
+    found.then_some(result)
+}
+
+/// Contents of the fenced code blocks (```` ``` ````, optionally tagged
+/// `json`) in `output`
+fn fenced_blocks(output: &str) -> Vec<&str> {
+    let mut blocks = Vec::new();
+    let mut rest = output;
+    while let Some(start) = rest.find("```") {
+        let after_fence = &rest[start + 3..];
+        // The info string (`json`, `jsonc`, ...) runs to the end of the line
+        let Some(newline) = after_fence.find('\n') else {
+            break;
+        };
+        let info = after_fence[..newline].trim().to_lowercase();
+        let body = &after_fence[newline + 1..];
+        let Some(end) = body.find("```") else {
+            break;
+        };
+        if info.is_empty() || info.starts_with("json") {
+            blocks.push(&body[..end]);
+        }
+        rest = &body[end + 3..];
+    }
+    blocks
+}
+
+/// Balanced JSON objects and arrays in `text`, outermost first
+///
+/// Brackets inside JSON strings are skipped. A bracket in prose that never
+/// closes doesn't hide the JSON after it: scanning restarts after it.
+fn json_values(text: &str) -> Vec<&str> {
+    let bytes = text.as_bytes();
+    let mut values = Vec::new();
+    let mut position = 0;
+
+    while let Some(offset) = bytes[position..].iter().position(|byte| matches!(byte, b'{' | b'[')) {
+        let start = position + offset;
+        match balanced_end(&bytes[start..]) {
+            Some(length) if serde_json::from_str::<Value>(&text[start..start + length]).is_ok() => {
+                values.push(&text[start..start + length]);
+                position = start + length;
+            }
+            _ => position = start + 1,
+        }
+    }
+    values
+}
+
+/// Length of the bracketed value at the start of `bytes`, if it closes
+fn balanced_end(bytes: &[u8]) -> Option<usize> {
+    let mut stack = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (index, &byte) in bytes.iter().enumerate() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' => stack.push(b'}'),
+            b'[' => stack.push(b']'),
+            b'}' | b']' => {
+                if stack.pop() != Some(byte) {
+                    return None;
+                }
+                if stack.is_empty() {
+                    return Some(index + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fenced_block_with_prose_around() {
+        let output = r#"Here is my review {see below}:
+
+```json
+{
+  "findings": [{"severity": "high", "category": "security", "title": "Token in URL", "description": "Use a header"}],
+  "score": 3.5
+}
+```
+
+Let me know if you have {questions}."#;
+
+        let result = extract(output).unwrap();
+        assert_eq!(result.findings.len(), 1);
+        assert_eq!(result.findings[0].title, "Token in URL");
+        assert_eq!(result.score, Some(3.5));
+    }
+
+    #[test]
+    fn test_multiple_objects_and_trailing_prose() {
+        let output = r#"{"status": "analyzing"}
+{"severity": "medium", "title": "Unbounded cache", "file": "src/cache.rs", "line": "42"}
+{"severity": "low", "title": "Typo in log message: {user]"}
+Done. Two issues found."#;
+
+        let result = extract(output).unwrap();
+        let titles: Vec<&str> = result.findings.iter().map(|finding| finding.title.as_str()).collect();
+        assert_eq!(titles, ["Unbounded cache", "Typo in log message: {user]"]);
+        assert_eq!(result.findings[0].line, Some(42));
+    }
+
+    #[test]
+    fn test_confidence() {
+        let output = r#"[
+  {"severity": "high", "title": "Race on shutdown", "confidence": 0.9},
+  {"severity": "low", "title": "Maybe unused", "certainty": "40"},
+  {"severity": "low", "title": "No confidence given"}
+]"#;
+
+        let result = extract(output).unwrap();
+        let confidences: Vec<Option<f32>> = result.findings.iter().map(|finding| finding.confidence).collect();
+        assert_eq!(confidences, [Some(0.9), Some(0.4), None]);
+    }
+
+    #[test]
+    fn test_lenient_field_aliases() {
+        let output = r#"Summary: [2 issues]
+{"issues": [
+  {"level": "Critical", "type": "best practice", "summary": "SQL built by concatenation", "details": "Use bind parameters", "path": "db.rs", "line_number": 7, "fix": "Use query!"},
+  {"priority": "minor", "message": "No title field is fine when message is set"},
+  {"description": "Dropped: no severity or title"}
+], "rating": 4}"#;
+
+        let result = extract(output).unwrap();
+        assert_eq!(result.findings.len(), 2);
+        let finding = &result.findings[0];
+        assert_eq!(finding.severity, Severity::Critical);
+        assert_eq!(finding.category, Category::BestPractice);
+        assert_eq!(finding.file.as_deref(), Some("db.rs"));
+        assert_eq!(finding.suggestion.as_deref(), Some("Use query!"));
+        assert_eq!(result.findings[1].severity, Severity::Low);
+        assert_eq!(result.score, Some(4.0));
+    }
+
+    #[test]
+    fn test_no_findings_payload() {
+        assert!(extract("Looks good to me.").is_none());
+        assert!(extract("Config is {\"debug\": true} [unclosed").is_none());
+        assert!(extract("```rust\nfn main() { let v = [1, 2]; }\n```").is_none());
+    }
+
+    #[test]
+    fn test_json_values_skip_brackets_in_strings() {
+        let values = json_values(r#"prefix {"a": "}{[", "b": [1, {"c": 2}]} suffix [3]"#);
+        assert_eq!(values, [r#"{"a": "}{[", "b": [1, {"c": 2}]}"#, "[3]"]);
+    }
+
+    #[test]
+    fn test_unwrap_claude() {
+        let output = r#"{"type":"result","subtype":"success","result":"No issues","total_cost_usd":0.03,"usage":{"input_tokens":10,"cache_read_input_tokens":90,"output_tokens":20}}"#;
+        let envelope = unwrap("claude", output).unwrap();
+        assert_eq!(envelope.text, "No issues");
+        assert_eq!(envelope.usage, Some(Usage { tokens: 120, cost_usd: Some(0.03) }));
+
+        assert!(unwrap("claude", "No issues").is_none());
+        assert!(unwrap("claude", r#"{"findings": []}"#).is_none());
+    }
+
+    #[test]
+    fn test_unwrap_codex_events() {
+        let output = r#"{"type":"thread.started","thread_id":"t1"}
+{"type":"item.completed","item":{"id":"i0","type":"reasoning","text":"Reading the diff"}}
+{"type":"item.completed","item":{"id":"i1","type":"agent_message","text":"{\"findings\": []}"}}
+{"type":"turn.completed","usage":{"input_tokens":1500,"cached_input_tokens":1000,"output_tokens":300}}
+"#;
+        let envelope = unwrap("codex", output).unwrap();
+        assert_eq!(envelope.text, r#"{"findings": []}"#);
+        assert_eq!(envelope.usage, Some(Usage { tokens: 1800, cost_usd: None }));
+
+        // Streamed lines unwrap one at a time
+        assert_eq!(unwrap("codex", r#"{"type":"thread.started"}"#).unwrap().text, "");
+        assert!(unwrap("codex", "No issues found\ntokens used: 12").is_none());
+    }
+
+    #[test]
+    fn test_unwrap_gemini() {
+        let output = r#"{
+  "response": "Looks fine",
+  "stats": {"models": {"gemini-2.5-pro": {"tokens": {"prompt": 900, "candidates": 100, "total": 1000}}}}
+}"#;
+        let envelope = unwrap("gemini", output).unwrap();
+        assert_eq!(envelope.text, "Looks fine");
+        assert_eq!(envelope.usage, Some(Usage { tokens: 1000, cost_usd: None }));
+        assert!(unwrap("gemini", r#"{"error": {"message": "quota"}}"#).is_none());
+    }
+
+    #[test]
+    fn test_unwrap_cursor_agent() {
+        let output = r#"{"type":"result","subtype":"success","is_error":false,"duration_ms":5400,"result":"No issues","session_id":"abc"}"#;
+        let envelope = unwrap("cursor-agent", output).unwrap();
+        assert_eq!(envelope.text, "No issues");
+        assert_eq!(envelope.usage, None);
+        assert!(unwrap("cursor-agent", "No issues").is_none());
+    }
+
+    #[test]
+    fn test_unwrap_copilot() {
+        let output = "Found one issue:\n- Token is logged\n\nTotal usage est:       1 Premium request\nTotal duration (API):  12.3s\nUsage by model:\n    claude-sonnet-4.5    24.1k input, 812 output, 0 cache read, 0 cache write (Est. 1 Premium request)\n";
+        let envelope = unwrap("copilot", output).unwrap();
+        assert_eq!(envelope.text, "Found one issue:\n- Token is logged\n");
+        assert_eq!(envelope.usage, Some(Usage { tokens: 24_912, cost_usd: None }));
+        assert!(unwrap("copilot", "Found one issue").is_none());
+    }
+}
@@ -0,0 +1,507 @@
+//! Anonymized diffs for cloud agents
+//!
+//! With `agents.anonymize.enabled`, agents review a copy of the review's diff
+//! in which identifiers, file paths, email addresses, and string literals are
+//! replaced by placeholders. The same name always gets the same placeholder,
+//! so the code still reads consistently. The [`Mapping`] from placeholders
+//! back to the real names stays on this machine and is used to restore the
+//! findings the agents report.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::config::AnonymizeConfig;
+use crate::core::review_analysis::ReviewAnalysis;
+use crate::error::{ChabaError, Result};
+
+/// Where the mapping is saved, relative to the worktree (in `.chaba/`, excluded from git)
+pub const MAPPING_FILE: &str = ".chaba/anonymize-map.json";
+
+/// Name of the diff file in the agents' scratch directory
+pub const DIFF_FILE: &str = "changes.diff";
+
+/// Domain used for placeholder email addresses
+const EMAIL_DOMAIN: &str = "example.invalid";
+
+/// Prompt context telling agents what they are looking at
+pub const PROMPT_CONTEXT: &str = "The changes under review are in `changes.diff` in the current directory; the source code is not available. The diff is anonymized: identifiers are replaced by `id_N`, file paths by `file_N`, string literals by `str_N`, and email addresses by `user_N@example.invalid`. Review the logic as shown and use these placeholders, with the file and line from the diff, in your findings.";
+
+/// Keywords, primitive types, and common library names of popular languages,
+/// which carry no private information and keep the diff readable
+const KEYWORDS: &[&str] = &[
+    // Rust
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn",
+    "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self",
+    "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while", "bool", "char", "str",
+    "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize", "f32", "f64", "String",
+    "Vec", "Option", "Some", "None", "Result", "Ok", "Err", "Box", "Rc", "Arc", "HashMap", "HashSet", "BTreeMap",
+    "derive", "unwrap", "expect", "clone", "println", "format", "panic", "todo", "unimplemented", "assert",
+    "assert_eq", "test", "cfg",
+    // JavaScript / TypeScript
+    "function", "var", "new", "this", "class", "extends", "export", "import", "from", "default", "null",
+    "undefined", "typeof", "instanceof", "try", "catch", "finally", "throw", "switch", "case", "do", "delete",
+    "void", "yield", "interface", "implements", "private", "protected", "public", "readonly", "any", "unknown",
+    "never", "string", "number", "boolean", "object", "Promise", "console", "log", "require", "module", "exports",
+    "of", "get", "set",
+    // Python
+    "def", "elif", "except", "lambda", "None", "True", "False", "and", "or", "not", "is", "pass", "raise", "with",
+    "global", "nonlocal", "assert", "del", "print", "len", "range", "int", "float", "dict", "list", "tuple",
+    "__init__", "__name__", "__main__",
+    // Go / Java / C-family
+    "func", "package", "go", "defer", "chan", "select", "map", "range", "nil", "err", "error", "fallthrough",
+    "goto", "byte", "rune", "final", "abstract", "throws", "synchronized", "volatile", "transient", "native",
+    "boolean", "long", "short", "double", "signed", "unsigned", "sizeof", "struct", "typedef", "union",
+    "include", "define", "ifdef", "ifndef", "endif", "main",
+];
+
+/// Placeholders mapped back to the real names they replace
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Mapping {
+    pub placeholders: BTreeMap<String, String>,
+}
+
+impl Mapping {
+    /// Number of distinct names replaced
+    pub fn len(&self) -> usize {
+        self.placeholders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.placeholders.is_empty()
+    }
+
+    /// Replace the placeholders in `text` with the names they stand for
+    pub fn restore(&self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut out = String::with_capacity(text.len());
+        let mut i = 0;
+
+        while i < chars.len() {
+            if !is_ident_char(chars[i]) || (i > 0 && is_ident_char(chars[i - 1])) {
+                out.push(chars[i]);
+                i += 1;
+                continue;
+            }
+
+            let end = ident_end(&chars, i);
+            let word: String = chars[i..end].iter().collect();
+            match self.restore_at(&word, &chars, end) {
+                Some((original, consumed)) => {
+                    out.push_str(original);
+                    i = end + consumed;
+                }
+                None => {
+                    out.push_str(&word);
+                    i = end;
+                }
+            }
+        }
+
+        out
+    }
+
+    /// The original for the placeholder `word` (followed by `chars[end..]`)
+    /// and how many characters after `word` it also covers
+    fn restore_at(&self, word: &str, chars: &[char], end: usize) -> Option<(&String, usize)> {
+        let rest: String = chars[end..].iter().take(EMAIL_DOMAIN.len() + 1).collect();
+        if word.starts_with("user_") && rest == format!("@{}", EMAIL_DOMAIN) {
+            let key = format!("{}{}", word, rest);
+            return self.placeholders.get(&key).map(|original| (original, rest.len()));
+        }
+        // File placeholders keep the extension: file_3.rs
+        if word.starts_with("file_") && chars.get(end) == Some(&'.') {
+            let ext_end = ident_end(chars, end + 1);
+            let key: String = chars[end..ext_end].iter().collect::<String>();
+            if let Some(original) = self.placeholders.get(&format!("{}{}", word, key)) {
+                return Some((original, ext_end - end));
+            }
+        }
+        self.placeholders.get(word).map(|original| (original, 0))
+    }
+
+    /// Restore the real names in an agent's findings and raw output
+    ///
+    /// Finding IDs are recomputed from the restored file and title, so they
+    /// match the IDs of a non-anonymized run.
+    pub fn restore_analysis(&self, analysis: &mut ReviewAnalysis) {
+        for finding in &mut analysis.findings {
+            finding.title = self.restore(&finding.title);
+            finding.description = self.restore(&finding.description);
+            finding.file = finding.file.as_deref().map(|file| self.restore(file));
+            finding.suggestion = finding.suggestion.as_deref().map(|text| self.restore(text));
+            finding.diff = finding.diff.as_deref().map(|diff| self.restore(diff));
+            finding.id = finding.compute_id(&analysis.agent);
+        }
+        analysis.raw_output = analysis.raw_output.as_deref().map(|output| self.restore(output));
+    }
+
+    pub fn save(&self, worktree_path: &Path) -> Result<PathBuf> {
+        let path = worktree_path.join(MAPPING_FILE);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| ChabaError::Other(e.into()))?;
+        std::fs::write(&path, json)?;
+        Ok(path)
+    }
+}
+
+/// Kinds of names that get placeholders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+    Identifier,
+    Path,
+    Str,
+    Email,
+}
+
+/// Replaces names with placeholders, remembering each one
+struct Anonymizer<'a> {
+    config: &'a AnonymizeConfig,
+    keep: HashSet<&'a str>,
+    assigned: HashMap<(Kind, String), String>,
+    counts: HashMap<Kind, usize>,
+    mapping: Mapping,
+}
+
+impl<'a> Anonymizer<'a> {
+    fn new(config: &'a AnonymizeConfig) -> Self {
+        let keep = KEYWORDS
+            .iter()
+            .copied()
+            .chain(config.keep.iter().map(String::as_str))
+            .collect();
+        Anonymizer {
+            config,
+            keep,
+            assigned: HashMap::new(),
+            counts: HashMap::new(),
+            mapping: Mapping::default(),
+        }
+    }
+
+    fn placeholder(&mut self, kind: Kind, original: &str) -> String {
+        if let Some(placeholder) = self.assigned.get(&(kind, original.to_string())) {
+            return placeholder.clone();
+        }
+
+        let count = self.counts.entry(kind).or_default();
+        *count += 1;
+        let placeholder = match kind {
+            Kind::Identifier => format!("id_{}", count),
+            Kind::Str => format!("str_{}", count),
+            Kind::Email => format!("user_{}@{}", count, EMAIL_DOMAIN),
+            Kind::Path => match Path::new(original).extension().and_then(|ext| ext.to_str()) {
+                Some(ext) if ext.chars().all(is_ident_char) => format!("file_{}.{}", count, ext),
+                _ => format!("file_{}", count),
+            },
+        };
+
+        self.assigned.insert((kind, original.to_string()), placeholder.clone());
+        self.mapping.placeholders.insert(placeholder.clone(), original.to_string());
+        placeholder
+    }
+
+    fn path(&mut self, path: &str) -> String {
+        if !self.config.identifiers || path == "/dev/null" {
+            return path.to_string();
+        }
+        self.placeholder(Kind::Path, path)
+    }
+
+    /// Anonymize one line of a unified diff
+    ///
+    /// `in_header` tracks whether we are between `diff --git` and the first hunk.
+    fn diff_line(&mut self, line: &str, in_header: &mut bool) -> String {
+        if let Some(paths) = line.strip_prefix("diff --git a/") {
+            *in_header = true;
+            return match paths.split_once(" b/") {
+                Some((old, new)) => format!("diff --git a/{} b/{}", self.path(old), self.path(new)),
+                None => "diff --git".to_string(),
+            };
+        }
+        if let Some(hunk) = line.strip_prefix("@@") {
+            *in_header = false;
+            // Keep the line ranges, anonymize the function context after them
+            return match hunk.find("@@") {
+                Some(close) => {
+                    let (ranges, context) = line.split_at(close + 4);
+                    format!("{}{}", ranges, self.code(context))
+                }
+                None => line.to_string(),
+            };
+        }
+        if *in_header {
+            for prefix in ["--- a/", "+++ b/", "rename from ", "rename to ", "copy from ", "copy to "] {
+                if let Some(path) = line.strip_prefix(prefix) {
+                    return format!("{}{}", prefix, self.path(path));
+                }
+            }
+            if line.starts_with("Binary files ") {
+                return "Binary files differ".to_string();
+            }
+            // index, mode, and similarity lines, and `--- /dev/null`
+            return line.to_string();
+        }
+        if line.starts_with('\\') {
+            return line.to_string();
+        }
+        match line.chars().next() {
+            Some(marker @ ('+' | '-' | ' ')) => format!("{}{}", marker, self.code(&line[1..])),
+            _ => self.code(line),
+        }
+    }
+
+    /// Anonymize a line of source code
+    fn code(&mut self, line: &str) -> String {
+        let chars: Vec<char> = line.chars().collect();
+        let mut out = String::with_capacity(line.len());
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+            let starts_word = i == 0 || !is_ident_char(chars[i - 1]);
+
+            if self.config.emails && starts_word {
+                if let Some(end) = email_end(&chars, i) {
+                    let email: String = chars[i..end].iter().collect();
+                    out.push_str(&self.placeholder(Kind::Email, &email));
+                    i = end;
+                    continue;
+                }
+            }
+
+            if self.config.strings && is_string_start(&chars, i) {
+                if let Some(close) = string_end(&chars, i) {
+                    let content: String = chars[i + 1..close].iter().collect();
+                    out.push(c);
+                    if !content.is_empty() {
+                        out.push_str(&self.placeholder(Kind::Str, &content));
+                    }
+                    out.push(c);
+                    i = close + 1;
+                    continue;
+                }
+            }
+
+            if starts_word && is_ident_char(c) {
+                let end = ident_end(&chars, i);
+                let word: String = chars[i..end].iter().collect();
+                // A word after a lone quote is a Rust lifetime such as 'a or 'static
+                let lifetime = i > 0 && chars[i - 1] == '\'';
+                let keep = !self.config.identifiers
+                    || c.is_ascii_digit()
+                    || lifetime
+                    || self.keep.contains(word.as_str());
+                if keep {
+                    out.push_str(&word);
+                } else {
+                    out.push_str(&self.placeholder(Kind::Identifier, &word));
+                }
+                i = end;
+                continue;
+            }
+
+            out.push(c);
+            i += 1;
+        }
+
+        out
+    }
+}
+
+/// Anonymize a unified diff, returning it with the mapping to restore names
+pub fn anonymize(diff: &str, config: &AnonymizeConfig) -> (String, Mapping) {
+    let mut anonymizer = Anonymizer::new(config);
+    let mut in_header = false;
+    let mut out: Vec<String> = diff
+        .lines()
+        .map(|line| anonymizer.diff_line(line, &mut in_header))
+        .collect();
+    if diff.ends_with('\n') {
+        out.push(String::new());
+    }
+
+    (out.join("\n"), anonymizer.mapping)
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn ident_end(chars: &[char], start: usize) -> usize {
+    let mut end = start;
+    while end < chars.len() && is_ident_char(chars[end]) {
+        end += 1;
+    }
+    end
+}
+
+/// End of an email address starting at `start`, if one does
+fn email_end(chars: &[char], start: usize) -> Option<usize> {
+    let is_local = |c: char| c.is_ascii_alphanumeric() || "._%+-".contains(c);
+    let is_domain = |c: char| c.is_ascii_alphanumeric() || c == '.' || c == '-';
+
+    let mut at = start;
+    while at < chars.len() && is_local(chars[at]) {
+        at += 1;
+    }
+    if at == start || chars.get(at) != Some(&'@') {
+        return None;
+    }
+
+    let mut end = at + 1;
+    while end < chars.len() && is_domain(chars[end]) {
+        end += 1;
+    }
+    // A trailing dot ends the sentence, not the domain
+    while end > at + 1 && chars[end - 1] == '.' {
+        end -= 1;
+    }
+
+    let domain: String = chars[at + 1..end].iter().collect();
+    let tld = domain.rsplit_once('.').map(|(_, tld)| tld)?;
+    (tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic())).then_some(end)
+}
+
+/// Whether a string literal opens at `i`
+///
+/// Single quotes right after `&`, `<`, or a word are Rust lifetimes or
+/// apostrophes, not strings.
+fn is_string_start(chars: &[char], i: usize) -> bool {
+    match chars[i] {
+        '"' | '`' => true,
+        '\'' => !matches!(i.checked_sub(1).map(|p| chars[p]), Some(p) if p == '&' || p == '<' || is_ident_char(p)),
+        _ => false,
+    }
+}
+
+/// Index of the quote closing the string opened at `start`, on the same line
+fn string_end(chars: &[char], start: usize) -> Option<usize> {
+    let quote = chars[start];
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            c if c == quote => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::review_analysis::{Category, Finding, Severity};
+
+    const DIFF: &str = "\
+diff --git a/src/billing.rs b/src/billing.rs
+index 1111111..2222222 100644
+--- a/src/billing.rs
++++ b/src/billing.rs
+@@ -10,3 +10,4 @@ fn charge_customer(customer: &Customer) {
+     let total = customer.balance;
+-    notify(\"ops@acme-corp.com\", total);
++    notify(\"ops@acme-corp.com\", total);
++    audit_log(&customer.id, 'charged');
+";
+
+    fn config() -> AnonymizeConfig {
+        AnonymizeConfig {
+            enabled: true,
+            ..AnonymizeConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_anonymize_diff() {
+        let (diff, mapping) = anonymize(DIFF, &config());
+
+        assert_eq!(
+            diff,
+            "\
+diff --git a/file_1.rs b/file_1.rs
+index 1111111..2222222 100644
+--- a/file_1.rs
++++ b/file_1.rs
+@@ -10,3 +10,4 @@ fn id_1(id_2: &id_3) {
+     let id_4 = id_2.id_5;
+-    id_6(\"str_1\", id_4);
++    id_6(\"str_1\", id_4);
++    id_7(&id_2.id_8, 'str_2');
+"
+        );
+        assert_eq!(mapping.placeholders["file_1.rs"], "src/billing.rs");
+        assert_eq!(mapping.placeholders["str_1"], "ops@acme-corp.com");
+        assert_eq!(mapping.placeholders["id_1"], "charge_customer");
+        assert!(!diff.contains("acme"));
+    }
+
+    #[test]
+    fn test_anonymize_only_selected_kinds() {
+        let config = AnonymizeConfig {
+            enabled: true,
+            identifiers: false,
+            strings: false,
+            keep: Vec::new(),
+            emails: true,
+        };
+        let (diff, mapping) = anonymize(DIFF, &config);
+
+        assert!(diff.contains("--- a/src/billing.rs"));
+        assert!(diff.contains("notify(\"user_1@example.invalid\", total);"));
+        assert!(diff.contains("'charged'"));
+        assert_eq!(mapping.len(), 1);
+    }
+
+    #[test]
+    fn test_anonymize_keeps_lifetimes_and_kept_names() {
+        let config = AnonymizeConfig {
+            keep: vec!["serde".to_string()],
+            ..config()
+        };
+        let (diff, _) = anonymize("+fn parse<'a>(input: &'a str) -> serde::Result<u32> { 42 }\n", &config);
+
+        assert_eq!(diff, "+fn id_1<'a>(id_2: &'a str) -> serde::Result<u32> { 42 }\n");
+    }
+
+    #[test]
+    fn test_restore_analysis() {
+        let (_, mapping) = anonymize(DIFF, &config());
+        let mut analysis = ReviewAnalysis::new("claude".to_string());
+        analysis.add_finding(
+            Finding::new(
+                Severity::High,
+                Category::Security,
+                "id_1 mails user data to str_1".to_string(),
+                "In file_1.rs, id_6 sends id_4 for every id_3.".to_string(),
+            )
+            .with_file("file_1.rs".to_string())
+            .with_line(12),
+        );
+        let anonymized_id = analysis.findings[0].id.clone();
+
+        mapping.restore_analysis(&mut analysis);
+
+        let finding = &analysis.findings[0];
+        assert_eq!(finding.title, "charge_customer mails user data to ops@acme-corp.com");
+        assert_eq!(finding.description, "In src/billing.rs, notify sends total for every Customer.");
+        assert_eq!(finding.file.as_deref(), Some("src/billing.rs"));
+        assert_ne!(finding.id, anonymized_id);
+        // Unknown placeholders are left alone
+        assert_eq!(mapping.restore("id_99 and file_1.txt"), "id_99 and file_1.txt");
+    }
+
+    #[test]
+    fn test_restore_email_placeholder() {
+        let config = AnonymizeConfig {
+            strings: false,
+            ..config()
+        };
+        let (_, mapping) = anonymize(DIFF, &config);
+
+        assert_eq!(mapping.restore("Don't hardcode user_1@example.invalid."), "Don't hardcode ops@acme-corp.com.");
+    }
+}
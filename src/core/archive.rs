@@ -0,0 +1,117 @@
+//! Archive of cleaned-up reviews
+//!
+//! When a review is removed or detached, its full state (PR info, agent
+//! analyses and their history, usage) is moved to
+//! `~/.chaba/archive/pr-<number>-<timestamp>.yaml` instead of being dropped.
+//! `chaba history` browses the archive and `chaba report --summary` counts
+//! archived reviews. Archive files are encrypted like the state file when
+//! `chaba encrypt enable` was run.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::core::encryption;
+use crate::core::state::{self, ReviewState};
+use crate::error::Result;
+
+/// A review as it was when it was cleaned up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedReview {
+    pub archived_at: DateTime<Utc>,
+    pub review: ReviewState,
+}
+
+/// Directory holding archived reviews, `~/.chaba/archive/` by default
+pub fn archive_dir() -> Result<PathBuf> {
+    Ok(state::state_dir()?.join("archive"))
+}
+
+/// Archive a review that is being cleaned up
+pub fn archive(review: &ReviewState) -> Result<PathBuf> {
+    archive_in(&archive_dir()?, review, Utc::now())
+}
+
+/// Archive a review under a specific directory
+pub fn archive_in(dir: &Path, review: &ReviewState, archived_at: DateTime<Utc>) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!(
+        "pr-{}-{}.yaml",
+        review.pr_number,
+        archived_at.format("%Y%m%d-%H%M%S")
+    ));
+
+    let archived = ArchivedReview {
+        archived_at,
+        review: review.clone(),
+    };
+    std::fs::write(&path, encryption::seal(serde_yaml::to_string(&archived)?.into_bytes())?)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    Ok(path)
+}
+
+/// Archived reviews, oldest first
+pub fn load() -> Result<Vec<ArchivedReview>> {
+    load_from(&archive_dir()?)
+}
+
+/// Archived reviews under a specific directory, oldest first
+///
+/// Files that cannot be read are skipped with a warning.
+pub fn load_from(dir: &Path) -> Result<Vec<ArchivedReview>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut archived = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "yaml") {
+            continue;
+        }
+        let parsed = encryption::read_to_string(&path)
+            .and_then(|content| Ok(serde_yaml::from_str::<ArchivedReview>(&content)?));
+        match parsed {
+            Ok(review) => archived.push(review),
+            Err(e) => tracing::warn!("Skipping archived review {}: {}", path.display(), e),
+        }
+    }
+
+    archived.sort_by_key(|review| review.archived_at);
+    Ok(archived)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn review(pr_number: u32) -> ReviewState {
+        ReviewState::for_test(pr_number, "/tmp/review")
+    }
+
+    #[test]
+    fn test_archive_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load_from(&dir.path().join("missing")).unwrap().is_empty());
+
+        let now = Utc::now();
+        let later = archive_in(dir.path(), &review(2), now).unwrap();
+        archive_in(dir.path(), &review(1), now - Duration::days(1)).unwrap();
+        std::fs::write(dir.path().join("pr-3-broken.yaml"), "not: [valid").unwrap();
+
+        assert!(later.file_name().unwrap().to_string_lossy().starts_with("pr-2-"));
+        let archived = load_from(dir.path()).unwrap();
+        assert_eq!(
+            archived.iter().map(|a| a.review.pr_number).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(archived[1].review.branch, "feature-2");
+    }
+}
@@ -0,0 +1,184 @@
+//! Baseline of accepted findings
+//!
+//! `.chaba/baseline.yaml` in the repository lists fingerprints of findings
+//! the team has accepted. Baselined findings are hidden from `agent-result`
+//! and don't count toward `chaba verdict`, so long-standing issues don't
+//! drown out new ones. `chaba baseline update --pr N` adds a review's
+//! current findings.
+//!
+//! A fingerprint hashes only the file and title, so a finding stays
+//! baselined when lines shift or a different agent reports it.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+use crate::core::git::GitOps;
+use crate::core::review_analysis::{Finding, ReviewAnalysis};
+use crate::core::state::ReviewState;
+use crate::error::Result;
+
+/// Baseline file, relative to the main worktree
+pub const BASELINE_FILE: &str = ".chaba/baseline.yaml";
+
+/// Length of a fingerprint in hex characters
+const FINGERPRINT_LEN: usize = 16;
+
+/// An accepted finding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub fingerprint: String,
+    /// File and title, kept so the baseline is readable in review
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(default)]
+    pub title: String,
+}
+
+/// Accepted findings of a repository
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    #[serde(default)]
+    pub findings: Vec<BaselineEntry>,
+}
+
+/// Fingerprint of a finding: a hash of its file and title
+///
+/// The file is compared without a leading `./` and the title trimmed and
+/// case-insensitive, matching how finding IDs treat titles.
+pub fn fingerprint(finding: &Finding) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(finding.file.as_deref().unwrap_or("").trim_start_matches("./").as_bytes());
+    hasher.update([0]);
+    hasher.update(finding.title.trim().to_lowercase().as_bytes());
+
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>()[..FINGERPRINT_LEN]
+        .to_string()
+}
+
+impl Baseline {
+    /// Baseline file of the repository a review was created from
+    pub fn path_for_review(review: &ReviewState) -> Result<PathBuf> {
+        Ok(GitOps::for_review(review)?.repo_root().join(BASELINE_FILE))
+    }
+
+    /// Load the baseline for a review
+    ///
+    /// Empty when the review's repository can't be found or has no baseline.
+    pub fn for_review(review: &ReviewState) -> Result<Self> {
+        match Self::path_for_review(review) {
+            Ok(path) => Self::load(&path),
+            Err(e) => {
+                tracing::debug!("No baseline for review #{}: {}", review.pr_number, e);
+                Ok(Self::default())
+            }
+        }
+    }
+
+    /// Load a baseline file; a missing file is an empty baseline
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_yaml::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn contains(&self, finding: &Finding) -> bool {
+        let fingerprint = fingerprint(finding);
+        self.findings.iter().any(|entry| entry.fingerprint == fingerprint)
+    }
+
+    /// Accept a finding; returns `false` if it was already baselined
+    pub fn add(&mut self, finding: &Finding) -> bool {
+        if self.contains(finding) {
+            return false;
+        }
+
+        self.findings.push(BaselineEntry {
+            fingerprint: fingerprint(finding),
+            file: finding.file.clone(),
+            title: finding.title.trim().to_string(),
+        });
+        true
+    }
+
+    /// Copies of `analyses` without baselined findings, and how many were removed
+    pub fn suppress(&self, analyses: &[ReviewAnalysis]) -> (Vec<ReviewAnalysis>, usize) {
+        let mut suppressed = 0;
+        let analyses = analyses
+            .iter()
+            .map(|analysis| {
+                let mut analysis = analysis.clone();
+                let before = analysis.findings.len();
+                analysis.findings.retain(|finding| !self.contains(finding));
+                suppressed += before - analysis.findings.len();
+                analysis
+            })
+            .collect();
+
+        (analyses, suppressed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::review_analysis::{Category, Severity};
+
+    fn finding(file: &str, line: u32, title: &str) -> Finding {
+        Finding::new(Severity::High, Category::Security, title.to_string(), String::new())
+            .with_file(file.to_string())
+            .with_line(line)
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_line_and_case() {
+        assert_eq!(
+            fingerprint(&finding("src/auth.rs", 10, "Token is logged")),
+            fingerprint(&finding("./src/auth.rs", 42, "  token is LOGGED"))
+        );
+        assert_ne!(
+            fingerprint(&finding("src/auth.rs", 10, "Token is logged")),
+            fingerprint(&finding("src/api.rs", 10, "Token is logged"))
+        );
+    }
+
+    #[test]
+    fn test_suppress_and_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(BASELINE_FILE);
+
+        let mut baseline = Baseline::load(&path).unwrap();
+        assert!(baseline.findings.is_empty());
+        assert!(baseline.add(&finding("src/auth.rs", 10, "Token is logged")));
+        assert!(!baseline.add(&finding("src/auth.rs", 12, "Token is logged")));
+        baseline.save(&path).unwrap();
+
+        let baseline = Baseline::load(&path).unwrap();
+        assert_eq!(baseline.findings.len(), 1);
+        assert_eq!(baseline.findings[0].title, "Token is logged");
+
+        let mut analysis = ReviewAnalysis::new("codex".to_string());
+        analysis.add_finding(finding("src/auth.rs", 30, "Token is logged"));
+        analysis.add_finding(finding("src/auth.rs", 31, "Missing rate limit"));
+
+        let (analyses, suppressed) = baseline.suppress(&[analysis]);
+        assert_eq!(suppressed, 1);
+        assert_eq!(analyses[0].findings.len(), 1);
+        assert_eq!(analyses[0].findings[0].title, "Missing rate limit");
+    }
+}
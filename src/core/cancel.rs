@@ -0,0 +1,73 @@
+//! Ctrl-C handling for agent runs
+//!
+//! While agents run, the first Ctrl-C cancels them instead of killing chaba:
+//! the runner kills the agent processes it spawned, the results of agents that
+//! already finished are saved, and the review is marked "analysis
+//! interrupted". A second Ctrl-C, or one while no agents are running, exits
+//! immediately.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Once, OnceLock};
+use tokio::sync::watch;
+
+/// Exit code for a process ended by Ctrl-C (128 + SIGINT)
+pub const EXIT_CODE: i32 = 130;
+
+/// Number of live [`CancelGuard`]s
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+
+fn sender() -> &'static watch::Sender<bool> {
+    static CANCELLED: OnceLock<watch::Sender<bool>> = OnceLock::new();
+    CANCELLED.get_or_init(|| watch::channel(false).0)
+}
+
+/// Cancel running agents
+pub fn cancel() {
+    sender().send_replace(true);
+}
+
+/// Whether Ctrl-C cancelled the agent run
+pub fn is_cancelled() -> bool {
+    *sender().borrow()
+}
+
+/// Wait until the agent run is cancelled
+pub async fn cancelled() {
+    let mut receiver = sender().subscribe();
+    let _ = receiver.wait_for(|cancelled| *cancelled).await;
+}
+
+/// The error a command returns when it was killed by cancellation
+pub fn interrupted() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Interrupted, "cancelled by Ctrl-C")
+}
+
+/// Marks agents as running, so Ctrl-C cancels them until the guard is dropped
+pub struct CancelGuard(());
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        ACTIVE.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Route Ctrl-C to agent cancellation while the returned guard lives
+///
+/// Must be called inside the Tokio runtime.
+pub fn guard() -> CancelGuard {
+    static HANDLER: Once = Once::new();
+    HANDLER.call_once(|| {
+        tokio::spawn(async {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                if ACTIVE.load(Ordering::SeqCst) == 0 || is_cancelled() {
+                    std::process::exit(EXIT_CODE);
+                }
+                eprintln!("\n⏹  Cancelling agents... (press Ctrl-C again to exit immediately)");
+                cancel();
+            }
+        });
+    });
+
+    ACTIVE.fetch_add(1, Ordering::SeqCst);
+    CancelGuard(())
+}
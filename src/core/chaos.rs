@@ -0,0 +1,250 @@
+//! Fault injection for resilience testing
+//!
+//! In chaos mode every external command run through a [`CommandRunner`]
+//! fails with the configured probability: the spawn errors, the command
+//! exits non-zero, or it times out. Used to check that rollback, retries,
+//! and saved state hold up under partial failures.
+//!
+//! Enabled with the hidden `--chaos RATE` flag or the `CHABA_CHAOS`
+//! environment variable (a probability between 0 and 1). Failures are drawn
+//! from a seeded generator, so a run can be reproduced with `--chaos-seed` /
+//! `CHABA_CHAOS_SEED`; the seed is logged when chaos mode starts.
+
+use async_trait::async_trait;
+use std::ffi::OsStr;
+use std::io;
+use std::path::Path;
+use std::process::Output;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::core::command::{CommandEnv, CommandRunner, LineSink};
+use crate::core::platform;
+
+/// How long an injected timeout stalls before failing
+const HANG: Duration = Duration::from_secs(1);
+
+/// Chaos mode configuration
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChaosSettings {
+    /// Probability that a command fails, from 0.0 to 1.0
+    pub rate: f64,
+    /// Seed for choosing failures; random when not set
+    pub seed: Option<u64>,
+}
+
+static SETTINGS: OnceLock<Option<ChaosSettings>> = OnceLock::new();
+
+/// Turn on chaos mode for the rest of the process
+///
+/// Must be called before the first runner is created; later calls and the
+/// environment variables are ignored.
+pub fn enable(settings: ChaosSettings) {
+    let _ = SETTINGS.set(Some(settings));
+}
+
+/// Chaos mode settings from [`enable`] or the environment, if it is on
+pub fn settings() -> Option<ChaosSettings> {
+    *SETTINGS.get_or_init(|| {
+        let rate = parse_rate(&std::env::var("CHABA_CHAOS").ok()?).ok()?;
+        let seed = std::env::var("CHABA_CHAOS_SEED").ok().and_then(|seed| seed.parse().ok());
+        Some(ChaosSettings { rate, seed })
+    })
+}
+
+/// Parse a failure probability for `--chaos`
+pub fn parse_rate(s: &str) -> std::result::Result<f64, String> {
+    let rate: f64 = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid chaos rate '{}': expected a number between 0 and 1", s))?;
+
+    if (0.0..=1.0).contains(&rate) {
+        Ok(rate)
+    } else {
+        Err(format!("Invalid chaos rate '{}': expected a number between 0 and 1", s))
+    }
+}
+
+/// A failure injected into a command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fault {
+    /// The process can't be started
+    Spawn,
+    /// The process exits with status 1
+    Exit,
+    /// The process stalls, then fails with a timeout
+    Timeout,
+}
+
+/// SplitMix64: small, seedable, and good enough for picking faults
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `[0, 1)`
+    fn next_f64(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Runner decorator that randomly fails commands
+pub struct ChaosCommandRunner {
+    inner: Arc<dyn CommandRunner + Send + Sync>,
+    rate: f64,
+    rng: Mutex<Rng>,
+}
+
+impl ChaosCommandRunner {
+    pub fn new(inner: Arc<dyn CommandRunner + Send + Sync>, settings: ChaosSettings) -> Self {
+        let seed = settings.seed.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_nanos() as u64)
+                .unwrap_or_default()
+        });
+        tracing::warn!(
+            "Chaos mode: failing {:.0}% of commands (seed {})",
+            settings.rate * 100.0,
+            seed
+        );
+
+        ChaosCommandRunner {
+            inner,
+            rate: settings.rate,
+            rng: Mutex::new(Rng(seed)),
+        }
+    }
+
+    /// Decide whether the next command fails, and how
+    fn next_fault(&self) -> Option<Fault> {
+        let mut rng = self.rng.lock().unwrap();
+        if rng.next_f64() >= self.rate {
+            return None;
+        }
+        Some(match rng.next() % 3 {
+            0 => Fault::Spawn,
+            1 => Fault::Exit,
+            _ => Fault::Timeout,
+        })
+    }
+}
+
+#[async_trait]
+impl CommandRunner for ChaosCommandRunner {
+    async fn run(
+        &self,
+        program: &str,
+        args: &[&OsStr],
+        current_dir: &Path,
+    ) -> Result<Output, io::Error> {
+        self.run_with_env(program, args, current_dir, &CommandEnv::default())
+            .await
+    }
+
+    async fn run_with_env(
+        &self,
+        program: &str,
+        args: &[&OsStr],
+        current_dir: &Path,
+        env: &CommandEnv,
+    ) -> Result<Output, io::Error> {
+        match self.next_fault() {
+            Some(fault) => inject(fault, program).await,
+            None => self.inner.run_with_env(program, args, current_dir, env).await,
+        }
+    }
+
+    async fn run_streaming(
+        &self,
+        program: &str,
+        args: &[&OsStr],
+        current_dir: &Path,
+        env: &CommandEnv,
+        on_line: &LineSink<'_>,
+    ) -> Result<Output, io::Error> {
+        match self.next_fault() {
+            Some(fault) => inject(fault, program).await,
+            None => {
+                self.inner
+                    .run_streaming(program, args, current_dir, env, on_line)
+                    .await
+            }
+        }
+    }
+}
+
+/// Fail a command the way `fault` describes instead of running it
+async fn inject(fault: Fault, program: &str) -> Result<Output, io::Error> {
+    tracing::warn!("Chaos mode: injecting {:?} failure into `{}`", fault, program);
+    match fault {
+        Fault::Spawn => Err(io::Error::other(format!("chaos: failed to start {}", program))),
+        Fault::Exit => Ok(Output {
+            status: platform::exit_status(1),
+            stdout: Vec::new(),
+            stderr: format!("chaos: injected failure in {}\n", program).into_bytes(),
+        }),
+        Fault::Timeout => {
+            tokio::time::sleep(HANG).await;
+            Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("chaos: {} timed out", program),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::command::LiveCommandRunner;
+
+    fn runner(rate: f64, seed: u64) -> ChaosCommandRunner {
+        ChaosCommandRunner::new(Arc::new(LiveCommandRunner), ChaosSettings { rate, seed: Some(seed) })
+    }
+
+    #[test]
+    fn test_parse_rate() {
+        assert_eq!(parse_rate("0.25"), Ok(0.25));
+        assert_eq!(parse_rate("1"), Ok(1.0));
+        assert!(parse_rate("1.5").is_err());
+        assert!(parse_rate("-0.1").is_err());
+        assert!(parse_rate("often").is_err());
+    }
+
+    #[test]
+    fn test_faults_follow_rate_and_seed() {
+        assert!((0..100).all(|_| runner(0.0, 1).next_fault().is_none()));
+        let always = runner(1.0, 1);
+        assert!((0..100).all(|_| always.next_fault().is_some()));
+
+        let faults = |seed| {
+            let runner = runner(0.5, seed);
+            (0..50).map(|_| runner.next_fault()).collect::<Vec<_>>()
+        };
+        assert_eq!(faults(42), faults(42));
+
+        let sample = faults(42);
+        assert!(sample.iter().any(Option::is_none));
+        assert!(sample.contains(&Some(Fault::Spawn)));
+        assert!(sample.contains(&Some(Fault::Exit)));
+        assert!(sample.contains(&Some(Fault::Timeout)));
+    }
+
+    #[tokio::test]
+    async fn test_runner_passes_through_without_fault() {
+        let output = runner(0.0, 1)
+            .run("echo", &["ok".as_ref()], std::env::current_dir().unwrap().as_path())
+            .await
+            .unwrap();
+
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "ok");
+    }
+}
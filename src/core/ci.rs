@@ -0,0 +1,143 @@
+//! CI check status of a PR
+//!
+//! Checks are read with `gh pr checks --json`, which groups every check run
+//! and commit status into a bucket: `pass`, `fail`, `pending`, `skipping`,
+//! or `cancel`. `chaba status` and `chaba list` summarize them, and
+//! `review --wait-ci` polls until CI settles before starting agents.
+
+use serde::Deserialize;
+use std::fmt;
+use std::time::Duration;
+
+use crate::core::git::GitOps;
+use crate::error::{ChabaError, Result};
+
+/// A single check run or commit status
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Check {
+    pub name: String,
+    /// `pass`, `fail`, `pending`, `skipping`, or `cancel`
+    pub bucket: String,
+    #[serde(default)]
+    pub link: String,
+}
+
+/// Overall CI result of a PR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CiStatus {
+    Passing,
+    Failing,
+    Pending,
+    /// The PR has no checks configured
+    NoChecks,
+}
+
+impl CiStatus {
+    /// Single-character form for table columns
+    pub fn symbol(self) -> &'static str {
+        match self {
+            CiStatus::Passing => "✓",
+            CiStatus::Failing => "✗",
+            CiStatus::Pending => "⏳",
+            CiStatus::NoChecks => "-",
+        }
+    }
+}
+
+impl fmt::Display for CiStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CiStatus::Passing => write!(f, "✓ passing"),
+            CiStatus::Failing => write!(f, "✗ failing"),
+            CiStatus::Pending => write!(f, "⏳ pending"),
+            CiStatus::NoChecks => write!(f, "no checks"),
+        }
+    }
+}
+
+/// Parse the output of `gh pr checks --json name,bucket,link`
+pub fn parse_checks(json: &str) -> Result<Vec<Check>> {
+    serde_json::from_str(json).map_err(|e| ChabaError::GhCliError(format!("Unexpected gh output: {}", e)))
+}
+
+/// Summarize checks: any failure fails, otherwise anything unfinished is pending
+///
+/// Skipped checks count as passing; cancelled ones as failing.
+pub fn summarize(checks: &[Check]) -> CiStatus {
+    if checks.is_empty() {
+        CiStatus::NoChecks
+    } else if checks.iter().any(|check| matches!(check.bucket.as_str(), "fail" | "cancel")) {
+        CiStatus::Failing
+    } else if checks.iter().any(|check| check.bucket == "pending") {
+        CiStatus::Pending
+    } else {
+        CiStatus::Passing
+    }
+}
+
+/// Names of the checks that failed or were cancelled
+pub fn failed_checks(checks: &[Check]) -> Vec<&str> {
+    checks
+        .iter()
+        .filter(|check| matches!(check.bucket.as_str(), "fail" | "cancel"))
+        .map(|check| check.name.as_str())
+        .collect()
+}
+
+/// Poll a PR's checks until none are pending or `timeout` passes
+///
+/// Returns the last status seen, which is still `Pending` on timeout.
+pub async fn wait_for_checks(
+    git: &GitOps,
+    pr_number: u32,
+    timeout: Duration,
+    interval: Duration,
+) -> Result<CiStatus> {
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    loop {
+        let status = summarize(&git.get_pr_checks(pr_number).await?);
+        if status != CiStatus::Pending || tokio::time::Instant::now() + interval > deadline {
+            return Ok(status);
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(name: &str, bucket: &str) -> Check {
+        Check {
+            name: name.to_string(),
+            bucket: bucket.to_string(),
+            link: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_checks() {
+        let json = r#"[{"bucket":"pass","link":"https://github.com/o/r/actions/runs/1","name":"build"},{"bucket":"pending","link":"","name":"lint"}]"#;
+        let checks = parse_checks(json).unwrap();
+
+        assert_eq!(checks.len(), 2);
+        assert_eq!(checks[0].name, "build");
+        assert_eq!(checks[1].bucket, "pending");
+    }
+
+    #[test]
+    fn test_summarize() {
+        assert_eq!(summarize(&[]), CiStatus::NoChecks);
+        assert_eq!(summarize(&[check("build", "pass"), check("docs", "skipping")]), CiStatus::Passing);
+        assert_eq!(summarize(&[check("build", "pass"), check("lint", "pending")]), CiStatus::Pending);
+        assert_eq!(summarize(&[check("build", "fail"), check("lint", "pending")]), CiStatus::Failing);
+        assert_eq!(summarize(&[check("deploy", "cancel")]), CiStatus::Failing);
+    }
+
+    #[test]
+    fn test_failed_checks() {
+        let checks = [check("build", "fail"), check("lint", "pass"), check("deploy", "cancel")];
+        assert_eq!(failed_checks(&checks), vec!["build", "deploy"]);
+    }
+}
@@ -6,7 +6,33 @@
 use async_trait::async_trait;
 use std::ffi::OsStr;
 use std::path::Path;
-use std::process::Output;
+use std::process::{Output, Stdio};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+
+use crate::core::cancel;
+use crate::core::chaos::{self, ChaosCommandRunner};
+use crate::core::platform;
+use crate::core::trace::{self, TraceCommandRunner};
+
+/// Environment overrides for a spawned command
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommandEnv {
+    /// Variables to set for the command
+    pub set: Vec<(String, String)>,
+    /// Variables to remove from the inherited environment
+    pub remove: Vec<String>,
+}
+
+impl CommandEnv {
+    /// Check if there are no overrides
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty() && self.remove.is_empty()
+    }
+}
+
+/// Callback receiving each line of a command's stdout, see [`CommandRunner::run_streaming`]
+pub type LineSink<'a> = dyn Fn(&str) + Send + Sync + 'a;
 
 /// Trait for executing external commands
 ///
@@ -49,6 +75,50 @@ pub trait CommandRunner {
         args: &[&OsStr],
         current_dir: &Path,
     ) -> Result<Output, std::io::Error>;
+
+    /// Execute a command with environment overrides
+    ///
+    /// The default implementation ignores `env` and delegates to [`CommandRunner::run`],
+    /// which is sufficient for runners that don't spawn real processes.
+    async fn run_with_env(
+        &self,
+        program: &str,
+        args: &[&OsStr],
+        current_dir: &Path,
+        env: &CommandEnv,
+    ) -> Result<Output, std::io::Error> {
+        let _ = env;
+        self.run(program, args, current_dir).await
+    }
+
+    /// Execute a command, passing each line of its stdout to `on_line` as it is printed
+    ///
+    /// Returns the complete output, like [`CommandRunner::run_with_env`]. The
+    /// default implementation runs the command to completion and then replays
+    /// its stdout, which is sufficient for runners that don't spawn real processes.
+    async fn run_streaming(
+        &self,
+        program: &str,
+        args: &[&OsStr],
+        current_dir: &Path,
+        env: &CommandEnv,
+        on_line: &LineSink<'_>,
+    ) -> Result<Output, std::io::Error> {
+        let output = self.run_with_env(program, args, current_dir, env).await?;
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            on_line(line);
+        }
+        Ok(output)
+    }
+}
+
+fn log_command(program: &str, args: &[&OsStr], current_dir: &Path) {
+    tracing::debug!(
+        "Running {} {} in {}",
+        program,
+        args.iter().map(|arg| arg.to_string_lossy()).collect::<Vec<_>>().join(" "),
+        current_dir.display()
+    );
 }
 
 /// Production implementation using tokio::process::Command
@@ -65,12 +135,190 @@ impl CommandRunner for LiveCommandRunner {
         args: &[&OsStr],
         current_dir: &Path,
     ) -> Result<Output, std::io::Error> {
-        tokio::process::Command::new(program)
+        log_command(program, args, current_dir);
+        tokio::process::Command::new(platform::resolve_program(program))
             .current_dir(current_dir)
             .args(args)
             .output()
             .await
     }
+
+    async fn run_with_env(
+        &self,
+        program: &str,
+        args: &[&OsStr],
+        current_dir: &Path,
+        env: &CommandEnv,
+    ) -> Result<Output, std::io::Error> {
+        log_command(program, args, current_dir);
+        let mut command = tokio::process::Command::new(platform::resolve_program(program));
+        command.current_dir(current_dir).args(args);
+        apply_env(&mut command, env);
+
+        command.output().await
+    }
+
+    async fn run_streaming(
+        &self,
+        program: &str,
+        args: &[&OsStr],
+        current_dir: &Path,
+        env: &CommandEnv,
+        on_line: &LineSink<'_>,
+    ) -> Result<Output, std::io::Error> {
+        log_command(program, args, current_dir);
+        let mut command = tokio::process::Command::new(platform::resolve_program(program));
+        command
+            .current_dir(current_dir)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // A timed-out caller drops this future; don't leave the process behind
+            .kill_on_drop(true);
+        apply_env(&mut command, env);
+
+        if cancel::is_cancelled() {
+            return Err(cancel::interrupted());
+        }
+        let mut child = command.spawn()?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+
+        let read_stdout = async {
+            let mut reader = BufReader::new(stdout);
+            let mut collected = Vec::new();
+            let mut line = Vec::new();
+            while reader.read_until(b'\n', &mut line).await? > 0 {
+                on_line(String::from_utf8_lossy(&line).trim_end_matches(['\n', '\r']));
+                collected.append(&mut line);
+            }
+            Ok::<_, std::io::Error>(collected)
+        };
+        // Drain stderr at the same time so a chatty process can't block on a full pipe
+        let read_stderr = async {
+            let mut collected = Vec::new();
+            stderr.read_to_end(&mut collected).await?;
+            Ok::<_, std::io::Error>(collected)
+        };
+
+        tokio::select! {
+            output = async { tokio::try_join!(read_stdout, read_stderr) } => {
+                let (stdout, stderr) = output?;
+                let status = child.wait().await?;
+                Ok(Output { status, stdout, stderr })
+            }
+            // Ctrl-C: kill the process we spawned rather than leave it running
+            _ = cancel::cancelled() => {
+                child.kill().await?;
+                Err(cancel::interrupted())
+            }
+        }
+    }
+}
+
+fn apply_env(command: &mut tokio::process::Command, env: &CommandEnv) {
+    for key in &env.remove {
+        command.env_remove(key);
+    }
+    for (key, value) in &env.set {
+        command.env(key, value);
+    }
+}
+
+/// Runner for real commands
+///
+/// Wrapped in a [`ChaosCommandRunner`] when chaos mode is on (see [`chaos`]),
+/// and in a [`TraceCommandRunner`] when commands are traced (see [`trace`]),
+/// so injected failures show up in the trace.
+pub fn default_runner() -> Arc<dyn CommandRunner + Send + Sync> {
+    let runner: Arc<dyn CommandRunner + Send + Sync> = match chaos::settings() {
+        Some(settings) => Arc::new(ChaosCommandRunner::new(Arc::new(LiveCommandRunner), settings)),
+        None => Arc::new(LiveCommandRunner),
+    };
+
+    match trace::current() {
+        Some(_) => Arc::new(TraceCommandRunner::new(runner)),
+        None => runner,
+    }
+}
+
+/// Runner decorator that applies fixed environment overrides to every command
+///
+/// Used to give a single agent its own credentials without touching the
+/// code paths that build its command line. Overrides passed with a command
+/// win over the fixed ones.
+pub struct EnvCommandRunner {
+    inner: Arc<dyn CommandRunner + Send + Sync>,
+    env: CommandEnv,
+}
+
+impl EnvCommandRunner {
+    pub fn new(inner: Arc<dyn CommandRunner + Send + Sync>, env: CommandEnv) -> Self {
+        EnvCommandRunner { inner, env }
+    }
+
+    /// The fixed overrides with `env` applied over them
+    fn merged(&self, env: &CommandEnv) -> CommandEnv {
+        let overridden = |key: &str| env.set.iter().any(|(k, _)| k == key) || env.remove.iter().any(|k| k == key);
+        CommandEnv {
+            set: self
+                .env
+                .set
+                .iter()
+                .filter(|(key, _)| !overridden(key))
+                .chain(&env.set)
+                .cloned()
+                .collect(),
+            remove: self
+                .env
+                .remove
+                .iter()
+                .filter(|key| !overridden(key))
+                .chain(&env.remove)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl CommandRunner for EnvCommandRunner {
+    async fn run(
+        &self,
+        program: &str,
+        args: &[&OsStr],
+        current_dir: &Path,
+    ) -> Result<Output, std::io::Error> {
+        self.inner
+            .run_with_env(program, args, current_dir, &self.env)
+            .await
+    }
+
+    async fn run_with_env(
+        &self,
+        program: &str,
+        args: &[&OsStr],
+        current_dir: &Path,
+        env: &CommandEnv,
+    ) -> Result<Output, std::io::Error> {
+        self.inner
+            .run_with_env(program, args, current_dir, &self.merged(env))
+            .await
+    }
+
+    async fn run_streaming(
+        &self,
+        program: &str,
+        args: &[&OsStr],
+        current_dir: &Path,
+        env: &CommandEnv,
+        on_line: &LineSink<'_>,
+    ) -> Result<Output, std::io::Error> {
+        self.inner
+            .run_streaming(program, args, current_dir, &self.merged(env), on_line)
+            .await
+    }
 }
 
 #[cfg(test)]
@@ -92,4 +340,80 @@ mod tests {
         assert!(output.status.success());
         assert!(String::from_utf8_lossy(&output.stdout).contains("test"));
     }
+
+    #[tokio::test]
+    async fn test_live_runner_applies_env() {
+        let runner = LiveCommandRunner;
+        let env = CommandEnv {
+            set: vec![("CHABA_TEST_VALUE".to_string(), "injected".to_string())],
+            remove: vec!["HOME".to_string()],
+        };
+        let output = runner
+            .run_with_env(
+                "sh",
+                &["-c".as_ref(), "echo \"$CHABA_TEST_VALUE:${HOME:-unset}\"".as_ref()],
+                std::env::current_dir().unwrap().as_path(),
+                &env,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "injected:unset");
+    }
+
+    #[tokio::test]
+    async fn test_live_runner_streams_lines() {
+        let runner = LiveCommandRunner;
+        let lines = std::sync::Mutex::new(Vec::new());
+        let output = runner
+            .run_streaming(
+                "sh",
+                &["-c".as_ref(), "echo one; echo oops >&2; printf two".as_ref()],
+                std::env::current_dir().unwrap().as_path(),
+                &CommandEnv::default(),
+                &|line: &str| lines.lock().unwrap().push(line.to_string()),
+            )
+            .await
+            .unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(*lines.lock().unwrap(), vec!["one", "two"]);
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "one\ntwo");
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "oops\n");
+    }
+
+    #[tokio::test]
+    async fn test_env_runner_keeps_per_call_env() {
+        let runner = EnvCommandRunner::new(
+            Arc::new(LiveCommandRunner),
+            CommandEnv {
+                set: vec![
+                    ("CHABA_TEST_TOKEN".to_string(), "agent".to_string()),
+                    ("CHABA_TEST_MODE".to_string(), "fixed".to_string()),
+                ],
+                remove: vec!["CHABA_TEST_CALL".to_string()],
+            },
+        );
+        let env = CommandEnv {
+            set: vec![
+                ("CHABA_TEST_CALL".to_string(), "call".to_string()),
+                ("CHABA_TEST_MODE".to_string(), "call".to_string()),
+            ],
+            remove: Vec::new(),
+        };
+        let script = "echo \"$CHABA_TEST_TOKEN:$CHABA_TEST_CALL:$CHABA_TEST_MODE\"";
+        let dir = std::env::current_dir().unwrap();
+
+        let output = runner
+            .run_with_env("sh", &["-c".as_ref(), script.as_ref()], &dir, &env)
+            .await
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "agent:call:call");
+
+        let output = runner
+            .run_streaming("sh", &["-c".as_ref(), script.as_ref()], &dir, &env, &|_: &str| {})
+            .await
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "agent:call:call");
+    }
 }
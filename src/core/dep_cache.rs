@@ -0,0 +1,249 @@
+//! Dependency cache sharing between worktrees
+//!
+//! Review worktrees usually have the same dependencies as the main worktree.
+//! This module lets them reuse the main worktree's package manager caches
+//! and, when lockfiles match, clone `node_modules` instead of installing.
+//!
+//! `node_modules` is cloned copy-on-write where the filesystem supports it
+//! (APFS, Btrfs, XFS, ReFS) and copied otherwise. It is never hardlinked:
+//! tools that write files in place, like patch-package or postinstall
+//! scripts, would then change the main worktree too.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+use crate::config::DependencyCacheConfig;
+use crate::core::platform;
+use crate::core::project::NodePackageManager;
+use crate::error::{ChabaError, Result};
+
+/// Check whether the lockfile of the main worktree matches the review worktree
+///
+/// Returns `false` if either lockfile is missing.
+pub fn lockfiles_match(main_worktree: &Path, review_worktree: &Path, pm: &NodePackageManager) -> bool {
+    let main_lock = main_worktree.join(pm.lock_file());
+    let review_lock = review_worktree.join(pm.lock_file());
+
+    match (std::fs::read(&main_lock), std::fs::read(&review_lock)) {
+        (Ok(main), Ok(review)) => main == review,
+        _ => false,
+    }
+}
+
+/// Try to reuse `node_modules` from the main worktree by cloning it
+///
+/// Returns `Ok(true)` if `node_modules` was cloned and installation can be skipped,
+/// `Ok(false)` if the lockfiles differ or the main worktree has no `node_modules`.
+pub async fn clone_node_modules(
+    main_worktree: &Path,
+    review_worktree: &Path,
+    pm: &NodePackageManager,
+) -> Result<bool> {
+    let src = main_worktree.join("node_modules");
+    if !src.is_dir() {
+        tracing::info!("Main worktree has no node_modules, skipping clone");
+        return Ok(false);
+    }
+
+    if !lockfiles_match(main_worktree, review_worktree, pm) {
+        tracing::info!("{} differs from main worktree, skipping clone", pm.lock_file());
+        return Ok(false);
+    }
+
+    let dst = review_worktree.join("node_modules");
+    tracing::info!("Cloning node_modules from {}", src.display());
+
+    tokio::task::spawn_blocking(move || clone_tree(&src, &dst))
+        .await
+        .map_err(|e| ChabaError::Other(anyhow::anyhow!("Clone task failed: {}", e)))??;
+
+    Ok(true)
+}
+
+/// Recursively mirror `src` into `dst` with copy-on-write clones
+///
+/// Symlinks are recreated as-is; files that cannot be cloned (e.g. across
+/// filesystems, or on filesystems without reflinks) are copied instead.
+fn clone_tree(src: &Path, dst: &Path) -> Result<()> {
+    std::fs::create_dir_all(dst)?;
+
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let from = entry.path();
+        let to = dst.join(entry.file_name());
+
+        if file_type.is_symlink() {
+            let target = std::fs::read_link(&from)?;
+            create_symlink(&target, &to)?;
+        } else if file_type.is_dir() {
+            clone_tree(&from, &to)?;
+        } else {
+            reflink_copy::reflink_or_copy(&from, &to)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(target, link)?;
+    Ok(())
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &Path, link: &Path) -> Result<()> {
+    if target.is_dir() {
+        std::os::windows::fs::symlink_dir(target, link)?;
+    } else {
+        std::os::windows::fs::symlink_file(target, link)?;
+    }
+    Ok(())
+}
+
+/// Configured cache directory, with a leading `~` expanded like `state.path`
+fn cache_dir(config: &DependencyCacheConfig) -> Option<PathBuf> {
+    let dir = config.cache_dir.as_ref()?;
+    match dir.strip_prefix("~") {
+        Ok(rest) => Some(dirs::home_dir()?.join(rest)),
+        Err(_) => Some(dir.clone()),
+    }
+}
+
+/// Build environment variables that point the package manager at shared caches
+pub async fn cache_env(
+    config: &DependencyCacheConfig,
+    pm: &NodePackageManager,
+    main_worktree: &Path,
+) -> Vec<(String, OsString)> {
+    let mut envs = Vec::new();
+
+    match pm {
+        NodePackageManager::Npm => {
+            if let Some(dir) = cache_dir(config) {
+                envs.push(("npm_config_cache".to_string(), dir.join("npm").into_os_string()));
+            }
+        }
+        NodePackageManager::Yarn => {
+            if let Some(dir) = cache_dir(config) {
+                envs.push(("YARN_CACHE_FOLDER".to_string(), dir.join("yarn").into_os_string()));
+            }
+        }
+        NodePackageManager::Pnpm => {
+            if config.share_pnpm_store {
+                if let Some(store) = pnpm_store_path(main_worktree).await {
+                    envs.push(("npm_config_store_dir".to_string(), store.into_os_string()));
+                }
+            }
+        }
+        NodePackageManager::Bun => {}
+    }
+
+    envs
+}
+
+/// Resolve the pnpm store used by the main worktree
+async fn pnpm_store_path(main_worktree: &Path) -> Option<PathBuf> {
+    let output = Command::new(platform::resolve_program("pnpm"))
+        .args(["store", "path"])
+        .current_dir(main_worktree)
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        tracing::warn!("Failed to resolve pnpm store path");
+        return None;
+    }
+
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if path.is_empty() {
+        None
+    } else {
+        Some(PathBuf::from(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_lockfiles_match() {
+        let main = TempDir::new().unwrap();
+        let review = TempDir::new().unwrap();
+
+        std::fs::write(main.path().join("yarn.lock"), "a@1.0.0").unwrap();
+        std::fs::write(review.path().join("yarn.lock"), "a@1.0.0").unwrap();
+        assert!(lockfiles_match(main.path(), review.path(), &NodePackageManager::Yarn));
+
+        std::fs::write(review.path().join("yarn.lock"), "a@2.0.0").unwrap();
+        assert!(!lockfiles_match(main.path(), review.path(), &NodePackageManager::Yarn));
+    }
+
+    #[test]
+    fn test_lockfiles_match_missing() {
+        let main = TempDir::new().unwrap();
+        let review = TempDir::new().unwrap();
+
+        assert!(!lockfiles_match(main.path(), review.path(), &NodePackageManager::Npm));
+    }
+
+    #[tokio::test]
+    async fn test_clone_node_modules() {
+        let main = TempDir::new().unwrap();
+        let review = TempDir::new().unwrap();
+
+        std::fs::write(main.path().join("package-lock.json"), "{}").unwrap();
+        std::fs::write(review.path().join("package-lock.json"), "{}").unwrap();
+        let pkg = main.path().join("node_modules").join("left-pad");
+        std::fs::create_dir_all(&pkg).unwrap();
+        std::fs::write(pkg.join("index.js"), "module.exports = 1;").unwrap();
+
+        let cloned = clone_node_modules(main.path(), review.path(), &NodePackageManager::Npm)
+            .await
+            .unwrap();
+
+        assert!(cloned);
+        let copied = review.path().join("node_modules/left-pad/index.js");
+        assert_eq!(std::fs::read_to_string(&copied).unwrap(), "module.exports = 1;");
+
+        // Patching the review's copy in place leaves the main worktree alone
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(&copied)
+            .unwrap()
+            .write_all(b"patched")
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(pkg.join("index.js")).unwrap(), "module.exports = 1;");
+    }
+
+    #[tokio::test]
+    async fn test_cache_env_npm() {
+        let config = DependencyCacheConfig {
+            cache_dir: Some(PathBuf::from("/tmp/chaba-cache")),
+            ..Default::default()
+        };
+
+        let envs = cache_env(&config, &NodePackageManager::Npm, Path::new("/tmp")).await;
+        assert_eq!(envs.len(), 1);
+        assert_eq!(envs[0].0, "npm_config_cache");
+        assert_eq!(envs[0].1, OsString::from("/tmp/chaba-cache/npm"));
+    }
+
+    #[tokio::test]
+    async fn test_cache_env_expands_home() {
+        let config = DependencyCacheConfig {
+            cache_dir: Some(PathBuf::from("~/.chaba/cache")),
+            ..Default::default()
+        };
+
+        let envs = cache_env(&config, &NodePackageManager::Yarn, Path::new("/tmp")).await;
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(envs[0].1, home.join(".chaba/cache/yarn").into_os_string());
+    }
+}
@@ -0,0 +1,408 @@
+//! Encryption at rest
+//!
+//! `~/.chaba/state.yaml` holds worktree paths, env-copy facts, and raw agent
+//! output, and snapshots keep copies of env files. `chaba encrypt enable`
+//! encrypts both with an [age](https://age-encryption.org) key. The key is
+//! kept in the OS keychain, or in `~/.chaba/state-key.age` protected by a
+//! passphrase. `CHABA_STATE_KEY` supplies a key directly, e.g. in CI.
+//!
+//! Encrypted files are recognized by the age header, so plaintext files
+//! written before encryption was enabled still load.
+
+use age::secrecy::{ExposeSecret, SecretString};
+use age::x25519;
+use serde::{Deserialize, Serialize};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::core::secrets;
+use crate::core::state;
+use crate::error::{ChabaError, Result};
+
+/// Environment variable holding a key (`AGE-SECRET-KEY-1...`)
+pub const KEY_ENV: &str = "CHABA_STATE_KEY";
+
+/// Environment variable holding the passphrase for a passphrase-protected key
+pub const PASSPHRASE_ENV: &str = "CHABA_PASSPHRASE";
+
+/// Keychain entry the key is stored under
+pub const KEYCHAIN_NAME: &str = "state-key";
+
+/// Encryption settings, relative to `~/.chaba`
+const SETTINGS_FILE: &str = "encryption.yaml";
+
+/// Passphrase-protected key, relative to `~/.chaba`
+const KEY_FILE: &str = "state-key.age";
+
+/// Header every age file starts with
+const AGE_HEADER: &[u8] = b"age-encryption.org/v1";
+
+/// Where the key is kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyStorage {
+    /// OS keychain entry [`KEYCHAIN_NAME`]
+    Keychain,
+    /// `~/.chaba/state-key.age`, unlocked with a passphrase
+    Passphrase,
+    /// The `CHABA_STATE_KEY` environment variable
+    Env,
+}
+
+impl std::fmt::Display for KeyStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyStorage::Keychain => write!(f, "OS keychain"),
+            KeyStorage::Passphrase => write!(f, "passphrase-protected key file"),
+            KeyStorage::Env => write!(f, "{}", KEY_ENV),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Settings {
+    key: KeyStorage,
+}
+
+/// Key that encrypts state and snapshot env files
+pub struct StateKey {
+    identity: x25519::Identity,
+}
+
+impl StateKey {
+    pub fn generate() -> Self {
+        StateKey {
+            identity: x25519::Identity::generate(),
+        }
+    }
+
+    /// Parse a key in age's `AGE-SECRET-KEY-1...` format
+    pub fn parse(key: &str) -> Result<Self> {
+        let identity = x25519::Identity::from_str(key.trim())
+            .map_err(|e| ChabaError::ConfigError(format!("Invalid state key: {}", e)))?;
+        Ok(StateKey { identity })
+    }
+
+    /// The key in age's `AGE-SECRET-KEY-1...` format
+    pub fn secret(&self) -> String {
+        self.identity.to_string().expose_secret().to_string()
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        age::encrypt(&self.identity.to_public(), plaintext)
+            .map_err(|e| ChabaError::Other(anyhow::anyhow!("Encryption failed: {}", e)))
+    }
+
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        age::decrypt(&self.identity, ciphertext).map_err(|e| {
+            ChabaError::ConfigError(format!("Decryption failed (wrong key?): {}", e))
+        })
+    }
+
+    /// Encrypt the key itself with a passphrase
+    fn wrap(&self, passphrase: SecretString) -> Result<Vec<u8>> {
+        age::encrypt(&age::scrypt::Recipient::new(passphrase), self.secret().as_bytes())
+            .map_err(|e| ChabaError::Other(anyhow::anyhow!("Encryption failed: {}", e)))
+    }
+
+    /// Decrypt a key wrapped with [`StateKey::wrap`]
+    fn unwrap(wrapped: &[u8], passphrase: SecretString) -> Result<Self> {
+        let key = age::decrypt(&age::scrypt::Identity::new(passphrase), wrapped)
+            .map_err(|_| ChabaError::ConfigError("Wrong passphrase for the state key".to_string()))?;
+        Self::parse(&String::from_utf8_lossy(&key))
+    }
+}
+
+/// Whether `data` is age-encrypted
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(AGE_HEADER)
+}
+
+fn chaba_dir() -> Result<PathBuf> {
+    state::state_dir()
+}
+
+/// How the key is stored, or `None` when encryption is disabled
+pub fn storage() -> Result<Option<KeyStorage>> {
+    if std::env::var(KEY_ENV).is_ok_and(|key| !key.is_empty()) {
+        return Ok(Some(KeyStorage::Env));
+    }
+
+    let path = chaba_dir()?.join(SETTINGS_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let settings: Settings = serde_yaml::from_str(&std::fs::read_to_string(&path)?)?;
+    Ok(Some(settings.key))
+}
+
+/// Passphrase from `CHABA_PASSPHRASE`, or asked for on the terminal
+fn passphrase(confirm: bool) -> Result<SecretString> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        if !passphrase.is_empty() {
+            return Ok(SecretString::from(passphrase));
+        }
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(ChabaError::ConfigError(format!(
+            "The state key is protected by a passphrase; set {} to unlock it",
+            PASSPHRASE_ENV
+        )));
+    }
+
+    let mut prompt = dialoguer::Password::new().with_prompt("Chaba state passphrase");
+    if confirm {
+        prompt = prompt.with_confirmation("Repeat passphrase", "Passphrases don't match");
+    }
+    let passphrase = prompt.interact().map_err(|e| ChabaError::Other(e.into()))?;
+    Ok(SecretString::from(passphrase))
+}
+
+fn load_key(storage: KeyStorage) -> Result<StateKey> {
+    match storage {
+        KeyStorage::Env => StateKey::parse(&std::env::var(KEY_ENV).unwrap_or_default()),
+        KeyStorage::Keychain => StateKey::parse(&secrets::keychain_get(KEYCHAIN_NAME)?),
+        KeyStorage::Passphrase => {
+            let wrapped = std::fs::read(chaba_dir()?.join(KEY_FILE))?;
+            StateKey::unwrap(&wrapped, passphrase(false)?)
+        }
+    }
+}
+
+/// The key in use, or `None` when encryption is disabled
+///
+/// The key is looked up (and the passphrase asked for) once per process.
+pub fn key() -> Result<Option<&'static StateKey>> {
+    static KEY: OnceLock<Option<StateKey>> = OnceLock::new();
+
+    if let Some(key) = KEY.get() {
+        return Ok(key.as_ref());
+    }
+    let key = storage()?.map(load_key).transpose()?;
+    Ok(KEY.get_or_init(|| key).as_ref())
+}
+
+/// Encrypt `plaintext` for writing when encryption is enabled
+pub fn seal(plaintext: Vec<u8>) -> Result<Vec<u8>> {
+    match key()? {
+        Some(key) => key.encrypt(&plaintext),
+        None => Ok(plaintext),
+    }
+}
+
+/// Read a file written with [`seal`], decrypting it if needed
+pub fn read(path: &Path) -> Result<Vec<u8>> {
+    let data = std::fs::read(path)?;
+    if !is_encrypted(&data) {
+        return Ok(data);
+    }
+
+    match key()? {
+        Some(key) => key.decrypt(&data),
+        None => Err(ChabaError::ConfigError(format!(
+            "{} is encrypted, but no key is configured. Set {} to the key it was encrypted with.",
+            path.display(),
+            KEY_ENV
+        ))),
+    }
+}
+
+/// Read a UTF-8 file written with [`seal`]
+pub fn read_to_string(path: &Path) -> Result<String> {
+    String::from_utf8(read(path)?)
+        .map_err(|e| ChabaError::ConfigError(format!("{} is not valid UTF-8: {}", path.display(), e)))
+}
+
+/// State and snapshot env files that are encrypted when encryption is enabled
+pub fn protected_files() -> Result<Vec<PathBuf>> {
+    Ok(protected_files_in(&chaba_dir()?))
+}
+
+fn protected_files_in(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    let state = dir.join("state.yaml");
+    if state.exists() {
+        files.push(state);
+    }
+    // ~/.chaba/snapshots/<repo-hash>/pr-<number>/<id>/env/..., or
+    // pr-<number>/<id>/env/... for reviews without a repository
+    let reviews = read_dirs(&dir.join("snapshots")).into_iter().flat_map(|dir| {
+        let is_review = dir.file_name().is_some_and(|name| name.to_string_lossy().starts_with("pr-"));
+        if is_review {
+            vec![dir]
+        } else {
+            read_dirs(&dir)
+        }
+    });
+    for review in reviews {
+        for snapshot in read_dirs(&review) {
+            collect_files(&snapshot.join("env"), &mut files);
+        }
+    }
+
+    files
+}
+
+fn read_dirs(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|path| path.is_dir())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// Rewrite a file from one key to another; `None` means plaintext
+///
+/// Returns whether the file changed.
+pub fn rewrite(path: &Path, from: Option<&StateKey>, to: Option<&StateKey>) -> Result<bool> {
+    let data = std::fs::read(path)?;
+    if !is_encrypted(&data) && to.is_none() {
+        return Ok(false);
+    }
+    let plaintext = match (is_encrypted(&data), from) {
+        (false, _) => data,
+        (true, Some(from)) => from.decrypt(&data)?,
+        (true, None) => {
+            return Err(ChabaError::ConfigError(format!(
+                "{} is encrypted with another key",
+                path.display()
+            )))
+        }
+    };
+    let rewritten = match to {
+        Some(to) => to.encrypt(&plaintext)?,
+        None => plaintext,
+    };
+
+    std::fs::write(path, rewritten)?;
+    Ok(true)
+}
+
+/// Store a new key and record that encryption is enabled
+pub fn enable(key: &StateKey, storage: KeyStorage) -> Result<()> {
+    let dir = chaba_dir()?;
+    std::fs::create_dir_all(&dir)?;
+
+    match storage {
+        KeyStorage::Keychain => secrets::keychain_set(KEYCHAIN_NAME, &key.secret())?,
+        KeyStorage::Passphrase => std::fs::write(dir.join(KEY_FILE), key.wrap(passphrase(true)?)?)?,
+        KeyStorage::Env => {
+            return Err(ChabaError::ConfigError(format!(
+                "{} keys are managed outside chaba",
+                KEY_ENV
+            )))
+        }
+    }
+
+    let yaml = serde_yaml::to_string(&Settings { key: storage })?;
+    std::fs::write(dir.join(SETTINGS_FILE), yaml)?;
+    Ok(())
+}
+
+/// Remove the stored key and record that encryption is disabled
+pub fn disable(storage: KeyStorage) -> Result<()> {
+    let dir = chaba_dir()?;
+    match storage {
+        KeyStorage::Keychain => secrets::keychain_delete(KEYCHAIN_NAME)?,
+        KeyStorage::Passphrase => std::fs::remove_file(dir.join(KEY_FILE))?,
+        KeyStorage::Env => {}
+    }
+
+    match std::fs::remove_file(dir.join(SETTINGS_FILE)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_protected_files_in_both_snapshot_layouts() {
+        let temp_dir = TempDir::new().unwrap();
+        let snapshots = temp_dir.path().join("snapshots");
+        let keyed = snapshots.join("0123456789ab/pr-7/20240101-120000/env/.env");
+        let legacy = snapshots.join("pr-7/20240101-120000/env/.env");
+        for file in [&keyed, &legacy] {
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, "SECRET=1\n").unwrap();
+        }
+        std::fs::write(snapshots.join("0123456789ab/pr-7/20240101-120000/snapshot.json"), "{}").unwrap();
+
+        let mut files = protected_files_in(temp_dir.path());
+        files.sort();
+        let mut expected = vec![keyed, legacy];
+        expected.sort();
+        assert_eq!(files, expected);
+    }
+
+    #[test]
+    fn test_encrypt_round_trip() {
+        let key = StateKey::generate();
+        let ciphertext = key.encrypt(b"reviews: []\n").unwrap();
+
+        assert!(is_encrypted(&ciphertext));
+        assert!(!is_encrypted(b"reviews: []\n"));
+        assert_eq!(key.decrypt(&ciphertext).unwrap(), b"reviews: []\n");
+        assert!(StateKey::generate().decrypt(&ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_parse_key() {
+        let key = StateKey::generate();
+        let parsed = StateKey::parse(&format!("{}\n", key.secret())).unwrap();
+
+        assert_eq!(parsed.secret(), key.secret());
+        assert!(StateKey::parse("not a key").is_err());
+    }
+
+    #[test]
+    fn test_wrap_key_with_passphrase() {
+        let key = StateKey::generate();
+        let wrapped = key.wrap(SecretString::from("correct horse".to_string())).unwrap();
+
+        let unwrapped = StateKey::unwrap(&wrapped, SecretString::from("correct horse".to_string())).unwrap();
+        assert_eq!(unwrapped.secret(), key.secret());
+        assert!(StateKey::unwrap(&wrapped, SecretString::from("wrong".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_rewrite_between_keys() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(".env");
+        std::fs::write(&path, "API_KEY=secret\n").unwrap();
+        let key = StateKey::generate();
+
+        assert!(rewrite(&path, None, Some(&key)).unwrap());
+        let data = std::fs::read(&path).unwrap();
+        assert!(is_encrypted(&data));
+
+        assert!(rewrite(&path, None, None).is_err());
+        assert!(rewrite(&path, Some(&key), None).unwrap());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "API_KEY=secret\n");
+        // Already plaintext
+        assert!(!rewrite(&path, None, None).unwrap());
+    }
+}
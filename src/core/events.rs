@@ -0,0 +1,142 @@
+//! Per-review event log
+//!
+//! What happened to a review environment (created or adopted, dependencies
+//! installed, agents started and finished, branches merged in, cleaned up) is
+//! appended to `~/.chaba/logs/<repo-hash>/pr-<number>/events.jsonl` with a
+//! timestamp. The log is never rewritten and outlives the review, so `chaba events --pr <n>` and
+//! the TUI timeline can show how an environment got into its current state.
+//! Recording is best effort: a failure to write is only logged.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+use crate::core::logs::ReviewLogs;
+use crate::core::state::ReviewKey;
+use crate::error::{ChabaError, Result};
+
+/// Kind of a review event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// The worktree was created
+    Created,
+    /// A worktree created outside chaba was taken over (`chaba adopt`)
+    Adopted,
+    /// Project dependencies were installed
+    DepsInstalled,
+    /// An agent run started
+    AgentStarted,
+    /// An agent run finished, successfully or not
+    AgentFinished,
+    /// A branch was merged into the worktree
+    Merged,
+    /// The worktree was removed
+    CleanedUp,
+    /// Chaba stopped tracking the review but kept the worktree
+    Detached,
+}
+
+impl std::fmt::Display for EventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            EventKind::Created => "created",
+            EventKind::Adopted => "adopted",
+            EventKind::DepsInstalled => "deps_installed",
+            EventKind::AgentStarted => "agent_started",
+            EventKind::AgentFinished => "agent_finished",
+            EventKind::Merged => "merged",
+            EventKind::CleanedUp => "cleaned_up",
+            EventKind::Detached => "detached",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// One line of the event log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    pub at: DateTime<Utc>,
+    pub event: EventKind,
+    /// e.g. the agent name or merged branch
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub detail: String,
+}
+
+/// Record an event for a review
+pub fn record(key: &ReviewKey, kind: EventKind, detail: impl Into<String>) {
+    match ReviewLogs::for_review(key) {
+        Ok(logs) => record_in(&logs, kind, detail),
+        Err(e) => tracing::warn!("Failed to record {} event: {}", kind, e),
+    }
+}
+
+/// Record an event in a specific review's logs
+pub fn record_in(logs: &ReviewLogs, kind: EventKind, detail: impl Into<String>) {
+    let event = Event {
+        at: Utc::now(),
+        event: kind,
+        detail: detail.into(),
+    };
+    if let Err(e) = append(logs, &event) {
+        tracing::warn!("Failed to record {} event: {}", kind, e);
+    }
+}
+
+fn append(logs: &ReviewLogs, event: &Event) -> Result<()> {
+    let path = logs.events_log();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let line = serde_json::to_string(event).map_err(|e| ChabaError::Other(e.into()))?;
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// Events of a review, oldest first
+///
+/// Lines that cannot be parsed (e.g. cut off by a crash) are skipped.
+pub fn load(logs: &ReviewLogs) -> Result<Vec<Event>> {
+    let path = logs.events_log();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let logs = ReviewLogs::at(dir.path().join("pr-42"));
+        assert!(load(&logs).unwrap().is_empty());
+
+        record_in(&logs, EventKind::Created, "feature/login");
+        record_in(&logs, EventKind::AgentStarted, "claude");
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(logs.events_log())
+            .and_then(|mut file| writeln!(file, "{{\"at\":"))
+            .unwrap();
+        record_in(&logs, EventKind::CleanedUp, "");
+
+        let events = load(&logs).unwrap();
+        assert_eq!(
+            events.iter().map(|e| e.event).collect::<Vec<_>>(),
+            vec![EventKind::Created, EventKind::AgentStarted, EventKind::CleanedUp]
+        );
+        assert_eq!(events[1].detail, "claude");
+
+        let content = std::fs::read_to_string(logs.events_log()).unwrap();
+        assert!(content.contains(r#""event":"agent_started","detail":"claude""#));
+        assert!(!content.lines().last().unwrap().contains("detail"));
+    }
+}
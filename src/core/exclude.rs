@@ -0,0 +1,220 @@
+//! Keep chaba-injected files out of git
+//!
+//! Env files, copied config files, and chaba's own files in `.chaba/` are
+//! added to the repository's `info/exclude` so they don't show up as
+//! untracked changes, agents don't flag them, and injected secrets aren't
+//! committed by accident.
+//!
+//! Git reads `info/exclude` from the common git directory, so the entries
+//! apply to the main worktree and every review worktree alike. `.chaba/`
+//! itself isn't excluded: `.chaba/baseline.yaml` and
+//! `.chaba/env.overrides.yaml` are meant to be committed.
+
+use git2::Repository;
+use std::path::{Path, PathBuf};
+
+use crate::config::SandboxConfig;
+use crate::error::{ChabaError, Result};
+
+/// Header written above chaba's entries in `info/exclude`
+const EXCLUDE_HEADER: &str = "# Added by chaba: files injected into review worktrees";
+
+/// Files chaba writes into `.chaba/` of a worktree
+const INJECTED_FILES: &[&str] = &[
+    "/.chaba/review.json",
+    "/.chaba/anonymize-map.json",
+    "/.chaba/snapshot.index",
+    // Scratch patches of `chaba apply` and `chaba apply-fix`
+    "/.chaba/*.patch",
+    "/.chaba/*.code-workspace",
+];
+
+/// Patterns earlier versions added that now hide files meant to be committed
+const OUTDATED_PATTERNS: &[&str] = &["/.chaba/"];
+
+/// Exclude patterns for everything chaba may write into a worktree
+pub fn injected_patterns(config: &SandboxConfig) -> Vec<String> {
+    let mut patterns: Vec<String> = INJECTED_FILES.iter().map(|f| f.to_string()).collect();
+
+    if config.copy_env_from_main {
+        patterns.push("/.env".to_string());
+        patterns.extend(config.additional_env_files.iter().map(|f| anchor(f)));
+    }
+
+    patterns.extend(config.copy_files.iter().map(|f| anchor(f)));
+    patterns.dedup();
+    patterns
+}
+
+/// Anchor a worktree-relative path to the repository root
+fn anchor(path: &str) -> String {
+    format!("/{}", path.trim_start_matches("./").trim_start_matches('/'))
+}
+
+/// Add `patterns` to the `info/exclude` of the repository owning `worktree_path`
+///
+/// Patterns already present are skipped, and outdated ones chaba added
+/// below its header are dropped. Returns the number of patterns added.
+pub fn exclude_patterns(worktree_path: &Path, patterns: &[String]) -> Result<usize> {
+    let repo = Repository::open(worktree_path).map_err(|_| ChabaError::NotInGitRepo)?;
+    let info_dir = common_dir(repo.path()).join("info");
+    let exclude_path = info_dir.join("exclude");
+
+    let original = std::fs::read_to_string(&exclude_path).unwrap_or_default();
+    let existing = without_outdated(&original);
+    let missing: Vec<&String> = patterns
+        .iter()
+        .filter(|p| !existing.lines().any(|line| line.trim() == p.as_str()))
+        .collect();
+
+    if missing.is_empty() && existing.lines().count() == original.lines().count() {
+        return Ok(0);
+    }
+
+    let mut content = existing.clone();
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    if !existing.lines().any(|line| line == EXCLUDE_HEADER) {
+        content.push_str(EXCLUDE_HEADER);
+        content.push('\n');
+    }
+    for pattern in &missing {
+        content.push_str(pattern);
+        content.push('\n');
+    }
+
+    std::fs::create_dir_all(&info_dir)?;
+    std::fs::write(&exclude_path, content)?;
+
+    Ok(missing.len())
+}
+
+/// `content` of an exclude file without the outdated patterns below chaba's header
+fn without_outdated(content: &str) -> String {
+    let mut ours = false;
+    let mut kept = String::new();
+    for line in content.lines() {
+        ours |= line == EXCLUDE_HEADER;
+        if ours && OUTDATED_PATTERNS.contains(&line.trim()) {
+            continue;
+        }
+        kept.push_str(line);
+        kept.push('\n');
+    }
+    kept
+}
+
+/// Common git directory shared by all worktrees of a repository
+///
+/// A linked worktree's git directory records the common directory in its
+/// `commondir` file; the main worktree's git directory is the common one.
+fn common_dir(git_dir: &Path) -> PathBuf {
+    match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(content) => git_dir.join(content.trim()),
+        Err(_) => git_dir.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_injected_patterns() {
+        let config = SandboxConfig {
+            additional_env_files: vec![".env.local".to_string()],
+            copy_files: vec!["./certs/**".to_string()],
+            ..SandboxConfig::default()
+        };
+
+        assert_eq!(
+            injected_patterns(&config),
+            vec![
+                "/.chaba/review.json",
+                "/.chaba/anonymize-map.json",
+                "/.chaba/snapshot.index",
+                "/.chaba/*.patch",
+                "/.chaba/*.code-workspace",
+                "/.env",
+                "/.env.local",
+                "/certs/**"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_injected_patterns_without_env_copy() {
+        let config = SandboxConfig {
+            copy_env_from_main: false,
+            ..SandboxConfig::default()
+        };
+
+        assert_eq!(injected_patterns(&config), INJECTED_FILES);
+    }
+
+    #[test]
+    fn test_exclude_patterns_is_idempotent() {
+        let temp_dir = TempDir::new().unwrap();
+        Repository::init(temp_dir.path()).unwrap();
+        let patterns = vec!["/.chaba/review.json".to_string(), "/.env".to_string()];
+
+        assert_eq!(exclude_patterns(temp_dir.path(), &patterns).unwrap(), 2);
+        assert_eq!(exclude_patterns(temp_dir.path(), &patterns).unwrap(), 0);
+
+        let content = std::fs::read_to_string(temp_dir.path().join(".git/info/exclude")).unwrap();
+        assert_eq!(content.matches(EXCLUDE_HEADER).count(), 1);
+        assert_eq!(content.matches("/.env\n").count(), 1);
+    }
+
+    #[test]
+    fn test_excluded_files_are_not_untracked() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        std::fs::write(temp_dir.path().join(".env"), "API_KEY=secret").unwrap();
+
+        exclude_patterns(temp_dir.path(), &["/.env".to_string()]).unwrap();
+
+        assert!(repo.is_path_ignored(".env").unwrap());
+    }
+
+    #[test]
+    fn test_linked_worktree_uses_common_exclude() {
+        let temp_dir = TempDir::new().unwrap();
+        let main = temp_dir.path().join("main");
+        let repo = Repository::init(&main).unwrap();
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+
+        let review = temp_dir.path().join("pr-1");
+        repo.worktree("pr-1", &review, None).unwrap();
+
+        exclude_patterns(&review, &["/.chaba/review.json".to_string()]).unwrap();
+
+        let content = std::fs::read_to_string(main.join(".git/info/exclude")).unwrap();
+        assert!(content.lines().any(|line| line == "/.chaba/review.json"));
+    }
+
+    #[test]
+    fn test_committed_chaba_files_are_not_ignored() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let exclude = temp_dir.path().join(".git/info/exclude");
+        std::fs::create_dir_all(exclude.parent().unwrap()).unwrap();
+        std::fs::write(&exclude, format!("*.swp\n{}\n/.chaba/\n/.env\n", EXCLUDE_HEADER)).unwrap();
+
+        exclude_patterns(temp_dir.path(), &injected_patterns(&SandboxConfig::default())).unwrap();
+
+        assert!(!repo.is_path_ignored(".chaba/baseline.yaml").unwrap());
+        assert!(!repo.is_path_ignored(".chaba/env.overrides.yaml").unwrap());
+        assert!(repo.is_path_ignored(".chaba/review.json").unwrap());
+        assert!(repo.is_path_ignored(".chaba/apply-fix.patch").unwrap());
+        let content = std::fs::read_to_string(&exclude).unwrap();
+        assert!(content.starts_with("*.swp\n"));
+        assert_eq!(content.matches("/.env\n").count(), 1);
+    }
+}
@@ -0,0 +1,225 @@
+//! Agent analysis history
+//!
+//! Each agent run replaces a review's current analyses, but the run before
+//! it is kept in the review's history, one entry per commit. `chaba
+//! agent-result --diff` compares the latest run with the previous one to
+//! show which findings are new, resolved, or still there after the PR was
+//! updated.
+//!
+//! Findings are matched by their baseline fingerprint (file and title), so
+//! a finding that moved to another line or was reported by a different
+//! agent still counts as the same one.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::core::baseline;
+use crate::core::review_analysis::{Finding, ReviewAnalysis};
+use crate::core::state::ReviewState;
+
+/// Number of earlier runs kept per review
+pub const MAX_RUNS: usize = 10;
+
+/// Analyses from one agent run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisRun {
+    /// Commit the agents reviewed, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    pub analyses: Vec<ReviewAnalysis>,
+}
+
+impl AnalysisRun {
+    /// Abbreviated commit for display
+    pub fn short_commit(&self) -> &str {
+        short(self.commit.as_deref())
+    }
+}
+
+/// Abbreviated commit, or `unknown` for runs recorded without one
+pub fn short(commit: Option<&str>) -> &str {
+    match commit {
+        Some(commit) => &commit[..commit.len().min(7)],
+        None => "unknown",
+    }
+}
+
+/// Store a new run as the review's current analyses
+///
+/// The current run moves into the history unless the new run is for the
+/// same commit, in which case it is simply replaced. Only the latest run
+/// per commit and the last [`MAX_RUNS`] runs are kept.
+pub fn record(review: &mut ReviewState, analyses: Vec<ReviewAnalysis>, commit: Option<String>) {
+    let same_commit = commit.is_some() && commit == review.analysis_commit;
+    let previous = std::mem::replace(&mut review.agent_analyses, analyses);
+    let previous_commit = std::mem::replace(&mut review.analysis_commit, commit);
+
+    if same_commit || previous.is_empty() {
+        return;
+    }
+
+    if previous_commit.is_some() {
+        review.analysis_history.retain(|run| run.commit != previous_commit);
+    }
+    review.analysis_history.push(AnalysisRun {
+        commit: previous_commit,
+        analyses: previous,
+    });
+
+    let excess = review.analysis_history.len().saturating_sub(MAX_RUNS);
+    review.analysis_history.drain(..excess);
+}
+
+/// The run before the current one, if any
+pub fn previous(review: &ReviewState) -> Option<&AnalysisRun> {
+    review.analysis_history.last()
+}
+
+/// A finding and the agent that reported it
+#[derive(Debug, Clone)]
+pub struct AgentFinding {
+    pub agent: String,
+    pub finding: Finding,
+}
+
+/// How findings changed between two runs
+#[derive(Debug, Clone, Default)]
+pub struct FindingDiff {
+    /// Reported now but not before
+    pub new: Vec<AgentFinding>,
+    /// Reported before but not now
+    pub resolved: Vec<AgentFinding>,
+    /// Reported in both runs (as reported now)
+    pub persisting: Vec<AgentFinding>,
+    /// Agents left out because they only ran in one of the two runs
+    pub skipped_agents: Vec<String>,
+}
+
+/// Compare the findings of two runs
+///
+/// Only agents that ran both times are compared, so an agent that was added
+/// or dropped doesn't make all of its findings look new or resolved.
+pub fn diff(previous: &[ReviewAnalysis], current: &[ReviewAnalysis]) -> FindingDiff {
+    let agents_before: HashSet<&str> = previous.iter().map(|a| a.agent.as_str()).collect();
+    let agents_now: HashSet<&str> = current.iter().map(|a| a.agent.as_str()).collect();
+
+    let mut skipped_agents: Vec<String> = agents_before
+        .symmetric_difference(&agents_now)
+        .map(|agent| agent.to_string())
+        .collect();
+    skipped_agents.sort();
+
+    let before = unique_findings(previous, &agents_now);
+    let now = unique_findings(current, &agents_before);
+    let fingerprints_before: HashSet<&str> = before.iter().map(|(fp, _)| fp.as_str()).collect();
+    let fingerprints_now: HashSet<&str> = now.iter().map(|(fp, _)| fp.as_str()).collect();
+
+    let mut result = FindingDiff {
+        skipped_agents,
+        ..FindingDiff::default()
+    };
+    for (fingerprint, finding) in &now {
+        if fingerprints_before.contains(fingerprint.as_str()) {
+            result.persisting.push(finding.clone());
+        } else {
+            result.new.push(finding.clone());
+        }
+    }
+    result.resolved = before
+        .into_iter()
+        .filter(|(fingerprint, _)| !fingerprints_now.contains(fingerprint.as_str()))
+        .map(|(_, finding)| finding)
+        .collect();
+
+    result
+}
+
+/// Findings of the agents in `agents`, one per fingerprint, in report order
+fn unique_findings(analyses: &[ReviewAnalysis], agents: &HashSet<&str>) -> Vec<(String, AgentFinding)> {
+    let mut seen = HashSet::new();
+    analyses
+        .iter()
+        .filter(|analysis| agents.contains(analysis.agent.as_str()))
+        .flat_map(|analysis| analysis.findings.iter().map(move |finding| (&analysis.agent, finding)))
+        .filter_map(|(agent, finding)| {
+            let fingerprint = baseline::fingerprint(finding);
+            seen.insert(fingerprint.clone()).then(|| {
+                (
+                    fingerprint,
+                    AgentFinding {
+                        agent: agent.clone(),
+                        finding: finding.clone(),
+                    },
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::review_analysis::{Category, Severity};
+
+    fn analysis(agent: &str, findings: &[(&str, u32, &str)]) -> ReviewAnalysis {
+        let mut analysis = ReviewAnalysis::new(agent.to_string());
+        for (file, line, title) in findings {
+            analysis.add_finding(
+                Finding::new(Severity::High, Category::Security, title.to_string(), String::new())
+                    .with_file(file.to_string())
+                    .with_line(*line),
+            );
+        }
+        analysis
+    }
+
+    fn review() -> ReviewState {
+        ReviewState {
+            branch: "feature/login".to_string(),
+            ..ReviewState::for_test(42, "/tmp/pr-42")
+        }
+    }
+
+    #[test]
+    fn test_diff_new_resolved_persisting() {
+        let previous = vec![
+            analysis("claude", &[("src/auth.rs", 10, "Token is logged"), ("src/db.rs", 5, "SQL injection")]),
+            analysis("codex", &[("src/api.rs", 1, "Missing timeout")]),
+        ];
+        let current = vec![
+            analysis("claude", &[("src/auth.rs", 14, "Token is logged"), ("src/cache.rs", 3, "Unbounded cache")]),
+            analysis("gemini", &[("src/ui.rs", 2, "Unescaped HTML")]),
+        ];
+
+        let diff = diff(&previous, &current);
+
+        let titles = |findings: &[AgentFinding]| findings.iter().map(|f| f.finding.title.clone()).collect::<Vec<_>>();
+        assert_eq!(titles(&diff.new), vec!["Unbounded cache"]);
+        assert_eq!(titles(&diff.resolved), vec!["SQL injection"]);
+        assert_eq!(titles(&diff.persisting), vec!["Token is logged"]);
+        assert_eq!(diff.persisting[0].finding.line, Some(14));
+        assert_eq!(diff.skipped_agents, vec!["codex", "gemini"]);
+    }
+
+    #[test]
+    fn test_record_keeps_one_run_per_commit() {
+        let mut review = review();
+
+        record(&mut review, vec![analysis("claude", &[])], Some("aaa".to_string()));
+        assert!(review.analysis_history.is_empty());
+
+        // Re-running on the same commit replaces the current run
+        record(&mut review, vec![analysis("claude", &[])], Some("aaa".to_string()));
+        assert!(review.analysis_history.is_empty());
+
+        record(&mut review, vec![analysis("claude", &[])], Some("bbb".to_string()));
+        assert_eq!(review.analysis_commit.as_deref(), Some("bbb"));
+        assert_eq!(previous(&review).unwrap().commit.as_deref(), Some("aaa"));
+
+        for i in 0..MAX_RUNS + 5 {
+            record(&mut review, vec![analysis("claude", &[])], Some(format!("c{}", i)));
+        }
+        assert_eq!(review.analysis_history.len(), MAX_RUNS);
+        assert_eq!(previous(&review).unwrap().short_commit(), format!("c{}", MAX_RUNS + 3));
+    }
+}
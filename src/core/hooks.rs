@@ -1,18 +1,25 @@
 use std::path::Path;
 use std::process::Stdio;
-use tokio::process::Command;
 
 use crate::config::HooksConfig;
+use crate::core::logs::ReviewLogs;
 
 /// Hook execution manager
 pub struct HookManager {
     config: HooksConfig,
+    logs: Option<ReviewLogs>,
 }
 
 impl HookManager {
     /// Create a new HookManager
     pub fn new(config: HooksConfig) -> Self {
-        HookManager { config }
+        HookManager { config, logs: None }
+    }
+
+    /// Write hook output to the review's hooks log
+    pub fn with_logs(mut self, logs: ReviewLogs) -> Self {
+        self.logs = Some(logs);
+        self
     }
 
     /// Run post-create hook asynchronously
@@ -38,16 +45,17 @@ impl HookManager {
         };
 
         let command = hook_command.clone();
+        let shell = self.config.shell.unwrap_or_default();
         let path = worktree_path.to_path_buf();
         let branch_name = branch.to_string();
+        let logs = self.logs.clone();
 
         // Spawn async task to run hook in background
         tokio::spawn(async move {
             tracing::info!("Running post-create hook in background");
 
-            let result = Command::new("sh")
-                .arg("-c")
-                .arg(&command)
+            let result = shell
+                .command(&command)
                 .env("CHABA_WORKTREE_PATH", &path)
                 .env("CHABA_BRANCH", &branch_name)
                 .env("CHABA_PR", pr_number.to_string())
@@ -57,6 +65,12 @@ impl HookManager {
                 .output()
                 .await;
 
+            if let (Some(logs), Ok(output)) = (&logs, &result) {
+                if let Err(e) = logs.append_output(&logs.hooks_log(), "post_create", output).await {
+                    tracing::warn!("Failed to write hooks log: {}", e);
+                }
+            }
+
             match result {
                 Ok(output) => {
                     if output.status.success() {
@@ -91,27 +105,28 @@ impl HookManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
 
     #[test]
     fn test_hook_manager_no_hook() {
         let config = HooksConfig {
             post_create: None,
+            shell: None,
         };
         let manager = HookManager::new(config);
 
         // Should not panic when no hook is configured
-        manager.run_post_create(&PathBuf::from("/tmp"), "test-branch", 123);
+        manager.run_post_create(&std::env::temp_dir(), "test-branch", 123);
     }
 
     #[tokio::test]
     async fn test_hook_manager_with_simple_command() {
         let config = HooksConfig {
-            post_create: Some("echo 'Hello from hook'".to_string()),
+            post_create: Some("echo Hello from hook".to_string()),
+            shell: None,
         };
         let manager = HookManager::new(config);
 
-        manager.run_post_create(&PathBuf::from("/tmp"), "test-branch", 123);
+        manager.run_post_create(&std::env::temp_dir(), "test-branch", 123);
 
         // Give the background task some time to execute
         tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
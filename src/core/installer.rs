@@ -1,24 +1,38 @@
+use std::ffi::{OsStr, OsString};
 use std::path::Path;
+use std::process::Output;
 use tokio::process::Command;
 
+use crate::config::DependencyCacheConfig;
+use crate::core::dep_cache;
+use crate::core::logs::ReviewLogs;
+use crate::core::platform;
 use crate::core::project::{NodePackageManager, ProjectType};
+use crate::core::trace;
 use crate::error::Result;
 
 /// Install dependencies for the given project type
+///
+/// For Node.js projects, `cache` controls whether caches and `node_modules`
+/// from `main_worktree` are reused. Command output is appended to the
+/// install log when `logs` is given.
 pub async fn install_dependencies(
     worktree_path: &Path,
+    main_worktree: &Path,
     project_type: &ProjectType,
+    cache: &DependencyCacheConfig,
+    logs: Option<&ReviewLogs>,
 ) -> Result<()> {
     match project_type {
         ProjectType::NodeJs { package_manager } => {
-            install_node_deps(worktree_path, package_manager).await
+            install_node_deps(worktree_path, main_worktree, package_manager, cache, logs).await
         }
-        ProjectType::Rust => install_rust_deps(worktree_path).await,
+        ProjectType::Rust => install_rust_deps(worktree_path, logs).await,
         ProjectType::Python {
             has_requirements,
             has_pyproject,
-        } => install_python_deps(worktree_path, *has_requirements, *has_pyproject).await,
-        ProjectType::Go => install_go_deps(worktree_path).await,
+        } => install_python_deps(worktree_path, *has_requirements, *has_pyproject, logs).await,
+        ProjectType::Go => install_go_deps(worktree_path, logs).await,
         ProjectType::Unknown => {
             tracing::info!("Unknown project type, skipping dependency installation");
             Ok(())
@@ -27,17 +41,36 @@ pub async fn install_dependencies(
 }
 
 /// Install Node.js dependencies
-async fn install_node_deps(path: &Path, pm: &NodePackageManager) -> Result<()> {
+async fn install_node_deps(
+    path: &Path,
+    main_worktree: &Path,
+    pm: &NodePackageManager,
+    cache: &DependencyCacheConfig,
+    logs: Option<&ReviewLogs>,
+) -> Result<()> {
+    if cache.clone_node_modules {
+        match dep_cache::clone_node_modules(main_worktree, path, pm).await {
+            Ok(true) => {
+                tracing::info!("Reused node_modules from main worktree");
+                return Ok(());
+            }
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!("Failed to clone node_modules, falling back to install: {}", e);
+                // Remove partial copy so the install starts clean
+                let _ = tokio::fs::remove_dir_all(path.join("node_modules")).await;
+            }
+        }
+    }
+
     tracing::info!("Installing Node.js dependencies using {}...", pm.as_str());
 
     let parts: Vec<&str> = pm.install_command().split_whitespace().collect();
     let (cmd, args) = parts.split_first().unwrap();
+    let envs = dep_cache::cache_env(cache, pm, main_worktree).await;
 
-    let output = Command::new(cmd)
-        .args(args)
-        .current_dir(path)
-        .output()
-        .await?;
+    let output = run(cmd, args, path, envs).await?;
+    record(logs, pm.install_command(), &output).await;
 
     if !output.status.success() {
         let error = String::from_utf8_lossy(&output.stderr);
@@ -53,14 +86,11 @@ async fn install_node_deps(path: &Path, pm: &NodePackageManager) -> Result<()> {
 }
 
 /// Install Rust dependencies
-async fn install_rust_deps(path: &Path) -> Result<()> {
+async fn install_rust_deps(path: &Path, logs: Option<&ReviewLogs>) -> Result<()> {
     tracing::info!("Building Rust project...");
 
-    let output = Command::new("cargo")
-        .args(["build"])
-        .current_dir(path)
-        .output()
-        .await?;
+    let output = run("cargo", &["build"], path, Vec::new()).await?;
+    record(logs, "cargo build", &output).await;
 
     if !output.status.success() {
         let error = String::from_utf8_lossy(&output.stderr);
@@ -80,15 +110,13 @@ async fn install_python_deps(
     path: &Path,
     has_requirements: bool,
     has_pyproject: bool,
+    logs: Option<&ReviewLogs>,
 ) -> Result<()> {
     tracing::info!("Installing Python dependencies...");
 
     if has_requirements {
-        let output = Command::new("pip")
-            .args(["install", "-r", "requirements.txt"])
-            .current_dir(path)
-            .output()
-            .await?;
+        let output = run("pip", &["install", "-r", "requirements.txt"], path, Vec::new()).await?;
+        record(logs, "pip install -r requirements.txt", &output).await;
 
         if !output.status.success() {
             let error = String::from_utf8_lossy(&output.stderr);
@@ -101,11 +129,8 @@ async fn install_python_deps(
     }
 
     if has_pyproject {
-        let output = Command::new("pip")
-            .args(["install", "-e", "."])
-            .current_dir(path)
-            .output()
-            .await?;
+        let output = run("pip", &["install", "-e", "."], path, Vec::new()).await?;
+        record(logs, "pip install -e .", &output).await;
 
         if !output.status.success() {
             let error = String::from_utf8_lossy(&output.stderr);
@@ -119,14 +144,11 @@ async fn install_python_deps(
 }
 
 /// Install Go dependencies
-async fn install_go_deps(path: &Path) -> Result<()> {
+async fn install_go_deps(path: &Path, logs: Option<&ReviewLogs>) -> Result<()> {
     tracing::info!("Downloading Go modules...");
 
-    let output = Command::new("go")
-        .args(["mod", "download"])
-        .current_dir(path)
-        .output()
-        .await?;
+    let output = run("go", &["mod", "download"], path, Vec::new()).await?;
+    record(logs, "go mod download", &output).await;
 
     if !output.status.success() {
         let error = String::from_utf8_lossy(&output.stderr);
@@ -140,3 +162,19 @@ async fn install_go_deps(path: &Path) -> Result<()> {
     tracing::info!("Go modules downloaded successfully");
     Ok(())
 }
+
+/// Run an install command, traced when `--trace-commands` is on
+async fn run(program: &str, args: &[&str], dir: &Path, envs: Vec<(String, OsString)>) -> std::io::Result<Output> {
+    let trace_args: Vec<&OsStr> = args.iter().map(OsStr::new).collect();
+    let output = Command::new(platform::resolve_program(program)).args(args).envs(envs).current_dir(dir).output();
+    trace::traced(program, &trace_args, dir, output).await
+}
+
+/// Append command output to the install log, if logging is enabled
+async fn record(logs: Option<&ReviewLogs>, title: &str, output: &Output) {
+    if let Some(logs) = logs {
+        if let Err(e) = logs.append_output(&logs.install_log(), title, output).await {
+            tracing::warn!("Failed to write install log: {}", e);
+        }
+    }
+}
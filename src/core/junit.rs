@@ -0,0 +1,185 @@
+//! JUnit XML export of agent findings
+//!
+//! `chaba agent-result --junit <FILE>` writes a review's findings as a JUnit
+//! report that CI test-report UIs (Jenkins, GitLab CI) display without custom
+//! tooling. Each agent run becomes a test suite and each finding a test case
+//! that fails when the finding is high or critical. Dismissed and fixed
+//! findings are reported as skipped.
+
+use crate::core::review_analysis::{Finding, ReviewAnalysis, Severity, TriageStatus};
+
+/// Lowest severity reported as a test failure
+const FAILURE_SEVERITY: Severity = Severity::High;
+
+/// Render analyses as a JUnit XML document
+pub fn render(pr: u32, analyses: &[ReviewAnalysis]) -> String {
+    let tests: usize = analyses.iter().map(|a| a.findings.len()).sum();
+    let failures: usize = analyses.iter().map(failure_count).sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\">\n",
+        escape(&format!("chaba PR #{}", pr)),
+        tests,
+        failures
+    ));
+    for analysis in analyses {
+        render_suite(&mut xml, analysis);
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+/// Number of findings of an analysis reported as failures
+pub fn failure_count(analysis: &ReviewAnalysis) -> usize {
+    analysis.findings.iter().filter(|f| is_failure(f)).count()
+}
+
+fn is_failure(finding: &Finding) -> bool {
+    finding.severity >= FAILURE_SEVERITY && !is_skipped(finding)
+}
+
+fn is_skipped(finding: &Finding) -> bool {
+    matches!(finding.triage, TriageStatus::Dismissed | TriageStatus::Fixed)
+}
+
+fn render_suite(xml: &mut String, analysis: &ReviewAnalysis) {
+    let name = match &analysis.profile {
+        Some(profile) => format!("{} ({})", analysis.agent, profile),
+        None => analysis.agent.clone(),
+    };
+    let skipped = analysis.findings.iter().filter(|f| is_skipped(f)).count();
+    xml.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" timestamp=\"{}\">\n",
+        escape(&name),
+        analysis.findings.len(),
+        failure_count(analysis),
+        skipped,
+        escape(&analysis.timestamp)
+    ));
+
+    for finding in &analysis.findings {
+        let location = match (&finding.file, finding.line) {
+            (Some(file), Some(line)) => Some(format!("{}:{}", file, line)),
+            (Some(file), None) => Some(file.clone()),
+            _ => None,
+        };
+        let title = if finding.id.is_empty() {
+            finding.title.clone()
+        } else {
+            format!("[{}] {}", finding.id, finding.title)
+        };
+        xml.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"{}\"",
+            escape(&title),
+            escape(&format!("{}.{:?}", analysis.agent, finding.category).to_lowercase())
+        ));
+        if let Some(file) = &finding.file {
+            xml.push_str(&format!(" file=\"{}\"", escape(file)));
+        }
+        if let Some(line) = finding.line {
+            xml.push_str(&format!(" line=\"{}\"", line));
+        }
+
+        let severity = format!("{:?}", finding.severity).to_lowercase();
+        if is_skipped(finding) {
+            xml.push_str(&format!(">\n      <skipped message=\"{}\"/>\n    </testcase>\n", finding.triage));
+        } else if is_failure(finding) {
+            let mut details = Vec::new();
+            if let Some(location) = &location {
+                details.push(location.clone());
+            }
+            if !finding.description.is_empty() {
+                details.push(finding.description.clone());
+            }
+            if let Some(suggestion) = &finding.suggestion {
+                details.push(format!("Suggestion: {}", suggestion));
+            }
+            xml.push_str(&format!(
+                ">\n      <failure message=\"{}\" type=\"{}\">{}</failure>\n    </testcase>\n",
+                escape(&finding.title),
+                severity,
+                escape(&details.join("\n\n"))
+            ));
+        } else {
+            xml.push_str(&format!(
+                ">\n      <system-out>{}</system-out>\n    </testcase>\n",
+                escape(&format!("[{}] {}", severity, finding.description))
+            ));
+        }
+    }
+    xml.push_str("  </testsuite>\n");
+}
+
+/// Escape text for XML attributes and content, dropping characters XML 1.0 forbids
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::review_analysis::Category;
+
+    fn analysis() -> ReviewAnalysis {
+        let mut analysis = ReviewAnalysis::new("claude".to_string());
+        analysis.add_finding(
+            Finding::new(
+                Severity::Critical,
+                Category::Security,
+                "SQL built with <format!>".to_string(),
+                "User input reaches the \"query\"".to_string(),
+            )
+            .with_file("src/db.rs".to_string())
+            .with_line(12),
+        );
+        analysis.add_finding(Finding::new(
+            Severity::Low,
+            Category::Documentation,
+            "Missing docs".to_string(),
+            "Document the API".to_string(),
+        ));
+        let mut dismissed = Finding::new(
+            Severity::High,
+            Category::Performance,
+            "Slow loop".to_string(),
+            String::new(),
+        );
+        dismissed.triage = TriageStatus::Dismissed;
+        analysis.add_finding(dismissed);
+        analysis
+    }
+
+    #[test]
+    fn test_render() {
+        let xml = render(42, &[analysis(), ReviewAnalysis::new("gemini".to_string())]);
+
+        assert!(xml.contains("<testsuites name=\"chaba PR #42\" tests=\"3\" failures=\"1\">"));
+        assert!(xml.contains("<testsuite name=\"claude\" tests=\"3\" failures=\"1\" skipped=\"1\""));
+        assert!(xml.contains("<testsuite name=\"gemini\" tests=\"0\" failures=\"0\" skipped=\"0\""));
+        assert!(xml.contains("classname=\"claude.security\" file=\"src/db.rs\" line=\"12\">"));
+        assert!(xml.contains(
+            "<failure message=\"SQL built with &lt;format!&gt;\" type=\"critical\">src/db.rs:12\n\nUser input reaches the &quot;query&quot;</failure>"
+        ));
+        assert!(xml.contains("<system-out>[low] Document the API</system-out>"));
+        assert!(xml.contains("<skipped message=\"dismissed\"/>"));
+    }
+
+    #[test]
+    fn test_escape_drops_control_characters() {
+        assert_eq!(escape("a\u{1b}[31mb & 'c'\n"), "a[31mb &amp; &apos;c&apos;\n");
+    }
+}
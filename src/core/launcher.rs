@@ -0,0 +1,165 @@
+//! Machine-readable review lists for macOS launchers
+//!
+//! `chaba list --output alfred` prints an Alfred Script Filter document and
+//! `--output raycast` a list for Raycast extensions. Each review becomes an
+//! item that opens its worktree, with a secondary action that cleans it up.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+use crate::core::stack;
+use crate::core::state::ReviewState;
+
+/// A launcher action, expressed as a chaba command line
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LauncherAction {
+    pub title: String,
+    /// Program and arguments to run
+    pub command: Vec<String>,
+}
+
+/// A review as shown in a Raycast list
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RaycastItem {
+    pub id: String,
+    pub title: String,
+    pub subtitle: String,
+    /// Worktree to open for the primary action
+    pub path: PathBuf,
+    /// Short labels shown on the right (tests, port, stack parent)
+    pub accessories: Vec<String>,
+    pub actions: Vec<LauncherAction>,
+}
+
+fn title(review: &ReviewState) -> String {
+    format!("PR #{} — {}", review.pr_number, review.branch)
+}
+
+fn cleanup_command(review: &ReviewState) -> Vec<String> {
+    ["chaba", "cleanup", "--pr", &review.pr_number.to_string(), "--force"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+fn accessories(review: &ReviewState) -> Vec<String> {
+    let mut accessories = Vec::new();
+    if !review.worktree_path.exists() {
+        accessories.push("missing".to_string());
+    }
+    if let Some(tests) = &review.tests {
+        accessories.push(format!("tests {}", if tests.passed { "✓" } else { "✗" }));
+    }
+    if let Some(port) = review.port {
+        accessories.push(format!(":{}", port));
+    }
+    if let Some(parent) = review.parent_pr {
+        accessories.push(format!("on #{}", parent));
+    }
+    accessories
+}
+
+/// Raycast list items, in stack order
+pub fn raycast_items(reviews: &[ReviewState]) -> Vec<RaycastItem> {
+    stack::stack_order(reviews)
+        .into_iter()
+        .map(|(_, review)| RaycastItem {
+            id: format!("pr-{}", review.pr_number),
+            title: title(review),
+            subtitle: review.worktree_path.display().to_string(),
+            path: review.worktree_path.clone(),
+            accessories: accessories(review),
+            actions: vec![LauncherAction {
+                title: "Clean Up Review Environment".to_string(),
+                command: cleanup_command(review),
+            }],
+        })
+        .collect()
+}
+
+/// Alfred Script Filter JSON (`{"items": [...]}`)
+///
+/// `arg` is the worktree path, so the default action opens it; holding ⌘
+/// passes the cleanup command instead.
+pub fn alfred_items(reviews: &[ReviewState]) -> Value {
+    let items: Vec<Value> = stack::stack_order(reviews)
+        .into_iter()
+        .map(|(_, review)| {
+            let path = review.worktree_path.display().to_string();
+            let mut subtitle = path.clone();
+            let accessories = accessories(review);
+            if !accessories.is_empty() {
+                subtitle = format!("{} · {}", subtitle, accessories.join(" · "));
+            }
+
+            json!({
+                "uid": format!("pr-{}", review.pr_number),
+                "title": title(review),
+                "subtitle": subtitle,
+                "arg": path,
+                "type": "file",
+                "valid": review.worktree_path.exists(),
+                "text": { "copy": path },
+                "variables": { "pr": review.pr_number.to_string() },
+                "mods": {
+                    "cmd": {
+                        "valid": true,
+                        "subtitle": "Clean up review environment",
+                        "arg": cleanup_command(review).join(" "),
+                    }
+                },
+            })
+        })
+        .collect();
+
+    json!({ "items": items })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn review(pr_number: u32, worktree_path: PathBuf) -> ReviewState {
+        ReviewState {
+            branch: "feature/login".to_string(),
+            port: Some(3001),
+            ..ReviewState::for_test(pr_number, worktree_path)
+        }
+    }
+
+    #[test]
+    fn test_raycast_items() {
+        let temp_dir = TempDir::new().unwrap();
+        let items = raycast_items(&[review(123, temp_dir.path().to_path_buf())]);
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, "pr-123");
+        assert_eq!(items[0].title, "PR #123 — feature/login");
+        assert_eq!(items[0].accessories, vec![":3001"]);
+        assert_eq!(
+            items[0].actions[0].command,
+            vec!["chaba", "cleanup", "--pr", "123", "--force"]
+        );
+    }
+
+    #[test]
+    fn test_alfred_items() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = temp_dir.path().join("gone");
+        let value = alfred_items(&[review(7, missing.clone())]);
+
+        let item = &value["items"][0];
+        assert_eq!(item["uid"], "pr-7");
+        assert_eq!(item["arg"], missing.display().to_string());
+        assert_eq!(item["valid"], false);
+        assert!(item["subtitle"].as_str().unwrap().ends_with("· missing · :3001"));
+        assert_eq!(item["mods"]["cmd"]["arg"], "chaba cleanup --pr 7 --force");
+    }
+
+    #[test]
+    fn test_alfred_items_empty() {
+        assert_eq!(alfred_items(&[]), json!({ "items": [] }));
+    }
+}
@@ -0,0 +1,281 @@
+//! Per-review log files
+//!
+//! Dependency installation, seeding, hook, test, and agent outputs are written to
+//! `~/.chaba/logs/<repo-hash>/pr-<number>/` so they can be inspected after the fact
+//! with `chaba logs`. The full stdout/stderr of each agent run is also kept
+//! as a transcript, shown by `chaba agent-result --raw`, and an event log
+//! records what happened to the review (see [`crate::core::events`]).
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use tokio::io::AsyncWriteExt;
+
+use crate::core::state::{self, ReviewKey};
+use crate::error::Result;
+
+/// Log files for a single review environment
+#[derive(Debug, Clone)]
+pub struct ReviewLogs {
+    dir: PathBuf,
+}
+
+impl ReviewLogs {
+    /// Logs for the given review under `~/.chaba/logs/<repo-hash>/pr-<number>/`
+    pub fn for_review(key: &ReviewKey) -> Result<Self> {
+        Ok(Self::at(state::state_dir()?.join("logs").join(key.dir_name())))
+    }
+
+    /// Logs rooted at a specific directory
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        ReviewLogs { dir: dir.into() }
+    }
+
+    /// Log directory for this review
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Dependency installation log
+    pub fn install_log(&self) -> PathBuf {
+        self.dir.join("install.log")
+    }
+
+    /// Hook execution log
+    pub fn hooks_log(&self) -> PathBuf {
+        self.dir.join("hooks.log")
+    }
+
+    /// Test suite log
+    pub fn tests_log(&self) -> PathBuf {
+        self.dir.join("tests.log")
+    }
+
+    /// Data seeding log
+    pub fn seed_log(&self) -> PathBuf {
+        self.dir.join("seed.log")
+    }
+
+    /// Output of actions started from the TUI that failed
+    pub fn actions_log(&self) -> PathBuf {
+        self.dir.join("actions.log")
+    }
+
+    /// Event log, see [`crate::core::events`]
+    pub fn events_log(&self) -> PathBuf {
+        self.dir.join("events.jsonl")
+    }
+
+    /// Log for a single agent
+    pub fn agent_log(&self, agent: &str) -> PathBuf {
+        self.dir.join("agents").join(format!("{}.log", agent))
+    }
+
+    /// Transcript of one agent run started at `started`
+    ///
+    /// `agent` may include a profile, e.g. `claude-security`.
+    pub fn transcript_log(&self, agent: &str, started: chrono::DateTime<chrono::Local>) -> PathBuf {
+        self.dir
+            .join("agents")
+            .join(format!("{}-{}.log", agent, started.format(TRANSCRIPT_TIME_FORMAT)))
+    }
+
+    /// List all existing log files, in a stable order
+    ///
+    /// Agent transcripts are left out; they repeat the agent logs.
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+
+        for path in [
+            self.install_log(),
+            self.seed_log(),
+            self.hooks_log(),
+            self.tests_log(),
+            self.actions_log(),
+        ] {
+            if path.is_file() {
+                files.push(path);
+            }
+        }
+
+        if let Ok(entries) = std::fs::read_dir(self.dir.join("agents")) {
+            let mut agent_logs: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().and_then(|s| s.to_str()) == Some("log"))
+                .filter(|p| !is_transcript(p))
+                .collect();
+            agent_logs.sort();
+            files.extend(agent_logs);
+        }
+
+        files
+    }
+
+    /// Append a section with a title and free-form text to a log file
+    pub async fn append(&self, path: &Path, title: &str, body: &str) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await?;
+
+        let header = format!(
+            "==> [{}] {}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+            title
+        );
+        file.write_all(header.as_bytes()).await?;
+        file.write_all(body.as_bytes()).await?;
+        if !body.is_empty() && !body.ends_with('\n') {
+            file.write_all(b"\n").await?;
+        }
+        file.flush().await?;
+
+        Ok(())
+    }
+
+    /// Start an entry in a log file that lines are appended to as they arrive
+    ///
+    /// Used for output that should be readable (e.g. with `chaba logs --follow`)
+    /// while the command is still running.
+    pub fn stream(&self, path: &Path, title: &str) -> Result<LogStream> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        let header = format!(
+            "==> [{}] {}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+            title
+        );
+        file.write_all(header.as_bytes())?;
+
+        Ok(LogStream {
+            file: std::sync::Mutex::new(file),
+        })
+    }
+
+    /// Append the stdout/stderr of a finished command to a log file
+    pub async fn append_output(&self, path: &Path, title: &str, output: &Output) -> Result<()> {
+        let mut body = String::new();
+        body.push_str(&String::from_utf8_lossy(&output.stdout));
+        if !output.stderr.is_empty() {
+            if !body.is_empty() && !body.ends_with('\n') {
+                body.push('\n');
+            }
+            body.push_str(&String::from_utf8_lossy(&output.stderr));
+        }
+        if !body.is_empty() && !body.ends_with('\n') {
+            body.push('\n');
+        }
+        body.push_str(&format!("--> exit status: {}\n", output.status));
+
+        self.append(path, title, &body).await
+    }
+}
+
+/// Timestamp suffix of transcript file names
+const TRANSCRIPT_TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// Whether `path` is named like a [`ReviewLogs::transcript_log`]
+fn is_transcript(path: &Path) -> bool {
+    let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+        return false;
+    };
+    // `<agent>-<date>-<time>`
+    let mut parts = stem.rsplitn(3, '-');
+    let (Some(time), Some(date), Some(_)) = (parts.next(), parts.next(), parts.next()) else {
+        return false;
+    };
+    chrono::NaiveDateTime::parse_from_str(&format!("{}-{}", date, time), TRANSCRIPT_TIME_FORMAT).is_ok()
+}
+
+/// A log entry being written line by line, see [`ReviewLogs::stream`]
+#[derive(Debug)]
+pub struct LogStream {
+    file: std::sync::Mutex<std::fs::File>,
+}
+
+impl LogStream {
+    /// Append a line; write errors are logged and otherwise ignored
+    pub fn line(&self, line: &str) {
+        let mut file = self.file.lock().unwrap();
+        if let Err(e) = writeln!(file, "{}", line) {
+            tracing::debug!("Failed to write log line: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::platform::exit_status;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_log_paths() {
+        let logs = ReviewLogs::at("/tmp/logs/pr-123");
+        assert_eq!(logs.install_log(), PathBuf::from("/tmp/logs/pr-123/install.log"));
+        assert_eq!(logs.hooks_log(), PathBuf::from("/tmp/logs/pr-123/hooks.log"));
+        assert_eq!(
+            logs.agent_log("claude"),
+            PathBuf::from("/tmp/logs/pr-123/agents/claude.log")
+        );
+
+        let started = chrono::Local.with_ymd_and_hms(2026, 10, 17, 9, 5, 0).unwrap();
+        let transcript = logs.transcript_log("claude-security", started);
+        assert_eq!(
+            transcript,
+            PathBuf::from("/tmp/logs/pr-123/agents/claude-security-20261017-090500.log")
+        );
+        assert!(is_transcript(&transcript));
+        assert!(!is_transcript(&logs.agent_log("claude")));
+    }
+
+    #[tokio::test]
+    async fn test_append_output_and_list_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let logs = ReviewLogs::at(temp_dir.path());
+
+        let output = Output {
+            status: exit_status(0),
+            stdout: b"added 42 packages".to_vec(),
+            stderr: b"npm warn deprecated".to_vec(),
+        };
+        logs.append_output(&logs.install_log(), "npm install", &output)
+            .await
+            .unwrap();
+        logs.append(&logs.agent_log("codex"), "codex", "no issues")
+            .await
+            .unwrap();
+
+        let content = std::fs::read_to_string(logs.install_log()).unwrap();
+        assert!(content.contains("npm install"));
+        assert!(content.contains("added 42 packages"));
+        assert!(content.contains("npm warn deprecated"));
+
+        let files = logs.files();
+        assert_eq!(files, vec![logs.install_log(), logs.agent_log("codex")]);
+    }
+
+    #[test]
+    fn test_stream_appends_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let logs = ReviewLogs::at(temp_dir.path());
+
+        let stream = logs.stream(&logs.agent_log("claude"), "claude started").unwrap();
+        stream.line("Reading src/lib.rs");
+        stream.line("Found 2 issues");
+        drop(stream);
+
+        let content = std::fs::read_to_string(logs.agent_log("claude")).unwrap();
+        assert!(content.contains("] claude started\nReading src/lib.rs\nFound 2 issues\n"));
+    }
+}
@@ -0,0 +1,130 @@
+//! Review metadata written into each worktree
+//!
+//! `.chaba/review.json` lets tools running inside a review worktree (hooks,
+//! agents, editor extensions) discover their chaba context without reading
+//! the global state file.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::core::state::ReviewState;
+use crate::error::{ChabaError, Result};
+
+/// Metadata file location, relative to the worktree root
+pub const METADATA_FILE: &str = ".chaba/review.json";
+
+/// Contents of `.chaba/review.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewMetadata {
+    /// Identifier of the review environment (e.g. `pr-123`)
+    pub review_id: String,
+    pub pr_number: u32,
+    pub branch: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_branch: Option<String>,
+    /// PR this one is stacked on, for stacked PRs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_pr: Option<u32>,
+    /// Patch file applied on top of the base branch, for patch reviews
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    pub worktree_path: PathBuf,
+    pub main_worktree: PathBuf,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Version of chaba that wrote the file
+    pub chaba_version: String,
+}
+
+impl ReviewMetadata {
+    /// Build metadata for a review
+    pub fn from_review(review: &ReviewState, main_worktree: &Path) -> Self {
+        ReviewMetadata {
+            review_id: review_id(review.pr_number),
+            pr_number: review.pr_number,
+            branch: review.branch.clone(),
+            base_branch: review.base_branch.clone(),
+            parent_pr: review.parent_pr,
+            patch: review.patch.clone(),
+            port: review.port,
+            worktree_path: review.worktree_path.clone(),
+            main_worktree: main_worktree.to_path_buf(),
+            created_at: review.created_at,
+            expires_at: review.expires_at,
+            chaba_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    /// Write metadata to `.chaba/review.json` in `worktree_path`
+    pub async fn write(&self, worktree_path: &Path) -> Result<()> {
+        let path = worktree_path.join(METADATA_FILE);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| ChabaError::Other(e.into()))?;
+        tokio::fs::write(&path, json).await?;
+        Ok(())
+    }
+
+    /// Read metadata from a worktree root
+    pub fn read(worktree_path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(worktree_path.join(METADATA_FILE))?;
+        serde_json::from_str(&content).map_err(|e| ChabaError::Other(e.into()))
+    }
+
+    /// Find metadata for the worktree containing `dir`, searching parent directories
+    pub fn discover(dir: &Path) -> Option<Self> {
+        dir.ancestors()
+            .find(|d| d.join(METADATA_FILE).is_file())
+            .and_then(|d| Self::read(d).ok())
+    }
+}
+
+/// Review identifier for a PR number
+pub fn review_id(pr_number: u32) -> String {
+    format!("pr-{}", pr_number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn review(worktree_path: PathBuf) -> ReviewState {
+        ReviewState {
+            branch: "feature/x".to_string(),
+            base_branch: Some("main".to_string()),
+            port: Some(3001),
+            ..ReviewState::for_test(42, worktree_path)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_and_discover() {
+        let temp_dir = TempDir::new().unwrap();
+        let worktree = temp_dir.path().join("pr-42");
+        std::fs::create_dir_all(worktree.join("src/nested")).unwrap();
+
+        let metadata = ReviewMetadata::from_review(&review(worktree.clone()), Path::new("/repo"));
+        metadata.write(&worktree).await.unwrap();
+
+        assert_eq!(ReviewMetadata::read(&worktree).unwrap(), metadata);
+
+        let discovered = ReviewMetadata::discover(&worktree.join("src/nested")).unwrap();
+        assert_eq!(discovered.review_id, "pr-42");
+        assert_eq!(discovered.port, Some(3001));
+        assert_eq!(discovered.base_branch.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn test_discover_outside_worktree() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(ReviewMetadata::discover(temp_dir.path()).is_none());
+    }
+}
@@ -0,0 +1,159 @@
+//! State export and import for moving to another machine
+//!
+//! `chaba state export` writes every tracked review, and optionally the
+//! archive of cleaned-up reviews, as one JSON document. `chaba state import`
+//! reads it back on the new machine. Paths are rewritten on import: the old
+//! home directory becomes the new one, and `--rewrite OLD=NEW` moves any
+//! other prefix, e.g. when repositories live somewhere else now. Worktrees
+//! themselves are not copied.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::core::archive::ArchivedReview;
+use crate::core::state::ReviewState;
+use crate::error::{ChabaError, Result};
+
+/// Version of the export format
+pub const EXPORT_VERSION: u32 = 1;
+
+/// Everything `chaba state export` writes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateExport {
+    pub version: u32,
+    /// Home directory on the exporting machine, rewritten on import
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home: Option<PathBuf>,
+    pub reviews: Vec<ReviewState>,
+    /// Cleaned-up reviews, with `--with-archive`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub archive: Vec<ArchivedReview>,
+}
+
+impl StateExport {
+    pub fn new(reviews: Vec<ReviewState>, archive: Vec<ArchivedReview>) -> Self {
+        StateExport {
+            version: EXPORT_VERSION,
+            home: dirs::home_dir(),
+            reviews,
+            archive,
+        }
+    }
+
+    /// Parse an export, rejecting ones written by a newer chaba
+    pub fn parse(json: &str) -> Result<Self> {
+        let export: StateExport = serde_json::from_str(json)
+            .map_err(|e| ChabaError::ConfigError(format!("Invalid state export: {}", e)))?;
+        if export.version > EXPORT_VERSION {
+            return Err(ChabaError::ConfigError(format!(
+                "State export version {} is newer than this chaba supports ({}); upgrade chaba first",
+                export.version, EXPORT_VERSION
+            )));
+        }
+        Ok(export)
+    }
+
+    /// Rewrite the paths of all reviews with the first matching rewrite
+    pub fn rewrite_paths(&mut self, rewrites: &[PathRewrite]) {
+        let reviews = self
+            .reviews
+            .iter_mut()
+            .chain(self.archive.iter_mut().map(|archived| &mut archived.review));
+        for review in reviews {
+            rewrite_review(review, rewrites);
+        }
+    }
+}
+
+fn rewrite_review(review: &mut ReviewState, rewrites: &[PathRewrite]) {
+    review.worktree_path = rewrite(&review.worktree_path, rewrites);
+    for path in [&mut review.repo, &mut review.patch].into_iter().flatten() {
+        *path = rewrite(path, rewrites);
+    }
+
+    let analyses = review
+        .agent_analyses
+        .iter_mut()
+        .chain(review.analysis_history.iter_mut().flat_map(|run| run.analyses.iter_mut()));
+    for analysis in analyses {
+        if let Some(transcript) = &mut analysis.transcript {
+            *transcript = rewrite(transcript, rewrites);
+        }
+    }
+}
+
+fn rewrite(path: &Path, rewrites: &[PathRewrite]) -> PathBuf {
+    rewrites
+        .iter()
+        .find_map(|rewrite| Some(rewrite.to.join(path.strip_prefix(&rewrite.from).ok()?)))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// A path prefix to move on import, `OLD=NEW`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathRewrite {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+impl FromStr for PathRewrite {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((from, to)) if !from.is_empty() && !to.is_empty() => Ok(PathRewrite {
+                from: PathBuf::from(from),
+                to: PathBuf::from(to),
+            }),
+            _ => Err(format!("invalid rewrite '{}' (expected OLD=NEW, e.g. /home/old/src=/Users/new/code)", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::review_analysis::ReviewAnalysis;
+
+    fn review() -> ReviewState {
+        let mut analysis = ReviewAnalysis::new("claude".to_string());
+        analysis.transcript = Some(PathBuf::from("/home/old/.chaba/logs/pr-7/agents/claude.log"));
+        ReviewState {
+            branch: "feature".to_string(),
+            patch: Some(PathBuf::from("/tmp/fix.patch")),
+            repo: Some(PathBuf::from("/home/old/src/app")),
+            agent_analyses: vec![analysis],
+            ..ReviewState::for_test(7, "/home/old/src/app-reviews/pr-7")
+        }
+    }
+
+    #[test]
+    fn test_rewrite_paths_uses_first_matching_prefix() {
+        let mut export = StateExport::new(vec![review()], vec![]);
+        export.rewrite_paths(&[
+            "/home/old/src=/Users/new/code".parse().unwrap(),
+            "/home/old=/Users/new".parse().unwrap(),
+        ]);
+
+        let review = &export.reviews[0];
+        assert_eq!(review.worktree_path, PathBuf::from("/Users/new/code/app-reviews/pr-7"));
+        assert_eq!(review.repo, Some(PathBuf::from("/Users/new/code/app")));
+        assert_eq!(review.patch, Some(PathBuf::from("/tmp/fix.patch")));
+        assert_eq!(
+            review.agent_analyses[0].transcript,
+            Some(PathBuf::from("/Users/new/.chaba/logs/pr-7/agents/claude.log"))
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let json = serde_json::to_string(&StateExport::new(vec![review()], vec![])).unwrap();
+        assert_eq!(StateExport::parse(&json).unwrap().reviews[0].pr_number, 7);
+
+        let newer = json.replacen("\"version\":1", "\"version\":99", 1);
+        assert!(StateExport::parse(&newer).unwrap_err().to_string().contains("upgrade chaba"));
+        assert!(StateExport::parse("[]").is_err());
+        assert!("no-equals".parse::<PathRewrite>().is_err());
+    }
+}
@@ -1,13 +1,57 @@
+pub mod activity;
 pub mod agent;
+pub mod agent_output;
+pub mod anonymize;
+pub mod archive;
+pub mod baseline;
+pub mod cancel;
+pub mod chaos;
+pub mod ci;
 pub mod command;
+pub mod dep_cache;
+pub mod encryption;
 pub mod env;
+pub mod events;
+pub mod exclude;
 pub mod git;
+pub mod history;
 pub mod hooks;
 pub mod installer;
+pub mod junit;
+pub mod launcher;
+pub mod logs;
+pub mod metadata;
+pub mod migrate;
+pub mod naming;
+pub mod notification;
+pub mod platform;
 pub mod port;
+pub mod pr_state;
+pub mod preflight;
 pub mod project;
+pub mod queue;
+pub mod quota;
+pub mod replay;
+pub mod report;
+pub mod resources;
 pub mod review_analysis;
 pub mod sandbox;
+pub mod secrets;
+pub mod seed;
 pub mod session;
+pub mod snapshot;
+pub mod sparse;
+pub mod stack;
 pub mod state;
+pub mod stats_cache;
+pub mod statusline;
+pub mod suggestions;
+pub mod test_suite;
+pub mod trace;
+pub mod ttl;
+pub mod verdict;
+pub mod vscode;
+pub mod watcher;
+pub mod webhook;
+pub mod workspace;
 pub mod worktree;
@@ -0,0 +1,111 @@
+//! Worktree directory naming
+//!
+//! `worktree.naming_template` supports these placeholders:
+//! - `{pr}`: PR number (or the pseudo-number of a branch, patch, or local review)
+//! - `{branch}`: branch name
+//! - `{date}`: creation date (`YYYY-MM-DD`)
+//! - `{repo}`: repository directory name
+//! - `{author}`: PR author, or the branch tip's commit author
+//!
+//! Substituted values are sanitized so a branch like `feature/login` becomes
+//! `feature-login` instead of a nested directory.
+
+/// Values substituted into a naming template
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NameVars {
+    pub pr: u32,
+    pub branch: String,
+    pub date: String,
+    pub repo: String,
+    pub author: Option<String>,
+}
+
+/// Whether `template` uses the `{author}` placeholder, which may need a lookup
+pub fn uses_author(template: &str) -> bool {
+    template.contains("{author}")
+}
+
+/// Expand a naming template
+pub fn render(template: &str, vars: &NameVars) -> String {
+    template
+        .replace("{pr}", &vars.pr.to_string())
+        .replace("{branch}", &sanitize(&vars.branch))
+        .replace("{date}", &sanitize(&vars.date))
+        .replace("{repo}", &sanitize(&vars.repo))
+        .replace("{author}", &sanitize(vars.author.as_deref().unwrap_or("unknown")))
+}
+
+/// Make a value safe to use as a single path component
+///
+/// Anything other than ASCII letters, digits, `.`, `_`, and `-` becomes `-`,
+/// runs of `-` are collapsed, and leading/trailing `-` and `.` are trimmed
+/// (so the result can never be `..`).
+pub fn sanitize(value: &str) -> String {
+    let mut sanitized = String::with_capacity(value.len());
+    for c in value.chars() {
+        let c = if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+            c
+        } else {
+            '-'
+        };
+        if !(c == '-' && sanitized.ends_with('-')) {
+            sanitized.push(c);
+        }
+    }
+
+    let sanitized = sanitized.trim_matches(|c| c == '-' || c == '.');
+    if sanitized.is_empty() {
+        "unknown".to_string()
+    } else {
+        sanitized.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> NameVars {
+        NameVars {
+            pr: 123,
+            branch: "feature/login-form".to_string(),
+            date: "2024-01-15".to_string(),
+            repo: "my app".to_string(),
+            author: Some("octocat".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_render_placeholders() {
+        assert_eq!(render("pr-{pr}", &vars()), "pr-123");
+        assert_eq!(
+            render("{repo}-{branch}-{date}-{author}", &vars()),
+            "my-app-feature-login-form-2024-01-15-octocat"
+        );
+    }
+
+    #[test]
+    fn test_render_unknown_author() {
+        let vars = NameVars {
+            author: None,
+            ..vars()
+        };
+        assert_eq!(render("{author}/pr-{pr}", &vars), "unknown/pr-123");
+    }
+
+    #[test]
+    fn test_sanitize() {
+        assert_eq!(sanitize("feature/foo bar"), "feature-foo-bar");
+        assert_eq!(sanitize("fix//weird***name"), "fix-weird-name");
+        assert_eq!(sanitize("../../etc"), "etc");
+        assert_eq!(sanitize(".."), "unknown");
+        assert_eq!(sanitize("ユーザー"), "unknown");
+        assert_eq!(sanitize("v1.2_rc-1"), "v1.2_rc-1");
+    }
+
+    #[test]
+    fn test_uses_author() {
+        assert!(uses_author("{author}-{pr}"));
+        assert!(!uses_author("pr-{pr}"));
+    }
+}
@@ -0,0 +1,101 @@
+//! Desktop notifications
+//!
+//! With `notifications.desktop: true`, long operations (review setup, agent
+//! runs, `chaba watch` events) end with a desktop notification, so the
+//! terminal can be left alone meanwhile. Notifications are shown with the
+//! platform's own tool: `osascript` on macOS, `notify-send` on Linux, and a
+//! PowerShell toast on Windows. Best effort: failures are only logged.
+
+use std::process::Stdio;
+use tokio::process::Command;
+
+use crate::config::NotificationsConfig;
+use crate::core::platform;
+
+/// Show a desktop notification, if enabled
+pub async fn notify(config: &NotificationsConfig, title: &str, body: &str) {
+    if !config.desktop {
+        return;
+    }
+    let (program, args, env) = command(title, body);
+    let result = Command::new(platform::resolve_program(program))
+        .args(&args)
+        .envs(env)
+        .stdin(Stdio::null())
+        .output()
+        .await;
+    match result {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => tracing::warn!(
+            "Failed to show a desktop notification: {} exited with {}: {}",
+            program,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Err(e) => tracing::warn!("Failed to show a desktop notification with {}: {}", program, e),
+    }
+}
+
+/// Program, arguments and environment that show a notification on this platform
+///
+/// The body names branches and quotes errors, both chosen by PR authors, so
+/// the text never becomes PowerShell code: the script reads it from the
+/// environment.
+fn command(title: &str, body: &str) -> (&'static str, Vec<String>, Vec<(&'static str, String)>) {
+    if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification {} with title {}",
+            quoted(body),
+            quoted(title)
+        );
+        ("osascript", vec!["-e".to_string(), script], Vec::new())
+    } else if cfg!(windows) {
+        let script = "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+             $xml = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+             $text = $xml.GetElementsByTagName('text'); \
+             $text.Item(0).AppendChild($xml.CreateTextNode($env:CHABA_TITLE)) > $null; \
+             $text.Item(1).AppendChild($xml.CreateTextNode($env:CHABA_BODY)) > $null; \
+             [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('chaba').Show([Windows.UI.Notifications.ToastNotification]::new($xml))";
+        let (program, args) = platform::Shell::PowerShell.argv(script);
+        let env = vec![("CHABA_TITLE", title.to_string()), ("CHABA_BODY", body.to_string())];
+        (program, args.into_iter().map(String::from).collect(), env)
+    } else {
+        // `--` so a body starting with `-` isn't taken for an option
+        let args = vec!["--app-name=chaba".to_string(), "--".to_string(), title.to_string(), body.to_string()];
+        ("notify-send", args, Vec::new())
+    }
+}
+
+/// `text` as an AppleScript string literal
+fn quoted(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quoted() {
+        assert_eq!(quoted(r#"PR "42" \ done"#), r#""PR \"42\" \\ done""#);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_command() {
+        let (program, args, env) = command("Agent analysis finished", "--PR #42: 3 finding(s)");
+        assert_eq!(program, "notify-send");
+        assert_eq!(args[1..], ["--", "Agent analysis finished", "--PR #42: 3 finding(s)"]);
+        assert!(env.is_empty());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_command_passes_text_outside_the_script() {
+        let body = "feature/it\u{2019}s'; Remove-Item -Recurse C:\\ #";
+        let (program, args, env) = command("Review ready", body);
+        assert_eq!(program, "powershell");
+        assert!(args.iter().all(|arg| !arg.contains("Remove-Item")));
+        assert_eq!(env, vec![("CHABA_TITLE", "Review ready".to_string()), ("CHABA_BODY", body.to_string())]);
+    }
+}
@@ -0,0 +1,187 @@
+//! Differences between Unix and Windows
+//!
+//! Hooks, test and seed commands run through a shell: `sh -c` on Unix and
+//! `cmd /C` on Windows unless `hooks.shell` picks another one. External
+//! programs installed as `.cmd` scripts are found on Windows too. Paths are
+//! canonicalized without Windows' `\\?\` prefix so they still compare equal
+//! to configured paths and can be handed to git, and compared without
+//! case on Windows.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use tokio::process::Command;
+
+/// Shell that runs configured command lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Shell {
+    /// `sh -c`, the default on Unix
+    Sh,
+    /// `cmd /C`, the default on Windows
+    Cmd,
+    /// `powershell -NoProfile -Command`
+    PowerShell,
+}
+
+impl Default for Shell {
+    fn default() -> Self {
+        if cfg!(windows) {
+            Shell::Cmd
+        } else {
+            Shell::Sh
+        }
+    }
+}
+
+impl Shell {
+    /// Program and arguments that run `script`
+    pub fn argv<'a>(&self, script: &'a str) -> (&'static str, Vec<&'a str>) {
+        match self {
+            Shell::Sh => ("sh", vec!["-c", script]),
+            Shell::Cmd => ("cmd", vec!["/C", script]),
+            Shell::PowerShell => ("powershell", vec!["-NoProfile", "-NonInteractive", "-Command", script]),
+        }
+    }
+
+    /// A command that runs `script` in this shell
+    pub fn command(&self, script: &str) -> Command {
+        let (program, args) = self.argv(script);
+        let mut cmd = Command::new(program);
+        cmd.args(args);
+        cmd
+    }
+}
+
+/// A command that runs `script` in the platform's shell
+pub fn shell_command(script: &str) -> Command {
+    Shell::default().command(script)
+}
+
+/// Program to spawn for `program`
+///
+/// On Windows, `Command` only looks for `.exe` files on `PATH`, while npm
+/// installs CLIs such as `claude` or `pnpm` as `.cmd` scripts. Those resolve
+/// to their full path here; everything else is returned unchanged.
+pub fn resolve_program(program: &str) -> PathBuf {
+    if cfg!(windows) && Path::new(program).extension().is_none() {
+        if let Some(script) = find_script(program, std::env::var_os("PATH").as_deref()) {
+            return script;
+        }
+    }
+    PathBuf::from(program)
+}
+
+/// The first `.cmd`/`.bat` for `program` on `path`, unless an `.exe` comes first
+fn find_script(program: &str, path: Option<&std::ffi::OsStr>) -> Option<PathBuf> {
+    for dir in std::env::split_paths(path?) {
+        if dir.join(format!("{}.exe", program)).is_file() {
+            return None;
+        }
+        let script = ["cmd", "bat"]
+            .iter()
+            .map(|ext| dir.join(format!("{}.{}", program, ext)))
+            .find(|script| script.is_file());
+        if script.is_some() {
+            return script;
+        }
+    }
+    None
+}
+
+/// Canonical, absolute form of `path` with symlinks resolved
+///
+/// Unlike [`std::fs::canonicalize`], avoids `\\?\` paths on Windows where a
+/// normal path works.
+pub fn canonicalize(path: &Path) -> io::Result<PathBuf> {
+    dunce::canonicalize(path)
+}
+
+/// Whether `path` is `base` or inside it, ignoring case on Windows
+pub fn starts_with(path: &Path, base: &Path) -> bool {
+    if cfg!(windows) {
+        let lower = |path: &Path| PathBuf::from(path.to_string_lossy().to_lowercase());
+        lower(path).starts_with(lower(base))
+    } else {
+        path.starts_with(base)
+    }
+}
+
+/// Exit status of a process that exited with `code`; negative means killed
+#[cfg(unix)]
+pub fn exit_status(code: i32) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    if code < 0 {
+        // SIGKILL
+        ExitStatus::from_raw(9)
+    } else {
+        ExitStatus::from_raw((code & 0xff) << 8)
+    }
+}
+
+/// Exit status of a process that exited with `code`; negative means killed
+#[cfg(windows)]
+pub fn exit_status(code: i32) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(code as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_status() {
+        assert!(exit_status(0).success());
+        assert_eq!(exit_status(3).code(), Some(3));
+    }
+
+    #[test]
+    fn test_shell_argv() {
+        assert_eq!(Shell::Sh.argv("echo hi"), ("sh", vec!["-c", "echo hi"]));
+        assert_eq!(Shell::Cmd.argv("echo hi"), ("cmd", vec!["/C", "echo hi"]));
+        assert_eq!(Shell::PowerShell.argv("echo hi").1.last(), Some(&"echo hi"));
+        assert_eq!(serde_yaml::from_str::<Shell>("powershell").unwrap(), Shell::PowerShell);
+    }
+
+    #[tokio::test]
+    async fn test_shell_command_runs_script() {
+        let output = shell_command("echo chaba").output().await.unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "chaba");
+    }
+
+    #[test]
+    fn test_find_script() {
+        let npm = tempfile::tempdir().unwrap();
+        let git = tempfile::tempdir().unwrap();
+        std::fs::write(npm.path().join("claude.cmd"), "").unwrap();
+        std::fs::write(git.path().join("git.exe"), "").unwrap();
+        std::fs::write(npm.path().join("git.cmd"), "").unwrap();
+        let path = std::env::join_paths([git.path(), npm.path()]).unwrap();
+
+        assert_eq!(find_script("claude", Some(&path)), Some(npm.path().join("claude.cmd")));
+        assert_eq!(find_script("git", Some(&path)), None);
+        assert_eq!(find_script("codex", Some(&path)), None);
+        if cfg!(unix) {
+            assert_eq!(resolve_program("claude"), PathBuf::from("claude"));
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_starts_with_ignores_case_on_windows() {
+        assert!(starts_with(Path::new(r"C:\Reviews\pr-1"), Path::new(r"c:\reviews")));
+    }
+
+    #[test]
+    fn test_starts_with() {
+        let dir = tempfile::tempdir().unwrap();
+        let canonical = canonicalize(dir.path()).unwrap();
+        assert!(canonical.is_absolute());
+        assert!(starts_with(&canonical.join("pr-1"), &canonical));
+        assert!(!starts_with(&canonical, &canonical.join("pr-1")));
+    }
+}
@@ -1,9 +1,24 @@
 use std::collections::HashSet;
 use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Mutex;
 
-use crate::core::state::State;
+use crate::config::{PortConvention, PreferredPort};
+use crate::core::project::ProjectType;
+use crate::core::resources::ResourceMonitor;
+use crate::core::state::{ReviewState, State};
 use crate::error::{ChabaError, Result};
 
+/// Ports handed out by this process
+///
+/// `review --all-open` sets up several reviews at once from the same state
+/// snapshot, and none of them has saved its port or started a server yet.
+static ASSIGNED: Mutex<Option<HashSet<u16>>> = Mutex::new(None);
+
+/// Ports tried from a preferred one before scanning the range
+const PREFERRED_SPAN: u16 = 10;
+
 pub struct PortManager {
     range_start: u16,
     range_end: u16,
@@ -19,32 +34,205 @@ impl PortManager {
 
     /// Assign an available port
     pub fn assign_port(&self, state: &State) -> Result<u16> {
-        // Collect already assigned ports
-        let used_ports: HashSet<u16> = state
-            .reviews
-            .iter()
-            .filter_map(|r| r.port)
-            .collect();
+        take_first(state, self.range_start..=self.range_end).ok_or(ChabaError::NoAvailablePort {
+            range_start: self.range_start,
+            range_end: self.range_end,
+        })
+    }
 
-        // Find an available port
-        for port in self.range_start..=self.range_end {
-            if !used_ports.contains(&port) && !is_port_in_use(port) {
+    /// Assign `preferred` if it is in range and available, otherwise any available port
+    ///
+    /// Used to keep a review's port when its environment is rebuilt.
+    pub fn assign_port_preferring(&self, state: &State, preferred: u16) -> Result<u16> {
+        if (self.range_start..=self.range_end).contains(&preferred) {
+            if let Some(port) = take_first(state, [preferred]) {
                 return Ok(port);
             }
         }
+        self.assign_port(state)
+    }
 
-        Err(ChabaError::NoAvailablePort {
-            range_start: self.range_start,
-            range_end: self.range_end,
-        })
+    /// Assign the first available port from `base` on, scanning the range if
+    /// none of the next few is free
+    ///
+    /// `base` comes from `sandbox.port.preferred` and may lie outside the range.
+    pub fn assign_port_near(&self, state: &State, base: u16) -> Result<u16> {
+        match take_first(state, base..=base.saturating_add(PREFERRED_SPAN - 1)) {
+            Some(port) => Ok(port),
+            None => self.assign_port(state),
+        }
+    }
+}
+
+/// Hand out the first of `candidates` that no review holds and nothing listens on
+fn take_first(state: &State, candidates: impl IntoIterator<Item = u16>) -> Option<u16> {
+    let used_ports = held_ports(state);
+    let mut assigned = ASSIGNED.lock().unwrap_or_else(|e| e.into_inner());
+    let assigned = assigned.get_or_insert_with(HashSet::new);
+
+    let port = candidates
+        .into_iter()
+        .find(|port| !used_ports.contains(port) && !assigned.contains(port) && !is_port_in_use(*port))?;
+    assigned.insert(port);
+    Some(port)
+}
+
+/// First port to try for a review, from `sandbox.port.preferred`
+pub fn preferred_base(preferred: Option<PreferredPort>, worktree: &Path, project_type: &ProjectType) -> Option<u16> {
+    match preferred? {
+        PreferredPort::Port(port) => Some(port),
+        PreferredPort::Convention(PortConvention::Auto) => {
+            let (port, framework) = conventional_port(worktree, project_type)?;
+            tracing::info!("Preferring port {} for {}", port, framework);
+            Some(port)
+        }
+    }
+}
+
+/// Usual dev server port of the project in `worktree`, with the framework's name
+pub fn conventional_port(worktree: &Path, project_type: &ProjectType) -> Option<(u16, &'static str)> {
+    match project_type {
+        ProjectType::NodeJs { .. } => {
+            let manifest: serde_json::Value = std::fs::read_to_string(worktree.join("package.json"))
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())
+                .unwrap_or_default();
+            let depends_on = |name: &str| {
+                ["dependencies", "devDependencies"]
+                    .iter()
+                    .any(|key| manifest[key].get(name).is_some())
+            };
+            let framework = [
+                ("next", 3000, "Next.js"),
+                ("nuxt", 3000, "Nuxt"),
+                ("@angular/core", 4200, "Angular"),
+                ("astro", 4321, "Astro"),
+                ("vite", 5173, "Vite"),
+            ]
+            .into_iter()
+            .find(|(package, _, _)| depends_on(package));
+            Some(framework.map_or((3000, "Node.js"), |(_, port, name)| (port, name)))
+        }
+        ProjectType::Python { .. } => {
+            if worktree.join("manage.py").exists() {
+                return Some((8000, "Django"));
+            }
+            let requirements = ["requirements.txt", "pyproject.toml"]
+                .iter()
+                .filter_map(|file| std::fs::read_to_string(worktree.join(file)).ok())
+                .collect::<String>()
+                .to_lowercase();
+            if requirements.contains("flask") {
+                Some((5000, "Flask"))
+            } else if requirements.contains("fastapi") {
+                Some((8000, "FastAPI"))
+            } else {
+                Some((8000, "Python"))
+            }
+        }
+        ProjectType::Go => Some((8080, "Go")),
+        ProjectType::Rust => Some((8080, "Rust")),
+        ProjectType::Unknown => None,
+    }
+}
+
+/// Ports held by reviews, except those whose worktree is gone
+///
+/// A worktree deleted by hand leaves its review in state. Its port goes back
+/// to the pool instead of shrinking it until `chaba ports --prune`.
+fn held_ports(state: &State) -> HashSet<u16> {
+    state
+        .reviews
+        .iter()
+        .filter(|r| r.worktree_path.exists())
+        .filter_map(|r| r.port)
+        .collect()
+}
+
+/// Whether a review holds a port its worktree no longer needs
+pub fn is_stale(review: &ReviewState) -> bool {
+    review.port.is_some() && !review.worktree_path.exists()
+}
+
+/// Release the ports of reviews whose worktree is missing
+///
+/// Returns the released `(pr, port)` pairs.
+pub fn release_stale(state: &mut State) -> Result<Vec<(u32, u16)>> {
+    let stale: Vec<ReviewState> = state.reviews.iter().filter(|r| is_stale(r)).cloned().collect();
+    let mut released = Vec::new();
+    for review in stale {
+        if let Some(port) = review.port {
+            released.push((review.pr_number, port));
+            state.update_review(&review.key(), |r| r.port = None)?;
+        }
     }
+    Ok(released)
 }
 
 /// Check if a port is currently in use
-fn is_port_in_use(port: u16) -> bool {
+///
+/// Also tells whether a review's dev server is listening on its assigned port.
+pub fn is_port_in_use(port: u16) -> bool {
     TcpListener::bind(("127.0.0.1", port)).is_err()
 }
 
+/// What is listening on a review's port
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PortOwner {
+    /// Nothing; a server can bind it
+    Free,
+    /// A process running in the review's worktree, e.g. its dev server
+    Review,
+    /// An unrelated process; `pids` is empty when it couldn't be identified
+    Other { pids: Vec<u32> },
+}
+
+/// Find out who holds a review's `port`
+///
+/// Listening processes are looked up with `lsof` and attributed to the
+/// review by their working directory. Without `lsof`, a port in use while
+/// processes run in the worktree is assumed to be the review's own.
+pub fn port_owner(port: u16, review: &ReviewState) -> PortOwner {
+    if !is_port_in_use(port) {
+        return PortOwner::Free;
+    }
+
+    let own: HashSet<u32> = ResourceMonitor::new()
+        .review_usage(review)
+        .processes
+        .iter()
+        .map(|process| process.pid)
+        .collect();
+
+    match listening_pids(port) {
+        Some(pids) if !pids.is_empty() => {
+            if pids.iter().any(|pid| own.contains(pid)) {
+                PortOwner::Review
+            } else {
+                PortOwner::Other { pids }
+            }
+        }
+        _ if !own.is_empty() => PortOwner::Review,
+        _ => PortOwner::Other { pids: Vec::new() },
+    }
+}
+
+/// Processes listening on TCP `port`, or `None` if `lsof` isn't available
+fn listening_pids(port: u16) -> Option<Vec<u32>> {
+    let output = Command::new("lsof")
+        .args(["-nP", &format!("-iTCP:{}", port), "-sTCP:LISTEN", "-t"])
+        .output()
+        .ok()?;
+
+    // lsof exits with 1 both when nothing matched and on errors
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.trim().parse().ok())
+            .collect(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -58,7 +246,7 @@ mod tests {
         let state = State::default();
 
         let port = manager.assign_port(&state).unwrap();
-        assert!(port >= 3000 && port <= 3010);
+        assert!((3000..=3010).contains(&port));
     }
 
     #[test]
@@ -69,18 +257,31 @@ mod tests {
         state.reviews.push(ReviewState {
             pr_number: 1,
             branch: "test".to_string(),
-            worktree_path: PathBuf::from("/tmp/test"),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
+            worktree_path: std::env::temp_dir(),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
             created_at: Utc::now(),
             port: Some(3000),
             project_type: None,
             deps_installed: false,
             env_copied: false,
+            tests: None,
+            seed: None,
+            expires_at: None,
             agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
         });
 
         let port = manager.assign_port(&state).unwrap();
         assert_ne!(port, 3000);
-        assert!(port >= 3000 && port <= 3002);
+        assert!((3000..=3002).contains(&port));
     }
 
     #[test]
@@ -91,16 +292,177 @@ mod tests {
         state.reviews.push(ReviewState {
             pr_number: 1,
             branch: "test".to_string(),
-            worktree_path: PathBuf::from("/tmp/test"),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
+            worktree_path: std::env::temp_dir(),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
             created_at: Utc::now(),
             port: Some(3000),
             project_type: None,
             deps_installed: false,
             env_copied: false,
+            tests: None,
+            seed: None,
+            expires_at: None,
             agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
         });
 
         let result = manager.assign_port(&state);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_missing_worktree_releases_port() {
+        let manager = PortManager::new(45300, 45300);
+        let missing = tempfile::tempdir().unwrap().path().join("pr-1");
+
+        let mut state = State::default();
+        state.reviews.push(ReviewState {
+            pr_number: 1,
+            branch: "test".to_string(),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
+            worktree_path: missing,
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
+            created_at: Utc::now(),
+            port: Some(45300),
+            project_type: None,
+            deps_installed: false,
+            env_copied: false,
+            tests: None,
+            seed: None,
+            expires_at: None,
+            agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
+        });
+
+        assert!(is_stale(&state.reviews[0]));
+        assert_eq!(manager.assign_port(&state).unwrap(), 45300);
+    }
+
+    #[test]
+    fn test_ports_not_reassigned_within_process() {
+        // Reviews set up concurrently all see the same state snapshot
+        let manager = PortManager::new(45100, 45101);
+        let state = State::default();
+
+        let first = manager.assign_port(&state).unwrap();
+        let second = manager.assign_port(&state).unwrap();
+        assert_ne!(first, second);
+        assert!(manager.assign_port(&state).is_err());
+    }
+
+    #[test]
+    fn test_assign_port_preferring() {
+        let manager = PortManager::new(45200, 45203);
+        let state = State::default();
+
+        assert_eq!(manager.assign_port_preferring(&state, 45202).unwrap(), 45202);
+        // Already handed out in this process
+        assert_ne!(manager.assign_port_preferring(&state, 45202).unwrap(), 45202);
+        assert!((45200..=45203).contains(&manager.assign_port_preferring(&state, 80).unwrap()));
+    }
+
+    #[test]
+    fn test_assign_port_near() {
+        let manager = PortManager::new(45400, 45410);
+        let state = State::default();
+        let _listener = TcpListener::bind(("127.0.0.1", 45500)).unwrap();
+
+        // Outside the range, skipping the port in use
+        assert_eq!(manager.assign_port_near(&state, 45500).unwrap(), 45501);
+        assert_eq!(manager.assign_port_near(&state, 45500).unwrap(), 45502);
+        // Nothing free near the preferred port: falls back to the range
+        let _taken: Vec<TcpListener> = (45600..45600 + PREFERRED_SPAN)
+            .map(|port| TcpListener::bind(("127.0.0.1", port)).unwrap())
+            .collect();
+        assert!((45400..=45410).contains(&manager.assign_port_near(&state, 45600).unwrap()));
+    }
+
+    #[test]
+    fn test_conventional_port() {
+        let dir = tempfile::tempdir().unwrap();
+        let node = ProjectType::NodeJs {
+            package_manager: crate::core::project::NodePackageManager::Npm,
+        };
+        assert_eq!(conventional_port(dir.path(), &node), Some((3000, "Node.js")));
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"dependencies": {"react": "18"}, "devDependencies": {"vite": "5"}}"#,
+        )
+        .unwrap();
+        assert_eq!(conventional_port(dir.path(), &node), Some((5173, "Vite")));
+
+        let python = ProjectType::Python {
+            has_requirements: true,
+            has_pyproject: false,
+        };
+        std::fs::write(dir.path().join("requirements.txt"), "Flask==3.0\n").unwrap();
+        assert_eq!(conventional_port(dir.path(), &python), Some((5000, "Flask")));
+        std::fs::write(dir.path().join("manage.py"), "").unwrap();
+        assert_eq!(conventional_port(dir.path(), &python), Some((8000, "Django")));
+        assert_eq!(conventional_port(dir.path(), &ProjectType::Unknown), None);
+
+        assert_eq!(
+            preferred_base(Some(PreferredPort::Port(4100)), dir.path(), &ProjectType::Unknown),
+            Some(4100)
+        );
+        assert_eq!(
+            preferred_base(Some(PreferredPort::Convention(PortConvention::Auto)), dir.path(), &python),
+            Some(8000)
+        );
+        assert_eq!(preferred_base(None, dir.path(), &python), None);
+    }
+
+    #[test]
+    fn test_port_owner() {
+        let review = |worktree_path: PathBuf| ReviewState {
+            pr_number: 1,
+            branch: "test".to_string(),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
+            worktree_path,
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
+            created_at: Utc::now(),
+            port: None,
+            project_type: None,
+            deps_installed: false,
+            env_copied: false,
+            tests: None,
+            seed: None,
+            expires_at: None,
+            agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
+        };
+        let elsewhere = tempfile::tempdir().unwrap();
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        // This test process holds the port and runs in the current directory
+        let here = review(std::env::current_dir().unwrap());
+        assert_eq!(port_owner(port, &here), PortOwner::Review);
+        assert!(matches!(port_owner(port, &review(elsewhere.path().to_path_buf())), PortOwner::Other { .. }));
+
+        drop(listener);
+        assert_eq!(port_owner(port, &here), PortOwner::Free);
+    }
 }
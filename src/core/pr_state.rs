@@ -0,0 +1,103 @@
+//! Tracking PR updates for `chaba watch`
+//!
+//! Each poll compares a PR's state and head commit on GitHub with its review
+//! worktree and decides what to do: pull new commits, or remove the
+//! environment once the PR is merged or closed.
+
+use std::fmt;
+
+/// Lifecycle state of a PR on GitHub
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrState {
+    Open,
+    Merged,
+    Closed,
+}
+
+impl PrState {
+    /// Parse gh's `state` field (`OPEN`, `MERGED`, `CLOSED`)
+    pub fn parse(state: &str) -> Option<Self> {
+        match state.to_ascii_uppercase().as_str() {
+            "OPEN" => Some(PrState::Open),
+            "MERGED" => Some(PrState::Merged),
+            "CLOSED" => Some(PrState::Closed),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PrState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrState::Open => write!(f, "open"),
+            PrState::Merged => write!(f, "merged"),
+            PrState::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+/// A PR's state and head commit, from `gh pr view --json state,headRefOid`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrStatus {
+    pub state: PrState,
+    pub head_sha: String,
+}
+
+/// What a poll should do with a review
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrUpdate {
+    /// The worktree already has the PR's head commit
+    UpToDate,
+    /// New commits were pushed; pull them
+    Pull,
+    /// The PR was merged or closed; remove the environment
+    Remove(PrState),
+}
+
+/// Decide how to bring a review up to date with its PR
+///
+/// `local_head` is the worktree's HEAD commit, if it could be read.
+pub fn plan(status: &PrStatus, local_head: Option<&str>) -> PrUpdate {
+    if status.state != PrState::Open {
+        PrUpdate::Remove(status.state)
+    } else if local_head == Some(status.head_sha.as_str()) {
+        PrUpdate::UpToDate
+    } else {
+        PrUpdate::Pull
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(state: PrState, head_sha: &str) -> PrStatus {
+        PrStatus {
+            state,
+            head_sha: head_sha.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_state() {
+        assert_eq!(PrState::parse("OPEN"), Some(PrState::Open));
+        assert_eq!(PrState::parse("merged"), Some(PrState::Merged));
+        assert_eq!(PrState::parse("CLOSED"), Some(PrState::Closed));
+        assert_eq!(PrState::parse("DRAFT"), None);
+    }
+
+    #[test]
+    fn test_plan() {
+        assert_eq!(plan(&status(PrState::Open, "abc"), Some("abc")), PrUpdate::UpToDate);
+        assert_eq!(plan(&status(PrState::Open, "def"), Some("abc")), PrUpdate::Pull);
+        assert_eq!(plan(&status(PrState::Open, "def"), None), PrUpdate::Pull);
+        assert_eq!(
+            plan(&status(PrState::Merged, "abc"), Some("abc")),
+            PrUpdate::Remove(PrState::Merged)
+        );
+        assert_eq!(
+            plan(&status(PrState::Closed, "def"), Some("abc")),
+            PrUpdate::Remove(PrState::Closed)
+        );
+    }
+}
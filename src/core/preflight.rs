@@ -0,0 +1,278 @@
+//! Agent availability checks
+//!
+//! Before agents are launched, each one is checked for its CLI on `PATH` and
+//! for credentials it can log in with, so a missing or logged-out agent fails
+//! at once with instructions instead of running into the timeout. `chaba
+//! doctor` shows the same checks.
+//!
+//! Credentials are recognized by the environment variables and files each CLI
+//! reads. Where a CLI keeps them somewhere chaba can't see (Claude Code on
+//! macOS uses the keychain), the check only warns.
+
+use std::path::{Path, PathBuf};
+
+use crate::core::command::CommandEnv;
+
+/// How an agent CLI is installed and how it finds credentials
+struct AgentSpec {
+    name: &'static str,
+    install: &'static str,
+    login: &'static str,
+    /// Any of these being set means the agent has credentials
+    vars: &'static [&'static str],
+    /// Credential files, relative to the home directory
+    files: &'static [&'static str],
+    /// Whether credentials may be stored where chaba can't check
+    opaque_store: bool,
+}
+
+const AGENTS: &[AgentSpec] = &[
+    AgentSpec {
+        name: "claude",
+        install: "npm install -g @anthropic-ai/claude-code",
+        login: "run 'claude' once and log in, or store an API key with 'chaba auth set claude'",
+        vars: &["ANTHROPIC_API_KEY", "CLAUDE_CODE_OAUTH_TOKEN", "CLAUDE_CODE_USE_BEDROCK", "CLAUDE_CODE_USE_VERTEX"],
+        files: &[".claude/.credentials.json"],
+        opaque_store: cfg!(target_os = "macos"),
+    },
+    AgentSpec {
+        name: "codex",
+        install: "npm install -g @openai/codex",
+        login: "run 'codex login', or store an API key with 'chaba auth set codex'",
+        vars: &["OPENAI_API_KEY", "CODEX_API_KEY"],
+        files: &[".codex/auth.json"],
+        opaque_store: false,
+    },
+    AgentSpec {
+        name: "gemini",
+        install: "npm install -g @google/gemini-cli",
+        login: "run 'gemini' once and sign in, or store an API key with 'chaba auth set gemini'",
+        vars: &["GEMINI_API_KEY", "GOOGLE_API_KEY", "GOOGLE_GENAI_USE_VERTEXAI", "GOOGLE_APPLICATION_CREDENTIALS"],
+        files: &[".gemini/oauth_creds.json"],
+        opaque_store: false,
+    },
+    AgentSpec {
+        name: "cursor-agent",
+        install: "curl https://cursor.com/install -fsS | bash",
+        login: "run 'cursor-agent login', or store an API key with 'chaba auth set cursor-agent'",
+        vars: &["CURSOR_API_KEY"],
+        files: &[],
+        // Logins are kept in the OS keychain
+        opaque_store: true,
+    },
+    AgentSpec {
+        name: "copilot",
+        install: "npm install -g @github/copilot",
+        login: "run 'copilot' once and use /login, or store a token with 'chaba auth set copilot'",
+        vars: &["COPILOT_GITHUB_TOKEN", "GH_TOKEN", "GITHUB_TOKEN"],
+        files: &[],
+        // Logins are kept in the OS keychain
+        opaque_store: true,
+    },
+];
+
+/// Outcome of one check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckStatus {
+    Ok(String),
+    /// Might not work; the agent is still run
+    Warning(String),
+    /// Won't work; the agent is skipped
+    Failed(String),
+}
+
+/// Result of checking one agent or tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    /// What to do about a warning or failure
+    pub hint: Option<String>,
+}
+
+impl Check {
+    pub fn is_available(&self) -> bool {
+        !matches!(self.status, CheckStatus::Failed(_))
+    }
+}
+
+/// Where the checks look for programs, variables, and credential files
+pub struct Environment<'a> {
+    pub path: Option<std::ffi::OsString>,
+    pub home: Option<PathBuf>,
+    /// Variables of chaba's own environment
+    pub var: &'a dyn Fn(&str) -> Option<String>,
+}
+
+impl Environment<'_> {
+    /// The environment chaba runs in
+    pub fn current() -> Environment<'static> {
+        Environment {
+            path: std::env::var_os("PATH"),
+            home: dirs::home_dir(),
+            var: &|name| std::env::var(name).ok(),
+        }
+    }
+}
+
+/// Find `program` in the directories of a `PATH` value
+pub fn find_in_path(program: &str, path: Option<&std::ffi::OsStr>) -> Option<PathBuf> {
+    let path = path?;
+    std::env::split_paths(path)
+        .map(|dir| dir.join(program))
+        .find(|candidate| is_executable(candidate))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file() || path.with_extension("exe").is_file() || path.with_extension("cmd").is_file()
+}
+
+/// Check that `agent` is installed and has credentials
+///
+/// `env` holds the overrides the agent is run with (see
+/// [`crate::core::secrets::resolve_agent_env`]).
+pub fn check_agent(agent: &str, env: &CommandEnv, environment: &Environment) -> Check {
+    let check = |status: CheckStatus, hint: Option<String>| Check {
+        name: agent.to_string(),
+        status,
+        hint,
+    };
+
+    let Some(spec) = AGENTS.iter().find(|spec| spec.name == agent) else {
+        return check(
+            CheckStatus::Failed(format!("unknown agent '{}'", agent)),
+            Some("Supported agents are claude, codex, gemini, cursor-agent, and copilot".to_string()),
+        );
+    };
+
+    let Some(program) = find_in_path(agent, environment.path.as_deref()) else {
+        return check(
+            CheckStatus::Failed(format!("'{}' not found on PATH", agent)),
+            Some(format!("Install it with: {}", spec.install)),
+        );
+    };
+
+    let is_set = |var: &str| {
+        if let Some((_, value)) = env.set.iter().find(|(key, _)| key == var) {
+            return !value.is_empty();
+        }
+        !env.remove.iter().any(|key| key == var) && (environment.var)(var).is_some_and(|value| !value.is_empty())
+    };
+    let has_file = |file: &str| environment.home.as_ref().is_some_and(|home| home.join(file).is_file());
+
+    let location = program.display().to_string();
+    if let Some(var) = spec.vars.iter().find(|var| is_set(var)) {
+        return check(CheckStatus::Ok(format!("{} ({})", location, var)), None);
+    }
+    if spec.files.iter().any(|file| has_file(file)) {
+        return check(CheckStatus::Ok(format!("{} (logged in)", location)), None);
+    }
+
+    let hint = Some(format!("To log in, {}", spec.login));
+    if spec.opaque_store {
+        return check(
+            CheckStatus::Warning(format!("{}; could not verify login", location)),
+            hint,
+        );
+    }
+    check(CheckStatus::Failed(format!("{} is not logged in", agent)), hint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn install(dir: &Path, program: &str) {
+        let path = dir.join(program);
+        std::fs::write(&path, "#!/bin/sh\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_missing_agent_fails_with_install_hint() {
+        let temp_dir = TempDir::new().unwrap();
+        let environment = Environment {
+            path: Some(temp_dir.path().as_os_str().to_owned()),
+            home: Some(temp_dir.path().to_path_buf()),
+            var: &|_| None,
+        };
+
+        let check = check_agent("codex", &CommandEnv::default(), &environment);
+
+        assert!(!check.is_available());
+        assert_eq!(check.status, CheckStatus::Failed("'codex' not found on PATH".to_string()));
+        assert_eq!(check.hint.as_deref(), Some("Install it with: npm install -g @openai/codex"));
+    }
+
+    #[test]
+    fn test_credentials_from_env_overrides_or_files() {
+        let temp_dir = TempDir::new().unwrap();
+        install(temp_dir.path(), "codex");
+        install(temp_dir.path(), "gemini");
+        let environment = Environment {
+            path: Some(temp_dir.path().as_os_str().to_owned()),
+            home: Some(temp_dir.path().to_path_buf()),
+            var: &|name| (name == "OPENAI_API_KEY").then(|| "sk-test".to_string()),
+        };
+
+        let codex = check_agent("codex", &CommandEnv::default(), &environment);
+        assert!(matches!(codex.status, CheckStatus::Ok(ref detail) if detail.ends_with("(OPENAI_API_KEY)")));
+
+        // A variable removed from the agent's environment doesn't count
+        let isolated = CommandEnv {
+            set: Vec::new(),
+            remove: vec!["OPENAI_API_KEY".to_string()],
+        };
+        let codex = check_agent("codex", &isolated, &environment);
+        assert_eq!(codex.status, CheckStatus::Failed("codex is not logged in".to_string()));
+        assert!(codex.hint.unwrap().contains("codex login"));
+
+        let gemini = check_agent("gemini", &CommandEnv::default(), &environment);
+        assert!(!gemini.is_available());
+        std::fs::create_dir_all(temp_dir.path().join(".gemini")).unwrap();
+        std::fs::write(temp_dir.path().join(".gemini/oauth_creds.json"), "{}").unwrap();
+        let gemini = check_agent("gemini", &CommandEnv::default(), &environment);
+        assert!(matches!(gemini.status, CheckStatus::Ok(ref detail) if detail.ends_with("(logged in)")));
+    }
+
+    #[test]
+    fn test_keychain_logins_only_warn() {
+        let temp_dir = TempDir::new().unwrap();
+        install(temp_dir.path(), "copilot");
+        let environment = Environment {
+            path: Some(temp_dir.path().as_os_str().to_owned()),
+            home: Some(temp_dir.path().to_path_buf()),
+            var: &|_| None,
+        };
+
+        let copilot = check_agent("copilot", &CommandEnv::default(), &environment);
+        assert!(matches!(copilot.status, CheckStatus::Warning(_)));
+        assert!(copilot.is_available());
+
+        let token = CommandEnv {
+            set: vec![("GH_TOKEN".to_string(), "ghp_test".to_string())],
+            remove: Vec::new(),
+        };
+        let copilot = check_agent("copilot", &token, &environment);
+        assert!(matches!(copilot.status, CheckStatus::Ok(ref detail) if detail.ends_with("(GH_TOKEN)")));
+    }
+
+    #[test]
+    fn test_unknown_agent() {
+        let check = check_agent("aider", &CommandEnv::default(), &Environment::current());
+        assert_eq!(check.status, CheckStatus::Failed("unknown agent 'aider'".to_string()));
+    }
+}
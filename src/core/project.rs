@@ -51,6 +51,15 @@ impl NodePackageManager {
             NodePackageManager::Bun => "bun install",
         }
     }
+
+    pub fn lock_file(&self) -> &str {
+        match self {
+            NodePackageManager::Npm => "package-lock.json",
+            NodePackageManager::Yarn => "yarn.lock",
+            NodePackageManager::Pnpm => "pnpm-lock.yaml",
+            NodePackageManager::Bun => "bun.lockb",
+        }
+    }
 }
 
 /// Detect project type from worktree path
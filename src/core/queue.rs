@@ -0,0 +1,105 @@
+//! Selecting PRs from the open review queue
+//!
+//! `chaba review --all-open` lists the repository's open PRs and creates a
+//! review environment for each one that passes a [`PrFilter`].
+
+use crate::core::git::OpenPr;
+
+/// Criteria an open PR must meet to be reviewed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrFilter {
+    /// Labels the PR must all have
+    pub labels: Vec<String>,
+    /// GitHub login of the PR author
+    pub author: Option<String>,
+    /// `Some(true)` for drafts only, `Some(false)` to skip drafts
+    pub draft: Option<bool>,
+}
+
+impl PrFilter {
+    pub fn matches(&self, pr: &OpenPr) -> bool {
+        let labels_match = self
+            .labels
+            .iter()
+            .all(|label| pr.info.labels.iter().any(|l| l.eq_ignore_ascii_case(label)));
+        let author_match = self
+            .author
+            .as_deref()
+            .is_none_or(|author| pr.info.author.eq_ignore_ascii_case(author.trim_start_matches('@')));
+        let draft_match = self.draft.is_none_or(|draft| pr.info.draft == draft);
+
+        labels_match && author_match && draft_match
+    }
+}
+
+/// PRs from `open` that match `filter` and don't have a review environment yet
+///
+/// Keeps gh's order (newest first).
+pub fn select(open: Vec<OpenPr>, filter: &PrFilter, existing: &[u32]) -> Vec<OpenPr> {
+    open.into_iter()
+        .filter(|pr| filter.matches(pr) && !existing.contains(&pr.number))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::git::PrInfo;
+
+    fn pr(number: u32, author: &str, draft: bool, labels: &[&str]) -> OpenPr {
+        OpenPr {
+            number,
+            info: PrInfo {
+                title: format!("PR {}", number),
+                author: author.to_string(),
+                draft,
+                labels: labels.iter().map(|l| l.to_string()).collect(),
+            },
+        }
+    }
+
+    fn queue() -> Vec<OpenPr> {
+        vec![
+            pr(14, "octocat", false, &["bug", "backend"]),
+            pr(13, "hubot", true, &["bug"]),
+            pr(12, "octocat", true, &[]),
+            pr(11, "monalisa", false, &["docs"]),
+        ]
+    }
+
+    fn numbers(prs: &[OpenPr]) -> Vec<u32> {
+        prs.iter().map(|pr| pr.number).collect()
+    }
+
+    #[test]
+    fn test_select_all() {
+        assert_eq!(numbers(&select(queue(), &PrFilter::default(), &[])), vec![14, 13, 12, 11]);
+    }
+
+    #[test]
+    fn test_select_by_label_author_and_draft() {
+        let by_label = PrFilter {
+            labels: vec!["BUG".to_string()],
+            ..PrFilter::default()
+        };
+        assert_eq!(numbers(&select(queue(), &by_label, &[])), vec![14, 13]);
+
+        let by_author = PrFilter {
+            author: Some("@octocat".to_string()),
+            draft: Some(false),
+            ..PrFilter::default()
+        };
+        assert_eq!(numbers(&select(queue(), &by_author, &[])), vec![14]);
+
+        let drafts = PrFilter {
+            draft: Some(true),
+            ..PrFilter::default()
+        };
+        assert_eq!(numbers(&select(queue(), &drafts, &[])), vec![13, 12]);
+    }
+
+    #[test]
+    fn test_select_skips_existing_reviews() {
+        assert_eq!(numbers(&select(queue(), &PrFilter::default(), &[13, 11])), vec![14, 12]);
+    }
+}
@@ -0,0 +1,289 @@
+//! Agent run quotas
+//!
+//! `chaba review --all-open` and `chaba watch` can start agents for many PRs
+//! without anyone watching, so their runs count against `agents.quota`: a cap
+//! per day and per PR author per day. Runs that would exceed a cap are
+//! deferred instead of started. The ledger of runs and deferred work is kept
+//! in `~/.chaba/quota.json`; `chaba queue` shows it, and `chaba watch` starts
+//! deferred runs once the quota allows.
+
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::config::QuotaConfig;
+use crate::core::state;
+use crate::error::{ChabaError, Result};
+
+/// An agent run counted against the quota
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub pr_number: u32,
+    /// PR author, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Number of agents the run invoked
+    pub agents: u32,
+    pub at: DateTime<Utc>,
+}
+
+/// An agent run held back because it would exceed the quota
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeferredRun {
+    pub pr_number: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub thorough: bool,
+    /// Which cap was reached
+    pub reason: String,
+    /// When the run was first deferred
+    pub deferred_at: DateTime<Utc>,
+}
+
+/// Runs counted today and runs waiting for quota
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuotaLedger {
+    #[serde(default)]
+    pub runs: Vec<RunRecord>,
+    /// Oldest first
+    #[serde(default)]
+    pub deferred: Vec<DeferredRun>,
+}
+
+/// Ledger file, `~/.chaba/quota.json` by default
+pub fn ledger_path() -> Result<PathBuf> {
+    Ok(state::state_dir()?.join("quota.json"))
+}
+
+/// Local calendar day of a timestamp; quotas reset at local midnight
+fn day(at: DateTime<Utc>) -> NaiveDate {
+    at.with_timezone(&Local).date_naive()
+}
+
+impl QuotaLedger {
+    /// Load the ledger, empty if it doesn't exist yet
+    pub fn load() -> Result<Self> {
+        Self::load_from(&ledger_path()?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| {
+            ChabaError::ConfigError(format!("Failed to parse {}: {}", path.display(), e))
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&ledger_path()?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| ChabaError::Other(e.into()))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Agent runs counted on the day of `now`, for one author or everyone
+    pub fn used(&self, author: Option<&str>, now: DateTime<Utc>) -> u32 {
+        let today = day(now);
+        self.runs
+            .iter()
+            .filter(|run| day(run.at) == today)
+            .filter(|run| author.is_none_or(|author| run.author.as_deref() == Some(author)))
+            .map(|run| run.agents)
+            .sum()
+    }
+
+    /// Authors with runs today and how many runs each used, busiest first
+    pub fn used_by_author(&self, now: DateTime<Utc>) -> Vec<(String, u32)> {
+        let mut authors: Vec<String> = self
+            .runs
+            .iter()
+            .filter(|run| day(run.at) == day(now))
+            .filter_map(|run| run.author.clone())
+            .collect();
+        authors.sort();
+        authors.dedup();
+
+        let mut used: Vec<(String, u32)> = authors
+            .into_iter()
+            .map(|author| {
+                let runs = self.used(Some(&author), now);
+                (author, runs)
+            })
+            .collect();
+        used.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        used
+    }
+
+    /// Why a run of `agents` agents would exceed the quota, if it would
+    pub fn exceeded(&self, quota: &QuotaConfig, author: Option<&str>, agents: u32, now: DateTime<Utc>) -> Option<String> {
+        if let Some(per_day) = quota.per_day {
+            let used = self.used(None, now);
+            if used + agents > per_day {
+                return Some(format!("daily quota reached ({}/{} runs)", used, per_day));
+            }
+        }
+        if let (Some(per_user), Some(author)) = (quota.per_user, author) {
+            let used = self.used(Some(author), now);
+            if used + agents > per_user {
+                return Some(format!("quota for @{} reached ({}/{} runs)", author, used, per_user));
+            }
+        }
+        None
+    }
+
+    /// Count a run, dropping any deferred run for the PR and runs before yesterday
+    pub fn record(&mut self, pr_number: u32, author: Option<String>, agents: u32, now: DateTime<Utc>) {
+        let cutoff = day(now - Duration::days(1));
+        self.runs.retain(|run| day(run.at) >= cutoff);
+        self.forget(pr_number);
+        self.runs.push(RunRecord {
+            pr_number,
+            author,
+            agents,
+            at: now,
+        });
+    }
+
+    /// Hold a run back, keeping its place in the queue if it was deferred before
+    pub fn defer(&mut self, pr_number: u32, author: Option<String>, thorough: bool, reason: String, now: DateTime<Utc>) {
+        if let Some(existing) = self.deferred.iter_mut().find(|run| run.pr_number == pr_number) {
+            existing.author = author;
+            existing.thorough |= thorough;
+            existing.reason = reason;
+            return;
+        }
+        self.deferred.push(DeferredRun {
+            pr_number,
+            author,
+            thorough,
+            reason,
+            deferred_at: now,
+        });
+    }
+
+    /// Drop the deferred run for a PR, returning whether there was one
+    pub fn forget(&mut self, pr_number: u32) -> bool {
+        let before = self.deferred.len();
+        self.deferred.retain(|run| run.pr_number != pr_number);
+        self.deferred.len() != before
+    }
+}
+
+/// Count a run against the quota, or defer it when it would exceed a cap
+///
+/// Returns the reason when the run was deferred. Without a quota configured
+/// every run is allowed and nothing is recorded.
+pub fn reserve(quota: &QuotaConfig, pr_number: u32, author: Option<&str>, agents: usize, thorough: bool) -> Result<Option<String>> {
+    if !quota.is_limited() {
+        return Ok(None);
+    }
+
+    let now = Utc::now();
+    let agents = agents as u32;
+    let mut ledger = QuotaLedger::load()?;
+    let reason = ledger.exceeded(quota, author, agents, now);
+    match &reason {
+        Some(reason) => ledger.defer(pr_number, author.map(str::to_string), thorough, reason.clone(), now),
+        None => ledger.record(pr_number, author.map(str::to_string), agents, now),
+    }
+    ledger.save()?;
+
+    Ok(reason)
+}
+
+/// Drop the deferred run for a PR, e.g. after its agents ran by hand
+pub fn forget(pr_number: u32) -> Result<()> {
+    let path = ledger_path()?;
+    if !path.exists() {
+        return Ok(());
+    }
+    let mut ledger = QuotaLedger::load_from(&path)?;
+    if ledger.forget(pr_number) {
+        ledger.save_to(&path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quota(per_day: Option<u32>, per_user: Option<u32>) -> QuotaConfig {
+        QuotaConfig { per_day, per_user }
+    }
+
+    #[test]
+    fn test_exceeded_per_day_and_per_user() {
+        let now = Utc::now();
+        let mut ledger = QuotaLedger::default();
+        ledger.record(1, Some("octocat".to_string()), 3, now);
+        ledger.record(2, Some("hubot".to_string()), 1, now);
+
+        let quota = quota(Some(5), Some(3));
+        assert_eq!(ledger.exceeded(&quota, Some("hubot"), 1, now), None);
+        assert_eq!(
+            ledger.exceeded(&quota, Some("octocat"), 1, now).as_deref(),
+            Some("quota for @octocat reached (3/3 runs)")
+        );
+        assert_eq!(
+            ledger.exceeded(&quota, Some("hubot"), 2, now).as_deref(),
+            Some("daily quota reached (4/5 runs)")
+        );
+        // Without a known author only the daily cap applies
+        assert_eq!(ledger.exceeded(&quota, None, 1, now), None);
+        assert_eq!(ledger.used_by_author(now), vec![("octocat".to_string(), 3), ("hubot".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_runs_from_earlier_days_do_not_count() {
+        let now = Utc::now();
+        let mut ledger = QuotaLedger::default();
+        ledger.record(1, None, 4, now - Duration::days(3));
+        ledger.record(2, None, 2, now - Duration::days(1));
+        assert_eq!(ledger.used(None, now - Duration::days(1)), 2);
+
+        ledger.record(3, None, 1, now);
+
+        assert_eq!(ledger.used(None, now), 1);
+        // Only today's and yesterday's runs are kept
+        assert_eq!(ledger.runs.iter().map(|run| run.pr_number).collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_defer_keeps_queue_position_until_run() {
+        let now = Utc::now();
+        let mut ledger = QuotaLedger::default();
+        ledger.defer(7, None, false, "daily quota reached (5/5 runs)".to_string(), now);
+        ledger.defer(8, None, true, "daily quota reached (5/5 runs)".to_string(), now);
+        ledger.defer(7, None, true, "daily quota reached (6/5 runs)".to_string(), now + Duration::hours(1));
+
+        assert_eq!(ledger.deferred.iter().map(|run| run.pr_number).collect::<Vec<_>>(), vec![7, 8]);
+        assert_eq!(ledger.deferred[0].deferred_at, now);
+        assert!(ledger.deferred[0].thorough);
+
+        ledger.record(7, None, 1, now);
+        assert_eq!(ledger.deferred.iter().map(|run| run.pr_number).collect::<Vec<_>>(), vec![8]);
+    }
+
+    #[test]
+    fn test_ledger_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("quota.json");
+        assert_eq!(QuotaLedger::load_from(&path).unwrap(), QuotaLedger::default());
+
+        let mut ledger = QuotaLedger::default();
+        ledger.record(1, Some("octocat".to_string()), 2, Utc::now());
+        ledger.defer(2, None, false, "daily quota reached (2/2 runs)".to_string(), Utc::now());
+        ledger.save_to(&path).unwrap();
+
+        assert_eq!(QuotaLedger::load_from(&path).unwrap(), ledger);
+    }
+}
@@ -0,0 +1,339 @@
+//! Record and replay external commands
+//!
+//! [`RecordingCommandRunner`] wraps a real runner and saves every command it
+//! runs, with its exit code and output, to a JSON fixture file.
+//! [`ReplayCommandRunner`] serves those outputs back without spawning
+//! anything, so a captured git/gh interaction can be replayed as a
+//! deterministic regression test.
+//!
+//! Commands are matched by program and arguments; the working directory and
+//! environment are not recorded. Paths that differ between runs (temporary
+//! directories, home) can be swapped for placeholders with `placeholder`.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use chaba::core::command::{CommandRunner, LiveCommandRunner};
+//! use chaba::core::replay::{RecordingCommandRunner, ReplayCommandRunner};
+//! use std::path::Path;
+//! use std::sync::Arc;
+//!
+//! # async fn example(repo: &Path) -> Result<(), Box<dyn std::error::Error>> {
+//! let fixture = Path::new("tests/fixtures/pr-view.json");
+//!
+//! // Capture once against the real tools...
+//! let recorder = RecordingCommandRunner::new(Arc::new(LiveCommandRunner), fixture)
+//!     .placeholder("$REPO", repo);
+//! recorder.run("gh", &["pr".as_ref(), "view".as_ref(), "42".as_ref()], repo).await?;
+//!
+//! // ...then replay without network access
+//! let replay = ReplayCommandRunner::load(fixture)?.placeholder("$REPO", repo);
+//! let output = replay.run("gh", &["pr".as_ref(), "view".as_ref(), "42".as_ref()], repo).await?;
+//! assert!(replay.remaining().is_empty());
+//! # Ok(())
+//! # }
+//! ```
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::sync::{Arc, Mutex};
+
+use crate::core::command::{CommandEnv, CommandRunner, LineSink};
+use crate::core::platform::exit_status;
+
+/// A command and what it produced
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    /// Exit code, or `-1` if the process was killed by a signal
+    pub code: i32,
+    #[serde(default)]
+    pub stdout: String,
+    #[serde(default)]
+    pub stderr: String,
+}
+
+/// Placeholder substitutions applied to recorded text
+#[derive(Debug, Clone, Default)]
+struct Placeholders(Vec<(String, String)>);
+
+impl Placeholders {
+    fn add(&mut self, name: &str, value: &Path) {
+        self.0.push((name.to_string(), value.to_string_lossy().into_owned()));
+        // Longest values first, so a path isn't replaced by a prefix of itself
+        self.0.sort_by_key(|(_, value)| std::cmp::Reverse(value.len()));
+    }
+
+    /// Replace real values with placeholder names
+    fn redact(&self, text: &str) -> String {
+        self.0
+            .iter()
+            .fold(text.to_string(), |text, (name, value)| text.replace(value.as_str(), name))
+    }
+
+    /// Replace placeholder names with real values
+    fn expand(&self, text: &str) -> String {
+        self.0
+            .iter()
+            .fold(text.to_string(), |text, (name, value)| text.replace(name.as_str(), value))
+    }
+
+    fn expand_all(&self, texts: &[String]) -> Vec<String> {
+        texts.iter().map(|text| self.expand(text)).collect()
+    }
+}
+
+fn args_to_strings(args: &[&OsStr]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string_lossy().into_owned()).collect()
+}
+
+fn other_error(message: String) -> io::Error {
+    io::Error::other(message)
+}
+
+/// Runner decorator that saves every command and its output to a fixture file
+///
+/// The file is rewritten after each command, so it is complete even if the
+/// process exits early.
+pub struct RecordingCommandRunner {
+    inner: Arc<dyn CommandRunner + Send + Sync>,
+    path: PathBuf,
+    placeholders: Placeholders,
+    records: Mutex<Vec<RecordedCommand>>,
+}
+
+impl RecordingCommandRunner {
+    pub fn new(inner: Arc<dyn CommandRunner + Send + Sync>, path: impl Into<PathBuf>) -> Self {
+        RecordingCommandRunner {
+            inner,
+            path: path.into(),
+            placeholders: Placeholders::default(),
+            records: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Record occurrences of `value` in arguments and output as `name`
+    pub fn placeholder(mut self, name: &str, value: &Path) -> Self {
+        self.placeholders.add(name, value);
+        self
+    }
+
+    /// Commands recorded so far
+    pub fn records(&self) -> Vec<RecordedCommand> {
+        self.records.lock().unwrap().clone()
+    }
+
+    fn record(&self, program: &str, args: &[&OsStr], output: &Output) -> io::Result<()> {
+        let record = RecordedCommand {
+            program: program.to_string(),
+            args: args_to_strings(args)
+                .iter()
+                .map(|arg| self.placeholders.redact(arg))
+                .collect(),
+            code: output.status.code().unwrap_or(-1),
+            stdout: self.placeholders.redact(&String::from_utf8_lossy(&output.stdout)),
+            stderr: self.placeholders.redact(&String::from_utf8_lossy(&output.stderr)),
+        };
+
+        let mut records = self.records.lock().unwrap();
+        records.push(record);
+
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(&*records)
+            .map_err(|e| other_error(format!("Failed to serialize recorded commands: {}", e)))?;
+        std::fs::write(&self.path, json)
+    }
+}
+
+#[async_trait]
+impl CommandRunner for RecordingCommandRunner {
+    async fn run(
+        &self,
+        program: &str,
+        args: &[&OsStr],
+        current_dir: &Path,
+    ) -> Result<Output, io::Error> {
+        self.run_with_env(program, args, current_dir, &CommandEnv::default())
+            .await
+    }
+
+    async fn run_with_env(
+        &self,
+        program: &str,
+        args: &[&OsStr],
+        current_dir: &Path,
+        env: &CommandEnv,
+    ) -> Result<Output, io::Error> {
+        let output = self.inner.run_with_env(program, args, current_dir, env).await?;
+        self.record(program, args, &output)?;
+        Ok(output)
+    }
+
+    async fn run_streaming(
+        &self,
+        program: &str,
+        args: &[&OsStr],
+        current_dir: &Path,
+        env: &CommandEnv,
+        on_line: &LineSink<'_>,
+    ) -> Result<Output, io::Error> {
+        let output = self
+            .inner
+            .run_streaming(program, args, current_dir, env, on_line)
+            .await?;
+        self.record(program, args, &output)?;
+        Ok(output)
+    }
+}
+
+/// Runner that answers commands from a recorded fixture
+///
+/// Each call is served by the first unused recording with the same program
+/// and arguments, so repeated commands replay in order while commands run
+/// concurrently (e.g. parallel agents) may arrive in any order. A command
+/// with no matching recording fails with an error naming it.
+pub struct ReplayCommandRunner {
+    records: Vec<RecordedCommand>,
+    placeholders: Placeholders,
+    used: Mutex<Vec<bool>>,
+}
+
+impl ReplayCommandRunner {
+    pub fn new(records: Vec<RecordedCommand>) -> Self {
+        let used = Mutex::new(vec![false; records.len()]);
+        ReplayCommandRunner {
+            records,
+            placeholders: Placeholders::default(),
+            used,
+        }
+    }
+
+    /// Load a fixture written by [`RecordingCommandRunner`]
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        let records = serde_json::from_str(&json).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid command fixture {}: {}", path.display(), e),
+            )
+        })?;
+        Ok(Self::new(records))
+    }
+
+    /// Replay `name` in recorded arguments and output as `value`
+    pub fn placeholder(mut self, name: &str, value: &Path) -> Self {
+        self.placeholders.add(name, value);
+        self
+    }
+
+    /// Recordings that haven't been replayed yet
+    pub fn remaining(&self) -> Vec<RecordedCommand> {
+        let used = self.used.lock().unwrap();
+        self.records
+            .iter()
+            .zip(used.iter())
+            .filter(|(_, used)| !**used)
+            .map(|(record, _)| record.clone())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl CommandRunner for ReplayCommandRunner {
+    async fn run(
+        &self,
+        program: &str,
+        args: &[&OsStr],
+        _current_dir: &Path,
+    ) -> Result<Output, io::Error> {
+        let args = args_to_strings(args);
+        let mut used = self.used.lock().unwrap();
+
+        let index = self
+            .records
+            .iter()
+            .enumerate()
+            .position(|(index, record)| {
+                !used[index] && record.program == program && self.placeholders.expand_all(&record.args) == args
+            })
+            .ok_or_else(|| other_error(format!("No recorded output for: {} {}", program, args.join(" "))))?;
+        used[index] = true;
+
+        let record = &self.records[index];
+        Ok(Output {
+            status: exit_status(record.code),
+            stdout: self.placeholders.expand(&record.stdout).into_bytes(),
+            stderr: self.placeholders.expand(&record.stderr).into_bytes(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::command::LiveCommandRunner;
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let fixture = temp_dir.path().join("fixtures/commands.json");
+        let dir = temp_dir.path();
+
+        let recorder = RecordingCommandRunner::new(Arc::new(LiveCommandRunner), &fixture).placeholder("$TMP", dir);
+        let first = recorder
+            .run("sh", &["-c".as_ref(), "echo one".as_ref()], dir)
+            .await
+            .unwrap();
+        let failed = recorder
+            .run("sh", &["-c".as_ref(), "pwd; echo oops >&2; exit 3".as_ref()], dir)
+            .await
+            .unwrap();
+
+        let records = recorder.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].code, 3);
+        assert!(records[1].stdout.starts_with("$TMP"));
+
+        let replay = ReplayCommandRunner::load(&fixture).unwrap().placeholder("$TMP", dir);
+        let replayed_failure = replay
+            .run("sh", &["-c".as_ref(), "pwd; echo oops >&2; exit 3".as_ref()], dir)
+            .await
+            .unwrap();
+        let replayed_first = replay
+            .run("sh", &["-c".as_ref(), "echo one".as_ref()], dir)
+            .await
+            .unwrap();
+
+        assert_eq!(replayed_first, first);
+        assert_eq!(replayed_failure, failed);
+        assert_eq!(replayed_failure.status.code(), Some(3));
+        assert!(replay.remaining().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_replay_serves_repeated_commands_in_order() {
+        let record = |stdout: &str| RecordedCommand {
+            program: "gh".to_string(),
+            args: vec!["pr".to_string(), "checks".to_string()],
+            code: 0,
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+        };
+        let replay = ReplayCommandRunner::new(vec![record("pending"), record("pass")]);
+        let dir = Path::new(".");
+        let args: &[&OsStr] = &["pr".as_ref(), "checks".as_ref()];
+
+        assert_eq!(replay.run("gh", args, dir).await.unwrap().stdout, b"pending");
+        assert_eq!(replay.run("gh", args, dir).await.unwrap().stdout, b"pass");
+
+        let error = replay.run("gh", args, dir).await.unwrap_err();
+        assert_eq!(error.to_string(), "No recorded output for: gh pr checks");
+    }
+}
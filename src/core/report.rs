@@ -0,0 +1,195 @@
+//! Review summary reports
+//!
+//! `chaba report --summary` combines the active reviews with the archived
+//! ones (see [`crate::core::archive`]) to show how AI review was used over a
+//! period.
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::core::review_analysis::{Category, Severity};
+use crate::core::state::ReviewState;
+
+/// Severity and category of a finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FindingKind {
+    pub severity: Severity,
+    pub category: Category,
+}
+
+/// Summary of one review, active or cleaned up
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReviewRecord {
+    pub pr_number: u32,
+    /// Main worktree of the review's repository, when recorded
+    pub repo: Option<PathBuf>,
+    pub created_at: DateTime<Utc>,
+    /// When the review was cleaned up; `None` while it is active
+    pub closed_at: Option<DateTime<Utc>>,
+    /// Agent of every agent run on the review, including earlier runs
+    pub agent_runs: Vec<String>,
+    /// Findings of the latest agent run
+    pub findings: Vec<FindingKind>,
+}
+
+impl ReviewRecord {
+    pub fn from_review(review: &ReviewState, closed_at: Option<DateTime<Utc>>) -> Self {
+        let agent_runs = review
+            .analysis_history
+            .iter()
+            .flat_map(|run| &run.analyses)
+            .chain(&review.agent_analyses)
+            .map(|analysis| analysis.agent.clone())
+            .collect();
+        let findings = review
+            .agent_analyses
+            .iter()
+            .flat_map(|analysis| &analysis.findings)
+            .map(|finding| FindingKind {
+                severity: finding.severity,
+                category: finding.category,
+            })
+            .collect();
+
+        ReviewRecord {
+            pr_number: review.pr_number,
+            repo: review.repo.clone(),
+            created_at: review.created_at,
+            closed_at,
+            agent_runs,
+            findings,
+        }
+    }
+
+    /// Repository name for per-repo breakdowns
+    fn repo_name(&self) -> String {
+        self.repo
+            .as_deref()
+            .and_then(Path::file_name)
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "(unknown)".to_string())
+    }
+}
+
+/// Activity of one repository in a [`Summary`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepoSummary {
+    pub reviews: usize,
+    pub agent_runs: usize,
+    pub findings: usize,
+}
+
+/// Reviews created in a period, aggregated
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Summary {
+    pub reviews: usize,
+    /// Reviews not cleaned up yet
+    pub active: usize,
+    /// Agent runs by agent
+    pub agent_runs: BTreeMap<String, usize>,
+    pub severities: HashMap<Severity, usize>,
+    pub categories: HashMap<Category, usize>,
+    /// Mean time from creation to cleanup of the cleaned-up reviews
+    pub average_time_to_cleanup: Option<Duration>,
+    /// By repository name
+    pub repos: BTreeMap<String, RepoSummary>,
+}
+
+impl Summary {
+    pub fn findings(&self) -> usize {
+        self.severities.values().sum()
+    }
+}
+
+/// Aggregate the reviews created at or after `since`
+pub fn summarize(records: &[ReviewRecord], since: DateTime<Utc>) -> Summary {
+    let mut summary = Summary::default();
+    let mut cleanup_times = Vec::new();
+
+    for record in records.iter().filter(|record| record.created_at >= since) {
+        summary.reviews += 1;
+        match record.closed_at {
+            Some(closed_at) => cleanup_times.push(closed_at - record.created_at),
+            None => summary.active += 1,
+        }
+        for agent in &record.agent_runs {
+            *summary.agent_runs.entry(agent.clone()).or_default() += 1;
+        }
+        for finding in &record.findings {
+            *summary.severities.entry(finding.severity).or_default() += 1;
+            *summary.categories.entry(finding.category).or_default() += 1;
+        }
+
+        let repo = summary.repos.entry(record.repo_name()).or_default();
+        repo.reviews += 1;
+        repo.agent_runs += record.agent_runs.len();
+        repo.findings += record.findings.len();
+    }
+
+    if !cleanup_times.is_empty() {
+        let total = cleanup_times.iter().fold(Duration::zero(), |total, time| total + *time);
+        summary.average_time_to_cleanup = Some(total / cleanup_times.len() as i32);
+    }
+    summary
+}
+
+/// Human-readable duration, e.g. `2d 3h` or `45m`
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    match (days, hours) {
+        (0, 0) => format!("{}m", minutes),
+        (0, _) => format!("{}h {}m", hours, minutes),
+        _ => format!("{}d {}h", days, hours),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(pr_number: u32, repo: &str, days_ago: i64, closed_after_hours: Option<i64>) -> ReviewRecord {
+        let created_at = Utc::now() - Duration::days(days_ago);
+        ReviewRecord {
+            pr_number,
+            repo: Some(PathBuf::from(repo)),
+            created_at,
+            closed_at: closed_after_hours.map(|hours| created_at + Duration::hours(hours)),
+            agent_runs: vec!["claude".to_string(), "codex".to_string()],
+            findings: vec![
+                FindingKind { severity: Severity::High, category: Category::Security },
+                FindingKind { severity: Severity::Low, category: Category::Testing },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let records = vec![
+            record(1, "/src/api", 2, Some(10)),
+            record(2, "/src/api", 3, Some(20)),
+            record(3, "/src/web", 1, None),
+            // Outside the period
+            record(4, "/src/web", 30, Some(1)),
+        ];
+
+        let summary = summarize(&records, Utc::now() - Duration::days(7));
+        assert_eq!(summary.reviews, 3);
+        assert_eq!(summary.active, 1);
+        assert_eq!(summary.agent_runs["claude"], 3);
+        assert_eq!(summary.severities[&Severity::High], 3);
+        assert_eq!(summary.categories[&Category::Testing], 3);
+        assert_eq!(summary.findings(), 6);
+        assert_eq!(summary.average_time_to_cleanup, Some(Duration::hours(15)));
+        assert_eq!(summary.repos["api"], RepoSummary { reviews: 2, agent_runs: 4, findings: 4 });
+        assert_eq!(summary.repos["web"].reviews, 1);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::minutes(45)), "45m");
+        assert_eq!(format_duration(Duration::minutes(200)), "3h 20m");
+        assert_eq!(format_duration(Duration::hours(51)), "2d 3h");
+    }
+}
@@ -0,0 +1,293 @@
+//! Per-review resource usage
+//!
+//! Dev servers, test runs, and agents all run with their working directory
+//! inside a review worktree, so processes are attributed to a review by their
+//! current directory. `chaba status` and the TUI show the totals and warn
+//! when they cross the thresholds in the `monitor` config section.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+use crate::config::MonitorConfig;
+use crate::core::platform;
+use crate::core::state::{ReviewKey, ReviewState};
+
+/// Resource usage of a single process
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessUsage {
+    pub pid: u32,
+    pub name: String,
+    /// CPU usage, where 100% is one full core
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+}
+
+/// Resource usage of all processes running in a review worktree
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResourceUsage {
+    /// Processes, heaviest memory users first
+    pub processes: Vec<ProcessUsage>,
+}
+
+impl ResourceUsage {
+    /// Total CPU usage, where 100% is one full core
+    pub fn cpu_percent(&self) -> f32 {
+        self.processes.iter().map(|p| p.cpu_percent).sum()
+    }
+
+    /// Total resident memory
+    pub fn memory_bytes(&self) -> u64 {
+        self.processes.iter().map(|p| p.memory_bytes).sum()
+    }
+
+    /// One-line summary, e.g. `12.5% CPU, 512.0 MB (3 processes)`
+    pub fn summary(&self) -> String {
+        format!(
+            "{:.1}% CPU, {} ({} process{})",
+            self.cpu_percent(),
+            format_bytes(self.memory_bytes()),
+            self.processes.len(),
+            if self.processes.len() == 1 { "" } else { "es" }
+        )
+    }
+
+    /// Thresholds from `config` that this usage exceeds
+    pub fn alerts(&self, config: &MonitorConfig) -> Vec<String> {
+        let mut alerts = Vec::new();
+
+        if let Some(limit) = config.cpu_alert_percent {
+            if self.cpu_percent() > limit {
+                alerts.push(format!("CPU {:.1}% exceeds {:.0}%", self.cpu_percent(), limit));
+            }
+        }
+        if let Some(limit) = config.memory_alert_mb {
+            if self.memory_bytes() > limit * 1024 * 1024 {
+                alerts.push(format!(
+                    "memory {} exceeds {} MB",
+                    format_bytes(self.memory_bytes()),
+                    limit
+                ));
+            }
+        }
+
+        alerts
+    }
+}
+
+/// Format a byte count as MB or GB
+pub fn format_bytes(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    let mb = bytes as f64 / MB;
+    if mb >= 1024.0 {
+        format!("{:.1} GB", mb / 1024.0)
+    } else {
+        format!("{:.1} MB", mb)
+    }
+}
+
+/// Group processes by the review whose worktree contains their working directory
+///
+/// When worktrees are nested, the deepest one wins. Reviews without any
+/// processes are left out.
+pub fn attribute(
+    processes: impl IntoIterator<Item = (PathBuf, ProcessUsage)>,
+    reviews: &[ReviewState],
+) -> BTreeMap<ReviewKey, ResourceUsage> {
+    // Process working directories are canonical, state paths may not be
+    let worktrees: Vec<(ReviewKey, PathBuf)> = reviews
+        .iter()
+        .map(|review| {
+            let path = platform::canonicalize(&review.worktree_path)
+                .unwrap_or_else(|_| review.worktree_path.clone());
+            (review.key(), path)
+        })
+        .collect();
+
+    let mut usage: BTreeMap<ReviewKey, ResourceUsage> = BTreeMap::new();
+    for (cwd, process) in processes {
+        let owner = worktrees
+            .iter()
+            .filter(|(_, path)| cwd.starts_with(path))
+            .max_by_key(|(_, path)| path.components().count());
+
+        if let Some((key, _)) = owner {
+            usage.entry(key.clone()).or_default().processes.push(process);
+        }
+    }
+
+    for review_usage in usage.values_mut() {
+        review_usage
+            .processes
+            .sort_by(|a, b| b.memory_bytes.cmp(&a.memory_bytes).then(a.pid.cmp(&b.pid)));
+    }
+
+    usage
+}
+
+/// Samples process CPU and memory usage
+///
+/// CPU usage is measured between two refreshes, so the first `usage` after
+/// creating a monitor reports 0% CPU. Long-running callers like the TUI
+/// should keep one monitor and refresh it periodically.
+pub struct ResourceMonitor {
+    system: System,
+}
+
+impl Default for ResourceMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResourceMonitor {
+    pub fn new() -> Self {
+        let mut monitor = ResourceMonitor {
+            system: System::new(),
+        };
+        monitor.refresh();
+        monitor
+    }
+
+    /// Re-read process information
+    pub fn refresh(&mut self) {
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing()
+                .with_cpu()
+                .with_memory()
+                .with_cwd(UpdateKind::OnlyIfNotSet),
+        );
+    }
+
+    /// Take a monitor through two refreshes so CPU usage is meaningful
+    pub async fn sample() -> Self {
+        let mut monitor = Self::new();
+        tokio::time::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL).await;
+        monitor.refresh();
+        monitor
+    }
+
+    /// Usage per review, keyed by PR number
+    pub fn usage(&self, reviews: &[ReviewState]) -> BTreeMap<ReviewKey, ResourceUsage> {
+        let processes = self
+            .system
+            .processes()
+            .iter()
+            // Linux lists threads as processes too; count each process once
+            .filter(|(_, process)| process.thread_kind().is_none())
+            .filter_map(|(pid, process)| {
+                let cwd = process.cwd()?;
+                Some((
+                    cwd.to_path_buf(),
+                    ProcessUsage {
+                        pid: pid.as_u32(),
+                        name: process.name().to_string_lossy().into_owned(),
+                        cpu_percent: process.cpu_usage(),
+                        memory_bytes: process.memory(),
+                    },
+                ))
+            });
+
+        attribute(processes, reviews)
+    }
+
+    /// Usage of a single review's processes
+    pub fn review_usage(&self, review: &ReviewState) -> ResourceUsage {
+        self.usage(std::slice::from_ref(review))
+            .remove(&review.key())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn review(pr_number: u32, worktree_path: PathBuf) -> ReviewState {
+        ReviewState {
+            branch: "feature".to_string(),
+            ..ReviewState::for_test(pr_number, worktree_path)
+        }
+    }
+
+    fn process(pid: u32, cpu_percent: f32, memory_mb: u64) -> ProcessUsage {
+        ProcessUsage {
+            pid,
+            name: format!("proc-{}", pid),
+            cpu_percent,
+            memory_bytes: memory_mb * 1024 * 1024,
+        }
+    }
+
+    #[test]
+    fn test_attribute_by_working_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path().canonicalize().unwrap();
+        let pr_1 = base.join("pr-1");
+        let pr_2 = base.join("pr-1").join("nested");
+        std::fs::create_dir_all(&pr_2).unwrap();
+
+        let reviews = vec![review(1, pr_1.clone()), review(2, pr_2.clone())];
+        let usage = attribute(
+            vec![
+                (pr_1.clone(), process(10, 5.0, 100)),
+                (pr_1.join("src"), process(11, 20.0, 300)),
+                (pr_2.join("web"), process(12, 1.0, 50)),
+                (base.clone(), process(13, 90.0, 4096)),
+            ],
+            &reviews,
+        );
+
+        let (pr_1, pr_2) = (&usage[&reviews[0].key()], &usage[&reviews[1].key()]);
+        assert_eq!(usage.len(), 2);
+        assert_eq!(pr_1.processes.len(), 2);
+        assert_eq!(pr_1.processes[0].pid, 11);
+        assert_eq!(pr_1.cpu_percent(), 25.0);
+        assert_eq!(pr_1.memory_bytes(), 400 * 1024 * 1024);
+        assert_eq!(pr_2.processes[0].pid, 12);
+    }
+
+    #[test]
+    fn test_summary_and_alerts() {
+        let usage = ResourceUsage {
+            processes: vec![process(1, 150.0, 1536), process(2, 10.0, 512)],
+        };
+        assert_eq!(usage.summary(), "160.0% CPU, 2.0 GB (2 processes)");
+
+        assert!(usage.alerts(&MonitorConfig::default()).is_empty());
+
+        let config = MonitorConfig {
+            cpu_alert_percent: Some(100.0),
+            memory_alert_mb: Some(4096),
+            ..MonitorConfig::default()
+        };
+        assert_eq!(usage.alerts(&config), vec!["CPU 160.0% exceeds 100%"]);
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0.0 MB");
+        assert_eq!(format_bytes(512 * 1024 * 1024), "512.0 MB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+
+    #[test]
+    fn test_monitor_sees_current_process() {
+        let temp_dir = TempDir::new().unwrap();
+        let cwd = std::env::current_dir().unwrap();
+        let monitor = ResourceMonitor::new();
+
+        // The test process runs inside the crate directory, but not in the temp dir
+        let here = monitor.review_usage(&review(1, cwd));
+        let elsewhere = monitor.review_usage(&review(2, temp_dir.path().to_path_buf()));
+
+        if cfg!(target_os = "linux") {
+            assert!(here.processes.iter().any(|p| p.pid == std::process::id()));
+        }
+        assert!(elsewhere.processes.is_empty());
+    }
+}
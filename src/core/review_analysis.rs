@@ -27,19 +27,29 @@
 //! ```
 
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::config::AgentsConfig;
+use crate::error::{ChabaError, Result};
 
 /// Severity level of a code finding.
 ///
 /// Severity levels are ordered from most to least severe:
 /// Critical > High > Medium > Low > Info
 ///
+/// `Ord` follows this ordering, so `Severity::Critical > Severity::Info`.
+///
 /// # JSON Serialization
 ///
 /// Serializes to lowercase strings:
 /// - `Critical` → `"critical"`
 /// - `High` → `"high"`
 /// - etc.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum Severity {
     /// Critical issues requiring immediate attention
@@ -54,6 +64,58 @@ pub enum Severity {
     Info,
 }
 
+impl Severity {
+    /// All severities, most severe first
+    pub const ALL: [Severity; 5] = [
+        Severity::Critical,
+        Severity::High,
+        Severity::Medium,
+        Severity::Low,
+        Severity::Info,
+    ];
+
+    /// Numeric rank, higher is more severe
+    pub fn rank(&self) -> u8 {
+        match self {
+            Severity::Critical => 4,
+            Severity::High => 3,
+            Severity::Medium => 2,
+            Severity::Low => 1,
+            Severity::Info => 0,
+        }
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "critical" => Ok(Severity::Critical),
+            "high" => Ok(Severity::High),
+            "medium" => Ok(Severity::Medium),
+            "low" => Ok(Severity::Low),
+            "info" => Ok(Severity::Info),
+            other => Err(format!(
+                "unknown severity '{}' (expected critical, high, medium, low, or info)",
+                other
+            )),
+        }
+    }
+}
+
+impl Ord for Severity {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl PartialOrd for Severity {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 /// Category of a code finding.
 ///
 /// Categories help organize findings by their nature and impact area.
@@ -64,7 +126,7 @@ pub enum Severity {
 /// - `BestPractice` → `"best-practice"`
 /// - `CodeQuality` → `"code-quality"`
 /// - etc.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "kebab-case")]
 pub enum Category {
     /// Security vulnerabilities and issues
@@ -85,6 +147,106 @@ pub enum Category {
     Other,
 }
 
+impl Category {
+    /// All categories, in display order
+    pub const ALL: [Category; 8] = [
+        Category::Security,
+        Category::Performance,
+        Category::BestPractice,
+        Category::CodeQuality,
+        Category::Architecture,
+        Category::Testing,
+        Category::Documentation,
+        Category::Other,
+    ];
+}
+
+impl FromStr for Category {
+    type Err = String;
+
+    /// Parse kebab-case (`best-practice`), snake_case, or run-together names
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+            "security" => Ok(Category::Security),
+            "performance" => Ok(Category::Performance),
+            "bestpractice" => Ok(Category::BestPractice),
+            "codequality" => Ok(Category::CodeQuality),
+            "architecture" => Ok(Category::Architecture),
+            "testing" => Ok(Category::Testing),
+            "documentation" => Ok(Category::Documentation),
+            "other" => Ok(Category::Other),
+            _ => Err(format!(
+                "unknown category '{}' (expected security, performance, best-practice, code-quality, architecture, testing, documentation, or other)",
+                s
+            )),
+        }
+    }
+}
+
+/// Where a finding stands in the team's triage.
+///
+/// Serializes to lowercase strings. Findings start out `Open`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TriageStatus {
+    /// Not looked at yet
+    #[default]
+    Open,
+    /// Seen and accepted, but not fixed yet
+    Acknowledged,
+    /// Not an issue, or won't be fixed
+    Dismissed,
+    /// Addressed in the PR
+    Fixed,
+}
+
+impl TriageStatus {
+    /// All statuses, in workflow order
+    pub const ALL: [TriageStatus; 4] = [
+        TriageStatus::Open,
+        TriageStatus::Acknowledged,
+        TriageStatus::Dismissed,
+        TriageStatus::Fixed,
+    ];
+
+    pub fn is_open(&self) -> bool {
+        *self == TriageStatus::Open
+    }
+
+    /// Whether the finding no longer needs attention
+    pub fn is_resolved(&self) -> bool {
+        matches!(self, TriageStatus::Dismissed | TriageStatus::Fixed)
+    }
+}
+
+impl std::fmt::Display for TriageStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TriageStatus::Open => write!(f, "open"),
+            TriageStatus::Acknowledged => write!(f, "acknowledged"),
+            TriageStatus::Dismissed => write!(f, "dismissed"),
+            TriageStatus::Fixed => write!(f, "fixed"),
+        }
+    }
+}
+
+impl FromStr for TriageStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "open" => Ok(TriageStatus::Open),
+            "acknowledged" | "ack" => Ok(TriageStatus::Acknowledged),
+            "dismissed" | "dismiss" => Ok(TriageStatus::Dismissed),
+            "fixed" | "fix" => Ok(TriageStatus::Fixed),
+            other => Err(format!(
+                "unknown triage status '{}' (expected open, acknowledged, dismissed, or fixed)",
+                other
+            )),
+        }
+    }
+}
+
 /// Individual finding from an AI agent.
 ///
 /// Represents a single issue, suggestion, or observation found during
@@ -108,6 +270,13 @@ pub enum Category {
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Finding {
+    /// Stable identifier derived from agent, file, line, and title
+    ///
+    /// Assigned by [`ReviewAnalysis::add_finding`]. Empty for findings
+    /// that were never added to an analysis.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+
     /// Severity level
     pub severity: Severity,
 
@@ -137,6 +306,24 @@ pub struct Finding {
     /// Omitted from JSON if not present.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub suggestion: Option<String>,
+
+    /// How sure the agent is that this is a real issue, from 0.0 to 1.0
+    ///
+    /// Omitted from JSON when the agent didn't say.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+
+    /// Unified diff implementing the suggestion (optional)
+    ///
+    /// Can be applied to the worktree with `chaba apply`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+
+    /// Triage status, set with `chaba triage`
+    ///
+    /// Omitted from JSON while the finding is open.
+    #[serde(default, skip_serializing_if = "TriageStatus::is_open")]
+    pub triage: TriageStatus,
 }
 
 /// Analysis result from a single AI agent.
@@ -168,9 +355,15 @@ pub struct Finding {
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReviewAnalysis {
-    /// Agent name (claude, codex, gemini)
+    /// Agent name (claude, codex, gemini, cursor-agent, copilot)
     pub agent: String,
 
+    /// Profile the agent ran under (`chaba review --profile`)
+    ///
+    /// Omitted from JSON for plain agent runs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+
     /// Analysis timestamp (ISO 8601)
     ///
     /// Automatically set to current time when created.
@@ -192,6 +385,95 @@ pub struct ReviewAnalysis {
     /// Omitted from JSON if not present.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raw_output: Option<String>,
+
+    /// Full stdout/stderr of the agent run, see `chaba agent-result --raw`
+    ///
+    /// Omitted from JSON when no transcript was saved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcript: Option<PathBuf>,
+
+    /// Follow-up questions asked about this analysis with `chaba ask`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub follow_ups: Vec<FollowUp>,
+
+    /// Tokens and cost the agent reported for this run
+    ///
+    /// Omitted from JSON when the agent doesn't report usage.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+}
+
+/// Tokens used and cost reported by an agent CLI
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Usage {
+    /// Input and output tokens, including cached prompt tokens
+    #[serde(default)]
+    pub tokens: u64,
+
+    /// Cost in US dollars, for agents that report it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+}
+
+impl Usage {
+    pub fn is_empty(&self) -> bool {
+        self.tokens == 0 && self.cost_usd.is_none()
+    }
+
+    /// Add another run's usage; the cost is known if either is
+    pub fn add(&mut self, other: &Usage) {
+        self.tokens += other.tokens;
+        self.cost_usd = match (self.cost_usd, other.cost_usd) {
+            (None, None) => None,
+            (cost, other) => Some(cost.unwrap_or(0.0) + other.unwrap_or(0.0)),
+        };
+    }
+
+    /// Combined usage of `analyses`
+    pub fn sum<'a>(analyses: impl IntoIterator<Item = &'a ReviewAnalysis>) -> Usage {
+        let mut total = Usage::default();
+        for usage in analyses.into_iter().filter_map(|analysis| analysis.usage.as_ref()) {
+            total.add(usage);
+        }
+        total
+    }
+
+    /// Cost for display, e.g. `$0.42`, or `-` when unknown
+    pub fn format_cost(&self) -> String {
+        match self.cost_usd {
+            Some(cost) => format!("${:.2}", cost),
+            None => "-".to_string(),
+        }
+    }
+
+    /// Token count for display, e.g. `12.3k tokens`
+    pub fn format_tokens(&self) -> String {
+        if self.tokens >= 1_000_000 {
+            format!("{:.1}M tokens", self.tokens as f64 / 1_000_000.0)
+        } else if self.tokens >= 1_000 {
+            format!("{:.1}k tokens", self.tokens as f64 / 1_000.0)
+        } else {
+            format!("{} tokens", self.tokens)
+        }
+    }
+}
+
+impl std::fmt::Display for Usage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.cost_usd {
+            Some(_) => write!(f, "{} ({})", self.format_cost(), self.format_tokens()),
+            None => write!(f, "{}", self.format_tokens()),
+        }
+    }
+}
+
+/// A question about a review and the agent's answer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowUp {
+    pub question: String,
+    pub answer: String,
+    /// When the question was asked (ISO 8601)
+    pub asked_at: String,
 }
 
 impl ReviewAnalysis {
@@ -199,18 +481,74 @@ impl ReviewAnalysis {
     pub fn new(agent: String) -> Self {
         ReviewAnalysis {
             agent,
+            profile: None,
             timestamp: chrono::Utc::now().to_rfc3339(),
             score: None,
             findings: Vec::new(),
             raw_output: None,
+            transcript: None,
+            follow_ups: Vec::new(),
+            usage: None,
         }
     }
 
-    /// Add a finding
-    pub fn add_finding(&mut self, finding: Finding) {
+    /// Add a finding, assigning its ID
+    pub fn add_finding(&mut self, mut finding: Finding) {
+        finding.id = finding.compute_id(&self.agent);
         self.findings.push(finding);
     }
 
+    /// Move findings in [`Category::Other`] to `category`
+    pub fn recategorize_other(&mut self, category: Category) {
+        for finding in &mut self.findings {
+            if finding.category == Category::Other {
+                finding.category = category;
+            }
+        }
+    }
+
+    /// Assign IDs to findings that don't have one (e.g. loaded from older state)
+    pub fn assign_ids(&mut self) {
+        for finding in &mut self.findings {
+            if finding.id.is_empty() {
+                finding.id = finding.compute_id(&self.agent);
+            }
+        }
+    }
+
+    /// Find a finding by ID
+    pub fn finding(&self, id: &str) -> Option<&Finding> {
+        self.findings.iter().find(|f| f.id == id)
+    }
+
+    /// Record a follow-up question and the agent's answer
+    pub fn add_follow_up(&mut self, question: String, answer: String) {
+        self.follow_ups.push(FollowUp {
+            question,
+            answer,
+            asked_at: chrono::Utc::now().to_rfc3339(),
+        });
+    }
+
+    /// Keep the triage status of findings reported again since `previous`
+    ///
+    /// Acknowledged and dismissed findings keep their status. A finding
+    /// marked fixed that is reported again is reopened.
+    pub fn carry_triage(&mut self, previous: &[ReviewAnalysis]) {
+        for finding in &mut self.findings {
+            let earlier = previous
+                .iter()
+                .filter(|analysis| analysis.agent == self.agent)
+                .find_map(|analysis| analysis.finding(&finding.id));
+            if let Some(earlier) = earlier {
+                finding.triage = match earlier.triage {
+                    TriageStatus::Fixed => TriageStatus::Open,
+                    status => status,
+                };
+            }
+        }
+    }
+
     /// Set overall score
     #[allow(dead_code)]
     pub fn set_score(&mut self, score: f32) {
@@ -232,17 +570,76 @@ impl ReviewAnalysis {
         self.findings.iter().filter(|f| &f.category == category).count()
     }
 
+    /// Findings ordered from most to least severe
+    ///
+    /// Findings with the same severity keep their original order.
+    pub fn sorted_findings(&self) -> Vec<&Finding> {
+        let mut findings: Vec<&Finding> = self.findings.iter().collect();
+        findings.sort_by_key(|f| std::cmp::Reverse(f.severity));
+        findings
+    }
+
+    /// Findings grouped by severity, most severe first, skipping empty groups
+    pub fn findings_by_severity(&self) -> Vec<(Severity, Vec<&Finding>)> {
+        Severity::ALL
+            .iter()
+            .map(|severity| {
+                let findings = self.findings.iter().filter(|f| &f.severity == severity).collect();
+                (*severity, findings)
+            })
+            .filter(|(_, findings): &(Severity, Vec<&Finding>)| !findings.is_empty())
+            .collect()
+    }
+
+    /// Findings grouped by category in [`Category::ALL`] order, skipping empty groups
+    pub fn findings_by_category(&self) -> Vec<(Category, Vec<&Finding>)> {
+        Category::ALL
+            .iter()
+            .map(|category| {
+                let findings = self.findings.iter().filter(|f| &f.category == category).collect();
+                (*category, findings)
+            })
+            .filter(|(_, findings): &(Category, Vec<&Finding>)| !findings.is_empty())
+            .collect()
+    }
+
     /// Get critical and high severity findings
     #[allow(dead_code)]
     pub fn critical_findings(&self) -> Vec<&Finding> {
         self.findings
             .iter()
-            .filter(|f| matches!(f.severity, Severity::Critical | Severity::High))
+            .filter(|f| f.severity >= Severity::High)
             .collect()
     }
 }
 
 impl Finding {
+    /// Length of a finding ID in hex characters
+    pub const ID_LEN: usize = 12;
+
+    /// Deterministic ID for this finding as reported by `agent`
+    ///
+    /// Hashes the agent, file, line, and title (trimmed, case-insensitive),
+    /// so the same issue gets the same ID across runs. Description and
+    /// severity are not included since agents reword and re-rank freely.
+    pub fn compute_id(&self, agent: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(agent.as_bytes());
+        hasher.update([0]);
+        hasher.update(self.file.as_deref().unwrap_or("").as_bytes());
+        hasher.update([0]);
+        hasher.update(self.line.map(|l| l.to_string()).unwrap_or_default().as_bytes());
+        hasher.update([0]);
+        hasher.update(self.title.trim().to_lowercase().as_bytes());
+
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>()[..Self::ID_LEN]
+            .to_string()
+    }
+
     /// Create a new finding
     pub fn new(
         severity: Severity,
@@ -251,6 +648,7 @@ impl Finding {
         description: String,
     ) -> Self {
         Finding {
+            id: String::new(),
             severity,
             category,
             file: None,
@@ -258,6 +656,9 @@ impl Finding {
             title,
             description,
             suggestion: None,
+            confidence: None,
+            diff: None,
+            triage: TriageStatus::Open,
         }
     }
 
@@ -281,12 +682,364 @@ impl Finding {
         self.suggestion = Some(suggestion);
         self
     }
+
+    /// Set the diff implementing the suggestion
+    pub fn with_diff(mut self, diff: String) -> Self {
+        self.diff = Some(diff);
+        self
+    }
+
+    /// Set the agent's confidence, clamped to 0.0–1.0
+    pub fn with_confidence(mut self, confidence: f32) -> Self {
+        self.confidence = Some(confidence.clamp(0.0, 1.0));
+        self
+    }
+}
+
+/// A finding reported by one or more agents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregatedFinding {
+    /// The finding, at the highest severity any agent assigned it
+    #[serde(flatten)]
+    pub finding: Finding,
+
+    /// Agents that reported this finding
+    pub agents: Vec<String>,
+}
+
+/// Findings from several agents merged into one report.
+///
+/// Findings with the same file, line, and title (ignoring case) are treated
+/// as duplicates and merged, keeping the highest severity.
+///
+/// # Examples
+///
+/// ```rust
+/// use chaba::core::review_analysis::{AggregatedReport, ReviewAnalysis, Finding, Severity, Category};
+///
+/// let mut claude = ReviewAnalysis::new("claude".to_string());
+/// claude.add_finding(Finding::new(
+///     Severity::High,
+///     Category::Security,
+///     "SQL injection".to_string(),
+///     "User input is not sanitized".to_string(),
+/// ));
+///
+/// let mut codex = ReviewAnalysis::new("codex".to_string());
+/// codex.add_finding(Finding::new(
+///     Severity::Critical,
+///     Category::Security,
+///     "SQL Injection".to_string(),
+///     "Query is built with format!".to_string(),
+/// ));
+///
+/// let report = AggregatedReport::from_analyses(&[claude, codex]);
+/// assert_eq!(report.total(), 1);
+/// assert_eq!(report.count_by_severity(&Severity::Critical), 1);
+/// assert_eq!(report.findings[0].agents, vec!["claude", "codex"]);
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AggregatedReport {
+    /// Agents whose analyses were merged
+    pub agents: Vec<String>,
+
+    /// Deduplicated findings, most severe first
+    pub findings: Vec<AggregatedFinding>,
+
+    /// Average of the agents' scores, if any agent reported one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
+}
+
+impl AggregatedReport {
+    /// Merge analyses from multiple agents
+    pub fn from_analyses(analyses: &[ReviewAnalysis]) -> Self {
+        let mut findings: Vec<AggregatedFinding> = Vec::new();
+
+        for analysis in analyses {
+            for finding in &analysis.findings {
+                let existing = findings
+                    .iter_mut()
+                    .find(|f| Self::same_finding(&f.finding, finding));
+
+                match existing {
+                    Some(existing) => {
+                        if finding.severity > existing.finding.severity {
+                            existing.finding = finding.clone();
+                        }
+                        if !existing.agents.contains(&analysis.agent) {
+                            existing.agents.push(analysis.agent.clone());
+                        }
+                    }
+                    None => findings.push(AggregatedFinding {
+                        finding: finding.clone(),
+                        agents: vec![analysis.agent.clone()],
+                    }),
+                }
+            }
+        }
+
+        findings.sort_by_key(|f| std::cmp::Reverse(f.finding.severity));
+
+        let scores: Vec<f32> = analyses.iter().filter_map(|a| a.score).collect();
+        let score = (!scores.is_empty()).then(|| scores.iter().sum::<f32>() / scores.len() as f32);
+
+        AggregatedReport {
+            agents: analyses.iter().map(|a| a.agent.clone()).collect(),
+            findings,
+            score,
+        }
+    }
+
+    fn same_finding(a: &Finding, b: &Finding) -> bool {
+        a.file == b.file
+            && a.line == b.line
+            && a.title.trim().eq_ignore_ascii_case(b.title.trim())
+    }
+
+    /// Number of unique findings
+    pub fn total(&self) -> usize {
+        self.findings.len()
+    }
+
+    /// Count unique findings by severity
+    pub fn count_by_severity(&self, severity: &Severity) -> usize {
+        self.findings.iter().filter(|f| &f.finding.severity == severity).count()
+    }
+
+    /// Count unique findings by category
+    pub fn count_by_category(&self, category: &Category) -> usize {
+        self.findings.iter().filter(|f| &f.finding.category == category).count()
+    }
+
+    /// Non-zero severity counts, most severe first
+    pub fn severity_counts(&self) -> Vec<(Severity, usize)> {
+        Severity::ALL
+            .iter()
+            .map(|s| (*s, self.count_by_severity(s)))
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+
+    /// Non-zero category counts in [`Category::ALL`] order
+    pub fn category_counts(&self) -> Vec<(Category, usize)> {
+        Category::ALL
+            .iter()
+            .map(|c| (*c, self.count_by_category(c)))
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+}
+
+/// Set the triage status of the finding with `id`
+///
+/// Returns `false` if no analysis has a finding with that ID.
+pub fn set_triage(analyses: &mut [ReviewAnalysis], id: &str, status: TriageStatus) -> bool {
+    let mut found = false;
+    for finding in analyses.iter_mut().flat_map(|analysis| analysis.findings.iter_mut()) {
+        if finding.id == id {
+            finding.triage = status;
+            found = true;
+        }
+    }
+    found
+}
+
+/// Agent prompt context describing the stored findings, for follow-up questions
+///
+/// Findings are numbered across all agents in stored order, so a question can
+/// refer to a finding by number or by ID. Earlier follow-ups asked of `agent`
+/// are included so the conversation can continue.
+pub fn follow_up_context(analyses: &[ReviewAnalysis], agent: &str) -> String {
+    let mut context = String::from("Findings from the earlier review of this PR:\n");
+    let findings = analyses
+        .iter()
+        .flat_map(|analysis| analysis.findings.iter().map(move |finding| (&analysis.agent, finding)));
+    for (number, (reported_by, finding)) in findings.enumerate() {
+        context.push_str(&format!(
+            "{}. [{}] {:?}/{:?}: {}",
+            number + 1,
+            finding.id,
+            finding.severity,
+            finding.category,
+            finding.title
+        ));
+        if let Some(file) = &finding.file {
+            match finding.line {
+                Some(line) => context.push_str(&format!(" ({}:{})", file, line)),
+                None => context.push_str(&format!(" ({})", file)),
+            }
+        }
+        context.push_str(&format!(" — reported by {}\n", reported_by));
+        if !finding.description.is_empty() {
+            context.push_str(&format!("   {}\n", finding.description));
+        }
+        if let Some(suggestion) = &finding.suggestion {
+            context.push_str(&format!("   Suggestion: {}\n", suggestion));
+        }
+    }
+
+    let earlier = analyses
+        .iter()
+        .filter(|analysis| analysis.agent == agent)
+        .flat_map(|analysis| &analysis.follow_ups);
+    for follow_up in earlier {
+        context.push_str(&format!("\nEarlier question: {}\nYour answer: {}\n", follow_up.question, follow_up.answer));
+    }
+
+    context
+}
+
+/// Team policy for the severity and category of agent findings
+///
+/// Built from `agents.category_mapping` and `agents.severity_overrides`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FindingPolicy {
+    categories: HashMap<Category, Category>,
+    severities: HashMap<Category, Severity>,
+}
+
+impl FindingPolicy {
+    /// Read the policy from the agents config
+    pub fn from_config(config: &AgentsConfig) -> Result<Self> {
+        let invalid = |setting: &str, error: String| ChabaError::ConfigError(format!("agents.{}: {}", setting, error));
+
+        let mut policy = FindingPolicy::default();
+        for (from, to) in &config.category_mapping {
+            let from = from.parse().map_err(|e| invalid("category_mapping", e))?;
+            let to = to.parse().map_err(|e| invalid("category_mapping", e))?;
+            policy.categories.insert(from, to);
+        }
+        for (category, severity) in &config.severity_overrides {
+            let category = category.parse().map_err(|e| invalid("severity_overrides", e))?;
+            let severity = severity.parse().map_err(|e| invalid("severity_overrides", e))?;
+            policy.severities.insert(category, severity);
+        }
+        Ok(policy)
+    }
+
+    /// Remap the categories, then override the severities, of `analysis`'s findings
+    pub fn apply(&self, analysis: &mut ReviewAnalysis) {
+        for finding in &mut analysis.findings {
+            if let Some(category) = self.categories.get(&finding.category) {
+                finding.category = *category;
+            }
+            if let Some(severity) = self.severities.get(&finding.category) {
+                finding.severity = *severity;
+            }
+        }
+    }
+}
+
+/// Parse a finding confidence for `--min-confidence`
+pub fn parse_confidence(s: &str) -> std::result::Result<f32, String> {
+    let confidence: f32 = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid confidence '{}': expected a number between 0 and 1", s))?;
+
+    if (0.0..=1.0).contains(&confidence) {
+        Ok(confidence)
+    } else {
+        Err(format!("Invalid confidence '{}': expected a number between 0 and 1", s))
+    }
+}
+
+/// Criteria for narrowing down findings, e.g. in `chaba agent-result`.
+///
+/// Empty lists match everything.
+#[derive(Debug, Clone, Default)]
+pub struct FindingFilter {
+    /// Hide findings less severe than this
+    pub min_severity: Option<Severity>,
+    /// Hide findings the agent is less confident about than this
+    ///
+    /// Findings without a confidence always pass.
+    pub min_confidence: Option<f32>,
+    /// Only show findings in these categories
+    pub categories: Vec<Category>,
+    /// Only show findings from these agents
+    pub agents: Vec<String>,
+    /// Only show findings whose file matches one of these globs
+    pub files: Vec<glob::Pattern>,
+    /// Only show findings with these triage statuses
+    pub statuses: Vec<TriageStatus>,
+}
+
+impl FindingFilter {
+    /// Whether any criteria are set
+    pub fn is_empty(&self) -> bool {
+        self.min_severity.is_none()
+            && self.min_confidence.is_none()
+            && self.categories.is_empty()
+            && self.agents.is_empty()
+            && self.files.is_empty()
+            && self.statuses.is_empty()
+    }
+
+    /// Whether `finding`, reported by `agent`, passes the filter
+    pub fn matches(&self, agent: &str, finding: &Finding) -> bool {
+        let severity_match = self.min_severity.is_none_or(|min| finding.severity >= min);
+        let confidence_match = self
+            .min_confidence
+            .is_none_or(|min| finding.confidence.is_none_or(|confidence| confidence >= min));
+        let category_match = self.categories.is_empty() || self.categories.contains(&finding.category);
+        let agent_match = self.agents.is_empty() || self.agents.iter().any(|a| a.eq_ignore_ascii_case(agent));
+        let file_match = self.files.is_empty()
+            || finding.file.as_deref().is_some_and(|file| {
+                let file = file.trim_start_matches("./");
+                self.files.iter().any(|pattern| pattern.matches(file))
+            });
+
+        let status_match = self.statuses.is_empty() || self.statuses.contains(&finding.triage);
+
+        severity_match && confidence_match && category_match && agent_match && file_match && status_match
+    }
+
+    /// Copies of `analyses` keeping only matching findings
+    ///
+    /// Analyses from agents excluded by the filter are dropped entirely.
+    pub fn apply(&self, analyses: &[ReviewAnalysis]) -> Vec<ReviewAnalysis> {
+        analyses
+            .iter()
+            .filter(|analysis| self.agents.is_empty() || self.agents.iter().any(|a| a.eq_ignore_ascii_case(&analysis.agent)))
+            .map(|analysis| {
+                let mut analysis = analysis.clone();
+                let agent = analysis.agent.clone();
+                analysis.findings.retain(|finding| self.matches(&agent, finding));
+                analysis
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_follow_up_context() {
+        let mut claude = ReviewAnalysis::new("claude".to_string());
+        claude.add_finding(
+            Finding::new(Severity::High, Category::Security, "Token is logged".to_string(), "Logs leak it".to_string())
+                .with_file("src/auth.rs".to_string())
+                .with_line(10),
+        );
+        claude.add_follow_up("Is it logged in production?".to_string(), "Yes, at info level.".to_string());
+        let mut codex = ReviewAnalysis::new("codex".to_string());
+        codex.add_finding(Finding::new(Severity::Low, Category::Performance, "Extra clone".to_string(), String::new()));
+
+        let context = follow_up_context(&[claude.clone(), codex.clone()], "claude");
+
+        assert!(context.contains(&format!(
+            "1. [{}] High/Security: Token is logged (src/auth.rs:10) — reported by claude\n   Logs leak it\n",
+            claude.findings[0].id
+        )));
+        assert!(context.contains(&format!("2. [{}] Low/Performance: Extra clone — reported by codex\n", codex.findings[0].id)));
+        assert!(context.contains("Earlier question: Is it logged in production?\nYour answer: Yes, at info level."));
+        assert!(!follow_up_context(&[claude, codex], "codex").contains("Earlier question"));
+    }
+
     #[test]
     fn test_finding_creation() {
         let finding = Finding::new(
@@ -516,4 +1269,293 @@ mod tests {
         assert!(json.contains("\"score\":4.0"));
         assert!(json.contains("\"findings\""));
     }
+
+    fn finding(severity: Severity, category: Category, title: &str) -> Finding {
+        Finding::new(severity, category, title.to_string(), String::new())
+    }
+
+    #[test]
+    fn test_severity_ordering() {
+        assert!(Severity::Critical > Severity::High);
+        assert!(Severity::High > Severity::Medium);
+        assert!(Severity::Medium > Severity::Low);
+        assert!(Severity::Low > Severity::Info);
+
+        let mut severities = vec![Severity::Low, Severity::Critical, Severity::Info, Severity::High];
+        severities.sort();
+        assert_eq!(
+            severities,
+            vec![Severity::Info, Severity::Low, Severity::High, Severity::Critical]
+        );
+    }
+
+    #[test]
+    fn test_sorted_findings() {
+        let mut analysis = ReviewAnalysis::new("claude".to_string());
+        analysis.add_finding(finding(Severity::Low, Category::CodeQuality, "a"));
+        analysis.add_finding(finding(Severity::Critical, Category::Security, "b"));
+        analysis.add_finding(finding(Severity::Low, Category::Testing, "c"));
+        analysis.add_finding(finding(Severity::High, Category::Performance, "d"));
+
+        let titles: Vec<&str> = analysis.sorted_findings().iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(titles, vec!["b", "d", "a", "c"]);
+    }
+
+    #[test]
+    fn test_findings_grouping() {
+        let mut analysis = ReviewAnalysis::new("claude".to_string());
+        analysis.add_finding(finding(Severity::Low, Category::Security, "a"));
+        analysis.add_finding(finding(Severity::Critical, Category::Security, "b"));
+        analysis.add_finding(finding(Severity::Low, Category::Testing, "c"));
+
+        let by_severity = analysis.findings_by_severity();
+        assert_eq!(by_severity.len(), 2);
+        assert_eq!(by_severity[0].0, Severity::Critical);
+        assert_eq!(by_severity[1].0, Severity::Low);
+        assert_eq!(by_severity[1].1.len(), 2);
+
+        let by_category = analysis.findings_by_category();
+        assert_eq!(by_category.len(), 2);
+        assert_eq!(by_category[0].0, Category::Security);
+        assert_eq!(by_category[0].1.len(), 2);
+        assert_eq!(by_category[1].0, Category::Testing);
+    }
+
+    #[test]
+    fn test_aggregated_report_dedupes_across_agents() {
+        let mut claude = ReviewAnalysis::new("claude".to_string());
+        claude.add_finding(finding(Severity::Medium, Category::Security, "Missing auth check").with_file("src/api.rs".to_string()));
+        claude.add_finding(finding(Severity::Low, Category::Documentation, "Missing docs"));
+        claude.set_score(4.0);
+
+        let mut codex = ReviewAnalysis::new("codex".to_string());
+        codex.add_finding(finding(Severity::High, Category::Security, "missing auth check ").with_file("src/api.rs".to_string()));
+        codex.add_finding(finding(Severity::Medium, Category::Security, "Missing auth check").with_file("src/other.rs".to_string()));
+        codex.set_score(3.0);
+
+        let gemini = ReviewAnalysis::new("gemini".to_string());
+
+        let report = AggregatedReport::from_analyses(&[claude, codex, gemini]);
+
+        assert_eq!(report.agents, vec!["claude", "codex", "gemini"]);
+        assert_eq!(report.total(), 3);
+        assert_eq!(report.findings[0].finding.severity, Severity::High);
+        assert_eq!(report.findings[0].agents, vec!["claude", "codex"]);
+        assert_eq!(report.severity_counts(), vec![(Severity::High, 1), (Severity::Medium, 1), (Severity::Low, 1)]);
+        assert_eq!(report.category_counts(), vec![(Category::Security, 2), (Category::Documentation, 1)]);
+        assert_eq!(report.score, Some(3.5));
+    }
+
+    #[test]
+    fn test_aggregated_report_empty() {
+        let report = AggregatedReport::from_analyses(&[]);
+        assert_eq!(report.total(), 0);
+        assert!(report.score.is_none());
+        assert!(report.severity_counts().is_empty());
+    }
+
+    #[test]
+    fn test_finding_ids_are_stable() {
+        let mut first = ReviewAnalysis::new("claude".to_string());
+        first.add_finding(finding(Severity::High, Category::Security, "SQL injection").with_file("src/db.rs".to_string()).with_line(10));
+
+        let mut second = ReviewAnalysis::new("claude".to_string());
+        second.add_finding(
+            Finding::new(Severity::Medium, Category::Security, " sql Injection ".to_string(), "reworded".to_string())
+                .with_file("src/db.rs".to_string())
+                .with_line(10),
+        );
+
+        let id = &first.findings[0].id;
+        assert_eq!(id.len(), Finding::ID_LEN);
+        assert_eq!(id, &second.findings[0].id);
+        assert!(first.finding(id).is_some());
+    }
+
+    #[test]
+    fn test_finding_ids_differ_by_agent_and_location() {
+        let base = finding(Severity::High, Category::Security, "SQL injection").with_file("src/db.rs".to_string());
+
+        let claude = base.compute_id("claude");
+        assert_ne!(claude, base.compute_id("codex"));
+        assert_ne!(claude, base.clone().with_line(3).compute_id("claude"));
+    }
+
+    #[test]
+    fn test_assign_ids_fills_missing() {
+        let mut analysis = ReviewAnalysis::new("gemini".to_string());
+        analysis.findings.push(finding(Severity::Low, Category::Other, "Legacy"));
+        assert!(analysis.findings[0].id.is_empty());
+
+        analysis.assign_ids();
+        assert_eq!(analysis.findings[0].id, analysis.findings[0].compute_id("gemini"));
+    }
+
+    #[test]
+    fn test_parse_severity_and_category() {
+        assert_eq!("HIGH".parse::<Severity>(), Ok(Severity::High));
+        assert!("urgent".parse::<Severity>().is_err());
+        assert_eq!("best-practice".parse::<Category>(), Ok(Category::BestPractice));
+        assert_eq!("code_quality".parse::<Category>(), Ok(Category::CodeQuality));
+        assert!("style".parse::<Category>().is_err());
+    }
+
+    #[test]
+    fn test_finding_filter() {
+        let mut claude = ReviewAnalysis::new("claude".to_string());
+        claude.add_finding(finding(Severity::Critical, Category::Security, "Injection").with_file("./src/db.rs".to_string()));
+        claude.add_finding(finding(Severity::Low, Category::Security, "Weak hash").with_file("src/auth.rs".to_string()));
+        claude.add_finding(finding(Severity::High, Category::Performance, "N+1 query").with_file("web/app.ts".to_string()));
+        let mut codex = ReviewAnalysis::new("codex".to_string());
+        codex.add_finding(finding(Severity::High, Category::Security, "No file"));
+
+        let titles = |filter: &FindingFilter| -> Vec<String> {
+            filter
+                .apply(&[claude.clone(), codex.clone()])
+                .iter()
+                .flat_map(|analysis| analysis.findings.iter().map(|f| f.title.clone()))
+                .collect()
+        };
+
+        assert!(FindingFilter::default().is_empty());
+        assert_eq!(titles(&FindingFilter::default()).len(), 4);
+
+        let filter = FindingFilter {
+            min_severity: Some(Severity::High),
+            categories: vec![Category::Security],
+            ..FindingFilter::default()
+        };
+        assert_eq!(titles(&filter), vec!["Injection", "No file"]);
+
+        let filter = FindingFilter {
+            agents: vec!["Claude".to_string()],
+            files: vec![glob::Pattern::new("src/**").unwrap()],
+            ..FindingFilter::default()
+        };
+        assert_eq!(titles(&filter), vec!["Injection", "Weak hash"]);
+        assert_eq!(filter.apply(&[codex.clone()]).len(), 0);
+    }
+
+    #[test]
+    fn test_finding_policy() {
+        let mut config = AgentsConfig::default();
+        config.category_mapping.insert("architecture".to_string(), "code-quality".to_string());
+        config.severity_overrides.insert("best_practice".to_string(), "info".to_string());
+        config.severity_overrides.insert("code-quality".to_string(), "low".to_string());
+        let policy = FindingPolicy::from_config(&config).unwrap();
+
+        let mut analysis = ReviewAnalysis::new("claude".to_string());
+        analysis.add_finding(finding(Severity::Medium, Category::BestPractice, "Magic number"));
+        analysis.add_finding(finding(Severity::High, Category::Architecture, "God object"));
+        analysis.add_finding(finding(Severity::High, Category::Security, "Injection"));
+        policy.apply(&mut analysis);
+
+        let findings: Vec<(Severity, Category)> = analysis.findings.iter().map(|f| (f.severity, f.category)).collect();
+        assert_eq!(
+            findings,
+            vec![
+                (Severity::Info, Category::BestPractice),
+                (Severity::Low, Category::CodeQuality),
+                (Severity::High, Category::Security),
+            ]
+        );
+
+        config.severity_overrides.insert("style".to_string(), "info".to_string());
+        assert!(FindingPolicy::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_finding_filter_by_confidence() {
+        let mut claude = ReviewAnalysis::new("claude".to_string());
+        claude.add_finding(finding(Severity::High, Category::Security, "Likely").with_confidence(0.9));
+        claude.add_finding(finding(Severity::High, Category::Security, "Speculative").with_confidence(0.3));
+        claude.add_finding(finding(Severity::High, Category::Security, "Unrated"));
+
+        let filter = FindingFilter {
+            min_confidence: Some(0.5),
+            ..FindingFilter::default()
+        };
+        let filtered = filter.apply(&[claude]);
+        let titles: Vec<&str> = filtered[0].findings.iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(titles, vec!["Likely", "Unrated"]);
+    }
+
+    #[test]
+    fn test_parse_triage_status() {
+        assert_eq!("ack".parse::<TriageStatus>(), Ok(TriageStatus::Acknowledged));
+        assert_eq!("Dismissed".parse::<TriageStatus>(), Ok(TriageStatus::Dismissed));
+        assert!("done".parse::<TriageStatus>().is_err());
+    }
+
+    #[test]
+    fn test_triage_serialization() {
+        let mut finding = finding(Severity::High, Category::Security, "Injection");
+        assert!(!serde_json::to_string(&finding).unwrap().contains("triage"));
+
+        finding.triage = TriageStatus::Dismissed;
+        let json = serde_json::to_string(&finding).unwrap();
+        assert!(json.contains(r#""triage":"dismissed""#));
+        assert_eq!(serde_json::from_str::<Finding>(&json).unwrap().triage, TriageStatus::Dismissed);
+    }
+
+    #[test]
+    fn test_carry_triage() {
+        let mut previous = ReviewAnalysis::new("claude".to_string());
+        previous.add_finding(finding(Severity::High, Category::Security, "Injection"));
+        previous.add_finding(finding(Severity::Low, Category::Other, "Naming"));
+        previous.findings[0].triage = TriageStatus::Dismissed;
+        previous.findings[1].triage = TriageStatus::Fixed;
+
+        let mut current = ReviewAnalysis::new("claude".to_string());
+        current.add_finding(finding(Severity::Critical, Category::Security, "injection"));
+        current.add_finding(finding(Severity::Low, Category::Other, "Naming"));
+        current.add_finding(finding(Severity::Low, Category::Other, "New"));
+        current.carry_triage(&[previous]);
+
+        let statuses: Vec<TriageStatus> = current.findings.iter().map(|f| f.triage).collect();
+        assert_eq!(statuses, vec![TriageStatus::Dismissed, TriageStatus::Open, TriageStatus::Open]);
+    }
+
+    #[test]
+    fn test_finding_filter_by_status() {
+        let mut analysis = ReviewAnalysis::new("claude".to_string());
+        analysis.add_finding(finding(Severity::High, Category::Security, "Injection"));
+        analysis.add_finding(finding(Severity::Low, Category::Other, "Naming"));
+        analysis.findings[1].triage = TriageStatus::Fixed;
+
+        let filter = FindingFilter {
+            statuses: vec![TriageStatus::Open, TriageStatus::Acknowledged],
+            ..FindingFilter::default()
+        };
+        let filtered = filter.apply(&[analysis]);
+        assert_eq!(filtered[0].findings.len(), 1);
+        assert_eq!(filtered[0].findings[0].title, "Injection");
+    }
+
+    #[test]
+    fn test_set_triage() {
+        let mut analysis = ReviewAnalysis::new("claude".to_string());
+        analysis.add_finding(finding(Severity::High, Category::Security, "Injection"));
+        let id = analysis.findings[0].id.clone();
+        let mut analyses = vec![analysis];
+
+        assert!(set_triage(&mut analyses, &id, TriageStatus::Acknowledged));
+        assert_eq!(analyses[0].findings[0].triage, TriageStatus::Acknowledged);
+        assert!(!set_triage(&mut analyses, "missing", TriageStatus::Fixed));
+    }
+
+    #[test]
+    fn test_usage_sum() {
+        let mut claude = ReviewAnalysis::new("claude".to_string());
+        claude.usage = Some(Usage { tokens: 12_000, cost_usd: Some(0.25) });
+        let mut codex = ReviewAnalysis::new("codex".to_string());
+        codex.usage = Some(Usage { tokens: 800, cost_usd: None });
+        let gemini = ReviewAnalysis::new("gemini".to_string());
+
+        let total = Usage::sum([&claude, &codex, &gemini]);
+        assert_eq!(total, Usage { tokens: 12_800, cost_usd: Some(0.25) });
+        assert_eq!(total.to_string(), "$0.25 (12.8k tokens)");
+        assert_eq!(Usage::sum([&codex]).to_string(), "800 tokens");
+        assert!(Usage::sum([&gemini]).is_empty());
+    }
 }
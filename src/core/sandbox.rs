@@ -1,11 +1,12 @@
 use std::path::Path;
 
 use crate::config::SandboxConfig;
-use crate::core::{env, installer, port::PortManager, project, state::State};
+use crate::core::{env, installer, logs::ReviewLogs, port::{self, PortManager}, project, seed, state::State, test_suite};
 use crate::error::Result;
 
 pub struct SandboxManager {
     config: SandboxConfig,
+    preferred_port: Option<u16>,
 }
 
 #[derive(Debug, Default)]
@@ -14,19 +15,34 @@ pub struct SandboxInfo {
     pub deps_installed: bool,
     pub env_copied: bool,
     pub port: Option<u16>,
+    pub tests: Option<test_suite::TestRunResult>,
+    pub seed: Option<seed::SeedResult>,
 }
 
 impl SandboxManager {
     pub fn new(config: SandboxConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            preferred_port: None,
+        }
+    }
+
+    /// Keep this port if it is still available, e.g. when a review is rebuilt
+    pub fn prefer_port(mut self, port: Option<u16>) -> Self {
+        self.preferred_port = port;
+        self
     }
 
     /// Set up sandbox environment for a review worktree
+    ///
+    /// Installer and test output is written to the review's logs when `logs` is given.
     pub async fn setup(
         &self,
+        pr_number: u32,
         worktree_path: &Path,
         main_worktree: &Path,
         state: &State,
+        logs: Option<&ReviewLogs>,
     ) -> Result<SandboxInfo> {
         let mut info = SandboxInfo::default();
 
@@ -40,7 +56,15 @@ impl SandboxManager {
         // 2. Install dependencies
         if self.config.auto_install_deps {
             tracing::info!("Installing dependencies...");
-            match installer::install_dependencies(worktree_path, &project_type).await {
+            match installer::install_dependencies(
+                worktree_path,
+                main_worktree,
+                &project_type,
+                &self.config.node.cache,
+                logs,
+            )
+            .await
+            {
                 Ok(_) => {
                     info.deps_installed = true;
                     tracing::info!("Dependencies installed successfully");
@@ -59,6 +83,7 @@ impl SandboxManager {
                 main_worktree,
                 worktree_path,
                 &self.config.additional_env_files,
+                &self.config.env,
             )
             .await
             {
@@ -73,6 +98,24 @@ impl SandboxManager {
             }
         }
 
+        // Copy additional configured files
+        if !self.config.copy_files.is_empty() {
+            match env::copy_matching_files(
+                main_worktree,
+                worktree_path,
+                &self.config.copy_files,
+                &self.config.env,
+            )
+            .await
+            {
+                Ok(count) => tracing::info!("Copied {} configured file(s)", count),
+                Err(e) => {
+                    tracing::warn!("Failed to copy configured files: {}", e);
+                    // Continue even if copy fails
+                }
+            }
+        }
+
         // 4. Assign port
         if self.config.port.enabled {
             let port_manager = PortManager::new(
@@ -80,7 +123,13 @@ impl SandboxManager {
                 self.config.port.range_end,
             );
 
-            match port_manager.assign_port(state) {
+            let base = port::preferred_base(self.config.port.preferred, worktree_path, &project_type);
+            let assigned = match (self.preferred_port, base) {
+                (Some(preferred), _) => port_manager.assign_port_preferring(state, preferred),
+                (None, Some(base)) => port_manager.assign_port_near(state, base),
+                (None, None) => port_manager.assign_port(state),
+            };
+            match assigned {
                 Ok(port) => {
                     info.port = Some(port);
                     tracing::info!("Assigned port: {}", port);
@@ -92,6 +141,69 @@ impl SandboxManager {
             }
         }
 
+        // 5. Apply env overrides (after port assignment so {port} resolves)
+        let vars = env::TemplateVars {
+            pr: pr_number,
+            port: info.port,
+        };
+        if let Err(e) = env::apply_env_overrides(
+            main_worktree,
+            worktree_path,
+            &self.config.additional_env_files,
+            &self.config.env,
+            &vars,
+        )
+        .await
+        {
+            tracing::warn!("Failed to apply env overrides: {}", e);
+            // Continue even if overrides fail
+        }
+
+        // 6. Seed data (after env files so seed scripts find the database config)
+        if seed::is_configured(&self.config) {
+            tracing::info!("Seeding review environment...");
+            let vars = seed::SeedVars {
+                pr: pr_number,
+                port: info.port,
+            };
+            let result = seed::seed(&self.config, main_worktree, worktree_path, vars, logs).await;
+            match &result.error {
+                None => tracing::info!("Seeded {}", result.summary()),
+                // Continue even if seeding fails
+                Some(e) => tracing::warn!("Failed to seed review environment: {}", e),
+            }
+            info.seed = Some(result);
+        }
+
+        // 7. Run test suite
+        if self.config.run_tests {
+            let command = self
+                .config
+                .test_command
+                .clone()
+                .or_else(|| test_suite::default_test_command(&project_type));
+
+            match command {
+                Some(command) => {
+                    match test_suite::run_tests(worktree_path, &command, self.config.test_timeout, logs).await {
+                        Ok(result) => {
+                            if result.passed {
+                                tracing::info!("Tests passed");
+                            } else {
+                                tracing::warn!("Tests failed: {}", command);
+                            }
+                            info.tests = Some(result);
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to run tests: {}", e);
+                            // Continue even if tests can't be run
+                        }
+                    }
+                }
+                None => tracing::info!("No test command for this project type, skipping tests"),
+            }
+        }
+
         tracing::info!("Sandbox environment setup complete");
         Ok(info)
     }
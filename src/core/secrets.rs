@@ -0,0 +1,258 @@
+//! Agent credentials and environment isolation
+//!
+//! Resolves per-agent environment variables from literal values, a secrets
+//! file, or the OS keychain, so API keys don't have to live in the user's
+//! global shell environment.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::{AgentsConfig, EnvValue};
+use crate::core::command::CommandEnv;
+use crate::error::{ChabaError, Result};
+
+/// Service name used for OS keychain entries
+pub const KEYCHAIN_SERVICE: &str = "chaba";
+
+/// Value of a literal, secrets file, or keychain reference
+///
+/// `owner` names what the value is for in errors, e.g. `agent 'claude'`.
+/// Without `secrets_file`, secrets are read from [`default_secrets_file`].
+pub fn resolve_value(value: &EnvValue, secrets_file: Option<&Path>, owner: &str) -> Result<String> {
+    match value {
+        EnvValue::Literal(value) => Ok(value.clone()),
+        EnvValue::Secret { secret } => {
+            let path = match secrets_file {
+                Some(path) => path.to_path_buf(),
+                None => default_secrets_file()?,
+            };
+            load_secrets_file(&path)?.remove(secret).ok_or_else(|| {
+                ChabaError::ConfigError(format!("Secret '{}' for {} not found in secrets file", secret, owner))
+            })
+        }
+        EnvValue::Keychain { keychain } => keychain_get(keychain),
+    }
+}
+
+/// Default secrets file location (`~/.chaba/secrets.yaml`)
+pub fn default_secrets_file() -> Result<PathBuf> {
+    let home = dirs::home_dir()
+        .ok_or_else(|| ChabaError::ConfigError("Cannot find home directory".to_string()))?;
+
+    Ok(home.join(".chaba").join("secrets.yaml"))
+}
+
+/// Load a secrets file as a map of secret names to values
+///
+/// A missing file is treated as empty.
+pub fn load_secrets_file(path: &Path) -> Result<BTreeMap<String, String>> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    warn_if_world_readable(path);
+
+    let content = std::fs::read_to_string(path)?;
+    let secrets: BTreeMap<String, String> = serde_yaml::from_str(&content)?;
+    Ok(secrets)
+}
+
+#[cfg(unix)]
+fn warn_if_world_readable(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Ok(metadata) = std::fs::metadata(path) {
+        if metadata.permissions().mode() & 0o077 != 0 {
+            tracing::warn!(
+                "Secrets file {} is accessible by other users. Run: chmod 600 {}",
+                path.display(),
+                path.display()
+            );
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn warn_if_world_readable(_path: &Path) {}
+
+/// Look up a value stored in the OS keychain under the chaba service
+pub fn keychain_get(name: &str) -> Result<String> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, name)
+        .map_err(|e| ChabaError::ConfigError(format!("Keychain error for '{}': {}", name, e)))?;
+
+    entry
+        .get_password()
+        .map_err(|e| ChabaError::ConfigError(format!("Keychain entry '{}' unavailable: {}", name, e)))
+}
+
+/// Store a value in the OS keychain under the chaba service
+pub fn keychain_set(name: &str, value: &str) -> Result<()> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, name)
+        .map_err(|e| ChabaError::ConfigError(format!("Keychain error for '{}': {}", name, e)))?;
+
+    entry
+        .set_password(value)
+        .map_err(|e| ChabaError::ConfigError(format!("Failed to store '{}' in keychain: {}", name, e)))
+}
+
+/// Remove a value from the OS keychain
+pub fn keychain_delete(name: &str) -> Result<()> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, name)
+        .map_err(|e| ChabaError::ConfigError(format!("Keychain error for '{}': {}", name, e)))?;
+
+    entry
+        .delete_credential()
+        .map_err(|e| ChabaError::ConfigError(format!("Failed to delete '{}' from keychain: {}", name, e)))
+}
+
+/// Well-known tokens and the environment variable they are injected as
+///
+/// `github` is passed to `gh`; the others are passed to the matching agent.
+pub const KNOWN_TOKENS: &[(&str, &str)] = &[
+    ("github", "GH_TOKEN"),
+    ("claude", "ANTHROPIC_API_KEY"),
+    ("codex", "OPENAI_API_KEY"),
+    ("gemini", "GEMINI_API_KEY"),
+    ("cursor-agent", "CURSOR_API_KEY"),
+    ("copilot", "COPILOT_GITHUB_TOKEN"),
+];
+
+/// Environment variable a well-known token is injected as
+pub fn token_env_var(name: &str) -> Option<&'static str> {
+    KNOWN_TOKENS
+        .iter()
+        .find(|(token, _)| *token == name)
+        .map(|(_, var)| *var)
+}
+
+/// Add a well-known token from the keychain to `env`, if one is stored
+///
+/// Explicitly configured variables take precedence over the keychain.
+pub fn inject_keychain_token(env: &mut CommandEnv, name: &str) {
+    let Some(var) = token_env_var(name) else {
+        return;
+    };
+
+    if env.set.iter().any(|(key, _)| key == var) {
+        return;
+    }
+
+    if let Ok(token) = keychain_get(name) {
+        env.remove.retain(|key| key != var);
+        env.set.push((var.to_string(), token));
+    }
+}
+
+/// Build the environment overrides for a single agent
+///
+/// Variables configured for the agent are set; variables configured only for
+/// other agents are removed so one agent never sees another agent's credentials.
+pub fn resolve_agent_env(config: &AgentsConfig, agent: &str) -> Result<CommandEnv> {
+    let mut env = CommandEnv::default();
+    let own = config.env.get(agent);
+
+    if let Some(vars) = own {
+        for (key, value) in vars {
+            let resolved = resolve_value(value, config.secrets_file.as_deref(), &format!("agent '{}'", agent))?;
+            env.set.push((key.clone(), resolved));
+        }
+    }
+
+    for (other, vars) in &config.env {
+        if other == agent {
+            continue;
+        }
+        for key in vars.keys() {
+            let shared = own.is_some_and(|o| o.contains_key(key));
+            if !shared && !env.remove.contains(key) {
+                env.remove.push(key.clone());
+            }
+        }
+    }
+
+    Ok(env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn config_with_env(secrets_file: Option<PathBuf>) -> AgentsConfig {
+        let yaml = r#"
+env:
+  claude:
+    ANTHROPIC_BASE_URL: https://proxy.example.com
+    ANTHROPIC_API_KEY:
+      secret: anthropic
+  codex:
+    OPENAI_API_KEY: sk-test
+"#;
+        let mut config: AgentsConfig = serde_yaml::from_str(yaml).unwrap();
+        config.secrets_file = secrets_file;
+        config
+    }
+
+    #[test]
+    fn test_resolve_agent_env_with_secret() {
+        let temp_dir = TempDir::new().unwrap();
+        let secrets_path = temp_dir.path().join("secrets.yaml");
+        std::fs::write(&secrets_path, "anthropic: sk-ant-123\n").unwrap();
+
+        let config = config_with_env(Some(secrets_path));
+        let env = resolve_agent_env(&config, "claude").unwrap();
+
+        assert!(env.set.contains(&("ANTHROPIC_API_KEY".to_string(), "sk-ant-123".to_string())));
+        assert!(env.set.contains(&(
+            "ANTHROPIC_BASE_URL".to_string(),
+            "https://proxy.example.com".to_string()
+        )));
+        assert_eq!(env.remove, vec!["OPENAI_API_KEY".to_string()]);
+    }
+
+    #[test]
+    fn test_resolve_agent_env_isolates_other_agents() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = config_with_env(Some(temp_dir.path().join("missing.yaml")));
+
+        let env = resolve_agent_env(&config, "codex").unwrap();
+        assert_eq!(env.set, vec![("OPENAI_API_KEY".to_string(), "sk-test".to_string())]);
+        assert!(env.remove.contains(&"ANTHROPIC_API_KEY".to_string()));
+        assert!(env.remove.contains(&"ANTHROPIC_BASE_URL".to_string()));
+    }
+
+    #[test]
+    fn test_resolve_agent_env_missing_secret() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = config_with_env(Some(temp_dir.path().join("missing.yaml")));
+
+        let result = resolve_agent_env(&config, "claude");
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Secret 'anthropic'"));
+    }
+
+    #[test]
+    fn test_token_env_var() {
+        assert_eq!(token_env_var("github"), Some("GH_TOKEN"));
+        assert_eq!(token_env_var("claude"), Some("ANTHROPIC_API_KEY"));
+        assert_eq!(token_env_var("unknown"), None);
+    }
+
+    #[test]
+    fn test_inject_keychain_token_keeps_configured_value() {
+        let mut env = CommandEnv {
+            set: vec![("ANTHROPIC_API_KEY".to_string(), "from-config".to_string())],
+            remove: Vec::new(),
+        };
+
+        inject_keychain_token(&mut env, "claude");
+        assert_eq!(env.set, vec![("ANTHROPIC_API_KEY".to_string(), "from-config".to_string())]);
+    }
+
+    #[test]
+    fn test_resolve_agent_env_unconfigured() {
+        let config = AgentsConfig::default();
+        let env = resolve_agent_env(&config, "gemini").unwrap();
+        assert!(env.is_empty());
+    }
+}
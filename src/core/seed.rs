@@ -0,0 +1,282 @@
+//! Data seeding for review environments
+//!
+//! `sandbox.seed_fixtures` copies files such as a SQLite database or SQL dump
+//! into the worktree, then `sandbox.seed_commands` (e.g. `npm run seed`) run
+//! in order, so each environment starts with realistic data for manual
+//! testing. The outcome is stored in the review state.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+use crate::config::{SandboxConfig, SeedFixture};
+use crate::core::logs::ReviewLogs;
+use crate::core::platform::shell_command;
+use crate::error::{ChabaError, Result};
+
+/// Outcome of seeding a review environment
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeedResult {
+    /// Fixture files copied, relative to the worktree
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fixtures: Vec<String>,
+    /// Seed commands that completed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<String>,
+    /// The step that failed and why; later steps were skipped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub seeded_at: DateTime<Utc>,
+}
+
+impl SeedResult {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Short description for review and status output
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.fixtures.is_empty() {
+            parts.push(format!("{} fixture(s)", self.fixtures.len()));
+        }
+        if !self.commands.is_empty() {
+            parts.push(format!("{} command(s)", self.commands.len()));
+        }
+        let done = if parts.is_empty() { "nothing".to_string() } else { parts.join(", ") };
+
+        match &self.error {
+            Some(error) => format!("failed after {}: {}", done, error),
+            None => done,
+        }
+    }
+}
+
+/// Values seed commands see in their environment
+#[derive(Debug, Clone, Copy)]
+pub struct SeedVars {
+    pub pr: u32,
+    pub port: Option<u16>,
+}
+
+/// Whether the config asks for any seeding
+pub fn is_configured(config: &SandboxConfig) -> bool {
+    !config.seed_fixtures.is_empty() || !config.seed_commands.is_empty()
+}
+
+/// Copy fixtures and run seed commands in `worktree_path`
+///
+/// Stops at the first failing step; the failure is recorded in the result
+/// rather than returned. Command output is appended to the seed log when
+/// `logs` is given.
+pub async fn seed(
+    config: &SandboxConfig,
+    main_worktree: &Path,
+    worktree_path: &Path,
+    vars: SeedVars,
+    logs: Option<&ReviewLogs>,
+) -> SeedResult {
+    let mut result = SeedResult {
+        fixtures: Vec::new(),
+        commands: Vec::new(),
+        error: None,
+        seeded_at: Utc::now(),
+    };
+
+    for fixture in &config.seed_fixtures {
+        match copy_fixture(fixture, main_worktree, worktree_path).await {
+            Ok(dest) => {
+                tracing::info!("Copied seed fixture {}", dest);
+                result.fixtures.push(dest);
+            }
+            Err(e) => {
+                result.error = Some(format!("fixture {}: {}", fixture.from, e));
+                return result;
+            }
+        }
+    }
+
+    for command in &config.seed_commands {
+        tracing::info!("Running seed command: {}", command);
+        match run_command(command, worktree_path, vars, config.seed_timeout, logs).await {
+            Ok(()) => result.commands.push(command.clone()),
+            Err(e) => {
+                result.error = Some(format!("`{}`: {}", command, e));
+                return result;
+            }
+        }
+    }
+
+    result.seeded_at = Utc::now();
+    result
+}
+
+/// Copy one fixture into the worktree, returning its destination
+async fn copy_fixture(fixture: &SeedFixture, main_worktree: &Path, worktree_path: &Path) -> Result<String> {
+    let source = source_path(&fixture.from, main_worktree)?;
+    if !source.is_file() {
+        return Err(ChabaError::ConfigError(format!("{} does not exist", source.display())));
+    }
+
+    let dest = destination(fixture, &source)?;
+    let target = worktree_path.join(&dest);
+    if let Some(parent) = target.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::copy(&source, &target).await?;
+
+    Ok(dest.to_string_lossy().into_owned())
+}
+
+/// Fixture source: `~/`-relative, absolute, or relative to the main worktree
+fn source_path(from: &str, main_worktree: &Path) -> Result<PathBuf> {
+    if let Some(rest) = from.strip_prefix("~/") {
+        let home = dirs::home_dir()
+            .ok_or_else(|| ChabaError::ConfigError("Cannot find home directory".to_string()))?;
+        return Ok(home.join(rest));
+    }
+
+    Ok(main_worktree.join(from))
+}
+
+/// Worktree-relative destination of a fixture
+///
+/// Defaults to the source path for fixtures inside the repository and to
+/// the file name for fixtures outside it.
+fn destination(fixture: &SeedFixture, source: &Path) -> Result<PathBuf> {
+    let dest = match &fixture.to {
+        Some(to) => PathBuf::from(to),
+        None if Path::new(&fixture.from).is_relative() && !fixture.from.starts_with("~/") => {
+            PathBuf::from(&fixture.from)
+        }
+        None => PathBuf::from(source.file_name().unwrap_or_default()),
+    };
+
+    let escapes = dest
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+    if escapes || dest.as_os_str().is_empty() {
+        return Err(ChabaError::ConfigError(format!(
+            "seed fixture destination must be a relative path inside the worktree: {}",
+            dest.display()
+        )));
+    }
+
+    Ok(dest)
+}
+
+/// Run a seed command through the shell
+async fn run_command(
+    command: &str,
+    worktree_path: &Path,
+    vars: SeedVars,
+    timeout_secs: u64,
+    logs: Option<&ReviewLogs>,
+) -> Result<()> {
+    let mut cmd = shell_command(command);
+    cmd.current_dir(worktree_path)
+        .env("CHABA_WORKTREE_PATH", worktree_path)
+        .env("CHABA_PR", vars.pr.to_string())
+        .kill_on_drop(true);
+    if let Some(port) = vars.port {
+        cmd.env("CHABA_PORT", port.to_string());
+    }
+
+    let output = tokio::time::timeout(Duration::from_secs(timeout_secs), cmd.output())
+        .await
+        .map_err(|_| ChabaError::Other(anyhow::anyhow!("timed out after {} seconds", timeout_secs)))??;
+
+    if let Some(logs) = logs {
+        if let Err(e) = logs.append_output(&logs.seed_log(), command, &output).await {
+            tracing::warn!("Failed to write seed log: {}", e);
+        }
+    }
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let detail = stderr.lines().rev().find(|line| !line.trim().is_empty()).unwrap_or("").trim();
+        return Err(ChabaError::Other(anyhow::anyhow!(
+            "exited with {}{}",
+            output.status.code().map(|c| c.to_string()).unwrap_or_else(|| "a signal".to_string()),
+            if detail.is_empty() { String::new() } else { format!(" ({})", detail) }
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn config(fixtures: Vec<SeedFixture>, commands: &[&str]) -> SandboxConfig {
+        SandboxConfig {
+            seed_fixtures: fixtures,
+            seed_commands: commands.iter().map(|c| c.to_string()).collect(),
+            ..SandboxConfig::default()
+        }
+    }
+
+    fn fixture(from: &str, to: Option<&str>) -> SeedFixture {
+        SeedFixture {
+            from: from.to_string(),
+            to: to.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_seed_copies_fixtures_and_runs_commands() {
+        let main = TempDir::new().unwrap();
+        let review = TempDir::new().unwrap();
+        std::fs::create_dir_all(main.path().join("fixtures")).unwrap();
+        std::fs::write(main.path().join("fixtures/dev.sqlite"), "data").unwrap();
+
+        let config = config(
+            vec![fixture("fixtures/dev.sqlite", Some("db/review.sqlite"))],
+            &["cat db/review.sqlite > seeded.txt && echo $CHABA_PR $CHABA_PORT >> seeded.txt"],
+        );
+        let vars = SeedVars { pr: 42, port: Some(3001) };
+        let result = seed(&config, main.path(), review.path(), vars, None).await;
+
+        assert!(result.succeeded(), "{:?}", result.error);
+        assert_eq!(result.fixtures, vec!["db/review.sqlite"]);
+        assert_eq!(result.commands.len(), 1);
+        assert_eq!(
+            std::fs::read_to_string(review.path().join("seeded.txt")).unwrap(),
+            "data42 3001\n"
+        );
+        assert_eq!(result.summary(), "1 fixture(s), 1 command(s)");
+    }
+
+    #[tokio::test]
+    async fn test_seed_stops_at_first_failure() {
+        let main = TempDir::new().unwrap();
+        let review = TempDir::new().unwrap();
+
+        let config = config(Vec::new(), &["echo migrate failed >&2; exit 2", "touch never"]);
+        let vars = SeedVars { pr: 1, port: None };
+        let result = seed(&config, main.path(), review.path(), vars, None).await;
+
+        assert!(!result.succeeded());
+        assert!(result.commands.is_empty());
+        assert_eq!(
+            result.error.as_deref(),
+            Some("`echo migrate failed >&2; exit 2`: exited with 2 (migrate failed)")
+        );
+        assert!(!review.path().join("never").exists());
+    }
+
+    #[test]
+    fn test_fixture_destination() {
+        let source = Path::new("/dumps/prod.sql");
+        assert_eq!(destination(&fixture("/dumps/prod.sql", None), source).unwrap(), PathBuf::from("prod.sql"));
+        assert_eq!(
+            destination(&fixture("fixtures/dev.sqlite", None), source).unwrap(),
+            PathBuf::from("fixtures/dev.sqlite")
+        );
+        assert!(destination(&fixture("/dumps/prod.sql", Some("../outside.sql")), source).is_err());
+        assert!(destination(&fixture("/dumps/prod.sql", Some("/etc/passwd")), source).is_err());
+    }
+}
@@ -17,10 +17,10 @@ impl SessionManager {
     }
 
     /// Convert a filesystem path to Claude's escaped format
-    /// Example: /Users/foo/bar -> -Users-foo-bar
+    /// Example: /Users/foo/bar -> -Users-foo-bar, C:\Users\foo -> C--Users-foo
     fn escape_path(path: &Path) -> String {
         path.to_string_lossy()
-            .replace('/', "-")
+            .replace(['/', '\\', ':'], "-")
     }
 
     /// Get the session directory path for a given worktree path
@@ -127,4 +127,10 @@ mod tests {
         let path = Path::new("relative/path");
         assert_eq!(SessionManager::escape_path(path), "relative-path");
     }
+
+    #[test]
+    fn test_escape_path_windows() {
+        let path = Path::new(r"C:\Users\foo\bar");
+        assert_eq!(SessionManager::escape_path(path), "C--Users-foo-bar");
+    }
 }
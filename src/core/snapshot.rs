@@ -0,0 +1,302 @@
+//! Worktree snapshots
+//!
+//! `chaba snapshot` records a review worktree's contents as a git commit kept
+//! under `refs/chaba/snapshots/`, plus copies of its env files (which git
+//! ignores) in `~/.chaba/snapshots/<repo-hash>/pr-<number>/<id>/`. `chaba rollback`
+//! restores both, so risky agent-driven edits can be undone in one step.
+//! The env copies are encrypted when encryption at rest is enabled (see
+//! [`crate::core::encryption`]).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::core::encryption;
+use crate::core::git::{GitOps, SnapshotCommit};
+use crate::core::state::{self, ReviewKey, ReviewState};
+use crate::error::{ChabaError, Result};
+
+/// Snapshot description file inside each snapshot directory
+const SNAPSHOT_FILE: &str = "snapshot.json";
+
+/// Temporary index used while recording a snapshot, relative to the worktree
+const SNAPSHOT_INDEX: &str = ".chaba/snapshot.index";
+
+/// A recorded worktree state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Identifier, derived from the creation time (e.g. `20240101-120000`)
+    pub id: String,
+    pub pr_number: u32,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// HEAD when the snapshot was taken
+    pub head: String,
+    /// Commit holding the worktree contents
+    pub commit: String,
+    /// Env files copied alongside the commit, relative to the worktree
+    #[serde(default)]
+    pub env_files: Vec<String>,
+}
+
+impl Snapshot {
+    /// Git ref that keeps the snapshot commit reachable
+    pub fn reference(&self) -> String {
+        snapshot_ref(self.pr_number, &self.id)
+    }
+
+    fn git_commit(&self) -> SnapshotCommit {
+        SnapshotCommit {
+            head: self.head.clone(),
+            commit: self.commit.clone(),
+        }
+    }
+}
+
+/// Git ref for a snapshot
+pub fn snapshot_ref(pr_number: u32, id: &str) -> String {
+    format!("refs/chaba/snapshots/pr-{}/{}", pr_number, id)
+}
+
+/// Snapshots of a single review environment
+#[derive(Debug, Clone)]
+pub struct SnapshotStore {
+    pr_number: u32,
+    dir: PathBuf,
+}
+
+impl SnapshotStore {
+    /// Snapshots for the given review under `~/.chaba/snapshots/<repo-hash>/pr-<number>/`
+    pub fn for_review(key: &ReviewKey) -> Result<Self> {
+        Ok(Self::at(
+            key.pr_number,
+            state::state_dir()?.join("snapshots").join(key.dir_name()),
+        ))
+    }
+
+    /// Snapshots rooted at a specific directory
+    pub fn at(pr_number: u32, dir: impl Into<PathBuf>) -> Self {
+        SnapshotStore {
+            pr_number,
+            dir: dir.into(),
+        }
+    }
+
+    /// All snapshots, oldest first
+    pub fn list(&self) -> Result<Vec<Snapshot>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut snapshots: Vec<Snapshot> = entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| std::fs::read_to_string(e.path().join(SNAPSHOT_FILE)).ok())
+            .filter_map(|content| serde_json::from_str(&content).ok())
+            .collect();
+        snapshots.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+
+        Ok(snapshots)
+    }
+
+    /// Look up a snapshot by ID
+    pub fn get(&self, id: &str) -> Result<Snapshot> {
+        self.list()?
+            .into_iter()
+            .find(|s| s.id == id)
+            .ok_or_else(|| {
+                ChabaError::ConfigError(format!(
+                    "Snapshot '{}' not found for PR #{}. Run 'chaba snapshot --pr {} --list' to see available snapshots.",
+                    id, self.pr_number, self.pr_number
+                ))
+            })
+    }
+
+    /// Record the current state of `review`'s worktree
+    ///
+    /// `env_files` (relative to the worktree) are copied when they exist.
+    pub async fn take(
+        &self,
+        git_ops: &GitOps,
+        review: &ReviewState,
+        env_files: &[String],
+        message: Option<String>,
+    ) -> Result<Snapshot> {
+        let created_at = Utc::now();
+        let id = self.unused_id(created_at);
+        let worktree = &review.worktree_path;
+
+        let index_file = worktree.join(SNAPSHOT_INDEX);
+        if let Some(parent) = index_file.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let commit_message = message
+            .clone()
+            .unwrap_or_else(|| format!("chaba snapshot {}", id));
+        let commit = git_ops
+            .snapshot_worktree(worktree, &index_file, &snapshot_ref(self.pr_number, &id), &commit_message)
+            .await?;
+
+        let snapshot_dir = self.dir.join(&id);
+        let mut copied = Vec::new();
+        for file in env_files {
+            let source = worktree.join(file);
+            if source.is_file() {
+                let target = snapshot_dir.join("env").join(file);
+                if let Some(parent) = target.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                let content = encryption::seal(tokio::fs::read(&source).await?)?;
+                tokio::fs::write(&target, content).await?;
+                copied.push(file.clone());
+            }
+        }
+
+        let snapshot = Snapshot {
+            id,
+            pr_number: self.pr_number,
+            created_at,
+            message,
+            head: commit.head,
+            commit: commit.commit,
+            env_files: copied,
+        };
+
+        tokio::fs::create_dir_all(&snapshot_dir).await?;
+        let json = serde_json::to_string_pretty(&snapshot)
+            .map_err(|e| ChabaError::Other(e.into()))?;
+        tokio::fs::write(snapshot_dir.join(SNAPSHOT_FILE), json).await?;
+
+        Ok(snapshot)
+    }
+
+    /// Restore `review`'s worktree, including env files, to `snapshot`
+    pub async fn restore(&self, git_ops: &GitOps, review: &ReviewState, snapshot: &Snapshot) -> Result<()> {
+        git_ops
+            .restore_snapshot(&review.worktree_path, &snapshot.git_commit())
+            .await?;
+
+        let env_dir = self.dir.join(&snapshot.id).join("env");
+        for file in &snapshot.env_files {
+            let content = encryption::read(&env_dir.join(file))?;
+            tokio::fs::write(review.worktree_path.join(file), content).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Delete all snapshots and their git refs
+    pub async fn remove_all(&self, git_ops: &GitOps) -> Result<()> {
+        for snapshot in self.list()? {
+            git_ops.delete_ref(&snapshot.reference()).await?;
+        }
+
+        match tokio::fs::remove_dir_all(&self.dir).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Time-based ID, with a suffix if a snapshot was already taken this second
+    fn unused_id(&self, created_at: DateTime<Utc>) -> String {
+        let base = created_at.format("%Y%m%d-%H%M%S").to_string();
+        let exists = |id: &str| self.dir.join(id).exists();
+
+        if !exists(&base) {
+            return base;
+        }
+        (2..)
+            .map(|n| format!("{}-{}", base, n))
+            .find(|id| !exists(id))
+            .unwrap_or(base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Repository;
+    use std::path::Path;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    fn review(worktree_path: PathBuf) -> ReviewState {
+        ReviewState {
+            branch: "feature".to_string(),
+            ..ReviewState::for_test(7, worktree_path)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_take_and_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_dir = temp_dir.path().join("repo");
+        std::fs::create_dir_all(&repo_dir).unwrap();
+        Repository::init(&repo_dir).unwrap();
+        std::fs::write(repo_dir.join(".gitignore"), ".env\n.chaba/\n").unwrap();
+        std::fs::write(repo_dir.join("tracked.txt"), "original\n").unwrap();
+        git(&repo_dir, &["add", "."]);
+        git(&repo_dir, &["commit", "-qm", "init"]);
+
+        // Work in progress at snapshot time: a modification, an untracked file, an env file
+        std::fs::write(repo_dir.join("tracked.txt"), "edited\n").unwrap();
+        std::fs::write(repo_dir.join("notes.txt"), "untracked\n").unwrap();
+        std::fs::write(repo_dir.join(".env"), "PORT=3001\n").unwrap();
+
+        let git_ops = GitOps::open_at(&repo_dir).unwrap();
+        let store = SnapshotStore::at(7, temp_dir.path().join("snapshots"));
+        let review = review(repo_dir.clone());
+
+        let snapshot = store
+            .take(&git_ops, &review, &[".env".to_string(), ".env.local".to_string()], Some("before agent".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(snapshot.env_files, vec![".env"]);
+        assert_eq!(store.get(&snapshot.id).unwrap(), snapshot);
+
+        // Risky edits after the snapshot
+        std::fs::write(repo_dir.join("tracked.txt"), "broken\n").unwrap();
+        std::fs::remove_file(repo_dir.join("notes.txt")).unwrap();
+        std::fs::write(repo_dir.join("stray.txt"), "agent output\n").unwrap();
+        std::fs::write(repo_dir.join(".env"), "PORT=9999\n").unwrap();
+        git(&repo_dir, &["commit", "-qam", "agent commit"]);
+
+        store.restore(&git_ops, &review, &snapshot).await.unwrap();
+
+        let read = |name: &str| std::fs::read_to_string(repo_dir.join(name)).unwrap();
+        assert_eq!(read("tracked.txt"), "edited\n");
+        assert_eq!(read("notes.txt"), "untracked\n");
+        assert_eq!(read(".env"), "PORT=3001\n");
+        assert!(!repo_dir.join("stray.txt").exists());
+
+        let repo = Repository::open(&repo_dir).unwrap();
+        assert_eq!(repo.head().unwrap().target().unwrap().to_string(), snapshot.head);
+
+        store.remove_all(&git_ops).await.unwrap();
+        assert!(store.list().unwrap().is_empty());
+        assert!(repo.find_reference(&snapshot.reference()).is_err());
+    }
+
+    #[test]
+    fn test_get_missing_snapshot() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = SnapshotStore::at(7, temp_dir.path());
+
+        assert!(store.list().unwrap().is_empty());
+        assert!(store.get("nope").is_err());
+    }
+}
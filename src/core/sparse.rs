@@ -0,0 +1,73 @@
+//! Sparse checkout for large repositories
+//!
+//! With `worktree.sparse_paths` configured (or `review --sparse`), new
+//! worktrees use cone-mode sparse checkout limited to the directories the
+//! changes touch plus the configured paths. Files at the repository root are
+//! always checked out in cone mode.
+
+use std::collections::BTreeSet;
+
+/// Cone-mode directories covering `changed_files` and `include`
+///
+/// Each changed file contributes its parent directory. Directories inside
+/// another listed directory are dropped, since cone mode includes
+/// subdirectories anyway.
+pub fn cone_directories(changed_files: &[String], include: &[String]) -> Vec<String> {
+    let parents = changed_files
+        .iter()
+        .filter_map(|file| file.rsplit_once('/').map(|(dir, _)| dir));
+    let included = include.iter().map(|path| path.as_str());
+
+    let dirs: BTreeSet<String> = parents
+        .chain(included)
+        .map(|dir| dir.trim_matches('/'))
+        .filter(|dir| !dir.is_empty() && *dir != ".")
+        .map(str::to_string)
+        .collect();
+
+    // Sorted order puts every parent before its descendants
+    let mut cone: Vec<String> = Vec::new();
+    for dir in dirs {
+        let covered = cone
+            .iter()
+            .any(|parent| dir.starts_with(&format!("{}/", parent)));
+        if !covered {
+            cone.push(dir);
+        }
+    }
+
+    cone
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_cone_directories() {
+        let changed = strings(&[
+            "services/api/src/main.rs",
+            "services/api/Cargo.toml",
+            "services/api-gateway/src/lib.rs",
+            "services/api/src/bin/tool.rs",
+            "web/app/src/index.ts",
+            "README.md",
+        ]);
+        let include = strings(&["/tools/", "web"]);
+
+        assert_eq!(
+            cone_directories(&changed, &include),
+            vec!["services/api", "services/api-gateway/src", "tools", "web"]
+        );
+    }
+
+    #[test]
+    fn test_cone_directories_root_files_only() {
+        let changed = strings(&["README.md", "Cargo.toml"]);
+        assert!(cone_directories(&changed, &strings(&["."])).is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+//! Stacked PRs
+//!
+//! A PR whose base branch is another PR's head is recorded with a
+//! `parent_pr`. This module orders reviews so each stack is shown as a tree.
+
+use std::collections::HashSet;
+
+use crate::core::state::{ReviewKey, ReviewState};
+
+/// Reviews ordered so each parent precedes its children, with nesting depth
+///
+/// Reviews whose parent isn't tracked in the same repository are roots.
+/// Input order is kept among siblings.
+pub fn stack_order(reviews: &[ReviewState]) -> Vec<(usize, &ReviewState)> {
+    let tracked: HashSet<ReviewKey> = reviews.iter().map(ReviewState::key).collect();
+    let mut visited = HashSet::new();
+    let mut ordered = Vec::with_capacity(reviews.len());
+
+    for root in reviews
+        .iter()
+        .filter(|r| {
+            r.parent_pr.is_none_or(|parent| {
+                !tracked.contains(&ReviewKey {
+                    repo: r.repo.clone(),
+                    pr_number: parent,
+                })
+            })
+        })
+    {
+        push_with_children(root, 0, reviews, &mut visited, &mut ordered);
+    }
+
+    // Parent cycles have no root; show what's left flat rather than dropping it
+    for review in reviews {
+        if !visited.contains(&review.key()) {
+            push_with_children(review, 0, reviews, &mut visited, &mut ordered);
+        }
+    }
+
+    ordered
+}
+
+fn push_with_children<'a>(
+    review: &'a ReviewState,
+    depth: usize,
+    reviews: &'a [ReviewState],
+    visited: &mut HashSet<ReviewKey>,
+    ordered: &mut Vec<(usize, &'a ReviewState)>,
+) {
+    if !visited.insert(review.key()) {
+        return;
+    }
+    ordered.push((depth, review));
+
+    for child in reviews
+        .iter()
+        .filter(|r| r.parent_pr == Some(review.pr_number) && r.repo == review.repo)
+    {
+        push_with_children(child, depth + 1, reviews, visited, ordered);
+    }
+}
+
+/// Tree prefix for a review at `depth` (e.g. `└─ ` for a direct child)
+pub fn tree_prefix(depth: usize) -> String {
+    if depth == 0 {
+        String::new()
+    } else {
+        format!("{}└─ ", "   ".repeat(depth - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn review(pr_number: u32, parent_pr: Option<u32>) -> ReviewState {
+        ReviewState {
+            branch: format!("branch-{}", pr_number),
+            parent_pr,
+            ..ReviewState::for_test(pr_number, format!("/tmp/pr-{}", pr_number))
+        }
+    }
+
+    fn order(reviews: &[ReviewState]) -> Vec<(usize, u32)> {
+        stack_order(reviews)
+            .into_iter()
+            .map(|(depth, r)| (depth, r.pr_number))
+            .collect()
+    }
+
+    #[test]
+    fn test_stack_order_nests_children_under_parents() {
+        let reviews = vec![
+            review(3, Some(2)),
+            review(10, None),
+            review(2, Some(1)),
+            review(1, None),
+        ];
+
+        assert_eq!(order(&reviews), vec![(0, 10), (0, 1), (1, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn test_stack_order_untracked_parent_is_root() {
+        let reviews = vec![review(5, Some(4))];
+        assert_eq!(order(&reviews), vec![(0, 5)]);
+    }
+
+    #[test]
+    fn test_stack_order_keeps_cycles() {
+        let reviews = vec![review(1, Some(2)), review(2, Some(1))];
+        assert_eq!(order(&reviews), vec![(0, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn test_stack_order_keeps_repositories_apart() {
+        let in_repo = |repo: &str, review: ReviewState| ReviewState {
+            repo: Some(PathBuf::from(repo)),
+            ..review
+        };
+        let reviews = vec![
+            in_repo("/repos/a", review(1, None)),
+            in_repo("/repos/b", review(1, None)),
+            in_repo("/repos/b", review(2, Some(1))),
+        ];
+
+        let ordered: Vec<_> = stack_order(&reviews)
+            .into_iter()
+            .map(|(depth, r)| (depth, r.pr_number, r.repo.clone().unwrap()))
+            .collect();
+        assert_eq!(
+            ordered,
+            vec![
+                (0, 1, PathBuf::from("/repos/a")),
+                (0, 1, PathBuf::from("/repos/b")),
+                (1, 2, PathBuf::from("/repos/b")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tree_prefix() {
+        assert_eq!(tree_prefix(0), "");
+        assert_eq!(tree_prefix(1), "└─ ");
+        assert_eq!(tree_prefix(2), "   └─ ");
+    }
+}
@@ -1,18 +1,120 @@
 use chrono::{DateTime, Utc};
 use fs2::FileExt;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs::File;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
 use tempfile::NamedTempFile;
 
-use crate::core::review_analysis::ReviewAnalysis;
-use crate::error::Result;
+use crate::config::Config;
+use crate::core::encryption;
+use crate::core::git::{GitOps, PrInfo};
+use crate::core::history::AnalysisRun;
+use crate::core::platform;
+use crate::core::review_analysis::{ReviewAnalysis, Usage};
+use crate::core::seed::SeedResult;
+use crate::core::test_suite::TestRunResult;
+use crate::error::{ChabaError, Result};
+
+/// Environment variable overriding the state directory
+pub const STATE_DIR_ENV: &str = "CHABA_STATE_DIR";
+
+/// Attempts at saving a change before a concurrent writer's conflict is returned
+const MAX_SAVE_ATTEMPTS: usize = 5;
+
+/// Directory holding chaba's state, logs, and archive
+///
+/// See [`crate::config::StateConfig`] for how it is chosen.
+pub fn state_dir() -> Result<PathBuf> {
+    // The config cannot change while chaba runs, so it is only read once
+    static CONFIGURED: OnceLock<Option<PathBuf>> = OnceLock::new();
+    let configured = CONFIGURED.get_or_init(|| Config::load().ok().and_then(|config| config.state.path));
+
+    resolve_state_dir(
+        std::env::var_os(STATE_DIR_ENV).map(PathBuf::from),
+        configured.as_deref(),
+        std::env::var_os("XDG_STATE_HOME").map(PathBuf::from),
+        dirs::home_dir(),
+    )
+    .ok_or_else(|| ChabaError::ConfigError("Cannot find home directory".to_string()))
+}
+
+fn resolve_state_dir(
+    env: Option<PathBuf>,
+    configured: Option<&Path>,
+    xdg_state_home: Option<PathBuf>,
+    home: Option<PathBuf>,
+) -> Option<PathBuf> {
+    if let Some(dir) = env.filter(|dir| !dir.as_os_str().is_empty()) {
+        return Some(dir);
+    }
+    if let Some(dir) = configured {
+        return match dir.strip_prefix("~") {
+            Ok(rest) => Some(home?.join(rest)),
+            Err(_) => Some(dir.to_path_buf()),
+        };
+    }
+    // Relative values are invalid per the XDG spec and ignored
+    if let Some(dir) = xdg_state_home.filter(|dir| dir.is_absolute()) {
+        return Some(dir.join("chaba"));
+    }
+    Some(home?.join(".chaba"))
+}
+
+/// A review named on the command line: a PR number, or a branch name when not numeric
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewTarget {
+    Pr(u32),
+    Branch(String),
+}
+
+impl FromStr for ReviewTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err("expected a PR number or branch name".to_string());
+        }
+        match s.strip_prefix('#').unwrap_or(s).parse() {
+            Ok(pr) => Ok(ReviewTarget::Pr(pr)),
+            Err(_) => Ok(ReviewTarget::Branch(s.to_string())),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReviewState {
     pub pr_number: u32,
     pub branch: String,
+
+    /// Branch the PR targets, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_branch: Option<String>,
+
+    /// PR this one is stacked on, when its base branch is another PR's head
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_pr: Option<u32>,
+
+    /// Patch file applied on top of the base branch (`review --patch`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch: Option<PathBuf>,
+
     pub worktree_path: PathBuf,
+
+    /// Directories checked out when the worktree uses sparse checkout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse_paths: Option<Vec<String>>,
+
+    /// Title, author, draft state, and labels of the PR, for PR reviews
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr_info: Option<PrInfo>,
+
+    /// Main worktree of the repository the review belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<PathBuf>,
+
     pub created_at: DateTime<Utc>,
 
     // Phase 2: Sandbox environment info
@@ -28,9 +130,168 @@ pub struct ReviewState {
     #[serde(default)]
     pub env_copied: bool,
 
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tests: Option<TestRunResult>,
+
+    /// Fixtures and seed commands applied during setup
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<SeedResult>,
+
+    /// When the review expires and becomes eligible for `cleanup --expired`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+
     // Phase 3: AI Agent analysis results
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub agent_analyses: Vec<ReviewAnalysis>,
+
+    /// Commit the current agent analyses were run on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub analysis_commit: Option<String>,
+
+    /// Earlier agent runs, oldest first (see [`crate::core::history`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub analysis_history: Vec<AnalysisRun>,
+
+    /// The last agent run was cancelled with Ctrl-C; its analyses are partial
+    #[serde(default)]
+    pub analysis_interrupted: bool,
+
+    /// Tokens and cost of every agent run on this review so far
+    #[serde(default, skip_serializing_if = "Usage::is_empty")]
+    pub agent_usage: Usage,
+}
+
+#[cfg(test)]
+impl ReviewState {
+    /// Review of `feature-<pr>` with every optional field unset, for tests
+    ///
+    /// Override fields with struct update syntax:
+    /// `ReviewState { port: Some(3001), ..ReviewState::for_test(42, path) }`.
+    pub(crate) fn for_test(pr_number: u32, worktree_path: impl Into<PathBuf>) -> Self {
+        ReviewState {
+            pr_number,
+            branch: format!("feature-{}", pr_number),
+            base_branch: None,
+            parent_pr: None,
+            patch: None,
+            worktree_path: worktree_path.into(),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
+            created_at: Utc::now(),
+            port: None,
+            project_type: None,
+            deps_installed: false,
+            env_copied: false,
+            tests: None,
+            seed: None,
+            expires_at: None,
+            agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Usage::default(),
+        }
+    }
+}
+
+/// What identifies a review: PR numbers are only unique within a repository
+///
+/// `repo` is `None` for reviews recorded before the repository was stored.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ReviewKey {
+    pub repo: Option<PathBuf>,
+    pub pr_number: u32,
+}
+
+impl ReviewKey {
+    /// Whether both keys can name the same review
+    ///
+    /// A key without a repository matches the PR in any repository.
+    pub fn matches(&self, other: &ReviewKey) -> bool {
+        self.pr_number == other.pr_number
+            && match (&self.repo, &other.repo) {
+                (Some(repo), Some(other)) => repo == other,
+                _ => true,
+            }
+    }
+
+    /// Short hash of the repository path, naming its reviews' files
+    fn repo_hash(&self) -> Option<String> {
+        let repo = self.repo.as_ref()?;
+        let digest = Sha256::digest(repo.to_string_lossy().as_bytes());
+        Some(digest.iter().take(6).map(|b| format!("{:02x}", b)).collect())
+    }
+
+    /// Directory for the review in per-review stores such as logs and snapshots
+    ///
+    /// `<repo-hash>/pr-<number>`, so the same PR number in two repositories
+    /// gets separate files; `pr-<number>` for reviews without a repository.
+    pub fn dir_name(&self) -> PathBuf {
+        let pr = format!("pr-{}", self.pr_number);
+        match self.repo_hash() {
+            Some(hash) => Path::new(&hash).join(pr),
+            None => PathBuf::from(pr),
+        }
+    }
+
+    /// File name stem for the review in flat directories: `<repo-hash>-pr-<number>`
+    pub fn file_stem(&self) -> String {
+        match self.repo_hash() {
+            Some(hash) => format!("{}-pr-{}", hash, self.pr_number),
+            None => format!("pr-{}", self.pr_number),
+        }
+    }
+}
+
+impl ReviewState {
+    pub fn key(&self) -> ReviewKey {
+        ReviewKey {
+            repo: self.repo.clone(),
+            pr_number: self.pr_number,
+        }
+    }
+
+    /// Check if the review's TTL has passed
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Whether the review is of an actual GitHub PR
+    ///
+    /// Branch, patch, and local reviews use pseudo-PR numbers in 90000-99999.
+    /// Reviews recorded before PR info was stored are recognized by number.
+    pub fn is_pull_request(&self) -> bool {
+        self.patch.is_none()
+            && (self.pr_info.is_some() || !(90000..100000).contains(&self.pr_number))
+    }
+
+    /// Agent prompt context naming the branch the changes will merge into
+    ///
+    /// Stacked PRs and patches have their own, more specific context.
+    pub fn base_context(&self) -> Option<String> {
+        if self.parent_pr.is_some() || self.patch.is_some() {
+            return None;
+        }
+        let base = self.base_branch.as_deref()?;
+
+        Some(format!(
+            "The changes will be merged into `{}`. Review them against that merge target: `git diff origin/{}...HEAD`.",
+            base, base
+        ))
+    }
+
+    /// Agent prompt context for a stacked PR, pointing at the parent's branch
+    pub fn stack_context(&self) -> Option<String> {
+        let parent = self.parent_pr?;
+        let base = self.base_branch.as_deref()?;
+
+        Some(format!(
+            "This PR is stacked on PR #{} (branch `{}`). Review only the changes it adds on top of its parent: `git diff origin/{}...HEAD`.",
+            parent, base, base
+        ))
+    }
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -43,21 +304,38 @@ pub struct State {
     pub reviews: Vec<ReviewState>,
 }
 
+/// Main worktree of the repository chaba runs in, resolved once per process
+fn current_repo() -> Option<PathBuf> {
+    static REPO: OnceLock<Option<PathBuf>> = OnceLock::new();
+    REPO.get_or_init(|| GitOps::open().ok().map(|git| git.repo_root()))
+        .clone()
+}
+
 impl State {
     /// Load state from file with shared lock
     pub fn load() -> Result<Self> {
-        let state_path = Self::state_file_path()?;
+        Self::load_from(&Self::state_file_path()?)
+    }
 
+    /// Load state from a specific file
+    fn load_from(state_path: &Path) -> Result<Self> {
         if !state_path.exists() {
             return Ok(State::default());
         }
 
         // Open file with shared lock for reading
-        let file = File::open(&state_path)?;
+        let file = File::open(state_path)?;
         file.lock_shared()?;
 
-        let content = std::fs::read_to_string(&state_path)?;
-        let state: State = serde_yaml::from_str(&content)?;
+        let content = encryption::read_to_string(state_path)?;
+        let mut state: State = serde_yaml::from_str(&content)?;
+
+        // Findings saved before IDs existed get them on load
+        for review in &mut state.reviews {
+            for analysis in &mut review.agent_analyses {
+                analysis.assign_ids();
+            }
+        }
 
         // Lock is automatically released when file is dropped
         Ok(state)
@@ -65,35 +343,43 @@ impl State {
 
     /// Save state to file with atomic write and optimistic locking
     pub fn save(&mut self) -> Result<()> {
-        let state_path = Self::state_file_path()?;
+        self.save_to(&Self::state_file_path()?)
+    }
 
+    /// Save state to a specific file
+    fn save_to(&mut self, state_path: &Path) -> Result<()> {
         // Ensure directory exists
         if let Some(parent) = state_path.parent() {
             std::fs::create_dir_all(parent)?;
         }
 
+        // Writers take turns on a sidecar lock held until the new file is in
+        // place; the state file itself is replaced, so its lock can't be held
+        let lock = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(Self::lock_path(state_path))?;
+        lock.lock_exclusive()?;
+
         // Optimistic locking: Check if file was modified by another process
         if state_path.exists() {
-            // Read current version from file
-            let file = File::open(&state_path)?;
-            file.lock_shared()?;
-
-            let content = std::fs::read_to_string(&state_path)?;
+            let content = encryption::read_to_string(state_path)?;
             if let Ok(current_state) = serde_yaml::from_str::<State>(&content) {
                 if current_state.version != self.version {
-                    return Err(crate::error::ChabaError::StateConflict {
+                    return Err(ChabaError::StateConflict {
                         expected: self.version,
                         actual: current_state.version,
                     });
                 }
             }
-            // Lock is released when file is dropped
         }
 
         // Increment version before saving
         self.version += 1;
 
-        let content = serde_yaml::to_string(&self)?;
+        // Encrypted at rest when `chaba encrypt enable` was run
+        let content = encryption::seal(serde_yaml::to_string(&self)?.into_bytes())?;
 
         // Use tempfile + rename for atomic write
         // Create temp file in same directory as target to ensure same filesystem
@@ -101,9 +387,6 @@ impl State {
             state_path.parent().expect("state path should have parent directory")
         )?;
 
-        // Lock the temp file exclusively
-        temp_file.as_file().lock_exclusive()?;
-
         // Write to temp file
         std::fs::write(temp_file.path(), &content)?;
 
@@ -118,39 +401,178 @@ impl State {
 
         // Atomic rename (replaces existing file)
         // persist() returns PersistError which contains the underlying io::Error
-        temp_file.persist(&state_path)
+        temp_file.persist(state_path)
             .map_err(|e| e.error)?;
 
-        // Lock is automatically released when temp_file is dropped
+        // The sidecar lock is released when `lock` is dropped
         Ok(())
     }
 
+    /// Lock file next to the state file, e.g. `state.lock` for `state.yaml`
+    fn lock_path(state_path: &Path) -> PathBuf {
+        state_path.with_extension("lock")
+    }
+
     /// Add a review to state
+    ///
+    /// Refuses when the PR is already tracked for the same repository, so its
+    /// analyses and history are never dropped by accident. Use
+    /// [`State::update_review`] to change a tracked review.
     pub fn add_review(&mut self, review: ReviewState) -> Result<()> {
-        // Remove existing review with same PR number
-        self.reviews.retain(|r| r.pr_number != review.pr_number);
-        self.reviews.push(review);
-        self.save()
+        self.update(&Self::state_file_path()?, |state| state.insert_review(&review))
+    }
+
+    /// Change a tracked review
+    ///
+    /// `change` is applied to the review as last saved, so it only overwrites
+    /// the fields it sets, even when another process saved in the meantime.
+    pub fn update_review(&mut self, key: &ReviewKey, change: impl Fn(&mut ReviewState)) -> Result<()> {
+        self.update(&Self::state_file_path()?, |state| state.change_review(key, &change))
     }
 
     /// Remove a review from state
-    pub fn remove_review(&mut self, pr_number: u32) -> Result<()> {
-        self.reviews.retain(|r| r.pr_number != pr_number);
-        self.save()
+    pub fn remove_review(&mut self, key: &ReviewKey) -> Result<()> {
+        self.update(&Self::state_file_path()?, |state| {
+            state.reviews.retain(|r| !r.key().matches(key));
+            Ok(())
+        })
+    }
+
+    fn insert_review(&mut self, review: &ReviewState) -> Result<()> {
+        if self.reviews.iter().any(|r| r.key().matches(&review.key())) {
+            return Err(ChabaError::ReviewExists(review.pr_number));
+        }
+        self.reviews.push(review.clone());
+        Ok(())
+    }
+
+    fn change_review(&mut self, key: &ReviewKey, change: &impl Fn(&mut ReviewState)) -> Result<()> {
+        let review = self
+            .reviews
+            .iter_mut()
+            .find(|r| r.key().matches(key))
+            .ok_or(ChabaError::WorktreeNotFound(key.pr_number))?;
+        change(review);
+        Ok(())
+    }
+
+    /// Apply a change and save it
+    ///
+    /// When another process saved in the meantime (e.g. a concurrent
+    /// `chaba review`), the file is reloaded and the change re-applied to
+    /// the fresh state instead of failing, so neither write is lost.
+    fn update(&mut self, state_path: &Path, change: impl Fn(&mut State) -> Result<()>) -> Result<()> {
+        change(self)?;
+        let mut attempt = 1;
+        loop {
+            match self.save_to(state_path) {
+                Err(ChabaError::StateConflict { expected, actual }) if attempt < MAX_SAVE_ATTEMPTS => {
+                    tracing::debug!(
+                        "State changed on disk (version {} → {}), re-applying change",
+                        expected,
+                        actual
+                    );
+                    let mut fresh = Self::load_from(state_path)?;
+                    change(&mut fresh)?;
+                    *self = fresh;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
     }
 
     /// Get review by PR number
+    ///
+    /// When reviews of several repositories share the number, the one of the
+    /// repository chaba runs in (see `--repo`) is returned.
     pub fn get_review(&self, pr_number: u32) -> Option<&ReviewState> {
-        self.reviews.iter().find(|r| r.pr_number == pr_number)
+        let mut matching = self.reviews.iter().filter(|r| r.pr_number == pr_number);
+        let first = matching.next()?;
+        if matching.next().is_none() {
+            return Some(first);
+        }
+        let repo = current_repo()?;
+        self.find_review(&ReviewKey {
+            repo: Some(repo),
+            pr_number,
+        })
+    }
+
+    /// Key of the review of a PR, assuming the current repository if there is none
+    ///
+    /// For per-review files that outlive the review, such as its logs.
+    pub fn review_key(&self, pr_number: u32) -> ReviewKey {
+        match self.get_review(pr_number) {
+            Some(review) => review.key(),
+            None => ReviewKey {
+                repo: current_repo(),
+                pr_number,
+            },
+        }
+    }
+
+    /// Review of a PR in a specific repository
+    pub fn find_review(&self, key: &ReviewKey) -> Option<&ReviewState> {
+        self.reviews
+            .iter()
+            .find(|r| r.key() == *key)
+            .or_else(|| self.reviews.iter().find(|r| r.key().matches(key)))
+    }
+
+    /// Review whose worktree contains `path`, the innermost one if worktrees are nested
+    pub fn review_containing(&self, path: &Path) -> Option<&ReviewState> {
+        let path = platform::canonicalize(path).ok()?;
+        self.reviews
+            .iter()
+            .filter_map(|r| Some((r, platform::canonicalize(&r.worktree_path).ok()?)))
+            .filter(|(_, worktree)| path.starts_with(worktree))
+            .max_by_key(|(_, worktree)| worktree.components().count())
+            .map(|(r, _)| r)
+    }
+
+    /// The given PR number, or else the one whose worktree the current directory is in
+    pub fn resolve_pr(&self, pr: Option<u32>) -> Result<u32> {
+        self.resolve_target(pr.map(ReviewTarget::Pr))
+    }
+
+    /// PR number of the review named by `target`, or else of the worktree the current directory is in
+    pub fn resolve_target(&self, target: Option<ReviewTarget>) -> Result<u32> {
+        match target {
+            Some(ReviewTarget::Pr(pr)) => return Ok(pr),
+            Some(ReviewTarget::Branch(branch)) => {
+                let matching: Vec<u32> = self
+                    .reviews
+                    .iter()
+                    .filter(|r| r.branch == branch)
+                    .map(|r| r.pr_number)
+                    .collect();
+                return match matching[..] {
+                    [pr] => Ok(pr),
+                    [] => Err(ChabaError::ConfigError(format!("No review environment for branch '{}'", branch))),
+                    _ => Err(ChabaError::ConfigError(format!(
+                        "Several reviews use branch '{}' (PRs {}); pass --pr <number>",
+                        branch,
+                        matching.iter().map(|pr| format!("#{}", pr)).collect::<Vec<_>>().join(", ")
+                    ))),
+                };
+            }
+            None => {}
+        }
+        let cwd = std::env::current_dir()?;
+        self.review_containing(&cwd)
+            .map(|review| review.pr_number)
+            .ok_or_else(|| {
+                ChabaError::ConfigError(format!(
+                    "{} is not inside a review worktree; pass --pr <number>",
+                    cwd.display()
+                ))
+            })
     }
 
     /// Get state file path
     fn state_file_path() -> Result<PathBuf> {
-        let home = dirs::home_dir().ok_or_else(|| {
-            crate::error::ChabaError::ConfigError("Cannot find home directory".to_string())
-        })?;
-
-        Ok(home.join(".chaba").join("state.yaml"))
+        Ok(state_dir()?.join("state.yaml"))
     }
 }
 
@@ -170,13 +592,26 @@ mod tests {
         let review = ReviewState {
             pr_number: 123,
             branch: "feature/test".to_string(),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
             worktree_path: PathBuf::from("/tmp/test"),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
             created_at: Utc::now(),
             port: Some(3000),
             project_type: Some("node".to_string()),
             deps_installed: true,
             env_copied: true,
+            tests: None,
+            seed: None,
+            expires_at: None,
             agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
         };
 
         assert_eq!(review.pr_number, 123);
@@ -187,6 +622,158 @@ mod tests {
         assert!(review.agent_analyses.is_empty());
     }
 
+    #[test]
+    fn test_review_state_is_expired() {
+        let now = Utc::now();
+        let mut review = ReviewState {
+            pr_number: 123,
+            branch: "feature/test".to_string(),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
+            worktree_path: PathBuf::from("/tmp/test"),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
+            created_at: now,
+            port: None,
+            project_type: None,
+            deps_installed: false,
+            env_copied: false,
+            tests: None,
+            seed: None,
+            expires_at: None,
+            agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
+        };
+
+        assert!(!review.is_expired(now));
+
+        review.expires_at = Some(now + chrono::Duration::hours(1));
+        assert!(!review.is_expired(now));
+        assert!(review.is_expired(now + chrono::Duration::hours(2)));
+    }
+
+    #[test]
+    fn test_review_state_stack_context() {
+        let mut review = ReviewState {
+            pr_number: 124,
+            branch: "feature/child".to_string(),
+            base_branch: Some("feature/parent".to_string()),
+            patch: None,
+            parent_pr: None,
+            worktree_path: PathBuf::from("/tmp/test"),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
+            created_at: Utc::now(),
+            port: None,
+            project_type: None,
+            deps_installed: false,
+            env_copied: false,
+            tests: None,
+            seed: None,
+            expires_at: None,
+            agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
+        };
+
+        assert!(review.stack_context().is_none());
+
+        review.parent_pr = Some(123);
+        let context = review.stack_context().unwrap();
+        assert!(context.contains("PR #123"));
+        assert!(context.contains("git diff origin/feature/parent...HEAD"));
+        assert!(review.base_context().is_none());
+    }
+
+    #[test]
+    fn test_review_state_base_context() {
+        let mut review = ReviewState {
+            pr_number: 123,
+            branch: "feature/login".to_string(),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
+            worktree_path: PathBuf::from("/tmp/test"),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
+            created_at: Utc::now(),
+            port: None,
+            project_type: None,
+            deps_installed: false,
+            env_copied: false,
+            tests: None,
+            seed: None,
+            expires_at: None,
+            agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
+        };
+
+        assert!(review.base_context().is_none());
+
+        review.base_branch = Some("develop".to_string());
+        let context = review.base_context().unwrap();
+        assert!(context.contains("merged into `develop`"));
+        assert!(context.contains("git diff origin/develop...HEAD"));
+    }
+
+    #[test]
+    fn test_review_state_is_pull_request() {
+        let mut review = ReviewState {
+            pr_number: 123,
+            branch: "feature/login".to_string(),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
+            worktree_path: PathBuf::from("/tmp/test"),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
+            created_at: Utc::now(),
+            port: None,
+            project_type: None,
+            deps_installed: false,
+            env_copied: false,
+            tests: None,
+            seed: None,
+            expires_at: None,
+            agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
+        };
+        assert!(review.is_pull_request());
+
+        // Pseudo-PR number of a branch review
+        review.pr_number = 94321;
+        assert!(!review.is_pull_request());
+
+        review.pr_info = Some(PrInfo {
+            title: "Fix login".to_string(),
+            author: "octocat".to_string(),
+            draft: false,
+            labels: Vec::new(),
+        });
+        assert!(review.is_pull_request());
+
+        review.pr_number = 123;
+        review.pr_info = None;
+        review.patch = Some(PathBuf::from("/tmp/fix.patch"));
+        assert!(!review.is_pull_request());
+    }
+
     #[test]
     fn test_state_add_review() {
         let mut state = State::default();
@@ -194,13 +781,26 @@ mod tests {
         let review = ReviewState {
             pr_number: 123,
             branch: "feature/test".to_string(),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
             worktree_path: PathBuf::from("/tmp/test"),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
             created_at: Utc::now(),
             port: Some(3000),
             project_type: Some("node".to_string()),
             deps_installed: true,
             env_copied: true,
+            tests: None,
+            seed: None,
+            expires_at: None,
             agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
         };
 
         state.reviews.push(review);
@@ -215,25 +815,51 @@ mod tests {
         let review1 = ReviewState {
             pr_number: 123,
             branch: "feature/test1".to_string(),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
             worktree_path: PathBuf::from("/tmp/test1"),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
             created_at: Utc::now(),
             port: Some(3000),
             project_type: Some("node".to_string()),
             deps_installed: true,
             env_copied: true,
+            tests: None,
+            seed: None,
+            expires_at: None,
             agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
         };
 
         let review2 = ReviewState {
             pr_number: 456,
             branch: "feature/test2".to_string(),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
             worktree_path: PathBuf::from("/tmp/test2"),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
             created_at: Utc::now(),
             port: Some(3001),
             project_type: Some("rust".to_string()),
             deps_installed: false,
             env_copied: false,
+            tests: None,
+            seed: None,
+            expires_at: None,
             agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
         };
 
         state.reviews.push(review1);
@@ -247,6 +873,51 @@ mod tests {
         assert!(not_found.is_none());
     }
 
+    #[test]
+    fn test_state_reviews_are_keyed_by_repository() {
+        let in_repo = |repo: &Path| ReviewState {
+            repo: Some(repo.to_path_buf()),
+            ..ReviewState::for_test(7, repo.join("pr-7"))
+        };
+        // Unit tests run inside the crate's own repository
+        let here = GitOps::open().unwrap().repo_root();
+        let elsewhere = PathBuf::from("/repos/other");
+        let state = State {
+            version: 0,
+            reviews: vec![in_repo(&elsewhere), in_repo(&here)],
+        };
+
+        assert_eq!(state.get_review(7).unwrap().repo.as_deref(), Some(here.as_path()));
+        let key = ReviewKey {
+            repo: Some(elsewhere.clone()),
+            pr_number: 7,
+        };
+        assert_eq!(state.find_review(&key).unwrap().repo, Some(elsewhere));
+
+        // Reviews recorded without a repository match any
+        let legacy = ReviewKey { repo: None, pr_number: 7 };
+        assert!(legacy.matches(&key) && key.matches(&legacy));
+        assert!(!key.matches(&state.reviews[1].key()));
+    }
+
+    #[test]
+    fn test_review_key_dir_name_separates_repositories() {
+        let key = |repo: Option<&str>| ReviewKey {
+            repo: repo.map(PathBuf::from),
+            pr_number: 7,
+        };
+        let a = key(Some("/repos/a"));
+        let b = key(Some("/repos/b"));
+
+        assert_ne!(a.dir_name(), b.dir_name());
+        assert_ne!(a.file_stem(), b.file_stem());
+        assert!(a.dir_name().ends_with("pr-7"));
+        assert!(a.file_stem().ends_with("-pr-7"));
+        assert_eq!(a.dir_name(), key(Some("/repos/a")).dir_name());
+        assert_eq!(key(None).dir_name(), PathBuf::from("pr-7"));
+        assert_eq!(key(None).file_stem(), "pr-7");
+    }
+
     #[test]
     fn test_state_remove_review() {
         let mut state = State::default();
@@ -254,13 +925,26 @@ mod tests {
         let review = ReviewState {
             pr_number: 123,
             branch: "feature/test".to_string(),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
             worktree_path: PathBuf::from("/tmp/test"),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
             created_at: Utc::now(),
             port: None,
             project_type: None,
             deps_installed: false,
             env_copied: false,
+            tests: None,
+            seed: None,
+            expires_at: None,
             agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
         };
 
         state.reviews.push(review);
@@ -283,13 +967,26 @@ mod tests {
         let review = ReviewState {
             pr_number: 123,
             branch: "feature/test".to_string(),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
             worktree_path: PathBuf::from("/tmp/test"),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
             created_at: Utc::now(),
             port: Some(3000),
             project_type: Some("node".to_string()),
             deps_installed: true,
             env_copied: true,
+            tests: None,
+            seed: None,
+            expires_at: None,
             agent_analyses: vec![analysis],
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
         };
 
         assert_eq!(review.agent_analyses.len(), 1);
@@ -302,13 +999,26 @@ mod tests {
         let review = ReviewState {
             pr_number: 123,
             branch: "feature/test".to_string(),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
             worktree_path: PathBuf::from("/tmp/test"),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
             created_at: Utc::now(),
             port: Some(3000),
             project_type: Some("node".to_string()),
             deps_installed: true,
             env_copied: true,
+            tests: None,
+            seed: None,
+            expires_at: None,
             agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
         };
 
         let state = State {
@@ -374,13 +1084,26 @@ reviews:
         let review = ReviewState {
             pr_number: 123,
             branch: "feature/test".to_string(),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
             worktree_path: PathBuf::from("/tmp/test"),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
             created_at: Utc::now(),
             port: Some(3000),
             project_type: Some("node".to_string()),
             deps_installed: true,
             env_copied: true,
+            tests: None,
+            seed: None,
+            expires_at: None,
             agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
         };
 
         let state = State {
@@ -406,13 +1129,26 @@ reviews:
         let review = ReviewState {
             pr_number: 123,
             branch: "feature/test".to_string(),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
             worktree_path: PathBuf::from("/tmp/test"),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
             created_at: Utc::now(),
             port: Some(3000),
             project_type: Some("node".to_string()),
             deps_installed: true,
             env_copied: true,
+            tests: None,
+            seed: None,
+            expires_at: None,
             agent_analyses: vec![analysis],
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
         };
 
         let state = State {
@@ -430,44 +1166,39 @@ reviews:
         use tempfile::TempDir;
 
         let temp_dir = TempDir::new().unwrap();
-        std::env::set_var("HOME", temp_dir.path());
+        let path = temp_dir.path().join("state.yaml");
 
         let mut state = State::default();
         assert_eq!(state.version, 0);
 
         // First save
-        state.save().unwrap();
+        state.save_to(&path).unwrap();
         assert_eq!(state.version, 1);
 
         // Second save
-        state.save().unwrap();
+        state.save_to(&path).unwrap();
         assert_eq!(state.version, 2);
 
         // Load and verify version
-        let loaded = State::load().unwrap();
+        let loaded = State::load_from(&path).unwrap();
         assert_eq!(loaded.version, 2);
     }
 
     #[test]
-    #[ignore] // FIXME: This test is flaky and fails intermittently in CI.
     fn test_concurrent_modification_detection() {
         use tempfile::TempDir;
 
         let temp_dir = TempDir::new().unwrap();
-        std::env::set_var("HOME", temp_dir.path());
-
-        // Ensure .chaba directory exists
-        let chaba_dir = temp_dir.path().join(".chaba");
-        std::fs::create_dir_all(&chaba_dir).unwrap();
+        let path = temp_dir.path().join("state.yaml");
 
         // Create initial state
         let mut state1 = State::default();
-        state1.save().unwrap();
+        state1.save_to(&path).unwrap();
         assert_eq!(state1.version, 1);
 
         // Simulate two processes loading the same state
-        let mut state2 = State::load().unwrap();
-        let mut state3 = State::load().unwrap();
+        let mut state2 = State::load_from(&path).unwrap();
+        let mut state3 = State::load_from(&path).unwrap();
         assert_eq!(state2.version, 1);
         assert_eq!(state3.version, 1);
 
@@ -475,35 +1206,61 @@ reviews:
         state2.reviews.push(ReviewState {
             pr_number: 123,
             branch: "feature/test".to_string(),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
             worktree_path: PathBuf::from("/tmp/test"),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
             created_at: Utc::now(),
             port: Some(3000),
             project_type: None,
             deps_installed: false,
             env_copied: false,
+            tests: None,
+            seed: None,
+            expires_at: None,
             agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
         });
-        state2.save().unwrap();
+        state2.save_to(&path).unwrap();
         assert_eq!(state2.version, 2);
 
         // Process 3 tries to save - should fail due to conflict
         state3.reviews.push(ReviewState {
             pr_number: 456,
             branch: "feature/other".to_string(),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
             worktree_path: PathBuf::from("/tmp/other"),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
             created_at: Utc::now(),
             port: Some(3001),
             project_type: None,
             deps_installed: false,
             env_copied: false,
+            tests: None,
+            seed: None,
+            expires_at: None,
             agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
         });
 
-        let result = state3.save();
+        let result = state3.save_to(&path);
         assert!(result.is_err());
 
         match result {
-            Err(crate::error::ChabaError::StateConflict { expected, actual }) => {
+            Err(ChabaError::StateConflict { expected, actual }) => {
                 assert_eq!(expected, 1);
                 assert_eq!(actual, 2);
             }
@@ -511,6 +1268,236 @@ reviews:
         }
     }
 
+    #[test]
+    fn test_update_reapplies_change_after_concurrent_save() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("state.yaml");
+        let review = |pr_number: u32| ReviewState {
+            pr_number,
+            branch: format!("feature/{}", pr_number),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
+            worktree_path: PathBuf::from(format!("/tmp/pr-{}", pr_number)),
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
+            created_at: Utc::now(),
+            port: None,
+            project_type: None,
+            deps_installed: false,
+            env_copied: false,
+            tests: None,
+            seed: None,
+            expires_at: None,
+            agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
+        };
+
+        let mut initial = State::default();
+        initial.reviews.push(review(1));
+        initial.save_to(&path).unwrap();
+
+        // Two processes load the same state; one saves first
+        let mut first = State::load_from(&path).unwrap();
+        let mut second = State::load_from(&path).unwrap();
+        first
+            .update(&path, |state| {
+                state.reviews.push(review(2));
+                Ok(())
+            })
+            .unwrap();
+        second
+            .update(&path, |state| {
+                state.reviews.retain(|r| r.pr_number != 1);
+                Ok(())
+            })
+            .unwrap();
+
+        let loaded = State::load_from(&path).unwrap();
+        assert_eq!(loaded.version, 3);
+        assert_eq!(loaded.reviews.iter().map(|r| r.pr_number).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(second.version, 3);
+        assert_eq!(second.reviews.len(), 1);
+    }
+
+    #[test]
+    fn test_concurrent_writers_keep_every_update() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("state.yaml");
+        State::default().save_to(&path).unwrap();
+
+        let writers = 8;
+        let barrier = std::sync::Barrier::new(writers);
+        std::thread::scope(|scope| {
+            for writer in 0..writers as u32 {
+                let (path, barrier) = (&path, &barrier);
+                scope.spawn(move || {
+                    for i in 0..5 {
+                        let review = ReviewState::for_test(writer * 100 + i, format!("/tmp/pr-{}-{}", writer, i));
+                        barrier.wait();
+                        // Retried until this writer wins, however often the others do
+                        let mut state = State::load_from(path).unwrap();
+                        while let Err(e) = state.update(path, |state| state.insert_review(&review)) {
+                            assert!(matches!(e, ChabaError::StateConflict { .. }), "{}", e);
+                            state = State::load_from(path).unwrap();
+                        }
+                    }
+                });
+            }
+        });
+
+        let saved = State::load_from(&path).unwrap();
+        assert_eq!(saved.reviews.len(), writers * 5);
+        assert_eq!(saved.version, 1 + writers as u64 * 5);
+        assert!(temp_dir.path().join("state.lock").exists());
+    }
+
+    #[test]
+    fn test_reviews_are_added_once_and_changed_in_place() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("state.yaml");
+        let mut analyzed = ReviewState::for_test(1, "/tmp/pr-1");
+        analyzed.agent_analyses.push(ReviewAnalysis::new("claude".to_string()));
+        let mut initial = State::default();
+        initial.insert_review(&analyzed).unwrap();
+        initial.save_to(&path).unwrap();
+
+        // Adding the PR again would drop its analyses
+        let again = ReviewState::for_test(1, "/tmp/pr-1");
+        assert!(matches!(initial.insert_review(&again), Err(ChabaError::ReviewExists(1))));
+
+        // A change made against stale state keeps what another process saved
+        let mut stale = State::load_from(&path).unwrap();
+        let mut first = State::load_from(&path).unwrap();
+        first
+            .update(&path, |state| state.change_review(&analyzed.key(), &|r| r.port = Some(3001)))
+            .unwrap();
+        stale
+            .update(&path, |state| state.change_review(&analyzed.key(), &|r| r.analysis_interrupted = true))
+            .unwrap();
+
+        let saved = State::load_from(&path).unwrap();
+        let review = &saved.reviews[0];
+        assert_eq!(saved.reviews.len(), 1);
+        assert_eq!(review.port, Some(3001));
+        assert!(review.analysis_interrupted);
+        assert_eq!(review.agent_analyses.len(), 1);
+
+        let missing = ReviewState::for_test(2, "/tmp/pr-2").key();
+        assert!(matches!(
+            stale.change_review(&missing, &|_| {}),
+            Err(ChabaError::WorktreeNotFound(2))
+        ));
+    }
+
+    #[test]
+    fn test_review_containing() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let reviews = temp_dir.path().join("app-reviews");
+        let review = |pr_number: u32, worktree_path: PathBuf| ReviewState {
+            pr_number,
+            branch: format!("feature/{}", pr_number),
+            base_branch: None,
+            patch: None,
+            parent_pr: None,
+            worktree_path,
+            sparse_paths: None,
+            pr_info: None,
+            repo: None,
+            created_at: Utc::now(),
+            port: None,
+            project_type: None,
+            deps_installed: false,
+            env_copied: false,
+            tests: None,
+            seed: None,
+            expires_at: None,
+            agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
+        };
+        std::fs::create_dir_all(reviews.join("pr-1/src")).unwrap();
+        std::fs::create_dir_all(reviews.join("pr-10")).unwrap();
+        std::fs::create_dir_all(reviews.join("pr-1/nested")).unwrap();
+
+        let mut state = State::default();
+        state.reviews.push(review(1, reviews.join("pr-1")));
+        state.reviews.push(review(10, reviews.join("pr-10")));
+        state.reviews.push(review(2, reviews.join("pr-1/nested")));
+        state.reviews.push(review(3, reviews.join("missing")));
+
+        let pr = |path: PathBuf| state.review_containing(&path).map(|r| r.pr_number);
+        assert_eq!(pr(reviews.join("pr-1")), Some(1));
+        assert_eq!(pr(reviews.join("pr-1/src/../src")), Some(1));
+        assert_eq!(pr(reviews.join("pr-10")), Some(10));
+        assert_eq!(pr(reviews.join("pr-1/nested")), Some(2));
+        assert_eq!(pr(reviews.clone()), None);
+        assert_eq!(pr(reviews.join("missing")), None);
+        assert_eq!(state.resolve_pr(Some(7)).unwrap(), 7);
+    }
+
+    #[test]
+    fn test_resolve_target() {
+        assert_eq!("123".parse(), Ok(ReviewTarget::Pr(123)));
+        assert_eq!("#123".parse(), Ok(ReviewTarget::Pr(123)));
+        assert_eq!("feature/login".parse(), Ok(ReviewTarget::Branch("feature/login".to_string())));
+        assert!("".parse::<ReviewTarget>().is_err());
+
+        let mut state: State = serde_yaml::from_str(
+            r#"
+reviews:
+  - pr_number: 1
+    branch: feature/login
+    worktree_path: /tmp/pr-1
+    created_at: 2024-01-01T00:00:00Z
+  - pr_number: 2
+    branch: fix
+    worktree_path: /tmp/pr-2
+    created_at: 2024-01-01T00:00:00Z
+"#,
+        )
+        .unwrap();
+        let branch = |name: &str| Some(ReviewTarget::Branch(name.to_string()));
+        assert_eq!(state.resolve_target(branch("feature/login")).unwrap(), 1);
+        assert!(state.resolve_target(branch("main")).unwrap_err().to_string().contains("No review"));
+
+        let mut other = state.reviews[1].clone();
+        other.pr_number = 3;
+        state.reviews.push(other);
+        assert!(state.resolve_target(branch("fix")).unwrap_err().to_string().contains("#2, #3"));
+    }
+
+    #[test]
+    fn test_resolve_state_dir() {
+        let home = Some(PathBuf::from("/home/me"));
+        let xdg = Some(PathBuf::from("/home/me/.local/state"));
+        let configured = Path::new("~/chaba-state");
+
+        assert_eq!(
+            resolve_state_dir(Some(PathBuf::from("/ci/state")), Some(configured), xdg.clone(), home.clone()),
+            Some(PathBuf::from("/ci/state"))
+        );
+        assert_eq!(
+            resolve_state_dir(Some(PathBuf::new()), Some(configured), xdg.clone(), home.clone()),
+            Some(PathBuf::from("/home/me/chaba-state"))
+        );
+        assert_eq!(
+            resolve_state_dir(None, None, xdg, home.clone()),
+            Some(PathBuf::from("/home/me/.local/state/chaba"))
+        );
+        assert_eq!(
+            resolve_state_dir(None, None, Some(PathBuf::from("relative")), home.clone()),
+            Some(PathBuf::from("/home/me/.chaba"))
+        );
+        assert_eq!(resolve_state_dir(None, None, None, None), None);
+    }
+
     #[test]
     fn test_version_backward_compatibility() {
         // Old format without version field
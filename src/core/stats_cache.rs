@@ -0,0 +1,258 @@
+//! Short-lived cache of worktree git stats
+//!
+//! Reading stats diffs the whole worktree, so `chaba list` and the TUI reuse
+//! recent results instead of re-scanning every worktree on each run or
+//! redraw. An entry is used while the worktree's HEAD and the modification
+//! time of its index are unchanged, and for at most [`TTL`]: unstaged edits
+//! and fetched upstream commits touch neither, so they show up once the entry
+//! expires. The TUI keeps a cache in memory; `chaba list` keeps one in
+//! `~/.chaba/git-stats.json`.
+
+use chrono::{DateTime, Utc};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tempfile::NamedTempFile;
+
+use crate::core::git::GitStats;
+use crate::core::state;
+use crate::error::{ChabaError, Result};
+
+/// How long cached stats are used
+pub const TTL: Duration = Duration::from_secs(15);
+
+/// Cache file, `~/.chaba/git-stats.json` by default
+pub fn cache_path() -> Result<PathBuf> {
+    Ok(state::state_dir()?.join("git-stats.json"))
+}
+
+/// What cached stats of a worktree stay valid for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    /// Ref HEAD points to, or `HEAD` when detached
+    head_ref: String,
+    head_commit: String,
+    /// `None` before anything is staged
+    index_modified: Option<SystemTime>,
+}
+
+impl Fingerprint {
+    /// Fingerprint of a worktree, `None` if it can't be read or has no commits
+    pub fn of(worktree_path: &Path) -> Option<Self> {
+        let repo = Repository::open(worktree_path).ok()?;
+        let head = repo.head().ok()?;
+        let index_modified = std::fs::metadata(repo.path().join("index"))
+            .and_then(|metadata| metadata.modified())
+            .ok();
+
+        Some(Fingerprint {
+            head_ref: head.name().unwrap_or("HEAD").to_string(),
+            head_commit: head.target()?.to_string(),
+            index_modified,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedStats {
+    fingerprint: Fingerprint,
+    cached_at: DateTime<Utc>,
+    stats: GitStats,
+}
+
+impl CachedStats {
+    fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        // A clock that went backwards expires the entry
+        (now - self.cached_at).to_std().is_ok_and(|age| age < TTL)
+    }
+}
+
+/// Recent git stats by worktree and compared base branch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatsCache {
+    #[serde(default)]
+    entries: HashMap<String, CachedStats>,
+}
+
+/// Stats compared with different base branches are cached separately
+fn key(worktree_path: &Path, base_branch: Option<&str>) -> String {
+    format!("{}\n{}", worktree_path.display(), base_branch.unwrap_or_default())
+}
+
+impl StatsCache {
+    /// Load the cache, empty if it doesn't exist yet or can't be read
+    pub fn load() -> Self {
+        match cache_path() {
+            Ok(path) => Self::load_from(&path),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        let Ok(content) = std::fs::read_to_string(path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::debug!("Ignoring unreadable {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    /// Save the cache without its expired entries
+    pub fn save(&mut self, now: DateTime<Utc>) -> Result<()> {
+        self.save_to(&cache_path()?, now)
+    }
+
+    /// Save to a specific file
+    ///
+    /// Like the state file, the cache is written to a temp file next to it
+    /// and renamed into place, so a `chaba list` running alongside the TUI
+    /// never reads half of it.
+    pub fn save_to(&mut self, path: &Path, now: DateTime<Utc>) -> Result<()> {
+        self.entries.retain(|_, entry| entry.is_fresh(now));
+        let dir = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        std::fs::create_dir_all(dir)?;
+        let json = serde_json::to_string(self).map_err(|e| ChabaError::Other(e.into()))?;
+
+        let mut temp_file = NamedTempFile::new_in(dir)?;
+        temp_file.write_all(json.as_bytes())?;
+        temp_file.persist(path).map_err(|e| e.error)?;
+        Ok(())
+    }
+
+    /// Cached stats of a worktree, if still valid for its current fingerprint
+    pub fn get(
+        &self,
+        worktree_path: &Path,
+        base_branch: Option<&str>,
+        fingerprint: &Fingerprint,
+        now: DateTime<Utc>,
+    ) -> Option<GitStats> {
+        self.entries
+            .get(&key(worktree_path, base_branch))
+            .filter(|entry| entry.fingerprint == *fingerprint && entry.is_fresh(now))
+            .map(|entry| entry.stats.clone())
+    }
+
+    /// Cache stats read while the worktree had `fingerprint`
+    ///
+    /// Take the fingerprint before reading the stats, so changes made
+    /// meanwhile aren't hidden behind them.
+    pub fn insert(
+        &mut self,
+        worktree_path: &Path,
+        base_branch: Option<&str>,
+        fingerprint: Fingerprint,
+        stats: GitStats,
+        now: DateTime<Utc>,
+    ) {
+        let entry = CachedStats { fingerprint, cached_at: now, stats };
+        self.entries.insert(key(worktree_path, base_branch), entry);
+    }
+
+    /// Forget a worktree's stats, e.g. when its files are known to have changed
+    pub fn invalidate(&mut self, worktree_path: &Path) {
+        let prefix = key(worktree_path, None);
+        self.entries.retain(|key, _| !key.starts_with(&prefix));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(dir: &Path, message: &str) {
+        let repo = Repository::open(dir).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_all(["*"], git2::IndexAddOption::DEFAULT, None).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+            .unwrap();
+    }
+
+    fn stats(lines_added: usize) -> GitStats {
+        GitStats { lines_added, ..GitStats::default() }
+    }
+
+    #[test]
+    fn test_fingerprint_follows_head() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        Repository::init(dir).unwrap();
+        assert_eq!(Fingerprint::of(dir), None);
+
+        std::fs::write(dir.join("a.txt"), "a").unwrap();
+        commit(dir, "first");
+        let first = Fingerprint::of(dir).unwrap();
+        assert_eq!(Fingerprint::of(dir), Some(first.clone()));
+
+        std::fs::write(dir.join("a.txt"), "b").unwrap();
+        commit(dir, "second");
+        assert_ne!(Fingerprint::of(dir), Some(first));
+    }
+
+    #[test]
+    fn test_get_checks_fingerprint_and_ttl() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        Repository::init(dir).unwrap();
+        std::fs::write(dir.join("a.txt"), "a").unwrap();
+        commit(dir, "first");
+        let fingerprint = Fingerprint::of(dir).unwrap();
+        let now = Utc::now();
+
+        let mut cache = StatsCache::default();
+        cache.insert(dir, Some("main"), fingerprint.clone(), stats(3), now);
+        assert_eq!(cache.get(dir, Some("main"), &fingerprint, now).unwrap().lines_added, 3);
+        // Another base branch is another entry
+        assert!(cache.get(dir, None, &fingerprint, now).is_none());
+
+        let expired = now + chrono::Duration::from_std(TTL).unwrap();
+        assert!(cache.get(dir, Some("main"), &fingerprint, expired).is_none());
+
+        std::fs::write(dir.join("a.txt"), "b").unwrap();
+        commit(dir, "second");
+        let moved = Fingerprint::of(dir).unwrap();
+        assert!(cache.get(dir, Some("main"), &moved, now).is_none());
+
+        cache.invalidate(dir);
+        assert!(cache.get(dir, Some("main"), &fingerprint, now).is_none());
+    }
+
+    #[test]
+    fn test_save_drops_expired_entries() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("git-stats.json");
+        let fingerprint = Fingerprint {
+            head_ref: "refs/heads/main".to_string(),
+            head_commit: "abc".to_string(),
+            index_modified: None,
+        };
+        let now = Utc::now();
+
+        let mut cache = StatsCache::default();
+        cache.insert(Path::new("/tmp/old"), None, fingerprint.clone(), stats(1), now - chrono::Duration::minutes(5));
+        cache.insert(Path::new("/tmp/new"), None, fingerprint.clone(), stats(2), now);
+        cache.save_to(&path, now).unwrap();
+
+        let loaded = StatsCache::load_from(&path);
+        assert!(loaded.get(Path::new("/tmp/old"), None, &fingerprint, now).is_none());
+        assert_eq!(loaded.get(Path::new("/tmp/new"), None, &fingerprint, now).unwrap().lines_added, 2);
+        // Saved by renaming a temp file over the cache, which leaves nothing else behind
+        let files: Vec<_> = std::fs::read_dir(temp_dir.path()).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        assert_eq!(files, vec!["git-stats.json"]);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(StatsCache::load_from(&path).entries.is_empty());
+    }
+}
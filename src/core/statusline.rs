@@ -0,0 +1,227 @@
+//! One-line review summary for terminal status bars
+//!
+//! `chaba statusline` renders every active review as a colored `#<number>`
+//! segment, followed by the agents running for it, e.g.
+//! `🍵 #123 #124✗ #125 🤖claude+codex`. It is meant to be called
+//! periodically from tmux `status-right` or a zellij plugin, so it only reads
+//! local state and never talks to GitHub.
+
+use chrono::{DateTime, Duration, Utc};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::core::activity::AgentRun;
+use crate::core::stack;
+use crate::core::state::{ReviewKey, ReviewState};
+
+/// Reviews expiring within this window are highlighted
+const EXPIRY_WARNING_MINUTES: i64 = 60;
+
+/// How colors are expressed in the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatuslineFormat {
+    /// ANSI escape codes (zellij, shell prompts)
+    #[default]
+    Ansi,
+    /// tmux `#[fg=...]` style directives
+    Tmux,
+    /// No colors
+    Plain,
+}
+
+impl FromStr for StatuslineFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ansi" => Ok(StatuslineFormat::Ansi),
+            "tmux" => Ok(StatuslineFormat::Tmux),
+            "plain" => Ok(StatuslineFormat::Plain),
+            other => Err(format!(
+                "unknown format '{}' (expected ansi, tmux, or plain)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for StatuslineFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatuslineFormat::Ansi => write!(f, "ansi"),
+            StatuslineFormat::Tmux => write!(f, "tmux"),
+            StatuslineFormat::Plain => write!(f, "plain"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Color {
+    Green,
+    Yellow,
+    Red,
+    Magenta,
+    Default,
+}
+
+impl StatuslineFormat {
+    fn paint(self, color: Color, text: &str) -> String {
+        let (ansi, tmux) = match color {
+            Color::Green => ("32", "green"),
+            Color::Yellow => ("33", "yellow"),
+            Color::Red => ("31", "red"),
+            Color::Magenta => ("35", "magenta"),
+            Color::Default => ("", ""),
+        };
+
+        match self {
+            StatuslineFormat::Ansi if !ansi.is_empty() => format!("\x1b[{}m{}\x1b[0m", ansi, text),
+            // tmux treats `#` as the start of a directive; `##` is a literal `#`
+            StatuslineFormat::Tmux if !tmux.is_empty() => {
+                format!("#[fg={}]{}#[default]", tmux, text.replace('#', "##"))
+            }
+            StatuslineFormat::Tmux => text.replace('#', "##"),
+            _ => text.to_string(),
+        }
+    }
+}
+
+/// Marker and color for a review, most severe condition first
+fn review_marker(review: &ReviewState, conflicted: bool, now: DateTime<Utc>) -> (&'static str, Color) {
+    if !review.worktree_path.exists() {
+        ("?", Color::Red)
+    } else if conflicted {
+        ("⚠", Color::Red)
+    } else if review.tests.as_ref().is_some_and(|tests| !tests.passed) {
+        ("✗", Color::Red)
+    } else if review
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= now + Duration::minutes(EXPIRY_WARNING_MINUTES))
+    {
+        ("⏳", Color::Yellow)
+    } else if review.tests.is_some() {
+        ("", Color::Green)
+    } else {
+        ("", Color::Default)
+    }
+}
+
+/// Render the status line
+///
+/// `conflicted` lists PRs whose worktree has an unfinished merge or rebase.
+/// Agent runs for PRs not (yet) in `reviews` are shown too, since a review
+/// is only recorded once its worktree is ready.
+pub fn render(
+    reviews: &[ReviewState],
+    conflicted: &[u32],
+    runs: &[AgentRun],
+    format: StatuslineFormat,
+    now: DateTime<Utc>,
+) -> String {
+    if reviews.is_empty() && runs.is_empty() {
+        return "🍵 no reviews".to_string();
+    }
+
+    let agents_segment = |key: &ReviewKey| {
+        runs.iter()
+            .find(|run| run.key().matches(key))
+            .map(|run| format.paint(Color::Magenta, &format!("🤖{}", run.agents.join("+"))))
+    };
+
+    let mut segments = vec!["🍵".to_string()];
+    for (_, review) in stack::stack_order(reviews) {
+        let (marker, color) = review_marker(review, conflicted.contains(&review.pr_number), now);
+        segments.push(format.paint(color, &format!("#{}{}", review.pr_number, marker)));
+        segments.extend(agents_segment(&review.key()));
+    }
+
+    for run in runs {
+        if !reviews.iter().any(|review| review.key().matches(&run.key())) {
+            segments.push(format.paint(Color::Default, &format!("#{}", run.pr_number)));
+            segments.extend(agents_segment(&run.key()));
+        }
+    }
+
+    segments.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_suite::TestRunResult;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn review(pr_number: u32, worktree_path: PathBuf) -> ReviewState {
+        ReviewState::for_test(pr_number, worktree_path)
+    }
+
+    fn tests(passed: bool) -> TestRunResult {
+        TestRunResult {
+            command: "cargo test".to_string(),
+            passed,
+            failure_summary: None,
+            duration_secs: 1,
+            ran_at: Utc::now(),
+        }
+    }
+
+    fn run(pr_number: u32, agents: &[&str]) -> AgentRun {
+        AgentRun {
+            pr_number,
+            repo: None,
+            agents: agents.iter().map(|a| a.to_string()).collect(),
+            started_at: Utc::now(),
+            deadline: Utc::now() + Duration::minutes(10),
+        }
+    }
+
+    #[test]
+    fn test_render_plain() {
+        let temp_dir = TempDir::new().unwrap();
+        let now = Utc::now();
+
+        let mut passing = review(1, temp_dir.path().to_path_buf());
+        passing.tests = Some(tests(true));
+        let mut failing = review(2, temp_dir.path().to_path_buf());
+        failing.tests = Some(tests(false));
+        let mut expiring = review(3, temp_dir.path().to_path_buf());
+        expiring.expires_at = Some(now + Duration::minutes(5));
+        let missing = review(4, temp_dir.path().join("gone"));
+        let conflicted = review(5, temp_dir.path().to_path_buf());
+
+        let line = render(
+            &[passing, failing, expiring, missing, conflicted],
+            &[5],
+            &[run(1, &["claude", "codex"]), run(9, &["gemini"])],
+            StatuslineFormat::Plain,
+            now,
+        );
+        assert_eq!(line, "🍵 #1 🤖claude+codex #2✗ #3⏳ #4? #5⚠ #9 🤖gemini");
+    }
+
+    #[test]
+    fn test_render_colors() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut failing = review(2, temp_dir.path().to_path_buf());
+        failing.tests = Some(tests(false));
+        let reviews = [failing];
+
+        let ansi = render(&reviews, &[], &[], StatuslineFormat::Ansi, Utc::now());
+        assert_eq!(ansi, "🍵 \x1b[31m#2✗\x1b[0m");
+
+        let tmux = render(&reviews, &[], &[], StatuslineFormat::Tmux, Utc::now());
+        assert_eq!(tmux, "🍵 #[fg=red]##2✗#[default]");
+    }
+
+    #[test]
+    fn test_render_empty() {
+        assert_eq!(render(&[], &[], &[], StatuslineFormat::Tmux, Utc::now()), "🍵 no reviews");
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("tmux".parse::<StatuslineFormat>().unwrap(), StatuslineFormat::Tmux);
+        assert!("html".parse::<StatuslineFormat>().is_err());
+    }
+}
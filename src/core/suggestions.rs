@@ -0,0 +1,428 @@
+//! Structured code suggestions that can be applied to a worktree
+//!
+//! `chaba apply` reads suggestions from JSON and applies their unified diffs
+//! hunk by hunk, so one stale hunk doesn't block the rest.
+//!
+//! Accepted JSON shapes:
+//! - `{"suggestions": [{"title": "...", "diff": "..."}]}`
+//! - a chaba analysis or report (`{"findings": [...]}`), using each finding's `diff`
+//! - a bare array of suggestion objects
+//!
+//! `patch` is accepted as an alias for `diff`. Entries without a diff are skipped.
+//!
+//! `chaba apply-fix` applies the fix of a single stored finding; see
+//! [`finding_fix`].
+
+use serde_json::Value;
+use std::path::Path;
+
+use crate::core::review_analysis::Finding;
+use crate::error::{ChabaError, Result};
+
+/// A suggested change expressed as a unified diff
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    /// Finding or suggestion identifier, if the source provided one
+    pub id: Option<String>,
+    pub title: Option<String>,
+    pub diff: String,
+}
+
+impl Suggestion {
+    /// Label used when reporting results
+    pub fn label(&self) -> String {
+        match (&self.id, &self.title) {
+            (Some(id), Some(title)) => format!("[{}] {}", id, title),
+            (None, Some(title)) => title.clone(),
+            (Some(id), None) => format!("[{}]", id),
+            (None, None) => "Suggestion".to_string(),
+        }
+    }
+
+    /// Split the diff into independently applicable single-hunk patches
+    pub fn hunks(&self) -> Vec<Hunk> {
+        split_hunks(&self.diff)
+    }
+}
+
+/// One hunk of a diff, with its file header so it can be applied on its own
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunk {
+    /// Path of the file the hunk changes
+    pub file: String,
+    /// The `@@ ... @@` line
+    pub range: String,
+    /// File header plus this hunk, as a standalone patch
+    pub patch: String,
+}
+
+/// Load suggestions from a JSON file
+pub fn load(path: &Path) -> Result<Vec<Suggestion>> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        ChabaError::ConfigError(format!("Failed to read {}: {}", path.display(), e))
+    })?;
+    parse(&content)
+}
+
+/// Parse suggestions from JSON
+pub fn parse(content: &str) -> Result<Vec<Suggestion>> {
+    let value: Value = serde_json::from_str(content)
+        .map_err(|e| ChabaError::ConfigError(format!("Invalid suggestions JSON: {}", e)))?;
+
+    let entries = match &value {
+        Value::Array(entries) => entries,
+        Value::Object(object) => match object.get("suggestions").or_else(|| object.get("findings")) {
+            Some(Value::Array(entries)) => entries,
+            _ => {
+                return Err(ChabaError::ConfigError(
+                    "Expected a \"suggestions\" or \"findings\" array".to_string(),
+                ))
+            }
+        },
+        _ => {
+            return Err(ChabaError::ConfigError(
+                "Expected a JSON object or array of suggestions".to_string(),
+            ))
+        }
+    };
+
+    Ok(entries.iter().filter_map(parse_suggestion).collect())
+}
+
+fn parse_suggestion(value: &Value) -> Option<Suggestion> {
+    let text = |key: &str| value.get(key).and_then(|v| v.as_str()).map(str::to_string);
+
+    let diff = text("diff").or_else(|| text("patch"))?;
+    if diff.trim().is_empty() {
+        return None;
+    }
+
+    Some(Suggestion {
+        id: text("id").filter(|id| !id.is_empty()),
+        title: text("title"),
+        diff,
+    })
+}
+
+/// Split a unified diff into single-hunk patches
+///
+/// Each hunk carries its file's header (`diff --git`, `---`, `+++`, ...) so
+/// `git apply` accepts it on its own.
+pub fn split_hunks(diff: &str) -> Vec<Hunk> {
+    let mut hunks = Vec::new();
+    let mut header: Vec<&str> = Vec::new();
+    let mut file = String::new();
+    let mut current: Option<(String, Vec<&str>)> = None;
+
+    let mut flush = |current: &mut Option<(String, Vec<&str>)>, header: &[&str], file: &str| {
+        if let Some((range, lines)) = current.take() {
+            let mut patch = header.join("\n");
+            patch.push('\n');
+            patch.push_str(&lines.join("\n"));
+            patch.push('\n');
+            hunks.push(Hunk {
+                file: file.to_string(),
+                range,
+                patch,
+            });
+        }
+    };
+
+    let mut lines = diff.lines().peekable();
+    while let Some(line) = lines.next() {
+        // A file section starts at `diff --git`, or at a `---`/`+++` pair
+        // after a hunk when the diff has no git headers
+        let starts_file = line.starts_with("diff --git ")
+            || (current.is_some()
+                && line.starts_with("--- ")
+                && lines.peek().is_some_and(|next| next.starts_with("+++ ")));
+
+        if starts_file {
+            flush(&mut current, &header, &file);
+            header.clear();
+            file.clear();
+        }
+
+        if line.starts_with("@@") {
+            flush(&mut current, &header, &file);
+            current = Some((line.to_string(), vec![line]));
+        } else if let Some((_, hunk_lines)) = current.as_mut() {
+            hunk_lines.push(line);
+        } else {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                if path != "/dev/null" {
+                    file = strip_diff_prefix(path, "b/");
+                }
+            } else if let Some(path) = line.strip_prefix("--- ") {
+                if path != "/dev/null" && file.is_empty() {
+                    file = strip_diff_prefix(path, "a/");
+                }
+            }
+            header.push(line);
+        }
+    }
+    flush(&mut current, &header, &file);
+
+    hunks
+}
+
+/// Unified diff implementing a finding's fix, if it has one
+///
+/// Taken from the finding's `diff`, or from its `suggestion` when that holds
+/// a unified diff or a fenced code block. A code block replaces the line the
+/// finding points at, like a GitHub suggestion, so the finding needs a file
+/// and line; the line is read from `worktree_path`.
+pub fn finding_fix(finding: &Finding, worktree_path: &Path) -> Result<Option<String>> {
+    if let Some(diff) = finding.diff.as_ref().filter(|diff| is_unified_diff(diff)) {
+        return Ok(Some(diff.clone()));
+    }
+
+    let Some(suggestion) = &finding.suggestion else {
+        return Ok(None);
+    };
+    let block = fenced_block(suggestion);
+    if let Some(diff) = [block.as_deref(), Some(suggestion.as_str())]
+        .into_iter()
+        .flatten()
+        .find(|text| is_unified_diff(text))
+    {
+        return Ok(Some(ensure_newline(diff)));
+    }
+
+    let Some(snippet) = block else {
+        return Ok(None);
+    };
+    let (Some(file), Some(line)) = (&finding.file, finding.line) else {
+        return Err(ChabaError::ConfigError(
+            "The suggested snippet has no file and line to replace".to_string(),
+        ));
+    };
+
+    let file = file.trim_start_matches("./");
+    let content = std::fs::read_to_string(worktree_path.join(file)).map_err(|e| {
+        ChabaError::ConfigError(format!("Failed to read {}: {}", file, e))
+    })?;
+    snippet_diff(file, &content, line, &snippet).map(Some)
+}
+
+/// Whether `text` looks like a unified diff
+fn is_unified_diff(text: &str) -> bool {
+    let has = |prefix: &str| text.lines().any(|line| line.starts_with(prefix));
+    has("@@") && (has("--- ") || has("+++ "))
+}
+
+/// Contents of the first fenced code block in `text`
+fn fenced_block(text: &str) -> Option<String> {
+    let mut lines = text.lines().skip_while(|line| !line.trim_start().starts_with("```"));
+    lines.next()?;
+
+    let mut block = String::new();
+    for line in lines {
+        if line.trim_start().starts_with("```") {
+            return Some(block);
+        }
+        block.push_str(line);
+        block.push('\n');
+    }
+    None
+}
+
+fn ensure_newline(text: &str) -> String {
+    let mut text = text.to_string();
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text
+}
+
+/// Lines of unchanged context around a replaced snippet line
+const SNIPPET_CONTEXT: usize = 3;
+
+/// Diff replacing line `line` (1-based) of `content` with `snippet`
+///
+/// Surrounding lines are included as context: `git apply` only accepts
+/// context-free hunks at the start or end of a file.
+fn snippet_diff(file: &str, content: &str, line: u32, snippet: &str) -> Result<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let index = line as usize;
+    let Some(old) = index.checked_sub(1).and_then(|i| lines.get(i)) else {
+        return Err(ChabaError::ConfigError(format!(
+            "{} has no line {} to replace",
+            file, line
+        )));
+    };
+
+    let new: Vec<&str> = snippet.lines().collect();
+    let before = &lines[(index - 1).saturating_sub(SNIPPET_CONTEXT)..index - 1];
+    let after = &lines[index..(index + SNIPPET_CONTEXT).min(lines.len())];
+    let no_newline = index + after.len() == lines.len() && !content.ends_with('\n');
+    const NO_NEWLINE: &str = "\\ No newline at end of file\n";
+
+    let old_start = index - before.len();
+    let new_count = before.len() + new.len() + after.len();
+    // An empty new side starts at the line before the hunk
+    let new_start = if new_count == 0 { old_start - 1 } else { old_start };
+
+    let mut diff = format!(
+        "--- a/{file}\n+++ b/{file}\n@@ -{old_start},{old_count} +{new_start},{new_count} @@\n",
+        old_count = before.len() + 1 + after.len(),
+    );
+    for context in before {
+        diff.push_str(&format!(" {}\n", context));
+    }
+    diff.push_str(&format!("-{}\n", old));
+    if no_newline && after.is_empty() {
+        diff.push_str(NO_NEWLINE);
+    }
+    for added in &new {
+        diff.push_str(&format!("+{}\n", added));
+    }
+    if no_newline && after.is_empty() && !new.is_empty() {
+        diff.push_str(NO_NEWLINE);
+    }
+    for context in after {
+        diff.push_str(&format!(" {}\n", context));
+    }
+    if no_newline && !after.is_empty() {
+        diff.push_str(NO_NEWLINE);
+    }
+
+    Ok(diff)
+}
+
+fn strip_diff_prefix(path: &str, prefix: &str) -> String {
+    let path = path.split('\t').next().unwrap_or(path);
+    path.strip_prefix(prefix).unwrap_or(path).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWO_FILE_DIFF: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+ fn a() {}
+-fn b() {}
++fn b() { todo!() }
+ fn c() {}
+@@ -10,2 +10,3 @@
+ fn x() {}
++fn y() {}
+ fn z() {}
+diff --git a/README.md b/README.md
+--- a/README.md
++++ b/README.md
+@@ -1 +1 @@
+-old
++new
+";
+
+    #[test]
+    fn test_split_hunks() {
+        let hunks = split_hunks(TWO_FILE_DIFF);
+
+        assert_eq!(hunks.len(), 3);
+        assert_eq!(hunks[0].file, "src/lib.rs");
+        assert_eq!(hunks[0].range, "@@ -1,3 +1,3 @@");
+        assert_eq!(hunks[1].range, "@@ -10,2 +10,3 @@");
+        assert_eq!(hunks[2].file, "README.md");
+
+        // Every hunk is a standalone patch with its file header
+        assert!(hunks[1].patch.starts_with("diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -10,2"));
+        assert!(!hunks[1].patch.contains("todo!"));
+        assert!(hunks[2].patch.ends_with("+new\n"));
+    }
+
+    #[test]
+    fn test_split_hunks_without_git_header() {
+        let diff = "--- /dev/null\n+++ b/new.txt\n@@ -0,0 +1 @@\n+hello\n";
+        let hunks = split_hunks(diff);
+
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].file, "new.txt");
+        assert_eq!(hunks[0].patch, diff);
+    }
+
+    #[test]
+    fn test_parse_suggestions_object() {
+        let json = r#"{"suggestions": [
+            {"title": "Fix b", "diff": "--- a/x\n+++ b/x\n@@ -1 +1 @@\n-a\n+b\n"},
+            {"title": "No diff"}
+        ]}"#;
+
+        let suggestions = parse(json).unwrap();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].label(), "Fix b");
+    }
+
+    #[test]
+    fn test_parse_findings_and_patch_alias() {
+        let json = r#"{"agent": "claude", "findings": [
+            {"id": "abc123", "title": "Use ?", "patch": "--- a/x\n+++ b/x\n@@ -1 +1 @@\n-a\n+b\n"}
+        ]}"#;
+
+        let suggestions = parse(json).unwrap();
+        assert_eq!(suggestions[0].id.as_deref(), Some("abc123"));
+        assert_eq!(suggestions[0].label(), "[abc123] Use ?");
+    }
+
+    fn finding(suggestion: &str) -> Finding {
+        use crate::core::review_analysis::{Category, Severity};
+
+        Finding::new(Severity::Medium, Category::CodeQuality, "Use ?".to_string(), String::new())
+            .with_file("src/lib.rs".to_string())
+            .with_line(2)
+            .with_suggestion(suggestion.to_string())
+    }
+
+    #[test]
+    fn test_finding_fix_from_snippet() {
+        let worktree = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(worktree.path().join("src")).unwrap();
+        std::fs::write(worktree.path().join("src/lib.rs"), "fn a() {\n    x.unwrap()\n}\n").unwrap();
+
+        let fix = finding_fix(&finding("Propagate the error:\n```rust\n    x?\n```"), worktree.path())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            fix,
+            "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,3 +1,3 @@\n fn a() {\n-    x.unwrap()\n+    x?\n }\n"
+        );
+    }
+
+    #[test]
+    fn test_finding_fix_from_diff() {
+        let worktree = tempfile::tempdir().unwrap();
+        let diff = "--- a/x\n+++ b/x\n@@ -1 +1 @@\n-a\n+b";
+
+        let fix = finding_fix(&finding(&format!("```diff\n{}\n```", diff)), worktree.path()).unwrap();
+        assert_eq!(fix.as_deref(), Some("--- a/x\n+++ b/x\n@@ -1 +1 @@\n-a\n+b\n"));
+
+        let mut with_diff = finding("prose only");
+        with_diff.diff = Some(TWO_FILE_DIFF.to_string());
+        assert_eq!(finding_fix(&with_diff, worktree.path()).unwrap().as_deref(), Some(TWO_FILE_DIFF));
+
+        assert_eq!(finding_fix(&finding("Use the ? operator"), worktree.path()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_snippet_diff_without_trailing_newline() {
+        let diff = snippet_diff("a.txt", "one\ntwo", 2, "2\n").unwrap();
+        assert_eq!(
+            diff,
+            "--- a/a.txt\n+++ b/a.txt\n@@ -1,2 +1,2 @@\n one\n-two\n\\ No newline at end of file\n+2\n\\ No newline at end of file\n"
+        );
+        assert!(snippet_diff("a.txt", "one\n", 5, "x\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse("not json").is_err());
+        assert!(parse(r#"{"other": []}"#).is_err());
+        assert!(parse("42").is_err());
+    }
+}
@@ -0,0 +1,218 @@
+//! Project test suite execution during review setup
+//!
+//! When `sandbox.run_tests` is enabled, the project's tests are run once the
+//! worktree is ready. The outcome is stored in the review state so reviewers
+//! and agents know up front whether the branch is green.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::core::logs::ReviewLogs;
+use crate::core::platform::shell_command;
+use crate::core::project::ProjectType;
+use crate::error::Result;
+
+/// Maximum number of lines kept in a failure summary
+const SUMMARY_MAX_LINES: usize = 20;
+
+/// Markers that identify failure lines across common test runners
+const FAILURE_MARKERS: &[&str] = &[
+    "FAILED",
+    "FAIL ",
+    "--- FAIL",
+    "failed",
+    "panicked at",
+    "AssertionError",
+    "Error:",
+    "✕",
+];
+
+/// Outcome of running the project's test suite
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestRunResult {
+    /// Command that was run
+    pub command: String,
+    /// Whether the test suite passed
+    pub passed: bool,
+    /// Relevant output lines when the suite failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_summary: Option<String>,
+    /// How long the run took
+    pub duration_secs: u64,
+    /// When the run finished
+    pub ran_at: DateTime<Utc>,
+}
+
+impl TestRunResult {
+    /// Short label for list/status output
+    pub fn status_label(&self) -> &'static str {
+        if self.passed {
+            "✓ Passed"
+        } else {
+            "✗ Failed"
+        }
+    }
+
+    /// Description of the result for agent prompts
+    pub fn prompt_context(&self) -> String {
+        if self.passed {
+            format!("The test suite (`{}`) passes on this branch.", self.command)
+        } else {
+            let mut context = format!("The test suite (`{}`) fails on this branch.", self.command);
+            if let Some(summary) = &self.failure_summary {
+                context.push_str("\nFailure summary:\n");
+                context.push_str(summary);
+            }
+            context
+        }
+    }
+}
+
+/// Default test command for a project type
+pub fn default_test_command(project_type: &ProjectType) -> Option<String> {
+    match project_type {
+        ProjectType::NodeJs { package_manager } => Some(format!("{} test", package_manager.as_str())),
+        ProjectType::Rust => Some("cargo test".to_string()),
+        ProjectType::Python { .. } => Some("pytest".to_string()),
+        ProjectType::Go => Some("go test ./...".to_string()),
+        ProjectType::Unknown => None,
+    }
+}
+
+/// Run `command` through the shell in `worktree_path`
+///
+/// A timeout counts as a failure rather than an error. Output is appended to
+/// the review's test log when `logs` is given.
+pub async fn run_tests(
+    worktree_path: &Path,
+    command: &str,
+    timeout_secs: u64,
+    logs: Option<&ReviewLogs>,
+) -> Result<TestRunResult> {
+    tracing::info!("Running tests: {}", command);
+
+    let started = Instant::now();
+    let child = shell_command(command)
+        .current_dir(worktree_path)
+        .kill_on_drop(true)
+        .output();
+
+    let result = tokio::time::timeout(Duration::from_secs(timeout_secs), child).await;
+    let duration_secs = started.elapsed().as_secs();
+
+    let (passed, failure_summary) = match result {
+        Ok(output) => {
+            let output = output?;
+
+            if let Some(logs) = logs {
+                if let Err(e) = logs.append_output(&logs.tests_log(), command, &output).await {
+                    tracing::warn!("Failed to write test log: {}", e);
+                }
+            }
+
+            if output.status.success() {
+                (true, None)
+            } else {
+                let combined = format!(
+                    "{}\n{}",
+                    String::from_utf8_lossy(&output.stdout),
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                (false, Some(summarize_failures(&combined)))
+            }
+        }
+        Err(_) => (false, Some(format!("Timed out after {} seconds", timeout_secs))),
+    };
+
+    Ok(TestRunResult {
+        command: command.to_string(),
+        passed,
+        failure_summary,
+        duration_secs,
+        ran_at: Utc::now(),
+    })
+}
+
+/// Extract the lines that explain a failure from test output
+///
+/// Falls back to the last lines of output when no known failure marker matches.
+pub fn summarize_failures(output: &str) -> String {
+    let lines: Vec<&str> = output
+        .lines()
+        .map(|l| l.trim_end())
+        .filter(|l| !l.trim().is_empty())
+        .collect();
+
+    let failures: Vec<&str> = lines
+        .iter()
+        .copied()
+        .filter(|l| FAILURE_MARKERS.iter().any(|m| l.contains(m)))
+        .take(SUMMARY_MAX_LINES)
+        .collect();
+
+    if !failures.is_empty() {
+        return failures.join("\n");
+    }
+
+    let start = lines.len().saturating_sub(SUMMARY_MAX_LINES);
+    lines[start..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::project::NodePackageManager;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_default_test_command() {
+        let node = ProjectType::NodeJs { package_manager: NodePackageManager::Pnpm };
+        assert_eq!(default_test_command(&node), Some("pnpm test".to_string()));
+        assert_eq!(default_test_command(&ProjectType::Rust), Some("cargo test".to_string()));
+        assert_eq!(default_test_command(&ProjectType::Unknown), None);
+    }
+
+    #[test]
+    fn test_summarize_failures_picks_failure_lines() {
+        let output = "running 3 tests\ntest a ... ok\ntest b ... FAILED\n\nthread 'b' panicked at src/lib.rs:3\n";
+        let summary = summarize_failures(output);
+        assert_eq!(summary, "test b ... FAILED\nthread 'b' panicked at src/lib.rs:3");
+    }
+
+    #[test]
+    fn test_summarize_failures_falls_back_to_tail() {
+        let output: String = (0..30).map(|i| format!("line {}\n", i)).collect();
+        let summary = summarize_failures(&output);
+        assert_eq!(summary.lines().count(), SUMMARY_MAX_LINES);
+        assert!(summary.ends_with("line 29"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_tests_records_result() {
+        let temp_dir = TempDir::new().unwrap();
+        let logs = ReviewLogs::at(temp_dir.path().join("logs"));
+
+        let passed = run_tests(temp_dir.path(), "true", 10, Some(&logs)).await.unwrap();
+        assert!(passed.passed);
+        assert!(passed.failure_summary.is_none());
+
+        let failed = run_tests(temp_dir.path(), "echo 'test x FAILED'; exit 1", 10, Some(&logs))
+            .await
+            .unwrap();
+        assert!(!failed.passed);
+        assert_eq!(failed.failure_summary.as_deref(), Some("test x FAILED"));
+        assert!(logs.tests_log().exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_tests_timeout_is_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let result = run_tests(temp_dir.path(), "sleep 5", 1, None).await.unwrap();
+        assert!(!result.passed);
+        assert!(result.failure_summary.unwrap().contains("Timed out"));
+    }
+}
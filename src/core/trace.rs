@@ -0,0 +1,374 @@
+//! Command traces for debugging slow or stuck runs
+//!
+//! With `--trace-commands`, every external command chaba runs (git, gh,
+//! agents, dependency installs) is written to
+//! `~/.chaba/traces/<run-id>.jsonl` as it starts and again when it finishes,
+//! with its arguments, working directory, duration, and exit code. A command
+//! that started but never finished is what a hung run was waiting on.
+//! `chaba trace show <run-id>` prints a trace.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+
+use crate::core::command::{CommandEnv, CommandRunner, LineSink};
+use crate::core::state;
+use crate::error::{ChabaError, Result};
+
+/// One line of a trace file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum TraceEvent {
+    /// The chaba invocation being traced
+    Run {
+        args: Vec<String>,
+        started_at: DateTime<Utc>,
+        pid: u32,
+    },
+    /// A command was started
+    Start {
+        id: u64,
+        program: String,
+        args: Vec<String>,
+        cwd: PathBuf,
+        started_at: DateTime<Utc>,
+    },
+    /// A started command finished, or failed to run
+    Finish {
+        id: u64,
+        duration_ms: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        exit_code: Option<i32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+}
+
+/// A command in a trace, with its outcome if it finished
+#[derive(Debug, Clone, PartialEq)]
+pub struct TracedCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub cwd: PathBuf,
+    pub started_at: DateTime<Utc>,
+    /// `None` if the command never finished
+    pub duration_ms: Option<u64>,
+    pub exit_code: Option<i32>,
+    pub error: Option<String>,
+}
+
+impl TracedCommand {
+    /// Command line as a single string
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.program.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// A parsed trace file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunTrace {
+    /// Arguments of the traced chaba invocation
+    pub args: Vec<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    /// Commands in the order they started
+    pub commands: Vec<TracedCommand>,
+}
+
+impl RunTrace {
+    /// Combine start and finish events into commands
+    ///
+    /// Unparseable lines (e.g. one cut short by a crash) are skipped.
+    pub fn parse(content: &str) -> Self {
+        let mut trace = RunTrace::default();
+        let mut ids = Vec::new();
+
+        for event in content.lines().filter_map(|line| serde_json::from_str::<TraceEvent>(line).ok()) {
+            match event {
+                TraceEvent::Run { args, started_at, .. } => {
+                    trace.args = args;
+                    trace.started_at = Some(started_at);
+                }
+                TraceEvent::Start { id, program, args, cwd, started_at } => {
+                    ids.push(id);
+                    trace.commands.push(TracedCommand {
+                        program,
+                        args,
+                        cwd,
+                        started_at,
+                        duration_ms: None,
+                        exit_code: None,
+                        error: None,
+                    });
+                }
+                TraceEvent::Finish { id, duration_ms, exit_code, error } => {
+                    if let Some(index) = ids.iter().position(|started| *started == id) {
+                        let command = &mut trace.commands[index];
+                        command.duration_ms = Some(duration_ms);
+                        command.exit_code = exit_code;
+                        command.error = error;
+                    }
+                }
+            }
+        }
+
+        trace
+    }
+}
+
+/// The trace file of the current run
+pub struct Trace {
+    run_id: String,
+    path: PathBuf,
+    file: Mutex<File>,
+    next_id: AtomicU64,
+}
+
+static TRACE: OnceLock<Trace> = OnceLock::new();
+
+/// Start tracing commands for the rest of the process
+///
+/// Returns the trace, which is created on the first call.
+pub fn enable(args: Vec<String>) -> Result<&'static Trace> {
+    if let Some(trace) = TRACE.get() {
+        return Ok(trace);
+    }
+
+    let started_at = Utc::now();
+    let pid = std::process::id();
+    let run_id = format!("{}-{}", started_at.format("%Y%m%d-%H%M%S"), pid);
+    let dir = traces_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.jsonl", run_id));
+
+    let trace = Trace {
+        run_id,
+        file: Mutex::new(File::create(&path)?),
+        path,
+        next_id: AtomicU64::new(1),
+    };
+    trace.write(&TraceEvent::Run { args, started_at, pid });
+
+    Ok(TRACE.get_or_init(|| trace))
+}
+
+/// The current run's trace, if tracing is on
+pub fn current() -> Option<&'static Trace> {
+    TRACE.get()
+}
+
+impl Trace {
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record that a command is starting; pass the returned span to [`Trace::finish`]
+    pub fn start(&self, program: &str, args: &[&OsStr], cwd: &Path) -> Span {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.write(&TraceEvent::Start {
+            id,
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string_lossy().into_owned()).collect(),
+            cwd: cwd.to_path_buf(),
+            started_at: Utc::now(),
+        });
+        Span {
+            id,
+            started: Instant::now(),
+        }
+    }
+
+    /// Record how a command ended
+    pub fn finish(&self, span: Span, result: &io::Result<Output>) {
+        let (exit_code, error) = match result {
+            Ok(output) => (output.status.code(), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        self.write(&TraceEvent::Finish {
+            id: span.id,
+            duration_ms: span.started.elapsed().as_millis() as u64,
+            exit_code,
+            error,
+        });
+    }
+
+    fn write(&self, event: &TraceEvent) {
+        let Ok(mut line) = serde_json::to_string(event) else {
+            return;
+        };
+        line.push('\n');
+
+        // Written straight through so the trace survives a hang or crash
+        if let Err(e) = self.file.lock().unwrap().write_all(line.as_bytes()) {
+            tracing::debug!("Failed to write command trace: {}", e);
+        }
+    }
+}
+
+/// A command in progress
+pub struct Span {
+    id: u64,
+    started: Instant,
+}
+
+/// Trace a command run outside a [`CommandRunner`]
+pub async fn traced<F>(program: &str, args: &[&OsStr], cwd: &Path, run: F) -> io::Result<Output>
+where
+    F: std::future::Future<Output = io::Result<Output>>,
+{
+    let Some(trace) = current() else {
+        return run.await;
+    };
+
+    let span = trace.start(program, args, cwd);
+    let result = run.await;
+    trace.finish(span, &result);
+    result
+}
+
+/// Directory holding trace files
+pub fn traces_dir() -> Result<PathBuf> {
+    Ok(state::state_dir()?.join("traces"))
+}
+
+/// Run IDs of saved traces, newest first
+pub fn list() -> Result<Vec<String>> {
+    let dir = traces_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut run_ids: Vec<String> = std::fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            name.strip_suffix(".jsonl").map(str::to_string)
+        })
+        .collect();
+    // Run IDs start with a sortable timestamp
+    run_ids.sort_by(|a, b| b.cmp(a));
+    Ok(run_ids)
+}
+
+/// Load the trace of a run
+pub fn load(run_id: &str) -> Result<RunTrace> {
+    let path = traces_dir()?.join(format!("{}.jsonl", run_id));
+    let content = std::fs::read_to_string(&path).map_err(|_| {
+        ChabaError::Other(anyhow::anyhow!("No trace found for run {} ({})", run_id, path.display()))
+    })?;
+    Ok(RunTrace::parse(&content))
+}
+
+/// Runner decorator that traces every command when tracing is on
+pub struct TraceCommandRunner {
+    inner: Arc<dyn CommandRunner + Send + Sync>,
+}
+
+impl TraceCommandRunner {
+    pub fn new(inner: Arc<dyn CommandRunner + Send + Sync>) -> Self {
+        TraceCommandRunner { inner }
+    }
+}
+
+#[async_trait]
+impl CommandRunner for TraceCommandRunner {
+    async fn run(
+        &self,
+        program: &str,
+        args: &[&OsStr],
+        current_dir: &Path,
+    ) -> std::result::Result<Output, io::Error> {
+        self.run_with_env(program, args, current_dir, &CommandEnv::default())
+            .await
+    }
+
+    async fn run_with_env(
+        &self,
+        program: &str,
+        args: &[&OsStr],
+        current_dir: &Path,
+        env: &CommandEnv,
+    ) -> std::result::Result<Output, io::Error> {
+        traced(
+            program,
+            args,
+            current_dir,
+            self.inner.run_with_env(program, args, current_dir, env),
+        )
+        .await
+    }
+
+    async fn run_streaming(
+        &self,
+        program: &str,
+        args: &[&OsStr],
+        current_dir: &Path,
+        env: &CommandEnv,
+        on_line: &LineSink<'_>,
+    ) -> std::result::Result<Output, io::Error> {
+        traced(
+            program,
+            args,
+            current_dir,
+            self.inner.run_streaming(program, args, current_dir, env, on_line),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_trace() {
+        let started_at = "2026-01-02T03:04:05Z";
+        let content = [
+            format!(r#"{{"event":"run","args":["chaba","review","--pr","42"],"started_at":"{}","pid":7}}"#, started_at),
+            format!(r#"{{"event":"start","id":1,"program":"gh","args":["pr","view","42"],"cwd":"/repo","started_at":"{}"}}"#, started_at),
+            format!(r#"{{"event":"start","id":2,"program":"claude","args":["--yes"],"cwd":"/reviews/pr-42","started_at":"{}"}}"#, started_at),
+            r#"{"event":"finish","id":1,"duration_ms":120,"exit_code":0}"#.to_string(),
+            r#"{"event":"fini"#.to_string(),
+        ]
+        .join("\n");
+
+        let trace = RunTrace::parse(&content);
+
+        assert_eq!(trace.args, vec!["chaba", "review", "--pr", "42"]);
+        assert_eq!(trace.commands.len(), 2);
+        assert_eq!(trace.commands[0].command_line(), "gh pr view 42");
+        assert_eq!(trace.commands[0].duration_ms, Some(120));
+        assert_eq!(trace.commands[0].exit_code, Some(0));
+        assert_eq!(trace.commands[1].program, "claude");
+        assert_eq!(trace.commands[1].duration_ms, None);
+    }
+
+    #[test]
+    fn test_finish_event_omits_missing_fields() {
+        let event = TraceEvent::Finish {
+            id: 3,
+            duration_ms: 5,
+            exit_code: None,
+            error: Some("not found".to_string()),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"finish","id":3,"duration_ms":5,"error":"not found"}"#
+        );
+    }
+}
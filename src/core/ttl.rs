@@ -0,0 +1,105 @@
+//! Per-review lifetimes
+//!
+//! A review can be created with `--ttl 48h`; once its expiry time passes it is
+//! picked up by `chaba cleanup --expired` and `chaba watch`.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::error::{ChabaError, Result};
+
+/// Parse a TTL such as `30m`, `48h`, `7d`, or `2w`
+///
+/// A bare number is interpreted as hours. TTLs too long to add to the
+/// current time are invalid.
+pub fn parse_ttl(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+
+    let invalid = || {
+        ChabaError::ConfigError(format!(
+            "Invalid TTL '{}'. Use a number followed by m, h, d, or w (e.g. 48h)",
+            input
+        ))
+    };
+
+    let value: i64 = number.parse().map_err(|_| invalid())?;
+    if value <= 0 {
+        return Err(invalid());
+    }
+
+    let ttl = match unit {
+        "m" => Duration::try_minutes(value),
+        "" | "h" => Duration::try_hours(value),
+        "d" => Duration::try_days(value),
+        "w" => Duration::try_weeks(value),
+        _ => None,
+    };
+    ttl.filter(|ttl| Utc::now().checked_add_signed(*ttl).is_some())
+        .ok_or_else(invalid)
+}
+
+/// When a review created at `created_at` with `ttl` expires
+pub fn expiry(created_at: DateTime<Utc>, ttl: Duration) -> Result<DateTime<Utc>> {
+    created_at
+        .checked_add_signed(ttl)
+        .ok_or_else(|| ChabaError::ConfigError(format!("TTL of {} days is too long", ttl.num_days())))
+}
+
+/// Human-readable time remaining until `expires_at`, e.g. `1d 4h` or `expired`
+pub fn format_remaining(expires_at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let remaining = expires_at.signed_duration_since(now);
+
+    if remaining <= Duration::zero() {
+        return "expired".to_string();
+    }
+
+    let days = remaining.num_days();
+    let hours = remaining.num_hours() % 24;
+    let minutes = remaining.num_minutes() % 60;
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes.max(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ttl_units() {
+        assert_eq!(parse_ttl("30m").unwrap(), Duration::minutes(30));
+        assert_eq!(parse_ttl("48h").unwrap(), Duration::hours(48));
+        assert_eq!(parse_ttl("12").unwrap(), Duration::hours(12));
+        assert_eq!(parse_ttl("7d").unwrap(), Duration::days(7));
+        assert_eq!(parse_ttl("2w").unwrap(), Duration::weeks(2));
+    }
+
+    #[test]
+    fn test_parse_ttl_invalid() {
+        assert!(parse_ttl("").is_err());
+        assert!(parse_ttl("0h").is_err());
+        assert!(parse_ttl("h").is_err());
+        assert!(parse_ttl("5y").is_err());
+        assert!(parse_ttl("-3h").is_err());
+        assert!(parse_ttl("99999999999999w").is_err());
+        assert!(parse_ttl("9999999999999999999m").is_err());
+        assert!(parse_ttl("999999999999d").is_err());
+    }
+
+    #[test]
+    fn test_format_remaining() {
+        let now = Utc::now();
+        assert_eq!(format_remaining(now + Duration::hours(28), now), "1d 4h");
+        assert_eq!(format_remaining(now + Duration::minutes(90), now), "1h 30m");
+        assert_eq!(format_remaining(now + Duration::minutes(5), now), "5m");
+        assert_eq!(format_remaining(now - Duration::minutes(5), now), "expired");
+    }
+}
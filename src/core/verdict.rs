@@ -0,0 +1,196 @@
+//! Overall review verdict from agent findings
+//!
+//! `chaba verdict` merges a review's stored agent analyses, checks them
+//! against the thresholds in [`VerdictConfig`], and can submit the result to
+//! GitHub with `gh pr review`.
+
+use std::fmt;
+
+use crate::config::VerdictConfig;
+use crate::core::review_analysis::{AggregatedReport, Severity};
+
+/// Review decision for a PR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Approve,
+    RequestChanges,
+}
+
+impl Verdict {
+    /// `gh pr review` flag that submits this verdict
+    pub fn gh_flag(self) -> &'static str {
+        match self {
+            Verdict::Approve => "--approve",
+            Verdict::RequestChanges => "--request-changes",
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Approve => write!(f, "✅ approve"),
+            Verdict::RequestChanges => write!(f, "❌ request changes"),
+        }
+    }
+}
+
+/// A verdict and the thresholds that produced it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decision {
+    pub verdict: Verdict,
+    /// Why changes are requested; empty when approving
+    pub reasons: Vec<String>,
+}
+
+/// Decide on a verdict for the merged findings of a review
+pub fn decide(report: &AggregatedReport, config: &VerdictConfig) -> Decision {
+    let limits = [
+        (Severity::Critical, "critical", config.max_critical),
+        (Severity::High, "high", config.max_high),
+        (Severity::Medium, "medium", config.max_medium),
+    ];
+
+    let mut reasons: Vec<String> = limits
+        .iter()
+        .filter_map(|(severity, name, max)| {
+            let count = report.count_by_severity(severity);
+            let max = (*max)?;
+            (count > max).then(|| format!("{} {} finding(s) (at most {} allowed)", count, name, max))
+        })
+        .collect();
+
+    if let (Some(min), Some(score)) = (config.min_score, report.score) {
+        if score < min {
+            reasons.push(format!("score {:.1}/5.0 is below {:.1}", score, min));
+        }
+    }
+
+    Decision {
+        verdict: if reasons.is_empty() { Verdict::Approve } else { Verdict::RequestChanges },
+        reasons,
+    }
+}
+
+/// Markdown review body summarizing the decision and the findings behind it
+///
+/// Lists critical, high, and medium findings; low and info findings are
+/// only counted.
+pub fn summary(report: &AggregatedReport, decision: &Decision) -> String {
+    let mut body = String::from("## 🍵 Chaba review\n\n");
+
+    match decision.verdict {
+        Verdict::Approve => body.push_str("No findings exceed the review thresholds.\n"),
+        Verdict::RequestChanges => {
+            body.push_str("Changes requested:\n\n");
+            for reason in &decision.reasons {
+                body.push_str(&format!("- {}\n", reason));
+            }
+        }
+    }
+
+    body.push_str(&format!("\nAgents: {}", report.agents.join(", ")));
+    if let Some(score) = report.score {
+        body.push_str(&format!(" · Score: {:.1}/5.0", score));
+    }
+    body.push('\n');
+
+    let counts: Vec<String> = report
+        .severity_counts()
+        .iter()
+        .map(|(severity, count)| format!("{} {:?}", count, severity).to_lowercase())
+        .collect();
+    if !counts.is_empty() {
+        body.push_str(&format!("Findings: {}\n", counts.join(", ")));
+    }
+
+    let listed: Vec<_> = report
+        .findings
+        .iter()
+        .filter(|f| f.finding.severity >= Severity::Medium)
+        .collect();
+    if !listed.is_empty() {
+        body.push_str("\n### Findings\n\n");
+        for aggregated in listed {
+            let finding = &aggregated.finding;
+            let location = match (&finding.file, finding.line) {
+                (Some(file), Some(line)) => format!(" (`{}:{}`)", file, line),
+                (Some(file), None) => format!(" (`{}`)", file),
+                _ => String::new(),
+            };
+            body.push_str(&format!(
+                "- **{:?}** {}{}\n",
+                finding.severity, finding.title, location
+            ));
+        }
+    }
+
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::review_analysis::{Category, Finding, ReviewAnalysis};
+
+    fn report(severities: &[Severity], score: Option<f32>) -> AggregatedReport {
+        let mut analysis = ReviewAnalysis::new("claude".to_string());
+        for (i, severity) in severities.iter().enumerate() {
+            analysis.add_finding(
+                Finding::new(*severity, Category::Security, format!("Issue {}", i), String::new())
+                    .with_file("src/auth.rs".to_string())
+                    .with_line(i as u32 + 1),
+            );
+        }
+        if let Some(score) = score {
+            analysis.set_score(score);
+        }
+        AggregatedReport::from_analyses(&[analysis])
+    }
+
+    #[test]
+    fn test_decide_default_requests_changes_on_critical() {
+        let config = VerdictConfig::default();
+
+        let clean = decide(&report(&[Severity::High, Severity::Low], None), &config);
+        assert_eq!(clean.verdict, Verdict::Approve);
+        assert!(clean.reasons.is_empty());
+
+        let critical = decide(&report(&[Severity::Critical], None), &config);
+        assert_eq!(critical.verdict, Verdict::RequestChanges);
+        assert_eq!(critical.reasons, vec!["1 critical finding(s) (at most 0 allowed)"]);
+    }
+
+    #[test]
+    fn test_decide_with_limits_and_score() {
+        let config = VerdictConfig {
+            max_critical: None,
+            max_high: Some(1),
+            max_medium: None,
+            min_score: Some(3.0),
+        };
+
+        let decision = decide(&report(&[Severity::Critical, Severity::High], Some(4.0)), &config);
+        assert_eq!(decision.verdict, Verdict::Approve);
+
+        let decision = decide(&report(&[Severity::High, Severity::High], Some(2.5)), &config);
+        assert_eq!(decision.verdict, Verdict::RequestChanges);
+        assert_eq!(
+            decision.reasons,
+            vec!["2 high finding(s) (at most 1 allowed)", "score 2.5/5.0 is below 3.0"]
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        let report = report(&[Severity::Critical, Severity::Low], Some(2.0));
+        let decision = decide(&report, &VerdictConfig::default());
+        let body = summary(&report, &decision);
+
+        assert!(body.contains("- 1 critical finding(s) (at most 0 allowed)"));
+        assert!(body.contains("Agents: claude · Score: 2.0/5.0"));
+        assert!(body.contains("Findings: 1 critical, 1 low"));
+        assert!(body.contains("- **Critical** Issue 0 (`src/auth.rs:1`)"));
+        assert!(!body.contains("Issue 1"));
+    }
+}
@@ -0,0 +1,201 @@
+//! VS Code workspace and debug configuration for a review
+//!
+//! `chaba open --vscode` writes `.chaba/<repo-hash>-pr-<number>.code-workspace`
+//! into the review worktree, titled after the PR and with `PORT` set to the review's
+//! assigned port in integrated terminals. With `--launch` it also writes
+//! `.vscode/launch.json` with debug configurations for the detected project
+//! type that run on that port, so breakpoints work without setup. An existing
+//! `launch.json` from the repository is never overwritten.
+
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+use crate::core::exclude;
+use crate::core::project::{self, ProjectType};
+use crate::core::state::ReviewState;
+use crate::core::workspace;
+use crate::error::{ChabaError, Result};
+
+/// Launch file, relative to the worktree
+pub const LAUNCH_FILE: &str = ".vscode/launch.json";
+
+/// Path of a review's workspace file
+pub fn workspace_path(review: &ReviewState) -> PathBuf {
+    review
+        .worktree_path
+        .join(".chaba")
+        .join(format!("{}.code-workspace", review.key().file_stem()))
+}
+
+/// Workspace file contents for a review
+///
+/// The file lives in `.chaba/`, so the folder is its parent directory.
+pub fn workspace(review: &ReviewState) -> Value {
+    let name = workspace::folder_name(review);
+    let mut settings = json!({
+        "window.title": format!("{} — ${{activeEditorShort}}", name),
+    });
+    if let Some(port) = review.port {
+        let env = json!({ "PORT": port.to_string() });
+        for platform in ["linux", "osx", "windows"] {
+            settings[format!("terminal.integrated.env.{}", platform)] = env.clone();
+        }
+    }
+
+    json!({
+        "folders": [{ "name": name, "path": ".." }],
+        "settings": settings,
+    })
+}
+
+/// `launch.json` contents for a project type, using the review's port if any
+pub fn launch(project_type: &ProjectType, port: Option<u16>) -> Value {
+    let env = match port {
+        Some(port) => json!({ "PORT": port.to_string() }),
+        None => json!({}),
+    };
+    let suffix = port.map(|port| format!(" (port {})", port)).unwrap_or_default();
+
+    let mut configurations = match project_type {
+        ProjectType::NodeJs { package_manager } => vec![json!({
+            "name": format!("chaba: {} run dev{}", package_manager.as_str(), suffix),
+            "type": "node",
+            "request": "launch",
+            "cwd": "${workspaceFolder}",
+            "runtimeExecutable": package_manager.as_str(),
+            "runtimeArgs": ["run", "dev"],
+            "env": env,
+            "console": "integratedTerminal",
+        })],
+        ProjectType::Rust => vec![json!({
+            "name": format!("chaba: cargo run{}", suffix),
+            "type": "lldb",
+            "request": "launch",
+            "cwd": "${workspaceFolder}",
+            "cargo": { "args": ["build"] },
+            "env": env,
+        })],
+        ProjectType::Python { .. } => vec![json!({
+            "name": format!("chaba: current file{}", suffix),
+            "type": "debugpy",
+            "request": "launch",
+            "cwd": "${workspaceFolder}",
+            "program": "${file}",
+            "env": env,
+            "console": "integratedTerminal",
+        })],
+        ProjectType::Go => vec![json!({
+            "name": format!("chaba: go run{}", suffix),
+            "type": "go",
+            "request": "launch",
+            "mode": "auto",
+            "program": "${workspaceFolder}",
+            "env": env,
+        })],
+        ProjectType::Unknown => Vec::new(),
+    };
+    if let Some(port) = port {
+        configurations.push(json!({
+            "name": format!("chaba: browser on localhost:{}", port),
+            "type": "chrome",
+            "request": "launch",
+            "url": format!("http://localhost:{}", port),
+            "webRoot": "${workspaceFolder}",
+        }));
+    }
+
+    json!({ "version": "0.2.0", "configurations": configurations })
+}
+
+/// Write a review's workspace file, returning its path
+pub fn write_workspace(review: &ReviewState) -> Result<PathBuf> {
+    let path = workspace_path(review);
+    write_json(&path, &workspace(review))?;
+    Ok(path)
+}
+
+/// Write `.vscode/launch.json` into a review's worktree
+///
+/// The file is kept out of git. Returns `None` without touching anything if
+/// the worktree already has one.
+pub fn write_launch(review: &ReviewState) -> Result<Option<PathBuf>> {
+    let path = review.worktree_path.join(LAUNCH_FILE);
+    if path.exists() {
+        return Ok(None);
+    }
+    let project_type = project::detect_project_type(&review.worktree_path)?;
+    write_json(&path, &launch(&project_type, review.port))?;
+    let patterns = [format!("/{}", LAUNCH_FILE)];
+    if let Err(e) = exclude::exclude_patterns(&review.worktree_path, &patterns) {
+        tracing::warn!("Failed to exclude {} from git: {}", LAUNCH_FILE, e);
+    }
+    Ok(Some(path))
+}
+
+fn write_json(path: &Path, value: &Value) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(value).map_err(|e| ChabaError::Other(e.into()))?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::project::NodePackageManager;
+    use tempfile::TempDir;
+
+    fn review(worktree_path: PathBuf, port: Option<u16>) -> ReviewState {
+        ReviewState {
+            branch: "feature/login".to_string(),
+            port,
+            ..ReviewState::for_test(123, worktree_path)
+        }
+    }
+
+    #[test]
+    fn test_workspace() {
+        let value = workspace(&review(PathBuf::from("/tmp/pr-123"), Some(3001)));
+        assert_eq!(value["folders"], json!([{ "name": "PR #123 — feature/login", "path": ".." }]));
+        assert_eq!(value["settings"]["terminal.integrated.env.osx"]["PORT"], "3001");
+
+        let value = workspace(&review(PathBuf::from("/tmp/pr-123"), None));
+        assert!(value["settings"].get("terminal.integrated.env.linux").is_none());
+    }
+
+    #[test]
+    fn test_launch() {
+        let node = ProjectType::NodeJs { package_manager: NodePackageManager::Pnpm };
+        let value = launch(&node, Some(3001));
+        let configurations = value["configurations"].as_array().unwrap();
+        assert_eq!(configurations.len(), 2);
+        assert_eq!(configurations[0]["runtimeExecutable"], "pnpm");
+        assert_eq!(configurations[0]["env"]["PORT"], "3001");
+        assert_eq!(configurations[1]["url"], "http://localhost:3001");
+
+        let value = launch(&ProjectType::Go, None);
+        assert_eq!(value["configurations"].as_array().unwrap().len(), 1);
+        assert_eq!(value["configurations"][0]["env"], json!({}));
+        assert_eq!(launch(&ProjectType::Unknown, None)["configurations"], json!([]));
+    }
+
+    #[test]
+    fn test_write_launch_keeps_existing_file() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("go.mod"), "module example.com/app\n").unwrap();
+        let review = review(temp_dir.path().to_path_buf(), Some(3001));
+
+        let path = write_launch(&review).unwrap().unwrap();
+        let value: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(value["configurations"][0]["type"], "go");
+
+        std::fs::write(&path, "{}").unwrap();
+        assert_eq!(write_launch(&review).unwrap(), None);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{}");
+
+        let path = write_workspace(&review).unwrap();
+        assert_eq!(path, temp_dir.path().join(".chaba/pr-123.code-workspace"));
+    }
+}
@@ -0,0 +1,135 @@
+//! File system watcher for review worktrees
+//!
+//! The TUI keeps git stats and dev-server health for every review and only
+//! recomputes them for reviews whose worktree changed, instead of running
+//! `git diff` for every review on a timer. Changes under `.git`,
+//! `node_modules`, and `target` are ignored: they come from git itself,
+//! installs, and builds, and would otherwise trigger constant refreshes.
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+
+use crate::core::platform;
+use crate::core::state::ReviewState;
+use crate::error::{ChabaError, Result};
+
+/// Directories whose changes never affect review stats
+const IGNORED_DIRS: &[&str] = &[".git", "node_modules", "target"];
+
+/// Watches the worktrees of a set of reviews
+pub struct WorktreeWatcher {
+    // Dropping the watcher stops the notifications
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    worktrees: Vec<(u32, PathBuf)>,
+}
+
+impl WorktreeWatcher {
+    /// Start watching the existing worktrees of `reviews`
+    pub fn new(reviews: &[ReviewState]) -> Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)
+            .map_err(|e| ChabaError::Other(anyhow::anyhow!("Failed to start file watcher: {}", e)))?;
+
+        let mut worktrees = Vec::new();
+        for review in reviews.iter().filter(|review| review.worktree_path.exists()) {
+            // Events report canonical paths, state paths may not be
+            let path = platform::canonicalize(&review.worktree_path)
+                .unwrap_or_else(|_| review.worktree_path.clone());
+            watcher.watch(&path, RecursiveMode::Recursive).map_err(|e| {
+                ChabaError::Other(anyhow::anyhow!("Failed to watch {}: {}", path.display(), e))
+            })?;
+            worktrees.push((review.pr_number, path));
+        }
+
+        Ok(WorktreeWatcher {
+            _watcher: watcher,
+            events,
+            worktrees,
+        })
+    }
+
+    /// PR numbers of reviews whose worktree changed since the last call
+    pub fn changed(&self) -> BTreeSet<u32> {
+        self.events
+            .try_iter()
+            .filter_map(|event| event.ok())
+            .flat_map(|event| event.paths)
+            .filter_map(|path| owner(&path, &self.worktrees))
+            .collect()
+    }
+}
+
+/// The review whose worktree contains `path`, unless the path is ignored
+///
+/// When worktrees are nested, the deepest one wins.
+fn owner(path: &Path, worktrees: &[(u32, PathBuf)]) -> Option<u32> {
+    let (pr_number, worktree) = worktrees
+        .iter()
+        .filter(|(_, worktree)| path.starts_with(worktree))
+        .max_by_key(|(_, worktree)| worktree.components().count())?;
+
+    let relative = path.strip_prefix(worktree).ok()?;
+    let ignored = relative
+        .components()
+        .any(|component| matches!(component, Component::Normal(name) if IGNORED_DIRS.iter().any(|dir| name == *dir)));
+
+    (!ignored).then_some(*pr_number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    fn review(pr_number: u32, worktree_path: PathBuf) -> ReviewState {
+        ReviewState {
+            branch: "feature".to_string(),
+            ..ReviewState::for_test(pr_number, worktree_path)
+        }
+    }
+
+    #[test]
+    fn test_owner() {
+        let worktrees = vec![
+            (1, PathBuf::from("/reviews/pr-1")),
+            (2, PathBuf::from("/reviews/pr-1/nested")),
+        ];
+
+        assert_eq!(owner(Path::new("/reviews/pr-1/src/main.rs"), &worktrees), Some(1));
+        assert_eq!(owner(Path::new("/reviews/pr-1/nested/app.ts"), &worktrees), Some(2));
+        assert_eq!(owner(Path::new("/reviews/pr-10/app.ts"), &worktrees), None);
+        assert_eq!(owner(Path::new("/reviews/pr-1/target/debug/app"), &worktrees), None);
+        assert_eq!(owner(Path::new("/reviews/pr-1/web/node_modules/x/index.js"), &worktrees), None);
+        assert_eq!(owner(Path::new("/reviews/pr-1/.git"), &worktrees), None);
+    }
+
+    #[test]
+    fn test_watcher_reports_changed_worktree() {
+        let temp_dir = TempDir::new().unwrap();
+        let pr_1 = temp_dir.path().join("pr-1");
+        let pr_2 = temp_dir.path().join("pr-2");
+        std::fs::create_dir_all(&pr_1).unwrap();
+        std::fs::create_dir_all(&pr_2).unwrap();
+
+        let watcher = WorktreeWatcher::new(&[
+            review(1, pr_1.clone()),
+            review(2, pr_2),
+            review(3, temp_dir.path().join("missing")),
+        ])
+        .unwrap();
+        std::fs::write(pr_1.join("file.txt"), "changed").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut changed = BTreeSet::new();
+        while changed.is_empty() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+            changed.extend(watcher.changed());
+        }
+
+        assert_eq!(changed, BTreeSet::from([1]));
+    }
+}
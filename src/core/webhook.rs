@@ -0,0 +1,341 @@
+//! Slack and Discord webhooks
+//!
+//! `notifications.webhooks` pushes agent results into team channels: when an
+//! analysis completes (`analysis_complete`), when it reports a critical
+//! finding (`critical_finding`), and when a review environment is removed
+//! (`cleanup`). Each message summarizes the findings by severity, lists the
+//! most severe ones, and links to the PR. Messages are posted with `curl`;
+//! the URL goes through a temporary curl config file so it never shows up in
+//! process listings. Best effort: failures are only logged.
+
+use serde_json::{json, Value};
+use std::ffi::OsStr;
+use std::io::Write;
+
+use crate::config::{Config, WebhookConfig, WebhookEvent, WebhookFormat};
+use crate::core::command::default_runner;
+use crate::core::git::GitOps;
+use crate::core::review_analysis::{Finding, Severity};
+use crate::core::secrets;
+use crate::core::state::ReviewState;
+use crate::error::{ChabaError, Result};
+
+/// Findings listed per message before the rest is summarized
+const MAX_LISTED: usize = 5;
+
+/// Longest message Discord accepts
+const DISCORD_LIMIT: usize = 2000;
+
+/// Post a finished analysis to webhooks that want it
+///
+/// With a critical finding, webhooks that want `critical_finding` get that
+/// message instead of the plain `analysis_complete` one, so no channel sees
+/// the same run twice.
+pub async fn analysis_finished(config: &Config, review: &ReviewState) {
+    let critical = findings(review).any(|(_, finding)| finding.severity == Severity::Critical);
+    post(config, review, |webhook| {
+        if critical && webhook.wants(WebhookEvent::CriticalFinding) {
+            Some("Critical finding")
+        } else if webhook.wants(WebhookEvent::AnalysisComplete) {
+            Some("Agent analysis finished")
+        } else {
+            None
+        }
+    })
+    .await;
+}
+
+/// Post a removed review environment to webhooks that want it
+pub async fn cleaned_up(config: &Config, review: &ReviewState) {
+    post(config, review, |webhook| {
+        webhook.wants(WebhookEvent::Cleanup).then_some("Review environment removed")
+    })
+    .await;
+}
+
+/// Send each webhook the message `headline` picks for it, if any
+async fn post(config: &Config, review: &ReviewState, headline: impl Fn(&WebhookConfig) -> Option<&'static str>) {
+    let targets: Vec<(&WebhookConfig, &str)> = config
+        .notifications
+        .webhooks
+        .iter()
+        .filter_map(|webhook| headline(webhook).map(|headline| (webhook, headline)))
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+
+    let link = pr_url(review).await;
+    for (webhook, headline) in targets {
+        if let Err(e) = send(config, webhook, &message(headline, review, link.as_deref())).await {
+            tracing::warn!("Failed to post PR #{} to a webhook: {}", review.pr_number, e);
+        }
+    }
+}
+
+/// A PR's web URL, if it is a GitHub PR
+async fn pr_url(review: &ReviewState) -> Option<String> {
+    if !review.is_pull_request() {
+        return None;
+    }
+    let result = match GitOps::for_review(review) {
+        Ok(git) => git.get_pr_url(review.pr_number).await,
+        Err(e) => Err(e),
+    };
+    result
+        .map_err(|e| tracing::debug!("Cannot look up the URL of PR #{}: {}", review.pr_number, e))
+        .ok()
+}
+
+async fn send(config: &Config, webhook: &WebhookConfig, message: &Message) -> Result<()> {
+    let url = secrets::resolve_value(&webhook.url, config.agents.secrets_file.as_deref(), "webhook")?;
+    let format = webhook.format.unwrap_or_else(|| detect_format(&url));
+    let payload = message.payload(format).to_string();
+
+    // The URL carries the webhook's credentials
+    let mut curl_config = tempfile::NamedTempFile::new()?;
+    writeln!(curl_config, "url = \"{}\"", url.replace('\\', "\\\\").replace('"', "\\\""))?;
+
+    let args: Vec<&OsStr> = vec![
+        "--silent".as_ref(),
+        "--show-error".as_ref(),
+        "--fail".as_ref(),
+        "--max-time".as_ref(),
+        "30".as_ref(),
+        "--config".as_ref(),
+        curl_config.path().as_os_str(),
+        "--header".as_ref(),
+        "Content-Type: application/json".as_ref(),
+        "--data-binary".as_ref(),
+        payload.as_ref(),
+        "--output".as_ref(),
+        null_device().as_ref(),
+    ];
+    let output = default_runner().run("curl", &args, &std::env::temp_dir()).await?;
+    if !output.status.success() {
+        return Err(ChabaError::Other(anyhow::anyhow!(
+            "curl exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+fn null_device() -> &'static str {
+    if cfg!(windows) {
+        "NUL"
+    } else {
+        "/dev/null"
+    }
+}
+
+/// Discord for Discord webhook URLs, Slack otherwise
+fn detect_format(url: &str) -> WebhookFormat {
+    let host = url.split("://").nth(1).unwrap_or(url).split('/').next().unwrap_or_default();
+    if host.ends_with("discord.com") || host.ends_with("discordapp.com") {
+        WebhookFormat::Discord
+    } else {
+        WebhookFormat::Slack
+    }
+}
+
+/// Findings of a review that still need attention, with the agent that reported them
+fn findings(review: &ReviewState) -> impl Iterator<Item = (&str, &Finding)> {
+    review
+        .agent_analyses
+        .iter()
+        .flat_map(|analysis| analysis.findings.iter().map(move |finding| (analysis.agent.as_str(), finding)))
+        .filter(|(_, finding)| !finding.triage.is_resolved())
+}
+
+/// A message about a review, before formatting for Slack or Discord
+#[derive(Debug)]
+struct Message {
+    headline: String,
+    /// e.g. `PR #42: Add login`
+    subject: String,
+    url: Option<String>,
+    lines: Vec<String>,
+}
+
+fn message(headline: &str, review: &ReviewState, url: Option<&str>) -> Message {
+    let subject = match &review.pr_info {
+        Some(info) => format!("PR #{}: {}", review.pr_number, info.title),
+        None => format!("PR #{} ({})", review.pr_number, review.branch),
+    };
+
+    let mut found: Vec<(&str, &Finding)> = findings(review).collect();
+    found.sort_by_key(|(_, finding)| std::cmp::Reverse(finding.severity));
+
+    let mut lines = Vec::new();
+    if !review.agent_analyses.is_empty() {
+        let counts: Vec<String> = Severity::ALL
+            .iter()
+            .filter_map(|severity| {
+                let count = found.iter().filter(|(_, finding)| finding.severity == *severity).count();
+                (count > 0).then(|| format!("{} {}", count, severity_label(*severity)))
+            })
+            .collect();
+        let mut summary = format!("{} finding(s) from {} agent(s)", found.len(), review.agent_analyses.len());
+        if !counts.is_empty() {
+            summary.push_str(&format!(": {}", counts.join(", ")));
+        }
+        lines.push(summary);
+    }
+    for (agent, finding) in found.iter().take(MAX_LISTED) {
+        let location = match (&finding.file, finding.line) {
+            (Some(file), Some(line)) => format!(" ({}:{})", file, line),
+            (Some(file), None) => format!(" ({})", file),
+            _ => String::new(),
+        };
+        lines.push(format!(
+            "• [{}] {}{} ({})",
+            severity_label(finding.severity),
+            finding.title,
+            location,
+            agent
+        ));
+    }
+    if found.len() > MAX_LISTED {
+        lines.push(format!("… and {} more", found.len() - MAX_LISTED));
+    }
+
+    Message {
+        headline: headline.to_string(),
+        subject,
+        url: url.map(str::to_string),
+        lines,
+    }
+}
+
+fn severity_label(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "critical",
+        Severity::High => "high",
+        Severity::Medium => "medium",
+        Severity::Low => "low",
+        Severity::Info => "info",
+    }
+}
+
+impl Message {
+    /// JSON body for the webhook
+    fn payload(&self, format: WebhookFormat) -> Value {
+        match format {
+            WebhookFormat::Slack => {
+                let escape = |text: &str| text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+                let subject = match &self.url {
+                    Some(url) => format!("<{}|{}>", url, escape(&self.subject)),
+                    None => escape(&self.subject),
+                };
+                let mut text = format!("*{}*: {}", escape(&self.headline), subject);
+                for line in &self.lines {
+                    text.push_str(&format!("\n{}", escape(line)));
+                }
+                json!({ "text": text })
+            }
+            WebhookFormat::Discord => {
+                // Angle brackets keep Discord from embedding a preview of the PR
+                let subject = match &self.url {
+                    Some(url) => format!("[{}](<{}>)", self.subject, url),
+                    None => self.subject.clone(),
+                };
+                let mut content = format!("**{}**: {}", self.headline, subject);
+                for line in &self.lines {
+                    content.push_str(&format!("\n{}", line));
+                }
+                if content.chars().count() > DISCORD_LIMIT {
+                    content = content.chars().take(DISCORD_LIMIT - 1).collect::<String>() + "…";
+                }
+                // Finding titles come from agents and must not ping anyone
+                json!({ "content": content, "allowed_mentions": { "parse": [] } })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::EnvValue;
+    use crate::core::review_analysis::{Category, ReviewAnalysis, TriageStatus};
+
+    fn finding(severity: Severity, title: &str, file: Option<&str>) -> Finding {
+        let finding = Finding::new(severity, Category::Security, title.to_string(), String::new());
+        match file {
+            Some(file) => finding.with_file(file.to_string()).with_line(10),
+            None => finding,
+        }
+    }
+
+    fn review() -> ReviewState {
+        let mut analysis = ReviewAnalysis::new("claude".to_string());
+        analysis.findings = vec![
+            finding(Severity::Low, "Unused import", None),
+            finding(Severity::Critical, "SQL injection in <login>", Some("src/db.rs")),
+            finding(Severity::High, "Token is logged", Some("src/auth.rs")),
+            Finding {
+                triage: TriageStatus::Dismissed,
+                ..finding(Severity::Critical, "False alarm", None)
+            },
+        ];
+        ReviewState {
+            branch: "feature/login".to_string(),
+            agent_analyses: vec![analysis],
+            ..ReviewState::for_test(42, "/tmp/pr-42")
+        }
+    }
+
+    #[test]
+    fn test_message_summarizes_findings() {
+        let message = message("Critical finding", &review(), Some("https://github.com/acme/app/pull/42"));
+        assert_eq!(message.subject, "PR #42 (feature/login)");
+        assert_eq!(
+            message.lines,
+            vec![
+                "3 finding(s) from 1 agent(s): 1 critical, 1 high, 1 low",
+                "• [critical] SQL injection in <login> (src/db.rs:10) (claude)",
+                "• [high] Token is logged (src/auth.rs:10) (claude)",
+                "• [low] Unused import (claude)",
+            ]
+        );
+
+        let slack = message.payload(WebhookFormat::Slack);
+        let text = slack["text"].as_str().unwrap();
+        assert!(text.starts_with("*Critical finding*: <https://github.com/acme/app/pull/42|PR #42 (feature/login)>\n"));
+        assert!(text.contains("SQL injection in &lt;login&gt;"));
+
+        let discord = message.payload(WebhookFormat::Discord);
+        let content = discord["content"].as_str().unwrap();
+        assert!(content.starts_with("**Critical finding**: [PR #42 (feature/login)](<https://github.com/acme/app/pull/42>)\n"));
+        assert_eq!(discord["allowed_mentions"]["parse"], json!([]));
+    }
+
+    #[test]
+    fn test_message_limits() {
+        let mut review = review();
+        review.agent_analyses[0].findings = (0..8).map(|i| finding(Severity::Medium, &"x".repeat(400 + i), None)).collect();
+        let message = message("Agent analysis finished", &review, None);
+        assert_eq!(message.lines.len(), 1 + MAX_LISTED + 1);
+        assert_eq!(message.lines.last().unwrap(), "… and 3 more");
+
+        let discord = message.payload(WebhookFormat::Discord);
+        assert_eq!(discord["content"].as_str().unwrap().chars().count(), DISCORD_LIMIT);
+    }
+
+    #[test]
+    fn test_detect_format_and_events() {
+        assert_eq!(detect_format("https://discord.com/api/webhooks/1/abc"), WebhookFormat::Discord);
+        assert_eq!(detect_format("https://hooks.slack.com/services/T/B/x"), WebhookFormat::Slack);
+        assert_eq!(detect_format("https://example.com/discord.com"), WebhookFormat::Slack);
+
+        let webhook: WebhookConfig =
+            serde_yaml::from_str("url: { secret: slack }\nevents: [critical_finding]").unwrap();
+        assert_eq!(webhook.url, EnvValue::Secret { secret: "slack".to_string() });
+        assert!(webhook.wants(WebhookEvent::CriticalFinding));
+        assert!(!webhook.wants(WebhookEvent::Cleanup));
+        let webhook: WebhookConfig = serde_yaml::from_str("url: https://hooks.slack.com/x").unwrap();
+        assert!(webhook.wants(WebhookEvent::Cleanup));
+    }
+}
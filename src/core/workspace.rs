@@ -0,0 +1,123 @@
+//! Editor workspace file
+//!
+//! Keeps `<base_dir>/chaba.code-workspace` listing every active review
+//! worktree, so `code chaba.code-workspace` (or Cursor) opens them all as a
+//! multi-root workspace. Only `folders` is managed; other keys such as
+//! `settings` are preserved.
+
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+
+use crate::config::WorktreeConfig;
+use crate::core::stack;
+use crate::core::state::ReviewState;
+use crate::error::{ChabaError, Result};
+
+/// Workspace file name, inside the worktree base directory
+pub const WORKSPACE_FILE: &str = "chaba.code-workspace";
+
+/// Path of the workspace file for a configuration
+pub fn workspace_path(config: &WorktreeConfig) -> PathBuf {
+    config.base_dir.join(WORKSPACE_FILE)
+}
+
+/// Folder label for a review, e.g. `PR #123 — feature/login`
+pub fn folder_name(review: &ReviewState) -> String {
+    format!("PR #{} — {}", review.pr_number, review.branch)
+}
+
+/// Rewrite the workspace file's folders to match `reviews`
+///
+/// Missing worktrees are left out. Stacked PRs follow their parent.
+pub fn write(path: &Path, reviews: &[ReviewState]) -> Result<()> {
+    let mut workspace = match std::fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str::<Map<String, Value>>(&content).unwrap_or_default(),
+        Err(_) => Map::new(),
+    };
+
+    let folders: Vec<Value> = stack::stack_order(reviews)
+        .into_iter()
+        .map(|(_, review)| review)
+        .filter(|review| review.worktree_path.exists())
+        .map(|review| {
+            json!({
+                "name": folder_name(review),
+                "path": review.worktree_path,
+            })
+        })
+        .collect();
+
+    workspace.insert("folders".to_string(), Value::Array(folders));
+    workspace
+        .entry("settings".to_string())
+        .or_insert_with(|| json!({}));
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(&workspace).map_err(|e| ChabaError::Other(e.into()))?;
+    std::fs::write(path, json)?;
+
+    Ok(())
+}
+
+/// Refresh the workspace file if enabled, logging instead of failing
+pub fn update(config: &WorktreeConfig, reviews: &[ReviewState]) {
+    if !config.editor_workspace {
+        return;
+    }
+
+    if let Err(e) = write(&workspace_path(config), reviews) {
+        tracing::warn!("Failed to update editor workspace file: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn review(pr_number: u32, branch: &str, worktree_path: PathBuf) -> ReviewState {
+        ReviewState {
+            branch: branch.to_string(),
+            ..ReviewState::for_test(pr_number, worktree_path)
+        }
+    }
+
+    #[test]
+    fn test_write_lists_existing_worktrees() {
+        let temp_dir = TempDir::new().unwrap();
+        let pr_123 = temp_dir.path().join("pr-123");
+        std::fs::create_dir_all(&pr_123).unwrap();
+
+        let reviews = vec![
+            review(123, "feature/login", pr_123.clone()),
+            review(124, "feature/gone", temp_dir.path().join("pr-124")),
+        ];
+        let path = temp_dir.path().join(WORKSPACE_FILE);
+        write(&path, &reviews).unwrap();
+
+        let workspace: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let folders = workspace["folders"].as_array().unwrap();
+        assert_eq!(folders.len(), 1);
+        assert_eq!(folders[0]["name"], "PR #123 — feature/login");
+        assert_eq!(folders[0]["path"], pr_123.display().to_string());
+    }
+
+    #[test]
+    fn test_write_preserves_user_settings() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(WORKSPACE_FILE);
+        std::fs::write(
+            &path,
+            r#"{"folders": [{"path": "/old"}], "settings": {"editor.tabSize": 2}}"#,
+        )
+        .unwrap();
+
+        write(&path, &[]).unwrap();
+
+        let workspace: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(workspace["folders"].as_array().unwrap().is_empty());
+        assert_eq!(workspace["settings"]["editor.tabSize"], 2);
+    }
+}
@@ -2,10 +2,23 @@ use chrono::Utc;
 use std::path::{Path, PathBuf};
 use path_clean::PathClean;
 
-use crate::config::Config;
-use crate::core::{git::GitOps, state::{ReviewState, State}};
+use crate::config::{CheckoutMode, Config};
+use crate::core::{archive, events::{self, EventKind}, exclude, git::{GitOps, PrInfo, SnapshotCommit}, metadata::{ReviewMetadata, METADATA_FILE}, naming::{self, NameVars}, platform, port::{self, PortManager}, project, snapshot::SnapshotStore, sparse, state::{ReviewKey, ReviewState, State}, suggestions, ttl, webhook, workspace};
 use crate::error::{ChabaError, Result};
 
+/// What a review environment is created from
+#[derive(Debug, Clone)]
+pub enum ReviewSource {
+    /// A GitHub pull request
+    Pr(u32),
+    /// A remote branch without a PR
+    Branch(String),
+    /// A patch file applied on top of `base` (the default branch when `None`)
+    Patch { path: PathBuf, base: Option<String> },
+    /// Uncommitted changes in the current repository, copied on top of HEAD
+    Local,
+}
+
 pub struct WorktreeManager {
     git: GitOps,
     config: Config,
@@ -37,7 +50,7 @@ impl WorktreeManager {
         };
 
         // Check if the cleaned path starts with the cleaned base directory.
-        if cleaned_path.starts_with(&cleaned_base) {
+        if platform::starts_with(&cleaned_path, &cleaned_base) {
             Ok(cleaned_path)
         } else {
             Err(ChabaError::ConfigError(format!(
@@ -49,39 +62,163 @@ impl WorktreeManager {
     }
 
 
-    /// Create a new worktree for the given PR or branch
-    pub async fn create(&self, pr_number: Option<u32>, branch: Option<String>, force: bool, custom_path: Option<String>) -> Result<ReviewState> {
+    /// Create a new worktree for the given PR, branch, patch, or local changes
+    ///
+    /// When `ttl` is given, the review expires that long after creation.
+    /// `sparse` enables sparse checkout, with extra paths to include; it is
+    /// also enabled when `worktree.sparse_paths` is configured.
+    pub async fn create(
+        &self,
+        source: ReviewSource,
+        force: bool,
+        custom_path: Option<String>,
+        ttl: Option<chrono::Duration>,
+        sparse: Option<Vec<String>>,
+    ) -> Result<ReviewState> {
+        self.build(source, force, custom_path, ttl, sparse, None).await
+    }
+
+    /// [`WorktreeManager::create`], keeping `preferred_port` if it is available
+    async fn build(
+        &self,
+        source: ReviewSource,
+        force: bool,
+        custom_path: Option<String>,
+        ttl: Option<chrono::Duration>,
+        sparse: Option<Vec<String>>,
+        preferred_port: Option<u16>,
+    ) -> Result<ReviewState> {
         // Determine branch name
-        let (pr, branch_name) = match (pr_number, branch) {
-            (Some(pr), None) => {
-                let branch = self.git.get_pr_branch(pr).await?;
-                (pr, branch)
+        let mut parent_pr = None;
+        let mut pr_info: Option<PrInfo> = None;
+        let (pr, branch_name, base_branch, patch, local) = match source {
+            ReviewSource::Pr(pr) => {
+                let view = self.git.get_pr_view(pr).await?;
+                let base = Some(view.base_branch).filter(|base| !base.is_empty());
+                if let Some(base) = &base {
+                    parent_pr = self.git.find_pr_for_branch(base).await.ok().flatten();
+                }
+                pr_info = Some(view.info);
+                (pr, view.head_branch, base, None, None)
             }
-            (None, Some(branch)) => {
+            ReviewSource::Branch(branch) => {
                 // Generate PR number from branch name hash (for tracking)
                 let pr = Self::hash_branch_name(&branch);
-                (pr, branch)
+                let base = self.default_base().await;
+                (pr, branch, Some(base), None, None)
+            }
+            ReviewSource::Patch { path, base } => {
+                let patch = platform::canonicalize(&path).map_err(|_| {
+                    ChabaError::ConfigError(format!("Patch file not found: {}", path.display()))
+                })?;
+                let base = match base {
+                    Some(base) => base,
+                    None => self.default_base().await,
+                };
+                // Patches are tracked by a pseudo-PR number derived from their path
+                let pr = Self::hash_branch_name(&format!("patch:{}", patch.display()));
+                (pr, base.clone(), Some(base), Some(patch), None)
+            }
+            ReviewSource::Local => {
+                // Local changes live in whichever worktree chaba was run from
+                let current = self.git.current_worktree();
+                if !self.git.has_uncommitted_changes(&current).await? {
+                    return Err(ChabaError::Other(anyhow::anyhow!(
+                        "No local changes to review in {}",
+                        current.display()
+                    )));
+                }
+
+                let branch = self.git.current_branch().unwrap_or_else(|| "HEAD".to_string());
+                let pr = Self::hash_branch_name(&format!("local:{}", branch));
+
+                // Capture the working tree without touching it
+                let snapshot = self
+                    .git
+                    .snapshot_worktree(
+                        &current,
+                        &self.git.git_dir().join("chaba-local.index"),
+                        &format!("refs/chaba/local/pr-{}", pr),
+                        &format!("chaba local review of {}", branch),
+                    )
+                    .await?;
+                (pr, branch, None, None, Some(snapshot))
             }
-            _ => return Err(ChabaError::InvalidInput),
         };
 
+        let repo_root = self.git.repo_root();
+
         // Determine and validate worktree path
         let worktree_path = if let Some(custom) = custom_path {
             let path = PathBuf::from(custom);
             Self::validate_path_secure(&path, &self.config.worktree.base_dir)?
         } else {
-            let name = self.config.worktree.naming_template.replace("{pr}", &pr.to_string());
+            let template = &self.config.worktree.naming_template;
+            let author = if !naming::uses_author(template) {
+                None
+            } else if let Some(info) = &pr_info {
+                Some(info.author.clone())
+            } else if local.is_some() {
+                self.git.config_string("user.name")
+            } else if patch.is_none() {
+                self.git
+                    .commit_author(&format!("origin/{}", branch_name))
+                    .or_else(|| self.git.commit_author(&branch_name))
+            } else {
+                None
+            };
+            let name = naming::render(
+                template,
+                &NameVars {
+                    pr,
+                    branch: branch_name.clone(),
+                    date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+                    repo: repo_root
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    author,
+                },
+            );
             let path = self.config.worktree.base_dir.join(name);
             // Validate the auto-generated path to ensure it's clean and within the base dir.
             Self::validate_path_secure(&path, &self.config.worktree.base_dir)?
         };
 
+        // Never overwrite another review's worktree, e.g. the same PR number of another repository
+        let key = ReviewKey {
+            repo: Some(repo_root.clone()),
+            pr_number: pr,
+        };
+        let state = State::load()?;
+        if let Some(other) = state
+            .reviews
+            .iter()
+            .find(|r| !r.key().matches(&key) && r.worktree_path.clean() == worktree_path.clean())
+        {
+            return Err(ChabaError::ConfigError(format!(
+                "{} belongs to the review of PR #{}{}; add {{repo}} to worktree.naming_template or pass --worktree",
+                worktree_path.display(),
+                other.pr_number,
+                other.repo.as_ref().map(|repo| format!(" in {}", repo.display())).unwrap_or_default()
+            )));
+        }
+
+        // Re-reviewing a tracked PR elsewhere would leave its old worktree behind
+        if let Some(existing) = state.find_review(&key) {
+            if !force && existing.worktree_path.clean() != worktree_path.clean() {
+                return Err(ChabaError::ReviewExists(pr));
+            }
+        }
+
         // Check if worktree already exists
         if worktree_path.exists() {
             if force {
                 // Force flag: remove without asking
                 self.git.remove_worktree(&worktree_path).await?;
-                tokio::fs::remove_dir_all(&worktree_path).await?;
+                if worktree_path.exists() {
+                    tokio::fs::remove_dir_all(&worktree_path).await?;
+                }
             } else {
                 // Interactive mode: ask user if they want to overwrite
                 use dialoguer::Confirm;
@@ -97,7 +234,9 @@ impl WorktreeManager {
 
                 if overwrite {
                     self.git.remove_worktree(&worktree_path).await?;
-                    tokio::fs::remove_dir_all(&worktree_path).await?;
+                    if worktree_path.exists() {
+                        tokio::fs::remove_dir_all(&worktree_path).await?;
+                    }
                 } else {
                     return Err(ChabaError::WorktreeExists(worktree_path));
                 }
@@ -111,65 +250,590 @@ impl WorktreeManager {
             }
         }
 
-        // Fetch the branch
-        tracing::info!("Fetching branch: {}", branch_name);
-        self.git.fetch_branch("origin", &branch_name).await?;
+        let start_point = match &local {
+            Some(snapshot) => snapshot.head.clone(),
+            None => {
+                // Fetch the branch
+                tracing::info!("Fetching branch: {}", branch_name);
+                self.git.fetch_branch("origin", &branch_name).await?;
+                // Diffs and stats compare against the merge target (the parent
+                // branch for stacked PRs), so fetch it too
+                if let Some(base) = base_branch.as_ref().filter(|base| **base != branch_name) {
+                    if let Err(e) = self.git.fetch_branch("origin", base).await {
+                        tracing::warn!("Failed to fetch base branch {}: {}", base, e);
+                    }
+                }
+                format!("origin/{}", branch_name)
+            }
+        };
+
+        // Limit the checkout to the directories the changes touch
+        let sparse_include = match sparse {
+            Some(extra) => Some(extra),
+            None if !self.config.worktree.sparse_paths.is_empty() => Some(Vec::new()),
+            None => None,
+        };
+        let sparse_paths = match sparse_include {
+            Some(extra) => {
+                match self
+                    .changed_files(&start_point, base_branch.as_deref(), patch.as_deref(), local.as_ref())
+                    .await
+                {
+                    Ok(changed) => {
+                        let mut include = self.config.worktree.sparse_paths.clone();
+                        include.extend(extra);
+                        Some(sparse::cone_directories(&changed, &include))
+                    }
+                    Err(e) => {
+                        tracing::warn!("Cannot determine changed files, using a full checkout: {}", e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
 
         // Create worktree
         tracing::info!("Creating worktree at: {}", worktree_path.display());
-        self.git.add_worktree(&worktree_path, &format!("origin/{}", branch_name)).await?;
+        match (&sparse_paths, self.config.worktree.checkout) {
+            (_, CheckoutMode::SharedClone) => {
+                if let Err(e) = self
+                    .git
+                    .add_shared_clone(&worktree_path, &start_point, sparse_paths.as_deref())
+                    .await
+                {
+                    if worktree_path.exists() {
+                        let _ = tokio::fs::remove_dir_all(&worktree_path).await;
+                    }
+                    return Err(e);
+                }
+            }
+            (Some(directories), CheckoutMode::Worktree) => {
+                if let Err(e) = self
+                    .git
+                    .add_sparse_worktree(&worktree_path, &start_point, directories)
+                    .await
+                {
+                    let _ = self.git.remove_worktree(&worktree_path).await;
+                    return Err(e);
+                }
+            }
+            (None, CheckoutMode::Worktree) => self.git.add_worktree(&worktree_path, &start_point).await?,
+        }
+
+        if let Some(snapshot) = &local {
+            tracing::info!("Copying local changes into the worktree");
+            let result = self.git.restore_snapshot(&worktree_path, snapshot).await;
+            let _ = self.git.delete_ref(&format!("refs/chaba/local/pr-{}", pr)).await;
+            if let Err(e) = result {
+                let _ = self.git.remove_worktree(&worktree_path).await;
+                return Err(e);
+            }
+        }
+
+        if let Some(patch) = &patch {
+            tracing::info!("Applying patch: {}", patch.display());
+            if let Err(e) = self.git.apply_patch(&worktree_path, patch).await {
+                // Don't leave an unpatched worktree behind that state doesn't know about
+                let _ = self.git.remove_worktree(&worktree_path).await;
+                return Err(e);
+            }
+        }
+
+        // Replace LFS pointer files with real content before anything uses them
+        if self.config.worktree.git_lfs && GitOps::uses_lfs(&worktree_path) {
+            tracing::info!("Pulling Git LFS objects");
+            if let Err(e) = self.git.lfs_pull(&worktree_path).await {
+                tracing::warn!("Failed to pull Git LFS objects (is git-lfs installed?): {}", e);
+            }
+        }
+
+        if self.config.worktree.status_cache {
+            if let Err(e) = self.git.enable_status_cache(&worktree_path).await {
+                tracing::warn!("Failed to enable git status cache: {}", e);
+            }
+        }
+
+        // Keep files chaba injects into the worktree out of git
+        let patterns = exclude::injected_patterns(&self.config.sandbox);
+        if let Err(e) = exclude::exclude_patterns(&worktree_path, &patterns) {
+            tracing::warn!("Failed to update git exclude file: {}", e);
+        }
 
         // Phase 2: Setup sandbox environment
-        let mut state = State::load()?;
-        let sandbox_manager = super::sandbox::SandboxManager::new(self.config.sandbox.clone());
+        let state = State::load()?;
+        let sandbox_manager =
+            super::sandbox::SandboxManager::new(self.config.sandbox.clone()).prefer_port(preferred_port);
+        let key = ReviewKey {
+            repo: Some(repo_root.clone()),
+            pr_number: pr,
+        };
+        let logs = super::logs::ReviewLogs::for_review(&key).ok();
+        if let Some(logs) = &logs {
+            events::record_in(logs, EventKind::Created, branch_name.as_str());
+        }
         let sandbox_info = sandbox_manager
-            .setup(&worktree_path, &self.git.repo_root(), &state)
+            .setup(pr, &worktree_path, &self.git.repo_root(), &state, logs.as_ref())
             .await?;
+        if let (Some(logs), true) = (&logs, sandbox_info.deps_installed) {
+            events::record_in(logs, EventKind::DepsInstalled, sandbox_info.project_type.clone().unwrap_or_default());
+        }
 
         // Create review state with sandbox info
+        let created_at = Utc::now();
         let review = ReviewState {
             pr_number: pr,
             branch: branch_name.clone(),
+            base_branch,
+            parent_pr,
+            patch,
             worktree_path: worktree_path.clone(),
-            created_at: Utc::now(),
+            sparse_paths,
+            pr_info,
+            repo: Some(repo_root),
+            created_at,
             port: sandbox_info.port,
             project_type: sandbox_info.project_type,
             deps_installed: sandbox_info.deps_installed,
             env_copied: sandbox_info.env_copied,
+            tests: sandbox_info.tests,
+            seed: sandbox_info.seed,
+            expires_at: ttl.map(|ttl| ttl::expiry(created_at, ttl)).transpose()?,
             agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
         };
 
-        // Save state
+        // Write metadata for tools running inside the worktree
+        if let Err(e) = ReviewMetadata::from_review(&review, &self.git.repo_root())
+            .write(&worktree_path)
+            .await
+        {
+            tracing::warn!("Failed to write review metadata: {}", e);
+        }
+
+        // Save state, reloading it so reviews created meanwhile (`review --all-open`) are kept.
+        // Re-reviewing replaces the PR's old review, which stays in the archive.
+        let mut state = State::load()?;
+        if let Some(old) = state.find_review(&review.key()).cloned() {
+            if let Err(e) = archive::archive(&old) {
+                tracing::warn!("Failed to archive review: {}", e);
+            }
+            state.remove_review(&old.key())?;
+        }
         state.add_review(review.clone())?;
+        workspace::update(&self.config.worktree, &state.reviews);
 
         Ok(review)
     }
 
-    /// Remove a worktree
-    pub async fn remove(&self, pr_number: u32) -> Result<()> {
+    /// Start tracking a worktree created outside chaba (`git worktree add`)
+    ///
+    /// The review is keyed by `pr` when given, otherwise by the checked-out
+    /// branch like `review --branch`. The project type is detected and a port
+    /// assigned, but dependencies and env files are left as they are.
+    pub async fn adopt(&self, path: &Path, pr: Option<u32>) -> Result<ReviewState> {
+        let worktree_path = platform::canonicalize(path)
+            .map_err(|_| ChabaError::ConfigError(format!("Worktree not found: {}", path.display())))?;
+        let git = GitOps::open_at(&worktree_path).map_err(|_| {
+            ChabaError::ConfigError(format!("{} is not the root of a git worktree", worktree_path.display()))
+        })?;
+
+        let repo_root = git.repo_root();
+        if git.current_worktree() == repo_root {
+            return Err(ChabaError::ConfigError(format!(
+                "{} is the main worktree; only worktrees added with `git worktree add` can be adopted",
+                worktree_path.display()
+            )));
+        }
+        if repo_root != self.git.repo_root() {
+            return Err(ChabaError::ConfigError(format!(
+                "{} belongs to {}; run chaba adopt from that repository (or with --repo)",
+                worktree_path.display(),
+                repo_root.display()
+            )));
+        }
+
         let mut state = State::load()?;
+        if let Some(existing) = state.reviews.iter().find(|r| r.worktree_path == worktree_path) {
+            return Err(ChabaError::ConfigError(format!(
+                "{} is already tracked as PR #{}",
+                worktree_path.display(),
+                existing.pr_number
+            )));
+        }
 
-        let review = state
+        let checked_out = git.current_branch();
+        let mut parent_pr = None;
+        let mut pr_info = None;
+        let (pr, branch, base_branch) = match pr {
+            Some(pr) => {
+                let view = self.git.get_pr_view(pr).await?;
+                if checked_out.as_ref().is_some_and(|branch| *branch != view.head_branch) {
+                    tracing::warn!(
+                        "{} has {} checked out, but PR #{} is {}",
+                        worktree_path.display(),
+                        checked_out.as_deref().unwrap_or_default(),
+                        pr,
+                        view.head_branch
+                    );
+                }
+                let base = Some(view.base_branch).filter(|base| !base.is_empty());
+                if let Some(base) = &base {
+                    parent_pr = self.git.find_pr_for_branch(base).await.ok().flatten();
+                }
+                pr_info = Some(view.info);
+                (pr, checked_out.unwrap_or(view.head_branch), base)
+            }
+            None => {
+                let branch = checked_out.ok_or_else(|| {
+                    ChabaError::ConfigError(format!(
+                        "{} has a detached HEAD; pass --pr to adopt it for a pull request",
+                        worktree_path.display()
+                    ))
+                })?;
+                (Self::hash_branch_name(&branch), branch, Some(self.default_base().await))
+            }
+        };
+
+        let key = ReviewKey {
+            repo: Some(repo_root.clone()),
+            pr_number: pr,
+        };
+        if let Some(existing) = state.find_review(&key) {
+            return Err(ChabaError::ConfigError(format!(
+                "PR #{} already has a review environment at {}",
+                pr,
+                existing.worktree_path.display()
+            )));
+        }
+
+        let project_type = project::detect_project_type(&worktree_path)?;
+        let port_config = &self.config.sandbox.port;
+        let port = if port_config.enabled {
+            let manager = PortManager::new(port_config.range_start, port_config.range_end);
+            let assigned = match port::preferred_base(port_config.preferred, &worktree_path, &project_type) {
+                Some(base) => manager.assign_port_near(&state, base),
+                None => manager.assign_port(&state),
+            };
+            match assigned {
+                Ok(port) => Some(port),
+                Err(e) => {
+                    tracing::warn!("Failed to assign port: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let review = ReviewState {
+            pr_number: pr,
+            branch: branch.clone(),
+            base_branch,
+            parent_pr,
+            patch: None,
+            worktree_path: worktree_path.clone(),
+            sparse_paths: None,
+            pr_info,
+            repo: Some(repo_root.clone()),
+            created_at: Utc::now(),
+            port,
+            project_type: Some(project_type.as_string()),
+            deps_installed: false,
+            env_copied: false,
+            tests: None,
+            seed: None,
+            expires_at: None,
+            agent_analyses: Vec::new(),
+            analysis_commit: None,
+            analysis_history: Vec::new(),
+            analysis_interrupted: false,
+            agent_usage: Default::default(),
+        };
+
+        // Keep files chaba injects into the worktree out of git
+        let patterns = exclude::injected_patterns(&self.config.sandbox);
+        if let Err(e) = exclude::exclude_patterns(&worktree_path, &patterns) {
+            tracing::warn!("Failed to update git exclude file: {}", e);
+        }
+        if let Err(e) = ReviewMetadata::from_review(&review, &repo_root).write(&worktree_path).await {
+            tracing::warn!("Failed to write review metadata: {}", e);
+        }
+
+        state.add_review(review.clone())?;
+        events::record(&review.key(), EventKind::Adopted, branch);
+        workspace::update(&self.config.worktree, &state.reviews);
+
+        Ok(review)
+    }
+
+    /// Branch to compare non-PR reviews against
+    ///
+    /// Order: the repository's `chaba.defaultBase` git config, then
+    /// `worktree.default_base`, then the default branch of `origin`.
+    pub async fn default_base(&self) -> String {
+        if let Some(base) = self.git.config_string("chaba.defaultBase") {
+            return base;
+        }
+        if let Some(base) = &self.config.worktree.default_base {
+            return base.clone();
+        }
+        self.git.default_branch().await
+    }
+
+    /// Files changed by the review, used to pick sparse checkout directories
+    async fn changed_files(
+        &self,
+        start_point: &str,
+        base_branch: Option<&str>,
+        patch: Option<&Path>,
+        local: Option<&SnapshotCommit>,
+    ) -> Result<Vec<String>> {
+        if let Some(snapshot) = local {
+            return self
+                .git
+                .changed_files(&format!("{}..{}", snapshot.head, snapshot.commit))
+                .await;
+        }
+
+        if let Some(patch) = patch {
+            let content = tokio::fs::read_to_string(patch).await?;
+            return Ok(suggestions::split_hunks(&content)
+                .into_iter()
+                .map(|hunk| hunk.file)
+                .collect());
+        }
+
+        // The base branch was fetched along with the review branch
+        let base = match base_branch {
+            Some(base) => base.to_string(),
+            None => {
+                let base = self.default_base().await;
+                self.git.fetch_branch("origin", &base).await?;
+                base
+            }
+        };
+        self.git
+            .changed_files(&format!("origin/{}...{}", base, start_point))
+            .await
+    }
+
+    /// Remove a worktree
+    pub async fn remove(&self, pr_number: u32) -> Result<()> {
+        let review = State::load()?
             .get_review(pr_number)
             .ok_or(ChabaError::WorktreeNotFound(pr_number))?
             .clone();
+        self.remove_review(&review).await
+    }
 
+    /// Remove the worktree of a review from any repository
+    pub async fn remove_review(&self, review: &ReviewState) -> Result<()> {
         // Remove worktree
         tracing::info!("Removing worktree at: {}", review.worktree_path.display());
-        self.git.remove_worktree(&review.worktree_path).await?;
+        let git = GitOps::for_review(review)?;
+        git.remove_worktree(&review.worktree_path).await?;
+
+        Self::remove_snapshots(&git, &review.key()).await;
 
         // Remove from state
-        state.remove_review(pr_number)?;
+        let mut state = State::load()?;
+        state.remove_review(&review.key())?;
+        events::record(&review.key(), EventKind::CleanedUp, "");
+        if let Err(e) = archive::archive(review) {
+            tracing::warn!("Failed to archive review: {}", e);
+        }
+        workspace::update(&self.config.worktree, &state.reviews);
+        webhook::cleaned_up(&self.config, review).await;
 
         Ok(())
     }
 
+    /// Tear down a review's worktree and build it again from scratch
+    ///
+    /// The worktree is recreated at the same path from the same PR, branch,
+    /// or patch, and keeps its port when that is still free. Analyses,
+    /// triage, usage, and the TTL carry over. Uncommitted changes are lost,
+    /// so callers check for them first.
+    pub async fn recreate(&self, pr_number: u32) -> Result<ReviewState> {
+        let mut state = State::load()?;
+        let old = state
+            .get_review(pr_number)
+            .cloned()
+            .ok_or(ChabaError::WorktreeNotFound(pr_number))?;
+
+        let source = if old.is_pull_request() {
+            ReviewSource::Pr(pr_number)
+        } else if let Some(patch) = &old.patch {
+            ReviewSource::Patch {
+                path: patch.clone(),
+                base: old.base_branch.clone(),
+            }
+        } else if Self::is_local(&old) {
+            return Err(ChabaError::Other(anyhow::anyhow!(
+                "Local reviews can't be recreated; run `chaba review --local` again instead"
+            )));
+        } else {
+            ReviewSource::Branch(old.branch.clone())
+        };
+        if old.repo.as_ref().is_some_and(|repo| *repo != self.git.repo_root()) {
+            return Err(ChabaError::Other(anyhow::anyhow!(
+                "PR #{} was reviewed in {}; run chaba recreate from that repository",
+                pr_number,
+                old.repo.as_deref().unwrap_or(Path::new("")).display()
+            )));
+        }
+        if !platform::starts_with(&old.worktree_path.clean(), &self.config.worktree.base_dir.clean()) {
+            return Err(ChabaError::ConfigError(format!(
+                "{} is outside worktree.base_dir, so chaba can't rebuild it in place",
+                old.worktree_path.display()
+            )));
+        }
+
+        tracing::info!("Removing worktree at: {}", old.worktree_path.display());
+        if old.worktree_path.exists() {
+            self.git.remove_worktree(&old.worktree_path).await?;
+        }
+        if old.worktree_path.exists() {
+            tokio::fs::remove_dir_all(&old.worktree_path).await?;
+        }
+        self.git.prune_worktrees().await?;
+
+        // Off the state while rebuilding, so its port counts as free again
+        state.remove_review(&old.key())?;
+        let sparse = old.sparse_paths.as_ref().map(|_| Vec::new());
+        let built = self
+            .build(
+                source,
+                true,
+                Some(old.worktree_path.display().to_string()),
+                None,
+                sparse,
+                old.port,
+            )
+            .await;
+        let mut review = match built {
+            Ok(review) => review,
+            Err(e) => {
+                // Keep the analyses; `chaba list` shows the worktree as missing
+                State::load()?.add_review(old)?;
+                return Err(e);
+            }
+        };
+
+        let carry_over = |review: &mut ReviewState| {
+            review.created_at = old.created_at;
+            review.expires_at = old.expires_at;
+            review.agent_analyses = old.agent_analyses.clone();
+            review.analysis_commit = old.analysis_commit.clone();
+            review.analysis_history = old.analysis_history.clone();
+            review.agent_usage = old.agent_usage;
+        };
+        carry_over(&mut review);
+        State::load()?.update_review(&review.key(), carry_over)?;
+
+        Ok(review)
+    }
+
+    /// Save a review's worktree to a `chaba/backup/pr-<number>` branch
+    ///
+    /// The branch points at the worktree's HEAD, or at a commit of its
+    /// uncommitted and untracked files on top of HEAD when there are any, so
+    /// checking it out restores the worktree as it was. A timestamp is
+    /// appended when the PR already has a backup branch. The worktree itself
+    /// is untouched. Returns the branch name.
+    pub async fn backup(&self, review: &ReviewState) -> Result<String> {
+        let git = GitOps::for_review(review)?;
+        let mut branch = format!("chaba/backup/pr-{}", review.pr_number);
+        if git.has_reference(&format!("refs/heads/{}", branch)) {
+            branch = format!("{}-{}", branch, chrono::Local::now().format("%Y%m%d-%H%M%S"));
+        }
+        let reference = format!("refs/heads/{}", branch);
+
+        if git.has_uncommitted_changes(&review.worktree_path).await? {
+            git.snapshot_worktree(
+                &review.worktree_path,
+                &git.git_dir().join("chaba-backup.index"),
+                &reference,
+                &format!("chaba backup of PR #{} ({})", review.pr_number, review.branch),
+            )
+            .await?;
+        } else {
+            git.update_ref(&review.worktree_path, &reference, "HEAD").await?;
+        }
+        // A shared clone keeps the branch and its new commits to itself
+        if GitOps::is_shared_clone(&review.worktree_path) {
+            git.fetch_ref_from(&review.worktree_path, &reference).await?;
+        }
+        Ok(branch)
+    }
+
+    /// Stop tracking a review but keep its worktree on disk
+    ///
+    /// The worktree stays registered with git so it can be used manually.
+    /// Chaba's metadata file and snapshots are removed, and the port is freed.
+    pub async fn detach(&self, pr_number: u32) -> Result<ReviewState> {
+        let mut state = State::load()?;
+
+        let review = state
+            .get_review(pr_number)
+            .ok_or(ChabaError::WorktreeNotFound(pr_number))?
+            .clone();
+
+        let metadata_path = review.worktree_path.join(METADATA_FILE);
+        if metadata_path.exists() {
+            if let Err(e) = tokio::fs::remove_file(&metadata_path).await {
+                tracing::warn!("Failed to remove review metadata: {}", e);
+            }
+        }
+
+        match GitOps::for_review(&review) {
+            Ok(git) => Self::remove_snapshots(&git, &review.key()).await,
+            Err(e) => tracing::warn!("Failed to remove snapshots: {}", e),
+        }
+
+        state.remove_review(&review.key())?;
+        events::record(&review.key(), EventKind::Detached, review.worktree_path.display().to_string());
+        if let Err(e) = archive::archive(&review) {
+            tracing::warn!("Failed to archive review: {}", e);
+        }
+        workspace::update(&self.config.worktree, &state.reviews);
+
+        Ok(review)
+    }
+
+    /// Drop snapshots, which would otherwise keep refs alive in the repository
+    async fn remove_snapshots(git: &GitOps, key: &ReviewKey) {
+        match SnapshotStore::for_review(key) {
+            Ok(store) => {
+                if let Err(e) = store.remove_all(git).await {
+                    tracing::warn!("Failed to remove snapshots: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to remove snapshots: {}", e),
+        }
+    }
+
     /// List all active worktrees
     pub fn list(&self) -> Result<Vec<ReviewState>> {
         let state = State::load()?;
         Ok(state.reviews)
     }
 
+    /// List reviews whose TTL has passed
+    pub fn expired(&self) -> Result<Vec<ReviewState>> {
+        let now = Utc::now();
+        Ok(self.list()?.into_iter().filter(|r| r.is_expired(now)).collect())
+    }
+
+    /// Whether a review is of uncommitted local changes (`review --local`)
+    pub fn is_local(review: &ReviewState) -> bool {
+        review.patch.is_none() && review.pr_number == Self::hash_branch_name(&format!("local:{}", review.branch))
+    }
+
     /// Generate a pseudo-PR number from branch name for non-PR branches
     fn hash_branch_name(branch: &str) -> u32 {
         use std::collections::hash_map::DefaultHasher;
@@ -188,7 +852,7 @@ mod tests {
     #[test]
     fn test_hash_branch_name_range() {
         let pr = WorktreeManager::hash_branch_name("feature/test");
-        assert!(pr >= 90000 && pr < 100000, "Hash should be in range 90000-99999");
+        assert!((90000..100000).contains(&pr), "Hash should be in range 90000-99999");
     }
 
     #[test]
@@ -214,7 +878,7 @@ mod tests {
             #[test]
             fn test_hash_always_in_range(branch in "[a-zA-Z0-9/_-]{1,100}") {
                 let pr = WorktreeManager::hash_branch_name(&branch);
-                prop_assert!(pr >= 90000 && pr < 100000);
+                prop_assert!((90000..100000).contains(&pr));
             }
 
             #[test]
@@ -74,6 +74,9 @@ pub enum ChabaError {
     #[error("Worktree not found for PR #{0}")]
     WorktreeNotFound(u32),
 
+    #[error("PR #{0} already has a review environment. Clean it up or use --force to replace it.")]
+    ReviewExists(u32),
+
     #[error("Not in a git repository. Please run this command from within a git repository.")]
     NotInGitRepo,
 
@@ -102,6 +105,9 @@ pub enum ChabaError {
     #[error("State file was modified by another process. Expected version {expected}, but found {actual}. Please reload and try again.")]
     StateConflict { expected: u64, actual: u64 },
 
+    #[error("Interrupted by Ctrl-C")]
+    Interrupted,
+
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -53,6 +53,8 @@ pub mod commands;
 pub mod config;
 pub mod core;
 pub mod error;
+#[cfg(all(unix, any(test, feature = "test-util")))]
+pub mod test_support;
 
 // Re-export commonly used types
 pub use config::Config;
@@ -1,6 +1,24 @@
+use chaba::cli::output;
 use chaba::commands;
+use chaba::commands::cleanup::UnsavedWorkAction;
+use chaba::commands::list::{ListFilter, ListOptions, ListSort, OutputFormat};
+use chaba::commands::review::{BatchOptions, ReviewOptions};
+use chaba::commands::watch::WatchOptions;
+use chaba::core::cancel;
+use chaba::core::chaos::{self, ChaosSettings};
+use chaba::core::migrate::PathRewrite;
+use chaba::core::queue::PrFilter;
+use chaba::core::review_analysis::{parse_confidence, Category, FindingFilter, Severity, TriageStatus};
+use chaba::core::state::ReviewTarget;
+use chaba::core::statusline::StatuslineFormat;
+use chaba::core::trace;
+use chaba::core::ttl::parse_ttl;
+use chaba::error::ChabaError;
 use clap::{Parser, Subcommand};
 use std::process;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
 
 #[derive(Parser)]
 #[command(
@@ -16,20 +34,90 @@ struct Cli {
     /// Enable verbose logging
     #[arg(short, long, global = true)]
     verbose: bool,
+
+    /// Leave out banners, boxes and emoji, for scripts
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Also write debug-level logs to this file, whatever the console verbosity
+    #[arg(long, global = true, value_name = "PATH")]
+    log_file: Option<std::path::PathBuf>,
+
+    /// Run as if chaba was started in this repository (like `git -C`)
+    #[arg(long, global = true, value_name = "PATH")]
+    repo: Option<std::path::PathBuf>,
+
+    /// Randomly fail this fraction of external commands (resilience testing)
+    #[arg(long, global = true, hide = true, value_name = "RATE", value_parser = chaos::parse_rate)]
+    chaos: Option<f64>,
+
+    /// Seed for choosing which commands fail in chaos mode
+    #[arg(long, global = true, hide = true, requires = "chaos")]
+    chaos_seed: Option<u64>,
+
+    /// Log every external command to ~/.chaba/traces/ (view with `chaba trace show`)
+    #[arg(long, global = true)]
+    trace_commands: bool,
 }
 
+// `Review` carries many flags; the enum is parsed once, so its size doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
     /// Start a review environment for a PR or branch
     Review {
+        /// PR number or branch name, as a shorthand for --pr/--branch
+        #[arg(value_name = "PR|BRANCH", conflicts_with_all = ["pr", "branch", "patch", "local", "all_open"])]
+        target: Option<ReviewTarget>,
+
         /// Pull request number
-        #[arg(short, long, conflicts_with = "branch")]
+        #[arg(short, long, conflicts_with_all = ["branch", "patch", "local"])]
         pr: Option<u32>,
 
         /// Branch name
-        #[arg(short, long, conflicts_with = "pr")]
+        #[arg(short, long, conflicts_with_all = ["pr", "patch", "local"])]
         branch: Option<String>,
 
+        /// Review a patch/diff file applied on top of the base branch
+        #[arg(long, conflicts_with_all = ["pr", "branch", "local"])]
+        patch: Option<std::path::PathBuf>,
+
+        /// Branch to apply --patch on (defaults to the repository's default branch)
+        #[arg(long, requires = "patch", conflicts_with_all = ["pr", "branch", "local"])]
+        base: Option<String>,
+
+        /// Review uncommitted changes in the current repository before opening a PR
+        #[arg(long, conflicts_with_all = ["pr", "branch", "patch"])]
+        local: bool,
+
+        /// Create environments for every open PR (filter with --label, --author, --draft)
+        #[arg(long, conflicts_with_all = ["pr", "branch", "patch", "local", "worktree", "copy_session_from", "profile", "dry_run"])]
+        all_open: bool,
+
+        /// With --all-open: only PRs with this label (repeatable; all must match)
+        #[arg(long, requires = "all_open", conflicts_with_all = ["pr", "branch", "patch", "local"], value_name = "LABEL")]
+        label: Vec<String>,
+
+        /// With --all-open: only PRs by this author
+        #[arg(long, requires = "all_open", conflicts_with_all = ["pr", "branch", "patch", "local"], value_name = "LOGIN")]
+        author: Option<String>,
+
+        /// With --all-open: only draft PRs
+        #[arg(long, requires = "all_open", conflicts_with_all = ["pr", "branch", "patch", "local", "no_draft"])]
+        draft: bool,
+
+        /// With --all-open: skip draft PRs
+        #[arg(long, requires = "all_open", conflicts_with_all = ["pr", "branch", "patch", "local"])]
+        no_draft: bool,
+
+        /// With --all-open: how many environments to set up at once
+        #[arg(long, requires = "all_open", conflicts_with_all = ["pr", "branch", "patch", "local"], default_value_t = 2)]
+        jobs: usize,
+
+        /// Sparse-checkout only the directories the changes touch, plus any PATHs given
+        #[arg(long, num_args = 0.., value_name = "PATH")]
+        sparse: Option<Vec<String>>,
+
         /// Force creation even if worktree exists
         #[arg(short, long)]
         force: bool,
@@ -46,34 +134,179 @@ enum Commands {
         #[arg(long)]
         thorough: bool,
 
+        /// Run only this agent profile, e.g. security (repeatable; see agents.profiles)
+        #[arg(long = "profile", value_name = "NAME", conflicts_with = "thorough")]
+        profile: Vec<String>,
+
+        /// Wait for the PR's CI checks to pass before running agent analysis
+        #[arg(long, conflicts_with_all = ["branch", "patch", "local"])]
+        wait_ci: bool,
+
+        /// Print each agent's command line, working directory, and prompt instead of running it
+        #[arg(long)]
+        dry_run: bool,
+
         /// Copy Claude Code session data from source worktree path
         #[arg(long)]
         copy_session_from: Option<String>,
+
+        /// Remove the environment after this long (e.g. 30m, 48h, 7d)
+        #[arg(long, value_parser = parse_ttl)]
+        ttl: Option<chrono::Duration>,
     },
 
-    /// Clean up a review environment
+    /// Clean up a review environment (deletes worktree files and state)
     Cleanup {
-        /// Pull request number to clean up
-        #[arg(short, long)]
-        pr: u32,
+        /// PR number or branch name, as a shorthand for --pr
+        #[arg(value_name = "PR|BRANCH", conflicts_with_all = ["pr", "expired"])]
+        target: Option<ReviewTarget>,
+
+        /// Pull request number to clean up (defaults to the review worktree you are in)
+        #[arg(short, long, conflicts_with = "expired")]
+        pr: Option<u32>,
+
+        /// Clean up all environments whose TTL has passed
+        #[arg(long)]
+        expired: bool,
 
         /// Skip confirmation prompt (--force/--yes)
         #[arg(short, long, alias = "yes")]
         force: bool,
+
+        /// Remove worktrees even with uncommitted changes or local commits
+        #[arg(long, conflicts_with = "archive")]
+        discard_changes: bool,
+
+        /// Save each worktree to a chaba/backup/pr-<n> branch before removing it
+        #[arg(long, alias = "backup")]
+        archive: bool,
+    },
+
+    /// Stop tracking a review environment but keep its worktree files
+    Detach {
+        /// Pull request number
+        #[arg(short, long)]
+        pr: u32,
+    },
+
+    /// Tear down and rebuild a broken review environment, keeping its analyses
+    Recreate {
+        /// Pull request number
+        #[arg(short, long)]
+        pr: u32,
+
+        /// Rebuild even if the worktree has uncommitted changes or local commits
+        #[arg(long, conflicts_with = "archive")]
+        discard_changes: bool,
+
+        /// Save the worktree to a chaba/backup/pr-<n> branch before rebuilding
+        #[arg(long)]
+        archive: bool,
+    },
+
+    /// Track a worktree created with `git worktree add` as a review environment
+    Adopt {
+        /// Path to the worktree
+        path: std::path::PathBuf,
+
+        /// Pull request the worktree is for (default: tracked by its branch)
+        #[arg(short, long)]
+        pr: Option<u32>,
+    },
+
+    /// Search files across review worktrees (respects .gitignore)
+    Grep {
+        /// Extended regular expression to search for
+        pattern: String,
+
+        /// Only search this PR's worktree
+        #[arg(short, long)]
+        pr: Option<u32>,
+
+        /// Case-insensitive search
+        #[arg(short, long)]
+        ignore_case: bool,
     },
 
     /// List active review environments
-    List,
+    List {
+        /// Output format: table, json, tsv, alfred, or raycast
+        #[arg(long, visible_alias = "format", default_value = "table")]
+        output: OutputFormat,
+
+        /// Show the tokens and cost of agent runs per review, with a total
+        #[arg(long)]
+        costs: bool,
+
+        /// Sort flat instead of nesting stacked PRs: created (newest first), pr, or changes (largest first)
+        #[arg(long, value_name = "ORDER")]
+        sort: Option<ListSort>,
+
+        /// Only list matching reviews: status=ok|conflict|missing or project=<type>; repeatable
+        #[arg(long, value_name = "KEY=VALUE")]
+        filter: Vec<ListFilter>,
+    },
 
     /// Show status of a review environment
     Status {
-        /// Pull request number
+        /// PR number or branch name, as a shorthand for --pr
+        #[arg(value_name = "PR|BRANCH", conflicts_with = "pr")]
+        target: Option<ReviewTarget>,
+
+        /// Pull request number (defaults to the review worktree you are in)
         #[arg(short, long)]
-        pr: u32,
+        pr: Option<u32>,
+    },
+
+    /// Open a review worktree in the file manager, or in VS Code with --vscode
+    Open {
+        /// PR number or branch name, as a shorthand for --pr
+        #[arg(value_name = "PR|BRANCH", conflicts_with = "pr")]
+        target: Option<ReviewTarget>,
+
+        /// Pull request number (defaults to the review worktree you are in)
+        #[arg(short, long)]
+        pr: Option<u32>,
+
+        /// Generate a .code-workspace for the review and open it in VS Code
+        #[arg(long)]
+        vscode: bool,
+
+        /// Also write .vscode/launch.json with debug configurations for the review's port
+        #[arg(long, requires = "vscode")]
+        launch: bool,
+    },
+
+    /// Browse cleaned-up reviews and their findings
+    History {
+        /// Show the archived reviews of this PR with their findings
+        #[arg(short, long)]
+        pr: Option<u32>,
+    },
+
+    /// Summarize reviews, agent runs, and findings over a period, for team retros
+    Report {
+        /// Print the aggregate summary (reviews, agent runs, findings, time to cleanup, repositories)
+        #[arg(long, required = true)]
+        summary: bool,
+
+        /// Period to cover, counting back from now (e.g. 7d, 2w)
+        #[arg(long, value_parser = parse_ttl, default_value = "7d")]
+        since: chrono::Duration,
+    },
+
+    /// Print a one-line summary of reviews and running agents for tmux/zellij status bars
+    Statusline {
+        /// Color format: ansi, tmux, or plain
+        #[arg(long, default_value = "ansi")]
+        format: StatuslineFormat,
     },
 
     /// Initialize configuration
     Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+
         /// Initialize local config in current directory
         #[arg(short, long)]
         local: bool,
@@ -84,13 +317,147 @@ enum Commands {
         /// Pull request number
         #[arg(short, long)]
         pr: u32,
+
+        /// Hide findings less severe than this (critical, high, medium, low, info)
+        #[arg(long, value_name = "SEVERITY")]
+        min_severity: Option<Severity>,
+
+        /// Hide findings the agents are less confident about than this (0.0-1.0; defaults to agents.min_confidence)
+        #[arg(long, value_name = "CONFIDENCE", value_parser = parse_confidence)]
+        min_confidence: Option<f32>,
+
+        /// Only show these categories (e.g. security,performance)
+        #[arg(long, value_delimiter = ',', value_name = "CATEGORY")]
+        category: Vec<Category>,
+
+        /// Only show findings from these agents
+        #[arg(long, value_delimiter = ',', value_name = "AGENT")]
+        agent: Vec<String>,
+
+        /// Only show findings in files matching this glob (e.g. 'src/**'); repeatable
+        #[arg(long, value_name = "GLOB")]
+        file: Vec<glob::Pattern>,
+
+        /// Only show findings with these triage statuses (open, acknowledged, dismissed, fixed)
+        #[arg(long, value_delimiter = ',', value_name = "STATUS")]
+        status: Vec<TriageStatus>,
+
+        /// Show which findings are new, resolved, or persisting since the previous agent run
+        #[arg(long)]
+        diff: bool,
+
+        /// Print the full stdout/stderr of the latest agent runs (narrow down with --agent)
+        #[arg(long, conflicts_with = "diff")]
+        raw: bool,
+
+        /// Write the findings as a JUnit XML report for CI test-report UIs ('-' for stdout)
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["diff", "raw"])]
+        junit: Option<std::path::PathBuf>,
     },
 
-    /// Merge a branch into the worktree
-    Merge {
+    /// Triage agent findings: acknowledge, dismiss, or mark them fixed
+    Triage {
+        /// Pull request number
+        #[arg(short, long)]
+        pr: u32,
+
+        /// Finding ID to update (as shown by agent-result); prompts for each open finding when omitted
+        #[arg(long, requires = "set")]
+        id: Option<String>,
+
+        /// New status for --id (open, acknowledged, dismissed, fixed)
+        #[arg(long, requires = "id", value_name = "STATUS")]
+        set: Option<TriageStatus>,
+
+        /// Also prompt for acknowledged findings
+        #[arg(long, conflicts_with = "id")]
+        all: bool,
+    },
+
+    /// Approve or request changes based on agent findings
+    Verdict {
+        /// Pull request number
+        #[arg(short, long)]
+        pr: u32,
+
+        /// Submit the verdict to GitHub with `gh pr review`
+        #[arg(long)]
+        submit: bool,
+    },
+
+    /// Show setup, hook, and agent logs for a review environment
+    Logs {
+        /// Pull request number
+        #[arg(short, long)]
+        pr: u32,
+
+        /// Keep printing new log output as it is written
+        #[arg(short, long)]
+        follow: bool,
+    },
+
+    /// Show what happened to a review environment: creation, installs, agent runs, merges, cleanup
+    Events {
         /// Pull request number
         #[arg(short, long)]
         pr: u32,
+    },
+
+    /// Check a review's port before starting its dev server; reassign it if another process took it
+    Port {
+        /// Pull request number
+        #[arg(short, long)]
+        pr: u32,
+    },
+
+    /// List review ports: who holds them, conflicts with other processes, and free ports
+    Ports {
+        /// Release ports of reviews whose worktree was deleted by hand
+        #[arg(long)]
+        prune: bool,
+    },
+
+    /// Show agent quota usage and agent runs deferred by agents.quota
+    Queue,
+
+    /// Manage tokens stored in the OS keychain
+    Auth {
+        #[command(subcommand)]
+        action: AuthAction,
+    },
+
+    /// Check that git, gh, and the configured agents are installed and logged in
+    Doctor,
+
+    /// Encrypt state and snapshot env files at rest
+    Encrypt {
+        #[command(subcommand)]
+        action: EncryptAction,
+    },
+
+    /// Accept known findings so they stop showing up in reports
+    Baseline {
+        #[command(subcommand)]
+        action: BaselineAction,
+    },
+
+    /// Export or import tracked reviews, e.g. to move to a new machine
+    State {
+        #[command(subcommand)]
+        action: StateAction,
+    },
+
+    /// Inspect command traces recorded with --trace-commands
+    Trace {
+        #[command(subcommand)]
+        action: TraceAction,
+    },
+
+    /// Merge a branch into the worktree
+    Merge {
+        /// Pull request number (defaults to the review worktree you are in)
+        #[arg(short, long)]
+        pr: Option<u32>,
 
         /// Branch to merge from
         #[arg(short, long)]
@@ -99,52 +466,479 @@ enum Commands {
 
     /// Rebase the worktree onto another branch
     Rebase {
-        /// Pull request number
+        /// Pull request number (defaults to the review worktree you are in)
         #[arg(short, long)]
-        pr: u32,
+        pr: Option<u32>,
 
         /// Branch to rebase onto
         #[arg(short, long)]
         onto: String,
     },
 
+    /// Update worktrees with the latest changes from their remote branch
+    Sync {
+        /// Pull request number
+        #[arg(short, long, required_unless_present = "all", conflicts_with = "all")]
+        pr: Option<u32>,
+
+        /// Sync all review environments
+        #[arg(long)]
+        all: bool,
+
+        /// Stash uncommitted changes before syncing and restore them afterwards
+        #[arg(long)]
+        autostash: bool,
+    },
+
+    /// Keep PR reviews in sync: pull new commits, re-run agents, and remove merged/closed PRs and expired reviews
+    Watch {
+        /// Time between polls (e.g. 30m, 1h)
+        #[arg(long, value_parser = parse_ttl, default_value = "5m")]
+        interval: chrono::Duration,
+
+        /// Poll once and exit (e.g. from cron)
+        #[arg(long)]
+        once: bool,
+
+        /// Run agent analysis on new commits, also for reviews not analyzed before
+        #[arg(long)]
+        with_agent: bool,
+
+        /// Stash uncommitted changes while pulling and restore them afterwards
+        #[arg(long)]
+        autostash: bool,
+    },
+
+    /// Apply suggested diffs from a JSON file as staged changes
+    Apply {
+        /// Pull request number
+        #[arg(short, long)]
+        pr: u32,
+
+        /// JSON file with suggestions (chaba analysis output or {"suggestions": [...]})
+        #[arg(long)]
+        from: std::path::PathBuf,
+    },
+
+    /// Apply the suggested fix of an agent finding to the worktree
+    ApplyFix {
+        /// Pull request number
+        #[arg(short, long)]
+        pr: u32,
+
+        /// Finding ID (shown in brackets by agent-result)
+        #[arg(long, value_name = "ID")]
+        finding: String,
+
+        /// Show the fix and check that it applies, without changing the worktree
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Save the worktree state (including env files) so it can be rolled back
+    Snapshot {
+        /// Pull request number
+        #[arg(short, long)]
+        pr: u32,
+
+        /// Description of the snapshot
+        #[arg(short, long, conflicts_with = "list")]
+        message: Option<String>,
+
+        /// List existing snapshots instead of creating one
+        #[arg(long)]
+        list: bool,
+    },
+
+    /// Restore the worktree to a snapshot, discarding later changes
+    Rollback {
+        /// Pull request number
+        #[arg(short, long)]
+        pr: u32,
+
+        /// Snapshot ID (see 'chaba snapshot --list')
+        #[arg(long)]
+        to: String,
+
+        /// Skip confirmation prompt (--force/--yes)
+        #[arg(short, long, alias = "yes")]
+        force: bool,
+    },
+
+    /// Ask an AI agent a follow-up question about a review's findings
+    Ask {
+        /// Pull request number
+        #[arg(short, long)]
+        pr: u32,
+
+        /// The question, e.g. "why is finding 3 a problem?"
+        question: String,
+
+        /// Agent to ask (defaults to the default agent that reviewed the PR)
+        #[arg(long)]
+        agent: Option<String>,
+    },
+
+    /// Run AI agents on an existing review environment
+    Analyze {
+        /// Pull request number
+        #[arg(short, long)]
+        pr: u32,
+
+        /// Agents to run (comma-separated); their analyses replace only their own
+        #[arg(long, value_delimiter = ',', conflicts_with = "thorough")]
+        agents: Vec<String>,
+
+        /// Run all thorough agents
+        #[arg(long)]
+        thorough: bool,
+    },
+
+    /// Inspect, continue, or abort a merge/rebase stuck on conflicts
+    Resolve {
+        /// Pull request number
+        #[arg(short, long)]
+        pr: u32,
+
+        /// Abort the merge/rebase and restore the worktree
+        #[arg(long, conflicts_with_all = ["continue_", "with_agent"])]
+        abort: bool,
+
+        /// Continue the merge/rebase after staging resolved files
+        #[arg(long = "continue")]
+        continue_: bool,
+
+        /// Ask an AI agent to propose conflict resolutions
+        #[arg(long)]
+        with_agent: bool,
+    },
+
     /// Launch TUI (Terminal User Interface)
     Tui,
 }
 
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print the JSON Schema for chaba.yaml
+    Schema,
+}
+
+#[derive(Subcommand)]
+enum AuthAction {
+    /// Store a token (github, claude, codex, gemini, cursor-agent, copilot, or a custom name)
+    Set {
+        /// Token name
+        name: String,
+
+        /// Read the token from stdin instead of prompting
+        #[arg(long)]
+        stdin: bool,
+    },
+
+    /// Remove a stored token
+    Delete {
+        /// Token name
+        name: String,
+    },
+
+    /// Show which well-known tokens are stored
+    Status,
+}
+
+#[derive(Subcommand)]
+enum EncryptAction {
+    /// Generate a key and encrypt existing state and snapshot env files
+    Enable {
+        /// Protect the key with a passphrase instead of the OS keychain
+        #[arg(long)]
+        passphrase: bool,
+    },
+
+    /// Decrypt state and snapshot env files and remove the key
+    Disable,
+
+    /// Show whether encryption is enabled and which files are encrypted
+    Status,
+}
+
+#[derive(Subcommand)]
+enum BaselineAction {
+    /// Add a review's current findings to .chaba/baseline.yaml
+    Update {
+        /// Pull request number
+        #[arg(short, long)]
+        pr: u32,
+    },
+}
+
+#[derive(Subcommand)]
+enum StateAction {
+    /// Print all tracked reviews as JSON (e.g. `chaba state export > chaba-state.json`)
+    Export {
+        /// Also include cleaned-up reviews from the archive
+        #[arg(long)]
+        with_archive: bool,
+    },
+
+    /// Track the reviews of an export, rewriting paths for this machine
+    Import {
+        /// Export file, or '-' for stdin
+        file: std::path::PathBuf,
+
+        /// Move paths under OLD to NEW (e.g. /home/me/src=/Users/me/code); repeatable.
+        /// The exporting machine's home directory is rewritten to this one's automatically
+        #[arg(long, value_name = "OLD=NEW")]
+        rewrite: Vec<PathRewrite>,
+
+        /// Replace reviews of the same PR numbers that are already tracked
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum TraceAction {
+    /// List recorded traces, newest first
+    List,
+
+    /// Show the commands of a traced run
+    Show {
+        /// Run ID (defaults to the most recent trace)
+        run_id: Option<String>,
+    },
+}
+
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
 
     // Initialize tracing
     let log_level = if cli.verbose { "debug" } else { "info" };
-    tracing_subscriber::fmt()
-        .with_env_filter(log_level)
+    let console = tracing_subscriber::fmt::layer()
         .with_target(false)
-        .init();
+        .with_filter(EnvFilter::new(log_level));
+    let log_file = match &cli.log_file {
+        Some(path) => match std::fs::OpenOptions::new().create(true).append(true).open(path) {
+            Ok(file) => Some(
+                tracing_subscriber::fmt::layer()
+                    .with_ansi(false)
+                    .with_writer(std::sync::Mutex::new(file))
+                    .with_filter(EnvFilter::new("debug")),
+            ),
+            Err(e) => {
+                eprintln!("Error: Cannot open log file {}: {}", path.display(), e);
+                process::exit(1);
+            }
+        },
+        None => None,
+    };
+    tracing_subscriber::registry().with(console).with(log_file).init();
+    tracing::debug!("chaba {} started: {:?}", env!("CARGO_PKG_VERSION"), std::env::args().collect::<Vec<_>>());
+
+    output::set_quiet(cli.quiet);
+
+    // The repository's chaba.yaml and relative paths then resolve as they would inside it
+    if let Some(repo) = &cli.repo {
+        if let Err(e) = std::env::set_current_dir(repo) {
+            eprintln!("Error: Cannot use repository {}: {}", repo.display(), e);
+            process::exit(1);
+        }
+    }
+
+    if let Some(rate) = cli.chaos {
+        chaos::enable(ChaosSettings {
+            rate,
+            seed: cli.chaos_seed,
+        });
+    }
+
+    if cli.trace_commands {
+        match trace::enable(std::env::args().collect()) {
+            Ok(trace) => eprintln!("📝 Tracing commands to {} (run {})", trace.path().display(), trace.run_id()),
+            Err(e) => {
+                eprintln!("Error: Cannot start command trace: {}", e);
+                process::exit(1);
+            }
+        }
+    }
 
     let result = match cli.command {
         Commands::Review {
+            all_open: true,
+            label,
+            author,
+            draft,
+            no_draft,
+            jobs,
+            sparse,
+            force,
+            with_agent,
+            thorough,
+            wait_ci,
+            ttl,
+            ..
+        } => {
+            let draft = match (draft, no_draft) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            };
+            commands::review::execute_all_open(BatchOptions {
+                filter: PrFilter {
+                    labels: label,
+                    author,
+                    draft,
+                },
+                jobs,
+                sparse,
+                force,
+                with_agent,
+                thorough,
+                wait_ci,
+                ttl,
+            })
+            .await
+        }
+        Commands::Review {
+            target,
             pr,
             branch,
+            patch,
+            base,
+            local,
+            sparse,
             force,
             worktree,
             with_agent,
             thorough,
+            profile,
+            wait_ci,
+            dry_run,
             copy_session_from,
-        } => commands::review::execute(pr, branch, force, worktree, with_agent, thorough, copy_session_from).await,
-        Commands::Cleanup { pr, force } => commands::cleanup::execute(pr, force).await,
-        Commands::List => commands::list::execute().await,
-        Commands::Status { pr } => commands::status::execute(pr).await,
-        Commands::Config { local } => commands::config::execute(local).await,
-        Commands::AgentResult { pr } => commands::agent_result::execute(pr).await,
+            ttl,
+            ..
+        } => {
+            let (pr, branch) = match target {
+                Some(ReviewTarget::Pr(pr)) => (Some(pr), None),
+                Some(ReviewTarget::Branch(branch)) => (None, Some(branch)),
+                None => (pr, branch),
+            };
+            commands::review::execute(ReviewOptions {
+                pr,
+                branch,
+                patch,
+                base,
+                local,
+                sparse,
+                force,
+                worktree,
+                with_agent,
+                thorough,
+                profiles: profile,
+                wait_ci,
+                dry_run,
+                copy_session_from,
+                ttl,
+            })
+            .await
+        }
+        Commands::Cleanup { target, pr, expired, force, discard_changes, archive } => {
+            let unsaved = UnsavedWorkAction::from_flags(discard_changes, archive);
+            if expired {
+                commands::cleanup::execute_expired(force, unsaved).await
+            } else {
+                commands::cleanup::execute(target.or(pr.map(ReviewTarget::Pr)), force, unsaved).await
+            }
+        }
+        Commands::Detach { pr } => commands::detach::execute(pr).await,
+        Commands::Recreate { pr, discard_changes, archive } => {
+            commands::recreate::execute(pr, UnsavedWorkAction::from_flags(discard_changes, archive)).await
+        }
+        Commands::Adopt { path, pr } => commands::adopt::execute(&path, pr).await,
+        Commands::Grep { pattern, pr, ignore_case } => {
+            commands::grep::execute(pattern, pr, ignore_case).await
+        }
+        Commands::List { output, costs, sort, filter } => {
+            commands::list::execute(ListOptions { output, costs, sort, filters: filter }).await
+        }
+        Commands::Status { target, pr } => commands::status::execute(target.or(pr.map(ReviewTarget::Pr))).await,
+        Commands::Open { target, pr, vscode, launch } => {
+            commands::open::execute(target.or(pr.map(ReviewTarget::Pr)), vscode, launch).await
+        }
+        Commands::Statusline { format } => commands::statusline::execute(format).await,
+        Commands::History { pr } => commands::history::execute(pr).await,
+        Commands::Report { since, .. } => commands::report::execute(since).await,
+        Commands::Config { action, local } => match action {
+            Some(ConfigAction::Schema) => commands::config::schema().await,
+            None => commands::config::execute(local).await,
+        },
+        Commands::AgentResult { pr, agent, raw: true, .. } => commands::agent_result::raw(pr, &agent).await,
+        Commands::AgentResult { pr, min_severity, min_confidence, category, agent, file, status, diff, junit, .. } => {
+            let filter = FindingFilter {
+                min_severity,
+                min_confidence,
+                categories: category,
+                agents: agent,
+                files: file,
+                statuses: status,
+            };
+            match junit {
+                Some(path) => commands::agent_result::junit(pr, filter, &path).await,
+                None => commands::agent_result::execute(pr, filter, diff).await,
+            }
+        }
+        Commands::Triage { pr, id, set, all } => match (id, set) {
+            (Some(id), Some(status)) => commands::triage::set(pr, &id, status).await,
+            _ => commands::triage::execute(pr, all).await,
+        },
+        Commands::Verdict { pr, submit } => commands::verdict::execute(pr, submit).await,
+        Commands::Logs { pr, follow } => commands::logs::execute(pr, follow).await,
+        Commands::Events { pr } => commands::events::execute(pr).await,
+        Commands::Port { pr } => commands::port::execute(pr).await,
+        Commands::Ports { prune } => commands::port::list(prune).await,
+        Commands::Queue => commands::queue::execute().await,
+        Commands::Auth { action } => match action {
+            AuthAction::Set { name, stdin } => commands::auth::set(name, stdin).await,
+            AuthAction::Delete { name } => commands::auth::delete(name).await,
+            AuthAction::Status => commands::auth::status().await,
+        },
+        Commands::Doctor => commands::doctor::execute().await,
+        Commands::Encrypt { action } => match action {
+            EncryptAction::Enable { passphrase } => commands::encrypt::enable(passphrase).await,
+            EncryptAction::Disable => commands::encrypt::disable().await,
+            EncryptAction::Status => commands::encrypt::status().await,
+        },
+        Commands::Baseline { action } => match action {
+            BaselineAction::Update { pr } => commands::baseline::update(pr).await,
+        },
+        Commands::State { action } => match action {
+            StateAction::Export { with_archive } => commands::state::export(with_archive).await,
+            StateAction::Import { file, rewrite, force } => commands::state::import(&file, rewrite, force).await,
+        },
+        Commands::Trace { action } => match action {
+            TraceAction::List => commands::trace::list().await,
+            TraceAction::Show { run_id } => commands::trace::show(run_id).await,
+        },
         Commands::Merge { pr, from } => commands::merge::execute(pr, from).await,
         Commands::Rebase { pr, onto } => commands::rebase::execute(pr, onto).await,
+        Commands::Sync { pr, all, autostash } => commands::sync::execute(pr, all, autostash).await,
+        Commands::Watch { interval, once, with_agent, autostash } => {
+            commands::watch::execute(WatchOptions { interval, once, with_agent, autostash }).await
+        }
+        Commands::Apply { pr, from } => commands::apply::execute(pr, from).await,
+        Commands::ApplyFix { pr, finding, dry_run } => commands::apply_fix::execute(pr, finding, dry_run).await,
+        Commands::Snapshot { pr, message, list } => commands::snapshot::execute(pr, message, list).await,
+        Commands::Rollback { pr, to, force } => commands::rollback::execute(pr, to, force).await,
+        Commands::Ask { pr, question, agent } => commands::ask::execute(pr, question, agent).await,
+        Commands::Analyze { pr, agents, thorough } => commands::analyze::execute(pr, agents, thorough).await,
+        Commands::Resolve { pr, abort, continue_, with_agent } => {
+            commands::resolve::execute(pr, abort, continue_, with_agent).await
+        }
         Commands::Tui => commands::tui::execute().await,
     };
 
     if let Err(e) = result {
         eprintln!("Error: {}", e);
-        process::exit(1);
+        process::exit(if matches!(e, ChabaError::Interrupted) { cancel::EXIT_CODE } else { 1 });
     }
 }
@@ -0,0 +1,771 @@
+//! End-to-end scenario harness (requires the `test-util` feature)
+//!
+//! Runs the real `chaba` binary against a throwaway repository with no
+//! network access and no real AI CLIs:
+//!
+//! - [`FakeCommand`] writes a scripted executable (`gh`, `claude`, ...) that
+//!   logs every call and answers by matching its arguments
+//! - [`FakeGh`] and [`FakeAgent`] build those scripts for GitHub PRs and
+//!   agent findings
+//! - [`Scenario`] owns a temporary `HOME` and state directory, a `PATH` with
+//!   the fakes first, and a repository cloned from a local bare `origin`
+//!
+//! Setup failures panic, like any other test assertion. The fakes are POSIX
+//! shell scripts, so the module is only available on Unix.
+//!
+//! ```rust,no_run
+//! use chaba::core::review_analysis::{Category, Severity};
+//! use chaba::test_support::{FakeAgent, FakeGh, FakePr, Scenario};
+//! # fn example(chaba: &std::path::Path) {
+//!
+//! let scenario = Scenario::new();
+//! scenario.branch("feature/login", &[("login.rs", "fn login() {}\n")]);
+//! scenario.install(FakeGh::new().pr(FakePr::new(42, "feature/login")));
+//! scenario.install(
+//!     FakeAgent::new("claude").finding(Severity::High, Category::Security, "Token logged", "..."),
+//! );
+//!
+//! let status = std::process::Command::new(chaba)
+//!     .args(["review", "--pr", "42", "--with-agent"])
+//!     .current_dir(scenario.repo_path())
+//!     .envs(scenario.envs())
+//!     .status()
+//!     .unwrap();
+//! assert!(status.success());
+//! assert_eq!(scenario.state().reviews[0].agent_analyses.len(), 1);
+//! # }
+//! ```
+
+use serde_json::{json, Value};
+use std::ffi::OsString;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+use crate::core::review_analysis::{Category, Severity};
+use crate::core::state::{State, STATE_DIR_ENV};
+
+/// What a fake command prints and how it exits
+#[derive(Debug, Clone, Default)]
+struct Reply {
+    stdout: String,
+    stderr: String,
+    code: i32,
+    /// Keep running after printing, until killed
+    hang: bool,
+}
+
+/// A scripted stand-in for an external command
+///
+/// Each call is appended to `<name>.calls` and answered by the first rule
+/// whose pattern matches the space-joined arguments. Patterns are literal
+/// except for `*`, which matches anything. Calls matching no rule print an
+/// error and exit 1 unless [`FakeCommand::otherwise`] is set.
+#[derive(Debug, Clone)]
+pub struct FakeCommand {
+    name: String,
+    rules: Vec<(String, Reply)>,
+    fallback: Reply,
+}
+
+impl FakeCommand {
+    pub fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        let fallback = Reply {
+            stdout: String::new(),
+            stderr: format!("fake {}: no scripted response\n", name),
+            code: 1,
+            hang: false,
+        };
+        FakeCommand {
+            name,
+            rules: Vec::new(),
+            fallback,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Print `stdout` and succeed when the arguments match `pattern`
+    pub fn on(mut self, pattern: &str, stdout: impl Into<String>) -> Self {
+        self.rules.push((
+            pattern.to_string(),
+            Reply {
+                stdout: stdout.into(),
+                ..Reply::default()
+            },
+        ));
+        self
+    }
+
+    /// Print `stderr` and exit with `code` when the arguments match `pattern`
+    pub fn on_fail(mut self, pattern: &str, stderr: impl Into<String>, code: i32) -> Self {
+        self.rules.push((
+            pattern.to_string(),
+            Reply {
+                stderr: stderr.into(),
+                code,
+                ..Reply::default()
+            },
+        ));
+        self
+    }
+
+    /// Print `stdout` and succeed for calls matching no rule
+    pub fn otherwise(mut self, stdout: impl Into<String>) -> Self {
+        self.fallback = Reply {
+            stdout: stdout.into(),
+            ..Reply::default()
+        };
+        self
+    }
+
+    /// Exit with `code` and `stderr` for calls matching no rule
+    pub fn otherwise_fail(mut self, stderr: impl Into<String>, code: i32) -> Self {
+        self.fallback = Reply {
+            stdout: String::new(),
+            stderr: stderr.into(),
+            code,
+            hang: false,
+        };
+        self
+    }
+
+    /// Print `stdout` for calls matching no rule, then keep running until killed
+    ///
+    /// The process ID is written to `.<name>/pid` next to the script, see
+    /// [`Scenario::is_running`].
+    pub fn otherwise_hang(mut self, stdout: impl Into<String>) -> Self {
+        self.fallback = Reply {
+            stdout: stdout.into(),
+            hang: true,
+            ..Reply::default()
+        };
+        self
+    }
+
+    /// Write the script and its replies into `bin_dir`, logging calls to `log`
+    fn write(&self, bin_dir: &Path, log: &Path) {
+        let replies_dir = bin_dir.join(format!(".{}", self.name));
+        std::fs::create_dir_all(&replies_dir).expect("create fake reply directory");
+
+        // One append per call, so concurrent calls don't interleave their lines
+        let mut script = format!(
+            "#!/bin/sh\nprintf '%s\\n' \"$(printf '%s' \"$*\" | tr '\\n' ' ')\" >> {log}\ncase \"$*\" in\n",
+            log = shell_quote(&log.to_string_lossy())
+        );
+        let fallback = ("*".to_string(), self.fallback.clone());
+        for (index, (pattern, reply)) in self.rules.iter().chain(std::iter::once(&fallback)).enumerate() {
+            let stdout = replies_dir.join(format!("{}.out", index));
+            let stderr = replies_dir.join(format!("{}.err", index));
+            std::fs::write(&stdout, &reply.stdout).expect("write fake stdout");
+            std::fs::write(&stderr, &reply.stderr).expect("write fake stderr");
+
+            let exit = if reply.hang {
+                format!("echo $$ > {}; exec sleep 600", shell_quote(&replies_dir.join("pid").to_string_lossy()))
+            } else {
+                format!("exit {}", reply.code)
+            };
+            script.push_str(&format!(
+                "  {}) cat {}; cat {} >&2; {} ;;\n",
+                case_pattern(pattern),
+                shell_quote(&stdout.to_string_lossy()),
+                shell_quote(&stderr.to_string_lossy()),
+                exit
+            ));
+        }
+        script.push_str("esac\n");
+
+        let path = bin_dir.join(&self.name);
+        std::fs::write(&path, script).expect("write fake command");
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).expect("make fake command executable");
+    }
+}
+
+/// Quote `value` for a POSIX shell
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// A `case` pattern where only `*` is special
+fn case_pattern(pattern: &str) -> String {
+    pattern
+        .split('*')
+        .map(|literal| if literal.is_empty() { String::new() } else { shell_quote(literal) })
+        .collect::<Vec<_>>()
+        .join("*")
+}
+
+/// A PR served by [`FakeGh`]
+#[derive(Debug, Clone)]
+pub struct FakePr {
+    pub number: u32,
+    pub head_branch: String,
+    pub base_branch: String,
+    pub title: String,
+    pub author: String,
+    pub draft: bool,
+    pub labels: Vec<String>,
+    /// `OPEN`, `MERGED`, or `CLOSED`
+    pub state: String,
+    /// Reported as the PR's head commit by `gh pr view --json headRefOid`
+    pub head_sha: String,
+    /// `(name, bucket)` pairs for `gh pr checks`
+    pub checks: Vec<(String, String)>,
+}
+
+impl FakePr {
+    /// An open, non-draft PR from `head_branch` into `main`
+    pub fn new(number: u32, head_branch: impl Into<String>) -> Self {
+        FakePr {
+            number,
+            head_branch: head_branch.into(),
+            base_branch: "main".to_string(),
+            title: format!("PR {}", number),
+            author: "octocat".to_string(),
+            draft: false,
+            labels: Vec::new(),
+            state: "OPEN".to_string(),
+            head_sha: String::new(),
+            checks: Vec::new(),
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn author(mut self, author: impl Into<String>) -> Self {
+        self.author = author.into();
+        self
+    }
+
+    pub fn base(mut self, base_branch: impl Into<String>) -> Self {
+        self.base_branch = base_branch.into();
+        self
+    }
+
+    pub fn draft(mut self, draft: bool) -> Self {
+        self.draft = draft;
+        self
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.labels.push(label.into());
+        self
+    }
+
+    pub fn state(mut self, state: impl Into<String>) -> Self {
+        self.state = state.into();
+        self
+    }
+
+    pub fn head_sha(mut self, head_sha: impl Into<String>) -> Self {
+        self.head_sha = head_sha.into();
+        self
+    }
+
+    pub fn check(mut self, name: impl Into<String>, bucket: impl Into<String>) -> Self {
+        self.checks.push((name.into(), bucket.into()));
+        self
+    }
+
+    fn summary_json(&self) -> Value {
+        json!({
+            "number": self.number,
+            "title": self.title,
+            "author": { "login": self.author },
+            "isDraft": self.draft,
+            "labels": self.labels.iter().map(|name| json!({ "name": name })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// A fake `gh` that knows about a fixed set of PRs
+///
+/// Answers `pr view`, `pr checks`, `pr list --state open`, and
+/// `pr list --head`; PR URLs are `https://github.com/acme/app/pull/<n>`.
+/// Anything else fails like an unknown command.
+#[derive(Debug, Clone, Default)]
+pub struct FakeGh {
+    prs: Vec<FakePr>,
+}
+
+impl FakeGh {
+    pub fn new() -> Self {
+        FakeGh::default()
+    }
+
+    pub fn pr(mut self, pr: FakePr) -> Self {
+        self.prs.push(pr);
+        self
+    }
+
+    pub fn into_command(self) -> FakeCommand {
+        let mut command = FakeCommand::new("gh");
+
+        for pr in &self.prs {
+            let number = pr.number;
+            let status = json!({ "state": pr.state, "headRefOid": pr.head_sha });
+            let mut view = pr.summary_json();
+            view["baseRefName"] = json!(pr.base_branch);
+            view["headRefName"] = json!(pr.head_branch);
+            let checks: Vec<Value> = pr
+                .checks
+                .iter()
+                .map(|(name, bucket)| json!({ "name": name, "bucket": bucket, "link": "" }))
+                .collect();
+
+            command = command
+                .on(&format!("pr view {} --json state,*", number), status.to_string())
+                .on(
+                    &format!("pr view {} --json url *", number),
+                    format!("https://github.com/acme/app/pull/{}\n", number),
+                )
+                .on(&format!("pr view {} *", number), view.to_string())
+                .on(&format!("pr checks {} *", number), Value::from(checks).to_string())
+                .on(
+                    &format!("pr list --head {} *", pr.head_branch),
+                    format!("{}\n", number),
+                );
+        }
+
+        let open: Vec<Value> = self
+            .prs
+            .iter()
+            .filter(|pr| pr.state == "OPEN")
+            .map(FakePr::summary_json)
+            .collect();
+        command
+            .on("pr list --head *", "")
+            .on("pr list --state open *", Value::from(open).to_string())
+            .otherwise_fail("fake gh: unknown command\n", 1)
+    }
+}
+
+impl From<FakeGh> for FakeCommand {
+    fn from(gh: FakeGh) -> Self {
+        gh.into_command()
+    }
+}
+
+/// A fake AI agent CLI that reports a fixed set of findings as JSON
+#[derive(Debug, Clone)]
+pub struct FakeAgent {
+    name: String,
+    findings: Vec<Value>,
+    score: Option<f32>,
+    failure: Option<String>,
+    hang: bool,
+    usage: Option<(u64, f64)>,
+}
+
+impl FakeAgent {
+    /// A fake for `claude`, `codex`, or `gemini`
+    pub fn new(name: impl Into<String>) -> Self {
+        FakeAgent {
+            name: name.into(),
+            findings: Vec::new(),
+            score: None,
+            failure: None,
+            hang: false,
+            usage: None,
+        }
+    }
+
+    pub fn finding(
+        mut self,
+        severity: Severity,
+        category: Category,
+        title: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        // Agents spell multi-word categories with underscores
+        let category = serde_json::to_value(category)
+            .ok()
+            .and_then(|value| value.as_str().map(|s| s.replace('-', "_")))
+            .unwrap_or_default();
+        self.findings.push(json!({
+            "severity": severity,
+            "category": category,
+            "title": title.into(),
+            "description": description.into(),
+        }));
+        self
+    }
+
+    /// Point the last finding at `file:line`
+    pub fn at(mut self, file: impl Into<String>, line: u32) -> Self {
+        if let Some(finding) = self.findings.last_mut() {
+            finding["file"] = json!(file.into());
+            finding["line"] = json!(line);
+        }
+        self
+    }
+
+    /// Give the last finding a suggested fix
+    pub fn suggestion(mut self, suggestion: impl Into<String>) -> Self {
+        if let Some(finding) = self.findings.last_mut() {
+            finding["suggestion"] = json!(suggestion.into());
+        }
+        self
+    }
+
+    pub fn score(mut self, score: f32) -> Self {
+        self.score = Some(score);
+        self
+    }
+
+    /// Make the agent exit with an error instead of reporting findings
+    pub fn fails(mut self, stderr: impl Into<String>) -> Self {
+        self.failure = Some(stderr.into());
+        self
+    }
+
+    /// Reply with the CLI's native JSON output, reporting `tokens` and a cost
+    ///
+    /// `claude` prints a result object with the tokens and cost, `codex` prints
+    /// `--json` events with the tokens, and `gemini` prints a response object
+    /// with per-model token stats. Only `claude` reports the cost.
+    pub fn usage(mut self, tokens: u64, cost_usd: f64) -> Self {
+        self.usage = Some((tokens, cost_usd));
+        self
+    }
+
+    /// Make the agent print its findings and then never finish, like a stuck run
+    pub fn hangs(mut self) -> Self {
+        self.hang = true;
+        self
+    }
+
+    pub fn into_command(self) -> FakeCommand {
+        let command = FakeCommand::new(&self.name);
+        if let Some(stderr) = self.failure {
+            return command.otherwise_fail(stderr, 1);
+        }
+
+        let mut output = json!({ "findings": self.findings });
+        if let Some(score) = self.score {
+            output["score"] = json!(score);
+        }
+        let reply = output.to_string();
+        let output = match (self.usage, self.name.as_str()) {
+            (Some((tokens, cost_usd)), "claude") => json!({
+                "type": "result",
+                "result": reply,
+                "total_cost_usd": cost_usd,
+                "usage": { "input_tokens": tokens / 2, "output_tokens": tokens - tokens / 2 },
+            })
+            .to_string(),
+            (Some((tokens, _)), "codex") => [
+                json!({ "type": "thread.started", "thread_id": "fake" }),
+                json!({ "type": "item.completed", "item": { "type": "agent_message", "text": reply } }),
+                json!({
+                    "type": "turn.completed",
+                    "usage": { "input_tokens": tokens / 2, "output_tokens": tokens - tokens / 2 },
+                }),
+            ]
+            .iter()
+            .map(|event| format!("{}\n", event))
+            .collect(),
+            (Some((tokens, _)), "gemini") => serde_json::to_string_pretty(&json!({
+                "response": reply,
+                "stats": { "models": { "gemini-2.5-pro": { "tokens": { "total": tokens } } } },
+            }))
+            .unwrap(),
+            _ => reply,
+        };
+        if self.hang {
+            return command.otherwise_hang(output);
+        }
+        command.otherwise(output)
+    }
+}
+
+impl From<FakeAgent> for FakeCommand {
+    fn from(agent: FakeAgent) -> Self {
+        agent.into_command()
+    }
+}
+
+/// A sandboxed home, fake `PATH`, and repository for running `chaba`
+///
+/// The repository is cloned from a bare `origin` with a `main` branch, so
+/// branch fetches work without a network. Reviews are created under
+/// `<home>/reviews` and state lives in `<home>/.chaba`, as they would for a
+/// real user.
+pub struct Scenario {
+    _dir: TempDir,
+    home: PathBuf,
+    state_dir: PathBuf,
+    bin_dir: PathBuf,
+    origin: PathBuf,
+    repo: PathBuf,
+}
+
+impl Scenario {
+    pub fn new() -> Self {
+        let dir = TempDir::new().expect("create scenario directory");
+        // Canonical paths keep worktree paths comparable on macOS (/var -> /private/var)
+        let root = dir.path().canonicalize().expect("canonicalize scenario directory");
+        let home = root.join("home");
+        // Outside `HOME`, so a hardcoded `~/.chaba` anywhere shows up in tests
+        let state_dir = root.join("state");
+        let bin_dir = root.join("bin");
+        let origin = root.join("origin.git");
+        let repo = root.join("repo");
+        for path in [&home, &bin_dir, &repo] {
+            std::fs::create_dir_all(path).expect("create scenario directory");
+        }
+
+        let scenario = Scenario {
+            _dir: dir,
+            home,
+            state_dir,
+            bin_dir,
+            origin,
+            repo,
+        };
+
+        scenario.git(&root, &["init", "--bare", "--initial-branch=main", "origin.git"]);
+        scenario.git(&scenario.repo, &["init", "--initial-branch=main"]);
+        scenario.git(&scenario.repo, &["config", "user.name", "Test User"]);
+        scenario.git(&scenario.repo, &["config", "user.email", "test@example.com"]);
+        scenario.git(&scenario.repo, &["remote", "add", "origin", &scenario.origin.to_string_lossy()]);
+        scenario.commit(&[("README.md", "# Test Repository\n")], "Initial commit");
+        scenario.git(&scenario.repo, &["push", "-q", "origin", "main"]);
+
+        // Agents pass the login check of the preflight
+        for credentials in [".claude/.credentials.json", ".codex/auth.json", ".gemini/oauth_creds.json"] {
+            scenario.write_home(credentials, "{}");
+        }
+
+        scenario
+    }
+
+    /// Write a file under the scenario's home directory
+    pub fn write_home(&self, path: &str, contents: &str) {
+        let path = self.home.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).expect("create home directory");
+        std::fs::write(path, contents).expect("write home file");
+    }
+
+    pub fn home(&self) -> &Path {
+        &self.home
+    }
+
+    pub fn repo_path(&self) -> &Path {
+        &self.repo
+    }
+
+    /// Directory review worktrees are created in by default
+    pub fn reviews_dir(&self) -> PathBuf {
+        self.home.join("reviews")
+    }
+
+    /// Environment for running `chaba` inside the scenario
+    ///
+    /// Points `HOME` and the state directory at the sandbox and puts the
+    /// fakes first on `PATH`.
+    pub fn envs(&self) -> Vec<(OsString, OsString)> {
+        let mut path = OsString::from(&self.bin_dir);
+        if let Some(system) = std::env::var_os("PATH") {
+            path.push(":");
+            path.push(system);
+        }
+
+        vec![
+            ("HOME".into(), self.home.clone().into()),
+            ("XDG_CONFIG_HOME".into(), self.home.join(".config").into()),
+            (STATE_DIR_ENV.into(), self.state_dir.clone().into()),
+            ("PATH".into(), path),
+            ("GH_TOKEN".into(), "fake-token".into()),
+            ("NO_COLOR".into(), "1".into()),
+        ]
+    }
+
+    /// Install a fake command on the scenario's `PATH`, replacing any earlier one
+    pub fn install(&self, command: impl Into<FakeCommand>) {
+        let command = command.into();
+        command.write(&self.bin_dir, &self.calls_log(command.name()));
+    }
+
+    /// Arguments of every call made to the fake `name` so far
+    pub fn calls(&self, name: &str) -> Vec<String> {
+        std::fs::read_to_string(self.calls_log(name))
+            .map(|log| log.lines().map(|line| line.trim_end().to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Whether a hanging fake `name` (see [`FakeCommand::otherwise_hang`]) is still running
+    pub fn is_running(&self, name: &str) -> bool {
+        let Ok(pid) = std::fs::read_to_string(self.bin_dir.join(format!(".{}", name)).join("pid")) else {
+            return false;
+        };
+        Command::new("kill")
+            .args(["-0", pid.trim()])
+            .stderr(std::process::Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+
+    fn calls_log(&self, name: &str) -> PathBuf {
+        self.bin_dir.join(format!("{}.calls", name))
+    }
+
+    /// Write the user configuration `chaba` loads
+    pub fn config(&self, yaml: &str) {
+        let dir = if cfg!(target_os = "macos") {
+            self.home.join("Library").join("Application Support").join("chaba")
+        } else {
+            self.home.join(".config").join("chaba")
+        };
+        std::fs::create_dir_all(&dir).expect("create config directory");
+        std::fs::write(dir.join("chaba.yaml"), yaml).expect("write config");
+    }
+
+    /// Create `name` from `main` with `files` committed and push it to `origin`
+    ///
+    /// Returns the branch's head commit.
+    pub fn branch(&self, name: &str, files: &[(&str, &str)]) -> String {
+        self.git(&self.repo, &["checkout", "-q", "-b", name, "main"]);
+        self.commit(files, &format!("Changes on {}", name));
+        self.git(&self.repo, &["push", "-q", "origin", name]);
+        let head = self.git(&self.repo, &["rev-parse", "HEAD"]);
+        self.git(&self.repo, &["checkout", "-q", "main"]);
+        head
+    }
+
+    /// Push another commit with `files` to an existing branch on `origin`
+    ///
+    /// The commit is made in a scratch clone, like a push by the PR author.
+    /// Returns the new head commit.
+    pub fn push(&self, branch: &str, files: &[(&str, &str)]) -> String {
+        let clone = self._dir.path().join(format!("push-{}", branch.replace('/', "-")));
+        if !clone.exists() {
+            self.git(
+                self._dir.path(),
+                &["clone", "-q", &self.origin.to_string_lossy(), &clone.to_string_lossy()],
+            );
+            self.git(&clone, &["config", "user.name", "PR Author"]);
+            self.git(&clone, &["config", "user.email", "author@example.com"]);
+        }
+        self.git(&clone, &["fetch", "-q", "origin"]);
+        self.git(&clone, &["checkout", "-q", "-B", branch, &format!("origin/{}", branch)]);
+        for (path, contents) in files {
+            write_file(&clone.join(path), contents);
+        }
+        self.git(&clone, &["add", "-A"]);
+        self.git(&clone, &["commit", "-q", "-m", &format!("Update {}", branch)]);
+        self.git(&clone, &["push", "-q", "origin", branch]);
+        self.git(&clone, &["rev-parse", "HEAD"])
+    }
+
+    /// Commit `files` on the repository's current branch
+    pub fn commit(&self, files: &[(&str, &str)], message: &str) {
+        for (path, contents) in files {
+            write_file(&self.repo.join(path), contents);
+        }
+        self.git(&self.repo, &["add", "-A"]);
+        self.git(&self.repo, &["commit", "-q", "-m", message]);
+    }
+
+    /// Directory chaba keeps its state, logs, and archive in
+    pub fn state_dir(&self) -> &Path {
+        &self.state_dir
+    }
+
+    /// Chaba's saved state
+    pub fn state(&self) -> State {
+        let path = self.state_dir().join("state.yaml");
+        match std::fs::read_to_string(&path) {
+            Ok(content) => serde_yaml::from_str(&content).expect("parse state.yaml"),
+            Err(_) => State::default(),
+        }
+    }
+
+    /// Run git in `dir`, returning its trimmed stdout
+    fn git(&self, dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .env("HOME", &self.home)
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .output()
+            .expect("run git");
+        assert!(
+            output.status.success(),
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Scenario::new()
+    }
+}
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).expect("create fixture directory");
+    }
+    std::fs::write(path, contents).expect("write fixture file");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_pattern() {
+        assert_eq!(case_pattern("pr view 42 *"), "'pr view 42 '*");
+        assert_eq!(case_pattern("*"), "*");
+        assert_eq!(case_pattern("it's"), r"'it'\''s'");
+    }
+
+    #[test]
+    fn test_fake_command_replies_and_logs_calls() {
+        let scenario = Scenario::new();
+        scenario.install(
+            FakeCommand::new("tool")
+                .on("greet *", "hello\n")
+                .on_fail("break", "broken\n", 3),
+        );
+        let run = |args: &[&str]| {
+            Command::new("tool")
+                .args(args)
+                .envs(scenario.envs())
+                .output()
+                .unwrap()
+        };
+
+        let greet = run(&["greet", "world"]);
+        assert!(greet.status.success());
+        assert_eq!(String::from_utf8_lossy(&greet.stdout), "hello\n");
+
+        let broken = run(&["break"]);
+        assert_eq!(broken.status.code(), Some(3));
+        assert_eq!(String::from_utf8_lossy(&broken.stderr), "broken\n");
+
+        assert_eq!(run(&["other"]).status.code(), Some(1));
+        assert_eq!(scenario.calls("tool"), vec!["greet world", "break", "other"]);
+    }
+
+    #[test]
+    fn test_branch_is_pushed_to_origin() {
+        let scenario = Scenario::new();
+        let head = scenario.branch("feature/x", &[("src/x.rs", "fn x() {}\n")]);
+        let pushed = scenario.push("feature/x", &[("src/x.rs", "fn x() { todo!() }\n")]);
+
+        assert_ne!(head, pushed);
+        assert_eq!(scenario.git(&scenario.origin, &["rev-parse", "feature/x"]), pushed);
+    }
+}
@@ -44,7 +44,9 @@ fn test_review_command_help() {
         .stdout(predicate::str::contains("--force"))
         .stdout(predicate::str::contains("--worktree"))
         .stdout(predicate::str::contains("--with-agent"))
-        .stdout(predicate::str::contains("--thorough"));
+        .stdout(predicate::str::contains("--thorough"))
+        .stdout(predicate::str::contains("--wait-ci"))
+        .stdout(predicate::str::contains("--ttl"));
 }
 
 #[test]
@@ -56,7 +58,8 @@ fn test_cleanup_command_help() {
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("Clean up a review environment"))
-        .stdout(predicate::str::contains("--pr"));
+        .stdout(predicate::str::contains("--pr"))
+        .stdout(predicate::str::contains("--expired"));
 }
 
 #[test]
@@ -94,6 +97,18 @@ fn test_config_command_help() {
         .stdout(predicate::str::contains("--local"));
 }
 
+#[test]
+fn test_config_schema_command() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("config").arg("schema");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"$schema\""))
+        .stdout(predicate::str::contains("\"worktree\""));
+}
+
 #[test]
 fn test_agent_result_command_help() {
     let mut cmd = cargo::cargo_bin_cmd!("chaba");
@@ -106,6 +121,349 @@ fn test_agent_result_command_help() {
         .stdout(predicate::str::contains("--pr"));
 }
 
+#[test]
+fn test_agent_result_invalid_filters() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+    cmd.args(["agent-result", "--pr", "1", "--min-severity", "urgent"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown severity 'urgent'"));
+
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+    cmd.args(["agent-result", "--pr", "1", "--category", "security,style"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown category 'style'"));
+}
+
+#[test]
+fn test_chaos_flag_is_hidden_and_validated() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+    cmd.arg("--help");
+    cmd.assert().success().stdout(predicate::str::contains("--chaos").not());
+
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+    cmd.args(["--chaos", "2", "list"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("expected a number between 0 and 1"));
+}
+
+#[test]
+fn test_triage_set_requires_id() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+    cmd.args(["triage", "--pr", "1", "--set", "dismissed"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--id"));
+
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+    cmd.args(["triage", "--pr", "1", "--id", "abc", "--set", "done"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown triage status 'done'"));
+}
+
+#[test]
+fn test_verdict_command_help() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("verdict").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Approve or request changes"))
+        .stdout(predicate::str::contains("--submit"));
+}
+
+#[test]
+fn test_logs_command_help() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("logs").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Show setup, hook, and agent logs"))
+        .stdout(predicate::str::contains("--pr"))
+        .stdout(predicate::str::contains("--follow"));
+}
+
+#[test]
+fn test_auth_command_help() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("auth").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Manage tokens stored in the OS keychain"))
+        .stdout(predicate::str::contains("set"))
+        .stdout(predicate::str::contains("delete"))
+        .stdout(predicate::str::contains("status"));
+}
+
+#[test]
+fn test_baseline_update_missing_pr() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["baseline", "update"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--pr <PR>"));
+}
+
+#[test]
+fn test_port_command_missing_pr() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("port");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--pr <PR>"));
+}
+
+#[test]
+fn test_apply_fix_command_missing_finding() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["apply-fix", "--pr", "123"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--finding <ID>"));
+}
+
+#[test]
+fn test_ask_command_missing_question() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["ask", "--pr", "123"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("<QUESTION>"));
+}
+
+#[test]
+fn test_trace_command_help() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("trace").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Inspect command traces recorded with --trace-commands"))
+        .stdout(predicate::str::contains("list"))
+        .stdout(predicate::str::contains("show"));
+}
+
+#[test]
+fn test_sync_command_help() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("sync").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Update worktrees with the latest changes"))
+        .stdout(predicate::str::contains("--pr"))
+        .stdout(predicate::str::contains("--all"))
+        .stdout(predicate::str::contains("--autostash"));
+}
+
+#[test]
+fn test_sync_command_missing_pr() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("sync");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("required"));
+}
+
+#[test]
+fn test_review_patch_conflicts_with_pr() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["review", "--patch", "fix.patch", "--pr", "1"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_review_local_conflicts_with_branch() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["review", "--local", "--branch", "feature"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_review_wait_ci_conflicts_with_branch() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["review", "--branch", "feature", "--wait-ci"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_review_all_open_conflicts_with_pr() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["review", "--all-open", "--pr", "123"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_review_label_requires_all_open() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["review", "--label", "bug"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--all-open"));
+
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["review", "--pr", "123", "--label", "bug"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_review_base_requires_patch() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["review", "--branch", "feature", "--base", "main"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_apply_command_help() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("apply").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Apply suggested diffs"))
+        .stdout(predicate::str::contains("--from"));
+}
+
+#[test]
+fn test_snapshot_command_help() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("snapshot").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("--message"))
+        .stdout(predicate::str::contains("--list"));
+}
+
+#[test]
+fn test_rollback_command_missing_snapshot() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["rollback", "--pr", "1"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--to"));
+}
+
+#[test]
+fn test_detach_command_help() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("detach").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("keep its worktree files"))
+        .stdout(predicate::str::contains("--pr"));
+}
+
+#[test]
+fn test_grep_command_missing_pattern() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("grep");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("<PATTERN>"));
+}
+
+#[test]
+fn test_statusline_command_help() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("statusline").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("status bars"))
+        .stdout(predicate::str::contains("--format"));
+}
+
+#[test]
+fn test_statusline_command_invalid_format() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("statusline").arg("--format").arg("html");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("expected ansi, tmux, or plain"));
+}
+
+#[test]
+fn test_resolve_command_help() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("resolve").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("--abort"))
+        .stdout(predicate::str::contains("--continue"))
+        .stdout(predicate::str::contains("--with-agent"));
+}
+
+#[test]
+fn test_resolve_abort_conflicts_with_continue() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["resolve", "--pr", "1", "--abort", "--continue"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
 #[test]
 fn test_review_command_missing_args() {
     let mut cmd = cargo::cargo_bin_cmd!("chaba");
@@ -118,25 +476,37 @@ fn test_review_command_missing_args() {
 }
 
 #[test]
-fn test_cleanup_command_missing_pr() {
+fn test_cleanup_command_outside_review_worktree() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
     let mut cmd = cargo::cargo_bin_cmd!("chaba");
 
-    cmd.arg("cleanup");
+    // Without --pr the review is found from the current directory
+    cmd.current_dir(temp_dir.path())
+        .env("CHABA_STATE_DIR", temp_dir.path().join("state"))
+        .arg("cleanup");
 
     cmd.assert()
         .failure()
-        .stderr(predicate::str::contains("required"));
+        .stderr(predicate::str::contains("not inside a review worktree"));
 }
 
 #[test]
-fn test_status_command_missing_pr() {
+fn test_status_command_outside_review_worktree() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
     let mut cmd = cargo::cargo_bin_cmd!("chaba");
 
-    cmd.arg("status");
+    // Without --pr the review is found from the current directory
+    cmd.current_dir(temp_dir.path())
+        .env("CHABA_STATE_DIR", temp_dir.path().join("state"))
+        .arg("status");
 
     cmd.assert()
         .failure()
-        .stderr(predicate::str::contains("required"));
+        .stderr(predicate::str::contains("not inside a review worktree"));
 }
 
 #[test]
@@ -150,6 +520,50 @@ fn test_agent_result_command_missing_pr() {
         .stderr(predicate::str::contains("required"));
 }
 
+#[test]
+fn test_review_invalid_ttl() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("review").arg("--pr").arg("123").arg("--ttl").arg("soon");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid TTL"));
+}
+
+#[test]
+fn test_cleanup_pr_and_expired_conflict() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("cleanup").arg("--pr").arg("123").arg("--expired");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used"));
+}
+
+#[test]
+fn test_positional_target_and_pr_conflict() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["status", "123", "--pr", "456"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used"));
+}
+
+#[test]
+fn test_open_launch_requires_vscode() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["open", "123", "--launch"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--vscode"));
+}
+
 #[test]
 fn test_review_pr_and_branch_conflict() {
     let mut cmd = cargo::cargo_bin_cmd!("chaba");
@@ -175,6 +589,104 @@ fn test_list_command_basic() {
     cmd.assert().success();
 }
 
+#[test]
+fn test_list_command_outside_repository() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    // Listing only reads state, so it works from anywhere
+    cmd.current_dir(temp_dir.path()).args(["list", "--output", "json"]);
+
+    cmd.assert().success();
+}
+
+#[test]
+fn test_repo_option_missing_directory() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["--repo", "/nonexistent/chaba-repo", "list"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Cannot use repository /nonexistent/chaba-repo"));
+}
+
+#[test]
+fn test_repo_option_not_a_repository() {
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("--repo").arg(temp_dir.path()).args(["review", "--branch", "feature"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Not in a git repository"));
+}
+
+#[test]
+fn test_watch_command_help() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("watch").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("--interval"))
+        .stdout(predicate::str::contains("--once"));
+}
+
+#[test]
+fn test_watch_invalid_interval() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["watch", "--interval", "soon"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid TTL"));
+}
+
+#[test]
+fn test_list_command_invalid_output() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.arg("list").arg("--output").arg("xml");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("expected table, json, tsv, alfred, or raycast"));
+}
+
+#[test]
+fn test_list_command_invalid_sort_and_filter() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+    cmd.args(["list", "--sort", "size"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("expected created, pr, or changes"));
+
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+    cmd.args(["list", "--filter", "status=stale"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("expected ok, conflict, or missing"));
+}
+
+#[test]
+fn test_list_command_tsv_header() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["list", "--format", "tsv", "--sort", "pr", "--filter", "status=missing"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("pr\tbranch\tcreated\tstatus\t"));
+}
+
 #[test]
 fn test_config_command_local() {
     use tempfile::TempDir;
@@ -211,3 +723,14 @@ fn test_invalid_command() {
         .failure()
         .stderr(predicate::str::contains("unrecognized").or(predicate::str::contains("invalid")));
 }
+
+#[test]
+fn test_report_requires_summary() {
+    let mut cmd = cargo::cargo_bin_cmd!("chaba");
+
+    cmd.args(["report", "--since", "7d"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--summary"));
+}
@@ -8,23 +8,23 @@ fn test_config_default() {
 
     // Worktree config
     assert_eq!(config.worktree.naming_template, "pr-{pr}");
-    assert_eq!(config.worktree.auto_cleanup, true);
+    assert!(config.worktree.auto_cleanup);
     assert_eq!(config.worktree.keep_days, 7);
 
     // Sandbox config
-    assert_eq!(config.sandbox.auto_install_deps, true);
-    assert_eq!(config.sandbox.copy_env_from_main, true);
+    assert!(config.sandbox.auto_install_deps);
+    assert!(config.sandbox.copy_env_from_main);
     assert_eq!(config.sandbox.node.package_manager, "auto");
-    assert_eq!(config.sandbox.port.enabled, true);
+    assert!(config.sandbox.port.enabled);
     assert_eq!(config.sandbox.port.range_start, 3000);
     assert_eq!(config.sandbox.port.range_end, 4000);
 
     // Agents config
-    assert_eq!(config.agents.enabled, true);
+    assert!(config.agents.enabled);
     assert_eq!(config.agents.default_agents, vec!["claude"]);
     assert_eq!(config.agents.thorough_agents, vec!["claude", "codex", "gemini"]);
     assert_eq!(config.agents.timeout, 600);
-    assert_eq!(config.agents.parallel, true);
+    assert!(config.agents.parallel);
 }
 
 #[test]
@@ -84,22 +84,22 @@ agents:
     let config: Config = serde_yaml::from_str(yaml).unwrap();
 
     assert_eq!(config.worktree.naming_template, "review-{pr}");
-    assert_eq!(config.worktree.auto_cleanup, false);
+    assert!(!config.worktree.auto_cleanup);
     assert_eq!(config.worktree.keep_days, 14);
 
-    assert_eq!(config.sandbox.auto_install_deps, false);
-    assert_eq!(config.sandbox.copy_env_from_main, false);
+    assert!(!config.sandbox.auto_install_deps);
+    assert!(!config.sandbox.copy_env_from_main);
     assert_eq!(config.sandbox.additional_env_files, vec![".env.production"]);
     assert_eq!(config.sandbox.node.package_manager, "pnpm");
-    assert_eq!(config.sandbox.port.enabled, false);
+    assert!(!config.sandbox.port.enabled);
     assert_eq!(config.sandbox.port.range_start, 4000);
     assert_eq!(config.sandbox.port.range_end, 5000);
 
-    assert_eq!(config.agents.enabled, false);
+    assert!(!config.agents.enabled);
     assert_eq!(config.agents.default_agents, vec!["codex"]);
     assert_eq!(config.agents.thorough_agents, vec!["claude", "codex"]);
     assert_eq!(config.agents.timeout, 300);
-    assert_eq!(config.agents.parallel, false);
+    assert!(!config.agents.parallel);
 }
 
 #[test]
@@ -113,13 +113,13 @@ worktree:
     let config: Config = serde_yaml::from_str(yaml).unwrap();
 
     assert_eq!(config.worktree.naming_template, "custom-{pr}");
-    assert_eq!(config.worktree.auto_cleanup, true); // default
+    assert!(config.worktree.auto_cleanup); // default
 
     // Sandbox should use defaults
-    assert_eq!(config.sandbox.auto_install_deps, true);
+    assert!(config.sandbox.auto_install_deps);
 
     // Agents should use defaults
-    assert_eq!(config.agents.enabled, true);
+    assert!(config.agents.enabled);
 }
 
 #[test]
@@ -131,7 +131,7 @@ fn test_config_load_nonexistent() {
     let config = Config::load().unwrap();
 
     assert_eq!(config.worktree.naming_template, "pr-{pr}");
-    assert_eq!(config.agents.enabled, true);
+    assert!(config.agents.enabled);
 }
 
 #[test]
@@ -153,3 +153,15 @@ fn test_config_save_and_load() {
     assert_eq!(loaded.worktree.naming_template, "test-{pr}");
     assert_eq!(loaded.agents.timeout, 1200);
 }
+
+#[test]
+fn test_config_json_schema() {
+    let schema = Config::json_schema();
+    let value: serde_json::Value = serde_json::from_str(&schema).unwrap();
+
+    assert_eq!(value["title"], "Config");
+    assert!(value["properties"]["worktree"].is_object());
+    assert!(value["properties"]["sandbox"].is_object());
+    assert!(value["properties"]["agents"].is_object());
+    assert!(value["definitions"]["PortConfig"]["properties"]["range_start"].is_object());
+}
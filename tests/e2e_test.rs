@@ -1,7 +1,7 @@
-/// End-to-End tests for Chaba
-///
-/// These tests verify the complete workflow with real git operations,
-/// actual file system interactions, and full integration between components.
+//! End-to-End tests for Chaba
+//!
+//! These tests verify the complete workflow with real git operations,
+//! actual file system interactions, and full integration between components.
 
 use std::path::PathBuf;
 use tempfile::TempDir;
@@ -185,19 +185,20 @@ fn test_e2e_port_assignment() {
 
     // Test: Assign port
     let port = port_manager.assign_port(&state).unwrap();
-    assert!(port >= 50000 && port <= 50100);
+    assert!((50000..=50100).contains(&port));
 
     // Test: Assign multiple ports (should get same port as no state changes)
     let mut used_ports = vec![port];
     for _ in 0..5 {
         let next_port = port_manager.assign_port(&state).unwrap();
-        assert!(next_port >= 50000 && next_port <= 50100);
+        assert!((50000..=50100).contains(&next_port));
         used_ports.push(next_port);
     }
 }
 
 #[tokio::test]
 async fn test_e2e_env_file_detection() {
+    use chaba::config::EnvCopyConfig;
     use chaba::core::env::copy_env_files;
 
     let temp_dir = TempDir::new().unwrap();
@@ -218,7 +219,13 @@ async fn test_e2e_env_file_detection() {
     std::fs::write(main_path.join(".env.local"), "LOCAL=true\n").unwrap();
 
     // Test: Copy env files
-    let result = copy_env_files(&main_path, &review_path, &[".env.local".to_string()]).await;
+    let result = copy_env_files(
+        &main_path,
+        &review_path,
+        &[".env.local".to_string()],
+        &EnvCopyConfig::default(),
+    )
+    .await;
     assert!(result.is_ok());
 
     // Verify files were copied
@@ -243,6 +250,7 @@ fn test_e2e_config_validation() {
         enabled: true,
         range_start: 80,
         range_end: 100,
+        preferred: None,
     };
     assert!(invalid_port.validate().is_err());
 
@@ -251,31 +259,45 @@ fn test_e2e_config_validation() {
         enabled: true,
         range_start: 3000,
         range_end: 4000,
+        preferred: None,
     };
     assert!(valid_port.validate().is_ok());
 }
 
 #[test]
 fn test_e2e_state_persistence() {
-    use chaba::core::state::{ReviewState, State};
+    use chaba::core::state::{ReviewState, State, STATE_DIR_ENV};
     use chrono::Utc;
 
     let temp_dir = TempDir::new().unwrap();
 
-    // Override home directory for testing
-    std::env::set_var("HOME", temp_dir.path());
+    // Keep state out of the real home directory
+    std::env::set_var(STATE_DIR_ENV, temp_dir.path());
 
     let mut state = State::default();
     let review = ReviewState {
         pr_number: 999,
         branch: "test/branch".to_string(),
+        base_branch: None,
+        patch: None,
+        parent_pr: None,
         worktree_path: temp_dir.path().join("worktree"),
+        sparse_paths: None,
+        pr_info: None,
+        repo: None,
         created_at: Utc::now(),
         port: Some(3000),
         project_type: Some("node".to_string()),
         deps_installed: true,
         env_copied: true,
+        tests: None,
+        seed: None,
+        expires_at: None,
         agent_analyses: Vec::new(),
+        analysis_commit: None,
+        analysis_history: Vec::new(),
+        analysis_interrupted: false,
+        agent_usage: Default::default(),
     };
 
     // Test: Add review
@@ -288,7 +310,7 @@ fn test_e2e_state_persistence() {
 
     // Test: Remove review
     let mut state = State::load().unwrap();
-    state.remove_review(999).unwrap();
+    state.remove_review(&review.key()).unwrap();
 
     let loaded_state = State::load().unwrap();
     assert_eq!(loaded_state.reviews.len(), 0);
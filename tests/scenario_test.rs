@@ -0,0 +1,1562 @@
+//! Scenario tests for Chaba
+//!
+//! These run the `chaba` binary through complete workflows against a local
+//! repository, with fake `gh` and agent CLIs from `chaba::test_support`.
+
+#![cfg(unix)]
+
+use assert_cmd::Command;
+use chaba::core::review_analysis::{Category, Severity, TriageStatus};
+use chaba::test_support::{FakeAgent, FakeCommand, FakeGh, FakePr, Scenario};
+use predicates::prelude::*;
+
+fn chaba(scenario: &Scenario) -> Command {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("chaba");
+    cmd.current_dir(scenario.repo_path()).envs(scenario.envs());
+    cmd
+}
+
+#[test]
+fn test_scenario_review_agents_cleanup() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/login", &[("src/login.rs", "fn login() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(42, "feature/login").title("Add login")));
+    scenario.install(
+        FakeAgent::new("claude")
+            .finding(Severity::High, Category::Security, "Token is logged", "The session token is printed")
+            .score(6.5),
+    );
+
+    chaba(&scenario)
+        .args(["review", "--pr", "42", "--with-agent"])
+        .assert()
+        .success();
+
+    let state = scenario.state();
+    let review = state.get_review(42).expect("review is saved");
+    assert_eq!(review.branch, "feature/login");
+    assert_eq!(review.pr_info.as_ref().unwrap().title, "Add login");
+    assert!(review.worktree_path.join("src/login.rs").exists());
+    assert_eq!(review.agent_analyses.len(), 1);
+    assert_eq!(review.agent_analyses[0].findings[0].title, "Token is logged");
+    assert_eq!(scenario.calls("claude").len(), 1);
+
+    chaba(&scenario)
+        .args(["agent-result", "--pr", "42"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Token is logged"));
+    chaba(&scenario)
+        .args(["agent-result", "--pr", "42", "--category", "performance"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Showing 0 of 1 findings"))
+        .stdout(predicate::str::contains("Token is logged").not());
+
+    let worktree = review.worktree_path.clone();
+    chaba(&scenario)
+        .args(["cleanup", "--pr", "42", "--force"])
+        .assert()
+        .success();
+
+    assert!(!worktree.exists());
+    assert!(scenario.state().get_review(42).is_none());
+
+    // The review and its findings are archived
+    chaba(&scenario)
+        .args(["history"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("feature/login"));
+    chaba(&scenario)
+        .args(["history", "--pr", "42"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Token is logged"));
+
+    // So is what happened to the environment
+    chaba(&scenario)
+        .args(["events", "--pr", "42"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("created         feature/login"))
+        .stdout(predicate::str::contains("agent_finished  claude: 1 finding(s)"))
+        .stdout(predicate::str::contains("cleaned_up"));
+}
+
+#[test]
+fn test_scenario_failing_agent_keeps_review() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/api", &[("api.rs", "fn api() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(7, "feature/api")));
+    scenario.install(FakeAgent::new("claude").fails("rate limited"));
+
+    chaba(&scenario).args(["review", "--pr", "7", "--with-agent"]).assert().success();
+
+    let state = scenario.state();
+    let review = state.get_review(7).expect("review is saved");
+    assert!(review.worktree_path.exists());
+    assert!(review.agent_analyses.is_empty());
+}
+
+#[test]
+fn test_scenario_agent_output_is_streamed_to_logs() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/token", &[("token.rs", "fn token() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(9, "feature/token")));
+    scenario.install(FakeAgent::new("claude").finding(
+        Severity::Medium,
+        Category::Security,
+        "Token logged in plain text",
+        "The token is passed to println!",
+    ));
+
+    chaba(&scenario).args(["review", "--pr", "9", "--with-agent"]).assert().success();
+
+    chaba(&scenario)
+        .args(["logs", "--pr", "9"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("claude started"))
+        .stdout(predicate::str::contains("Token logged in plain text"))
+        .stdout(predicate::str::contains("claude completed"));
+}
+
+#[test]
+fn test_scenario_watch_removes_merged_pr() {
+    let scenario = Scenario::new();
+    let head = scenario.branch("feature/done", &[("done.rs", "fn done() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(9, "feature/done").head_sha(&head)));
+
+    chaba(&scenario).args(["review", "--pr", "9"]).assert().success();
+    let worktree = scenario.state().get_review(9).unwrap().worktree_path.clone();
+
+    scenario.install(FakeGh::new().pr(FakePr::new(9, "feature/done").head_sha(&head).state("MERGED")));
+    chaba(&scenario)
+        .args(["watch", "--once"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PR #9 was merged"));
+
+    assert!(!worktree.exists());
+    assert!(scenario.state().get_review(9).is_none());
+}
+
+#[test]
+fn test_scenario_verdict_requests_changes() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/sql", &[("db.rs", "fn query() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(5, "feature/sql")).into_command().on("pr review 5 *", ""));
+    scenario.install(FakeAgent::new("claude").finding(
+        Severity::Critical,
+        Category::Security,
+        "SQL injection",
+        "Query is built with format!",
+    ));
+
+    chaba(&scenario).args(["review", "--pr", "5", "--with-agent"]).assert().success();
+    chaba(&scenario)
+        .args(["verdict", "--pr", "5", "--submit"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("request changes"));
+
+    let review_call = scenario
+        .calls("gh")
+        .into_iter()
+        .find(|call| call.starts_with("pr review 5"))
+        .expect("review was submitted");
+    assert!(review_call.contains("--request-changes"));
+    assert!(review_call.contains("SQL injection"));
+}
+
+#[test]
+fn test_scenario_chaos_failure_leaves_no_partial_review() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/flaky", &[("flaky.rs", "fn flaky() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(3, "feature/flaky")));
+
+    chaba(&scenario)
+        .args(["--chaos", "1", "--chaos-seed", "7", "review", "--pr", "3"])
+        .assert()
+        .failure();
+
+    assert!(scenario.state().get_review(3).is_none());
+    assert!(!scenario.reviews_dir().join("pr-3").exists());
+}
+
+#[test]
+fn test_scenario_chaos_keeps_state_and_worktrees_consistent() {
+    for seed in 1..=8 {
+        let scenario = Scenario::new();
+        scenario.branch("feature/flaky", &[("flaky.rs", "fn flaky() {}\n")]);
+        scenario.install(FakeGh::new().pr(FakePr::new(3, "feature/flaky")));
+
+        let seed = seed.to_string();
+        let _ = chaba(&scenario)
+            .args(["--chaos", "0.2", "--chaos-seed", &seed, "review", "--pr", "3"])
+            .output()
+            .unwrap();
+
+        let saved = scenario.state().get_review(3).is_some();
+        let created = scenario.reviews_dir().join("pr-3").exists();
+        assert_eq!(saved, created, "seed {}: review saved={} worktree exists={}", seed, saved, created);
+    }
+}
+
+#[test]
+fn test_scenario_dismissed_findings_do_not_block_verdict() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/cache", &[("cache.rs", "fn cache() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(6, "feature/cache")));
+    scenario.install(FakeAgent::new("claude").finding(
+        Severity::Critical,
+        Category::Security,
+        "Cache key collision",
+        "Keys are not namespaced",
+    ));
+
+    chaba(&scenario).args(["review", "--pr", "6", "--with-agent"]).assert().success();
+    let id = scenario.state().get_review(6).unwrap().agent_analyses[0].findings[0].id.clone();
+
+    chaba(&scenario)
+        .args(["triage", "--pr", "6", "--id", &id, "--set", "dismissed"])
+        .assert()
+        .success();
+    chaba(&scenario)
+        .args(["agent-result", "--pr", "6", "--status", "dismissed"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cache key collision [dismissed]"));
+    chaba(&scenario)
+        .args(["verdict", "--pr", "6"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("approve"));
+
+    // Agents re-run on new commits keep the dismissal
+    let head = scenario.push("feature/cache", &[("cache.rs", "fn cache() { todo!() }\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(6, "feature/cache").head_sha(&head)));
+    chaba(&scenario).args(["watch", "--once"]).assert().success();
+    assert_eq!(scenario.calls("claude").len(), 2);
+    let review = scenario.state().get_review(6).unwrap().clone();
+    assert_eq!(review.agent_analyses[0].findings[0].triage, TriageStatus::Dismissed);
+}
+
+#[test]
+fn test_scenario_watch_removes_expired_reviews() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/old", &[("old.rs", "fn old() {}\n")]);
+    scenario.branch("feature/wip", &[("wip.rs", "fn wip() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(14, "feature/old")).pr(FakePr::new(15, "feature/wip")));
+
+    chaba(&scenario)
+        .args(["review", "--pr", "14", "--ttl", "99999999999999w"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Invalid TTL"));
+    for pr in ["14", "15"] {
+        chaba(&scenario).args(["review", "--pr", pr, "--ttl", "1h"]).assert().success();
+    }
+
+    // Both lifetimes run out; one worktree has work in progress
+    let mut state = scenario.state();
+    for review in &mut state.reviews {
+        review.expires_at = Some(chrono::Utc::now() - chrono::Duration::minutes(1));
+    }
+    std::fs::write(scenario.state_dir().join("state.yaml"), serde_yaml::to_string(&state).unwrap()).unwrap();
+    let wip = scenario.state().get_review(15).unwrap().worktree_path.clone();
+    std::fs::write(wip.join("wip.rs"), "fn wip() { todo!() }\n").unwrap();
+
+    chaba(&scenario)
+        .args(["watch", "--once"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PR #14 expired; removed its review environment"))
+        .stdout(predicate::str::contains("PR #15 expired, but its worktree has uncommitted changes"));
+    assert!(scenario.state().get_review(14).is_none());
+    assert!(scenario.state().get_review(15).is_some());
+    assert!(wip.exists());
+}
+
+#[test]
+fn test_scenario_trace_commands() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/slow", &[("slow.rs", "fn slow() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(8, "feature/slow")));
+    scenario.install(FakeAgent::new("claude").fails("overloaded"));
+
+    chaba(&scenario)
+        .args(["--trace-commands", "review", "--pr", "8", "--with-agent"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Tracing commands to"));
+
+    chaba(&scenario)
+        .args(["trace", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("review --pr 8 --with-agent"));
+    chaba(&scenario)
+        .args(["trace", "show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("gh pr view 8"))
+        .stdout(predicate::str::contains("git worktree add"))
+        .stdout(predicate::str::contains(scenario.repo_path().display().to_string()))
+        .stdout(predicate::str::contains("claude"));
+}
+
+#[test]
+fn test_scenario_baselined_findings_are_suppressed() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/legacy", &[("legacy.rs", "fn legacy() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(4, "feature/legacy")));
+    scenario.install(FakeAgent::new("claude").finding(
+        Severity::Critical,
+        Category::Security,
+        "Weak hash",
+        "MD5 is used for passwords",
+    ));
+
+    chaba(&scenario).args(["review", "--pr", "4", "--with-agent"]).assert().success();
+    chaba(&scenario)
+        .args(["verdict", "--pr", "4"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("request changes"));
+
+    chaba(&scenario)
+        .args(["baseline", "update", "--pr", "4"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added 1 finding(s)"));
+    let baseline = std::fs::read_to_string(scenario.repo_path().join(".chaba/baseline.yaml")).unwrap();
+    assert!(baseline.contains("Weak hash"));
+
+    chaba(&scenario)
+        .args(["verdict", "--pr", "4"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("approve"))
+        .stdout(predicate::str::contains("1 baselined not counted"));
+    chaba(&scenario)
+        .args(["agent-result", "--pr", "4"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 baselined finding(s) hidden"))
+        .stdout(predicate::str::contains("Weak hash").not());
+}
+
+#[test]
+fn test_scenario_seed_data() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/orders", &[("orders.rs", "fn orders() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(12, "feature/orders")));
+    std::fs::create_dir_all(scenario.repo_path().join("fixtures")).unwrap();
+    std::fs::write(scenario.repo_path().join("fixtures/dev.sqlite"), "orders").unwrap();
+    scenario.config(
+        r#"
+sandbox:
+  seed_fixtures:
+    - from: fixtures/dev.sqlite
+      to: db/review.sqlite
+  seed_commands:
+    - echo "seeded $CHABA_PR" > seed.out
+"#,
+    );
+
+    chaba(&scenario)
+        .args(["review", "--pr", "12"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Seeded data: 1 fixture(s), 1 command(s)"));
+
+    let state = scenario.state();
+    let review = state.get_review(12).unwrap();
+    let seed = review.seed.as_ref().expect("seed result is saved");
+    assert!(seed.succeeded());
+    assert_eq!(seed.fixtures, vec!["db/review.sqlite"]);
+    assert_eq!(std::fs::read_to_string(review.worktree_path.join("db/review.sqlite")).unwrap(), "orders");
+    assert_eq!(std::fs::read_to_string(review.worktree_path.join("seed.out")).unwrap(), "seeded 12\n");
+}
+
+#[test]
+fn test_scenario_diff_between_agent_runs() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/upload", &[("upload.rs", "fn upload() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(15, "feature/upload")));
+    scenario.install(
+        FakeAgent::new("claude")
+            .finding(Severity::High, Category::Security, "Path traversal", "File names are not sanitized")
+            .finding(Severity::Medium, Category::Performance, "File read into memory", "Use streaming"),
+    );
+
+    chaba(&scenario).args(["review", "--pr", "15", "--with-agent"]).assert().success();
+    chaba(&scenario)
+        .args(["agent-result", "--pr", "15", "--diff"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No earlier analysis of PR #15"));
+
+    let head = scenario.push("feature/upload", &[("upload.rs", "fn upload() { stream() }\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(15, "feature/upload").head_sha(&head)));
+    scenario.install(
+        FakeAgent::new("claude")
+            .finding(Severity::High, Category::Security, "Path traversal", "File names are not sanitized")
+            .finding(Severity::Low, Category::CodeQuality, "Missing docs", "Document upload limits"),
+    );
+    chaba(&scenario).args(["watch", "--once"]).assert().success();
+
+    let review = scenario.state().get_review(15).unwrap().clone();
+    assert_eq!(review.analysis_commit.as_deref(), Some(head.as_str()));
+    assert_eq!(review.analysis_history.len(), 1);
+
+    let output = chaba(&scenario)
+        .args(["agent-result", "--pr", "15", "--diff"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    let section = |heading: &str| {
+        let start = output.find(heading).unwrap();
+        output[start..].split("\n\n").next().unwrap().to_string()
+    };
+    assert!(section("🆕 New (1)").contains("Missing docs"));
+    assert!(section("✅ Resolved (1)").contains("File read into memory"));
+    assert!(section("➖ Persisting (1)").contains("Path traversal"));
+}
+
+#[test]
+fn test_scenario_port_taken_by_other_process_is_reassigned() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/web", &[("web.rs", "fn web() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(21, "feature/web")));
+    scenario.config(
+        r#"
+sandbox:
+  port:
+    range_start: 47300
+    range_end: 47399
+  env:
+    overrides:
+      APP_URL: "http://localhost:{port}"
+"#,
+    );
+
+    chaba(&scenario).args(["review", "--pr", "21"]).assert().success();
+    let review = scenario.state().get_review(21).unwrap().clone();
+    let old_port = review.port.expect("port is assigned");
+
+    chaba(&scenario)
+        .args(["port", "--pr", "21"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("Port {} is free", old_port)));
+
+    // An unrelated process (this test) takes the port
+    let _listener = std::net::TcpListener::bind(("127.0.0.1", old_port)).unwrap();
+    chaba(&scenario)
+        .args(["port", "--pr", "21"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("Port {} is taken by another process", old_port)))
+        .stdout(predicate::str::contains("Reassigned PR #21"));
+
+    let new_port = scenario.state().get_review(21).unwrap().port.unwrap();
+    assert_ne!(new_port, old_port);
+    let env = std::fs::read_to_string(review.worktree_path.join(".env")).unwrap();
+    assert!(env.contains(&format!("APP_URL=http://localhost:{}", new_port)));
+}
+
+#[test]
+fn test_scenario_ports_lists_conflicts_and_prunes_deleted_worktrees() {
+    let scenario = Scenario::new();
+    for branch in ["feature/a", "feature/b", "feature/c"] {
+        scenario.branch(branch, &[("app.rs", "fn app() {}\n")]);
+    }
+    scenario.install(
+        FakeGh::new()
+            .pr(FakePr::new(22, "feature/a"))
+            .pr(FakePr::new(23, "feature/b"))
+            .pr(FakePr::new(24, "feature/c")),
+    );
+    scenario.config("sandbox:\n  port:\n    range_start: 47400\n    range_end: 47410\n");
+
+    chaba(&scenario).args(["review", "--pr", "22"]).assert().success();
+    chaba(&scenario).args(["review", "--pr", "23"]).assert().success();
+    let deleted = scenario.state().get_review(22).unwrap().clone();
+    let taken = scenario.state().get_review(23).unwrap().port.unwrap();
+    std::fs::remove_dir_all(&deleted.worktree_path).unwrap();
+    let _listener = std::net::TcpListener::bind(("127.0.0.1", taken)).unwrap();
+
+    chaba(&scenario)
+        .args(["ports"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PR #22     worktree missing"))
+        .stdout(predicate::str::contains("PR #23     conflict: taken by"))
+        .stdout(predicate::str::contains("10 of 11 port(s) free"));
+
+    chaba(&scenario)
+        .args(["ports", "--prune"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("Released port {} from PR #22", deleted.port.unwrap())));
+    assert_eq!(scenario.state().get_review(22).unwrap().port, None);
+
+    // The released port goes to the next review
+    chaba(&scenario).args(["review", "--pr", "24"]).assert().success();
+    assert_eq!(scenario.state().get_review(24).unwrap().port, deleted.port);
+}
+
+#[test]
+fn test_scenario_preferred_port() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/a", &[("app.rs", "fn app() {}\n")]);
+    scenario.branch("feature/b", &[("app.rs", "fn app() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(25, "feature/a")).pr(FakePr::new(26, "feature/b")));
+    scenario.config("sandbox:\n  port:\n    range_start: 47500\n    range_end: 47510\n    preferred: 47620\n");
+
+    chaba(&scenario).args(["review", "--pr", "25"]).assert().success();
+    chaba(&scenario).args(["review", "--pr", "26"]).assert().success();
+    let state = scenario.state();
+    assert_eq!(state.get_review(25).unwrap().port, Some(47620));
+    assert_eq!(state.get_review(26).unwrap().port, Some(47621));
+}
+
+#[test]
+fn test_scenario_list_reads_worktrees_concurrently_in_order() {
+    let scenario = Scenario::new();
+    let mut gh = FakeGh::new();
+    for pr in [27, 28, 29] {
+        let branch = format!("feature/list-{}", pr);
+        scenario.branch(&branch, &[("list.rs", "fn list() {}\n")]);
+        gh = gh.pr(FakePr::new(pr, branch.as_str()).check("build", "pass"));
+    }
+    scenario.install(gh);
+    for pr in ["27", "28", "29"] {
+        chaba(&scenario).args(["review", "--pr", pr]).assert().success();
+    }
+    std::fs::remove_dir_all(&scenario.state().get_review(28).unwrap().worktree_path).unwrap();
+
+    let output = chaba(&scenario).args(["list"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let rows: Vec<&str> = stdout.lines().filter(|line| line.starts_with("2")).collect();
+    assert_eq!(rows.len(), 3, "{}", stdout);
+    assert!(rows[0].starts_with("27 ") && rows[0].contains("↑1 ↓0") && rows[0].contains("✓"), "{}", rows[0]);
+    assert!(rows[1].starts_with("28 ") && rows[1].contains("MISSING"), "{}", rows[1]);
+    assert!(rows[2].starts_with("29 ") && rows[2].contains("↑1 ↓0"), "{}", rows[2]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("PR #28 - worktree was manually removed"));
+    assert_eq!(scenario.calls("gh").iter().filter(|call| call.starts_with("pr checks")).count(), 3);
+
+    chaba(&scenario)
+        .args(["list", "--sort", "pr", "--filter", "status=ok"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\n27 ").and(predicate::str::contains("\n28 ").not()));
+}
+
+#[test]
+fn test_scenario_list_reuses_cached_git_stats() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/cache", &[("cache.rs", "fn cache() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(31, "feature/cache")));
+    chaba(&scenario).args(["review", "--pr", "31"]).assert().success();
+    chaba(&scenario).args(["list"]).assert().success().stdout(predicate::str::contains("↑1 ↓0"));
+
+    // While HEAD and the index are unchanged, the cached stats are shown
+    let cache_path = scenario.state_dir().join("git-stats.json");
+    let mut cache: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&cache_path).unwrap()).unwrap();
+    for entry in cache["entries"].as_object_mut().unwrap().values_mut() {
+        entry["stats"]["base"]["commits_ahead"] = 7.into();
+    }
+    std::fs::write(&cache_path, cache.to_string()).unwrap();
+    chaba(&scenario).args(["list"]).assert().success().stdout(predicate::str::contains("↑7 ↓0"));
+
+    // A new commit moves HEAD, so the worktree is read again
+    let worktree = scenario.state().get_review(31).unwrap().worktree_path.clone();
+    std::fs::write(worktree.join("cache.rs"), "fn cache() -> u32 { 1 }\n").unwrap();
+    let status = std::process::Command::new("git")
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-qam", "more"])
+        .current_dir(&worktree)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    chaba(&scenario).args(["list"]).assert().success().stdout(predicate::str::contains("↑2 ↓0"));
+}
+
+#[test]
+fn test_scenario_apply_fix_from_suggestion() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/parse", &[("parse.rs", "fn parse(s: &str) -> u32 {\n    s.parse().unwrap()\n}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(18, "feature/parse")));
+    scenario.install(
+        FakeAgent::new("claude")
+            .finding(Severity::Medium, Category::CodeQuality, "Unwrap on user input", "Panics on bad input")
+            .at("parse.rs", 2)
+            .suggestion("Fall back to zero:\n```rust\n    s.parse().unwrap_or(0)\n```"),
+    );
+
+    chaba(&scenario).args(["review", "--pr", "18", "--with-agent"]).assert().success();
+    let review = scenario.state().get_review(18).unwrap().clone();
+    let id = review.agent_analyses[0].findings[0].id.clone();
+
+    chaba(&scenario)
+        .args(["apply-fix", "--pr", "18", "--finding", &id, "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+    s.parse().unwrap_or(0)"))
+        .stdout(predicate::str::contains("applies cleanly"));
+    let source = review.worktree_path.join("parse.rs");
+    assert!(std::fs::read_to_string(&source).unwrap().contains("unwrap()"));
+
+    chaba(&scenario)
+        .args(["apply-fix", "--pr", "18", "--finding", &id])
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(&source).unwrap(),
+        "fn parse(s: &str) -> u32 {\n    s.parse().unwrap_or(0)\n}\n"
+    );
+    let finding = scenario.state().get_review(18).unwrap().agent_analyses[0].findings[0].clone();
+    assert_eq!(finding.triage, TriageStatus::Fixed);
+
+    chaba(&scenario)
+        .args(["apply-fix", "--pr", "18", "--finding", &id])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("already marked as fixed"));
+}
+
+#[test]
+fn test_scenario_agent_quota_defers_batch_runs() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/a", &[("a.rs", "fn a() {}\n")]);
+    scenario.branch("feature/b", &[("b.rs", "fn b() {}\n")]);
+    scenario.branch("feature/c", &[("c.rs", "fn c() {}\n")]);
+    scenario.install(
+        FakeGh::new()
+            .pr(FakePr::new(31, "feature/a").author("octocat"))
+            .pr(FakePr::new(32, "feature/b").author("octocat"))
+            .pr(FakePr::new(33, "feature/c").author("hubot")),
+    );
+    scenario.install(FakeAgent::new("claude").finding(Severity::Low, Category::CodeQuality, "Naming", "Rename it"));
+    scenario.config(
+        r#"
+agents:
+  quota:
+    per_day: 5
+    per_user: 1
+"#,
+    );
+
+    chaba(&scenario)
+        .args(["review", "--all-open", "--with-agent", "--jobs", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("agent run deferred, quota for @octocat reached (1/1 runs)"));
+
+    let analyzed: Vec<u32> = scenario
+        .state()
+        .reviews
+        .iter()
+        .filter(|review| !review.agent_analyses.is_empty())
+        .map(|review| review.pr_number)
+        .collect();
+    assert_eq!(analyzed.len(), 2);
+    let deferred = [31, 32].into_iter().find(|pr| !analyzed.contains(pr)).unwrap();
+
+    chaba(&scenario)
+        .arg("queue")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Today: 2 of 5 agent runs used"))
+        .stdout(predicate::str::contains("@octocat").and(predicate::str::contains("1 of 1")))
+        .stdout(predicate::str::contains(format!("#{}", deferred)))
+        .stdout(predicate::str::contains("Deferred agent runs (1)"));
+
+    // Still over quota: watch leaves the run queued
+    chaba(&scenario).args(["watch", "--once"]).assert().success();
+    assert!(scenario.state().get_review(deferred).unwrap().agent_analyses.is_empty());
+
+    scenario.config(
+        r#"
+agents:
+  quota:
+    per_day: 5
+    per_user: 2
+"#,
+    );
+    chaba(&scenario)
+        .args(["watch", "--once"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("PR #{}: starting deferred agent run", deferred)));
+    assert!(!scenario.state().get_review(deferred).unwrap().agent_analyses.is_empty());
+
+    chaba(&scenario)
+        .arg("queue")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No deferred agent runs."));
+}
+
+#[test]
+fn test_scenario_anonymized_agent_review() {
+    let scenario = Scenario::new();
+    scenario.branch(
+        "feature/billing",
+        &[("billing.rs", "fn charge(customer: &Customer) {\n    notify(\"ops@acme.com\");\n}\n")],
+    );
+    scenario.install(FakeGh::new().pr(FakePr::new(41, "feature/billing")));
+    // The agent only knows the placeholders from the anonymized diff
+    scenario.install(
+        FakeAgent::new("claude")
+            .finding(Severity::High, Category::Security, "id_4 leaks str_1", "id_1 sends str_1 for every id_3")
+            .at("file_1.rs", 2),
+    );
+    scenario.config(
+        r#"
+agents:
+  anonymize:
+    enabled: true
+"#,
+    );
+
+    chaba(&scenario)
+        .args(["review", "--pr", "41", "--with-agent"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Sending agents an anonymized diff"));
+
+    let calls = scenario.calls("claude");
+    assert!(calls.iter().any(|call| call.contains("changes.diff")));
+    assert!(!calls.iter().any(|call| call.contains("acme")));
+
+    let review = scenario.state().get_review(41).unwrap().clone();
+    let finding = &review.agent_analyses[0].findings[0];
+    assert_eq!(finding.title, "notify leaks ops@acme.com");
+    assert_eq!(finding.description, "charge sends ops@acme.com for every Customer");
+    assert_eq!(finding.file.as_deref(), Some("billing.rs"));
+    assert!(review.worktree_path.join(".chaba/anonymize-map.json").exists());
+}
+
+#[test]
+fn test_scenario_ask_follow_up_question() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/session", &[("session.rs", "fn session() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(44, "feature/session")));
+    scenario.install(FakeAgent::new("claude").finding(
+        Severity::High,
+        Category::Security,
+        "Session never expires",
+        "Tokens stay valid forever",
+    ));
+
+    chaba(&scenario).args(["review", "--pr", "44", "--with-agent"]).assert().success();
+    chaba(&scenario)
+        .args(["ask", "--pr", "44", "why is finding 1 a problem?"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Asking claude about PR #44"));
+
+    let prompt = scenario.calls("claude").last().unwrap().clone();
+    assert!(prompt.contains("why is finding 1 a problem?"));
+    assert!(prompt.contains("1. ["));
+    assert!(prompt.contains("Session never expires"));
+
+    let review = scenario.state().get_review(44).unwrap().clone();
+    let follow_ups = &review.agent_analyses[0].follow_ups;
+    assert_eq!(follow_ups.len(), 1);
+    assert_eq!(follow_ups[0].question, "why is finding 1 a problem?");
+    assert!(!follow_ups[0].answer.is_empty());
+
+    chaba(&scenario)
+        .args(["agent-result", "--pr", "44"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Follow-up questions (1)"))
+        .stdout(predicate::str::contains("Q: why is finding 1 a problem?"));
+
+    chaba(&scenario)
+        .args(["ask", "--pr", "44", "--agent", "codex", "and now?"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("codex has not reviewed PR #44; ask one of: claude"));
+}
+
+#[test]
+fn test_scenario_encrypted_state() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/secrets", &[("secrets.rs", "fn secrets() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(12, "feature/secrets")));
+    scenario.install(FakeAgent::new("claude").finding(
+        Severity::High,
+        Category::Security,
+        "API key committed",
+        "The key is hardcoded",
+    ));
+    chaba(&scenario).args(["review", "--pr", "12", "--with-agent"]).assert().success();
+
+    chaba(&scenario)
+        .args(["encrypt", "enable", "--passphrase"])
+        .env("CHABA_PASSPHRASE", "correct horse")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Encrypted 1 file(s)"));
+
+    let state_file = scenario.state_dir().join("state.yaml");
+    let content = std::fs::read(&state_file).unwrap();
+    assert!(content.starts_with(b"age-encryption.org/v1"));
+    assert!(!String::from_utf8_lossy(&content).contains("API key committed"));
+
+    chaba(&scenario)
+        .args(["agent-result", "--pr", "12"])
+        .env("CHABA_PASSPHRASE", "correct horse")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("API key committed"));
+    chaba(&scenario)
+        .args(["list"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("CHABA_PASSPHRASE"));
+
+    chaba(&scenario)
+        .args(["encrypt", "disable"])
+        .env("CHABA_PASSPHRASE", "correct horse")
+        .assert()
+        .success();
+    assert!(scenario.state().get_review(12).is_some());
+}
+
+#[test]
+fn test_scenario_ctrl_c_cancels_agents_and_keeps_partial_results() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/slow-agent", &[("slow.rs", "fn slow() {}\n")]);
+    scenario.config("agents:\n  default_agents: [claude, codex]\n");
+    scenario.install(FakeGh::new().pr(FakePr::new(14, "feature/slow-agent")));
+    scenario.install(FakeAgent::new("claude").finding(
+        Severity::Medium,
+        Category::Performance,
+        "Query in a loop",
+        "N+1 queries",
+    ));
+    scenario.install(FakeAgent::new("codex").hangs());
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin!("chaba"))
+        .args(["review", "--pr", "14", "--with-agent"])
+        .current_dir(scenario.repo_path())
+        .envs(scenario.envs())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let started = std::time::Instant::now();
+    while !scenario.is_running("codex") {
+        assert!(started.elapsed().as_secs() < 60, "codex was never started");
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    // Let claude's result come in
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    std::process::Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code(), Some(130));
+    assert!(!scenario.is_running("codex"));
+
+    let review = scenario.state().get_review(14).cloned().expect("review is saved");
+    assert!(review.analysis_interrupted);
+    assert_eq!(review.agent_analyses.len(), 1);
+    assert_eq!(review.agent_analyses[0].agent, "claude");
+
+    chaba(&scenario)
+        .args(["agent-result", "--pr", "14"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Analysis interrupted"))
+        .stdout(predicate::str::contains("Query in a loop"));
+}
+
+#[test]
+fn test_scenario_analyze_existing_review_with_one_agent() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/cart", &[("cart.rs", "fn cart() {}\n")]);
+    scenario.config("agents:\n  default_agents: [claude, codex]\n");
+    scenario.install(FakeGh::new().pr(FakePr::new(15, "feature/cart")));
+    scenario.install(FakeAgent::new("claude").finding(Severity::Low, Category::CodeQuality, "Long function", "Split it"));
+    scenario.install(FakeAgent::new("codex").finding(Severity::High, Category::CodeQuality, "Off by one", "Loop bound"));
+    chaba(&scenario).args(["review", "--pr", "15"]).assert().success();
+    assert!(scenario.state().get_review(15).unwrap().agent_analyses.is_empty());
+
+    chaba(&scenario).args(["analyze", "--pr", "15"]).assert().success();
+    assert_eq!(scenario.state().get_review(15).unwrap().agent_analyses.len(), 2);
+
+    scenario.install(FakeAgent::new("claude").finding(Severity::Medium, Category::Security, "Unchecked total", "Overflow"));
+    chaba(&scenario)
+        .args(["analyze", "--pr", "15", "--agents", "claude"])
+        .assert()
+        .success();
+
+    let review = scenario.state().get_review(15).cloned().unwrap();
+    let titles: Vec<&str> = review
+        .agent_analyses
+        .iter()
+        .flat_map(|analysis| analysis.findings.iter().map(|finding| finding.title.as_str()))
+        .collect();
+    assert!(titles.contains(&"Off by one"));
+    assert!(titles.contains(&"Unchecked total"));
+    assert!(!titles.contains(&"Long function"));
+    assert_eq!(scenario.calls("codex").len(), 1);
+
+    chaba(&scenario)
+        .args(["analyze", "--pr", "15", "--agents", "aider"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown agent: aider"));
+}
+
+#[test]
+fn test_scenario_preflight_skips_unavailable_agents() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/search", &[("search.rs", "fn search() {}\n")]);
+    scenario.config("agents:\n  default_agents: [claude, codex, gemini]\n");
+    scenario.install(FakeGh::new().pr(FakePr::new(16, "feature/search")));
+    scenario.install(FakeAgent::new("claude").finding(Severity::Low, Category::CodeQuality, "Nested loop", "Flatten"));
+    scenario.install(FakeAgent::new("codex"));
+    std::fs::remove_file(scenario.home().join(".codex/auth.json")).unwrap();
+
+    chaba(&scenario)
+        .args(["review", "--pr", "16", "--with-agent"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Skipping codex: codex is not logged in"))
+        .stderr(predicate::str::contains("codex login"))
+        .stderr(predicate::str::contains("Skipping gemini: 'gemini' not found on PATH"))
+        .stderr(predicate::str::contains("npm install -g @google/gemini-cli"));
+
+    let review = scenario.state().get_review(16).cloned().unwrap();
+    assert_eq!(review.agent_analyses.len(), 1);
+    assert_eq!(review.agent_analyses[0].agent, "claude");
+    assert!(scenario.calls("codex").is_empty());
+
+    chaba(&scenario)
+        .arg("doctor")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("✗ codex"))
+        .stdout(predicate::str::contains("✗ gemini"))
+        .stderr(predicate::str::contains("2 check(s) failed"));
+}
+
+#[test]
+fn test_scenario_agent_costs_accumulate_per_pr() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/billing", &[("billing.rs", "fn bill() {}\n")]);
+    scenario.config("agents:\n  default_agents: [claude, codex]\n");
+    scenario.install(FakeGh::new().pr(FakePr::new(17, "feature/billing")));
+    scenario.install(
+        FakeAgent::new("claude")
+            .finding(Severity::Medium, Category::CodeQuality, "Rounding", "Use decimals")
+            .usage(12_000, 0.25),
+    );
+    scenario.install(FakeAgent::new("codex").usage(3_000, 0.0));
+
+    chaba(&scenario)
+        .args(["review", "--pr", "17", "--with-agent"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("$0.25 (15.0k tokens)"));
+    chaba(&scenario).args(["analyze", "--pr", "17", "--agents", "claude"]).assert().success();
+
+    let review = scenario.state().get_review(17).cloned().unwrap();
+    let claude = review.agent_analyses.iter().find(|analysis| analysis.agent == "claude").unwrap();
+    assert_eq!(claude.findings[0].title, "Rounding");
+    assert_eq!(review.agent_usage.tokens, 27_000);
+    assert_eq!(review.agent_usage.cost_usd, Some(0.5));
+
+    chaba(&scenario)
+        .args(["agent-result", "--pr", "17"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Latest run: $0.25 (15.0k tokens)"))
+        .stdout(predicate::str::contains("All runs:   $0.50 (27.0k tokens)"));
+    chaba(&scenario)
+        .args(["list", "--costs"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Total agent usage: $0.50 (27.0k tokens)"));
+}
+
+#[test]
+fn test_scenario_native_json_output_from_each_agent() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/export", &[("export.rs", "fn export() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(18, "feature/export")));
+    for (agent, title) in [("claude", "Leaky handle"), ("codex", "Missing bound"), ("gemini", "Tight coupling")] {
+        scenario.install(
+            FakeAgent::new(agent)
+                .finding(Severity::Medium, Category::Architecture, title, "See export.rs")
+                .usage(1_000, 0.01),
+        );
+    }
+
+    chaba(&scenario).args(["review", "--pr", "18", "--thorough"]).assert().success();
+
+    let review = scenario.state().get_review(18).cloned().unwrap();
+    for (agent, title) in [("claude", "Leaky handle"), ("codex", "Missing bound"), ("gemini", "Tight coupling")] {
+        let analysis = review.agent_analyses.iter().find(|analysis| analysis.agent == agent).unwrap();
+        assert_eq!(analysis.findings.len(), 1, "{}", agent);
+        assert_eq!(analysis.findings[0].title, title);
+        assert_eq!(analysis.usage.unwrap().tokens, 1_000);
+    }
+    assert!(scenario.calls("codex")[0].contains("--json"));
+    assert!(scenario.calls("gemini")[0].contains("--output-format json"));
+
+    // Streamed events are shown as the agent's reply
+    chaba(&scenario)
+        .args(["logs", "--pr", "18"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Missing bound"))
+        .stdout(predicate::str::contains("turn.completed").not());
+}
+
+#[test]
+fn test_scenario_agents_scoped_to_changed_files() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/invoice", &[("src/invoice.rs", "fn invoice() {}\n"), ("docs/invoice.md", "# Invoice\n")]);
+    scenario.config("agents:\n  scope: changed_files\n");
+    scenario.install(FakeGh::new().pr(FakePr::new(19, "feature/invoice")));
+    scenario.install(FakeAgent::new("claude"));
+
+    chaba(&scenario)
+        .args(["review", "--pr", "19", "--with-agent"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Limiting the review to 2 changed file(s)"));
+
+    let prompt = scenario.calls("claude").pop().unwrap();
+    assert!(prompt.contains("Review only the following files"));
+    assert!(prompt.contains("- docs/invoice.md - src/invoice.rs"));
+    assert!(!prompt.contains("README.md"));
+}
+
+#[test]
+fn test_scenario_state_export_import_rewrites_home() {
+    let old = Scenario::new();
+    old.branch("feature/login", &[("src/login.rs", "fn login() {}\n")]);
+    old.install(FakeGh::new().pr(FakePr::new(42, "feature/login")));
+    chaba(&old).args(["review", "--pr", "42"]).assert().success();
+
+    let output = chaba(&old).args(["state", "export"]).output().unwrap();
+    assert!(output.status.success());
+    let export_file = old.home().join("chaba-state.json");
+    std::fs::write(&export_file, &output.stdout).unwrap();
+
+    let new = Scenario::new();
+    chaba(&new)
+        .args(["state", "import"])
+        .arg(&export_file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 1 review(s)"))
+        .stdout(predicate::str::contains("1 worktree(s) not found"));
+
+    let review = new.state().get_review(42).cloned().expect("review is imported");
+    assert!(review.worktree_path.starts_with(new.home()));
+    assert_eq!(review.repo.as_deref(), Some(old.repo_path()));
+
+    chaba(&new)
+        .args(["state", "import"])
+        .arg(&export_file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Skipped 1 already tracked: #42"));
+}
+
+#[test]
+fn test_scenario_adopt_external_worktree() {
+    let scenario = Scenario::new();
+    scenario.branch("hotfix", &[("Cargo.toml", "[package]\nname = \"app\"\n")]);
+    let worktree = scenario.home().join("manual/hotfix");
+    let status = std::process::Command::new("git")
+        .args(["worktree", "add", "-q"])
+        .arg(&worktree)
+        .arg("hotfix")
+        .current_dir(scenario.repo_path())
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    chaba(&scenario)
+        .arg("adopt")
+        .arg(scenario.repo_path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is the main worktree"));
+
+    chaba(&scenario)
+        .arg("adopt")
+        .arg(&worktree)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Branch: hotfix"))
+        .stdout(predicate::str::contains("Project: Rust"));
+
+    let state = scenario.state();
+    let review = state.reviews.iter().find(|r| r.worktree_path == worktree).expect("worktree is adopted");
+    assert_eq!(review.branch, "hotfix");
+    assert!(review.port.is_some());
+    assert!(worktree.join(".chaba/review.json").exists());
+
+    chaba(&scenario)
+        .args(["events", "--pr", &review.pr_number.to_string()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("adopted"));
+    chaba(&scenario)
+        .arg("adopt")
+        .arg(&worktree)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already tracked"));
+}
+
+#[test]
+fn test_scenario_shared_clone_checkout() {
+    let scenario = Scenario::new();
+    scenario.config("worktree:\n  checkout: shared_clone\n");
+    let head = scenario.branch("feature/clone", &[("src/clone.rs", "fn clone() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(43, "feature/clone")));
+    chaba(&scenario).args(["review", "--pr", "43"]).assert().success();
+
+    let worktree = scenario.state().get_review(43).unwrap().worktree_path.clone();
+    assert!(worktree.join(".git").is_dir());
+    assert!(worktree.join(".git/objects/info/alternates").is_file());
+    assert_eq!(std::fs::read_to_string(worktree.join("src/clone.rs")).unwrap(), "fn clone() {}\n");
+    let git = |args: &[&str]| {
+        let output = std::process::Command::new("git").args(args).current_dir(&worktree).output().unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+    assert_eq!(git(&["rev-parse", "HEAD"]), head);
+    // Remote refs and the remote itself are the main repository's
+    assert!(git(&["remote", "get-url", "origin"]).ends_with("origin.git"));
+    assert!(git(&["for-each-ref", "--format=%(refname)", "refs/remotes/origin"]).contains("refs/remotes/origin/main"));
+    chaba(&scenario).args(["list"]).assert().success().stdout(predicate::str::contains("↑1 ↓0"));
+
+    // Work saved on cleanup reaches the main repository before the clone is deleted
+    std::fs::write(worktree.join("notes.md"), "clone notes\n").unwrap();
+    chaba(&scenario)
+        .args(["cleanup", "--pr", "43", "--force", "--archive"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Saved PR #43 to branch chaba/backup/pr-43"));
+    assert!(!worktree.exists());
+    let saved = std::process::Command::new("git")
+        .args(["show", "chaba/backup/pr-43:notes.md"])
+        .current_dir(scenario.repo_path())
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&saved.stdout), "clone notes\n");
+}
+
+#[test]
+fn test_scenario_shared_clone_cleanup_keeps_clone_only_refs() {
+    let scenario = Scenario::new();
+    scenario.config("worktree:\n  checkout: shared_clone\n");
+    let head = scenario.branch("feature/clone", &[("src/clone.rs", "fn clone() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(43, "feature/clone")));
+    chaba(&scenario).args(["review", "--pr", "43"]).assert().success();
+
+    // A branch and a tag made in the clone, with HEAD back where chaba left it
+    let worktree = scenario.state().get_review(43).unwrap().worktree_path.clone();
+    let git = |dir: &std::path::Path, args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .args(["-c", "user.name=Reviewer", "-c", "user.email=reviewer@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+    git(&worktree, &["checkout", "-q", "-b", "review-fixes"]);
+    std::fs::write(worktree.join("src/clone.rs"), "fn clone() -> u8 { 1 }\n").unwrap();
+    git(&worktree, &["commit", "-q", "-am", "Fix clone"]);
+    git(&worktree, &["tag", "-a", "reviewed", "-m", "Reviewed"]);
+    git(&worktree, &["checkout", "-q", "--detach", &head]);
+
+    chaba(&scenario).args(["cleanup", "--pr", "43", "--force"]).assert().success();
+    assert!(!worktree.exists());
+
+    let repo = scenario.repo_path();
+    assert_eq!(git(repo, &["log", "-1", "--format=%s", "chaba/clone/pr-43/review-fixes"]), "Fix clone");
+    assert_eq!(git(repo, &["tag", "-l", "chaba/clone/pr-43/*"]), "chaba/clone/pr-43/reviewed");
+    // Refs the main repository already had aren't copied
+    assert_eq!(git(repo, &["branch", "-l", "chaba/clone/*"]), "chaba/clone/pr-43/review-fixes");
+}
+
+#[test]
+fn test_scenario_cleanup_protects_unsaved_work() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/login", &[("src/login.rs", "fn login() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(42, "feature/login")));
+    chaba(&scenario).args(["review", "--pr", "42"]).assert().success();
+
+    let worktree = scenario.state().get_review(42).unwrap().worktree_path.clone();
+    std::fs::write(worktree.join("notes.md"), "half-done fix\n").unwrap();
+
+    chaba(&scenario)
+        .args(["cleanup", "--pr", "42", "--force"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("?? notes.md"))
+        .stderr(predicate::str::contains("--discard-changes"));
+    assert!(worktree.exists());
+
+    chaba(&scenario)
+        .args(["cleanup", "--pr", "42", "--force", "--archive"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Saved PR #42 to branch chaba/backup/pr-42"));
+    assert!(!worktree.exists());
+
+    let saved = std::process::Command::new("git")
+        .args(["show", "chaba/backup/pr-42:notes.md"])
+        .current_dir(scenario.repo_path())
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&saved.stdout), "half-done fix\n");
+
+    // A clean worktree is archived at its HEAD, next to the earlier backup
+    chaba(&scenario).args(["review", "--pr", "42"]).assert().success();
+    chaba(&scenario)
+        .args(["cleanup", "--pr", "42", "--force", "--archive"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("to branch chaba/backup/pr-42-"));
+}
+
+#[test]
+fn test_scenario_cleanup_protects_unpushed_commits_on_a_branch() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/login", &[("src/login.rs", "fn login() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(42, "feature/login")));
+    chaba(&scenario).args(["review", "--pr", "42"]).assert().success();
+
+    // A fix committed on a branch checked out in the worktree, never pushed
+    let worktree = scenario.state().get_review(42).unwrap().worktree_path.clone();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=Reviewer", "-c", "user.email=reviewer@example.com"])
+            .args(args)
+            .current_dir(&worktree)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    };
+    git(&["checkout", "-q", "-b", "review-fixes"]);
+    std::fs::write(worktree.join("src/login.rs"), "fn login() -> bool { true }\n").unwrap();
+    git(&["commit", "-q", "-am", "Fix login"]);
+
+    chaba(&scenario)
+        .args(["cleanup", "--pr", "42", "--force"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Fix login"))
+        .stderr(predicate::str::contains("--discard-changes"));
+    assert!(worktree.exists());
+
+    // A worktree git can't read is kept too
+    std::fs::write(worktree.join(".git"), "gitdir: /nonexistent\n").unwrap();
+    chaba(&scenario)
+        .args(["cleanup", "--pr", "42", "--force"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Cannot check PR #42's worktree for unsaved work"));
+    assert!(worktree.exists());
+}
+
+#[test]
+fn test_scenario_recreate_keeps_analyses_and_port() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/login", &[("src/login.rs", "fn login() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(42, "feature/login")));
+    scenario.install(FakeAgent::new("claude").finding(
+        Severity::High,
+        Category::Security,
+        "Token is logged",
+        "The session token is printed",
+    ));
+    chaba(&scenario).args(["review", "--pr", "42", "--with-agent"]).assert().success();
+
+    let before = scenario.state().get_review(42).cloned().unwrap();
+    std::fs::remove_file(before.worktree_path.join("src/login.rs")).unwrap();
+
+    chaba(&scenario)
+        .args(["recreate", "--pr", "42"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--discard-changes"));
+
+    chaba(&scenario)
+        .args(["recreate", "--pr", "42", "--discard-changes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Kept 1 agent analysis(es)"));
+
+    let after = scenario.state().get_review(42).cloned().unwrap();
+    assert_eq!(after.worktree_path, before.worktree_path);
+    assert!(after.worktree_path.join("src/login.rs").exists());
+    assert_eq!(after.port, before.port);
+    assert_eq!(after.created_at, before.created_at);
+    assert_eq!(after.agent_analyses[0].findings[0].title, "Token is logged");
+}
+
+#[test]
+fn test_scenario_commands_detect_review_from_current_directory() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/login", &[("src/login.rs", "fn login() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(42, "feature/login")));
+    chaba(&scenario).args(["review", "--pr", "42"]).assert().success();
+
+    let worktree = scenario.state().get_review(42).unwrap().worktree_path.clone();
+
+    chaba(&scenario)
+        .current_dir(worktree.join("src"))
+        .arg("status")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PR Number:     #42"));
+
+    chaba(&scenario)
+        .arg("status")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not inside a review worktree"));
+
+    chaba(&scenario)
+        .current_dir(&worktree)
+        .args(["cleanup", "--force"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed worktree for PR #42"));
+    assert!(scenario.state().get_review(42).is_none());
+}
+
+#[test]
+fn test_scenario_positional_pr_and_branch() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/login", &[("src/login.rs", "fn login() {}\n")]);
+    scenario.branch("feature/search", &[("src/search.rs", "fn search() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(42, "feature/login")));
+
+    chaba(&scenario).args(["review", "42"]).assert().success();
+    chaba(&scenario).args(["review", "feature/search"]).assert().success();
+    assert_eq!(scenario.state().reviews.len(), 2);
+
+    chaba(&scenario)
+        .args(["status", "feature/login"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("PR Number:     #42"));
+
+    chaba(&scenario)
+        .args(["cleanup", "feature/search", "--force"])
+        .assert()
+        .success();
+    chaba(&scenario).args(["cleanup", "42", "--force"]).assert().success();
+    assert!(scenario.state().reviews.is_empty());
+}
+
+#[test]
+fn test_scenario_quiet_output_and_log_file() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/login", &[("src/login.rs", "fn login() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(42, "feature/login")));
+    let log_file = scenario.state_dir().with_file_name("chaba.log");
+
+    chaba(&scenario)
+        .args(["--quiet", "--log-file"])
+        .arg(&log_file)
+        .args(["review", "--pr", "42"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\nReady to review!").and(predicate::str::contains("🍵").not()));
+
+    let log = std::fs::read_to_string(&log_file).unwrap();
+    assert!(log.contains("DEBUG"), "{}", log);
+    assert!(log.contains("Running git"), "{}", log);
+
+    chaba(&scenario)
+        .args(["cleanup", "--pr", "42", "--force", "-q"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\nRemoved worktree for PR #42\n").and(predicate::str::contains("✓").not()));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_scenario_desktop_notifications() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/login", &[("src/login.rs", "fn login() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(42, "feature/login")));
+    scenario.install(FakeAgent::new("claude").finding(
+        Severity::High,
+        Category::Security,
+        "Token is logged",
+        "The session token is printed",
+    ));
+    scenario.install(FakeCommand::new("notify-send").otherwise(""));
+
+    // Off by default
+    chaba(&scenario).args(["review", "--pr", "42"]).assert().success();
+    assert!(scenario.calls("notify-send").is_empty());
+
+    scenario.config("notifications:\n  desktop: true\n");
+    chaba(&scenario).args(["analyze", "--pr", "42"]).assert().success();
+    let calls = scenario.calls("notify-send");
+    assert_eq!(calls.len(), 1, "{:?}", calls);
+    assert!(calls[0].contains("Agent analysis finished"), "{}", calls[0]);
+    assert!(calls[0].contains("PR #42: 1 finding(s) from 1 agent(s)"), "{}", calls[0]);
+}
+
+#[test]
+fn test_scenario_webhook_notifications() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/login", &[("src/login.rs", "fn login() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(42, "feature/login").title("Add login")));
+    scenario.install(FakeAgent::new("claude").finding(
+        Severity::High,
+        Category::Security,
+        "Token is logged",
+        "The session token is printed",
+    ));
+    scenario.install(FakeCommand::new("curl").otherwise(""));
+    scenario.config(
+        "notifications:\n  webhooks:\n    - url: https://hooks.slack.com/services/T0/B0/secret\n      events: [critical_finding]\n    - url: https://discord.com/api/webhooks/1/secret\n",
+    );
+
+    chaba(&scenario).args(["review", "--pr", "42"]).assert().success();
+    chaba(&scenario).args(["analyze", "--pr", "42"]).assert().success();
+    // No critical finding, so only the Discord webhook wants this run
+    let calls = scenario.calls("curl");
+    assert_eq!(calls.len(), 1, "{:?}", calls);
+    assert!(calls[0].contains("**Agent analysis finished**: [PR #42: Add login](<https://github.com/acme/app/pull/42>)"), "{}", calls[0]);
+    assert!(calls[0].contains("1 finding(s) from 1 agent(s): 1 high"), "{}", calls[0]);
+    assert!(calls[0].contains("• [high] Token is logged (claude)"), "{}", calls[0]);
+    assert!(!calls[0].contains("secret"), "webhook URL leaked into argv: {}", calls[0]);
+
+    chaba(&scenario).args(["cleanup", "--pr", "42", "--force"]).assert().success();
+    let calls = scenario.calls("curl");
+    assert_eq!(calls.len(), 2, "{:?}", calls);
+    assert!(calls[1].contains("**Review environment removed**"), "{}", calls[1]);
+}
+
+#[test]
+fn test_scenario_open_in_vscode() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/api", &[("go.mod", "module example.com/api\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(31, "feature/api")));
+    scenario.install(FakeCommand::new("code").otherwise(""));
+
+    chaba(&scenario).args(["review", "--pr", "31"]).assert().success();
+    let review = scenario.state().get_review(31).unwrap().clone();
+
+    chaba(&scenario).args(["open", "31", "--vscode", "--launch"]).assert().success();
+    let workspace = chaba::core::vscode::workspace_path(&review);
+    assert!(workspace.display().to_string().ends_with("-pr-31.code-workspace"), "{}", workspace.display());
+    assert_eq!(scenario.calls("code"), vec![workspace.display().to_string()]);
+    let content = std::fs::read_to_string(&workspace).unwrap();
+    assert!(content.contains("PR #31 — feature/api"), "{}", content);
+
+    let launch = std::fs::read_to_string(review.worktree_path.join(".vscode/launch.json")).unwrap();
+    assert!(launch.contains("\"type\": \"go\""), "{}", launch);
+    if let Some(port) = review.port {
+        assert!(launch.contains(&format!("\"PORT\": \"{}\"", port)), "{}", launch);
+    }
+
+    // Generated files don't count as changes to the PR
+    let status = std::process::Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(&review.worktree_path)
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&status.stdout), "");
+}
+
+#[test]
+fn test_scenario_same_pr_number_in_two_repositories() {
+    let scenario = Scenario::new();
+    scenario.branch("feature/login", &[("src/login.rs", "fn login() {}\n")]);
+    scenario.install(FakeGh::new().pr(FakePr::new(42, "feature/login")));
+    // A second clone stands in for another repository with its own PR #42
+    let other = scenario.home().join("other");
+    let status = std::process::Command::new("git")
+        .args(["clone", "-q"])
+        .arg(scenario.repo_path())
+        .arg(&other)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    chaba(&scenario).args(["review", "--pr", "42"]).assert().success();
+
+    // Both would be checked out at pr-42
+    chaba(&scenario)
+        .arg("--repo")
+        .arg(&other)
+        .args(["review", "--pr", "42", "--force"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("add {repo} to worktree.naming_template"));
+
+    scenario.config("worktree:\n  naming_template: \"{repo}-pr-{pr}\"\n");
+    chaba(&scenario)
+        .arg("--repo")
+        .arg(&other)
+        .args(["review", "--pr", "42"])
+        .assert()
+        .success();
+    let reviews = scenario.state().reviews;
+    assert_eq!(reviews.len(), 2);
+    assert!(reviews.iter().all(|review| review.worktree_path.exists()));
+    chaba(&scenario)
+        .args(["snapshot", "--pr", "42", "-m", "before the agent"])
+        .assert()
+        .success();
+
+    // Commands act on the PR of the repository they run in
+    chaba(&scenario)
+        .arg("--repo")
+        .arg(&other)
+        .args(["cleanup", "--pr", "42", "--force"])
+        .assert()
+        .success();
+    let reviews = scenario.state().reviews;
+    assert_eq!(reviews.len(), 1);
+    assert_eq!(reviews[0].repo.as_deref(), Some(scenario.repo_path()));
+    assert!(reviews[0].worktree_path.exists());
+
+    // Per-review files are kept apart too
+    chaba(&scenario)
+        .args(["snapshot", "--pr", "42", "--list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("before the agent"));
+    chaba(&scenario)
+        .args(["events", "--pr", "42"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("created").and(predicate::str::contains("cleaned").not()));
+}